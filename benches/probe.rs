@@ -0,0 +1,63 @@
+use lofty::Probe;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use std::io::Cursor;
+
+macro_rules! test_probe {
+	($c:ident, [$(($NAME:ident, $path:expr)),+ $(,)?]) => {
+		let mut g = $c.benchmark_group("File type guessing");
+
+		$(
+			const $NAME: &[u8] = include_bytes!($path);
+		)+
+
+		$(
+			g.bench_function(stringify!($NAME), |b| {
+				b.iter(|| {
+					Probe::new(Cursor::new($NAME))
+						.guess_file_type()
+						.unwrap()
+						.file_type()
+						.unwrap()
+				})
+			});
+		)+
+
+		// Probing a directory of files, one after another
+		g.bench_function("All minimal assets", |b| {
+			b.iter(|| {
+				for file in [$($NAME),+] {
+					Probe::new(Cursor::new(file))
+						.guess_file_type()
+						.unwrap()
+						.file_type()
+						.unwrap();
+				}
+			})
+		});
+	}
+}
+
+fn guess_file_type(c: &mut Criterion) {
+	test_probe!(
+		c,
+		[
+			(AAC, "../tests/files/assets/minimal/full_test.aac"),
+			(AIFF, "../tests/files/assets/minimal/full_test.aiff"),
+			(APE, "../tests/files/assets/minimal/full_test.ape"),
+			(FLAC, "../tests/files/assets/minimal/full_test.flac"),
+			(MP4, "../tests/files/assets/minimal/m4a_codec_aac.m4a"),
+			(MPC, "../tests/files/assets/minimal/mpc_sv8.mpc"),
+			(MP3, "../tests/files/assets/minimal/full_test.mp3"),
+			(OPUS, "../tests/files/assets/minimal/full_test.opus"),
+			(SPEEX, "../tests/files/assets/minimal/full_test.spx"),
+			(VORBIS, "../tests/files/assets/minimal/full_test.ogg"),
+			(WAV, "../tests/files/assets/minimal/wav_format_pcm.wav"),
+			(WAVPACK, "../tests/files/assets/minimal/full_test.wv"),
+		]
+	);
+}
+
+criterion_group!(benches, guess_file_type);
+criterion_main!(benches);
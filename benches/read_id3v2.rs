@@ -0,0 +1,22 @@
+use lofty::mpeg::MpegFile;
+use lofty::{AudioFile, ParseOptions};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use std::io::Cursor;
+
+const MP3: &[u8] = include_bytes!("../tests/files/assets/minimal/full_test.mp3");
+
+fn id3v2_read(c: &mut Criterion) {
+	let mut g = c.benchmark_group("ID3v2 reading");
+
+	// Properties are skipped, only the tags are of interest
+	let parse_options = ParseOptions::new().read_properties(false);
+
+	g.bench_function("full_test.mp3", |b| {
+		b.iter(|| MpegFile::read_from(&mut Cursor::new(MP3), parse_options).unwrap())
+	});
+}
+
+criterion_group!(benches, id3v2_read);
+criterion_main!(benches);
@@ -1,6 +1,14 @@
 #![no_main]
+use lofty::ParsingMode;
+
 use libfuzzer_sys::fuzz_target;
 
 fuzz_target!(|data: &[u8]| {
-    let _ = lofty::Picture::from_ape_bytes("Cover Art (Front)", data);
-});
\ No newline at end of file
+	for parse_mode in [
+		ParsingMode::Strict,
+		ParsingMode::BestAttempt,
+		ParsingMode::Relaxed,
+	] {
+		let _ = lofty::Picture::from_ape_bytes("Cover Art (Front)", data, parse_mode);
+	}
+});
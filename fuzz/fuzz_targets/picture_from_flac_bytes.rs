@@ -4,6 +4,12 @@ use lofty::ParsingMode;
 use libfuzzer_sys::fuzz_target;
 
 fuzz_target!(|data: &[u8]| {
-	let _ = lofty::Picture::from_flac_bytes(data, true, ParsingMode::Relaxed);
-	let _ = lofty::Picture::from_flac_bytes(data, false, ParsingMode::Relaxed);
+	for parse_mode in [
+		ParsingMode::Strict,
+		ParsingMode::BestAttempt,
+		ParsingMode::Relaxed,
+	] {
+		let _ = lofty::Picture::from_flac_bytes(data, true, parse_mode);
+		let _ = lofty::Picture::from_flac_bytes(data, false, parse_mode);
+	}
 });
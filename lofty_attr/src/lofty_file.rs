@@ -95,12 +95,14 @@ pub(crate) fn parse(
 			bail!(errors, input.ident.span(), "Struct has no properties field");
 		};
 
+		let internal_file_type = has_internal_file_type.then_some(&file_type);
 		audiofile_impl = generate_audiofile_impl(
 			&struct_name,
 			&tag_fields,
 			properties_field,
 			read_fn,
 			write_fn,
+			internal_file_type,
 		);
 	}
 
@@ -318,9 +320,39 @@ fn generate_audiofile_impl(
 	properties_field: &Field,
 	read_fn: proc_macro2::TokenStream,
 	write_fn: proc_macro2::TokenStream,
+	internal_file_type: Option<&proc_macro2::TokenStream>,
 ) -> proc_macro2::TokenStream {
 	let save_to_body = get_save_to_body(write_fn, tag_fields);
 
+	// Internal files keep a fingerprint of the file they were read from, to detect it being modified
+	// before saving, see `WriteOptions::ignore_stale_file`
+	let (read_from_body, save_to_body) = match internal_file_type {
+		Some(file_type) => (
+			quote! {
+				#[allow(unused_mut)]
+				let mut file = #read_fn(reader, parse_options)?;
+				#[cfg(feature = "fs")]
+				{
+					file.fingerprint = ::lofty::fingerprint::FingerprintCell::read_from(
+						reader,
+						::lofty::FileType::#file_type,
+					)?;
+				}
+
+				Ok(file)
+			},
+			quote! {
+				self.fingerprint.verify(file, write_options)?;
+
+				let result: ::lofty::error::Result<()> = { #save_to_body };
+				result?;
+
+				self.fingerprint.refresh(file)
+			},
+		),
+		None => (quote! { #read_fn(reader, parse_options) }, save_to_body),
+	};
+
 	let tag_exists = tag_exists_iter(tag_fields);
 	let tag_exists_2 = tag_exists_iter(tag_fields);
 
@@ -340,13 +372,17 @@ fn generate_audiofile_impl(
 			where
 				R: std::io::Read + std::io::Seek,
 			{
-				#read_fn(reader, parse_options)
+				#read_from_body
 			}
 
-			fn save_to(&self, file: &mut ::std::fs::File) -> ::lofty::error::Result<()> {
-				use ::lofty::TagExt as _;
-				use ::std::io::Seek as _;
-				#save_to_body
+			// Only implemented with lofty's "fs" feature, which a `cfg` here would check the
+			// calling crate for instead
+			::lofty::__fs_only! {
+				fn save_to(&self, file: &mut ::std::fs::File, write_options: ::lofty::WriteOptions) -> ::lofty::error::Result<()> {
+					use ::lofty::TagExt as _;
+					use ::std::io::Seek as _;
+					#save_to_body
+				}
 			}
 
 			fn properties(&self) -> &Self::Properties {
@@ -375,7 +411,7 @@ fn get_save_to_body(
 ) -> proc_macro2::TokenStream {
 	if !write_fn.is_empty() {
 		return quote! {
-			#write_fn(&self, file)
+			#write_fn(&self, file, write_options)
 		};
 	}
 
@@ -385,13 +421,13 @@ fn get_save_to_body(
 			quote! {
 				file.rewind()?;
 				if let Some(ref tag) = self.#name {
-					tag.save_to(file)?;
+					tag.save_to(file, write_options)?;
 				}
 			}
 		} else {
 			quote! {
 				file.rewind()?;
-				self.#name.save_to(file)?;
+				self.#name.save_to(file, write_options)?;
 			}
 		}
 	});
@@ -420,15 +456,50 @@ fn generate_from_taggedfile_impl(
 	file_type: proc_macro2::TokenStream,
 	has_internal_file_type: bool,
 ) -> proc_macro2::TokenStream {
+	// Internal files hold onto the ID3v2 frames that can't be represented in a `Tag`, so they can be
+	// written back, see `TaggedFile::id3v2_remainder`
+	let keeps_id3v2_remainder =
+		|f: &FieldContents| has_internal_file_type && f.tag_type.to_string() == "Id3v2";
+
 	let conditions = tag_fields.iter().map(|f| {
 		let name = &f.name;
+		let push = if keeps_id3v2_remainder(f) {
+			quote! {
+				let (remainder, tag) = ::lofty::SplitTag::split_tag(t);
+				id3v2_remainder = Some(remainder);
+				tags.push(tag);
+			}
+		} else {
+			quote! { tags.push(t.into()); }
+		};
+
 		if f.needs_option {
-			quote! { if let Some(t) = input.#name { tags.push(t.into()); } }
+			quote! { if let Some(t) = input.#name { #push } }
 		} else {
-			quote! { tags.push(input.#name.into()); }
+			quote! { let t = input.#name; #push }
 		}
 	});
 
+	let (declare_remainder, set_remainder) = if tag_fields.iter().any(keeps_id3v2_remainder) {
+		(
+			quote! { let mut id3v2_remainder = None; },
+			quote! { tagged_file.id3v2_remainder = id3v2_remainder; },
+		)
+	} else {
+		(quote! {}, quote! {})
+	};
+
+	let set_fingerprint = if has_internal_file_type {
+		quote! {
+			#[cfg(feature = "fs")]
+			{
+				tagged_file.fingerprint = input.fingerprint;
+			}
+		}
+	} else {
+		quote! {}
+	};
+
 	let file_type_variant = if has_internal_file_type {
 		quote! { ::lofty::FileType::#file_type }
 	} else {
@@ -441,16 +512,21 @@ fn generate_from_taggedfile_impl(
 			fn from(input: #struct_name) -> Self {
 				use ::lofty::TaggedFileExt as _;
 
-				::lofty::TaggedFile::new(
+				let mut tags: Vec<::lofty::Tag> = Vec::new();
+				#declare_remainder
+				#( #conditions )*
+
+				#[allow(unused_mut)]
+				let mut tagged_file = ::lofty::TaggedFile::new(
 					#file_type_variant,
 					::lofty::FileProperties::from(input.properties),
-					{
-						let mut tags: Vec<::lofty::Tag> = Vec::new();
-						#( #conditions )*
+					tags,
+				);
+				#set_remainder
+
+				#set_fingerprint
 
-						tags
-					}
-				)
+				tagged_file
 			}
 		}
 	}
@@ -1,22 +1,20 @@
 use crate::error::Result;
 use crate::{
-	segment_table, Page, PageHeader, CONTAINS_FIRST_PAGE_OF_BITSTREAM,
-	CONTAINS_LAST_PAGE_OF_BITSTREAM, CONTINUED_PACKET, MAX_WRITTEN_CONTENT_SIZE,
-	MAX_WRITTEN_SEGMENT_COUNT,
+	Page, PageHeader, CONTAINS_FIRST_PAGE_OF_BITSTREAM, CONTAINS_LAST_PAGE_OF_BITSTREAM,
+	CONTINUED_PACKET, MAX_WRITTEN_SEGMENT_COUNT,
 };
 
-use std::io::Read;
-
 struct PaginateContext {
 	pages: Vec<Page>,
 	abgp: u64,
 	stream_serial: u32,
 	header_flags: u8,
-	flags: PaginateContextFlags,
 	pos: u64,
-	idx: usize,
-	remaining_page_size: usize,
-	current_packet_len: usize,
+	content: Vec<u8>,
+	segments: Vec<u8>,
+	// A packet from the previous page continues onto this page
+	continued_packet: bool,
+	packet_finished_on_page: bool,
 }
 
 impl PaginateContext {
@@ -26,33 +24,29 @@ impl PaginateContext {
 			abgp,
 			stream_serial,
 			header_flags,
-			flags: PaginateContextFlags {
-				first_page: true,
-				fresh_packet: true,
-				packet_spans_multiple_pages: false,
-				packet_finished_on_page: false,
-			},
 			pos: 0,
-			idx: 0,
-			remaining_page_size: MAX_WRITTEN_CONTENT_SIZE,
-			current_packet_len: 0,
+			content: Vec::new(),
+			segments: Vec::new(),
+			continued_packet: false,
+			packet_finished_on_page: false,
 		}
 	}
 
-	fn flush(&mut self, content: &mut Vec<u8>, segment_table: &mut Vec<u8>) {
-		let mut header = PageHeader {
+	fn flush(&mut self) {
+		let first_page = self.pages.is_empty();
+
+		let header = PageHeader {
 			start: self.pos,
 			header_type_flag: {
-				match self.flags.first_page {
-					true if self.header_flags & CONTAINS_FIRST_PAGE_OF_BITSTREAM != 0 => {
-						CONTAINS_FIRST_PAGE_OF_BITSTREAM
-					},
-					// A packet from the previous page continues onto this page
-					false if !self.flags.fresh_packet => CONTINUED_PACKET,
-					_ => 0,
+				if first_page && self.header_flags & CONTAINS_FIRST_PAGE_OF_BITSTREAM != 0 {
+					CONTAINS_FIRST_PAGE_OF_BITSTREAM
+				} else if self.continued_packet {
+					CONTINUED_PACKET
+				} else {
+					0
 				}
 			},
-			abgp: if self.flags.packet_finished_on_page {
+			abgp: if self.packet_finished_on_page {
 				self.abgp
 			} else {
 				// A special value of '-1' (in two's complement) indicates that no packets
@@ -60,35 +54,14 @@ impl PaginateContext {
 				1_u64.wrapping_neg()
 			},
 			stream_serial: self.stream_serial,
-			sequence_number: self.idx as u32,
-			segments: Vec::new(),
+			sequence_number: self.pages.len() as u32,
+			segments: core::mem::take(&mut self.segments),
 			// No need to calculate this yet
 			checksum: 0,
 		};
 
-		let content = core::mem::take(content);
-		let content_len = content.len();
-		self.pos += content_len as u64;
-
-		// Moving on to a new packet
-		if self.pos > self.current_packet_len as u64 {
-			self.flags.packet_spans_multiple_pages = false;
-		}
-
-		// We need to determine how many segments our page content takes up.
-		// If it takes up the remainder of the segment table for the entire packet,
-		// we'll just consume it as is.
-		let segments_occupied = if content_len >= 255 {
-			content_len / 255
-		} else {
-			1
-		};
-
-		if self.flags.packet_spans_multiple_pages {
-			header.segments = segment_table.drain(..segments_occupied).collect();
-		} else {
-			header.segments = core::mem::take(segment_table);
-		}
+		let content = core::mem::take(&mut self.content);
+		self.pos += content.len() as u64;
 
 		self.pages.push(Page {
 			content,
@@ -96,21 +69,16 @@ impl PaginateContext {
 			end: self.pos,
 		});
 
-		self.idx += 1;
-		self.flags.packet_finished_on_page = false;
-		self.remaining_page_size = MAX_WRITTEN_CONTENT_SIZE;
+		self.continued_packet = false;
+		self.packet_finished_on_page = false;
 	}
 }
 
-struct PaginateContextFlags {
-	first_page: bool,
-	fresh_packet: bool,
-	packet_spans_multiple_pages: bool,
-	packet_finished_on_page: bool,
-}
-
 /// Create pages from a list of packets
 ///
+/// Packets share pages while there is room left, with the exception of the first packet of
+/// a bitstream (see [`CONTAINS_FIRST_PAGE_OF_BITSTREAM`]), which is always given its own page.
+///
 /// # Errors
 ///
 /// * Unable to read packet content
@@ -136,72 +104,41 @@ where
 	I: IntoIterator<Item = &'a [u8]>,
 {
 	let mut ctx = PaginateContext::new(abgp, stream_serial, flags);
-
-	let mut packets_iter = packets.into_iter();
-	let mut packet = match packets_iter.next() {
-		Some(packet) => packet,
-		// We weren't given any content to paginate
-		None => return Ok(ctx.pages),
-	};
-	ctx.current_packet_len = packet.len();
-
-	let mut segments = segment_table(packet.len());
-	let mut page_content = Vec::new();
-
-	loop {
-		if !ctx.flags.packet_spans_multiple_pages && !ctx.flags.first_page {
-			match packets_iter.next() {
-				Some(packet_) => {
-					packet = packet_;
-					segments.append(&mut segment_table(packet.len()));
-					ctx.current_packet_len = packet.len();
-					ctx.flags.fresh_packet = true;
-				},
-				None => break,
-			};
+	let first_page_of_bitstream = flags & CONTAINS_FIRST_PAGE_OF_BITSTREAM != 0;
+
+	for (idx, packet) in packets.into_iter().enumerate() {
+		// Every segment but the last is 255 bytes, the last being < 255 (possibly 0) to mark
+		// the end of the packet.
+		let segment_count = (packet.len() / 255) + 1;
+
+		let mut remaining = packet;
+		for segment_idx in 0..segment_count {
+			// We have a maximum of `MAX_WRITTEN_SEGMENT_COUNT` segments available per page, the
+			// rest of the packet will have to continue onto the next page.
+			if ctx.segments.len() == MAX_WRITTEN_SEGMENT_COUNT {
+				ctx.flush();
+				ctx.continued_packet = segment_idx > 0;
+			}
+
+			let segment_len = remaining.len().min(255);
+			ctx.segments.push(segment_len as u8);
+			ctx.content.extend_from_slice(&remaining[..segment_len]);
+			remaining = &remaining[segment_len..];
 		}
 
-		// We read as much of the packet as we can, given the amount of space left in the page.
-		// The packet may need to span multiple pages.
-		let bytes_read = packet
-			.take(ctx.remaining_page_size as u64)
-			.read_to_end(&mut page_content)?;
-		ctx.remaining_page_size -= bytes_read;
-
-		packet = &packet[bytes_read..];
 		// We need to indicate whether or not any packet was finished on this page.
 		// This is used for the absolute granule position.
-		if packet.is_empty() {
-			ctx.flags.packet_finished_on_page = true;
-		}
+		ctx.packet_finished_on_page = true;
 
 		// The first packet of the bitstream must have its own page, unlike any other packet.
-		let first_page_of_bitstream = ctx.header_flags & CONTAINS_FIRST_PAGE_OF_BITSTREAM != 0;
-		let first_packet_finished_on_page =
-			ctx.flags.first_page && first_page_of_bitstream && ctx.flags.packet_finished_on_page;
-
-		// We have a maximum of `MAX_WRITTEN_SEGMENT_COUNT` segments available per page, if we require more than
-		// is left in the segment table, we'll have to split the packet into multiple pages.
-		let segments_required = (packet.len() / MAX_WRITTEN_SEGMENT_COUNT) + 1;
-		let remaining_segments = MAX_WRITTEN_SEGMENT_COUNT.saturating_sub(segments.len());
-		ctx.flags.packet_spans_multiple_pages = segments_required > remaining_segments;
-
-		if first_packet_finished_on_page
-			// We've completely filled this page, we need to flush before moving on
-			|| (ctx.remaining_page_size == 0 || remaining_segments == 0)
-			// We've read all this packet has to offer
-			|| packet.is_empty()
-		{
-			ctx.flush(&mut page_content, &mut segments);
+		if idx == 0 && first_page_of_bitstream {
+			ctx.flush();
 		}
-
-		ctx.flags.first_page = false;
-		ctx.flags.fresh_packet = false;
 	}
 
 	// Flush any content leftover
-	if !page_content.is_empty() {
-		ctx.flush(&mut page_content, &mut segments);
+	if !ctx.segments.is_empty() {
+		ctx.flush();
 	}
 
 	if flags & CONTAINS_LAST_PAGE_OF_BITSTREAM == 0x04 {
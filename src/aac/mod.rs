@@ -18,11 +18,35 @@ pub use properties::AACProperties;
 /// An AAC (ADTS) file
 #[derive(LoftyFile, Default)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct AacFile {
 	#[lofty(tag_type = "Id3v2")]
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
 	#[lofty(tag_type = "Id3v1")]
 	pub(crate) id3v1_tag: Option<Id3v1Tag>,
 	pub(crate) properties: AACProperties,
+	pub(crate) audio_start_offset: u64,
+	pub(crate) audio_end_offset: u64,
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
+}
+
+impl AacFile {
+	/// The offset where the audio begins
+	///
+	/// This is the offset of the first ADTS frame, after any leading tags and junk. If no frame
+	/// could be found, this is the offset directly after any leading tags and zero padding.
+	///
+	/// Along with [`AacFile::audio_end_offset`], this allows for copying the ADTS frames
+	/// without having to locate them again.
+	pub fn audio_start_offset(&self) -> u64 {
+		self.audio_start_offset
+	}
+
+	/// The offset where the audio ends
+	///
+	/// This is the offset of the first byte of a trailing ID3v1 tag, or the end of the stream if
+	/// there is none.
+	pub fn audio_end_offset(&self) -> u64 {
+		self.audio_end_offset
+	}
 }
@@ -1,7 +1,7 @@
 use crate::aac::header::ADTSHeader;
 use crate::mp4::AudioObjectType;
 use crate::mpeg::header::MpegVersion;
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::time::Duration;
 
@@ -82,12 +82,15 @@ impl From<AACProperties> for FileProperties {
 	fn from(input: AACProperties) -> Self {
 		FileProperties {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
 			bit_depth: None,
-			channels: Some(input.channels),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -96,6 +99,7 @@ pub(super) fn read_properties(
 	properties: &mut AACProperties,
 	first_frame: ADTSHeader,
 	stream_len: u64,
+	file_length: u64,
 ) {
 	properties.version = first_frame.version;
 	properties.audio_object_type = first_frame.audio_object_ty;
@@ -108,7 +112,11 @@ pub(super) fn read_properties(
 
 	if bitrate > 0 {
 		properties.audio_bitrate = bitrate;
-		properties.overall_bitrate = bitrate;
 		properties.duration = Duration::from_millis((stream_len * 8) / u64::from(bitrate));
+
+		let length = properties.duration.as_millis() as u64;
+		if let Some(overall_bitrate) = (file_length * 8).checked_div(length) {
+			properties.overall_bitrate = overall_bitrate as u32;
+		}
 	}
 }
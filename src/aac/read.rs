@@ -2,11 +2,12 @@ use super::header::{ADTSHeader, HEADER_MASK};
 use super::AacFile;
 use crate::error::Result;
 use crate::id3::v2::header::Id3v2Header;
-use crate::id3::v2::read::parse_id3v2;
-use crate::id3::{find_id3v1, ID3FindResults};
+use crate::id3::v2::read::{merge_appended_id3v2, parse_id3v2};
+use crate::id3::{find_id3v1, find_id3v2_footer, ID3FindResults};
 use crate::macros::{decode_err, parse_mode_choice};
 use crate::mpeg::header::{cmp_header, search_for_frame_sync, HeaderCmpResult};
 use crate::probe::{ParseOptions, ParsingMode};
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -17,12 +18,15 @@ pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Resul
 where
 	R: Read + Seek,
 {
+	let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 	let parse_mode = parse_options.parsing_mode;
 
 	let mut file = AacFile::default();
 
 	let mut first_frame_header = None;
 	let mut first_frame_end = 0;
+	let mut first_search_start = None;
 
 	// Skip any invalid padding
 	while reader.read_u8()? == 0 {}
@@ -30,7 +34,8 @@ where
 	reader.seek(SeekFrom::Current(-1))?;
 
 	let pos = reader.stream_position()?;
-	let mut stream_len = reader.seek(SeekFrom::End(0))?;
+	let file_length = reader.seek(SeekFrom::End(0))?;
+	let mut stream_len = file_length;
 
 	reader.seek(SeekFrom::Start(pos))?;
 
@@ -46,9 +51,10 @@ where
 				let header = Id3v2Header::parse(reader)?;
 				let skip_footer = header.flags.footer;
 
-				stream_len -= u64::from(header.size);
+				// The size doesn't include the header itself
+				stream_len -= u64::from(header.size) + 10;
 
-				let id3v2 = parse_id3v2(reader, header, parse_mode)?;
+				let id3v2 = parse_id3v2(reader, header, parse_options)?;
 				if let Some(existing_tag) = &mut file.id3v2_tag {
 					// https://github.com/Serial-ATA/lofty-rs/issues/87
 					// Duplicate tags should have their frames appended to the previous
@@ -72,11 +78,19 @@ where
 				// Seek back the length of the temporary header buffer, to include them
 				// in the frame sync search
 				#[allow(clippy::neg_multiply)]
-				reader.seek(SeekFrom::Current(-1 * header.len() as i64))?;
+				let pos = reader.seek(SeekFrom::Current(-1 * header.len() as i64))?;
+
+				// Only the first search starts directly after the tags
+				let search_start = *first_search_start.get_or_insert(pos);
+				file.audio_start_offset = search_start + parse_options.start_offset;
 
 				if let Some((first_frame_header_, first_frame_end_)) =
 					find_next_frame(reader, parse_mode)?
 				{
+					let header_len = if first_frame_header_.has_crc { 9 } else { 7 };
+					file.audio_start_offset =
+						first_frame_end_ - header_len + parse_options.start_offset;
+
 					first_frame_header = Some(first_frame_header_);
 					first_frame_end = first_frame_end_;
 					break;
@@ -93,6 +107,21 @@ where
 		file.id3v1_tag = id3v1;
 	}
 
+	// An ID3v2 tag can also be appended after the audio
+	let audio_start = file
+		.audio_start_offset
+		.saturating_sub(parse_options.start_offset);
+	if let ID3FindResults(Some(header), Some(content)) =
+		find_id3v2_footer(reader, audio_start, true)?
+	{
+		stream_len -= u64::from(header.size) + 20;
+
+		let id3v2 = parse_id3v2(&mut &*content, header, parse_options)?;
+		merge_appended_id3v2(&mut file.id3v2_tag, id3v2);
+	}
+
+	file.audio_end_offset = reader.stream_position()? + parse_options.start_offset;
+
 	if parse_options.read_properties {
 		let mut first_frame_header = match first_frame_header {
 			Some(header) => header,
@@ -123,7 +152,12 @@ where
 
 		first_frame_header.bitrate /= frame_count;
 
-		super::properties::read_properties(&mut file.properties, first_frame_header, stream_len);
+		super::properties::read_properties(
+			&mut file.properties,
+			first_frame_header,
+			stream_len,
+			file_length,
+		);
 	}
 
 	Ok(file)
@@ -1,3 +1,4 @@
+use crate::ape::constants::APE_PREAMBLE;
 use crate::error::Result;
 use crate::macros::decode_err;
 use crate::traits::SeekStreamLen;
@@ -7,9 +8,13 @@ use std::ops::Neg;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
+const HAS_HEADER_FLAG: u32 = 1 << 31;
+
 #[derive(Copy, Clone)]
 pub(crate) struct ApeHeader {
 	pub(crate) size: u32,
+	/// The size of the items, excluding the header and footer
+	pub(crate) items_size: u32,
 	pub(crate) item_count: u32,
 }
 
@@ -27,20 +32,34 @@ where
 		decode_err!(@BAIL Ape, "APE tag has an invalid size (< 32)");
 	}
 
+	let items_size = size - 32;
 	let item_count = data.read_u32::<LittleEndian>()?;
+	let flags = data.read_u32::<LittleEndian>()?;
 
-	if footer {
+	let has_header = if footer {
 		// No point in reading the rest of the footer, just seek back to the end of the header
-		data.seek(SeekFrom::Current(i64::from(size - 12).neg()))?;
+		let items_start = data.seek(SeekFrom::Current(i64::from(size - 8).neg()))?;
+
+		// Version 1 doesn't include a header, but the version and flags can't be trusted. Some
+		// taggers claim there's a header when there isn't, and vice versa, so check for it.
+		let has_header = header_precedes(data, items_start)?;
+		if has_header != (version == 2000 && flags & HAS_HEADER_FLAG == HAS_HEADER_FLAG) {
+			log::warn!(
+				"APE: Tag footer (version: {version}, flags: {flags:#X}) is wrong about the presence \
+				 of a header, assuming there is {}",
+				if has_header { "one" } else { "none" }
+			);
+		}
+
+		has_header
 	} else {
-		// There are 12 bytes remaining in the header
-		// Flags (4)
 		// Reserved (8)
-		data.seek(SeekFrom::Current(12))?;
-	}
+		data.seek(SeekFrom::Current(8))?;
 
-	// Version 1 doesn't include a header
-	if version == 2000 {
+		true
+	};
+
+	if has_header {
 		size = size.saturating_add(32);
 	}
 
@@ -49,5 +68,26 @@ where
 		decode_err!(@BAIL Ape, "APE tag has an invalid size (> file size)");
 	}
 
-	Ok(ApeHeader { size, item_count })
+	Ok(ApeHeader {
+		size,
+		items_size,
+		item_count,
+	})
+}
+
+// Whether an APE tag header sits right before `items_start`, leaving the reader at `items_start`
+fn header_precedes<R>(data: &mut R, items_start: u64) -> Result<bool>
+where
+	R: Read + Seek,
+{
+	if items_start < 32 {
+		return Ok(false);
+	}
+
+	let mut preamble = [0; 8];
+	data.seek(SeekFrom::Start(items_start - 32))?;
+	data.read_exact(&mut preamble)?;
+	data.seek(SeekFrom::Start(items_start))?;
+
+	Ok(&preamble == APE_PREAMBLE)
 }
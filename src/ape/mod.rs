@@ -26,7 +26,6 @@ pub use tag::ApeTag;
 /// An APE file
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct ApeFile {
 	/// An ID3v1 tag
 	#[lofty(tag_type = "Id3v1")]
@@ -39,4 +38,28 @@ pub struct ApeFile {
 	pub(crate) ape_tag: Option<ApeTag>,
 	/// The file's audio properties
 	pub(crate) properties: ApeProperties,
+	/// The offset where the audio begins
+	pub(crate) audio_start_offset: u64,
+	/// The offset where the audio ends
+	pub(crate) audio_end_offset: u64,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
+}
+
+impl ApeFile {
+	/// The offset where the audio begins
+	///
+	/// This is the offset of the `MAC ` signature, after any leading tags.
+	pub fn audio_start_offset(&self) -> u64 {
+		self.audio_start_offset
+	}
+
+	/// The offset where the audio ends
+	///
+	/// This is the offset of the first byte of any trailing APE, Lyrics3v2, or ID3v1 tag, or the
+	/// end of the stream if there are none.
+	pub fn audio_end_offset(&self) -> u64 {
+		self.audio_end_offset
+	}
 }
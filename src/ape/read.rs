@@ -6,9 +6,10 @@ use crate::error::Result;
 use crate::id3::v1::tag::Id3v1Tag;
 use crate::id3::v2::read::parse_id3v2;
 use crate::id3::v2::tag::Id3v2Tag;
-use crate::id3::{find_id3v1, find_id3v2, find_lyrics3v2, ID3FindResults};
+use crate::id3::{find_id3v1, find_id3v2, find_lyrics3v2, FindId3v2Config, ID3FindResults};
 use crate::macros::decode_err;
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -16,6 +17,8 @@ pub(crate) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<
 where
 	R: Read + Seek,
 {
+	let data = &mut OffsetFile::new(data, parse_options.start_offset)?;
+
 	let start = data.stream_position()?;
 	let end = data.seek(SeekFrom::End(0))?;
 
@@ -29,8 +32,12 @@ where
 
 	// ID3v2 tags are unsupported in APE files, but still possible
 	#[allow(unused_variables)]
-	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(data, true)? {
-		stream_len -= u64::from(header.size);
+	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(
+		data,
+		FindId3v2Config::READ_TAG.allowed_padding(parse_options.max_junk_bytes as u64),
+	)? {
+		// The size doesn't include the header itself
+		stream_len -= u64::from(header.size) + 10;
 
 		// Exclude the footer
 		if header.flags.footer {
@@ -39,7 +46,7 @@ where
 
 		let reader = &mut &*content;
 
-		let id3v2 = parse_id3v2(reader, header, parse_options.parsing_mode)?;
+		let id3v2 = parse_id3v2(reader, header, parse_options)?;
 		id3v2_tag = Some(id3v2);
 	}
 
@@ -75,7 +82,7 @@ where
 				let ape_header = read_ape_header(data, false)?;
 				stream_len -= u64::from(ape_header.size);
 
-				let ape = read_ape_tag_with_header(data, ape_header)?;
+				let ape = read_ape_tag_with_header(data, ape_header, parse_options.parsing_mode)?;
 				ape_tag = Some(ape);
 			},
 			_ => {
@@ -108,10 +115,11 @@ where
 	// Starts with ['A', 'P', 'E', 'T', 'A', 'G', 'E', 'X']
 	// Exactly 32 bytes long
 	// Strongly recommended to be at the end of the file
-	data.seek(SeekFrom::Current(-32))?;
+	let mut audio_end = data.seek(SeekFrom::Current(-32))? + 32;
 
-	if let Some((tag, header)) = read_ape_tag(data, true)? {
+	if let Some((tag, header)) = read_ape_tag(data, true, parse_options.parsing_mode)? {
 		stream_len -= u64::from(header.size);
+		audio_end -= u64::from(header.size);
 		ape_tag = Some(tag);
 	}
 
@@ -134,5 +142,10 @@ where
 		} else {
 			ApeProperties::default()
 		},
+		// Reported from the start of the reader, see `ParseOptions::start_offset`
+		audio_start_offset: mac_start - 4 + parse_options.start_offset,
+		audio_end_offset: audio_end + parse_options.start_offset,
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	})
 }
@@ -5,15 +5,22 @@ mod write;
 use crate::ape::tag::item::{ApeItem, ApeItemRef};
 use crate::error::{LoftyError, Result};
 use crate::id3::v2::util::pairs::{format_number_pair, set_number, NUMBER_PAIR_KEYS};
+use crate::picture::Picture;
+use crate::probe::ParsingMode;
 use crate::tag::item::{ItemKey, ItemValue, ItemValueRef, TagItem};
+use crate::tag::rating::convert_rating_items;
 use crate::tag::{try_parse_year, Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
 use std::convert::TryInto;
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::Write;
 use std::ops::Deref;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
@@ -52,6 +59,18 @@ macro_rules! impl_accessor {
 	}
 }
 
+// Picture items are named "Cover Art (<type>)", see `APE_PICTURE_TYPES`
+// A track can have multiple ISRCs, which share a single item
+const ISRC_KEY: &str = "ISRC";
+
+fn is_picture_key(key: &str) -> bool {
+	const PREFIX: &str = "Cover Art (";
+
+	key.len() > PREFIX.len()
+		&& key.is_char_boundary(PREFIX.len())
+		&& key[..PREFIX.len()].eq_ignore_ascii_case(PREFIX)
+}
+
 /// ## Item storage
 ///
 /// `APE` isn't a very strict format. An [`ApeItem`] only restricted by its name, meaning it can use
@@ -67,8 +86,16 @@ macro_rules! impl_accessor {
 ///
 /// Any [`ApeItem`] with an [`ItemKey`] mapping will have a 1:1 conversion to [`TagItem`].
 ///
+/// Binary items with a `Cover Art (...)` key are converted to [`Picture`]s, with their
+/// [`PictureType`](crate::PictureType) taken from the key (see [`PictureType::from_ape_key`](crate::PictureType::from_ape_key)).
+///
 /// ### From `Tag`
 ///
+/// Pictures are stored under the key for their type (see [`PictureType::as_ape_key`](crate::PictureType::as_ape_key)).
+/// As the key is the only place to store the type, only one picture of each type can be kept, with
+/// later pictures replacing earlier ones. Most other software only recognizes `Cover Art (Front)` and
+/// `Cover Art (Back)`, and may ignore pictures of any other type.
+///
 /// When converting pictures, any of type [`PictureType::Undefined`](crate::PictureType::Undefined) will be discarded.
 /// For items, see [`ApeItem::new`].
 #[derive(Default, Debug, PartialEq, Eq, Clone)]
@@ -80,6 +107,7 @@ pub struct ApeTag {
 	/// Whether or not to mark the tag as read only
 	pub read_only: bool,
 	pub(super) items: Vec<ApeItem>,
+	pub(super) unparsed_items: Vec<Vec<u8>>,
 }
 
 impl ApeTag {
@@ -157,6 +185,30 @@ impl ApeTag {
 		self.items.retain(|i| !i.key().eq_ignore_ascii_case(key));
 	}
 
+	/// Returns the raw contents of all items that couldn't be parsed
+	///
+	/// These are only retained when reading with [`ParsingMode::Relaxed`](crate::ParsingMode::Relaxed),
+	/// and are written back as-is. They can be discarded with [`ApeTag::remove_unparsed_items`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ape::ApeTag;
+	///
+	/// let ape_tag = ApeTag::new();
+	/// assert_eq!(ape_tag.unparsed_items().count(), 0);
+	/// ```
+	pub fn unparsed_items(&self) -> impl Iterator<Item = &[u8]> + Clone {
+		self.unparsed_items.iter().map(Vec::as_slice)
+	}
+
+	/// Removes all items that couldn't be parsed
+	///
+	/// See [`ApeTag::unparsed_items`]
+	pub fn remove_unparsed_items(&mut self) {
+		self.unparsed_items.clear();
+	}
+
 	fn insert_item(&mut self, item: TagItem) {
 		match item.key() {
 			ItemKey::TrackNumber => set_number(&item, |number| self.set_track(number)),
@@ -312,21 +364,26 @@ impl TagExt for ApeTag {
 	}
 
 	fn is_empty(&self) -> bool {
-		self.items.is_empty()
+		self.items.is_empty() && self.unparsed_items.is_empty()
 	}
 
+	#[cfg(feature = "fs")]
 	/// Write an `APE` tag to a file
 	///
 	/// # Errors
 	///
 	/// * Attempting to write the tag to a format that does not support it
 	/// * An existing tag has an invalid size
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		ApeTagRef {
 			read_only: self.read_only,
 			items: self.items.iter().map(Into::into),
 		}
-		.write_to(file)
+		.write_to_with_unparsed(file, &self.unparsed_items, write_options)
 	}
 
 	/// Dumps the tag to a writer
@@ -334,24 +391,37 @@ impl TagExt for ApeTag {
 	/// # Errors
 	///
 	/// * [`std::io::Error`]
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
-		ApeTagRef {
-			read_only: self.read_only,
-			items: self.items.iter().map(Into::into),
-		}
-		.dump_to(writer)
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		let tag = write::create_ape_tag(
+			&mut ApeTagRef {
+				read_only: self.read_only,
+				items: self.items.iter().map(Into::into),
+			},
+			&self.unparsed_items,
+			write_options,
+		)?;
+
+		writer.write_all(&tag)?;
+		Ok(())
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::Ape.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::Ape.remove_from(file)
 	}
 
 	fn clear(&mut self) {
 		self.items.clear();
+		self.unparsed_items.clear();
 	}
 }
 
@@ -397,7 +467,29 @@ impl SplitTag for ApeTag {
 
 		let mut tag = Tag::new(TagType::Ape);
 
+		// The "ALBUMARTIST" spelling is only used if there's no "Album Artist"
+		let has_album_artist = self
+			.items
+			.iter()
+			.any(|item| item.key().eq_ignore_ascii_case("Album Artist"));
+
 		for item in std::mem::take(&mut self.items) {
+			if has_album_artist && item.key().eq_ignore_ascii_case("ALBUMARTIST") {
+				continue;
+			}
+
+			if let ItemValue::Binary(bytes) = item.value() {
+				// Anything that isn't clearly a picture is kept as a binary item, so it's written back unchanged
+				if is_picture_key(item.key()) {
+					if let Ok(picture) =
+						Picture::from_ape_bytes(item.key(), bytes, ParsingMode::Strict)
+					{
+						tag.pictures.push(picture);
+						continue;
+					}
+				}
+			}
+
 			let item_key = ItemKey::from_key(TagType::Ape, item.key());
 
 			// The text pairs need some special treatment
@@ -425,6 +517,15 @@ impl SplitTag for ApeTag {
 				{
 					continue; // Item consumed
 				},
+				// A medley has an ISRC for each of its tracks, separated by nulls
+				(ItemKey::Isrc, ItemValue::Text(val)) if val.contains('\0') => {
+					for isrc in val.split('\0') {
+						tag.items.push(TagItem::new(
+							ItemKey::Isrc,
+							ItemValue::Text(isrc.to_owned()),
+						));
+					}
+				},
 				(k, _) => {
 					tag.items.push(TagItem::new(k, item.value));
 				},
@@ -438,9 +539,20 @@ impl SplitTag for ApeTag {
 impl MergeTag for SplitTagRemainder {
 	type Merged = ApeTag;
 
-	fn merge_tag(self, tag: Tag) -> Self::Merged {
+	fn merge_tag(self, mut tag: Tag) -> Self::Merged {
 		let Self(mut merged) = self;
 
+		convert_rating_items(&mut tag.items, TagType::Ape);
+
+		let isrcs = tag.take_strings(&ItemKey::Isrc).collect::<Vec<_>>();
+		if !isrcs.is_empty() {
+			if let Ok(item) =
+				ApeItem::new(String::from(ISRC_KEY), ItemValue::Text(isrcs.join("\0")))
+			{
+				merged.insert(item);
+			}
+		}
+
 		for item in tag.items {
 			merged.insert_item(item);
 		}
@@ -483,12 +595,33 @@ impl<'a, I> ApeTagRef<'a, I>
 where
 	I: Iterator<Item = ApeItemRef<'a>>,
 {
-	pub(crate) fn write_to(&mut self, file: &mut File) -> Result<()> {
-		write::write_to(file, self)
+	pub(crate) fn write_to<F>(&mut self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		write::write_to(file, self, &[], write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let temp = write::create_ape_tag(self)?;
+	// Items that failed to parse are kept separately, see `ApeTag::unparsed_items`
+	#[cfg(feature = "fs")]
+	pub(crate) fn write_to_with_unparsed<F>(
+		&mut self,
+		file: &mut F,
+		unparsed_items: &[Vec<u8>],
+		write_options: WriteOptions,
+	) -> Result<()>
+	where
+		F: FileLike,
+	{
+		write::write_to(file, self, unparsed_items, write_options)
+	}
+
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let temp = write::create_ape_tag(self, &[], write_options)?;
 		writer.write_all(&temp)?;
 
 		Ok(())
@@ -508,8 +641,15 @@ pub(crate) fn tagitems_into_ape(tag: &Tag) -> impl Iterator<Item = ApeItemRef<'_
 		})
 	}
 
+	let isrcs = tag.get_strings(&ItemKey::Isrc).collect::<Vec<_>>();
+	let isrc = (!isrcs.is_empty()).then(|| ApeItemRef {
+		read_only: false,
+		key: ISRC_KEY,
+		value: ItemValueRef::Text(Cow::Owned(isrcs.join("\0"))),
+	});
+
 	tag.items()
-		.filter(|item| !NUMBER_PAIR_KEYS.contains(item.key()))
+		.filter(|item| !NUMBER_PAIR_KEYS.contains(item.key()) && item.key() != &ItemKey::Isrc)
 		.filter_map(|i| {
 			i.key().map_key(TagType::Ape, true).map(|key| ApeItemRef {
 				read_only: false,
@@ -527,14 +667,18 @@ pub(crate) fn tagitems_into_ape(tag: &Tag) -> impl Iterator<Item = ApeItemRef<'_
 			tag.get_string(&ItemKey::DiscTotal),
 			"Disk",
 		))
+		.chain(isrc)
 }
 
 #[cfg(test)]
 mod tests {
 	use crate::ape::{ApeItem, ApeTag};
-	use crate::{Accessor, ItemKey, ItemValue, Tag, TagExt, TagItem, TagType};
+	use crate::{
+		Accessor, ItemKey, ItemValue, ParsingMode, Tag, TagExt, TagItem, TagType, WriteOptions,
+	};
 
 	use crate::id3::v2::util::pairs::DEFAULT_NUMBER_IN_PAIR;
+	use crate::{MimeType, Picture, PictureType};
 	use std::io::Cursor;
 
 	#[test]
@@ -588,9 +732,10 @@ mod tests {
 		let tag = crate::tag::utils::test_utils::read_path("tests/tags/assets/test.apev2");
 		let mut reader = Cursor::new(tag);
 
-		let (parsed_tag, _) = crate::ape::tag::read::read_ape_tag(&mut reader, false)
-			.unwrap()
-			.unwrap();
+		let (parsed_tag, _) =
+			crate::ape::tag::read::read_ape_tag(&mut reader, false, ParsingMode::Strict)
+				.unwrap()
+				.unwrap();
 
 		assert_eq!(expected_tag.len(), parsed_tag.len());
 
@@ -604,28 +749,160 @@ mod tests {
 		let tag_bytes = crate::tag::utils::test_utils::read_path("tests/tags/assets/test.apev2");
 		let mut reader = Cursor::new(tag_bytes);
 
-		let (parsed_tag, _) = crate::ape::tag::read::read_ape_tag(&mut reader, false)
-			.unwrap()
-			.unwrap();
+		let (parsed_tag, _) =
+			crate::ape::tag::read::read_ape_tag(&mut reader, false, ParsingMode::Strict)
+				.unwrap()
+				.unwrap();
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let mut temp_reader = Cursor::new(writer);
 
-		let (temp_parsed_tag, _) = crate::ape::tag::read::read_ape_tag(&mut temp_reader, false)
-			.unwrap()
-			.unwrap();
+		let (temp_parsed_tag, _) =
+			crate::ape::tag::read::read_ape_tag(&mut temp_reader, false, ParsingMode::Strict)
+				.unwrap()
+				.unwrap();
 
 		assert_eq!(parsed_tag, temp_parsed_tag);
 	}
 
+	#[test]
+	fn unparsed_items_relaxed() {
+		let mut tag = ApeTag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.set_artist(String::from("XXXX"));
+
+		let mut tag_bytes = Vec::new();
+		tag.dump_to(&mut tag_bytes, WriteOptions::default())
+			.unwrap();
+
+		// Make the artist invalid UTF-8
+		let pos = tag_bytes.windows(4).position(|w| w == b"XXXX").unwrap();
+		tag_bytes[pos..pos + 4].copy_from_slice(b"\xFF\xFE\xFF\xFE");
+
+		let read = |tag_bytes: Vec<u8>, parse_mode| {
+			let mut reader = Cursor::new(tag_bytes);
+			crate::ape::tag::read::read_ape_tag(&mut reader, false, parse_mode)
+		};
+
+		assert!(read(tag_bytes.clone(), ParsingMode::BestAttempt).is_err());
+
+		let (parsed_tag, _) = read(tag_bytes, ParsingMode::Relaxed).unwrap().unwrap();
+		assert_eq!(parsed_tag.title().as_deref(), Some("Foo title"));
+		assert!(parsed_tag.artist().is_none());
+
+		let unparsed_items = parsed_tag.unparsed_items().collect::<Vec<_>>();
+		assert_eq!(unparsed_items.len(), 1);
+		assert!(unparsed_items[0].ends_with(b"Artist\0\xFF\xFE\xFF\xFE"));
+
+		// The unparsed item is written back untouched
+		let mut writer = Vec::new();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
+
+		let (re_read_tag, _) = read(writer, ParsingMode::Relaxed).unwrap().unwrap();
+		assert_eq!(parsed_tag, re_read_tag);
+	}
+
+	#[test]
+	fn forged_item_count() {
+		let mut tag = ApeTag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.set_artist(String::from("Bar artist"));
+
+		let mut tag_bytes = Vec::new();
+		tag.dump_to(&mut tag_bytes, WriteOptions::default())
+			.unwrap();
+		let tag_len = tag_bytes.len() as u64;
+
+		// An ID3v1 tag directly follows, which must never be read as an item
+		tag_bytes.extend(b"TAG");
+		tag_bytes.extend([0; 125]);
+
+		let forge = |item_count: u32| {
+			let mut tag_bytes = tag_bytes.clone();
+			let footer_start = tag_len as usize - 32;
+			for count_pos in [16, footer_start + 16] {
+				tag_bytes[count_pos..count_pos + 4].copy_from_slice(&item_count.to_le_bytes());
+			}
+
+			tag_bytes
+		};
+
+		for item_count in [1, 3] {
+			let mut reader = Cursor::new(forge(item_count));
+			assert!(
+				crate::ape::tag::read::read_ape_tag(&mut reader, false, ParsingMode::Strict)
+					.is_err()
+			);
+
+			let mut reader = Cursor::new(forge(item_count));
+			let (parsed_tag, _) =
+				crate::ape::tag::read::read_ape_tag(&mut reader, false, ParsingMode::BestAttempt)
+					.unwrap()
+					.unwrap();
+
+			assert_eq!(parsed_tag.len(), item_count.min(2) as usize);
+			assert_eq!(reader.position(), tag_len);
+		}
+	}
+
+	#[test]
+	fn picture_types_round_trip() {
+		let png_data = b"\x89PNG\r\n\x1a\nfoo".to_vec();
+
+		let mut tag = Tag::new(TagType::Ape);
+		for pic_type in [PictureType::CoverFront, PictureType::BandLogo] {
+			tag.push_picture(Picture::new_unchecked(
+				pic_type,
+				Some(MimeType::Png),
+				None,
+				png_data.clone(),
+			));
+		}
+
+		let ape: ApeTag = tag.into();
+		assert!(ape.get("Cover Art (Front)").is_some());
+		assert!(ape.get("Cover Art (Band Logotype)").is_some());
+
+		let mut writer = Vec::new();
+		ape.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let (parsed_ape, _) = crate::ape::tag::read::read_ape_tag(
+			&mut Cursor::new(writer),
+			false,
+			ParsingMode::Strict,
+		)
+		.unwrap()
+		.unwrap();
+
+		let tag: Tag = parsed_ape.into();
+		assert_eq!(tag.item_count(), 0);
+
+		let pic_types = tag
+			.pictures()
+			.iter()
+			.map(|p| p.pic_type())
+			.collect::<Vec<_>>();
+		assert_eq!(pic_types, [PictureType::CoverFront, PictureType::BandLogo]);
+
+		// The keys are case-insensitive
+		assert_eq!(
+			PictureType::from_ape_key("COVER ART (BAND LOGOTYPE)"),
+			PictureType::BandLogo
+		);
+	}
+
 	#[test]
 	fn ape_to_tag() {
 		let tag_bytes = crate::tag::utils::test_utils::read_path("tests/tags/assets/test.apev2");
 		let mut reader = Cursor::new(tag_bytes);
 
-		let (ape, _) = crate::ape::tag::read::read_ape_tag(&mut reader, false)
+		let (ape, _) = crate::ape::tag::read::read_ape_tag(&mut reader, false, ParsingMode::Strict)
 			.unwrap()
 			.unwrap();
 
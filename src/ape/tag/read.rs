@@ -4,6 +4,7 @@ use crate::ape::constants::{APE_PREAMBLE, INVALID_KEYS};
 use crate::ape::header::{self, ApeHeader};
 use crate::error::Result;
 use crate::macros::{decode_err, err, try_vec};
+use crate::probe::ParsingMode;
 use crate::tag::item::ItemValue;
 use crate::util::text::utf8_decode;
 
@@ -11,53 +12,123 @@ use std::io::{Read, Seek, SeekFrom};
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-pub(crate) fn read_ape_tag_with_header<R>(data: &mut R, header: ApeHeader) -> Result<ApeTag>
+pub(crate) fn read_ape_tag_with_header<R>(
+	data: &mut R,
+	header: ApeHeader,
+	parse_mode: ParsingMode,
+) -> Result<ApeTag>
 where
 	R: Read + Seek,
 {
 	let mut tag = ApeTag::default();
-	let mut remaining_size = header.size;
 
-	for _ in 0..header.item_count {
-		if remaining_size < 11 {
+	// The items are read up front, so nothing past the end of the tag can be mistaken for an item,
+	// regardless of what the item count says
+	let mut items_content = try_vec![0; header.items_size as usize];
+	data.read_exact(&mut items_content)?;
+
+	let mut items = &items_content[..];
+	let mut items_read = 0;
+
+	while items_read < header.item_count {
+		let Some((raw_item, flags, key, value)) = split_item(&mut items) else {
+			if parse_mode == ParsingMode::Strict {
+				err!(SizeMismatch);
+			}
+
+			log::warn!(
+				"APE: Tag declares {} items, but only {items_read} fit within its size",
+				header.item_count
+			);
 			break;
+		};
+
+		items_read += 1;
+
+		// In `ParsingMode::Relaxed`, items that can't be parsed are kept as-is, so they can be
+		// written back
+		let retain = parse_mode == ParsingMode::Relaxed;
+
+		match parse_item(key.to_vec(), flags, value.to_vec()) {
+			Ok(Some(item)) => tag.insert(item),
+			Ok(None) => {
+				if retain {
+					tag.unparsed_items.push(raw_item.to_vec());
+				}
+			},
+			Err(err) => {
+				if !retain {
+					return Err(err);
+				}
+
+				log::warn!("APE: Failed to parse item, retaining its raw content: {err}");
+				tag.unparsed_items.push(raw_item.to_vec());
+			},
 		}
+	}
 
-		let value_size = data.read_u32::<LittleEndian>()?;
-		if value_size > remaining_size {
-			err!(SizeMismatch);
+	if items_read == header.item_count && !items.is_empty() {
+		if parse_mode == ParsingMode::Strict {
+			decode_err!(@BAIL Ape, "APE tag contains more data than its declared items");
 		}
 
-		remaining_size -= 4;
-		let flags = data.read_u32::<LittleEndian>()?;
+		log::warn!(
+			"APE: Tag contains {} bytes past its {} declared items, ignoring them",
+			items.len(),
+			header.item_count
+		);
+	}
 
-		let mut key = Vec::new();
-		let mut key_char = data.read_u8()?;
+	// Skip over footer
+	data.seek(SeekFrom::Current(32))?;
 
-		while key_char != 0 {
-			key.push(key_char);
-			key_char = data.read_u8()?;
-		}
+	Ok(tag)
+}
 
-		let key = utf8_decode(key)
-			.map_err(|_| decode_err!(Ape, "APE tag item contains a non UTF-8 key"))?;
+// Splits the next item off of `items`, returning the entire item, its flags, key, and value
+//
+// Returns `None` if the item doesn't fit in `items`
+fn split_item<'a>(items: &mut &'a [u8]) -> Option<(&'a [u8], u32, &'a [u8], &'a [u8])> {
+	let mut reader = *items;
 
-		if INVALID_KEYS.contains(&&*key.to_uppercase()) {
-			decode_err!(@BAIL Ape, "APE tag item contains an illegal key");
-		}
+	let value_size = reader.read_u32::<LittleEndian>().ok()? as usize;
+	let flags = reader.read_u32::<LittleEndian>().ok()?;
 
-		let read_only = (flags & 1) == 1;
-		let item_type = (flags >> 1) & 3;
+	let key_len = reader.iter().position(|&b| b == 0)?;
+	let key = &reader[..key_len];
+	let value = reader.get(key_len + 1..)?.get(..value_size)?;
 
-		if value_size == 0 || key.len() < 2 || key.len() > 255 {
-			log::debug!("APE: Encountered invalid item key ({})", key);
-			continue;
-		}
+	let item_len = 8 + key_len + 1 + value_size;
+	let raw_item = &items[..item_len];
+	*items = &items[item_len..];
 
-		let mut value = try_vec![0; value_size as usize];
-		data.read_exact(&mut value)?;
+	Some((raw_item, flags, key, value))
+}
 
-		let parsed_value = match item_type {
+fn parse_item(key: Vec<u8>, flags: u32, value: Vec<u8>) -> Result<Option<ApeItem>> {
+	let key =
+		utf8_decode(key).map_err(|_| decode_err!(Ape, "APE tag item contains a non UTF-8 key"))?;
+
+	if INVALID_KEYS.contains(&&*key.to_uppercase()) {
+		decode_err!(@BAIL Ape, "APE tag item contains an illegal key");
+	}
+
+	let read_only = (flags & 1) == 1;
+	let item_type = (flags >> 1) & 3;
+
+	if key.len() < 2 || key.len() > 255 {
+		log::debug!("APE: Encountered invalid item key ({})", key);
+		return Ok(None);
+	}
+
+	// Empty text is kept, see `WriteOptions::remove_empty_items`
+	if value.is_empty() && item_type != 0 {
+		log::debug!("APE: Encountered an empty item ({})", key);
+		return Ok(None);
+	}
+
+	let parsed_value =
+		match item_type {
 			0 => ItemValue::Text(utf8_decode(value).map_err(|_| {
 				decode_err!(Ape, "Failed to convert text item into a UTF-8 string")
 			})?),
@@ -68,24 +139,19 @@ where
 			_ => decode_err!(@BAIL Ape, "APE tag item contains an invalid item type"),
 		};
 
-		let mut item = ApeItem::new(key, parsed_value)?;
-
-		if read_only {
-			item.read_only = true;
-		}
+	let mut item = ApeItem::new(key, parsed_value)?;
 
-		tag.insert(item);
+	if read_only {
+		item.read_only = true;
 	}
 
-	// Skip over footer
-	data.seek(SeekFrom::Current(32))?;
-
-	Ok(tag)
+	Ok(Some(item))
 }
 
 pub(crate) fn read_ape_tag<R: Read + Seek>(
 	reader: &mut R,
 	footer: bool,
+	parse_mode: ParsingMode,
 ) -> Result<Option<(ApeTag, ApeHeader)>> {
 	let mut ape_preamble = [0; 8];
 	reader.read_exact(&mut ape_preamble)?;
@@ -93,7 +159,7 @@ pub(crate) fn read_ape_tag<R: Read + Seek>(
 	if &ape_preamble == APE_PREAMBLE {
 		let ape_header = header::read_ape_header(reader, footer)?;
 
-		let ape = read_ape_tag_with_header(reader, ape_header)?;
+		let ape = read_ape_tag_with_header(reader, ape_header, parse_mode)?;
 		return Ok(Some((ape, ape_header)));
 	}
 
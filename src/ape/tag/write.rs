@@ -1,21 +1,30 @@
 use super::item::ApeItemRef;
-use super::ApeTagRef;
+use super::{is_picture_key, ApeTagRef};
 use crate::ape::constants::APE_PREAMBLE;
 use crate::ape::tag::read;
 use crate::error::Result;
-use crate::id3::{find_id3v1, find_id3v2, find_lyrics3v2};
+use crate::id3::{find_id3v1, find_id3v2, find_id3v2_footer, find_lyrics3v2, FindId3v2Config};
 use crate::macros::{decode_err, err};
-use crate::probe::Probe;
+use crate::picture::check_picture_size;
+use crate::probe::{ParseOptions, ParsingMode, Probe};
 use crate::tag::item::ItemValueRef;
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
-use std::fs::File;
-use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::borrow::Cow;
+use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use byteorder::{LittleEndian, WriteBytesExt};
 
 #[allow(clippy::shadow_unrelated)]
-pub(crate) fn write_to<'a, I>(data: &mut File, tag: &mut ApeTagRef<'a, I>) -> Result<()>
+pub(crate) fn write_to<'a, F, I>(
+	data: &mut F,
+	tag: &mut ApeTagRef<'a, I>,
+	unparsed_items: &[Vec<u8>],
+	write_options: WriteOptions,
+) -> Result<()>
 where
+	F: FileLike,
 	I: Iterator<Item = ApeItemRef<'a>>,
 {
 	let probe = Probe::new(data).guess_file_type()?;
@@ -28,7 +37,11 @@ where
 	let data = probe.into_inner();
 
 	// We don't actually need the ID3v2 tag, but reading it will seek to the end of it if it exists
-	find_id3v2(data, false)?;
+	find_id3v2(
+		data,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+	let leading_end = data.stream_position()?;
 
 	let mut ape_preamble = [0; 8];
 	data.read_exact(&mut ape_preamble)?;
@@ -41,7 +54,7 @@ where
 	let mut header_ape_tag = (false, (0, 0));
 
 	let start = data.stream_position()?;
-	match read::read_ape_tag(data, false)? {
+	match read::read_ape_tag(data, false, ParsingMode::BestAttempt)? {
 		Some((mut existing_tag, header)) => {
 			// Only keep metadata around that's marked read only
 			existing_tag.items.retain(|i| i.read_only);
@@ -64,6 +77,9 @@ where
 	// In case there's no ape tag already, this is the spot it belongs
 	let ape_position = data.stream_position()?;
 
+	// An existing APE tag may come before an appended ID3v2 tag, which is left as-is
+	find_id3v2_footer(data, leading_end, false)?;
+
 	// Now search for an APE tag at the end
 	data.seek(SeekFrom::Current(-32))?;
 
@@ -71,7 +87,9 @@ where
 
 	// Also check this tag for any read only items
 	let start = data.stream_position()? as usize + 32;
-	if let Some((mut existing_tag, header)) = read::read_ape_tag(data, true)? {
+	if let Some((mut existing_tag, header)) =
+		read::read_ape_tag(data, true, ParsingMode::BestAttempt)?
+	{
 		let size = header.size;
 
 		existing_tag.items.retain(|i| i.read_only);
@@ -90,12 +108,16 @@ where
 
 	// Preserve any metadata marked as read only
 	let tag = if let Some(read_only) = read_only {
-		create_ape_tag(&mut ApeTagRef {
-			read_only: read_only.read_only,
-			items: read_only.items.iter().map(Into::into),
-		})?
+		create_ape_tag(
+			&mut ApeTagRef {
+				read_only: read_only.read_only,
+				items: read_only.items.iter().map(Into::into),
+			},
+			&[],
+			write_options,
+		)?
 	} else {
-		create_ape_tag(tag)?
+		create_ape_tag(tag, unparsed_items, write_options)?
 	};
 
 	data.rewind()?;
@@ -105,13 +127,22 @@ where
 
 	// Write the tag in the appropriate place
 	if let Some(range) = ape_tag_location {
+		if range.len() != tag.len() {
+			data.mark_moved(range.end as u64);
+		}
+
 		file_bytes.splice(range, tag);
 	} else {
+		if !tag.is_empty() {
+			data.mark_moved(ape_position);
+		}
+
 		file_bytes.splice(ape_position as usize..ape_position as usize, tag);
 	}
 
 	// Now, if there was a tag at the beginning, remove it
 	if header_ape_tag.0 {
+		data.mark_moved(header_ape_tag.1 .1);
 		file_bytes.drain(header_ape_tag.1 .0 as usize..header_ape_tag.1 .1 as usize);
 	}
 
@@ -122,15 +153,47 @@ where
 	Ok(())
 }
 
-pub(super) fn create_ape_tag<'a, I>(tag: &mut ApeTagRef<'a, I>) -> Result<Vec<u8>>
+// See `WriteOptions::trim_whitespace` and `WriteOptions::remove_empty_items`, read only items are left as-is
+fn normalize_text_item(
+	item: ApeItemRef<'_>,
+	write_options: WriteOptions,
+) -> Option<ApeItemRef<'_>> {
+	let ItemValueRef::Text(text) = &item.value else {
+		return Some(item);
+	};
+
+	if item.read_only {
+		return Some(item);
+	}
+
+	let normalized = write_options.text_item(text)?;
+	if normalized.len() == text.len() {
+		return Some(item);
+	}
+
+	let normalized = normalized.to_owned();
+	Some(ApeItemRef {
+		value: ItemValueRef::Text(Cow::Owned(normalized)),
+		..item
+	})
+}
+
+pub(super) fn create_ape_tag<'a, I>(
+	tag: &mut ApeTagRef<'a, I>,
+	unparsed_items: &[Vec<u8>],
+	write_options: WriteOptions,
+) -> Result<Vec<u8>>
 where
 	I: Iterator<Item = ApeItemRef<'a>>,
 {
-	let items = &mut tag.items;
-	let mut peek = items.peekable();
+	let mut peek = tag
+		.items
+		.by_ref()
+		.filter_map(|item| normalize_text_item(item, write_options))
+		.peekable();
 
 	// Unnecessary to write anything if there's no metadata
-	if peek.peek().is_none() {
+	if peek.peek().is_none() && unparsed_items.is_empty() && write_options.remove_tags_if_empty {
 		return Ok(Vec::<u8>::new());
 	}
 
@@ -141,11 +204,26 @@ where
 	for item in peek {
 		let (mut flags, value) = match item.value {
 			ItemValueRef::Binary(value) => {
+				if is_picture_key(item.key) {
+					// Item size, item flags, key + null
+					check_picture_size(
+						(4 + 4 + item.key.len() + 1 + value.len()) as u64,
+						write_options,
+					)?;
+				}
+
 				tag_write.write_u32::<LittleEndian>(value.len() as u32)?;
 
 				(1_u32 << 1, value)
 			},
 			ItemValueRef::Text(ref value) => {
+				// Multiple values are separated by nulls
+				if item.key.eq_ignore_ascii_case("ISRC") {
+					for isrc in value.split('\0') {
+						write_options.check_isrc(isrc);
+					}
+				}
+
 				tag_write.write_u32::<LittleEndian>(value.len() as u32)?;
 
 				(0_u32, value.as_bytes())
@@ -169,6 +247,12 @@ where
 		item_count += 1;
 	}
 
+	// Items that failed to parse are written back exactly as they were read
+	for item in unparsed_items {
+		tag_write.write_all(item)?;
+		item_count += 1;
+	}
+
 	let size = tag_write.get_ref().len();
 
 	if size as u64 + 32 > u64::from(u32::MAX) {
@@ -0,0 +1,1192 @@
+use crate::ape::ApeTag;
+use crate::error::Result;
+use crate::file::{AudioFile, FileType};
+use crate::id3::v1::Id3v1Tag;
+use crate::id3::v2::Id3v2Tag;
+#[cfg(feature = "fs")]
+use crate::id3::{
+	find_id3v1, find_id3v2, find_id3v2_footer, find_lyrics3v2, FindId3v2Config, ID3FindResults,
+};
+use crate::iff::aiff::{AIFFTextChunks, AiffFile};
+use crate::iff::wav::RIFFInfoList;
+use crate::macros::{decode_err, err};
+use crate::mp4::Ilst;
+use crate::ogg::VorbisComments;
+use crate::probe::ParseOptions;
+use crate::tag::content::tag_type_id;
+use crate::tag::{Tag, TagType};
+#[cfg(feature = "fs")]
+use crate::{
+	aac::AacFile, ape::ApeFile, flac::FlacFile, iff::wav::WavFile, mp4::Mp4File, mpeg::MpegFile,
+	musepack::MpcFile, ogg::OpusFile, ogg::SpeexFile, ogg::VorbisFile, probe::ParsingMode,
+	probe::Probe, tag::item::TagItem, traits::TagExt, wavpack::WavPackFile,
+	write_options::WriteOptions,
+};
+
+#[cfg(feature = "fs")]
+use std::fs::{File, OpenOptions};
+#[cfg(feature = "fs")]
+use std::io::{BufReader, Seek, SeekFrom};
+use std::io::{Cursor, Read};
+use std::ops::Range;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "fs")]
+use byteorder::{ByteOrder, LittleEndian};
+
+const MAGIC: &[u8; 8] = b"LOFTYTAG";
+
+// The values are persisted in archives, they must never change
+fn file_type_id(file_type: FileType) -> Option<u8> {
+	match file_type {
+		FileType::Aac => Some(0),
+		FileType::Aiff => Some(1),
+		FileType::Ape => Some(2),
+		FileType::Flac => Some(3),
+		FileType::Mpeg => Some(4),
+		FileType::Mp4 => Some(5),
+		FileType::Mpc => Some(6),
+		FileType::Opus => Some(7),
+		FileType::Vorbis => Some(8),
+		FileType::Speex => Some(9),
+		FileType::Wav => Some(10),
+		FileType::WavPack => Some(11),
+		FileType::Custom(_) => None,
+	}
+}
+
+fn file_type_from_id(id: u8) -> Option<FileType> {
+	[
+		FileType::Aac,
+		FileType::Aiff,
+		FileType::Ape,
+		FileType::Flac,
+		FileType::Mpeg,
+		FileType::Mp4,
+		FileType::Mpc,
+		FileType::Opus,
+		FileType::Vorbis,
+		FileType::Speex,
+		FileType::Wav,
+		FileType::WavPack,
+	]
+	.into_iter()
+	.find(|file_type| file_type_id(*file_type) == Some(id))
+}
+
+fn tag_type_from_id(id: u8) -> Option<TagType> {
+	[
+		TagType::Ape,
+		TagType::Id3v1,
+		TagType::Id3v2,
+		TagType::Mp4Ilst,
+		TagType::VorbisComments,
+		TagType::RiffInfo,
+		TagType::AiffText,
+	]
+	.into_iter()
+	.find(|tag_type| tag_type_id(*tag_type) == id)
+}
+
+// The file and tag types are serialized as the IDs used in `TagArchive::as_bytes`, so renaming a
+// variant doesn't break existing archives
+#[cfg(feature = "serde")]
+mod serde_ids {
+	pub(super) mod file_type {
+		use crate::file::FileType;
+
+		use serde::de::Error;
+		use serde::{Deserialize, Deserializer, Serializer};
+
+		pub(crate) fn serialize<S: Serializer>(
+			file_type: &FileType,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			let Some(id) = super::super::file_type_id(*file_type) else {
+				return Err(serde::ser::Error::custom(
+					"custom file types can't be archived",
+				));
+			};
+
+			serializer.serialize_u8(id)
+		}
+
+		pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<FileType, D::Error> {
+			let id = u8::deserialize(deserializer)?;
+			super::super::file_type_from_id(id).ok_or_else(|| D::Error::custom("unknown file type"))
+		}
+	}
+
+	pub(super) mod tag_type {
+		use crate::tag::content::tag_type_id;
+		use crate::tag::TagType;
+
+		use serde::de::Error;
+		use serde::{Deserialize, Deserializer, Serializer};
+
+		// `serde(with)` passes the field by reference
+		#[allow(clippy::trivially_copy_pass_by_ref)]
+		pub(crate) fn serialize<S: Serializer>(
+			tag_type: &TagType,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			serializer.serialize_u8(tag_type_id(*tag_type))
+		}
+
+		pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<TagType, D::Error> {
+			let id = u8::deserialize(deserializer)?;
+			super::super::tag_type_from_id(id).ok_or_else(|| D::Error::custom("unknown tag type"))
+		}
+	}
+}
+
+// Whether lofty can write `tag_type` to files of `file_type`, some tags are only ever read
+#[cfg(feature = "fs")]
+fn is_writable(file_type: FileType, tag_type: TagType) -> bool {
+	let read_only = match tag_type {
+		TagType::Ape => ApeTag::READ_ONLY_FORMATS,
+		TagType::Id3v1 => Id3v1Tag::READ_ONLY_FORMATS,
+		TagType::Id3v2 => Id3v2Tag::READ_ONLY_FORMATS,
+		TagType::Mp4Ilst => Ilst::READ_ONLY_FORMATS,
+		TagType::VorbisComments => VorbisComments::READ_ONLY_FORMATS,
+		TagType::RiffInfo => RIFFInfoList::READ_ONLY_FORMATS,
+		TagType::AiffText => AIFFTextChunks::READ_ONLY_FORMATS,
+	};
+
+	file_type.supports_tag_type(tag_type) && !read_only.contains(&file_type)
+}
+
+/// A snapshot of every tag in a file, created by [`export_tags`]
+///
+/// Each tag is stored exactly as lofty would write it, so unknown items (e.g. ID3v2 frames lofty
+/// doesn't understand) and pictures are kept. This makes it suitable as a backup before a batch
+/// operation, which can be undone with [`import_tags`].
+///
+/// An archive can be persisted with [`TagArchive::as_bytes`], and loaded again with
+/// [`TagArchive::from_bytes`]. The format is versioned, see [`TagArchive::VERSION`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::{TagArchive, WriteOptions};
+///
+/// # #[cfg(feature = "fs")]
+/// # fn main() -> lofty::Result<()> {
+/// let archive = lofty::export_tags("foo.mp3")?;
+/// std::fs::write("foo.mp3.tags", archive.as_bytes()?)?;
+///
+/// // Some time later...
+/// let archive = TagArchive::from_bytes(&std::fs::read("foo.mp3.tags")?)?;
+/// lofty::import_tags("foo.mp3", &archive, WriteOptions::default())?;
+/// # Ok(()) }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TagArchive {
+	#[cfg_attr(feature = "serde", serde(with = "serde_ids::file_type"))]
+	file_type: FileType,
+	tags: Vec<ArchivedTag>,
+}
+
+impl TagArchive {
+	/// The current version of the format produced by [`TagArchive::as_bytes`]
+	///
+	/// Archives of any version up to and including this one can be read.
+	///
+	/// * **1**: The file type and tags
+	/// * **2**: Adds the [regions](ArchivedTag::regions) of the tags
+	pub const VERSION: u8 = 2;
+
+	/// The [`FileType`] of the file the tags were exported from
+	pub fn file_type(&self) -> FileType {
+		self.file_type
+	}
+
+	/// The archived tags, in the order they were read
+	pub fn tags(&self) -> &[ArchivedTag] {
+		&self.tags
+	}
+
+	/// Serialize the archive
+	///
+	/// # Errors
+	///
+	/// The archive can't be represented in the format, it has more than 255 tags, or a tag larger
+	/// than 4 GB (see [`ErrorKind::TooMuchData`](crate::error::ErrorKind::TooMuchData))
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::TagArchive;
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path = "tests/files/assets/minimal/full_test.mp3";
+	/// let archive = lofty::export_tags(path)?;
+	///
+	/// let bytes = archive.as_bytes()?;
+	/// assert_eq!(TagArchive::from_bytes(&bytes)?, archive);
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	pub fn as_bytes(&self) -> Result<Vec<u8>> {
+		// Custom file types are rejected by `export_tags`
+		let Some(file_type_id) = file_type_id(self.file_type) else {
+			err!(UnknownFormat);
+		};
+
+		let Ok(tag_count) = u8::try_from(self.tags.len()) else {
+			err!(TooMuchData);
+		};
+
+		let mut bytes = Vec::new();
+		bytes.extend(MAGIC);
+		bytes.push(Self::VERSION);
+		bytes.push(file_type_id);
+		bytes.push(tag_count);
+
+		for tag in &self.tags {
+			let (Ok(len), Ok(region_count)) = (
+				u32::try_from(tag.data.len()),
+				u32::try_from(tag.regions.len()),
+			) else {
+				err!(TooMuchData);
+			};
+
+			bytes.push(tag_type_id(tag.tag_type));
+			bytes.write_u32::<BigEndian>(len)?;
+			bytes.extend(&tag.data);
+
+			bytes.write_u32::<BigEndian>(region_count)?;
+			for region in &tag.regions {
+				bytes.write_u64::<BigEndian>(region.start)?;
+				bytes.write_u64::<BigEndian>(region.end)?;
+			}
+		}
+
+		Ok(bytes)
+	}
+
+	/// Deserialize an archive created by [`TagArchive::as_bytes`]
+	///
+	/// # Errors
+	///
+	/// * The archive is malformed
+	/// * The archive was created by a newer version of lofty, see [`TagArchive::VERSION`]
+	pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+		let mut reader = Cursor::new(bytes);
+
+		let mut magic = [0; 8];
+		reader.read_exact(&mut magic)?;
+		if &magic != MAGIC {
+			decode_err!(@BAIL "Tag archive: Invalid magic");
+		}
+
+		let version = reader.read_u8()?;
+		if version == 0 || version > Self::VERSION {
+			decode_err!(@BAIL "Tag archive: Unsupported version");
+		}
+
+		let Some(file_type) = file_type_from_id(reader.read_u8()?) else {
+			decode_err!(@BAIL "Tag archive: Unknown file type");
+		};
+
+		let tag_count = reader.read_u8()?;
+		let mut tags = Vec::with_capacity(usize::from(tag_count));
+		for _ in 0..tag_count {
+			let Some(tag_type) = tag_type_from_id(reader.read_u8()?) else {
+				decode_err!(@BAIL "Tag archive: Unknown tag type");
+			};
+
+			let len = reader.read_u32::<BigEndian>()? as usize;
+			let start = reader.position() as usize;
+			let Some(data) = bytes.get(start..start + len) else {
+				err!(SizeMismatch);
+			};
+			reader.set_position((start + len) as u64);
+
+			let mut regions = Vec::new();
+			if version >= 2 {
+				let region_count = reader.read_u32::<BigEndian>()?;
+				for _ in 0..region_count {
+					let start = reader.read_u64::<BigEndian>()?;
+					let end = reader.read_u64::<BigEndian>()?;
+					if start > end {
+						decode_err!(@BAIL "Tag archive: Invalid tag region");
+					}
+
+					regions.push(start..end);
+				}
+			}
+
+			let tag = ArchivedTag {
+				tag_type,
+				data: data.to_vec(),
+				regions,
+			};
+
+			// Make sure the tag can actually be restored
+			tag.parse()?;
+			tags.push(tag);
+		}
+
+		if reader.position() != bytes.len() as u64 {
+			err!(SizeMismatch);
+		}
+
+		Ok(Self { file_type, tags })
+	}
+}
+
+/// A single tag of a [`TagArchive`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArchivedTag {
+	#[cfg_attr(feature = "serde", serde(with = "serde_ids::tag_type"))]
+	tag_type: TagType,
+	data: Vec<u8>,
+	regions: Vec<Range<u64>>,
+}
+
+impl ArchivedTag {
+	/// The [`TagType`] of the tag
+	pub fn tag_type(&self) -> TagType {
+		self.tag_type
+	}
+
+	/// The tag, as it would be produced by [`TagExt::dump_to`](crate::TagExt::dump_to)
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Where the tag was stored in the file it was exported from, sorted by offset
+	///
+	/// A tag isn't necessarily stored in one place, for example the pictures in a FLAC file are
+	/// stored separately from its Vorbis comments, and an appended ID3v2 tag (see
+	/// [`WriteOptions::append_id3v2`](crate::WriteOptions::append_id3v2)) is separate from the
+	/// one at the start of the file. In Ogg files, these are the pages holding the comment header,
+	/// which may also hold the start of the next packet.
+	///
+	/// This is empty for tags read from an archive of [version](TagArchive::VERSION) 1.
+	pub fn regions(&self) -> &[Range<u64>] {
+		&self.regions
+	}
+
+	/// Convert the archived tag into a [`Tag`]
+	///
+	/// # Errors
+	///
+	/// The archived tag is malformed
+	pub fn to_tag(&self) -> Result<Tag> {
+		self.parse().map(Into::into)
+	}
+
+	fn parse(&self) -> Result<ConcreteTag> {
+		let parse_options = ParseOptions::default();
+		let mut reader = Cursor::new(self.data.as_slice());
+
+		let tag = match self.tag_type {
+			TagType::Ape => match crate::ape::tag::read::read_ape_tag(
+				&mut reader,
+				false,
+				parse_options.parsing_mode,
+			)? {
+				Some((tag, _)) => ConcreteTag::Ape(tag),
+				None => err!(FakeTag),
+			},
+			TagType::Id3v1 => {
+				let Ok(tag) = <[u8; 128]>::try_from(self.data.as_slice()) else {
+					err!(SizeMismatch);
+				};
+
+				if &tag[..3] != b"TAG" {
+					err!(FakeTag);
+				}
+
+				ConcreteTag::Id3v1(crate::id3::v1::read::parse_id3v1(tag))
+			},
+			TagType::Id3v2 => {
+				let header = crate::id3::v2::header::Id3v2Header::parse(&mut reader)?;
+				ConcreteTag::Id3v2(crate::id3::v2::read::parse_id3v2(
+					&mut reader,
+					header,
+					parse_options,
+				)?)
+			},
+			TagType::Mp4Ilst => ConcreteTag::Ilst(crate::mp4::ilst::read::parse_ilst_atom(
+				&self.data,
+				parse_options.parsing_mode,
+			)?),
+			TagType::VorbisComments => {
+				ConcreteTag::VorbisComments(crate::ogg::read::read_comments(
+					&mut reader,
+					self.data.len() as u64,
+					parse_options.parsing_mode,
+				)?)
+			},
+			TagType::RiffInfo => ConcreteTag::RiffInfo(
+				crate::iff::wav::tag::read::parse_riff_info_list(&self.data)?,
+			),
+			TagType::AiffText => {
+				// The text chunks are only ever read from an entire file
+				let mut form = Vec::with_capacity(self.data.len() + 12);
+				form.extend(b"FORM");
+				form.extend((self.data.len() as u32 + 4).to_be_bytes());
+				form.extend(b"AIFF");
+				form.extend(&self.data);
+
+				let aiff = AiffFile::read_from(
+					&mut Cursor::new(form),
+					ParseOptions::new().read_properties(false),
+				)?;
+				ConcreteTag::AiffText(aiff.text_chunks_tag.unwrap_or_default())
+			},
+		};
+
+		Ok(tag)
+	}
+}
+
+enum ConcreteTag {
+	Ape(ApeTag),
+	Id3v1(Id3v1Tag),
+	Id3v2(Id3v2Tag),
+	Ilst(Ilst),
+	VorbisComments(VorbisComments),
+	RiffInfo(RIFFInfoList),
+	AiffText(AIFFTextChunks),
+}
+
+macro_rules! for_each_concrete_tag {
+	($tag:expr, |$inner:ident| $body:expr) => {
+		match $tag {
+			ConcreteTag::Ape($inner) => $body,
+			ConcreteTag::Id3v1($inner) => $body,
+			ConcreteTag::Id3v2($inner) => $body,
+			ConcreteTag::Ilst($inner) => $body,
+			ConcreteTag::VorbisComments($inner) => $body,
+			ConcreteTag::RiffInfo($inner) => $body,
+			ConcreteTag::AiffText($inner) => $body,
+		}
+	};
+}
+
+#[cfg(feature = "fs")]
+impl ConcreteTag {
+	fn tag_type(&self) -> TagType {
+		match self {
+			ConcreteTag::Ape(_) => TagType::Ape,
+			ConcreteTag::Id3v1(_) => TagType::Id3v1,
+			ConcreteTag::Id3v2(_) => TagType::Id3v2,
+			ConcreteTag::Ilst(_) => TagType::Mp4Ilst,
+			ConcreteTag::VorbisComments(_) => TagType::VorbisComments,
+			ConcreteTag::RiffInfo(_) => TagType::RiffInfo,
+			ConcreteTag::AiffText(_) => TagType::AiffText,
+		}
+	}
+
+	fn archive(&self) -> Result<Option<ArchivedTag>> {
+		let mut data = Vec::new();
+		// Everything is archived as-is, `import_tags` applies the caller's `WriteOptions`
+		let write_options = WriteOptions::new()
+			.remove_tags_if_empty(false)
+			.remove_empty_items(false);
+		for_each_concrete_tag!(self, |tag| tag.dump_to(&mut data, write_options))?;
+
+		if data.is_empty() {
+			return Ok(None);
+		}
+
+		Ok(Some(ArchivedTag {
+			tag_type: self.tag_type(),
+			data,
+			regions: Vec::new(),
+		}))
+	}
+
+	#[cfg(feature = "fs")]
+	fn save_to(&self, file: &mut File, write_options: WriteOptions) -> Result<()> {
+		file.rewind()?;
+		for_each_concrete_tag!(self, |tag| tag.save_to(file, write_options))
+	}
+}
+
+impl From<ConcreteTag> for Tag {
+	fn from(input: ConcreteTag) -> Self {
+		for_each_concrete_tag!(input, |tag| tag.into())
+	}
+}
+
+#[cfg(feature = "fs")]
+fn read_tags(file: &mut File, file_type: FileType) -> Result<Vec<ConcreteTag>> {
+	let parse_options = ParseOptions::new().read_properties(false);
+	let reader = &mut BufReader::new(file);
+
+	let mut tags = Vec::new();
+	let mut push = |tag: Option<ConcreteTag>| tags.extend(tag);
+
+	match file_type {
+		FileType::Aac => {
+			let file = AacFile::read_from(reader, parse_options)?;
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+			push(file.id3v1_tag.map(ConcreteTag::Id3v1));
+		},
+		FileType::Aiff => {
+			let file = AiffFile::read_from(reader, parse_options)?;
+			push(file.text_chunks_tag.map(ConcreteTag::AiffText));
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+		},
+		FileType::Ape => {
+			let file = ApeFile::read_from(reader, parse_options)?;
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+			push(file.ape_tag.map(ConcreteTag::Ape));
+			push(file.id3v1_tag.map(ConcreteTag::Id3v1));
+		},
+		FileType::Flac => {
+			let mut file = FlacFile::read_from(reader, parse_options)?;
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+
+			// The pictures are written alongside the Vorbis comments, creating them if necessary
+			match file.vorbis_comments_tag {
+				Some(mut vorbis_comments) => {
+					vorbis_comments.pictures.append(&mut file.pictures);
+					push(Some(ConcreteTag::VorbisComments(vorbis_comments)));
+				},
+				None if !file.pictures.is_empty() => {
+					push(Some(ConcreteTag::VorbisComments(VorbisComments {
+						vendor: String::new(),
+						items: Vec::new(),
+						pictures: file.pictures,
+						unparsed_items: Vec::new(),
+					})));
+				},
+				None => {},
+			}
+		},
+		FileType::Mpeg => {
+			let file = MpegFile::read_from(reader, parse_options)?;
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+			push(file.ape_tag.map(ConcreteTag::Ape));
+			push(file.id3v1_tag.map(ConcreteTag::Id3v1));
+		},
+		FileType::Mp4 => {
+			let file = Mp4File::read_from(reader, parse_options)?;
+			push(file.ilst_tag.map(ConcreteTag::Ilst));
+		},
+		FileType::Mpc => {
+			let file = MpcFile::read_from(reader, parse_options)?;
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+			push(file.ape_tag.map(ConcreteTag::Ape));
+			push(file.id3v1_tag.map(ConcreteTag::Id3v1));
+		},
+		FileType::Opus => {
+			let file = OpusFile::read_from(reader, parse_options)?;
+			push(Some(ConcreteTag::VorbisComments(file.vorbis_comments_tag)));
+		},
+		FileType::Vorbis => {
+			let file = VorbisFile::read_from(reader, parse_options)?;
+			push(Some(ConcreteTag::VorbisComments(file.vorbis_comments_tag)));
+		},
+		FileType::Speex => {
+			let file = SpeexFile::read_from(reader, parse_options)?;
+			push(Some(ConcreteTag::VorbisComments(file.vorbis_comments_tag)));
+		},
+		FileType::Wav => {
+			let file = WavFile::read_from(reader, parse_options)?;
+			push(file.riff_info_tag.map(ConcreteTag::RiffInfo));
+			push(file.id3v2_tag.map(ConcreteTag::Id3v2));
+		},
+		FileType::WavPack => {
+			let file = WavPackFile::read_from(reader, parse_options)?;
+			push(file.ape_tag.map(ConcreteTag::Ape));
+			push(file.id3v1_tag.map(ConcreteTag::Id3v1));
+		},
+		FileType::Custom(_) => err!(UnknownFormat),
+	}
+
+	Ok(tags)
+}
+
+// Where each tag is stored in the file, a tag can have multiple regions
+#[cfg(feature = "fs")]
+pub(crate) fn tag_regions<R>(
+	file: &mut R,
+	file_type: FileType,
+) -> Result<Vec<(TagType, Range<u64>)>>
+where
+	R: Read + Seek,
+{
+	let mut regions = Vec::new();
+
+	file.rewind()?;
+	match file_type {
+		FileType::Aac | FileType::Ape | FileType::Mpeg | FileType::Mpc | FileType::WavPack => {
+			let leading_end = leading_id3v2_region(file, &mut regions)?;
+			trailing_tag_regions(file, leading_end, &mut regions)?;
+		},
+		FileType::Flac => {
+			leading_id3v2_region(file, &mut regions)?;
+
+			file.rewind()?;
+			for region in crate::flac::comment_block_ranges(file)? {
+				regions.push((TagType::VorbisComments, region));
+			}
+		},
+		FileType::Mp4 => {
+			if let Some(region) = crate::mp4::ilst_range(file)? {
+				regions.push((TagType::Mp4Ilst, region));
+			}
+		},
+		FileType::Opus | FileType::Vorbis | FileType::Speex => {
+			let header_sig = match file_type {
+				FileType::Opus => crate::ogg::constants::OPUSHEAD,
+				FileType::Vorbis => crate::ogg::constants::VORBIS_IDENT_HEAD,
+				_ => crate::ogg::constants::SPEEXHEADER,
+			};
+
+			for region in crate::ogg::comment_header_pages(file, header_sig)? {
+				regions.push((TagType::VorbisComments, region));
+			}
+		},
+		FileType::Wav => chunk_regions::<LittleEndian, _>(file, file_type, &mut regions)?,
+		FileType::Aiff => chunk_regions::<BigEndian, _>(file, file_type, &mut regions)?,
+		FileType::Custom(_) => err!(UnknownFormat),
+	}
+
+	file.rewind()?;
+	Ok(regions)
+}
+
+// Returns the end of the ID3v2 tag, or the start of the file if there isn't one
+#[cfg(feature = "fs")]
+fn leading_id3v2_region<R>(file: &mut R, regions: &mut Vec<(TagType, Range<u64>)>) -> Result<u64>
+where
+	R: Read + Seek,
+{
+	let ID3FindResults(header, _) = find_id3v2(
+		file,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+	let end = file.stream_position()?;
+
+	let Some(header) = header else {
+		return Ok(0);
+	};
+
+	let footer_len = if header.flags.footer { 10 } else { 0 };
+	let start = end - 10 - u64::from(header.size) - footer_len;
+	regions.push((TagType::Id3v2, start..end));
+
+	Ok(end)
+}
+
+// Trailing tags are laid out as: ID3v2, APE, Lyrics3v2, ID3v1, with the appended ID3v2 tag
+// possibly following the APE tag instead
+#[cfg(feature = "fs")]
+fn trailing_tag_regions<R>(
+	file: &mut R,
+	leading_end: u64,
+	regions: &mut Vec<(TagType, Range<u64>)>,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	file.seek(SeekFrom::End(0))?;
+
+	// This will seek us to the start of the ID3v1 tag, or the end of the file
+	let ID3FindResults(id3v1, _) = find_id3v1(file, false)?;
+	let mut end = file.stream_position()?;
+	if id3v1.is_some() {
+		regions.push((TagType::Id3v1, end..end + 128));
+	}
+
+	if end >= 15 {
+		find_lyrics3v2(file)?;
+		end = file.stream_position()?;
+	}
+
+	for _ in 0..2 {
+		file.seek(SeekFrom::Start(end))?;
+		if let ID3FindResults(Some(_), _) = find_id3v2_footer(file, leading_end, false)? {
+			let start = file.stream_position()?;
+			regions.push((TagType::Id3v2, start..end));
+
+			end = start;
+			continue;
+		}
+
+		if end < leading_end + 32 {
+			break;
+		}
+
+		file.seek(SeekFrom::Start(end - 32))?;
+		let Some((_, header)) =
+			crate::ape::tag::read::read_ape_tag(file, true, ParsingMode::BestAttempt)?
+		else {
+			break;
+		};
+
+		let start = end.saturating_sub(u64::from(header.size));
+		regions.push((TagType::Ape, start..end));
+		end = start;
+	}
+
+	Ok(())
+}
+
+// The tags of WAV and AIFF files are stored in top-level chunks
+#[cfg(feature = "fs")]
+fn chunk_regions<B: ByteOrder, R>(
+	file: &mut R,
+	file_type: FileType,
+	regions: &mut Vec<(TagType, Range<u64>)>,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let file_len = file.seek(SeekFrom::End(0))?;
+
+	// Skipping the RIFF/FORM header
+	let mut pos = 12;
+	while pos + 8 <= file_len {
+		file.seek(SeekFrom::Start(pos))?;
+
+		let mut fourcc = [0; 4];
+		file.read_exact(&mut fourcc)?;
+		let size = u64::from(file.read_u32::<B>()?);
+
+		// Chunks are padded to an even size
+		let end = (pos + 8 + size + (size % 2)).min(file_len);
+
+		let tag_type = match &fourcc {
+			b"ID3 " | b"id3 " => Some(TagType::Id3v2),
+			b"LIST" if file_type == FileType::Wav => {
+				let mut list_type = [0; 4];
+				let is_info = file.read_exact(&mut list_type).is_ok() && &list_type == b"INFO";
+				is_info.then_some(TagType::RiffInfo)
+			},
+			b"NAME" | b"AUTH" | b"(c) " | b"ANNO" | b"COMT" if file_type == FileType::Aiff => {
+				Some(TagType::AiffText)
+			},
+			_ => None,
+		};
+
+		if let Some(tag_type) = tag_type {
+			regions.push((tag_type, pos..end));
+		}
+
+		pos = end;
+	}
+
+	Ok(())
+}
+
+#[cfg(feature = "fs")]
+fn guess_file_type(file: &mut File) -> Result<FileType> {
+	file.rewind()?;
+	let file_type = Probe::new(BufReader::new(&mut *file))
+		.guess_file_type()?
+		.file_type();
+	file.rewind()?;
+
+	match file_type {
+		Some(FileType::Custom(_)) | None => err!(UnknownFormat),
+		Some(file_type) => Ok(file_type),
+	}
+}
+
+#[cfg(feature = "fs")]
+/// Export every tag in a file to a [`TagArchive`]
+///
+/// # Errors
+///
+/// * `path` does not exist
+/// * The file type could not be determined, or is a [`FileType::Custom`]
+/// * The file is malformed
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::{FileType, TagType};
+///
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.mp3";
+/// let archive = lofty::export_tags(path)?;
+/// assert_eq!(archive.file_type(), FileType::Mpeg);
+///
+/// for tag in archive.tags() {
+/// 	println!("{:?}: {} bytes", tag.tag_type(), tag.data().len());
+/// }
+/// # Ok(()) }
+/// ```
+pub fn export_tags<P: AsRef<Path>>(path: P) -> Result<TagArchive> {
+	let mut file = File::open(path)?;
+	let file_type = guess_file_type(&mut file)?;
+
+	let regions = tag_regions(&mut file, file_type)?;
+
+	let mut tags = Vec::new();
+	for tag in read_tags(&mut file, file_type)? {
+		let Some(mut archived) = tag.archive()? else {
+			continue;
+		};
+
+		archived.regions = regions
+			.iter()
+			.filter(|(tag_type, _)| *tag_type == archived.tag_type)
+			.map(|(_, region)| region.clone())
+			.collect();
+		archived.regions.sort_by_key(|region| region.start);
+
+		tags.push(archived);
+	}
+
+	Ok(TagArchive { file_type, tags })
+}
+
+#[cfg(feature = "fs")]
+/// Restore the tags of a [`TagArchive`] to a file
+///
+/// When the file is of the same [`FileType`] the archive was exported from, its tags are replaced by
+/// the archived tags, and any tag that isn't in the archive is removed. Provided the audio hasn't
+/// changed, this restores the file to the state it was in when exported, given that it was last
+/// written by lofty. Tags that are written in place (e.g. an ID3v2 tag that fits in the space of
+/// the previous one) keep the size of that space, so tags that have grown since being exported are
+/// restored with the difference as padding.
+///
+/// Archived tags that can't be written to the file are converted to its
+/// [primary tag type](FileType::primary_tag_type), unless the archive already contains a tag of
+/// that type. Tags that can be written to the file are written as-is.
+///
+/// NOTE: Tags that lofty can only read in the file (e.g. ID3v2 in FLAC) are left untouched.
+///
+/// Returns the items that could not be carried over to the file. This is always empty when
+/// restoring to the same [`FileType`].
+///
+/// # Errors
+///
+/// * `path` does not exist
+/// * The file type could not be determined, or is a [`FileType::Custom`]
+/// * The file is malformed
+/// * It is unable to write to the file
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::{ItemKey, WriteOptions};
+///
+/// # fn main() -> lofty::Result<()> {
+/// let archive = lofty::export_tags("foo.flac")?;
+///
+/// let lost = lofty::import_tags("foo.mp3", &archive, WriteOptions::default())?;
+/// for item in lost {
+/// 	println!("Unable to restore {:?}", item.key());
+/// }
+/// # Ok(()) }
+/// ```
+pub fn import_tags<P: AsRef<Path>>(
+	path: P,
+	archive: &TagArchive,
+	write_options: WriteOptions,
+) -> Result<Vec<TagItem>> {
+	let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+	let file_type = guess_file_type(&mut file)?;
+
+	let existing_tags = read_tags(&mut file, file_type)?
+		.iter()
+		.map(ConcreteTag::tag_type)
+		.collect::<Vec<_>>();
+
+	let mut restored = Vec::new();
+	let mut unwritable = Vec::new();
+	for archived in &archive.tags {
+		let tag = archived.parse()?;
+		if is_writable(file_type, archived.tag_type) {
+			tag.save_to(&mut file, write_options)?;
+			restored.push(archived.tag_type);
+		} else {
+			unwritable.push(tag);
+		}
+	}
+
+	let mut lost = Vec::new();
+	let primary_tag_type = file_type.primary_tag_type();
+	for tag in unwritable {
+		let mut tag = Tag::from(tag);
+		if restored.contains(&primary_tag_type) {
+			log::warn!(
+				"Unable to restore {:?} tag, the file already has a {primary_tag_type:?} tag",
+				tag.tag_type()
+			);
+			lost.extend(tag.items().cloned());
+			continue;
+		}
+
+		lost.extend(
+			tag.items()
+				.filter(|item| !item.re_map(primary_tag_type))
+				.cloned(),
+		);
+		tag.re_map(primary_tag_type);
+
+		file.rewind()?;
+		tag.save_to(&mut file, write_options)?;
+		restored.push(primary_tag_type);
+	}
+
+	for tag_type in existing_tags {
+		if restored.contains(&tag_type) || !is_writable(file_type, tag_type) {
+			continue;
+		}
+
+		file.rewind()?;
+		tag_type.remove_from(&mut file)?;
+	}
+
+	Ok(lost)
+}
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+	use super::{file_type_id, ArchivedTag, MAGIC};
+	use crate::error::ErrorKind;
+	use crate::tag::content::tag_type_id;
+	use crate::{
+		Accessor, AudioFile, FileType, ItemKey, TagArchive, TagExt, TagType, TaggedFileExt,
+		WriteOptions,
+	};
+
+	use std::io::{Seek, Write};
+
+	fn temp_file(path: &str) -> tempfile::NamedTempFile {
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		file.write_all(&std::fs::read(path).unwrap()).unwrap();
+		file.rewind().unwrap();
+
+		// Normalize the file, the archive holds the tags as lofty writes them
+		let tagged_file = crate::read_from(file.as_file_mut()).unwrap();
+		file.rewind().unwrap();
+		tagged_file
+			.save_to(file.as_file_mut(), WriteOptions::default())
+			.unwrap();
+
+		file.rewind().unwrap();
+		file
+	}
+
+	// The new ID3v2 tag is no larger than the original, so it's written in place and restoring it
+	// doesn't leave any padding behind
+	fn retag(file: &mut tempfile::NamedTempFile) {
+		file.rewind().unwrap();
+		let mut tagged_file = crate::read_from(file.as_file_mut()).unwrap();
+		let tag_types = tagged_file
+			.tags()
+			.iter()
+			.map(|tag| tag.tag_type())
+			.collect::<Vec<_>>();
+		for tag_type in tag_types {
+			let tag = tagged_file.tag_mut(tag_type).unwrap();
+			tag.set_title(String::from("A title"));
+			tag.remove_artist();
+		}
+
+		file.rewind().unwrap();
+		tagged_file
+			.save_to(file.as_file_mut(), WriteOptions::default())
+			.unwrap();
+	}
+
+	#[test]
+	fn restore_byte_for_byte() {
+		for path in [
+			"tests/files/assets/minimal/full_test.mp3",
+			"tests/files/assets/minimal/full_test.flac",
+			"tests/files/assets/minimal/m4a_codec_aac.m4a",
+			"tests/files/assets/minimal/wav_format_pcm.wav",
+			"tests/files/assets/minimal/full_test.ogg",
+			"tests/files/assets/minimal/full_test.ape",
+			"tests/files/assets/minimal/full_test.aiff",
+		] {
+			let mut file = temp_file(path);
+			let original = std::fs::read(file.path()).unwrap();
+
+			let archive = crate::export_tags(file.path()).unwrap();
+			assert!(!archive.tags().is_empty(), "{path}");
+			for tag in archive.tags() {
+				assert!(!tag.regions().is_empty(), "{path}: {:?}", tag.tag_type());
+			}
+
+			retag(&mut file);
+			assert_ne!(std::fs::read(file.path()).unwrap(), original, "{path}");
+
+			let lost = crate::import_tags(file.path(), &archive, WriteOptions::default()).unwrap();
+			assert!(lost.is_empty());
+			assert_eq!(std::fs::read(file.path()).unwrap(), original, "{path}");
+		}
+	}
+
+	#[test]
+	fn removes_new_tags() {
+		let mut file = temp_file("tests/files/assets/minimal/full_test.mp3");
+		TagType::Ape.remove_from(file.as_file_mut()).unwrap();
+
+		let original = std::fs::read(file.path()).unwrap();
+		let archive = crate::export_tags(file.path()).unwrap();
+		assert!(archive
+			.tags()
+			.iter()
+			.all(|tag| tag.tag_type() != TagType::Ape));
+
+		let mut ape = crate::ape::ApeTag::default();
+		ape.set_title(String::from("Foo title"));
+		file.rewind().unwrap();
+		ape.save_to(file.as_file_mut(), WriteOptions::default())
+			.unwrap();
+
+		crate::import_tags(file.path(), &archive, WriteOptions::default()).unwrap();
+		assert_eq!(std::fs::read(file.path()).unwrap(), original);
+	}
+
+	#[test]
+	fn serialization_round_trip() {
+		let file = temp_file("tests/files/assets/minimal/full_test.flac");
+		let archive = crate::export_tags(file.path()).unwrap();
+		assert_eq!(archive.file_type(), FileType::Flac);
+
+		let bytes = archive.as_bytes().unwrap();
+		assert_eq!(TagArchive::from_bytes(&bytes).unwrap(), archive);
+
+		// Truncated
+		assert!(TagArchive::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+		// Trailing data
+		let mut extended = bytes.clone();
+		extended.push(0);
+		assert!(TagArchive::from_bytes(&extended).is_err());
+
+		// A version from the future
+		let mut future = bytes;
+		future[8] = TagArchive::VERSION + 1;
+		assert!(TagArchive::from_bytes(&future).is_err());
+	}
+
+	#[test]
+	fn too_many_tags() {
+		let tag = ArchivedTag {
+			tag_type: TagType::Id3v1,
+			data: Vec::new(),
+			regions: Vec::new(),
+		};
+		let archive = TagArchive {
+			file_type: FileType::Mpeg,
+			tags: vec![tag; 256],
+		};
+
+		assert!(matches!(
+			archive.as_bytes().unwrap_err().kind(),
+			ErrorKind::TooMuchData
+		));
+	}
+
+	#[test]
+	fn read_version_1() {
+		let file = temp_file("tests/files/assets/minimal/full_test.mp3");
+		let archive = crate::export_tags(file.path()).unwrap();
+
+		// Version 1 archives are the same, minus the regions
+		let mut bytes = Vec::new();
+		bytes.extend(MAGIC);
+		bytes.push(1);
+		bytes.push(file_type_id(archive.file_type()).unwrap());
+		bytes.push(archive.tags().len() as u8);
+		for tag in archive.tags() {
+			bytes.push(tag_type_id(tag.tag_type()));
+			bytes.extend((tag.data().len() as u32).to_be_bytes());
+			bytes.extend(tag.data());
+		}
+
+		let read = TagArchive::from_bytes(&bytes).unwrap();
+		assert_eq!(read.tags().len(), archive.tags().len());
+		for (read, archived) in read.tags().iter().zip(archive.tags()) {
+			assert_eq!(read.tag_type(), archived.tag_type());
+			assert_eq!(read.data(), archived.data());
+			assert!(read.regions().is_empty());
+		}
+	}
+
+	#[test]
+	fn tag_regions() {
+		let file = temp_file("tests/files/assets/minimal/full_test.mp3");
+		let contents = std::fs::read(file.path()).unwrap();
+		let archive = crate::export_tags(file.path()).unwrap();
+
+		for tag in archive.tags() {
+			let regions = tag.regions();
+			assert_eq!(regions.len(), 1, "{:?}", tag.tag_type());
+
+			let region = &contents[regions[0].start as usize..regions[0].end as usize];
+			match tag.tag_type() {
+				TagType::Id3v2 => assert!(region.starts_with(b"ID3")),
+				TagType::Ape => {
+					assert!(region.starts_with(b"APETAGEX"));
+					assert!(region[region.len() - 32..].starts_with(b"APETAGEX"));
+				},
+				TagType::Id3v1 => {
+					assert!(region.starts_with(b"TAG"));
+					assert_eq!(regions[0].end, contents.len() as u64);
+				},
+				_ => unreachable!(),
+			}
+		}
+
+		// FLAC pictures are stored apart from the Vorbis comments
+		let file = temp_file("tests/files/assets/minimal/full_test.flac");
+		let archive = crate::export_tags(file.path()).unwrap();
+		let vorbis_comments = archive
+			.tags()
+			.iter()
+			.find(|tag| tag.tag_type() == TagType::VorbisComments)
+			.unwrap();
+		assert!(!vorbis_comments.regions().is_empty());
+		assert!(vorbis_comments
+			.regions()
+			.windows(2)
+			.all(|regions| regions[0].end <= regions[1].start));
+	}
+
+	#[test]
+	fn import_to_different_format() {
+		let mut flac = temp_file("tests/files/assets/minimal/full_test.flac");
+		let mut vorbis_comments = crate::ogg::VorbisComments::default();
+		vorbis_comments.set_title(String::from("Foo title"));
+		vorbis_comments.push(String::from("FOO_CUSTOM"), String::from("Bar"));
+		flac.rewind().unwrap();
+		vorbis_comments
+			.save_to(flac.as_file_mut(), WriteOptions::default())
+			.unwrap();
+
+		let archive = crate::export_tags(flac.path()).unwrap();
+
+		let mut m4a = temp_file("tests/files/assets/minimal/m4a_codec_aac.m4a");
+		let lost = crate::import_tags(m4a.path(), &archive, WriteOptions::default()).unwrap();
+		assert_eq!(lost.len(), 1);
+		assert_eq!(lost[0].key(), &ItemKey::Unknown(String::from("FOO_CUSTOM")));
+
+		m4a.rewind().unwrap();
+		let tagged_file = crate::read_from(m4a.as_file_mut()).unwrap();
+		assert_eq!(tagged_file.tags().len(), 1);
+
+		let ilst = tagged_file.tag(TagType::Mp4Ilst).unwrap();
+		assert_eq!(ilst.title().as_deref(), Some("Foo title"));
+		assert!(ilst.artist().is_none());
+	}
+}
@@ -4,11 +4,12 @@
 //! which can be extended at any time.
 
 use crate::file::FileType;
-use crate::id3::v2::FrameId;
+use crate::id3::v2::{FrameId, RestrictionViolation};
 use crate::tag::item::ItemKey;
 
 use std::collections::TryReserveError;
 use std::fmt::{Debug, Display, Formatter};
+use std::ops::Range;
 
 use ogg_pager::PageError;
 
@@ -35,12 +36,23 @@ pub enum ErrorKind {
 	FileDecoding(FileDecodingError),
 	/// Errors that occur while encoding a file
 	FileEncoding(FileEncodingError),
+	/// Arises when reading a partially available file, and the required data lies outside of the available ranges
+	///
+	/// This contains the first range that was requested, but unavailable. Note that more data may be required
+	/// after fetching this range.
+	///
+	/// See [`read_from_head_and_tail`](crate::read_from_head_and_tail).
+	NeedRange(Range<u64>),
 
 	// Picture related errors
 	/// Provided an invalid picture
 	NotAPicture,
 	/// Attempted to write a picture that the format does not support
 	UnsupportedPicture,
+	/// Attempted to write a picture larger than [`WriteOptions::max_picture_size`](crate::WriteOptions::max_picture_size)
+	///
+	/// This contains the size the picture would have occupied in the tag, see [`Picture::encoded_size_for`](crate::Picture::encoded_size_for).
+	PictureTooLarge(u64),
 
 	// Tag related errors
 	/// Arises when writing a tag to a file type that doesn't support it
@@ -51,11 +63,37 @@ pub enum ErrorKind {
 	TextDecode(&'static str),
 	/// Errors that arise while reading/writing ID3v2 tags
 	Id3v2(Id3v2Error),
+	/// Arises when writing an ID3v2 tag that violates its [`TagRestrictions`](crate::id3::v2::TagRestrictions)
+	///
+	/// This contains every violation that was found.
+	///
+	/// See [`WriteOptions::fit_id3v2_restrictions`](crate::WriteOptions::fit_id3v2_restrictions)
+	Id3v2Restrictions(Vec<RestrictionViolation>),
+	/// Arises when writing an ID3v1 genre that isn't in [`GENRES`](crate::id3::v1::GENRES)
+	///
+	/// This contains the genre that would have been lost.
+	///
+	/// See [`WriteOptions::error_on_lossy_id3v1_genre`](crate::WriteOptions::error_on_lossy_id3v1_genre)
+	LossyId3v1Genre(String),
+	/// Arises when a [`Timestamp`](crate::Timestamp) is malformed, see [`Timestamp::parse`](crate::Timestamp::parse)
+	BadTimestamp(&'static str),
+	/// Arises when an [`Isrc`](crate::Isrc) is malformed, see [`Isrc::parse`](crate::Isrc::parse)
+	BadIsrc(&'static str),
 
 	/// Arises when an atom contains invalid data
 	BadAtom(&'static str),
 	/// Arises when attempting to use [`Atom::merge`](crate::mp4::Atom::merge) with mismatching identifiers
 	AtomMismatch,
+	/// Arises when writing a tag to a fragmented MP4 file would require resizing its `moov` atom
+	///
+	/// Resizing the `moov` atom moves all of the fragments that follow it, which would invalidate any
+	/// absolute offsets into them. Tags can still be written if they fit in the existing padding.
+	FragmentedFile,
+
+	/// Arises when a file was modified since it was read, and saving would overwrite those modifications
+	///
+	/// See [`FileFingerprint`](crate::FileFingerprint) and [`WriteOptions::ignore_stale_file`](crate::WriteOptions::ignore_stale_file)
+	StaleFile,
 
 	// Conversions for external errors
 	/// Errors that arise while parsing OGG pages
@@ -70,6 +108,83 @@ pub enum ErrorKind {
 	Alloc(TryReserveError),
 }
 
+impl ErrorKind {
+	/// A stable numeric code for the kind of error
+	///
+	/// This is intended for consumers that can't match on the error, such as across an FFI boundary.
+	/// The code for a kind will never change, and new kinds will be given new codes.
+	///
+	/// | Code | Kind                              |
+	/// |------|-----------------------------------|
+	/// | 1    | [`ErrorKind::UnknownFormat`]      |
+	/// | 2    | [`ErrorKind::TooMuchData`]        |
+	/// | 3    | [`ErrorKind::SizeMismatch`]       |
+	/// | 4    | [`ErrorKind::FileDecoding`]       |
+	/// | 5    | [`ErrorKind::FileEncoding`]       |
+	/// | 6    | [`ErrorKind::NeedRange`]          |
+	/// | 7    | [`ErrorKind::NotAPicture`]        |
+	/// | 8    | [`ErrorKind::UnsupportedPicture`] |
+	/// | 9    | [`ErrorKind::PictureTooLarge`]    |
+	/// | 10   | [`ErrorKind::UnsupportedTag`]     |
+	/// | 11   | [`ErrorKind::FakeTag`]            |
+	/// | 12   | [`ErrorKind::TextDecode`]         |
+	/// | 13   | [`ErrorKind::Id3v2`]              |
+	/// | 14   | [`ErrorKind::Id3v2Restrictions`]  |
+	/// | 15   | [`ErrorKind::LossyId3v1Genre`]    |
+	/// | 16   | [`ErrorKind::BadTimestamp`]       |
+	/// | 17   | [`ErrorKind::BadIsrc`]            |
+	/// | 18   | [`ErrorKind::BadAtom`]            |
+	/// | 19   | [`ErrorKind::AtomMismatch`]       |
+	/// | 20   | [`ErrorKind::FragmentedFile`]     |
+	/// | 21   | [`ErrorKind::StaleFile`]          |
+	/// | 22   | [`ErrorKind::OggPage`]            |
+	/// | 23   | [`ErrorKind::StringFromUtf8`]     |
+	/// | 24   | [`ErrorKind::StrFromUtf8`]        |
+	/// | 25   | [`ErrorKind::Io`]                 |
+	/// | 26   | [`ErrorKind::Alloc`]              |
+	///
+	/// MP4 errors don't have a kind of their own, they are [`ErrorKind::BadAtom`], [`ErrorKind::AtomMismatch`],
+	/// and [`ErrorKind::FragmentedFile`]. For ID3v2 errors, see [`Id3v2ErrorKind::code`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::error::ErrorKind;
+	///
+	/// assert_eq!(ErrorKind::UnknownFormat.code(), 1);
+	/// ```
+	pub fn code(&self) -> u32 {
+		match self {
+			Self::UnknownFormat => 1,
+			Self::TooMuchData => 2,
+			Self::SizeMismatch => 3,
+			Self::FileDecoding(_) => 4,
+			Self::FileEncoding(_) => 5,
+			Self::NeedRange(_) => 6,
+			Self::NotAPicture => 7,
+			Self::UnsupportedPicture => 8,
+			Self::PictureTooLarge(_) => 9,
+			Self::UnsupportedTag => 10,
+			Self::FakeTag => 11,
+			Self::TextDecode(_) => 12,
+			Self::Id3v2(_) => 13,
+			Self::Id3v2Restrictions(_) => 14,
+			Self::LossyId3v1Genre(_) => 15,
+			Self::BadTimestamp(_) => 16,
+			Self::BadIsrc(_) => 17,
+			Self::BadAtom(_) => 18,
+			Self::AtomMismatch => 19,
+			Self::FragmentedFile => 20,
+			Self::StaleFile => 21,
+			Self::OggPage(_) => 22,
+			Self::StringFromUtf8(_) => 23,
+			Self::StrFromUtf8(_) => 24,
+			Self::Io(_) => 25,
+			Self::Alloc(_) => 26,
+		}
+	}
+}
+
 /// The types of errors that can occur while interacting with ID3v2 tags
 #[derive(Debug)]
 #[non_exhaustive]
@@ -83,6 +198,9 @@ pub enum Id3v2ErrorKind {
 	/// As such, it is recommended to ignore the tag entirely.
 	V2Compression,
 	/// Arises when an extended header has an invalid size (must be >= 6 bytes and less than the total tag size)
+	///
+	/// For ID3v2.3, this also arises when the size doesn't match the flags (6 bytes, or 10 with a CRC),
+	/// or the padding size exceeds the tag size.
 	BadExtendedHeaderSize,
 
 	// Frame
@@ -96,6 +214,12 @@ pub enum Id3v2ErrorKind {
 	BadFrameLength,
 	/// Arises when a frame with no content is parsed with [ParsingMode::Strict](crate::probe::ParsingMode::Strict)
 	EmptyFrame(FrameId<'static>),
+	/// Arises when a frame's content exceeds the allocation limit
+	///
+	/// This contains the ID of the frame and its size, as stated in the frame header.
+	///
+	/// See [`ParseOptions::allocation_limit`](crate::ParseOptions::allocation_limit).
+	FrameExceedsAllocationLimit(FrameId<'static>, u32),
 	/// Arises when reading/writing a compressed or encrypted frame with no data length indicator
 	MissingDataLengthIndicator,
 	/// Arises when a frame or tag has its unsynchronisation flag set, but the content is not actually synchsafe
@@ -110,6 +234,8 @@ pub enum Id3v2ErrorKind {
 	BadSyncText,
 	/// Arises when decoding a [`UniqueFileIdentifierFrame`](crate::id3::v2::UniqueFileIdentifierFrame) with no owner
 	MissingUfidOwner,
+	/// Arises when decoding a [`KeyValueFrame`](crate::id3::v2::KeyValueFrame) with a key that has no value
+	UnpairedKeyValue,
 	/// Arises when decoding a [`RelativeVolumeAdjustmentFrame`](crate::id3::v2::RelativeVolumeAdjustmentFrame) with an invalid channel type
 	BadRva2ChannelType,
 	/// Arises when decoding a [`TimestampFormat`](crate::id3::v2::TimestampFormat) with an invalid type
@@ -128,8 +254,92 @@ pub enum Id3v2ErrorKind {
 	InvalidEncryptionMethodSymbol(u8),
 	/// Arises when attempting to write an invalid Frame (Bad `FrameId`/`FrameValue` pairing)
 	BadFrame(String, &'static str),
+	/// Arises when attempting to write a frame larger than the maximum frame size (~256 MiB)
+	///
+	/// This contains the ID of the frame and the size of its content.
+	///
+	/// Oversized `USLT` and `TXXX` frames can be split instead, see [`WriteOptions::split_oversized_id3v2_frames`](crate::WriteOptions::split_oversized_id3v2_frames).
+	FrameTooLarge(FrameId<'static>, usize),
 	/// Arises when attempting to write a [`CommentFrame`](crate::id3::v2::CommentFrame) or [`UnsynchronizedTextFrame`](crate::id3::v2::UnsynchronizedTextFrame) with an invalid language
+	///
+	/// Only ASCII letters (`'a'..='z'` and `'A'..='Z'`) are allowed. Languages read from a file aren't
+	/// checked, so one such as `"\0\0\0"` has to be replaced before the frame can be written.
 	InvalidLanguage([u8; 3]),
+	/// Arises when inserting a frame that duplicates an existing one, with [`DuplicateBehavior::Error`](crate::id3::v2::DuplicateBehavior::Error)
+	DuplicateFrame(FrameId<'static>),
+	/// Arises when a chapter frame (`CHAP` or `CTOC`) is embedded in another chapter frame, with [`ParsingMode::Strict`](crate::ParsingMode::Strict)
+	NestedChapterFrame(FrameId<'static>),
+	/// Arises when writing a `TLAN` frame containing a language code that isn't ISO-639-2, with [`WriteOptions::parsing_mode`](crate::WriteOptions::parsing_mode)
+	/// set to [`ParsingMode::Strict`](crate::ParsingMode::Strict)
+	InvalidLanguageCode(String),
+}
+
+impl Id3v2ErrorKind {
+	/// A stable numeric code for the kind of error
+	///
+	/// Like [`ErrorKind::code`], the code for a kind will never change. Both compression kinds have a
+	/// code, though only one exists depending on the `id3v2_compression_support` feature.
+	///
+	/// | Code | Kind                                                |
+	/// |------|-----------------------------------------------------|
+	/// | 1    | [`Id3v2ErrorKind::BadId3v2Version`]                 |
+	/// | 2    | [`Id3v2ErrorKind::V2Compression`]                   |
+	/// | 3    | [`Id3v2ErrorKind::BadExtendedHeaderSize`]           |
+	/// | 4    | [`Id3v2ErrorKind::BadFrameId`]                      |
+	/// | 5    | [`Id3v2ErrorKind::UnsupportedFrameId`]              |
+	/// | 6    | [`Id3v2ErrorKind::BadFrameLength`]                  |
+	/// | 7    | [`Id3v2ErrorKind::EmptyFrame`]                      |
+	/// | 8    | [`Id3v2ErrorKind::FrameExceedsAllocationLimit`]     |
+	/// | 9    | [`Id3v2ErrorKind::MissingDataLengthIndicator`]      |
+	/// | 10   | [`Id3v2ErrorKind::InvalidUnsynchronisation`]        |
+	/// | 11   | [`Id3v2ErrorKind::V2InvalidTextEncoding`]           |
+	/// | 12   | [`Id3v2ErrorKind::BadPictureFormat`]                |
+	/// | 13   | [`Id3v2ErrorKind::BadSyncText`]                     |
+	/// | 14   | [`Id3v2ErrorKind::MissingUfidOwner`]                |
+	/// | 15   | [`Id3v2ErrorKind::UnpairedKeyValue`]                |
+	/// | 16   | [`Id3v2ErrorKind::BadRva2ChannelType`]              |
+	/// | 17   | [`Id3v2ErrorKind::BadTimestampFormat`]              |
+	/// | 18   | `Id3v2ErrorKind::Decompression`                     |
+	/// | 19   | `Id3v2ErrorKind::CompressedFrameEncountered`        |
+	/// | 20   | [`Id3v2ErrorKind::InvalidEncryptionMethodSymbol`]   |
+	/// | 21   | [`Id3v2ErrorKind::BadFrame`]                        |
+	/// | 22   | [`Id3v2ErrorKind::FrameTooLarge`]                   |
+	/// | 23   | [`Id3v2ErrorKind::InvalidLanguage`]                 |
+	/// | 24   | [`Id3v2ErrorKind::DuplicateFrame`]                  |
+	/// | 25   | [`Id3v2ErrorKind::NestedChapterFrame`]              |
+	/// | 26   | [`Id3v2ErrorKind::InvalidLanguageCode`]             |
+	pub fn code(&self) -> u32 {
+		match self {
+			Self::BadId3v2Version(..) => 1,
+			Self::V2Compression => 2,
+			Self::BadExtendedHeaderSize => 3,
+			Self::BadFrameId(_) => 4,
+			Self::UnsupportedFrameId(_) => 5,
+			Self::BadFrameLength => 6,
+			Self::EmptyFrame(_) => 7,
+			Self::FrameExceedsAllocationLimit(..) => 8,
+			Self::MissingDataLengthIndicator => 9,
+			Self::InvalidUnsynchronisation => 10,
+			Self::V2InvalidTextEncoding => 11,
+			Self::BadPictureFormat(_) => 12,
+			Self::BadSyncText => 13,
+			Self::MissingUfidOwner => 14,
+			Self::UnpairedKeyValue => 15,
+			Self::BadRva2ChannelType => 16,
+			Self::BadTimestampFormat => 17,
+			#[cfg(feature = "id3v2_compression_support")]
+			Self::Decompression(_) => 18,
+			#[cfg(not(feature = "id3v2_compression_support"))]
+			Self::CompressedFrameEncountered => 19,
+			Self::InvalidEncryptionMethodSymbol(_) => 20,
+			Self::BadFrame(..) => 21,
+			Self::FrameTooLarge(..) => 22,
+			Self::InvalidLanguage(_) => 23,
+			Self::DuplicateFrame(_) => 24,
+			Self::NestedChapterFrame(_) => 25,
+			Self::InvalidLanguageCode(_) => 26,
+		}
+	}
 }
 
 impl Display for Id3v2ErrorKind {
@@ -156,6 +366,10 @@ impl Display for Id3v2ErrorKind {
 				"Frame isn't long enough to extract the necessary information"
 			),
 			Self::EmptyFrame(id) => write!(f, "Frame `{id}` is empty"),
+			Self::FrameExceedsAllocationLimit(id, size) => write!(
+				f,
+				"Frame `{id}` ({size} bytes) exceeds the allocation limit"
+			),
 			Self::MissingDataLengthIndicator => write!(
 				f,
 				"Encountered an encrypted frame without a data length indicator"
@@ -169,6 +383,9 @@ impl Display for Id3v2ErrorKind {
 			},
 			Self::BadSyncText => write!(f, "Encountered invalid data in SYLT frame"),
 			Self::MissingUfidOwner => write!(f, "Missing owner in UFID frame"),
+			Self::UnpairedKeyValue => {
+				write!(f, "Encountered a key with no value in a key-value frame")
+			},
 			Self::BadRva2ChannelType => write!(f, "Encountered invalid channel type in RVA2 frame"),
 			Self::BadTimestampFormat => write!(
 				f,
@@ -193,10 +410,25 @@ impl Display for Id3v2ErrorKind {
 				f,
 				"Attempted to write an invalid frame. ID: \"{frame_id}\", Value: \"{frame_value}\"",
 			),
+			Self::FrameTooLarge(id, size) => {
+				write!(f, "Frame `{id}` is too large to be written ({size} bytes)")
+			},
 			Self::InvalidLanguage(lang) => write!(
 				f,
 				"Invalid frame language found: {lang:?} (expected 3 ascii characters)"
 			),
+			Self::DuplicateFrame(id) => {
+				write!(f, "Attempted to insert a duplicate `{id}` frame")
+			},
+			Self::NestedChapterFrame(id) => {
+				write!(f, "Found a `{id}` frame embedded in another chapter frame")
+			},
+			Self::InvalidLanguageCode(code) => {
+				write!(
+					f,
+					"`TLAN` frame contains an invalid ISO-639-2 language code: {code:?}"
+				)
+			},
 		}
 	}
 }
@@ -427,6 +659,56 @@ impl LoftyError {
 	pub fn kind(&self) -> &ErrorKind {
 		&self.kind
 	}
+
+	/// A stable numeric code for the error
+	///
+	/// The upper 16 bits are the [`ErrorKind::code`]. For [`ErrorKind::Id3v2`] errors, the lower 16 bits
+	/// are the [`Id3v2ErrorKind::code`], otherwise they are `0`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::error::{ErrorKind, Id3v2Error, Id3v2ErrorKind};
+	/// use lofty::LoftyError;
+	///
+	/// let unknown_format = LoftyError::new(ErrorKind::UnknownFormat);
+	/// assert_eq!(unknown_format.code(), 1 << 16);
+	///
+	/// let bad_frame_length: LoftyError = Id3v2Error::new(Id3v2ErrorKind::BadFrameLength).into();
+	/// assert_eq!(bad_frame_length.code() >> 16, 13);
+	/// assert_eq!(bad_frame_length.code() & 0xFFFF, 6);
+	/// ```
+	pub fn code(&self) -> u32 {
+		let sub_code = match self.kind {
+			ErrorKind::Id3v2(ref id3v2_err) => id3v2_err.kind().code(),
+			_ => 0,
+		};
+
+		(self.kind.code() << 16) | sub_code
+	}
+
+	/// Returns the [`std::io::ErrorKind`] if the error was caused by an I/O error
+	///
+	/// This includes I/O errors that occurred while reading OGG pages.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::error::ErrorKind;
+	/// use lofty::LoftyError;
+	///
+	/// let eof: LoftyError = std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into();
+	/// assert_eq!(eof.io_error_kind(), Some(std::io::ErrorKind::UnexpectedEof));
+	///
+	/// let unknown_format = LoftyError::new(ErrorKind::UnknownFormat);
+	/// assert_eq!(unknown_format.io_error_kind(), None);
+	/// ```
+	pub fn io_error_kind(&self) -> Option<std::io::ErrorKind> {
+		match self.kind {
+			ErrorKind::Io(ref err) | ErrorKind::OggPage(PageError::Io(ref err)) => Some(err.kind()),
+			_ => None,
+		}
+	}
 }
 
 impl std::error::Error for LoftyError {}
@@ -518,6 +800,10 @@ impl Display for LoftyError {
 			ErrorKind::UnsupportedPicture => {
 				write!(f, "Picture: attempted to write an unsupported picture")
 			},
+			ErrorKind::PictureTooLarge(size) => write!(
+				f,
+				"Picture: attempted to write a picture of {size} bytes, exceeding the maximum size"
+			),
 			ErrorKind::UnsupportedTag => write!(
 				f,
 				"Attempted to write a tag to a format that does not support it"
@@ -525,11 +811,37 @@ impl Display for LoftyError {
 			ErrorKind::FakeTag => write!(f, "Reading: Expected a tag, found invalid data"),
 			ErrorKind::TextDecode(message) => write!(f, "Text decoding: {message}"),
 			ErrorKind::Id3v2(ref id3v2_err) => write!(f, "{id3v2_err}"),
+			ErrorKind::Id3v2Restrictions(ref violations) => {
+				write!(f, "ID3v2: The tag violates its restrictions: ")?;
+				for (idx, violation) in violations.iter().enumerate() {
+					if idx > 0 {
+						write!(f, "; ")?;
+					}
+
+					write!(f, "{violation}")?;
+				}
+
+				Ok(())
+			},
 			ErrorKind::BadAtom(message) => write!(f, "MP4 Atom: {message}"),
 			ErrorKind::AtomMismatch => write!(
 				f,
 				"MP4 Atom: Attempted to use `Atom::merge()` with mismatching identifiers"
 			),
+			ErrorKind::LossyId3v1Genre(ref genre) => write!(
+				f,
+				"ID3v1: The genre \"{genre}\" has no ID3v1 equivalent, refusing to discard it"
+			),
+			ErrorKind::BadTimestamp(message) => write!(f, "Timestamp: {message}"),
+			ErrorKind::BadIsrc(message) => write!(f, "ISRC: {message}"),
+			ErrorKind::FragmentedFile => write!(
+				f,
+				"MP4: Unable to write the tag without resizing the \"moov\" atom of a fragmented file"
+			),
+			ErrorKind::StaleFile => write!(
+				f,
+				"The file has been modified since it was read, refusing to overwrite it"
+			),
 
 			// Files
 			ErrorKind::TooMuchData => write!(
@@ -542,6 +854,149 @@ impl Display for LoftyError {
 			),
 			ErrorKind::FileDecoding(ref file_decode_err) => write!(f, "{file_decode_err}"),
 			ErrorKind::FileEncoding(ref file_encode_err) => write!(f, "{file_encode_err}"),
+			ErrorKind::NeedRange(ref range) => write!(
+				f,
+				"Data outside of the available ranges is required: {}..{}",
+				range.start, range.end
+			),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{ErrorKind, FileDecodingError, FileEncodingError, Id3v2Error, Id3v2ErrorKind};
+	use crate::id3::v2::{FrameId, RestrictionViolation};
+	use crate::tag::item::ItemKey;
+	use crate::LoftyError;
+
+	use std::borrow::Cow;
+
+	use ogg_pager::PageError;
+
+	// These codes are relied on across FFI boundaries, and must never change
+	#[test]
+	fn stable_codes() {
+		let kinds = [
+			(ErrorKind::UnknownFormat, 1),
+			(ErrorKind::TooMuchData, 2),
+			(ErrorKind::SizeMismatch, 3),
+			(
+				ErrorKind::FileDecoding(FileDecodingError::from_description("")),
+				4,
+			),
+			(
+				ErrorKind::FileEncoding(FileEncodingError::from_description("")),
+				5,
+			),
+			(ErrorKind::NeedRange(0..1), 6),
+			(ErrorKind::NotAPicture, 7),
+			(ErrorKind::UnsupportedPicture, 8),
+			(ErrorKind::PictureTooLarge(0), 9),
+			(ErrorKind::UnsupportedTag, 10),
+			(ErrorKind::FakeTag, 11),
+			(ErrorKind::TextDecode(""), 12),
+			(
+				ErrorKind::Id3v2(Id3v2Error::new(Id3v2ErrorKind::BadFrameLength)),
+				13,
+			),
+			(
+				ErrorKind::Id3v2Restrictions(vec![RestrictionViolation::FrameCount {
+					count: 0,
+					max_frames: 0,
+				}]),
+				14,
+			),
+			(ErrorKind::LossyId3v1Genre(String::new()), 15),
+			(ErrorKind::BadTimestamp(""), 16),
+			(ErrorKind::BadIsrc(""), 17),
+			(ErrorKind::BadAtom(""), 18),
+			(ErrorKind::AtomMismatch, 19),
+			(ErrorKind::FragmentedFile, 20),
+			(ErrorKind::StaleFile, 21),
+			(ErrorKind::OggPage(PageError::MissingMagic), 22),
+			(
+				ErrorKind::StringFromUtf8(String::from_utf8(vec![0xFF]).unwrap_err()),
+				23,
+			),
+			(
+				ErrorKind::StrFromUtf8(std::str::from_utf8(&vec![0xFF]).unwrap_err()),
+				24,
+			),
+			(ErrorKind::Io(std::io::ErrorKind::UnexpectedEof.into()), 25),
+			(
+				ErrorKind::Alloc(Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err()),
+				26,
+			),
+		];
+
+		for (kind, code) in kinds {
+			assert_eq!(kind.code(), code, "{kind:?}");
+		}
+
+		let frame_id = FrameId::Valid(Cow::Borrowed("TIT2"));
+		let id3v2_kinds = [
+			(Id3v2ErrorKind::BadId3v2Version(5, 0), 1),
+			(Id3v2ErrorKind::V2Compression, 2),
+			(Id3v2ErrorKind::BadExtendedHeaderSize, 3),
+			(Id3v2ErrorKind::BadFrameId(Vec::new()), 4),
+			(Id3v2ErrorKind::UnsupportedFrameId(ItemKey::Work), 5),
+			(Id3v2ErrorKind::BadFrameLength, 6),
+			(Id3v2ErrorKind::EmptyFrame(frame_id.clone()), 7),
+			(
+				Id3v2ErrorKind::FrameExceedsAllocationLimit(frame_id.clone(), 0),
+				8,
+			),
+			(Id3v2ErrorKind::MissingDataLengthIndicator, 9),
+			(Id3v2ErrorKind::InvalidUnsynchronisation, 10),
+			(Id3v2ErrorKind::V2InvalidTextEncoding, 11),
+			(Id3v2ErrorKind::BadPictureFormat(String::new()), 12),
+			(Id3v2ErrorKind::BadSyncText, 13),
+			(Id3v2ErrorKind::MissingUfidOwner, 14),
+			(Id3v2ErrorKind::UnpairedKeyValue, 15),
+			(Id3v2ErrorKind::BadRva2ChannelType, 16),
+			(Id3v2ErrorKind::BadTimestampFormat, 17),
+			#[cfg(feature = "id3v2_compression_support")]
+			(
+				Id3v2ErrorKind::Decompression(
+					flate2::Decompress::new(true)
+						.decompress(&[0xFF; 4], &mut [0; 4], flate2::FlushDecompress::Finish)
+						.unwrap_err(),
+				),
+				18,
+			),
+			#[cfg(not(feature = "id3v2_compression_support"))]
+			(Id3v2ErrorKind::CompressedFrameEncountered, 19),
+			(Id3v2ErrorKind::InvalidEncryptionMethodSymbol(0xFF), 20),
+			(Id3v2ErrorKind::BadFrame(String::new(), ""), 21),
+			(Id3v2ErrorKind::FrameTooLarge(frame_id.clone(), 0), 22),
+			(Id3v2ErrorKind::InvalidLanguage(*b"\0\0\0"), 23),
+			(Id3v2ErrorKind::DuplicateFrame(frame_id.clone()), 24),
+			(Id3v2ErrorKind::NestedChapterFrame(frame_id), 25),
+			(Id3v2ErrorKind::InvalidLanguageCode(String::new()), 26),
+		];
+
+		for (kind, code) in id3v2_kinds {
+			assert_eq!(kind.code(), code, "{kind:?}");
+
+			let err = LoftyError::from(Id3v2Error::new(kind));
+			assert_eq!(err.code(), (13 << 16) | code);
 		}
+
+		assert_eq!(LoftyError::new(ErrorKind::StaleFile).code(), 21 << 16);
+	}
+
+	#[test]
+	fn io_error_kind() {
+		let eof = || std::io::Error::from(std::io::ErrorKind::UnexpectedEof);
+
+		let io = LoftyError::from(eof());
+		assert_eq!(io.io_error_kind(), Some(std::io::ErrorKind::UnexpectedEof));
+
+		let ogg = LoftyError::from(PageError::Io(eof()));
+		assert_eq!(ogg.io_error_kind(), Some(std::io::ErrorKind::UnexpectedEof));
+
+		let not_io = LoftyError::from(PageError::MissingMagic);
+		assert_eq!(not_io.io_error_kind(), None);
 	}
 }
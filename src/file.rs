@@ -1,14 +1,25 @@
 use crate::error::Result;
+#[cfg(feature = "fs")]
+use crate::fingerprint::FingerprintCell;
+use crate::id3::v2::tag::{Id3v2TagRef, SplitTagRemainder};
+use crate::id3::v2::{Frame, Id3v2Tag, Id3v2TagFlags};
 use crate::probe::ParseOptions;
 use crate::properties::FileProperties;
 use crate::resolve::CUSTOM_RESOLVERS;
+use crate::save_plan::SavePlan;
+use crate::tag::conflict::{self, Conflict, ReconcileAction, ReconcilePolicy};
+use crate::tag::item::{ItemKey, ItemValue, TagItem};
 use crate::tag::{Tag, TagType};
-use crate::traits::TagExt;
+use crate::traits::{MergeTag, TagExt};
+use crate::util::io::{FileLike, OffsetFile, WriteOverlay};
+use crate::write_options::WriteOptions;
 
+use std::borrow::Cow;
 use std::convert::TryInto;
 use std::ffi::OsStr;
+#[cfg(feature = "fs")]
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 
 /// Provides various methods for interaction with a file
@@ -28,6 +39,7 @@ pub trait AudioFile: Into<TaggedFile> {
 		R: Read + Seek,
 		Self: Sized;
 
+	#[cfg(feature = "fs")]
 	/// Attempts to write all tags to a path
 	///
 	/// # Errors
@@ -39,7 +51,7 @@ pub trait AudioFile: Into<TaggedFile> {
 	/// # Examples
 	///
 	/// ```rust,no_run
-	/// use lofty::{AudioFile, TaggedFileExt};
+	/// use lofty::{AudioFile, TaggedFileExt, WriteOptions};
 	///
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
@@ -47,23 +59,28 @@ pub trait AudioFile: Into<TaggedFile> {
 	///
 	/// // Edit the tags
 	///
-	/// tagged_file.save_to_path(path)?;
+	/// tagged_file.save_to_path(path, WriteOptions::default())?;
 	/// # Ok(()) }
 	/// ```
-	fn save_to_path(&self, path: impl AsRef<Path>) -> Result<()> {
-		self.save_to(&mut OpenOptions::new().read(true).write(true).open(path)?)
+	fn save_to_path(&self, path: impl AsRef<Path>, write_options: WriteOptions) -> Result<()> {
+		self.save_to(
+			&mut OpenOptions::new().read(true).write(true).open(path)?,
+			write_options,
+		)
 	}
 
+	#[cfg(feature = "fs")]
 	/// Attempts to write all tags to a file
 	///
 	/// # Errors
 	///
-	/// See [`Tag::save_to`], however this is applicable to every tag in the file.
+	/// * The file was modified since it was read, see [`WriteOptions::ignore_stale_file`]
+	/// * See [`Tag::save_to`], however this is applicable to every tag in the file.
 	///
 	/// # Examples
 	///
 	/// ```rust,no_run
-	/// use lofty::{AudioFile, TaggedFileExt};
+	/// use lofty::{AudioFile, TaggedFileExt, WriteOptions};
 	/// use std::fs::OpenOptions;
 	///
 	/// # fn main() -> lofty::Result<()> {
@@ -73,10 +90,10 @@ pub trait AudioFile: Into<TaggedFile> {
 	/// // Edit the tags
 	///
 	/// let mut file = OpenOptions::new().read(true).write(true).open(path)?;
-	/// tagged_file.save_to(&mut file)?;
+	/// tagged_file.save_to(&mut file, WriteOptions::default())?;
 	/// # Ok(()) }
 	/// ```
-	fn save_to(&self, file: &mut File) -> Result<()>;
+	fn save_to(&self, file: &mut File, write_options: WriteOptions) -> Result<()>;
 
 	/// Returns a reference to the file's properties
 	fn properties(&self) -> &Self::Properties;
@@ -95,12 +112,15 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{FileType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// let mut tagged_file = lofty::read_from_path(path_to_mp3)?;
 	///
 	/// assert_eq!(tagged_file.file_type(), FileType::Mpeg);
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn file_type(&self) -> FileType;
 
@@ -111,6 +131,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{FileType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // An MP3 file with 3 tags
@@ -120,6 +141,8 @@ pub trait TaggedFileExt {
 	///
 	/// assert_eq!(tags.len(), 3);
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn tags(&self) -> &[Tag];
 
@@ -132,12 +155,15 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// let mut tagged_file = lofty::read_from_path(path_to_mp3)?;
 	///
 	/// assert_eq!(tagged_file.primary_tag_type(), TagType::Id3v2);
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn primary_tag_type(&self) -> TagType {
 		self.file_type().primary_tag_type()
@@ -150,12 +176,15 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// let mut tagged_file = lofty::read_from_path(path_to_mp3)?;
 	///
 	/// assert!(tagged_file.supports_tag_type(TagType::Id3v2));
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn supports_tag_type(&self, tag_type: TagType) -> bool {
 		self.file_type().supports_tag_type(tag_type)
@@ -168,6 +197,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file with an ID3v2 tag
@@ -179,6 +209,8 @@ pub trait TaggedFileExt {
 	/// assert!(tag.is_some());
 	/// assert_eq!(tag.unwrap().tag_type(), TagType::Id3v2);
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn tag(&self, tag_type: TagType) -> Option<&Tag>;
 
@@ -189,6 +221,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file with an ID3v2 tag
@@ -202,6 +235,8 @@ pub trait TaggedFileExt {
 	///
 	/// // Alter the tag...
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn tag_mut(&mut self, tag_type: TagType) -> Option<&mut Tag>;
 
@@ -214,6 +249,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file with an ID3v2 tag
@@ -225,6 +261,8 @@ pub trait TaggedFileExt {
 	/// assert!(tag.is_some());
 	/// assert_eq!(tag.unwrap().tag_type(), TagType::Id3v2);
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn primary_tag(&self) -> Option<&Tag> {
 		self.tag(self.primary_tag_type())
@@ -239,6 +277,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file with an ID3v2 tag
@@ -252,6 +291,8 @@ pub trait TaggedFileExt {
 	///
 	/// // Alter the tag...
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn primary_tag_mut(&mut self) -> Option<&mut Tag> {
 		self.tag_mut(self.primary_tag_type())
@@ -267,6 +308,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::TaggedFileExt;
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
 	/// // A file we know has tags
@@ -276,6 +318,8 @@ pub trait TaggedFileExt {
 	/// let tag = tagged_file.first_tag();
 	/// assert!(tag.is_some());
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn first_tag(&self) -> Option<&Tag> {
 		self.tags().first()
@@ -291,6 +335,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::TaggedFileExt;
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
 	/// // A file we know has tags
@@ -302,6 +347,8 @@ pub trait TaggedFileExt {
 	///
 	/// // Alter the tag...
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn first_tag_mut(&mut self) -> Option<&mut Tag>;
 
@@ -317,6 +364,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{AudioFile, Tag, TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file without an ID3v2 tag
@@ -331,6 +379,8 @@ pub trait TaggedFileExt {
 	///
 	/// assert!(tagged_file.contains_tag_type(TagType::Id3v2));
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn insert_tag(&mut self, tag: Tag) -> Option<Tag>;
 
@@ -341,6 +391,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::{AudioFile, TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file containing an ID3v2 tag
@@ -353,6 +404,8 @@ pub trait TaggedFileExt {
 	///
 	/// assert!(!tagged_file.contains_tag_type(TagType::Id3v2));
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn remove(&mut self, tag_type: TagType) -> Option<Tag>;
 
@@ -363,6 +416,7 @@ pub trait TaggedFileExt {
 	/// ```rust
 	/// use lofty::TaggedFileExt;
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
 	/// let mut tagged_file = lofty::read_from_path(path)?;
@@ -371,8 +425,152 @@ pub trait TaggedFileExt {
 	///
 	/// assert!(tagged_file.tags().is_empty());
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	fn clear(&mut self);
+
+	/// Find every [`ItemKey`](crate::ItemKey) that has different values between the file's tags
+	///
+	/// Only the tags containing a key are compared, a key missing from a tag is not a conflict.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, TaggedFileExt};
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
+	/// // An MP3 file with ID3v2, ID3v1, and APE tags, each with a different artist
+	/// let tagged_file = lofty::read_from_path(path_to_mp3)?;
+	///
+	/// let conflicts = tagged_file.conflicts();
+	/// assert_eq!(conflicts.len(), 1);
+	/// assert_eq!(conflicts[0].key(), &ItemKey::TrackArtist);
+	///
+	/// for (tag_type, values) in conflicts[0].values() {
+	/// 	println!("{tag_type:?}: {values:?}");
+	/// }
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	fn conflicts(&self) -> Vec<Conflict> {
+		conflict::find_conflicts(self.tags())
+	}
+
+	/// Resolve every [`Conflict`] between the file's tags
+	///
+	/// The `policy` chooses the winning tag of each conflict, and the `action` decides what happens
+	/// to the tags that disagree with it. Nothing is written until the file is saved, so all tags are updated in a single save.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{Accessor, ReconcileAction, ReconcilePolicy, TagType, TaggedFileExt};
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
+	/// // An MP3 file with ID3v2, ID3v1, and APE tags, each with a different artist
+	/// let mut tagged_file = lofty::read_from_path(path_to_mp3)?;
+	///
+	/// // The ID3v2 tag is the primary tag of MP3 files
+	/// tagged_file.reconcile(ReconcilePolicy::PreferPrimary, ReconcileAction::Overwrite);
+	/// assert!(tagged_file.conflicts().is_empty());
+	///
+	/// let ape = tagged_file.tag(TagType::Ape).unwrap();
+	/// assert_eq!(ape.artist().as_deref(), Some("Foo artist"));
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	fn reconcile(&mut self, mut policy: ReconcilePolicy<'_>, action: ReconcileAction) {
+		let primary_tag_type = self.primary_tag_type();
+
+		for conflict in self.conflicts() {
+			let Some(winner) = conflict::winner(&conflict, primary_tag_type, &mut policy) else {
+				continue;
+			};
+
+			let winning_items = match self.tag(winner) {
+				Some(tag) => tag.get_items(conflict.key()).cloned().collect::<Vec<_>>(),
+				None => continue,
+			};
+			let winning_values = conflict.values_of(winner);
+
+			// Tags that already agree with the winner are left alone
+			for (tag_type, values) in conflict.values() {
+				if Some(values.as_slice()) == winning_values {
+					continue;
+				}
+
+				if let Some(tag) = self.tag_mut(*tag_type) {
+					conflict::apply(tag, conflict.key(), &winning_items, action);
+				}
+			}
+		}
+	}
+
+	/// List every encoder-identifying string in the file's tags
+	///
+	/// This includes the values of [`ItemKey::EncoderSoftware`], [`ItemKey::EncoderSettings`], and
+	/// [`ItemKey::EncodedBy`], from the most recent tag format to the oldest (see [`ReconcilePolicy::PreferNewest`]).
+	/// A file that passed through multiple encoders will often have a different string in each tag.
+	///
+	/// For MP3 files, see also [`MpegProperties::lame_header`](crate::mpeg::MpegProperties::lame_header).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, TagType, TaggedFileExt};
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
+	/// let mut tagged_file = lofty::read_from_path(path_to_mp3)?;
+	///
+	/// let id3v2 = tagged_file.tag_mut(TagType::Id3v2).unwrap();
+	/// id3v2.insert_text(ItemKey::EncoderSoftware, String::from("LAME 3.100"));
+	///
+	/// let ape = tagged_file.tag_mut(TagType::Ape).unwrap();
+	/// ape.insert_text(ItemKey::EncodedBy, String::from("FhG"));
+	///
+	/// // APE tags are newer than ID3v2 tags
+	/// assert_eq!(
+	/// 	tagged_file.encoder_chain(),
+	/// 	[(TagType::Ape, "FhG"), (TagType::Id3v2, "LAME 3.100")]
+	/// );
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	fn encoder_chain(&self) -> Vec<(TagType, &str)> {
+		let mut tags = self.tags().iter().collect::<Vec<_>>();
+		tags.sort_by_key(|tag| std::cmp::Reverse(conflict::recency(tag.tag_type())));
+
+		let mut chain = Vec::new();
+		for tag in tags {
+			for item in tag.items() {
+				if !matches!(
+					item.key(),
+					ItemKey::EncoderSoftware | ItemKey::EncoderSettings | ItemKey::EncodedBy
+				) {
+					continue;
+				}
+
+				if let Some(text) = item.value().text() {
+					let entry = (tag.tag_type(), text);
+					if !chain.contains(&entry) {
+						chain.push(entry);
+					}
+				}
+			}
+		}
+
+		chain
+	}
 }
 
 /// A generic representation of a file
@@ -385,6 +583,15 @@ pub struct TaggedFile {
 	pub(crate) properties: FileProperties,
 	/// A collection of the file's tags
 	pub(crate) tags: Vec<Tag>,
+	/// Where the file starts in the reader it was read from, see [`ParseOptions::start_offset`]
+	pub(crate) start_offset: u64,
+	/// The ID3v2 frames that couldn't be represented in the ID3v2 [`Tag`], see [`SplitTag`](crate::SplitTag)
+	///
+	/// These are merged back into the tag when writing, so editing the generic tag doesn't lose them.
+	pub(crate) id3v2_remainder: Option<SplitTagRemainder>,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: FingerprintCell,
 }
 
 impl TaggedFile {
@@ -396,6 +603,10 @@ impl TaggedFile {
 			ty,
 			properties,
 			tags,
+			start_offset: 0,
+			id3v2_remainder: None,
+			#[cfg(feature = "fs")]
+			fingerprint: FingerprintCell::empty(),
 		}
 	}
 
@@ -411,6 +622,7 @@ impl TaggedFile {
 	/// ```rust
 	/// use lofty::{AudioFile, FileType, TagType, TaggedFileExt};
 	///
+	/// # #[cfg(feature = "fs")]
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path_to_mp3 = "tests/files/assets/minimal/full_test.mp3";
 	/// // Read an MP3 file containing an ID3v2 tag
@@ -423,12 +635,201 @@ impl TaggedFile {
 	///
 	/// assert!(!tagged_file.contains_tag_type(TagType::Id3v2));
 	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
 	/// ```
 	pub fn change_file_type(&mut self, file_type: FileType) {
 		self.ty = file_type;
 		self.properties = FileProperties::default();
 		self.tags
 			.retain(|t| self.ty.supports_tag_type(t.tag_type()));
+
+		if !self.ty.supports_tag_type(TagType::Id3v2) {
+			self.id3v2_remainder = None;
+		}
+	}
+
+	/// Shrinks the capacity of every tag as much as possible
+	///
+	/// See [`Tag::shrink_to_fit`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path = "tests/files/assets/minimal/full_test.mp3";
+	/// let mut tagged_file = lofty::read_from_path(path)?;
+	///
+	/// // I'll be holding onto this for a while
+	/// tagged_file.shrink_to_fit();
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	pub fn shrink_to_fit(&mut self) {
+		self.tags.shrink_to_fit();
+
+		for tag in &mut self.tags {
+			tag.shrink_to_fit();
+		}
+	}
+
+	/// An approximation of the number of bytes the file's tags have allocated on the heap
+	///
+	/// See [`Tag::heap_size`]
+	pub fn heap_size(&self) -> usize {
+		self.tags.capacity() * std::mem::size_of::<Tag>()
+			+ self.tags.iter().map(Tag::heap_size).sum::<usize>()
+	}
+
+	/// Plan saving the tags to a file, without modifying it
+	///
+	/// This goes through the same steps as [`AudioFile::save_to`], keeping everything it would write in
+	/// memory rather than writing it to `reader`, and reports what the save would change. See [`SavePlan`].
+	///
+	/// NOTE: Saves that move the audio (see [`SavePlan::requires_rewrite`]) may still hold most of the
+	///       file in memory
+	///
+	/// # Errors
+	///
+	/// * See [`AudioFile::save_to`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{Accessor, TaggedFileExt, WriteOptions};
+	/// use std::fs::File;
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path = "tests/files/assets/minimal/full_test.mp3";
+	/// let mut file = File::open(path)?;
+	/// let mut tagged_file = lofty::read_from(&mut file)?;
+	///
+	/// let tag = tagged_file.primary_tag_mut().unwrap();
+	/// tag.set_title(String::from("Foo title"));
+	///
+	/// let plan = tagged_file.plan_save(&file, WriteOptions::default())?;
+	/// assert!(!plan.is_unchanged());
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	pub fn plan_save<R>(&self, mut reader: R, write_options: WriteOptions) -> Result<SavePlan>
+	where
+		R: Read + Seek,
+	{
+		let position = reader.stream_position()?;
+
+		let mut saved = WriteOverlay::new(&mut reader)?;
+		let plan = self
+			.write_tags(&mut saved, write_options)
+			.and_then(|()| self.plan_tags(write_options))
+			.and_then(|tags| SavePlan::new(&mut saved, tags));
+
+		reader.seek(SeekFrom::Start(position))?;
+		plan
+	}
+
+	// The serialized tags for `SavePlan::tags`
+	fn plan_tags(&self, write_options: WriteOptions) -> Result<Vec<(TagType, Vec<u8>)>> {
+		let mut tags = Vec::with_capacity(self.tags.len());
+		for (tag, write_options) in self.tags_to_write(write_options) {
+			let mut tag_bytes = Vec::new();
+			match self.merge_id3v2_remainder(&tag) {
+				Some(id3v2) => id3v2.dump_to(&mut tag_bytes, write_options)?,
+				None => tag.dump_to(&mut tag_bytes, write_options)?,
+			}
+
+			tags.push((tag.tag_type(), tag_bytes));
+		}
+
+		Ok(tags)
+	}
+
+	// The tags that will be written when saving, along with the options to write them with
+	fn tags_to_write(&self, write_options: WriteOptions) -> Vec<(Cow<'_, Tag>, WriteOptions)> {
+		// A genre that ID3v1 can't hold is kept in the ID3v2 tag, so the ID3v1 copy can be degraded
+		let lossy_genre = self
+			.tag(TagType::Id3v1)
+			.and_then(crate::id3::v1::tag::lossy_genre)
+			.filter(|_| self.contains_tag_type(TagType::Id3v2));
+
+		self.tags
+			.iter()
+			.map(|tag| {
+				let Some(genre) = lossy_genre else {
+					return (Cow::Borrowed(tag), write_options);
+				};
+
+				match tag.tag_type() {
+					TagType::Id3v2 if !tag.get_strings(&ItemKey::Genre).any(|g| g == genre) => {
+						let mut tag = tag.clone();
+						tag.push(TagItem::new(
+							ItemKey::Genre,
+							ItemValue::Text(genre.to_string()),
+						));
+						(Cow::Owned(tag), write_options)
+					},
+					TagType::Id3v1 => (
+						Cow::Borrowed(tag),
+						WriteOptions {
+							error_on_lossy_id3v1_genre: false,
+							..write_options
+						},
+					),
+					_ => (Cow::Borrowed(tag), write_options),
+				}
+			})
+			.collect()
+	}
+
+	fn write_tags<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		// Anything preceding the file is left as-is
+		let file = &mut OffsetFile::new(file, self.start_offset)?;
+
+		for (tag, write_options) in self.tags_to_write(write_options) {
+			// TODO: This is a temporary solution. Ideally we should probe once and use
+			//       the format-specific writing to avoid these rewinds.
+			file.rewind()?;
+			match self.merge_id3v2_remainder(&tag) {
+				Some(id3v2) => Id3v2TagRef {
+					flags: *id3v2.flags(),
+					frames: id3v2.frames.iter().filter_map(Frame::as_opt_ref),
+				}
+				.write_to(file, write_options)?,
+				None => tag.write_to(file, write_options)?,
+			}
+		}
+
+		Ok(())
+	}
+
+	// Reattach the ID3v2 frames that couldn't be represented in `tag`, see `TaggedFile::id3v2_remainder`
+	//
+	// This only applies to formats that can have an ID3v2 tag written, and only when there are frames
+	// (or a comment or lyrics language) to reattach. Otherwise, `tag` is written as-is.
+	fn merge_id3v2_remainder(&self, tag: &Tag) -> Option<Id3v2Tag> {
+		let remainder = self.id3v2_remainder.as_ref()?;
+		if tag.tag_type() != TagType::Id3v2
+			|| (remainder.is_empty()
+				&& remainder.comment_language.is_none()
+				&& remainder.lyrics_language.is_none())
+			|| !Id3v2Tag::SUPPORTED_FORMATS.contains(&self.ty)
+			|| Id3v2Tag::READ_ONLY_FORMATS.contains(&self.ty)
+		{
+			return None;
+		}
+
+		let mut id3v2 = remainder.clone().merge_tag(tag.clone());
+
+		// Written the same way as the generic tag would be
+		id3v2.set_flags(Id3v2TagFlags::default());
+		Some(id3v2)
 	}
 }
 
@@ -467,6 +868,10 @@ impl TaggedFileExt for TaggedFile {
 	}
 
 	fn remove(&mut self, tag_type: TagType) -> Option<Tag> {
+		if tag_type == TagType::Id3v2 {
+			self.id3v2_remainder = None;
+		}
+
 		self.tags
 			.iter()
 			.position(|t| t.tag_type() == tag_type)
@@ -474,6 +879,7 @@ impl TaggedFileExt for TaggedFile {
 	}
 
 	fn clear(&mut self) {
+		self.id3v2_remainder = None;
 		self.tags.clear()
 	}
 }
@@ -486,21 +892,18 @@ impl AudioFile for TaggedFile {
 		R: Read + Seek,
 		Self: Sized,
 	{
+		// The options are needed to guess from the start offset
 		crate::probe::Probe::new(reader)
-			.guess_file_type()?
 			.options(parse_options)
+			.guess_file_type()?
 			.read()
 	}
 
-	fn save_to(&self, file: &mut File) -> Result<()> {
-		for tag in &self.tags {
-			// TODO: This is a temporary solution. Ideally we should probe once and use
-			//       the format-specific writing to avoid these rewinds.
-			file.rewind()?;
-			tag.save_to(file)?;
-		}
-
-		Ok(())
+	#[cfg(feature = "fs")]
+	fn save_to(&self, file: &mut File, write_options: WriteOptions) -> Result<()> {
+		self.fingerprint.verify(file, write_options)?;
+		self.write_tags(file, write_options)?;
+		self.fingerprint.refresh(file)
 	}
 
 	fn properties(&self) -> &Self::Properties {
@@ -516,19 +919,21 @@ impl AudioFile for TaggedFile {
 	}
 }
 
+#[cfg(feature = "fs")]
 impl From<BoundTaggedFile> for TaggedFile {
 	fn from(input: BoundTaggedFile) -> Self {
 		input.inner
 	}
 }
 
+#[cfg(feature = "fs")]
 /// A variant of [`TaggedFile`] that holds a [`File`] handle, and reflects changes
 /// such as tag removals.
 ///
 /// For example:
 ///
 /// ```rust,no_run
-/// use lofty::{AudioFile, Tag, TagType, TaggedFileExt};
+/// use lofty::{AudioFile, Tag, TagType, TaggedFileExt, WriteOptions};
 /// # fn main() -> lofty::Result<()> {
 /// # let path = "tests/files/assets/minimal/full_test.mp3";
 ///
@@ -543,7 +948,7 @@ impl From<BoundTaggedFile> for TaggedFile {
 /// // After saving, our file still "contains" the ID3v2 tag, but if we were to read
 /// // "foo.mp3", it would not have an ID3v2 tag. Lofty does not write empty tags, but this
 /// // change will not be reflected in `TaggedFile`.
-/// tagged_file.save_to_path("foo.mp3")?;
+/// tagged_file.save_to_path("foo.mp3", WriteOptions::default())?;
 /// assert!(tagged_file.contains_tag_type(TagType::Id3v2));
 /// # Ok(()) }
 /// ```
@@ -551,7 +956,9 @@ impl From<BoundTaggedFile> for TaggedFile {
 /// However, when using `BoundTaggedFile`:
 ///
 /// ```rust,no_run
-/// use lofty::{AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt};
+/// use lofty::{
+/// 	AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt, WriteOptions,
+/// };
 /// use std::fs::OpenOptions;
 /// # fn main() -> lofty::Result<()> {
 /// # let path = "tests/files/assets/minimal/full_test.mp3";
@@ -570,7 +977,7 @@ impl From<BoundTaggedFile> for TaggedFile {
 ///
 /// // Now when saving, we no longer have to specify a path, and the tags in the `BoundTaggedFile`
 /// // reflect those in the actual file on disk.
-/// bound_tagged_file.save()?;
+/// bound_tagged_file.save(WriteOptions::default())?;
 /// assert!(!bound_tagged_file.contains_tag_type(TagType::Id3v2));
 /// # Ok(()) }
 /// ```
@@ -579,6 +986,7 @@ pub struct BoundTaggedFile {
 	file_handle: File,
 }
 
+#[cfg(feature = "fs")]
 impl BoundTaggedFile {
 	/// Create a new [`BoundTaggedFile`]
 	///
@@ -589,7 +997,9 @@ impl BoundTaggedFile {
 	/// # Examples
 	///
 	/// ```rust
-	/// use lofty::{AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt};
+	/// use lofty::{
+	/// 	AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt, WriteOptions,
+	/// };
 	/// use std::fs::OpenOptions;
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
@@ -603,6 +1013,9 @@ impl BoundTaggedFile {
 	/// ```
 	pub fn read_from(mut file: File, parse_options: ParseOptions) -> Result<Self> {
 		let inner = TaggedFile::read_from(&mut file, parse_options)?;
+
+		// The file is at hand, so its modification time can be checked as well
+		inner.fingerprint.track(&mut file)?;
 		file.rewind()?;
 
 		Ok(Self {
@@ -615,12 +1028,15 @@ impl BoundTaggedFile {
 	///
 	/// # Errors
 	///
-	/// See [`TaggedFile::save_to`]
+	/// * The file was modified since it was read, see [`WriteOptions::ignore_stale_file`]
+	/// * See [`TaggedFile::save_to`]
 	///
 	/// # Examples
 	///
 	/// ```rust,no_run
-	/// use lofty::{AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt};
+	/// use lofty::{
+	/// 	AudioFile, BoundTaggedFile, ParseOptions, Tag, TagType, TaggedFileExt, WriteOptions,
+	/// };
 	/// use std::fs::OpenOptions;
 	/// # fn main() -> lofty::Result<()> {
 	/// # let path = "tests/files/assets/minimal/full_test.mp3";
@@ -634,17 +1050,28 @@ impl BoundTaggedFile {
 	/// // Do some work to the tags...
 	///
 	/// // This will save the tags to the file we provided to `read_from`
-	/// bound_tagged_file.save()?;
+	/// bound_tagged_file.save(WriteOptions::default())?;
 	/// # Ok(()) }
 	/// ```
-	pub fn save(&mut self) -> Result<()> {
-		self.inner.save_to(&mut self.file_handle)?;
+	pub fn save(&mut self, write_options: WriteOptions) -> Result<()> {
+		self.inner.save_to(&mut self.file_handle, write_options)?;
 		self.inner.tags.retain(|tag| !tag.is_empty());
 
+		self.file_handle.rewind()?;
 		Ok(())
 	}
+
+	/// Plan saving the tags to the file stored internally, without modifying it
+	///
+	/// # Errors
+	///
+	/// See [`TaggedFile::plan_save`]
+	pub fn plan_save(&self, write_options: WriteOptions) -> Result<SavePlan> {
+		self.inner.plan_save(&self.file_handle, write_options)
+	}
 }
 
+#[cfg(feature = "fs")]
 impl TaggedFileExt for BoundTaggedFile {
 	fn file_type(&self) -> FileType {
 		self.inner.file_type()
@@ -679,6 +1106,7 @@ impl TaggedFileExt for BoundTaggedFile {
 	}
 }
 
+#[cfg(feature = "fs")]
 impl AudioFile for BoundTaggedFile {
 	type Properties = FileProperties;
 
@@ -692,8 +1120,9 @@ impl AudioFile for BoundTaggedFile {
 		)
 	}
 
-	fn save_to(&self, file: &mut File) -> Result<()> {
-		self.inner.save_to(file)
+	#[cfg(feature = "fs")]
+	fn save_to(&self, file: &mut File, write_options: WriteOptions) -> Result<()> {
+		self.inner.save_to(file, write_options)
 	}
 
 	fn properties(&self) -> &Self::Properties {
@@ -803,6 +1232,11 @@ impl FileType {
 
 	/// Attempts to extract a [`FileType`] from an extension
 	///
+	/// The extension is case-insensitive.
+	///
+	/// NOTE: `oga` can hold Vorbis, Opus, or FLAC, and is assumed to be Vorbis. [`Probe::open`](crate::Probe::open)
+	/// will check the content of such files when reading.
+	///
 	/// # Examples
 	///
 	/// ```rust
@@ -826,8 +1260,8 @@ impl FileType {
 			"wv" => Some(Self::WavPack),
 			"opus" => Some(Self::Opus),
 			"flac" => Some(Self::Flac),
-			"ogg" => Some(Self::Vorbis),
-			"mp4" | "m4a" | "m4b" | "m4p" | "m4r" | "m4v" | "3gp" => Some(Self::Mp4),
+			"ogg" | "oga" => Some(Self::Vorbis),
+			"mp4" | "m4a" | "mp4a" | "m4b" | "m4p" | "m4r" | "m4v" | "3gp" => Some(Self::Mp4),
 			"mpc" | "mp+" | "mpp" => Some(Self::Mpc),
 			"spx" => Some(Self::Speex),
 			e => {
@@ -906,6 +1340,7 @@ impl FileType {
 	// TODO: APE tags in the beginning of the file
 	pub(crate) fn from_buffer_inner(buf: &[u8]) -> FileTypeGuessResult {
 		use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+		use crate::ogg::constants::SKELETON_HEAD;
 
 		// Start out with an empty return
 		let mut ret = FileTypeGuessResult::Undetermined;
@@ -929,6 +1364,15 @@ impl FileType {
 			None if buf.first().copied() == Some(0) => {
 				ret = FileTypeGuessResult::MaybePrecededByJunk
 			},
+			// A RIFF/AIFF header that we don't recognize may be left over from a badly stripped
+			// container, with MPEG/AAC frames following it
+			None if buf.starts_with(b"RIFF") || buf.starts_with(b"FORM") => {
+				ret = FileTypeGuessResult::MaybePrecededByJunk
+			},
+			// The first page of an Ogg Skeleton stream, the audio stream's first page comes later
+			None if buf.len() >= 36 && &buf[..4] == b"OggS" && &buf[28..36] == SKELETON_HEAD => {
+				ret = FileTypeGuessResult::OggSkeleton
+			},
 			// We aren't able to determine a format
 			_ => {},
 		}
@@ -1021,8 +1465,10 @@ pub(crate) enum FileTypeGuessResult {
 	Determined(FileType),
 	/// The stream starts with an ID3v2 tag
 	MaybePrecededById3(u32),
-	/// The stream starts with junk zero bytes
+	/// The stream starts with junk, such as zero bytes or an unknown RIFF/AIFF header
 	MaybePrecededByJunk,
+	/// The stream is an Ogg file beginning with an Ogg Skeleton stream, the audio stream follows
+	OggSkeleton,
 	/// The `FileType` could not be guessed
 	Undetermined,
 }
@@ -0,0 +1,288 @@
+use crate::archive::tag_regions;
+use crate::error::Result;
+use crate::file::FileType;
+use crate::macros::err;
+use crate::probe::Probe;
+use crate::write_options::WriteOptions;
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+/// A lightweight snapshot of a file's state on disk
+///
+/// This is used to detect a file being modified by someone else between reading and writing it. For
+/// example, a background scanner rewriting the file while a user is editing its tags. Saving over such a
+/// file would discard those modifications.
+///
+/// The fingerprint consists of:
+///
+/// * The length of the file
+/// * The last modification time, if the platform provides it
+/// * A hash of the tag regions of the file, the same ones recorded in a [`TagArchive`](crate::TagArchive)
+///
+/// Every file type takes a fingerprint when it's read, and verifies it in [`AudioFile::save_to`](crate::AudioFile::save_to),
+/// see [`WriteOptions::ignore_stale_file`]. As files are read from any reader, the modification time is only known
+/// once the file has been saved, or when read through a [`BoundTaggedFile`](crate::BoundTaggedFile).
+///
+/// A fingerprint can also be taken manually, and checked prior to writing with [`FileFingerprint::verify`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::{AudioFile, FileFingerprint, ParseOptions, WriteOptions};
+/// use lofty::mpeg::MpegFile;
+/// use std::fs::OpenOptions;
+///
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.mp3";
+/// let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+///
+/// let fingerprint = FileFingerprint::of(&mut file)?;
+/// let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new())?;
+///
+/// // Some time later...
+/// fingerprint.verify(&mut file)?;
+/// mpeg_file.save_to(&mut file, WriteOptions::new())?;
+/// # Ok(()) }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FileFingerprint {
+	file_type: Option<FileType>,
+	len: u64,
+	modified: Option<SystemTime>,
+	hash: u64,
+}
+
+impl FileFingerprint {
+	/// Take a fingerprint of a file
+	///
+	/// The file type is guessed to find its tags, see [`Probe::guess_file_type`]. If it can't be determined,
+	/// only the length and modification time are used.
+	///
+	/// NOTE: The file's position will be restored afterwards
+	///
+	/// # Errors
+	///
+	/// * [`std::io::Error`]
+	pub fn of(file: &mut File) -> Result<Self> {
+		let position = file.stream_position()?;
+
+		file.rewind()?;
+		let file_type = Probe::new(BufReader::new(&mut *file))
+			.guess_file_type()?
+			.file_type();
+		file.seek(SeekFrom::Start(position))?;
+
+		let mut fingerprint = Self::read_from(file, file_type)?;
+		fingerprint.modified = file.metadata()?.modified().ok();
+
+		Ok(fingerprint)
+	}
+
+	// Takes a fingerprint of anything, without a modification time
+	pub(crate) fn read_from<R>(reader: &mut R, file_type: Option<FileType>) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let position = reader.stream_position()?;
+		let len = reader.seek(SeekFrom::End(0))?;
+
+		// Files that are only readable with `ParsingMode::Relaxed` may not have well-formed tags to
+		// locate, leaving just the length to compare
+		let regions = match file_type {
+			Some(FileType::Custom(_)) | None => Vec::new(),
+			Some(file_type) => tag_regions(reader, file_type).unwrap_or_default(),
+		};
+
+		let mut hasher = DefaultHasher::new();
+		let mut buf = Vec::new();
+		for (_, region) in regions {
+			hasher.write_u64(region.start);
+			hasher.write_u64(region.end);
+
+			buf.clear();
+			reader.seek(SeekFrom::Start(region.start))?;
+			reader
+				.by_ref()
+				.take(region.end - region.start)
+				.read_to_end(&mut buf)?;
+			hasher.write(&buf);
+		}
+
+		reader.seek(SeekFrom::Start(position))?;
+
+		Ok(Self {
+			file_type,
+			len,
+			modified: None,
+			hash: hasher.finish(),
+		})
+	}
+
+	/// Verify that a file still matches the fingerprint
+	///
+	/// The modification time is only compared if the fingerprint has one.
+	///
+	/// NOTE: The file's position will be restored afterwards
+	///
+	/// # Errors
+	///
+	/// * The file no longer matches, see [`ErrorKind::StaleFile`](crate::error::ErrorKind::StaleFile)
+	/// * [`std::io::Error`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::FileFingerprint;
+	/// use lofty::error::ErrorKind;
+	/// use std::io::Write;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut file = tempfile::tempfile()?;
+	/// let fingerprint = FileFingerprint::of(&mut file)?;
+	/// assert!(fingerprint.verify(&mut file).is_ok());
+	///
+	/// // Another program modifies the file
+	/// file.write_all(b"Foo")?;
+	///
+	/// let err = fingerprint.verify(&mut file).unwrap_err();
+	/// assert!(matches!(err.kind(), ErrorKind::StaleFile));
+	/// # Ok(()) }
+	/// ```
+	pub fn verify(&self, file: &mut File) -> Result<()> {
+		if *self != self.retake(file)? {
+			err!(StaleFile);
+		}
+
+		Ok(())
+	}
+
+	// Takes a new fingerprint of the same type of file
+	fn retake(&self, file: &mut File) -> Result<Self> {
+		let mut fingerprint = Self::read_from(file, self.file_type)?;
+		if self.modified.is_some() {
+			fingerprint.modified = file.metadata()?.modified().ok();
+		}
+
+		Ok(fingerprint)
+	}
+}
+
+/// The fingerprint a file type takes when it's read, see [`WriteOptions::ignore_stale_file`]
+///
+/// This is updated after every save, so the same file can be saved again.
+#[derive(Debug, Default)]
+pub(crate) struct FingerprintCell(Mutex<Option<FileFingerprint>>);
+
+impl FingerprintCell {
+	pub(crate) const fn empty() -> Self {
+		Self(Mutex::new(None))
+	}
+
+	pub(crate) fn read_from<R>(reader: &mut R, file_type: FileType) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let fingerprint = FileFingerprint::read_from(reader, Some(file_type))?;
+		Ok(Self(Mutex::new(Some(fingerprint))))
+	}
+
+	/// Verify the file prior to saving, see [`WriteOptions::ignore_stale_file`]
+	pub(crate) fn verify(&self, file: &mut File, write_options: WriteOptions) -> Result<()> {
+		match self.get() {
+			Some(fingerprint) if !write_options.ignore_stale_file => fingerprint.verify(file),
+			_ => Ok(()),
+		}
+	}
+
+	/// Take a new fingerprint after saving, which also records the modification time
+	pub(crate) fn refresh(&self, file: &mut File) -> Result<()> {
+		let Some(fingerprint) = self.get() else {
+			return Ok(());
+		};
+
+		let mut refreshed = FileFingerprint::read_from(file, fingerprint.file_type)?;
+		refreshed.modified = file.metadata()?.modified().ok();
+
+		self.set(refreshed);
+		Ok(())
+	}
+
+	/// Like [`FingerprintCell::refresh`], taking a fingerprint of a file of any type if there isn't one yet
+	pub(crate) fn track(&self, file: &mut File) -> Result<()> {
+		if self.get().is_some() {
+			return self.refresh(file);
+		}
+
+		self.set(FileFingerprint::of(file)?);
+		Ok(())
+	}
+
+	fn get(&self) -> Option<FileFingerprint> {
+		*self.0.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn set(&self, fingerprint: FileFingerprint) {
+		*self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(fingerprint);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::FileFingerprint;
+	use crate::archive::tag_regions;
+	use crate::file::FileType;
+
+	use std::io::{Seek, SeekFrom, Write};
+
+	#[test]
+	fn detects_changes() {
+		let contents = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(&contents).unwrap();
+
+		let fingerprint = FileFingerprint::of(&mut file).unwrap();
+		assert_eq!(fingerprint.file_type, Some(FileType::Mpeg));
+		assert_eq!(file.stream_position().unwrap(), contents.len() as u64);
+		assert!(fingerprint.verify(&mut file).is_ok());
+
+		let regions = tag_regions(&mut file, FileType::Mpeg).unwrap();
+		let (_, id3v2_region) = &regions[0];
+
+		// A change to a tag, with the length and (possibly coarse) modification time unchanged
+		file.seek(SeekFrom::Start(id3v2_region.start + 20)).unwrap();
+		file.write_all(&[1]).unwrap();
+		let fingerprint = FileFingerprint {
+			modified: file.metadata().unwrap().modified().ok(),
+			..fingerprint
+		};
+		assert!(fingerprint.verify(&mut file).is_err());
+
+		// A change to the audio goes unnoticed, that's not where tags live
+		let audio_pos = contents.len() as u64 / 2;
+		assert!(regions
+			.iter()
+			.all(|(_, region)| !region.contains(&audio_pos)));
+
+		let fingerprint = FileFingerprint::of(&mut file).unwrap();
+		file.seek(SeekFrom::Start(audio_pos)).unwrap();
+		file.write_all(&[1]).unwrap();
+		let fingerprint = FileFingerprint {
+			modified: file.metadata().unwrap().modified().ok(),
+			..fingerprint
+		};
+		assert!(fingerprint.verify(&mut file).is_ok());
+
+		// Without a modification time, only the tags and length are compared
+		let fingerprint = FileFingerprint {
+			modified: None,
+			..fingerprint
+		};
+		assert!(fingerprint.verify(&mut file).is_ok());
+	}
+}
@@ -7,6 +7,7 @@ use byteorder::{BigEndian, ReadBytesExt};
 
 pub(in crate::flac) const BLOCK_ID_STREAMINFO: u8 = 0;
 pub(in crate::flac) const BLOCK_ID_PADDING: u8 = 1;
+pub(in crate::flac) const BLOCK_ID_APPLICATION: u8 = 2;
 pub(in crate::flac) const BLOCK_ID_SEEKTABLE: u8 = 3;
 pub(in crate::flac) const BLOCK_ID_VORBIS_COMMENTS: u8 = 4;
 pub(in crate::flac) const BLOCK_ID_PICTURE: u8 = 6;
@@ -48,3 +49,17 @@ impl Block {
 		})
 	}
 }
+
+/// A FLAC `APPLICATION` block
+///
+/// These hold data for a specific application, identified by an ID registered with
+/// [Xiph](https://xiph.org/flac/id.html).
+///
+/// See [`FlacFile::insert_application_block`](crate::flac::FlacFile::insert_application_block).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplicationBlock {
+	/// The registered application ID
+	pub id: [u8; 4],
+	/// The application's data
+	pub data: Vec<u8>,
+}
@@ -9,22 +9,28 @@ pub(crate) mod properties;
 mod read;
 pub(crate) mod write;
 
-use crate::error::Result;
 use crate::file::{FileType, TaggedFile};
 use crate::id3::v2::tag::Id3v2Tag;
-use crate::ogg::tag::VorbisCommentsRef;
 use crate::ogg::{OggPictureStorage, VorbisComments};
 use crate::picture::{Picture, PictureInformation};
-use crate::traits::TagExt;
+#[cfg(feature = "fs")]
+use crate::{
+	error::Result, ogg::tag::VorbisCommentsRef, traits::TagExt, write_options::WriteOptions,
+};
 
+#[cfg(feature = "fs")]
 use std::fs::File;
+#[cfg(feature = "fs")]
 use std::io::Seek;
 
 use lofty_attr::LoftyFile;
 
 // Exports
 
+pub use block::ApplicationBlock;
 pub use properties::FlacProperties;
+#[cfg(feature = "fs")]
+pub(crate) use read::comment_block_ranges;
 
 /// A FLAC file
 ///
@@ -37,6 +43,7 @@ pub use properties::FlacProperties;
 ///   methods on `FlacFile` ([`FlacFile::pictures`], [`FlacFile::remove_picture_type`], etc.)
 /// * When converting to [`TaggedFile`], all pictures will be put inside of a [`VorbisComments`] tag, even if the
 ///   file did not originally contain one.
+/// * `APPLICATION` blocks are only written when saving the `FlacFile` itself, saving a tag leaves them untouched.
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
 #[lofty(write_fn = "Self::write_to")]
@@ -49,21 +56,122 @@ pub struct FlacFile {
 	#[lofty(tag_type = "VorbisComments")]
 	pub(crate) vorbis_comments_tag: Option<VorbisComments>,
 	pub(crate) pictures: Vec<(Picture, PictureInformation)>,
+	pub(crate) application_blocks: Vec<ApplicationBlock>,
 	/// The file's audio properties
 	pub(crate) properties: FlacProperties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
 
 impl FlacFile {
+	/// Returns the file's `APPLICATION` blocks, in the order they appear
+	pub fn application_blocks(&self) -> &[ApplicationBlock] {
+		&self.application_blocks
+	}
+
+	/// Gets the `APPLICATION` block with the given ID
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::flac::FlacFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut flac_reader = std::io::Cursor::new(&[]);
+	/// let flac_file = FlacFile::read_from(&mut flac_reader, ParseOptions::new())?;
+	///
+	/// if let Some(block) = flac_file.application_block(*b"MYAP") {
+	/// 	println!("Found {} bytes of MYAP data", block.data.len());
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn application_block(&self, id: [u8; 4]) -> Option<&ApplicationBlock> {
+		self.application_blocks.iter().find(|block| block.id == id)
+	}
+
+	/// Inserts an `APPLICATION` block
+	///
+	/// If a block with the same ID exists, it will be replaced and returned. New blocks are written
+	/// after any existing `APPLICATION` blocks, or after the last block that isn't padding.
+	///
+	/// NOTE: The block's size is only checked when writing, see [`ErrorKind::TooMuchData`](crate::error::ErrorKind::TooMuchData).
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::flac::{ApplicationBlock, FlacFile};
+	/// use lofty::{AudioFile, ParseOptions, WriteOptions};
+	///
+	/// # #[cfg(feature = "fs")]
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut flac_reader = std::io::Cursor::new(&[]);
+	/// let mut flac_file = FlacFile::read_from(&mut flac_reader, ParseOptions::new())?;
+	///
+	/// flac_file.insert_application_block(ApplicationBlock {
+	/// 	id: *b"MYAP",
+	/// 	data: vec![1, 2, 3],
+	/// });
+	///
+	/// flac_file.save_to_path("foo.flac", WriteOptions::default())?;
+	/// # Ok(()) }
+	/// # #[cfg(not(feature = "fs"))]
+	/// # fn main() {}
+	/// ```
+	pub fn insert_application_block(
+		&mut self,
+		block: ApplicationBlock,
+	) -> Option<ApplicationBlock> {
+		match self
+			.application_blocks
+			.iter_mut()
+			.find(|existing| existing.id == block.id)
+		{
+			Some(existing) => Some(std::mem::replace(existing, block)),
+			None => {
+				self.application_blocks.push(block);
+				None
+			},
+		}
+	}
+
+	/// Removes the `APPLICATION` block with the given ID
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::flac::FlacFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut flac_reader = std::io::Cursor::new(&[]);
+	/// let mut flac_file = FlacFile::read_from(&mut flac_reader, ParseOptions::new())?;
+	///
+	/// let removed = flac_file.remove_application_block(*b"MYAP");
+	/// assert!(flac_file.application_block(*b"MYAP").is_none());
+	/// # Ok(()) }
+	/// ```
+	pub fn remove_application_block(&mut self, id: [u8; 4]) -> Option<ApplicationBlock> {
+		let idx = self
+			.application_blocks
+			.iter()
+			.position(|block| block.id == id)?;
+
+		Some(self.application_blocks.remove(idx))
+	}
+
 	// We need a special write fn to append our pictures into a `VorbisComments` tag
-	fn write_to(&self, file: &mut File) -> Result<()> {
+	#[cfg(feature = "fs")]
+	fn write_to(&self, file: &mut File, write_options: WriteOptions) -> Result<()> {
 		if let Some(ref id3v2) = self.id3v2_tag {
-			id3v2.save_to(file)?;
+			id3v2.save_to(file, write_options)?;
 			file.rewind()?;
 		}
 
-		// We have an existing vorbis comments tag, we can just append our pictures to it
-		if let Some(ref vorbis_comments) = self.vorbis_comments_tag {
-			return VorbisCommentsRef {
+		match self.vorbis_comments_tag {
+			// We have an existing vorbis comments tag, we can just append our pictures to it
+			Some(ref vorbis_comments) => VorbisCommentsRef {
 				vendor: vorbis_comments.vendor.as_str(),
 				items: vorbis_comments
 					.items
@@ -74,21 +182,22 @@ impl FlacFile {
 					.iter()
 					.map(|(p, i)| (p, *i))
 					.chain(self.pictures.iter().map(|(p, i)| (p, *i))),
+				unparsed_items: &vorbis_comments.unparsed_items,
 			}
-			.write_to(file);
-		}
-
-		// We have pictures, but no vorbis comments tag, we'll need to create a dummy one
-		if !self.pictures.is_empty() {
-			return VorbisCommentsRef {
+			.write_to(file, write_options)?,
+			// We have pictures, but no vorbis comments tag, we'll need to create a dummy one
+			None if !self.pictures.is_empty() => VorbisCommentsRef {
 				vendor: "",
 				items: std::iter::empty(),
 				pictures: self.pictures.iter().map(|(p, i)| (p, *i)),
+				unparsed_items: &[],
 			}
-			.write_to(file);
+			.write_to(file, write_options)?,
+			None => {},
 		}
 
-		Ok(())
+		file.rewind()?;
+		write::write_application_blocks(file, &self.application_blocks)
 	}
 }
 
@@ -121,6 +230,7 @@ impl From<FlacFile> for TaggedFile {
 							vendor: String::new(),
 							items: Vec::new(),
 							pictures: value.pictures,
+							unparsed_items: Vec::new(),
 						}
 						.into(),
 					),
@@ -129,6 +239,11 @@ impl From<FlacFile> for TaggedFile {
 
 				tags
 			},
+			start_offset: 0,
+			// ID3v2 tags are read only in FLAC files, there's nothing to write back
+			id3v2_remainder: None,
+			#[cfg(feature = "fs")]
+			fingerprint: value.fingerprint,
 		}
 	}
 }
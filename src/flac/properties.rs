@@ -1,5 +1,5 @@
 use crate::error::Result;
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::io::Read;
 use std::time::Duration;
@@ -23,12 +23,15 @@ impl From<FlacProperties> for FileProperties {
 	fn from(input: FlacProperties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
-			bit_depth: Some(input.bit_depth),
-			channels: Some(input.channels),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
+			bit_depth: non_zero(input.bit_depth),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
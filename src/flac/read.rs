@@ -1,17 +1,18 @@
-use super::block::Block;
+use super::block::{ApplicationBlock, Block};
 use super::properties::FlacProperties;
 use super::FlacFile;
 use crate::error::Result;
 use crate::flac::block::{
-	BLOCK_ID_PADDING, BLOCK_ID_PICTURE, BLOCK_ID_SEEKTABLE, BLOCK_ID_STREAMINFO,
-	BLOCK_ID_VORBIS_COMMENTS,
+	BLOCK_ID_APPLICATION, BLOCK_ID_PADDING, BLOCK_ID_PICTURE, BLOCK_ID_SEEKTABLE,
+	BLOCK_ID_STREAMINFO, BLOCK_ID_VORBIS_COMMENTS,
 };
 use crate::id3::v2::read::parse_id3v2;
-use crate::id3::{find_id3v2, ID3FindResults};
+use crate::id3::{find_id3v2, FindId3v2Config, ID3FindResults};
 use crate::macros::decode_err;
 use crate::ogg::read::read_comments;
 use crate::picture::Picture;
 use crate::probe::{ParseOptions, ParsingMode};
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -35,22 +36,56 @@ where
 	Ok(block)
 }
 
+// The blocks holding the Vorbis comments and pictures, following any ID3v2 tag
+#[cfg(feature = "fs")]
+pub(crate) fn comment_block_ranges<R>(data: &mut R) -> Result<Vec<std::ops::Range<u64>>>
+where
+	R: Read + Seek,
+{
+	find_id3v2(
+		data,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+
+	let mut ranges = Vec::new();
+
+	let mut last_block = verify_flac(data)?.last;
+	while !last_block {
+		let block = Block::read(data)?;
+		last_block = block.last;
+
+		if block.ty == BLOCK_ID_VORBIS_COMMENTS || block.ty == BLOCK_ID_PICTURE {
+			ranges.push(block.start..block.end);
+		}
+	}
+
+	Ok(ranges)
+}
+
 pub(crate) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<FlacFile>
 where
 	R: Read + Seek,
 {
+	let data = &mut OffsetFile::new(data, parse_options.start_offset)?;
+
 	let mut flac_file = FlacFile {
 		id3v2_tag: None,
 		vorbis_comments_tag: None,
 		pictures: Vec::new(),
+		application_blocks: Vec::new(),
 		properties: FlacProperties::default(),
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	};
 
 	// It is possible for a FLAC file to contain an ID3v2 tag
-	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(data, true)? {
+	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(
+		data,
+		FindId3v2Config::READ_TAG.allowed_padding(parse_options.max_junk_bytes as u64),
+	)? {
 		let reader = &mut &*content;
 
-		let id3v2 = parse_id3v2(reader, header, parse_options.parsing_mode)?;
+		let id3v2 = parse_id3v2(reader, header, parse_options)?;
 		flac_file.id3v2_tag = Some(id3v2);
 	}
 
@@ -99,6 +134,25 @@ where
 			continue;
 		}
 
+		if block.ty == BLOCK_ID_APPLICATION {
+			if block.content.len() < 4 {
+				if parse_options.parsing_mode == ParsingMode::Strict {
+					decode_err!(@BAIL Flac, "Encountered an APPLICATION block without an ID");
+				}
+
+				log::warn!("Encountered an APPLICATION block without an ID, discarding");
+				continue;
+			}
+
+			let mut content = block.content;
+			let data = content.split_off(4);
+			flac_file.application_blocks.push(ApplicationBlock {
+				id: [content[0], content[1], content[2], content[3]],
+				data,
+			});
+			continue;
+		}
+
 		if block.ty == BLOCK_ID_PICTURE {
 			match Picture::from_flac_bytes(&block.content, false, parse_options.parsing_mode) {
 				Ok(picture) => flac_file.pictures.push(picture),
@@ -1,20 +1,28 @@
 use super::block::Block;
+#[cfg(feature = "fs")]
+use super::block::{ApplicationBlock, BLOCK_ID_APPLICATION, BLOCK_ID_PADDING};
 use super::read::verify_flac;
 use crate::error::Result;
+#[cfg(feature = "fs")]
+use crate::id3::{find_id3v2, FindId3v2Config};
 use crate::macros::err;
 use crate::ogg::tag::VorbisCommentsRef;
-use crate::ogg::write::create_comments;
-use crate::picture::{Picture, PictureInformation};
+use crate::ogg::write::{create_comments, create_unparsed_comments};
+use crate::picture::{check_picture_size, Picture, PictureInformation};
 use crate::tag::{Tag, TagType};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
-use std::fs::File;
-use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use byteorder::{LittleEndian, WriteBytesExt};
 
 const MAX_BLOCK_SIZE: u32 = 16_777_215;
 
-pub(crate) fn write_to(file: &mut File, tag: &Tag) -> Result<()> {
+pub(crate) fn write_to<F>(file: &mut F, tag: &Tag, write_options: WriteOptions) -> Result<()>
+where
+	F: FileLike,
+{
 	match tag.tag_type() {
 		TagType::VorbisComments => {
 			let (vendor, items, pictures) = crate::ogg::tag::create_vorbis_comments_ref(tag);
@@ -23,21 +31,24 @@ pub(crate) fn write_to(file: &mut File, tag: &Tag) -> Result<()> {
 				vendor,
 				items,
 				pictures,
+				unparsed_items: &[],
 			};
 
-			write_to_inner(file, &mut comments_ref)
+			write_to_inner(file, &mut comments_ref, write_options)
 		},
 		// This tag can *only* be removed in this format
-		TagType::Id3v2 => crate::id3::v2::tag::Id3v2TagRef::empty().write_to(file),
+		TagType::Id3v2 => crate::id3::v2::tag::Id3v2TagRef::empty().write_to(file, write_options),
 		_ => err!(UnsupportedTag),
 	}
 }
 
-pub(crate) fn write_to_inner<'a, II, IP>(
-	file: &mut File,
+pub(crate) fn write_to_inner<'a, F, II, IP>(
+	file: &mut F,
 	tag: &mut VorbisCommentsRef<'a, II, IP>,
+	write_options: WriteOptions,
 ) -> Result<()>
 where
+	F: FileLike,
 	II: Iterator<Item = (&'a str, &'a str)>,
 	IP: Iterator<Item = (&'a Picture, PictureInformation)>,
 {
@@ -48,6 +59,7 @@ where
 
 	let mut file_bytes = Vec::new();
 	file.read_to_end(&mut file_bytes)?;
+	let original_len = file_bytes.len();
 
 	let mut cursor = Cursor::new(file_bytes);
 
@@ -102,11 +114,17 @@ where
 
 	let mut comment_blocks = Cursor::new(Vec::new());
 
-	create_comment_block(&mut comment_blocks, tag.vendor, &mut tag.items)?;
+	create_comment_block(
+		&mut comment_blocks,
+		tag.vendor,
+		&mut tag.items,
+		tag.unparsed_items,
+		write_options,
+	)?;
 
 	let mut comment_blocks = comment_blocks.into_inner();
 
-	create_picture_blocks(&mut comment_blocks, &mut tag.pictures)?;
+	create_picture_blocks(&mut comment_blocks, &mut tag.pictures, write_options)?;
 
 	if blocks_remove.is_empty() {
 		file_bytes.splice(0..0, comment_blocks);
@@ -123,6 +141,11 @@ where
 		file_bytes.splice(first.0 as usize..first.1 as usize, comment_blocks);
 	}
 
+	// The blocks are rewritten as a whole, the audio only moves if their total size changed
+	if file_bytes.len() != original_len {
+		file.mark_moved(stream_info_end as u64);
+	}
+
 	file.seek(SeekFrom::Start(stream_info_end as u64))?;
 	file.set_len(stream_info_end as u64)?;
 	file.write_all(&file_bytes)?;
@@ -134,10 +157,16 @@ fn create_comment_block(
 	writer: &mut Cursor<Vec<u8>>,
 	vendor: &str,
 	items: &mut dyn Iterator<Item = (&str, &str)>,
+	unparsed_items: &[Vec<u8>],
+	write_options: WriteOptions,
 ) -> Result<()> {
-	let mut peek = items.peekable();
+	// Items that won't be written (see `create_comments`) shouldn't keep the block around
+	let mut peek = items
+		.filter(|(_, v)| write_options.text_item(v).is_some())
+		.peekable();
 
-	if peek.peek().is_some() {
+	// An empty block is only written if the caller wants to keep the vendor string around
+	if peek.peek().is_some() || !unparsed_items.is_empty() || !write_options.remove_tags_if_empty {
 		let mut byte = 0_u8;
 		byte |= 4 & 0x7F;
 
@@ -150,7 +179,8 @@ fn create_comment_block(
 
 		writer.write_u32::<LittleEndian>(count)?;
 
-		create_comments(writer, &mut count, &mut peek)?;
+		create_comments(writer, &mut count, &mut peek, write_options)?;
+		create_unparsed_comments(writer, &mut count, unparsed_items)?;
 
 		let len = (writer.get_ref().len() - 1) as u32;
 
@@ -175,6 +205,7 @@ fn create_comment_block(
 fn create_picture_blocks(
 	writer: &mut Vec<u8>,
 	pictures: &mut dyn Iterator<Item = (&Picture, PictureInformation)>,
+	write_options: WriteOptions,
 ) -> Result<()> {
 	let mut byte = 0_u8;
 	byte |= 6 & 0x7F;
@@ -189,9 +220,112 @@ fn create_picture_blocks(
 			err!(TooMuchData);
 		}
 
+		// Block header
+		check_picture_size(u64::from(pic_len) + 4, write_options)?;
+
 		writer.write_all(&pic_len.to_be_bytes()[1..])?;
 		writer.write_all(pic_bytes.as_slice())?;
 	}
 
 	Ok(())
 }
+
+// Replaces the `APPLICATION` blocks of a file, leaving every other block as-is
+//
+// The new blocks take the place of the first existing `APPLICATION` block. If there are none, they're
+// written after the last block that isn't padding, so the padding stays at the end.
+#[cfg(feature = "fs")]
+pub(crate) fn write_application_blocks<F>(
+	file: &mut F,
+	application_blocks: &[ApplicationBlock],
+) -> Result<()>
+where
+	F: FileLike,
+{
+	// It is possible for a FLAC file to contain an ID3v2 tag
+	find_id3v2(file, FindId3v2Config::NO_READ_TAG)?;
+
+	let stream_info = verify_flac(file)?;
+	let metadata_start = stream_info.start;
+
+	let mut last_block = stream_info.last;
+	let mut blocks = vec![(stream_info.ty, stream_info.content)];
+	while !last_block {
+		let block = Block::read(file)?;
+		last_block = block.last;
+		blocks.push((block.ty, block.content));
+	}
+
+	let metadata_end = file.stream_position()?;
+
+	let mut new_application_blocks = Vec::with_capacity(application_blocks.len());
+	for block in application_blocks {
+		let mut content = Vec::with_capacity(block.id.len() + block.data.len());
+		content.extend_from_slice(&block.id);
+		content.extend_from_slice(&block.data);
+
+		if content.len() > MAX_BLOCK_SIZE as usize {
+			err!(TooMuchData);
+		}
+
+		new_application_blocks.push(content);
+	}
+
+	let position = blocks
+		.iter()
+		.position(|(ty, _)| *ty == BLOCK_ID_APPLICATION)
+		.unwrap_or_else(|| {
+			blocks
+				.iter()
+				.rposition(|(ty, _)| *ty != BLOCK_ID_PADDING)
+				.map_or(blocks.len(), |idx| idx + 1)
+		});
+
+	let mut new_blocks = blocks
+		.iter()
+		.filter(|(ty, _)| *ty != BLOCK_ID_APPLICATION)
+		.map(|(ty, content)| (*ty, content.as_slice()))
+		.collect::<Vec<_>>();
+	new_blocks.splice(
+		position..position,
+		new_application_blocks
+			.iter()
+			.map(|content| (BLOCK_ID_APPLICATION, content.as_slice())),
+	);
+
+	// Nothing to do, avoid rewriting the file
+	if new_blocks
+		.iter()
+		.copied()
+		.eq(blocks.iter().map(|(ty, content)| (*ty, content.as_slice())))
+	{
+		return Ok(());
+	}
+
+	let mut metadata = Vec::new();
+	for (idx, (ty, content)) in new_blocks.iter().enumerate() {
+		let mut byte = ty & 0x7F;
+		if idx + 1 == new_blocks.len() {
+			byte |= 0x80;
+		}
+
+		metadata.write_u8(byte)?;
+		metadata.write_all(&(content.len() as u32).to_be_bytes()[1..])?;
+		metadata.write_all(content)?;
+	}
+
+	if metadata.len() as u64 != metadata_end - metadata_start {
+		file.mark_moved(metadata_start);
+	}
+
+	let mut audio = Vec::new();
+	file.seek(SeekFrom::Start(metadata_end))?;
+	file.read_to_end(&mut audio)?;
+
+	file.seek(SeekFrom::Start(metadata_start))?;
+	file.set_len(metadata_start)?;
+	file.write_all(&metadata)?;
+	file.write_all(&audio)?;
+
+	Ok(())
+}
@@ -9,11 +9,14 @@ pub mod v2;
 use crate::error::{ErrorKind, LoftyError, Result};
 use crate::macros::try_vec;
 use crate::util::text::utf8_decode_str;
-use v2::header::Id3v2Header;
+use v2::header::{Id3v2Header, Id3v2Version};
+use v2::util::synchsafe::SynchsafeInteger;
 
 use std::io::{Read, Seek, SeekFrom};
 use std::ops::Neg;
 
+use byteorder::{BigEndian, ByteOrder};
+
 pub(crate) struct ID3FindResults<Header, Content>(pub Option<Header>, pub Content);
 
 pub(crate) fn find_lyrics3v2<R>(data: &mut R) -> Result<ID3FindResults<(), u32>>
@@ -88,9 +91,36 @@ where
 	Ok(ID3FindResults(header, id3v1))
 }
 
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct FindId3v2Config {
+	/// Whether to read the tag content, or simply seek past it
+	pub(crate) read: bool,
+	/// The maximum number of zero bytes allowed before the tag
+	pub(crate) allowed_padding: Option<u64>,
+}
+
+impl FindId3v2Config {
+	pub(crate) const NO_READ_TAG: Self = Self {
+		read: false,
+		allowed_padding: None,
+	};
+
+	pub(crate) const READ_TAG: Self = Self {
+		read: true,
+		allowed_padding: None,
+	};
+
+	pub(crate) fn allowed_padding(self, allowed_padding: u64) -> Self {
+		Self {
+			allowed_padding: Some(allowed_padding),
+			..self
+		}
+	}
+}
+
 pub(crate) fn find_id3v2<R>(
 	data: &mut R,
-	read: bool,
+	config: FindId3v2Config,
 ) -> Result<ID3FindResults<Id3v2Header, Option<Vec<u8>>>>
 where
 	R: Read + Seek,
@@ -98,14 +128,34 @@ where
 	let mut header = None;
 	let mut id3v2 = None;
 
+	let start = data.stream_position()?;
+
+	// Some files have zero-byte padding before the tag
+	let mut padding = 0;
+	if let Some(allowed_padding) = config.allowed_padding {
+		let mut window = Vec::new();
+		data.by_ref()
+			.take(allowed_padding)
+			.read_to_end(&mut window)?;
+
+		padding = window.iter().take_while(|b| **b == 0).count() as u64;
+		data.seek(SeekFrom::Start(start + padding))?;
+	}
+
 	if let Ok(id3v2_header) = Id3v2Header::parse(data) {
-		if read {
-			let mut tag = try_vec![0; id3v2_header.size as usize];
+		if padding > 0 {
+			log::warn!("Found an ID3v2 tag preceded by {padding} bytes of padding");
+		}
+
+		// The extended header was already read along with the header
+		let remaining_size = id3v2_header.size - id3v2_header.extended_size;
+		if config.read {
+			let mut tag = try_vec![0; remaining_size as usize];
 			data.read_exact(&mut tag)?;
 
 			id3v2 = Some(tag)
 		} else {
-			data.seek(SeekFrom::Current(i64::from(id3v2_header.size)))?;
+			data.seek(SeekFrom::Current(i64::from(remaining_size)))?;
 		}
 
 		if id3v2_header.flags.footer {
@@ -114,8 +164,76 @@ where
 
 		header = Some(id3v2_header);
 	} else {
-		data.seek(SeekFrom::Current(-10))?;
+		// No tag, the padding belongs to whatever follows
+		data.seek(SeekFrom::Start(start))?;
 	}
 
 	Ok(ID3FindResults(header, id3v2))
 }
+
+/// Searches for an ID3v2 tag ending at the current position, identified by its footer
+///
+/// ID3v2.4 tags with a footer can be appended to the end of a file. The tag must start at or after
+/// `start_bound`, so the footer of a tag at the start of the file isn't mistaken for one.
+///
+/// If a tag is found, the reader is left at the start of it, otherwise the position is unchanged.
+pub(crate) fn find_id3v2_footer<R>(
+	data: &mut R,
+	start_bound: u64,
+	read: bool,
+) -> Result<ID3FindResults<Id3v2Header, Option<Vec<u8>>>>
+where
+	R: Read + Seek,
+{
+	let end = data.stream_position()?;
+
+	// The header and footer alone are 20 bytes
+	if end < start_bound.saturating_add(20) {
+		return Ok(ID3FindResults(None, None));
+	}
+
+	data.seek(SeekFrom::Start(end - 10))?;
+
+	let mut footer = [0; 10];
+	data.read_exact(&mut footer)?;
+
+	// [3, D, I, ver_major, ver_minor, flags, size (4 bytes)]
+	//
+	// Only ID3v2.4 tags can have a footer, which is a copy of the header with a different identifier
+	if &footer[..3] != b"3DI" || footer[3] != 4 || footer[5] & 0x10 == 0 {
+		return Ok(ID3FindResults(None, None));
+	}
+
+	let size = BigEndian::read_u32(&footer[6..]).unsynch();
+	let tag_start = end.checked_sub(u64::from(size) + 20);
+	let Some(tag_start) = tag_start.filter(|tag_start| *tag_start >= start_bound) else {
+		log::warn!("Found an ID3v2 footer with an invalid size, ignoring");
+		return Ok(ID3FindResults(None, None));
+	};
+
+	data.seek(SeekFrom::Start(tag_start))?;
+
+	let header = match Id3v2Header::parse(data) {
+		Ok(header)
+			if header.version == Id3v2Version::V4 && header.flags.footer && header.size == size =>
+		{
+			header
+		},
+		_ => {
+			log::warn!("Found an ID3v2 footer without a matching header, ignoring");
+			data.seek(SeekFrom::Start(end))?;
+			return Ok(ID3FindResults(None, None));
+		},
+	};
+
+	let mut id3v2 = None;
+	if read {
+		let mut tag = try_vec![0; (header.size - header.extended_size) as usize];
+		data.read_exact(&mut tag)?;
+
+		id3v2 = Some(tag);
+	}
+
+	data.seek(SeekFrom::Start(tag_start))?;
+	Ok(ID3FindResults(Some(header), id3v2))
+}
@@ -3,10 +3,14 @@ use crate::id3::v1::constants::GENRES;
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
 use crate::tag::{Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::Write;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
@@ -69,6 +73,9 @@ macro_rules! impl_accessor {
 ///
 /// 	[`GENRES`] contains the string **OR** The [`ItemValue`](crate::ItemValue) can be parsed into
 /// 	a `u8` ***and*** it is a valid index into [`GENRES`]
+///
+/// 	Otherwise, the genre will be written as `255` (unknown) and a warning logged, see
+/// 	[`WriteOptions::error_on_lossy_id3v1_genre`](crate::WriteOptions::error_on_lossy_id3v1_genre).
 #[derive(Default, Debug, PartialEq, Eq, Clone)]
 #[tag(
 	description = "An ID3v1 tag",
@@ -243,8 +250,13 @@ impl TagExt for Id3v1Tag {
 			&& self.genre.is_none()
 	}
 
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
-		Into::<Id3v1TagRef<'_>>::into(self).write_to(file)
+	#[cfg(feature = "fs")]
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		Into::<Id3v1TagRef<'_>>::into(self).write_to(file, write_options)
 	}
 
 	/// Dumps the tag to a writer
@@ -252,14 +264,20 @@ impl TagExt for Id3v1Tag {
 	/// # Errors
 	///
 	/// * [`std::io::Error`]
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
-		Into::<Id3v1TagRef<'_>>::into(self).dump_to(writer)
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		Into::<Id3v1TagRef<'_>>::into(self).dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::Id3v1.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::Id3v1.remove_from(file)
 	}
@@ -340,19 +358,24 @@ impl From<Tag> for Id3v1Tag {
 				.get_string(&ItemKey::TrackNumber)
 				.map(|g| g.parse::<u8>().ok())
 				.and_then(|g| g),
-			genre: input
-				.get_string(&ItemKey::Genre)
-				.map(|g| {
-					GENRES
-						.iter()
-						.position(|v| v == &g)
-						.map_or_else(|| g.parse::<u8>().ok(), |p| Some(p as u8))
-				})
-				.and_then(|g| g),
+			genre: input.get_string(&ItemKey::Genre).and_then(genre_index),
 		}
 	}
 }
 
+fn genre_index(genre: &str) -> Option<u8> {
+	GENRES
+		.iter()
+		.position(|v| v == &genre)
+		.map_or_else(|| genre.parse::<u8>().ok(), |p| Some(p as u8))
+}
+
+/// The genre of a [`Tag`], if it can't be represented in ID3v1
+pub(crate) fn lossy_genre(tag: &Tag) -> Option<&str> {
+	tag.get_string(&ItemKey::Genre)
+		.filter(|genre| genre_index(genre).is_none())
+}
+
 pub(crate) struct Id3v1TagRef<'a> {
 	pub title: Option<&'a str>,
 	pub artist: Option<&'a str>,
@@ -361,6 +384,8 @@ pub(crate) struct Id3v1TagRef<'a> {
 	pub comment: Option<&'a str>,
 	pub track_number: Option<u8>,
 	pub genre: Option<u8>,
+	// The genre that couldn't be converted to an index, see `WriteOptions::error_on_lossy_id3v1_genre`
+	pub lossy_genre: Option<&'a str>,
 }
 
 impl<'a> Into<Id3v1TagRef<'a>> for &'a Id3v1Tag {
@@ -373,6 +398,7 @@ impl<'a> Into<Id3v1TagRef<'a>> for &'a Id3v1Tag {
 			comment: self.comment.as_deref(),
 			track_number: self.track_number,
 			genre: self.genre,
+			lossy_genre: None,
 		}
 	}
 }
@@ -389,15 +415,8 @@ impl<'a> Into<Id3v1TagRef<'a>> for &'a Tag {
 				.get_string(&ItemKey::TrackNumber)
 				.map(|g| g.parse::<u8>().ok())
 				.and_then(|g| g),
-			genre: self
-				.get_string(&ItemKey::Genre)
-				.map(|g| {
-					GENRES
-						.iter()
-						.position(|v| v == &g)
-						.map_or_else(|| g.parse::<u8>().ok(), |p| Some(p as u8))
-				})
-				.and_then(|g| g),
+			genre: self.get_string(&ItemKey::Genre).and_then(genre_index),
+			lossy_genre: lossy_genre(self),
 		}
 	}
 }
@@ -413,12 +432,37 @@ impl<'a> Id3v1TagRef<'a> {
 			&& self.genre.is_none()
 	}
 
-	pub(crate) fn write_to(&self, file: &mut File) -> Result<()> {
-		super::write::write_id3v1(file, self)
+	// See `WriteOptions::trim_whitespace` and `WriteOptions::remove_empty_items`. The fields are fixed size,
+	// so an empty field is written the same as a missing one regardless.
+	fn normalize_text(&self, write_options: WriteOptions) -> Self {
+		let normalize =
+			|field: Option<&'a str>| field.and_then(|field| write_options.text_item(field));
+
+		Self {
+			title: normalize(self.title),
+			artist: normalize(self.artist),
+			album: normalize(self.album),
+			year: normalize(self.year),
+			comment: normalize(self.comment),
+			track_number: self.track_number,
+			genre: self.genre,
+			lossy_genre: self.lossy_genre,
+		}
+	}
+
+	pub(crate) fn write_to<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		super::write::write_id3v1(file, &self.normalize_text(write_options), write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let temp = super::write::encode(self)?;
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let temp = super::write::encode(&self.normalize_text(write_options))?;
 		writer.write_all(&temp)?;
 
 		Ok(())
@@ -428,7 +472,7 @@ impl<'a> Id3v1TagRef<'a> {
 #[cfg(test)]
 mod tests {
 	use crate::id3::v1::Id3v1Tag;
-	use crate::{Tag, TagExt, TagType};
+	use crate::{Tag, TagExt, TagType, WriteOptions};
 
 	#[test]
 	fn parse_id3v1() {
@@ -454,13 +498,85 @@ mod tests {
 		let parsed_tag = crate::id3::v1::read::parse_id3v1(tag.try_into().unwrap());
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let temp_parsed_tag = crate::id3::v1::read::parse_id3v1(writer.try_into().unwrap());
 
 		assert_eq!(parsed_tag, temp_parsed_tag);
 	}
 
+	#[test]
+	fn random_unicode_re_read() {
+		// Characters of every UTF-8 length, along with combining marks and joiners
+		const CHARS: &[char] = &[
+			'a',
+			'Z',
+			'7',
+			' ',
+			'\u{00E9}',
+			'\u{00F8}',
+			'\u{0416}',
+			'\u{05D0}',
+			'\u{0301}',
+			'\u{0308}',
+			'\u{3042}',
+			'\u{4E2D}',
+			'\u{200D}',
+			'\u{FE0F}',
+			'\u{1F3B5}',
+			'\u{1F469}',
+			'\u{1F3FD}',
+		];
+
+		// A fixed seed, so any failure can be reproduced
+		let mut state = 0x2545_F491_4F6C_DD1D_u64;
+		let mut next = |bound: usize| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			(state % bound as u64) as usize
+		};
+
+		for _ in 0..1000 {
+			let mut fields = Vec::new();
+			for _ in 0..5 {
+				let len = next(40);
+				fields.push(
+					(0..len)
+						.map(|_| CHARS[next(CHARS.len())])
+						.collect::<String>(),
+				);
+			}
+
+			let tag = Id3v1Tag {
+				title: Some(fields[0].clone()),
+				artist: Some(fields[1].clone()),
+				album: Some(fields[2].clone()),
+				year: Some(fields[3].clone()),
+				comment: Some(fields[4].clone()),
+				track_number: None,
+				genre: None,
+			};
+
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, WriteOptions::default().trim_whitespace(false))
+				.unwrap();
+			assert_eq!(writer.len(), 128);
+
+			for (field, range) in fields.iter().zip([3..33, 33..63, 63..93, 93..97, 97..125]) {
+				let bytes = &writer[range];
+				let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+				let written = std::str::from_utf8(&bytes[..end]).unwrap();
+				assert!(field.starts_with(written), "{field:?} {written:?}");
+			}
+
+			let _ = crate::id3::v1::read::parse_id3v1(writer.try_into().unwrap());
+		}
+	}
+
 	#[test]
 	fn id3v1_to_tag() {
 		let tag_bytes = crate::tag::utils::test_utils::read_path("tests/tags/assets/test.id3v1");
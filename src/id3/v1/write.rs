@@ -1,16 +1,35 @@
 use super::tag::Id3v1TagRef;
-use crate::error::Result;
+use crate::error::{ErrorKind, LoftyError, Result};
 use crate::id3::{find_id3v1, ID3FindResults};
 use crate::macros::err;
 use crate::probe::Probe;
+use crate::util::io::FileLike;
+use crate::util::text::{truncate_to_fit, TextEncoding};
+use crate::write_options::WriteOptions;
 
-use std::fs::File;
 use std::io::{Cursor, Seek, Write};
 
 use byteorder::WriteBytesExt;
 
 #[allow(clippy::shadow_unrelated)]
-pub(crate) fn write_id3v1(file: &mut File, tag: &Id3v1TagRef<'_>) -> Result<()> {
+pub(crate) fn write_id3v1<F>(
+	file: &mut F,
+	tag: &Id3v1TagRef<'_>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+{
+	if let Some(genre) = tag.lossy_genre {
+		if write_options.error_on_lossy_id3v1_genre {
+			return Err(LoftyError::new(ErrorKind::LossyId3v1Genre(
+				genre.to_string(),
+			)));
+		}
+
+		log::warn!("ID3v1: The genre \"{genre}\" has no ID3v1 equivalent, writing it as unknown");
+	}
+
 	let probe = Probe::new(file).guess_file_type()?;
 
 	match probe.file_type() {
@@ -23,10 +42,13 @@ pub(crate) fn write_id3v1(file: &mut File, tag: &Id3v1TagRef<'_>) -> Result<()>
 	// This will seek us to the writing position
 	let ID3FindResults(header, _) = find_id3v1(file, false)?;
 
-	if tag.is_empty() && header.is_some() {
+	if tag.is_empty() && write_options.remove_tags_if_empty {
 		// An ID3v1 tag occupies the last 128 bytes of the file, so we can just
 		// shrink it down.
-		file.set_len(file.metadata()?.len().saturating_sub(128))?;
+		if header.is_some() {
+			let file_len = file.len()?;
+			file.set_len(file_len.saturating_sub(128))?;
+		}
 
 		return Ok(());
 	}
@@ -44,11 +66,7 @@ pub(super) fn encode(tag: &Id3v1TagRef<'_>) -> std::io::Result<Vec<u8>> {
 		cursor.rewind()?;
 
 		if let Some(val) = value {
-			if val.len() > size {
-				cursor.write_all(val.split_at(size).0.as_bytes())?;
-			} else {
-				cursor.write_all(val.as_bytes())?;
-			}
+			cursor.write_all(truncate_to_fit(val, size, TextEncoding::UTF8).as_bytes())?;
 		}
 
 		Ok(cursor.into_inner())
@@ -1,24 +1,28 @@
 use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
-use crate::id3::v2::frame::FrameValue;
+use crate::id3::v2::frame::{FrameValue, PODCAST_URL_FRAME_ID};
 use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::items::play_counter::parse_counter;
 use crate::id3::v2::items::{
-	AttachedPictureFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame, ExtendedUrlFrame,
-	KeyValueFrame, OwnershipFrame, Popularimeter, PrivateFrame, RelativeVolumeAdjustmentFrame,
+	AttachedPictureFrame, ChapterFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame,
+	ExtendedUrlFrame, GeneralEncapsulatedObject, KeyValueFrame, OwnershipFrame, Popularimeter,
+	PrivateFrame, RelativeVolumeAdjustmentFrame, SeekFrame, SynchronizedText, TableOfContentsFrame,
 	TextInformationFrame, UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
 };
 use crate::macros::err;
 use crate::probe::ParsingMode;
 use crate::util::text::TextEncoding;
 
-use std::io::Read;
-
+// `content` is the parser's scratch buffer, holding the entire frame content. Everything that doesn't
+// need to hold onto the raw bytes should be parsed from it in place.
 #[rustfmt::skip]
-pub(super) fn parse_content<R: Read>(
-    reader: &mut R,
+pub(in crate::id3::v2) fn parse_content(
+    content: &[u8],
     id: &str,
     version: Id3v2Version,
 	parse_mode: ParsingMode,
 ) -> Result<Option<FrameValue>> {
+	let reader = &mut &*content;
+
 	Ok(match id {
 		// The ID was previously upgraded, but the content remains unchanged, so version is necessary
 		"APIC" => {
@@ -29,25 +33,42 @@ pub(super) fn parse_content<R: Read>(
 		"WXXX" => ExtendedUrlFrame::parse(reader, version)?.map(FrameValue::UserUrl),
 		"COMM" => CommentFrame::parse(reader, version)?.map(FrameValue::Comment),
 		"USLT" => UnsynchronizedTextFrame::parse(reader, version)?.map(FrameValue::UnsynchronizedText),
-		"TIPL" | "TMCL" => KeyValueFrame::parse(reader, version)?.map(FrameValue::KeyValue),
+		"TIPL" | "TMCL" => KeyValueFrame::parse(reader, version, parse_mode)?.map(FrameValue::KeyValue),
 		"UFID" => UniqueFileIdentifierFrame::parse(reader, parse_mode)?.map(FrameValue::UniqueFileIdentifier),
 		"RVA2" => RelativeVolumeAdjustmentFrame::parse(reader, parse_mode)?.map(FrameValue::RelativeVolumeAdjustment),
 		"OWNE" => OwnershipFrame::parse(reader)?.map(FrameValue::Ownership),
 		"ETCO" => EventTimingCodesFrame::parse(reader)?.map(FrameValue::EventTimingCodes),
 		"PRIV" => PrivateFrame::parse(reader)?.map(FrameValue::Private),
-		_ if id.starts_with('T') => TextInformationFrame::parse(reader, version)?.map(FrameValue::Text),
+		"SEEK" => SeekFrame::parse(reader)?.map(FrameValue::Seek),
+		"CHAP" => ChapterFrame::parse_slice(content, version, parse_mode)?.map(FrameValue::Chapter),
+		"CTOC" => TableOfContentsFrame::parse_slice(content, version, parse_mode)?.map(FrameValue::TableOfContents),
+		"SYLT" => match SynchronizedText::parse(content) {
+			Ok(sylt) => Some(FrameValue::SynchronizedText(sylt)),
+			// Keep the malformed frame around as-is, rather than discarding it
+			Err(_) if parse_mode != ParsingMode::Strict => Some(FrameValue::Binary(content.to_vec())),
+			Err(err) => return Err(err),
+		},
+		"GEOB" => match GeneralEncapsulatedObject::parse(content) {
+			Ok(geob) => Some(FrameValue::GeneralEncapsulatedObject(geob)),
+			Err(_) if parse_mode != ParsingMode::Strict => Some(FrameValue::Binary(content.to_vec())),
+			Err(err) => return Err(err),
+		},
+		_ if id.starts_with('T') => TextInformationFrame::parse_slice(content, version)?.map(FrameValue::Text),
 		// Apple proprietary frames
+		// Some software writes WFED (Podcast URL) as a URL frame, without an encoding. It's stored as
+		// a text frame regardless, so it's written back the way iTunes expects.
+		PODCAST_URL_FRAME_ID if content.first().is_some_and(|b| TextEncoding::from_u8(*b).is_none()) => {
+			UrlLinkFrame::parse(reader)?.map(|UrlLinkFrame(url)| {
+				FrameValue::Text(TextInformationFrame { encoding: TextEncoding::Latin1, value: url })
+			})
+		},
 		// WFED (Podcast URL), GRP1 (Grouping), MVNM (Movement Name), MVIN (Movement Number)
-		"WFED" | "GRP1" | "MVNM" | "MVIN" => TextInformationFrame::parse(reader, version)?.map(FrameValue::Text),
+		"WFED" | "GRP1" | "MVNM" | "MVIN" => TextInformationFrame::parse_slice(content, version)?.map(FrameValue::Text),
 		_ if id.starts_with('W') => UrlLinkFrame::parse(reader)?.map(FrameValue::Url),
 		"POPM" => Some(FrameValue::Popularimeter(Popularimeter::parse(reader)?)),
-		// SYLT, GEOB, and any unknown frames
-		_ => {
-			let mut content = Vec::new();
-			reader.read_to_end(&mut content)?;
-
-			Some(FrameValue::Binary(content))
-		},
+		"PCNT" => Some(FrameValue::PlayCounter(parse_counter(reader)?)),
+		// Any unknown frames
+		_ => Some(FrameValue::Binary(content.to_vec())),
 	})
 }
 
@@ -114,6 +114,11 @@ impl<'a> TryFrom<&'a ItemKey> for FrameId<'a> {
 				Self::verify_id(unknown)?;
 				Ok(Self::Valid(Cow::Borrowed(unknown)))
 			},
+			// Custom keys are always stored in `TXXX`/`WXXX` frames, as is `WORK`, which only looks
+			// like a frame ID
+			k @ (ItemKey::Custom(_) | ItemKey::Work) => {
+				Err(Id3v2Error::new(Id3v2ErrorKind::UnsupportedFrameId(k.clone())).into())
+			},
 			k => {
 				if let Some(mapped) = k.map_key(TagType::Id3v2, false) {
 					if mapped.len() == 4 {
@@ -4,16 +4,21 @@ pub(super) mod id;
 pub(super) mod read;
 
 use super::header::Id3v2Version;
+use super::items::play_counter::counter_bytes;
 use super::items::{
-	AttachedPictureFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame, ExtendedUrlFrame,
-	KeyValueFrame, OwnershipFrame, Popularimeter, PrivateFrame, RelativeVolumeAdjustmentFrame,
+	AttachedPictureFrame, ChapterFrame, CommentFrame, EventTimingCodesFrame, ExtendedTextFrame,
+	ExtendedUrlFrame, GeneralEncapsulatedObject, KeyValueFrame, OwnershipFrame, Popularimeter,
+	PrivateFrame, RelativeVolumeAdjustmentFrame, SeekFrame, SynchronizedText, TableOfContentsFrame,
 	TextInformationFrame, UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
 };
 use super::util::upgrade::{upgrade_v2, upgrade_v3};
+use super::write::frame_size;
 use crate::error::{ErrorKind, Id3v2Error, Id3v2ErrorKind, LoftyError, Result};
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::rating::text_popularimeter;
 use crate::tag::TagType;
-use crate::util::text::TextEncoding;
+use crate::util::text::{parse_flag, TextEncoding};
+use crate::write_options::WriteOptions;
 use id::FrameId;
 
 use std::borrow::Cow;
@@ -22,6 +27,15 @@ use std::hash::{Hash, Hasher};
 
 pub(super) const MUSICBRAINZ_UFID_OWNER: &str = "http://musicbrainz.org";
 
+// Apple's podcast frames don't follow the usual conventions for their IDs. `PCST` holds no
+// data, its presence is the flag, and `WFED` is a text frame despite its URL frame ID.
+pub(super) const PODCAST_FLAG_FRAME_ID: &str = "PCST";
+pub(super) const PODCAST_URL_FRAME_ID: &str = "WFED";
+// iTunes writes `PCST` as 4 null bytes
+const PODCAST_FLAG_CONTENT: [u8; 4] = [0; 4];
+// iTunes' compilation flag, a text frame holding "1" or "0"
+const COMPILATION_FLAG_FRAME_ID: &str = "TCMP";
+
 /// Empty content descriptor in text frame
 ///
 /// Unspecific [`CommentFrame`]s, [`UnsynchronizedTextFrame`]s, and [`ExtendedTextFrame`] frames
@@ -40,6 +54,11 @@ pub(super) const EMPTY_CONTENT_DESCRIPTOR: String = String::new();
 /// > the string “XXX” should be used.
 pub(super) const UNKNOWN_LANGUAGE: [u8; 3] = *b"XXX";
 
+/// Undetermined language, as defined by ISO-639-2
+///
+/// Used for lyrics when the caller doesn't specify a language, see [`Id3v2Tag::set_lyrics`](crate::id3::v2::Id3v2Tag::set_lyrics).
+pub(super) const UNDETERMINED_LANGUAGE: [u8; 3] = *b"und";
+
 // TODO: Messy module, rough conversions
 
 /// Represents an `ID3v2` frame
@@ -146,6 +165,41 @@ impl<'a> Frame<'a> {
 		self.flags = flags
 	}
 
+	/// The size of the frame when written, including its header
+	///
+	/// Frames with an outdated ID (see [`FrameId::Outdated`]) will not be written, and have a size of 0.
+	///
+	/// # Errors
+	///
+	/// * The frame is invalid, or too large, see [`WriteOptions::split_oversized_id3v2_frames`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::{Frame, FrameFlags, TextInformationFrame};
+	/// use lofty::{TextEncoding, WriteOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let frame = Frame::new(
+	/// 	"TIT2",
+	/// 	TextInformationFrame {
+	/// 		encoding: TextEncoding::Latin1,
+	/// 		value: String::from("Foo title"),
+	/// 	},
+	/// 	FrameFlags::default(),
+	/// )?;
+	///
+	/// // Frame header (10) + encoding (1) + "Foo title" (9)
+	/// assert_eq!(frame.encoded_size(WriteOptions::default())?, 20);
+	/// # Ok(()) }
+	/// ```
+	pub fn encoded_size(&self, write_options: WriteOptions) -> Result<usize> {
+		match self.as_opt_ref() {
+			Some(frame) => frame_size(frame, write_options),
+			None => Ok(0),
+		}
+	}
+
 	// Used internally, has no correctness checks
 	pub(crate) fn text(id: Cow<'a, str>, content: String) -> Self {
 		Self {
@@ -179,6 +233,8 @@ pub enum FrameValue {
 	Picture(AttachedPictureFrame),
 	/// Represents a "POPM" frame
 	Popularimeter(Popularimeter),
+	/// Represents a "PCNT" frame, the number of times the file has been played
+	PlayCounter(u64),
 	/// Represents an "IPLS" or "TPIL" frame
 	KeyValue(KeyValueFrame),
 	/// Represents an "RVA2" frame
@@ -191,12 +247,23 @@ pub enum FrameValue {
 	EventTimingCodes(EventTimingCodesFrame),
 	/// Represents a "PRIV" frame
 	Private(PrivateFrame),
+	/// Represents a "CHAP" frame
+	Chapter(ChapterFrame),
+	/// Represents a "CTOC" frame
+	TableOfContents(TableOfContentsFrame),
+	/// Represents a "SYLT" frame
+	SynchronizedText(SynchronizedText),
+	/// Represents a "GEOB" frame
+	GeneralEncapsulatedObject(GeneralEncapsulatedObject),
+	/// Represents a "SEEK" frame
+	Seek(SeekFrame),
 	/// Binary data
 	///
 	/// NOTES:
 	///
-	/// * This is used for rare frames, such as GEOB, SYLT, and ATXT to skip additional unnecessary work.
-	///   See [`GeneralEncapsulatedObject::parse`](crate::id3::v2::GeneralEncapsulatedObject::parse), [`SynchronizedText::parse`](crate::id3::v2::SynchronizedText::parse), and [`AudioTextFrame::parse`](crate::id3::v2::AudioTextFrame::parse) respectively
+	/// * This is used for rare frames, such as ATXT to skip additional unnecessary work.
+	///   See [`AudioTextFrame::parse`](crate::id3::v2::AudioTextFrame::parse)
+	/// * This is used for SYLT and GEOB frames that couldn't be parsed, unless using [`ParsingMode::Strict`](crate::ParsingMode::Strict)
 	/// * This is used for **all** frames with an ID of [`FrameId::Outdated`]
 	/// * This is used for unknown frames
 	Binary(Vec<u8>),
@@ -309,8 +376,38 @@ impl From<PrivateFrame> for FrameValue {
 	}
 }
 
+impl From<ChapterFrame> for FrameValue {
+	fn from(value: ChapterFrame) -> Self {
+		Self::Chapter(value)
+	}
+}
+
+impl From<TableOfContentsFrame> for FrameValue {
+	fn from(value: TableOfContentsFrame) -> Self {
+		Self::TableOfContents(value)
+	}
+}
+
+impl From<SynchronizedText> for FrameValue {
+	fn from(value: SynchronizedText) -> Self {
+		Self::SynchronizedText(value)
+	}
+}
+
+impl From<GeneralEncapsulatedObject> for FrameValue {
+	fn from(value: GeneralEncapsulatedObject) -> Self {
+		Self::GeneralEncapsulatedObject(value)
+	}
+}
+
+impl From<SeekFrame> for FrameValue {
+	fn from(value: SeekFrame) -> Self {
+		Self::Seek(value)
+	}
+}
+
 impl FrameValue {
-	pub(super) fn as_bytes(&self) -> Result<Vec<u8>> {
+	pub(super) fn as_bytes(&self, write_options: WriteOptions) -> Result<Vec<u8>> {
 		Ok(match self {
 			FrameValue::Comment(comment) => comment.as_bytes()?,
 			FrameValue::UnsynchronizedText(lf) => lf.as_bytes()?,
@@ -320,16 +417,36 @@ impl FrameValue {
 			FrameValue::Url(link) => link.as_bytes(),
 			FrameValue::Picture(attached_picture) => attached_picture.as_bytes(Id3v2Version::V4)?,
 			FrameValue::Popularimeter(popularimeter) => popularimeter.as_bytes(),
+			FrameValue::PlayCounter(counter) => counter_bytes(*counter),
 			FrameValue::KeyValue(content) => content.as_bytes(),
 			FrameValue::RelativeVolumeAdjustment(frame) => frame.as_bytes(),
 			FrameValue::UniqueFileIdentifier(frame) => frame.as_bytes(),
 			FrameValue::Ownership(frame) => frame.as_bytes()?,
 			FrameValue::EventTimingCodes(frame) => frame.as_bytes(),
 			FrameValue::Private(frame) => frame.as_bytes(),
+			FrameValue::Chapter(frame) => frame.as_bytes(write_options)?,
+			FrameValue::TableOfContents(frame) => frame.as_bytes(write_options)?,
+			FrameValue::SynchronizedText(frame) => frame.as_bytes()?,
+			FrameValue::GeneralEncapsulatedObject(frame) => frame.as_bytes(),
+			FrameValue::Seek(frame) => frame.as_bytes(),
 			FrameValue::Binary(binary) => binary.clone(),
 		})
 	}
 
+	/// A lower bound for the length of [`Self::as_bytes`]
+	///
+	/// This only accounts for raw binary payloads, so oversized frames can be rejected before
+	/// they're copied.
+	pub(super) fn min_size(&self) -> usize {
+		match self {
+			FrameValue::Picture(attached_picture) => attached_picture.picture.data().len(),
+			FrameValue::Private(frame) => frame.private_data.len(),
+			FrameValue::GeneralEncapsulatedObject(frame) => frame.data.len(),
+			FrameValue::Binary(binary) => binary.len(),
+			_ => 0,
+		}
+	}
+
 	/// Used for errors in write::frame::verify_frame
 	pub(super) fn name(&self) -> &'static str {
 		match self {
@@ -341,12 +458,18 @@ impl FrameValue {
 			FrameValue::UserUrl(_) => "UserUrl",
 			FrameValue::Picture { .. } => "Picture",
 			FrameValue::Popularimeter(_) => "Popularimeter",
+			FrameValue::PlayCounter(_) => "PlayCounter",
 			FrameValue::KeyValue(_) => "KeyValue",
 			FrameValue::UniqueFileIdentifier(_) => "UniqueFileIdentifier",
 			FrameValue::RelativeVolumeAdjustment(_) => "RelativeVolumeAdjustment",
 			FrameValue::Ownership(_) => "Ownership",
 			FrameValue::EventTimingCodes(_) => "EventTimingCodes",
 			FrameValue::Private(_) => "Private",
+			FrameValue::Chapter(_) => "Chapter",
+			FrameValue::TableOfContents(_) => "TableOfContents",
+			FrameValue::SynchronizedText(_) => "SynchronizedText",
+			FrameValue::GeneralEncapsulatedObject(_) => "GeneralEncapsulatedObject",
+			FrameValue::Seek(_) => "Seek",
 			FrameValue::Binary(_) => "Binary",
 		}
 	}
@@ -369,10 +492,15 @@ pub struct FrameFlags {
 	/// Frame is zlib compressed
 	///
 	/// It is **required** `data_length_indicator` be set if this is set.
+	///
+	/// NOTE: Compressed frames are decompressed when reading (with the `id3v2_compression_support` feature),
+	/// and this flag is cleared. Frames that fail to decompress are skipped, unless using
+	/// [`ParsingMode::Strict`](crate::ParsingMode::Strict).
 	pub compression: bool,
 	/// Frame encryption method symbol
 	///
-	/// NOTE: Since the encryption method is unknown, lofty cannot do anything with these frames
+	/// NOTE: Since the encryption method is unknown, lofty cannot do anything with these frames on its own.
+	///       They can be decrypted with [`Id3v2Tag::decrypt_frames`](crate::id3::v2::Id3v2Tag::decrypt_frames).
 	///
 	/// The encryption method symbol **must** be > 0x80.
 	pub encryption: Option<u8>,
@@ -382,9 +510,10 @@ pub struct FrameFlags {
 	/// with the MPEG frame header, which is often identified by its "frame sync" (11 set bits).
 	/// It is preferred an ID3v2 tag is either *completely* unsynchronised or not unsynchronised at all.
 	///
-	/// NOTE: While unsynchronized data is read, for the sake of simplicity, this flag has no effect when
-	/// writing. There isn't much reason to write unsynchronized data.
-	pub unsynchronisation: bool, /* TODO: Maybe? This doesn't seem very useful, and it is wasted effort if one forgets to make this false when writing. */
+	/// NOTE: When writing ID3v2.4, the frame content will be unsynchronised. This has no effect when
+	/// writing ID3v2.3, which can only unsynchronise the entire tag, see
+	/// [`Id3v2TagFlags::unsynchronisation`](crate::id3::v2::Id3v2TagFlags::unsynchronisation).
+	pub unsynchronisation: bool,
 	/// Frame has a data length indicator
 	///
 	/// The data length indicator is the size of the frame if the flags were all zeroed out.
@@ -394,11 +523,24 @@ pub struct FrameFlags {
 	pub data_length_indicator: Option<u32>,
 }
 
+// The frame ID for an item, if it maps to one
+//
+// URLs can only be stored in URL ("W...") frames. Any other key, such as an `ItemKey::Unknown`
+// holding a `WXXX` description that happens to look like a frame ID, is stored in a `WXXX` frame.
+fn item_frame_id(item: &TagItem) -> Result<FrameId<'_>> {
+	let id: FrameId<'_> = item.key().try_into()?;
+	if matches!(item.value(), ItemValue::Locator(_)) && !id.as_str().starts_with('W') {
+		return Err(Id3v2Error::new(Id3v2ErrorKind::UnsupportedFrameId(item.key().clone())).into());
+	}
+
+	Ok(id)
+}
+
 impl From<TagItem> for Option<Frame<'static>> {
 	fn from(input: TagItem) -> Self {
 		let frame_id;
 		let value;
-		match input.key().try_into().map(FrameId::into_owned) {
+		match item_frame_id(&input).map(FrameId::into_owned) {
 			Ok(id) => {
 				value = match (&id, input.item_value) {
 					(FrameId::Valid(ref s), ItemValue::Text(text)) if s == "COMM" => {
@@ -412,7 +554,7 @@ impl From<TagItem> for Option<Frame<'static>> {
 					(FrameId::Valid(ref s), ItemValue::Text(text)) if s == "USLT" => {
 						FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
 							encoding: TextEncoding::UTF8,
-							language: UNKNOWN_LANGUAGE,
+							language: UNDETERMINED_LANGUAGE,
 							description: EMPTY_CONTENT_DESCRIPTOR,
 							content: text,
 						})
@@ -436,6 +578,35 @@ impl From<TagItem> for Option<Frame<'static>> {
 					(FrameId::Valid(ref s), ItemValue::Binary(text)) if s == "POPM" => {
 						FrameValue::Popularimeter(Popularimeter::parse(&mut &text[..]).ok()?)
 					},
+					(FrameId::Valid(ref s), ItemValue::Text(text)) if s == "POPM" => {
+						FrameValue::Popularimeter(text_popularimeter(&text)?)
+					},
+					(FrameId::Valid(ref s), ItemValue::Text(text)) if s == "PCNT" => {
+						FrameValue::PlayCounter(text.trim().parse().ok()?)
+					},
+					(FrameId::Valid(ref s), ItemValue::Text(flag))
+						if s == PODCAST_FLAG_FRAME_ID =>
+					{
+						if parse_flag(&flag) != Some(true) {
+							return None;
+						}
+
+						FrameValue::Binary(PODCAST_FLAG_CONTENT.to_vec())
+					},
+					(FrameId::Valid(ref s), ItemValue::Text(flag))
+						if s == COMPILATION_FLAG_FRAME_ID =>
+					{
+						compilation_flag_frame(&flag)?
+					},
+					(FrameId::Valid(ref s), ItemValue::Locator(url) | ItemValue::Text(url))
+						if s == PODCAST_URL_FRAME_ID =>
+					{
+						podcast_url_frame(url)
+					},
+					// A URL set as text, such as with `Tag::insert_text`
+					(FrameId::Valid(ref s), ItemValue::Text(url)) if s.starts_with('W') => {
+						FrameValue::try_from(ItemValue::Locator(url)).ok()?
+					},
 					(_, item_value) => {
 						let Ok(value) = item_value.try_into() else {
 							return None;
@@ -469,15 +640,8 @@ impl From<TagItem> for Option<Frame<'static>> {
 				},
 				None => match (input.item_key, input.item_value) {
 					(ItemKey::MusicBrainzRecordingId, ItemValue::Text(recording_id)) => {
-						if !recording_id.is_ascii() {
-							return None;
-						}
-						let frame = UniqueFileIdentifierFrame {
-							owner: MUSICBRAINZ_UFID_OWNER.to_owned(),
-							identifier: recording_id.into_bytes(),
-						};
 						frame_id = FrameId::Valid(Cow::Borrowed("UFID"));
-						value = FrameValue::UniqueFileIdentifier(frame);
+						value = musicbrainz_ufid_frame(&recording_id)?;
 					},
 					_ => {
 						return None;
@@ -494,6 +658,43 @@ impl From<TagItem> for Option<Frame<'static>> {
 	}
 }
 
+// iTunes writes `WFED` as a Latin-1 text frame, falling back to UTF-8 for anything that doesn't fit
+fn podcast_url_frame(url: String) -> FrameValue {
+	let encoding = if TextEncoding::verify_latin1(&url) {
+		TextEncoding::Latin1
+	} else {
+		TextEncoding::UTF8
+	};
+
+	FrameValue::Text(TextInformationFrame {
+		encoding,
+		value: url,
+	})
+}
+
+// MusicBrainz Picard stores the recording ID as UTF-8 in a `UFID` frame, which can hold up to 64 bytes
+fn musicbrainz_ufid_frame(recording_id: &str) -> Option<FrameValue> {
+	if recording_id.is_empty() || recording_id.len() > 64 {
+		return None;
+	}
+
+	Some(FrameValue::UniqueFileIdentifier(
+		UniqueFileIdentifierFrame {
+			owner: MUSICBRAINZ_UFID_OWNER.to_owned(),
+			identifier: recording_id.as_bytes().to_vec(),
+		},
+	))
+}
+
+// `TCMP` is normalized to "1"/"0", since that is all iTunes understands
+fn compilation_flag_frame(flag: &str) -> Option<FrameValue> {
+	let flag = parse_flag(flag)?;
+	Some(FrameValue::Text(TextInformationFrame {
+		encoding: TextEncoding::Latin1,
+		value: String::from(if flag { "1" } else { "0" }),
+	}))
+}
+
 #[derive(Clone)]
 pub(crate) struct FrameRef<'a> {
 	pub id: FrameId<'a>,
@@ -515,11 +716,31 @@ impl<'a> Frame<'a> {
 	}
 }
 
+impl<'a> From<Frame<'a>> for FrameRef<'a> {
+	fn from(frame: Frame<'a>) -> Self {
+		FrameRef {
+			id: frame.id,
+			value: Cow::Owned(frame.value),
+			flags: frame.flags,
+		}
+	}
+}
+
+impl FrameRef<'_> {
+	pub(crate) fn into_owned(self) -> Frame<'static> {
+		Frame {
+			id: self.id.into_owned(),
+			value: self.value.into_owned(),
+			flags: self.flags,
+		}
+	}
+}
+
 impl<'a> TryFrom<&'a TagItem> for FrameRef<'a> {
 	type Error = LoftyError;
 
 	fn try_from(tag_item: &'a TagItem) -> std::result::Result<Self, Self::Error> {
-		let id: Result<FrameId<'a>> = tag_item.key().try_into();
+		let id = item_frame_id(tag_item);
 		let frame_id: FrameId<'a>;
 		let value: FrameValue;
 		match id {
@@ -536,7 +757,7 @@ impl<'a> TryFrom<&'a TagItem> for FrameRef<'a> {
 					("USLT", ItemValue::Text(text)) => {
 						FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
 							encoding: TextEncoding::UTF8,
-							language: UNKNOWN_LANGUAGE,
+							language: UNDETERMINED_LANGUAGE,
 							description: EMPTY_CONTENT_DESCRIPTOR,
 							content: text.clone(),
 						})
@@ -570,6 +791,55 @@ impl<'a> TryFrom<&'a TagItem> for FrameRef<'a> {
 					("POPM", ItemValue::Binary(contents)) => {
 						FrameValue::Popularimeter(Popularimeter::parse(&mut &contents[..])?)
 					},
+					("POPM", ItemValue::Text(text)) => match text_popularimeter(text) {
+						Some(popularimeter) => FrameValue::Popularimeter(popularimeter),
+						None => {
+							return Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
+								String::from("POPM"),
+								"Popularimeter",
+							))
+							.into())
+						},
+					},
+					("PCNT", ItemValue::Text(text)) => match text.trim().parse() {
+						Ok(counter) => FrameValue::PlayCounter(counter),
+						Err(_) => {
+							return Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
+								String::from("PCNT"),
+								"PlayCounter",
+							))
+							.into())
+						},
+					},
+					(PODCAST_FLAG_FRAME_ID, ItemValue::Text(flag)) => {
+						if parse_flag(flag) != Some(true) {
+							return Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
+								String::from(PODCAST_FLAG_FRAME_ID),
+								"Text",
+							))
+							.into());
+						}
+
+						FrameValue::Binary(PODCAST_FLAG_CONTENT.to_vec())
+					},
+					(COMPILATION_FLAG_FRAME_ID, ItemValue::Text(flag)) => {
+						let Some(value) = compilation_flag_frame(flag) else {
+							return Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
+								String::from(COMPILATION_FLAG_FRAME_ID),
+								"Text",
+							))
+							.into());
+						};
+
+						value
+					},
+					(PODCAST_URL_FRAME_ID, ItemValue::Locator(url) | ItemValue::Text(url)) => {
+						podcast_url_frame(url.clone())
+					},
+					// A URL set as text, such as with `Tag::insert_text`
+					(url_id, ItemValue::Text(url)) if url_id.starts_with('W') => {
+						ItemValue::Locator(url.clone()).try_into()?
+					},
 					(_, value) => value.try_into()?,
 				};
 
@@ -577,6 +847,24 @@ impl<'a> TryFrom<&'a TagItem> for FrameRef<'a> {
 			},
 			Err(_) => {
 				let item_key = tag_item.key();
+				if let (ItemKey::MusicBrainzRecordingId, ItemValue::Text(recording_id)) =
+					(item_key, tag_item.value())
+				{
+					let Some(value) = musicbrainz_ufid_frame(recording_id) else {
+						return Err(Id3v2Error::new(Id3v2ErrorKind::BadFrame(
+							String::from("UFID"),
+							"UniqueFileIdentifier",
+						))
+						.into());
+					};
+
+					return Ok(FrameRef {
+						id: FrameId::Valid(Cow::Borrowed("UFID")),
+						value: Cow::Owned(value),
+						flags: FrameFlags::default(),
+					});
+				}
+
 				let Some(desc) = item_key.map_key(TagType::Id3v2, true) else {
 					return Err(Id3v2Error::new(Id3v2ErrorKind::UnsupportedFrameId(
 						item_key.clone(),
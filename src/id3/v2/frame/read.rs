@@ -1,6 +1,6 @@
 use super::header::{parse_header, parse_v2_header};
 use super::Frame;
-use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
+use crate::error::{ErrorKind, Id3v2Error, Id3v2ErrorKind, LoftyError, Result};
 use crate::id3::v2::frame::content::parse_content;
 use crate::id3::v2::header::Id3v2Version;
 use crate::id3::v2::util::synchsafe::{SynchsafeInteger, UnsynchronizedStream};
@@ -19,9 +19,18 @@ pub(crate) enum ParsedFrame<'a> {
 }
 
 impl<'a> ParsedFrame<'a> {
+	/// Read the next frame
+	///
+	/// `content` is a scratch buffer that is reused between frames, to avoid allocating a
+	/// new buffer for the content of every frame.
+	///
+	/// `tag_unsynchronisation` is the unsynchronisation flag of the tag header, which in ID3v2.4
+	/// applies to every frame, regardless of their own flags.
 	pub(crate) fn read<R>(
 		reader: &mut R,
+		content: &mut Vec<u8>,
 		version: Id3v2Version,
+		tag_unsynchronisation: bool,
 		parse_mode: ParsingMode,
 	) -> Result<Self>
 	where
@@ -29,13 +38,7 @@ impl<'a> ParsedFrame<'a> {
 	{
 		let mut size = 0u32;
 
-		// The header will be upgraded to ID3v2.4 past this point, so they can all be treated the same
-		let parse_header_result = match version {
-			Id3v2Version::V2 => parse_v2_header(reader, &mut size),
-			Id3v2Version::V3 => parse_header(reader, &mut size, false),
-			Id3v2Version::V4 => parse_header(reader, &mut size, true),
-		};
-		let (id, mut flags) = match parse_header_result {
+		let (id, mut flags) = match read_header(reader, &mut size, version) {
 			Ok(None) => {
 				// Stop reading
 				return Ok(Self::Eof);
@@ -53,6 +56,10 @@ impl<'a> ParsedFrame<'a> {
 			},
 		};
 
+		if version == Id3v2Version::V4 && tag_unsynchronisation {
+			flags.unsynchronisation = true;
+		}
+
 		if size == 0 {
 			if parse_mode == ParsingMode::Strict {
 				return Err(Id3v2Error::new(Id3v2ErrorKind::EmptyFrame(id)).into());
@@ -108,6 +115,9 @@ impl<'a> ParsedFrame<'a> {
 		// unsynchronization -> compression -> encryption
 		//
 		// Which all have their own needs, so this gets a little messy...
+		//
+		// Encrypted frames are kept as-is, as they would have to be decrypted before they can
+		// be decompressed.
 		match flags {
 			// Possible combinations:
 			//
@@ -121,15 +131,14 @@ impl<'a> ParsedFrame<'a> {
 			} => {
 				let mut unsynchronized_reader = UnsynchronizedStream::new(reader);
 
-				if flags.compression {
-					let mut compression_reader = handle_compression(unsynchronized_reader)?;
-
-					if flags.encryption.is_some() {
-						return handle_encryption(&mut compression_reader, size, id, flags);
-					}
+				if flags.encryption.is_some() {
+					return handle_encryption(&mut unsynchronized_reader, size, id, flags);
+				}
 
-					return parse_frame(
-						&mut compression_reader,
+				if flags.compression {
+					return parse_compressed_frame(
+						&mut unsynchronized_reader,
+						content,
 						size,
 						id,
 						flags,
@@ -138,12 +147,9 @@ impl<'a> ParsedFrame<'a> {
 					);
 				}
 
-				if flags.encryption.is_some() {
-					return handle_encryption(&mut unsynchronized_reader, size, id, flags);
-				}
-
 				return parse_frame(
 					&mut unsynchronized_reader,
+					content,
 					size,
 					id,
 					flags,
@@ -154,18 +160,22 @@ impl<'a> ParsedFrame<'a> {
 			// Possible combinations:
 			//
 			// * compressed + encrypted
+			// * encrypted
+			FrameFlags {
+				encryption: Some(_),
+				..
+			} => {
+				return handle_encryption(&mut reader, size, id, flags);
+			},
+			// Possible combinations:
+			//
 			// * compressed
 			FrameFlags {
 				compression: true, ..
 			} => {
-				let mut compression_reader = handle_compression(reader)?;
-
-				if flags.encryption.is_some() {
-					return handle_encryption(&mut compression_reader, size, id, flags);
-				}
-
-				return parse_frame(
-					&mut compression_reader,
+				return parse_compressed_frame(
+					&mut reader,
+					content,
 					size,
 					id,
 					flags,
@@ -173,32 +183,151 @@ impl<'a> ParsedFrame<'a> {
 					parse_mode,
 				);
 			},
-			// Possible combinations:
-			//
-			// * encrypted
-			FrameFlags {
-				encryption: Some(_),
-				..
-			} => {
-				return handle_encryption(&mut reader, size, id, flags);
-			},
 			// Everything else that doesn't have special flags
 			_ => {
-				return parse_frame(&mut reader, size, id, flags, version, parse_mode);
+				return parse_frame(&mut reader, content, size, id, flags, version, parse_mode);
 			},
 		}
 	}
 }
 
+// The header will be upgraded to ID3v2.4 past this point, so they can all be treated the same
+fn read_header<R>(
+	reader: &mut R,
+	size: &mut u32,
+	version: Id3v2Version,
+) -> Result<Option<(FrameId<'static>, FrameFlags)>>
+where
+	R: Read,
+{
+	match version {
+		Id3v2Version::V2 => parse_v2_header(reader, size),
+		Id3v2Version::V3 => parse_header(reader, size, false),
+		Id3v2Version::V4 => parse_header(reader, size, true),
+	}
+}
+
+// Frames embedded in another frame, such as a `CHAP` frame, are laid out the same as the frames
+// of the tag itself
+pub(in crate::id3::v2) fn parse_embedded_frames(
+	mut content: &[u8],
+	version: Id3v2Version,
+	parse_mode: ParsingMode,
+) -> Result<Vec<Frame<'static>>> {
+	let mut frames = Vec::new();
+
+	let mut frame_content = Vec::new();
+	loop {
+		// Chapter frames can't hold other chapter frames, otherwise they could nest without bound
+		let mut header_reader = content;
+		let mut size = 0;
+		if let Ok(Some((id, _))) = read_header(&mut header_reader, &mut size, version) {
+			if matches!(id.as_str(), "CHAP" | "CTOC") {
+				if parse_mode == ParsingMode::Strict {
+					return Err(Id3v2Error::new(Id3v2ErrorKind::NestedChapterFrame(id)).into());
+				}
+
+				log::warn!("Skipping a `{id}` frame embedded in another chapter frame");
+				content = header_reader.get(size as usize..).unwrap_or_default();
+				continue;
+			}
+		}
+
+		match ParsedFrame::read(&mut content, &mut frame_content, version, false, parse_mode)? {
+			ParsedFrame::Next(frame) => frames.push(frame),
+			ParsedFrame::Skip { size } => {
+				content = content.get(size as usize..).unwrap_or_default();
+			},
+			ParsedFrame::Eof => break,
+		}
+	}
+
+	Ok(frames)
+}
+
+fn parse_compressed_frame<R: Read>(
+	reader: &mut R,
+	content: &mut Vec<u8>,
+	size: u32,
+	id: FrameId<'static>,
+	mut flags: FrameFlags,
+	version: Id3v2Version,
+	parse_mode: ParsingMode,
+) -> Result<ParsedFrame<'static>> {
+	// The entire frame is read up front, so a failure to decompress it can't leave the reader
+	// in the middle of the frame
+	let mut compressed = Vec::new();
+	reader.read_to_end(&mut compressed)?;
+
+	let decompressed = match decompress(&compressed, flags.data_length_indicator) {
+		Ok(decompressed) => decompressed,
+		Err(err) if parse_mode == ParsingMode::Strict => {
+			return Err(frame_allocation_error(err, &id, size))
+		},
+		// Exceeding the allocation limit is never recoverable
+		Err(err) if matches!(err.kind(), ErrorKind::TooMuchData) => {
+			return Err(frame_allocation_error(err, &id, size))
+		},
+		Err(err) => {
+			log::warn!("Failed to decompress frame `{id}`, skipping: {err}");
+			return Ok(ParsedFrame::Skip { size: 0 });
+		},
+	};
+
+	// The frame will be written back uncompressed
+	flags.compression = false;
+	flags.data_length_indicator = None;
+
+	parse_frame(
+		&mut &*decompressed,
+		content,
+		size,
+		id,
+		flags,
+		version,
+		parse_mode,
+	)
+}
+
+// The data length indicator is the size of the frame after decompression. It's only used to
+// allocate up front, the actual size of the decompressed data is still checked against the
+// allocation limit.
 #[cfg(feature = "id3v2_compression_support")]
-#[allow(clippy::unnecessary_wraps)]
-fn handle_compression<R: Read>(reader: R) -> Result<flate2::read::ZlibDecoder<R>> {
-	Ok(flate2::read::ZlibDecoder::new(reader))
+pub(in crate::id3::v2) fn decompress(
+	compressed: &[u8],
+	data_length_indicator: Option<u32>,
+) -> Result<Vec<u8>> {
+	let allocation_limit = crate::util::alloc::allocation_limit();
+
+	let expected_len = data_length_indicator.unwrap_or(0) as usize;
+	if expected_len > allocation_limit {
+		crate::macros::err!(TooMuchData);
+	}
+
+	let mut decompressed = Vec::new();
+	decompressed.try_reserve_exact(expected_len)?;
+
+	flate2::read::ZlibDecoder::new(compressed)
+		.take(allocation_limit as u64 + 1)
+		.read_to_end(&mut decompressed)?;
+
+	if decompressed.len() > allocation_limit {
+		crate::macros::err!(TooMuchData);
+	}
+
+	if data_length_indicator.is_some_and(|len| len as usize != decompressed.len()) {
+		log::debug!(
+			"Data length indicator ({expected_len}) doesn't match the decompressed size ({})",
+			decompressed.len()
+		);
+	}
+
+	Ok(decompressed)
 }
 
 #[cfg(not(feature = "id3v2_compression_support"))]
 #[allow(clippy::unnecessary_wraps)]
-fn handle_compression<R>(_: R) -> Result<std::io::Empty> {
+pub(in crate::id3::v2) fn decompress(_: &[u8], _: Option<u32>) -> Result<Vec<u8>> {
 	Err(Id3v2Error::new(Id3v2ErrorKind::CompressedFrameEncountered).into())
 }
 
@@ -212,8 +341,8 @@ fn handle_encryption<R: Read>(
 		return Err(Id3v2Error::new(Id3v2ErrorKind::MissingDataLengthIndicator).into());
 	}
 
-	let mut content = try_vec![0; size as usize];
-	reader.read_exact(&mut content)?;
+	let content = read_encrypted_content(reader, size)
+		.map_err(|err| frame_allocation_error(err, &id, size))?;
 
 	let encrypted_frame = Frame {
 		id,
@@ -225,16 +354,61 @@ fn handle_encryption<R: Read>(
 	Ok(ParsedFrame::Next(encrypted_frame))
 }
 
+fn read_encrypted_content<R: Read>(reader: &mut R, size: u32) -> Result<Vec<u8>> {
+	let mut content = try_vec![0; size as usize];
+	reader.read_exact(&mut content)?;
+
+	Ok(content)
+}
+
 fn parse_frame<R: Read>(
 	reader: &mut R,
+	content: &mut Vec<u8>,
 	size: u32,
 	id: FrameId<'static>,
-	flags: FrameFlags,
+	mut flags: FrameFlags,
 	version: Id3v2Version,
 	parse_mode: ParsingMode,
 ) -> Result<ParsedFrame<'static>> {
-	match parse_content(reader, id.as_str(), version, parse_mode)? {
-		Some(value) => Ok(ParsedFrame::Next(Frame { id, value, flags })),
-		None => Ok(ParsedFrame::Skip { size }),
+	content.clear();
+	reader.read_to_end(content)?;
+
+	match parse_content(content, id.as_str(), version, parse_mode) {
+		Ok(Some(value)) => Ok(ParsedFrame::Next(Frame { id, value, flags })),
+		// The content has already been consumed, there's nothing left to skip
+		Ok(None) => Ok(ParsedFrame::Skip { size: 0 }),
+		// Rather than discarding the frame, keep its content as-is, so it can be written back
+		Err(err)
+			if parse_mode == ParsingMode::Relaxed
+				&& !matches!(err.kind(), ErrorKind::TooMuchData) =>
+		{
+			log::warn!("Failed to parse frame `{id}`, retaining its raw content: {err}");
+
+			// The content has already been unsynchronised and decompressed
+			flags.unsynchronisation = false;
+			flags.compression = false;
+			flags.data_length_indicator = None;
+
+			Ok(ParsedFrame::Next(Frame {
+				id,
+				value: FrameValue::Binary(content.clone()),
+				flags,
+			}))
+		},
+		Err(err) => Err(frame_allocation_error(err, &id, size)),
 	}
 }
+
+// The allocation limit is enforced deep within the content parsers, which have no idea what frame
+// they're working on. Attach the frame ID, otherwise there's no way to tell which frame was the problem.
+fn frame_allocation_error(err: LoftyError, id: &FrameId<'_>, size: u32) -> LoftyError {
+	if matches!(err.kind(), ErrorKind::TooMuchData) {
+		return Id3v2Error::new(Id3v2ErrorKind::FrameExceedsAllocationLimit(
+			id.clone().into_owned(),
+			size,
+		))
+		.into();
+	}
+
+	err
+}
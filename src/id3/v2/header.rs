@@ -1,7 +1,7 @@
 use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
 use crate::id3::v2::restrictions::TagRestrictions;
 use crate::id3::v2::util::synchsafe::SynchsafeInteger;
-use crate::macros::err;
+use crate::macros::{err, try_vec};
 
 use std::io::Read;
 
@@ -23,29 +23,74 @@ pub enum Id3v2Version {
 #[allow(clippy::struct_excessive_bools)]
 pub struct Id3v2TagFlags {
 	/// Whether or not all frames are unsynchronised. See [`FrameFlags::unsynchronisation`](crate::id3::v2::FrameFlags::unsynchronisation)
+	///
+	/// This is applied when the tag is written. ID3v2.4 tags will have every frame unsynchronised
+	/// individually, while ID3v2.3 tags (see [`WriteOptions::use_id3v23`](crate::WriteOptions::use_id3v23))
+	/// are unsynchronised as a whole.
 	pub unsynchronisation: bool,
 	/// Indicates if the tag is in an experimental stage
 	pub experimental: bool,
 	/// Indicates that the tag includes a footer
 	///
 	/// A footer will be created if the tag is written
+	///
+	/// A tag with a footer can't have padding, so [`WriteOptions::preferred_padding`](crate::WriteOptions::preferred_padding)
+	/// is ignored.
 	pub footer: bool,
 	/// Whether or not to include a CRC-32 in the extended header
 	///
-	/// This is calculated if the tag is written
+	/// This is calculated if the tag is written. For the CRC of a tag that was read, see
+	/// [`Id3v2Tag::crc`](crate::id3::v2::Id3v2Tag::crc).
 	pub crc: bool,
 	/// Restrictions on the tag, written in the extended header
 	///
 	/// In addition to being setting this flag, all restrictions must be provided. See [`TagRestrictions`]
+	///
+	/// When writing an ID3v2.4 tag, it is checked against these restrictions. A tag violating them
+	/// results in [`ErrorKind::Id3v2Restrictions`](crate::error::ErrorKind::Id3v2Restrictions), unless
+	/// [`WriteOptions::fit_id3v2_restrictions`](crate::WriteOptions::fit_id3v2_restrictions) is used.
 	pub restrictions: Option<TagRestrictions>,
 }
 
+/// The CRC-32 of a tag's frame data, see [`Id3v2Tag::crc`](crate::id3::v2::Id3v2Tag::crc)
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Id3v2Crc {
+	pub(crate) stored: u32,
+	pub(crate) calculated: Option<u32>,
+}
+
+impl Id3v2Crc {
+	/// The CRC-32 stored in the extended header
+	pub fn stored(&self) -> u32 {
+		self.stored
+	}
+
+	/// The CRC-32 of the frame data as it was read
+	///
+	/// This is `None` if the tag exceeded the [allocation limit](crate::ParseOptions::allocation_limit),
+	/// as its frame data isn't read all at once.
+	pub fn calculated(&self) -> Option<u32> {
+		self.calculated
+	}
+
+	/// Whether the stored CRC-32 matches the frame data, if it could be calculated
+	pub fn is_valid(&self) -> Option<bool> {
+		self.calculated.map(|calculated| calculated == self.stored)
+	}
+}
+
 #[derive(Copy, Clone, Debug)]
 pub(crate) struct Id3v2Header {
 	pub version: Id3v2Version,
 	pub flags: Id3v2TagFlags,
 	pub size: u32,
 	pub extended_size: u32,
+	/// The CRC-32 from the extended header, if present
+	pub crc: Option<u32>,
+	/// The size of the padding, only known for ID3v2.3 tags with an extended header
+	pub padding_size: u32,
+	/// Whether the extended header was skipped due to being malformed
+	pub bad_extended_header: bool,
 }
 
 impl Id3v2Header {
@@ -93,11 +138,42 @@ impl Id3v2Header {
 
 		let size = BigEndian::read_u32(&header[6..]).unsynch();
 		let mut extended_size = 0;
+		let mut crc = None;
+		let mut padding_size = 0;
+		let mut bad_extended_header = false;
 
 		let extended_header =
 			(version == Id3v2Version::V4 || version == Id3v2Version::V3) && flags & 0x40 == 0x40;
 
-		if extended_header {
+		if extended_header && version == Id3v2Version::V3 {
+			// https://id3.org/id3v2.3.0#ID3v2_extended_header
+			//
+			// Structure of extended header:
+			//
+			// Size (4) (Not synchsafe, and excludes itself, so it is either 6 or 10)
+			// Flags (2)
+			// Padding size (4)
+			// CRC (4) (Optional)
+			let content_size = bytes.read_u32::<BigEndian>()?;
+			extended_size = content_size.saturating_add(4);
+
+			if extended_size >= size {
+				return Err(Id3v2Error::new(Id3v2ErrorKind::BadExtendedHeaderSize).into());
+			}
+
+			let mut content = try_vec![0; content_size as usize];
+			bytes.read_exact(&mut content)?;
+
+			match parse_v3_extended_header(&content, size - extended_size) {
+				Some((stored_crc, padding)) => {
+					flags_parsed.crc = stored_crc.is_some();
+					crc = stored_crc;
+					padding_size = padding;
+				},
+				// The size is still usable to skip the extended header, the caller decides whether that's acceptable
+				None => bad_extended_header = true,
+			}
+		} else if extended_header {
 			extended_size = bytes.read_u32::<BigEndian>()?.unsynch();
 
 			if extended_size < 6 {
@@ -114,9 +190,16 @@ impl Id3v2Header {
 			if extended_flags & 0x20 == 0x20 {
 				flags_parsed.crc = true;
 
-				// We don't care about the existing CRC (5) or its length byte (1)
-				let mut crc = [0; 6];
-				bytes.read_exact(&mut crc)?;
+				// We don't care about the length byte, it is always 5
+				let mut crc_bytes = [0; 6];
+				bytes.read_exact(&mut crc_bytes)?;
+
+				// A 35 bit synchsafe integer, of which only the lower 32 bits are used
+				crc = Some(
+					crc_bytes[1..]
+						.iter()
+						.fold(0_u64, |crc, b| crc << 7 | u64::from(b & 0x7F)) as u32,
+				);
 			}
 
 			if extended_flags & 0x10 == 0x10 {
@@ -136,6 +219,26 @@ impl Id3v2Header {
 			flags: flags_parsed,
 			size,
 			extended_size,
+			crc,
+			padding_size,
+			bad_extended_header,
 		})
 	}
 }
+
+// Returns the CRC (if present) and padding size, or `None` if the extended header is malformed
+fn parse_v3_extended_header(content: &[u8], remaining_size: u32) -> Option<(Option<u32>, u32)> {
+	let extended_flags = BigEndian::read_u16(content.get(..2)?);
+	let padding_size = BigEndian::read_u32(content.get(2..6)?);
+
+	// The CRC is the only flag, and the size has to account for it
+	let crc = extended_flags & 0x8000 == 0x8000;
+	let expected_size = if crc { 10 } else { 6 };
+
+	if content.len() != expected_size || padding_size > remaining_size {
+		return None;
+	}
+
+	let crc = crc.then(|| BigEndian::read_u32(&content[6..]));
+	Some((crc, padding_size))
+}
@@ -5,6 +5,7 @@ use crate::picture::{MimeType, Picture, PictureType};
 use crate::util::text::{encode_text, TextEncoding};
 
 use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
 use std::io::{Read, Write as _};
 
 use byteorder::{ReadBytesExt as _, WriteBytesExt as _};
@@ -12,7 +13,10 @@ use byteorder::{ReadBytesExt as _, WriteBytesExt as _};
 /// An `ID3v2` attached picture frame
 ///
 /// This is simply a wrapper around [`Picture`] to include a [`TextEncoding`]
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+///
+/// Pictures are told apart by their types and descriptions. There can only be one picture of type
+/// [`PictureType::Icon`] and [`PictureType::OtherIcon`], regardless of their descriptions.
+#[derive(Clone, Debug, Eq)]
 pub struct AttachedPictureFrame {
 	/// The encoding of the description
 	pub encoding: TextEncoding,
@@ -20,6 +24,22 @@ pub struct AttachedPictureFrame {
 	pub picture: Picture,
 }
 
+impl PartialEq for AttachedPictureFrame {
+	fn eq(&self, other: &Self) -> bool {
+		let pic_type = self.picture.pic_type;
+		pic_type == other.picture.pic_type
+			&& (matches!(pic_type, PictureType::Icon | PictureType::OtherIcon)
+				|| self.picture.description == other.picture.description)
+	}
+}
+
+impl Hash for AttachedPictureFrame {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		// Icons are equal regardless of their descriptions, so only the type can be hashed
+		self.picture.pic_type.hash(state);
+	}
+}
+
 impl AttachedPictureFrame {
 	/// Get an [`AttachedPictureFrame`] from ID3v2 A/PIC bytes:
 	///
@@ -128,7 +148,7 @@ impl AttachedPictureFrame {
 
 		match &self.picture.description {
 			Some(description) => data.write_all(&encode_text(description, self.encoding, true))?,
-			None => data.write_u8(0)?,
+			None => data.write_all(&encode_text("", self.encoding, true))?,
 		}
 
 		data.write_all(&self.picture.data)?;
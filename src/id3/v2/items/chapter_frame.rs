@@ -0,0 +1,300 @@
+use crate::error::Result;
+use crate::id3::v2::frame::read::parse_embedded_frames;
+use crate::id3::v2::frame::{Frame, FrameValue};
+use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::items::TextInformationFrame;
+use crate::id3::v2::write::create_embedded_frames;
+use crate::probe::ParsingMode;
+use crate::util::text::{decode_text, encode_text, TextEncoding};
+use crate::write_options::WriteOptions;
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ByteOrder};
+
+/// The value of [`ChapterFrame::start_offset`] and [`ChapterFrame::end_offset`] when they are unused
+pub const CHAPTER_OFFSET_UNUSED: u32 = 0xFFFF_FFFF;
+
+/// An `ID3v2` chapter frame
+///
+/// This marks a section of the audio, see the [ID3v2 Chapter Frame Addendum](https://id3.org/id3v2-chapters-1.0).
+/// Chapters are ordered by a [`TableOfContentsFrame`](crate::id3::v2::TableOfContentsFrame).
+///
+/// The frames embedded in a chapter (usually a `TIT2` for its title, and possibly an `APIC`) are
+/// stored in [`ChapterFrame::frames`], and are read and written the same way as the frames of the tag.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChapterFrame {
+	/// A unique identifier for the chapter within the tag, not meant to be displayed
+	pub element_id: String,
+	/// The start of the chapter, in milliseconds
+	pub start_time: u32,
+	/// The end of the chapter, in milliseconds
+	pub end_time: u32,
+	/// The byte offset of the first audio frame of the chapter, from the start of the file
+	///
+	/// This is [`CHAPTER_OFFSET_UNUSED`] if only [`ChapterFrame::start_time`] should be used.
+	pub start_offset: u32,
+	/// The byte offset of the first audio frame following the chapter, from the start of the file
+	///
+	/// This is [`CHAPTER_OFFSET_UNUSED`] if only [`ChapterFrame::end_time`] should be used.
+	pub end_offset: u32,
+	/// The frames describing the chapter
+	pub frames: Vec<Frame<'static>>,
+}
+
+impl ChapterFrame {
+	/// Create a new [`ChapterFrame`] with no byte offsets or embedded frames
+	pub fn new(element_id: String, start_time: u32, end_time: u32) -> Self {
+		Self {
+			element_id,
+			start_time,
+			end_time,
+			start_offset: CHAPTER_OFFSET_UNUSED,
+			end_offset: CHAPTER_OFFSET_UNUSED,
+			frames: Vec::new(),
+		}
+	}
+
+	/// The chapter's title, from its embedded `TIT2` frame
+	pub fn title(&self) -> Option<&str> {
+		self.frames.iter().find_map(|frame| match frame.content() {
+			FrameValue::Text(TextInformationFrame { value, .. }) if frame.id_str() == "TIT2" => {
+				Some(value.as_str())
+			},
+			_ => None,
+		})
+	}
+
+	/// Read a [`ChapterFrame`]
+	///
+	/// NOTE: This expects the frame header to have already been skipped
+	///
+	/// The embedded frames are expected to be laid out as they are in an `ID3v2` tag of `version`.
+	///
+	/// # Errors
+	///
+	/// * Failure to read from `reader`
+	/// * An embedded frame is invalid, or is another chapter frame, with [`ParsingMode::Strict`]
+	pub fn parse<R>(
+		reader: &mut R,
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read,
+	{
+		let mut content = Vec::new();
+		reader.read_to_end(&mut content)?;
+
+		Self::parse_slice(&content, version, parse_mode)
+	}
+
+	// The embedded frames are parsed from `content` in place
+	pub(in crate::id3::v2) fn parse_slice(
+		mut content: &[u8],
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>> {
+		let reader = &mut content;
+
+		let Ok(element_id) = decode_text(reader, TextEncoding::Latin1, true) else {
+			return Ok(None);
+		};
+
+		let mut times = [0; 16];
+		if reader.read_exact(&mut times).is_err() {
+			return Ok(None);
+		}
+
+		Ok(Some(ChapterFrame {
+			element_id: element_id.content,
+			start_time: BigEndian::read_u32(&times[..4]),
+			end_time: BigEndian::read_u32(&times[4..8]),
+			start_offset: BigEndian::read_u32(&times[8..12]),
+			end_offset: BigEndian::read_u32(&times[12..]),
+			frames: parse_embedded_frames(content, version, parse_mode)?,
+		}))
+	}
+
+	/// Convert a [`ChapterFrame`] to a byte vec
+	///
+	/// The embedded frames are written for the `ID3v2` version specified in `write_options`.
+	///
+	/// # Errors
+	///
+	/// * An embedded frame is invalid, or too large
+	pub fn as_bytes(&self, write_options: WriteOptions) -> Result<Vec<u8>> {
+		let mut content = encode_text(&self.element_id, TextEncoding::Latin1, true);
+		for value in [
+			self.start_time,
+			self.end_time,
+			self.start_offset,
+			self.end_offset,
+		] {
+			content.extend(value.to_be_bytes());
+		}
+
+		content.extend(create_embedded_frames(&self.frames, write_options)?);
+
+		Ok(content)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::error::{ErrorKind, Id3v2ErrorKind};
+	use crate::id3::v2::header::Id3v2Version;
+	use crate::id3::v2::{
+		ChapterFrame, Frame, FrameFlags, FrameValue, TextInformationFrame, CHAPTER_OFFSET_UNUSED,
+	};
+	use crate::util::text::TextEncoding;
+	use crate::{ParsingMode, WriteOptions};
+
+	fn expected() -> ChapterFrame {
+		let mut chapter = ChapterFrame::new(String::from("chp0"), 0, 5000);
+		chapter.end_offset = 1024;
+		chapter.frames.push(
+			Frame::new(
+				"TIT2",
+				TextInformationFrame {
+					encoding: TextEncoding::Latin1,
+					value: String::from("Introduction"),
+				},
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+		chapter.frames.push(
+			Frame::new(
+				"NCON",
+				FrameValue::Binary(vec![1, 2, 3]),
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+
+		chapter
+	}
+
+	fn expected_bytes() -> Vec<u8> {
+		let mut bytes = b"chp0\0".to_vec();
+		bytes.extend([0, 0, 0, 0]); // Start time
+		bytes.extend(5000_u32.to_be_bytes()); // End time
+		bytes.extend(CHAPTER_OFFSET_UNUSED.to_be_bytes()); // Start offset
+		bytes.extend(1024_u32.to_be_bytes()); // End offset
+
+		bytes.extend(b"TIT2\0\0\0\x0D\0\0\0Introduction");
+		bytes.extend(b"NCON\0\0\0\x03\0\0\x01\x02\x03");
+		bytes
+	}
+
+	#[test]
+	fn chap_decode() {
+		let parsed = ChapterFrame::parse(
+			&mut &expected_bytes()[..],
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(parsed, expected());
+		assert_eq!(parsed.title(), Some("Introduction"));
+
+		// The unknown frame is preserved
+		assert_eq!(
+			parsed.frames[1].content(),
+			&FrameValue::Binary(vec![1, 2, 3])
+		);
+	}
+
+	#[test]
+	fn chap_encode() {
+		let encoded = expected().as_bytes(WriteOptions::default()).unwrap();
+		assert_eq!(encoded, expected_bytes());
+	}
+
+	#[test]
+	fn chap_too_short() {
+		let parsed = ChapterFrame::parse(
+			&mut &b"chp0\0\0\0\0\0"[..],
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap();
+
+		assert!(parsed.is_none());
+	}
+
+	// Each chapter holds the next, `depth` levels deep
+	fn nested_chapters(depth: usize) -> Vec<u8> {
+		let chapter = |embedded: &[u8]| {
+			let mut content = b"chp\0".to_vec();
+			content.extend([0; 16]);
+			content.extend(embedded);
+			content
+		};
+
+		let mut content = chapter(&[]);
+		for _ in 0..depth {
+			let mut frame = b"CHAP".to_vec();
+			frame.extend((content.len() as u32).to_be_bytes());
+			frame.extend([0, 0]);
+			frame.extend(content);
+
+			content = chapter(&frame);
+		}
+
+		content
+	}
+
+	#[test]
+	fn nested_chapters_skipped() {
+		let content = nested_chapters(2000);
+
+		let parsed = ChapterFrame::parse(
+			&mut &content[..],
+			Id3v2Version::V3,
+			ParsingMode::BestAttempt,
+		)
+		.unwrap()
+		.unwrap();
+		assert!(parsed.frames.is_empty());
+
+		let err = ChapterFrame::parse(&mut &content[..], Id3v2Version::V3, ParsingMode::Strict)
+			.unwrap_err();
+		assert!(matches!(
+			err.kind(),
+			ErrorKind::Id3v2(e) if matches!(e.kind(), Id3v2ErrorKind::NestedChapterFrame(id) if id.as_str() == "CHAP")
+		));
+	}
+
+	#[test]
+	fn nested_chapters_in_tag() {
+		use crate::id3::v2::header::Id3v2Header;
+		use crate::id3::v2::read::parse_id3v2;
+		use crate::ParseOptions;
+
+		let content = nested_chapters(2000);
+
+		let mut frame = b"CHAP".to_vec();
+		frame.extend((content.len() as u32).to_be_bytes());
+		frame.extend([0, 0]);
+		frame.extend(content);
+
+		let size = frame.len() as u32;
+		let mut tag = b"ID3\x03\0\0".to_vec();
+		for shift in [21, 14, 7, 0] {
+			tag.push(((size >> shift) & 0x7F) as u8);
+		}
+		tag.extend(frame);
+
+		let reader = &mut &tag[..];
+		let header = Id3v2Header::parse(reader).unwrap();
+		let id3v2 = parse_id3v2(reader, header, ParseOptions::new()).unwrap();
+
+		let chapters = id3v2.chapters().collect::<Vec<_>>();
+		assert_eq!(chapters.len(), 1);
+		assert!(chapters[0].frames.is_empty());
+	}
+}
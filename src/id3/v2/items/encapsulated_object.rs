@@ -1,10 +1,18 @@
 use crate::error::{ErrorKind, Id3v2Error, Id3v2ErrorKind, LoftyError, Result};
 use crate::util::text::{decode_text, encode_text, TextEncoding};
 
+use std::hash::{Hash, Hasher};
 use std::io::{Cursor, Read};
 
 /// Allows for encapsulation of any file type inside an ID3v2 tag
-#[derive(PartialEq, Clone, Debug, Eq, Hash)]
+///
+/// This is commonly used by DJ software to store cue points, beat grids, etc.
+///
+/// ## Equality
+///
+/// Multiple `GEOB` frames may exist in a tag, so long as their descriptions differ. As such, only
+/// [`GeneralEncapsulatedObject::description`] is compared.
+#[derive(Clone, Debug, Eq)]
 pub struct GeneralEncapsulatedObject {
 	/// The text encoding of `file_name` and `description`
 	pub encoding: TextEncoding,
@@ -12,12 +20,24 @@ pub struct GeneralEncapsulatedObject {
 	pub mime_type: Option<String>,
 	/// The file's name
 	pub file_name: Option<String>,
-	/// A unique content descriptor
-	pub descriptor: Option<String>,
+	/// A unique content description
+	pub description: Option<String>,
 	/// The file's content
 	pub data: Vec<u8>,
 }
 
+impl PartialEq for GeneralEncapsulatedObject {
+	fn eq(&self, other: &Self) -> bool {
+		self.description == other.description
+	}
+}
+
+impl Hash for GeneralEncapsulatedObject {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.description.hash(state);
+	}
+}
+
 impl GeneralEncapsulatedObject {
 	/// Read a [`GeneralEncapsulatedObject`] from a slice
 	///
@@ -38,7 +58,7 @@ impl GeneralEncapsulatedObject {
 
 		let mime_type = decode_text(&mut cursor, TextEncoding::Latin1, true)?;
 		let file_name = decode_text(&mut cursor, encoding, true)?;
-		let descriptor = decode_text(&mut cursor, encoding, true)?;
+		let description = decode_text(&mut cursor, encoding, true)?;
 
 		let mut data = Vec::new();
 		cursor.read_to_end(&mut data)?;
@@ -47,7 +67,7 @@ impl GeneralEncapsulatedObject {
 			encoding,
 			mime_type: mime_type.text_or_none(),
 			file_name: file_name.text_or_none(),
-			descriptor: descriptor.text_or_none(),
+			description: description.text_or_none(),
 			data,
 		})
 	}
@@ -69,8 +89,8 @@ impl GeneralEncapsulatedObject {
 		let file_name = self.file_name.as_deref();
 		bytes.extend(&*encode_text(file_name.unwrap_or(""), encoding, true));
 
-		let descriptor = self.descriptor.as_deref();
-		bytes.extend(&*encode_text(descriptor.unwrap_or(""), encoding, true));
+		let description = self.description.as_deref();
+		bytes.extend(&*encode_text(description.unwrap_or(""), encoding, true));
 
 		bytes.extend(&self.data);
 
@@ -89,7 +109,7 @@ mod tests {
 			encoding: TextEncoding::Latin1,
 			mime_type: Some(String::from("audio/mpeg")),
 			file_name: Some(String::from("a.mp3")),
-			descriptor: Some(String::from("Test Asset")),
+			description: Some(String::from("Test Asset")),
 			data: crate::tag::utils::test_utils::read_path(
 				"tests/files/assets/minimal/full_test.mp3",
 			),
@@ -99,7 +119,11 @@ mod tests {
 
 		let parsed_geob = GeneralEncapsulatedObject::parse(&cont).unwrap();
 
-		assert_eq!(parsed_geob, expected);
+		assert_eq!(parsed_geob.encoding, expected.encoding);
+		assert_eq!(parsed_geob.mime_type, expected.mime_type);
+		assert_eq!(parsed_geob.file_name, expected.file_name);
+		assert_eq!(parsed_geob.description, expected.description);
+		assert_eq!(parsed_geob.data, expected.data);
 	}
 
 	#[test]
@@ -108,7 +132,7 @@ mod tests {
 			encoding: TextEncoding::Latin1,
 			mime_type: Some(String::from("audio/mpeg")),
 			file_name: Some(String::from("a.mp3")),
-			descriptor: Some(String::from("Test Asset")),
+			description: Some(String::from("Test Asset")),
 			data: crate::tag::utils::test_utils::read_path(
 				"tests/files/assets/minimal/full_test.mp3",
 			),
@@ -121,4 +145,28 @@ mod tests {
 
 		assert_eq!(encoded, expected_bytes);
 	}
+
+	#[test]
+	fn geob_serato_markers() {
+		// A `Serato Markers2` object, laid out the way Serato writes it: a two byte version, followed
+		// by base64 encoded cue/color entries, padded with NULs
+		let cont = crate::tag::utils::test_utils::read_path(
+			"tests/tags/assets/id3v2/serato_markers2.geob",
+		);
+
+		let parsed_geob = GeneralEncapsulatedObject::parse(&cont).unwrap();
+
+		assert_eq!(parsed_geob.encoding, TextEncoding::Latin1);
+		assert_eq!(
+			parsed_geob.mime_type.as_deref(),
+			Some("application/octet-stream")
+		);
+		assert_eq!(parsed_geob.file_name, None);
+		assert_eq!(parsed_geob.description.as_deref(), Some("Serato Markers2"));
+		assert!(parsed_geob.data.starts_with(b"\x01\x01"));
+		assert!(parsed_geob.data.ends_with(b"\0\0"));
+
+		// The payload (including its trailing NULs) must be untouched
+		assert_eq!(parsed_geob.as_bytes(), cont);
+	}
 }
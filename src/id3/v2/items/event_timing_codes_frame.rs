@@ -66,14 +66,14 @@ pub enum EventType {
 	AudioEnd = 0xFD,
 	AudioFileEnds = 0xFE,
 
-	/// 0x17..=0xDF and 0xF0..=0xFC
-	Reserved,
+	/// Any event type not defined by the spec (0x17..=0xDF, 0xF0..=0xFC, and 0xFF), kept as-is
+	Other(u8),
 }
 
 impl EventType {
 	/// Get a [`EventType`] from a `u8`
 	///
-	/// NOTE: 0x17..=0xDF and 0xF0..=0xFC map to [`EventType::Reserved`]
+	/// NOTE: 0x17..=0xDF, 0xF0..=0xFC, and 0xFF map to [`EventType::Other`]
 	///
 	/// # Examples
 	///
@@ -88,7 +88,7 @@ impl EventType {
 	///
 	/// // This is in the undefined range
 	/// let invalid_byte = 0x17;
-	/// assert_eq!(EventType::from_u8(invalid_byte), EventType::Reserved);
+	/// assert_eq!(EventType::from_u8(invalid_byte), EventType::Other(0x17));
 	/// ```
 	pub fn from_u8(byte: u8) -> Self {
 		match byte {
@@ -137,8 +137,69 @@ impl EventType {
 			0xFD => Self::AudioEnd,
 			0xFE => Self::AudioFileEnds,
 
-			// 0x17..=0xDF and 0xF0..=0xFC
-			_ => Self::Reserved,
+			// 0x17..=0xDF, 0xF0..=0xFC, and 0xFF
+			_ => Self::Other(byte),
+		}
+	}
+
+	/// Get the `u8` representation of an [`EventType`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::EventType;
+	///
+	/// assert_eq!(EventType::IntroStart.as_u8(), 0x02);
+	/// assert_eq!(EventType::Other(0x17).as_u8(), 0x17);
+	/// ```
+	pub fn as_u8(self) -> u8 {
+		match self {
+			Self::Padding => 0x00,
+			Self::EndOfInitialSilence => 0x01,
+			Self::IntroStart => 0x02,
+			Self::MainPartStart => 0x03,
+			Self::OutroStart => 0x04,
+			Self::OutroEnd => 0x05,
+			Self::VerseStart => 0x06,
+			Self::RefrainStart => 0x07,
+			Self::InterludeStart => 0x08,
+			Self::ThemeStart => 0x09,
+			Self::VariationStart => 0x0A,
+			Self::KeyChange => 0x0B,
+			Self::TimeChange => 0x0C,
+			Self::MomentaryUnwantedNoise => 0x0D,
+			Self::SustainedNoise => 0x0E,
+			Self::SustainedNoiseEnd => 0x0F,
+			Self::IntroEnd => 0x10,
+			Self::MainPartEnd => 0x11,
+			Self::VerseEnd => 0x12,
+			Self::RefrainEnd => 0x13,
+			Self::ThemeEnd => 0x14,
+			Self::Profanity => 0x15,
+			Self::ProfanityEnd => 0x16,
+
+			// User-defined events
+			Self::NotPredefinedSynch0 => 0xE0,
+			Self::NotPredefinedSynch1 => 0xE1,
+			Self::NotPredefinedSynch2 => 0xE2,
+			Self::NotPredefinedSynch3 => 0xE3,
+			Self::NotPredefinedSynch4 => 0xE4,
+			Self::NotPredefinedSynch5 => 0xE5,
+			Self::NotPredefinedSynch6 => 0xE6,
+			Self::NotPredefinedSynch7 => 0xE7,
+			Self::NotPredefinedSynch8 => 0xE8,
+			Self::NotPredefinedSynch9 => 0xE9,
+			Self::NotPredefinedSynchA => 0xEA,
+			Self::NotPredefinedSynchB => 0xEB,
+			Self::NotPredefinedSynchC => 0xEC,
+			Self::NotPredefinedSynchD => 0xED,
+			Self::NotPredefinedSynchE => 0xEE,
+			Self::NotPredefinedSynchF => 0xEF,
+
+			Self::AudioEnd => 0xFD,
+			Self::AudioFileEnds => 0xFE,
+
+			Self::Other(byte) => byte,
 		}
 	}
 }
@@ -230,7 +291,7 @@ impl EventTimingCodesFrame {
 		sorted_events.sort();
 
 		for event in sorted_events {
-			content.push(event.event_type as u8);
+			content.push(event.event_type.as_u8());
 			content.extend(event.timestamp.to_be_bytes())
 		}
 
@@ -290,4 +351,32 @@ mod tests {
 
 		assert_eq!(encoded, expected_bytes);
 	}
+
+	#[test]
+	fn etco_other_event_types() {
+		let bytes = [
+			2, // MS
+			0x17, 0, 0, 0, 1, // Reserved
+			0xE3, 0, 0, 0, 2, // User-defined
+			0xFF, 0, 0, 0, 3, // Reserved
+		];
+
+		let parsed_etco = EventTimingCodesFrame::parse(&mut &bytes[..])
+			.unwrap()
+			.unwrap();
+
+		assert_eq!(
+			parsed_etco
+				.events
+				.iter()
+				.map(|event| event.event_type)
+				.collect::<Vec<_>>(),
+			[
+				EventType::Other(0x17),
+				EventType::NotPredefinedSynch3,
+				EventType::Other(0xFF)
+			]
+		);
+		assert_eq!(parsed_etco.as_bytes(), bytes);
+	}
 }
@@ -37,6 +37,11 @@ impl Hash for ExtendedTextFrame {
 }
 
 impl ExtendedTextFrame {
+	// Cue sheets are useless once truncated or split, see `ItemKey::Cuesheet`
+	pub(crate) fn is_cuesheet(&self) -> bool {
+		self.description.eq_ignore_ascii_case("CUESHEET")
+	}
+
 	/// Read an [`ExtendedTextFrame`] from a slice
 	///
 	/// NOTE: This expects the frame header to have already been skipped
@@ -1,7 +1,9 @@
-use crate::error::Result;
+use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
 use crate::id3::v2::frame::content::verify_encoding;
 use crate::id3::v2::header::Id3v2Version;
-use crate::util::text::{decode_text, encode_text, TextEncoding};
+use crate::macros::parse_mode_choice;
+use crate::probe::ParsingMode;
+use crate::util::text::{decode_text_slice, encode_text, TextEncoding};
 
 use byteorder::ReadBytesExt;
 
@@ -21,14 +23,22 @@ impl KeyValueFrame {
 	///
 	/// NOTE: This expects the frame header to have already been skipped
 	///
+	/// Empty keys and values are preserved. If the final key has no value, it will be kept with an
+	/// empty value, unless `parse_mode` is [`ParsingMode::Strict`].
+	///
 	/// # Errors
 	///
 	/// * Unable to decode the text
+	/// * The final key has no value, with [`ParsingMode::Strict`]
 	///
 	/// ID3v2.2:
 	///
 	/// * The encoding is not [`TextEncoding::Latin1`] or [`TextEncoding::UTF16`]
-	pub fn parse<R>(reader: &mut R, version: Id3v2Version) -> Result<Option<Self>>
+	pub fn parse<R>(
+		reader: &mut R,
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
 	where
 		R: Read,
 	{
@@ -38,16 +48,29 @@ impl KeyValueFrame {
 
 		let encoding = verify_encoding(encoding_byte, version)?;
 
-		let mut values = Vec::new();
+		let mut content = Vec::new();
+		reader.read_to_end(&mut content)?;
 
-		loop {
-			let key = decode_text(reader, encoding, true)?;
-			let value = decode_text(reader, encoding, true)?;
-			if key.bytes_read == 0 || value.bytes_read == 0 {
-				break;
-			}
+		let mut strings = Vec::new();
+		let mut remaining = &content[..];
+		while !remaining.is_empty() {
+			let (text, rest) = split_terminated(remaining, encoding);
+			strings.push(decode_text_slice(text, encoding)?);
+			remaining = rest;
+		}
+
+		let mut values = Vec::with_capacity(strings.len() / 2);
 
-			values.push((key.content, value.content));
+		let mut strings = strings.into_iter();
+		while let Some(key) = strings.next() {
+			match strings.next() {
+				Some(value) => values.push((key, value)),
+				None => parse_mode_choice!(
+					parse_mode,
+					STRICT: return Err(Id3v2Error::new(Id3v2ErrorKind::UnpairedKeyValue).into()),
+					DEFAULT: values.push((key, String::new()))
+				),
+			}
 		}
 
 		Ok(Some(Self {
@@ -67,3 +90,92 @@ impl KeyValueFrame {
 		content
 	}
 }
+
+// Splits off the first string, returning it and the bytes following its terminator
+//
+// The final string may be unterminated.
+fn split_terminated(bytes: &[u8], encoding: TextEncoding) -> (&[u8], &[u8]) {
+	let terminator_pos = match encoding {
+		TextEncoding::Latin1 | TextEncoding::UTF8 => bytes.iter().position(|b| *b == 0),
+		TextEncoding::UTF16 | TextEncoding::UTF16BE => bytes
+			.chunks_exact(2)
+			.position(|c| c == [0, 0])
+			.map(|pos| pos * 2),
+	};
+
+	match terminator_pos {
+		Some(pos) => {
+			let terminator_len = match encoding {
+				TextEncoding::Latin1 | TextEncoding::UTF8 => 1,
+				TextEncoding::UTF16 | TextEncoding::UTF16BE => 2,
+			};
+
+			(&bytes[..pos], &bytes[pos + terminator_len..])
+		},
+		None => (bytes, &[]),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::id3::v2::{Id3v2Version, KeyValueFrame};
+	use crate::probe::ParsingMode;
+	use crate::util::text::TextEncoding;
+
+	fn key_value_frame(pairs: &[(&str, &str)]) -> KeyValueFrame {
+		KeyValueFrame {
+			encoding: TextEncoding::UTF8,
+			key_value_pairs: pairs
+				.iter()
+				.map(|(k, v)| (String::from(*k), String::from(*v)))
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn round_trip() {
+		for encoding in [
+			TextEncoding::Latin1,
+			TextEncoding::UTF16,
+			TextEncoding::UTF16BE,
+			TextEncoding::UTF8,
+		] {
+			let mut frame = key_value_frame(&[
+				("producer", "Foo"),
+				("engineer", ""),
+				("", "Bar"),
+				("mix", "Baz"),
+			]);
+			frame.encoding = encoding;
+
+			let bytes = frame.as_bytes();
+			let parsed =
+				KeyValueFrame::parse(&mut &bytes[..], Id3v2Version::V4, ParsingMode::Strict)
+					.unwrap()
+					.unwrap();
+
+			assert_eq!(parsed, frame);
+		}
+	}
+
+	#[test]
+	fn unpaired_key() {
+		// The final key has no value, and isn't terminated
+		let bytes = b"\x03producer\0Foo\0engineer";
+
+		assert!(
+			KeyValueFrame::parse(&mut &bytes[..], Id3v2Version::V4, ParsingMode::Strict).is_err()
+		);
+
+		for parse_mode in [ParsingMode::BestAttempt, ParsingMode::Relaxed] {
+			let parsed = KeyValueFrame::parse(&mut &bytes[..], Id3v2Version::V4, parse_mode)
+				.unwrap()
+				.unwrap();
+
+			assert_eq!(
+				parsed,
+				key_value_frame(&[("producer", "Foo"), ("engineer", "")])
+			);
+		}
+	}
+}
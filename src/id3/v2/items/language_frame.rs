@@ -51,9 +51,7 @@ impl LanguageFrame {
 	) -> Result<Vec<u8>> {
 		let mut bytes = vec![encoding as u8];
 
-		if language.len() != 3 || language.iter().any(|c| !c.is_ascii_alphabetic()) {
-			return Err(Id3v2Error::new(Id3v2ErrorKind::InvalidLanguage(language)).into());
-		}
+		verify_language(language)?;
 
 		bytes.extend(language);
 		bytes.extend(encode_text(description, encoding, true).iter());
@@ -63,14 +61,27 @@ impl LanguageFrame {
 	}
 }
 
+// Languages are ISO-639-2 codes, only `'a'..='z'` and `'A'..='Z'` are allowed
+pub(in crate::id3::v2) fn verify_language(language: [u8; 3]) -> Result<()> {
+	if language.iter().any(|c| !c.is_ascii_alphabetic()) {
+		return Err(Id3v2Error::new(Id3v2ErrorKind::InvalidLanguage(language)).into());
+	}
+
+	Ok(())
+}
+
 /// An `ID3v2` comment frame
 ///
-/// Similar to `TXXX` and `WXXX` frames, comments are told apart by their descriptions.
+/// Comments are told apart by their languages and descriptions. The comment with an empty
+/// description is the general one, others are used for application data, such as iTunes' `iTunNORM`.
 #[derive(Clone, Debug, Eq)]
 pub struct CommentFrame {
 	/// The encoding of the description and comment text
 	pub encoding: TextEncoding,
 	/// ISO-639-2 language code (3 bytes)
+	///
+	/// This is stricter than ID3v2 itself, only ASCII letters are allowed when writing. See
+	/// [`Id3v2ErrorKind::InvalidLanguage`].
 	pub language: [u8; 3],
 	/// Unique content description
 	pub description: String,
@@ -80,12 +91,13 @@ pub struct CommentFrame {
 
 impl PartialEq for CommentFrame {
 	fn eq(&self, other: &Self) -> bool {
-		self.description == other.description
+		self.language == other.language && self.description == other.description
 	}
 }
 
 impl Hash for CommentFrame {
 	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.language.hash(state);
 		self.description.hash(state);
 	}
 }
@@ -140,12 +152,16 @@ impl CommentFrame {
 
 /// An `ID3v2` unsynchronized lyrics/text frame
 ///
-/// Similar to `TXXX` and `WXXX` frames, USLT frames are told apart by their descriptions.
+/// USLT frames are told apart by their languages and descriptions, allowing for lyrics
+/// in multiple languages.
 #[derive(Clone, Debug, Eq)]
 pub struct UnsynchronizedTextFrame {
 	/// The encoding of the description and content
 	pub encoding: TextEncoding,
 	/// ISO-639-2 language code (3 bytes)
+	///
+	/// This is stricter than ID3v2 itself, only ASCII letters are allowed when writing. See
+	/// [`Id3v2ErrorKind::InvalidLanguage`].
 	pub language: [u8; 3],
 	/// Unique content description
 	pub description: String,
@@ -155,12 +171,13 @@ pub struct UnsynchronizedTextFrame {
 
 impl PartialEq for UnsynchronizedTextFrame {
 	fn eq(&self, other: &Self) -> bool {
-		self.description == other.description
+		self.language == other.language && self.description == other.description
 	}
 }
 
 impl Hash for UnsynchronizedTextFrame {
 	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.language.hash(state);
 		self.description.hash(state);
 	}
 }
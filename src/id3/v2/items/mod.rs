@@ -1,5 +1,6 @@
 mod attached_picture_frame;
 mod audio_text_frame;
+mod chapter_frame;
 mod encapsulated_object;
 mod event_timing_codes_frame;
 mod extended_text_frame;
@@ -7,16 +8,20 @@ mod extended_url_frame;
 mod key_value_frame;
 pub(in crate::id3::v2) mod language_frame;
 mod ownership_frame;
+pub(in crate::id3::v2) mod play_counter;
 mod popularimeter;
 mod private_frame;
 mod relative_volume_adjustment_frame;
+mod seek_frame;
 mod sync_text;
+mod table_of_contents_frame;
 mod text_information_frame;
 mod unique_file_identifier;
 mod url_link_frame;
 
 pub use attached_picture_frame::AttachedPictureFrame;
 pub use audio_text_frame::{scramble, AudioTextFrame, AudioTextFrameFlags};
+pub use chapter_frame::{ChapterFrame, CHAPTER_OFFSET_UNUSED};
 pub use encapsulated_object::GeneralEncapsulatedObject;
 pub use event_timing_codes_frame::{Event, EventTimingCodesFrame, EventType};
 pub use extended_text_frame::ExtendedTextFrame;
@@ -29,7 +34,9 @@ pub use private_frame::PrivateFrame;
 pub use relative_volume_adjustment_frame::{
 	ChannelInformation, ChannelType, RelativeVolumeAdjustmentFrame,
 };
+pub use seek_frame::SeekFrame;
 pub use sync_text::{SyncTextContentType, SynchronizedText, TimestampFormat};
+pub use table_of_contents_frame::TableOfContentsFrame;
 pub use text_information_frame::TextInformationFrame;
 pub use unique_file_identifier::UniqueFileIdentifierFrame;
 pub use url_link_frame::UrlLinkFrame;
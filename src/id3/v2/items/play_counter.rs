@@ -0,0 +1,68 @@
+use crate::error::Result;
+
+use std::io::Read;
+
+/// Read a play counter, as found in "PCNT" and "POPM" frames
+///
+/// The counter is stored with at least 4 bytes, and grows by a byte whenever it would overflow.
+/// A counter that doesn't fit in a `u64` is saturated to `u64::MAX`, and a missing counter is `0`.
+pub(in crate::id3::v2) fn parse_counter<R>(reader: &mut R) -> Result<u64>
+where
+	R: Read,
+{
+	let mut counter_content = Vec::new();
+	reader.read_to_end(&mut counter_content)?;
+
+	if counter_content.len() > 8 {
+		return Ok(u64::MAX);
+	}
+
+	let mut counter_bytes = [0; 8];
+	let counter_start_pos = 8 - counter_content.len();
+
+	counter_bytes[counter_start_pos..].copy_from_slice(&counter_content);
+	Ok(u64::from_be_bytes(counter_bytes))
+}
+
+/// Convert a play counter into the bytes of a "PCNT" frame, or the end of a "POPM" frame
+pub(in crate::id3::v2) fn counter_bytes(counter: u64) -> Vec<u8> {
+	// When the counter reaches all one's, one byte is inserted in front of the counter
+	// thus making the counter eight bits bigger
+	//
+	// $xx xx xx xx (xx ...)
+	if let Ok(counter) = u32::try_from(counter) {
+		return counter.to_be_bytes().to_vec();
+	}
+
+	let counter_bytes = counter.to_be_bytes();
+	let i = counter_bytes.iter().position(|b| *b != 0).unwrap_or(4);
+
+	counter_bytes[i..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{counter_bytes, parse_counter};
+
+	#[test]
+	fn counter_round_trip() {
+		for (counter, expected) in [
+			(0, &[0, 0, 0, 0][..]),
+			(1, &[0, 0, 0, 1]),
+			(u64::from(u32::MAX), &[0xFF, 0xFF, 0xFF, 0xFF]),
+			(u64::from(u32::MAX) + 1, &[1, 0, 0, 0, 0]),
+			(u64::MAX, &[0xFF; 8]),
+		] {
+			let bytes = counter_bytes(counter);
+			assert_eq!(bytes, expected);
+			assert_eq!(parse_counter(&mut &bytes[..]).unwrap(), counter);
+		}
+	}
+
+	#[test]
+	fn counter_saturates() {
+		assert_eq!(parse_counter(&mut &[1; 9][..]).unwrap(), u64::MAX);
+		assert_eq!(parse_counter(&mut &[][..]).unwrap(), 0);
+		assert_eq!(parse_counter(&mut &[1, 2][..]).unwrap(), 0x0102);
+	}
+}
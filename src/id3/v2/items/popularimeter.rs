@@ -1,3 +1,4 @@
+use super::play_counter::{counter_bytes, parse_counter};
 use crate::error::Result;
 use crate::util::text::{decode_text, encode_text, TextEncoding};
 
@@ -21,7 +22,9 @@ pub struct Popularimeter {
 	pub rating: u8,
 	/// A play counter for the user. It is to be incremented each time the file is played.
 	///
-	/// This is a `u64` for simplicity. It may change if it becomes an issue.
+	/// This is a `u64` for simplicity. It may change if it becomes an issue. The counter is stored
+	/// with at least 4 bytes, a larger counter (or one that doesn't fit in a `u64`) will be saturated to
+	/// `u64::MAX` when reading. A missing counter is read as `0`.
 	pub counter: u64,
 }
 
@@ -39,20 +42,7 @@ impl Popularimeter {
 		let email = decode_text(reader, TextEncoding::Latin1, true)?;
 		let rating = reader.read_u8()?;
 
-		let mut counter_content = Vec::new();
-		reader.read_to_end(&mut counter_content)?;
-
-		let counter;
-		let remaining_size = counter_content.len();
-		if remaining_size > 8 {
-			counter = u64::MAX;
-		} else {
-			let mut counter_bytes = [0; 8];
-			let counter_start_pos = 8 - remaining_size;
-
-			counter_bytes[counter_start_pos..].copy_from_slice(&counter_content);
-			counter = u64::from_be_bytes(counter_bytes);
-		}
+		let counter = parse_counter(reader)?;
 
 		Ok(Self {
 			email: email.content,
@@ -68,19 +58,7 @@ impl Popularimeter {
 		let mut content = Vec::with_capacity(self.email.len() + 9);
 		content.extend(encode_text(self.email.as_str(), TextEncoding::Latin1, true));
 		content.push(self.rating);
-
-		// When the counter reaches all one's, one byte is inserted in front of the counter
-		// thus making the counter eight bits bigger in the same away as the play counter ("PCNT")
-		//
-		// $xx xx xx xx (xx ...)
-		if let Ok(counter) = u32::try_from(self.counter) {
-			content.extend(counter.to_be_bytes())
-		} else {
-			let counter_bytes = self.counter.to_be_bytes();
-			let i = counter_bytes.iter().position(|b| *b != 0).unwrap_or(4);
-
-			content.extend(&counter_bytes[i..]);
-		}
+		content.extend(counter_bytes(self.counter));
 
 		content
 	}
@@ -140,4 +118,25 @@ mod tests {
 		test_popm(&popm_u32_boundary);
 		test_popm(&popm_u40);
 	}
+
+	#[test]
+	fn popm_counter_roundtrip() {
+		for (counter_bytes, counter) in [
+			(&[][..], 0),
+			(&[0, 0, 0, 1][..], 1),
+			(&[1, 0, 0, 0, 0][..], u64::from(u32::MAX) + 1),
+			(&[0xFF; 8][..], u64::MAX),
+		] {
+			let mut bytes = b"MusicBee\0\xFF".to_vec();
+			bytes.extend(counter_bytes);
+
+			let popm = Popularimeter::parse(&mut &bytes[..]).unwrap();
+			assert_eq!(popm.email, "MusicBee");
+			assert_eq!(popm.rating, 255);
+			assert_eq!(popm.counter, counter);
+
+			let reparsed = Popularimeter::parse(&mut &popm.as_bytes()[..]).unwrap();
+			assert_eq!(reparsed.counter, counter);
+		}
+	}
 }
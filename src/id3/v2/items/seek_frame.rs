@@ -0,0 +1,58 @@
+use crate::error::Result;
+
+use std::io::Read;
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+/// An `ID3v2` seek frame
+///
+/// This indicates where another ID3v2 tag can be found in the file, such as one appended
+/// to the end of it. See [`WriteOptions::append_id3v2`](crate::WriteOptions::append_id3v2).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SeekFrame {
+	/// The minimum offset to the next tag, from the end of the tag containing this frame
+	pub offset: u32,
+}
+
+impl SeekFrame {
+	/// Read a [`SeekFrame`]
+	///
+	/// NOTE: This expects the frame header to have already been skipped
+	///
+	/// # Errors
+	///
+	/// * Failure to read from `reader`
+	pub fn parse<R>(reader: &mut R) -> Result<Option<Self>>
+	where
+		R: Read,
+	{
+		let Ok(offset) = reader.read_u32::<BigEndian>() else {
+			return Ok(None);
+		};
+
+		Ok(Some(Self { offset }))
+	}
+
+	/// Convert a [`SeekFrame`] to a byte vec
+	pub fn as_bytes(&self) -> Vec<u8> {
+		self.offset.to_be_bytes().to_vec()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::id3::v2::SeekFrame;
+
+	#[test]
+	fn seek_round_trip() {
+		let frame = SeekFrame {
+			offset: 0x0102_0304,
+		};
+
+		let bytes = frame.as_bytes();
+		assert_eq!(bytes, [1, 2, 3, 4]);
+		assert_eq!(SeekFrame::parse(&mut &bytes[..]).unwrap(), Some(frame));
+
+		assert_eq!(SeekFrame::parse(&mut &[1, 2][..]).unwrap(), None);
+	}
+}
@@ -4,7 +4,7 @@ use crate::util::text::{
 	decode_text, encode_text, read_to_terminator, utf16_decode_bytes, TextEncoding,
 };
 
-use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::io::Write;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 
@@ -105,66 +105,37 @@ impl SynchronizedText {
 		let content_type = SyncTextContentType::from_u8(data[5])
 			.ok_or_else(|| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?;
 
-		let mut cursor = Cursor::new(&data[6..]);
-		let description = crate::util::text::decode_text(&mut cursor, encoding, true)
-			.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?
-			.text_or_none();
-
-		let mut endianness: fn([u8; 2]) -> u16 = u16::from_le_bytes;
-
-		// It's possible for the description to be the only string with a BOM
-		// To be safe, we change the encoding to the concrete variant determined from the description
-		if encoding == TextEncoding::UTF16 {
-			endianness = match cursor.get_ref()[..=1] {
-				[0xFF, 0xFE] => u16::from_le_bytes,
-				[0xFE, 0xFF] => u16::from_be_bytes,
-				// Since the description was already read, we can assume the BOM was valid
-				_ => unreachable!(),
-			};
-		}
+		let mut reader = &data[6..];
+		let description = decode_text(&mut reader, encoding, true)
+			.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?;
 
-		let mut pos = 0;
-		let total = (data.len() - 6) as u64 - cursor.stream_position()?;
+		// It's possible for the description to be the only string with a BOM, in which case the
+		// rest of the text uses its byte order
+		let endianness: fn([u8; 2]) -> u16 = match description.bom {
+			[0xFE, 0xFF] => u16::from_be_bytes,
+			_ => u16::from_le_bytes,
+		};
+		let description = description.text_or_none();
 
 		let mut content = Vec::new();
-
-		while pos < total {
-			let text = (|| -> Result<String> {
-				if encoding == TextEncoding::UTF16 {
-					// Check for a BOM
-					let mut bom = [0; 2];
-					cursor
-						.read_exact(&mut bom)
-						.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?;
-
-					cursor.seek(SeekFrom::Current(-2))?;
-
-					// Encountered text that doesn't include a BOM
-					if bom != [0xFF, 0xFE] && bom != [0xFE, 0xFF] {
-						if let Some(raw_text) = read_to_terminator(&mut cursor, TextEncoding::UTF16)
-						{
-							// text + null terminator
-							pos += (raw_text.len() + 2) as u64;
-
-							return utf16_decode_bytes(&raw_text, endianness)
-								.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText).into());
-						}
-
-						return Ok(String::new());
-					}
+		while !reader.is_empty() {
+			let has_bom = reader.starts_with(&[0xFF, 0xFE]) || reader.starts_with(&[0xFE, 0xFF]);
+
+			let text = if encoding == TextEncoding::UTF16 && !has_bom {
+				match read_to_terminator(&mut reader, TextEncoding::UTF16) {
+					Some(raw_text) => utf16_decode_bytes(&raw_text, endianness)
+						.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?,
+					None => String::new(),
 				}
+			} else {
+				decode_text(&mut reader, encoding, true)
+					.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?
+					.content
+			};
 
-				let decoded_text = decode_text(&mut cursor, encoding, true)
-					.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?;
-				pos += decoded_text.bytes_read as u64;
-
-				Ok(decoded_text.content)
-			})()?;
-
-			let time = cursor
+			let time = reader
 				.read_u32::<BigEndian>()
 				.map_err(|_| Id3v2Error::new(Id3v2ErrorKind::BadSyncText))?;
-			pos += 4;
 
 			content.push((time, text));
 		}
@@ -196,11 +167,8 @@ impl SynchronizedText {
 			data.write_u8(self.timestamp_format as u8)?;
 			data.write_u8(self.content_type as u8)?;
 
-			if let Some(description) = &self.description {
-				data.write_all(&encode_text(description, self.encoding, true))?;
-			} else {
-				data.write_u8(0)?;
-			}
+			let description = self.description.as_deref().unwrap_or_default();
+			data.write_all(&encode_text(description, self.encoding, true))?;
 
 			for (time, ref text) in &self.content {
 				data.write_all(&encode_text(text, self.encoding, true))?;
@@ -278,4 +246,45 @@ mod tests {
 
 		assert_eq!(encoded, expected_bytes);
 	}
+
+	#[test]
+	fn sylt_roundtrip_all_encodings() {
+		for encoding in [
+			TextEncoding::Latin1,
+			TextEncoding::UTF16,
+			TextEncoding::UTF16BE,
+			TextEncoding::UTF8,
+		] {
+			let mut sylt = expected(encoding);
+			sylt.timestamp_format = TimestampFormat::MPEG;
+
+			let parsed = SynchronizedText::parse(&sylt.as_bytes().unwrap()).unwrap();
+			assert_eq!(parsed, sylt, "{encoding:?}");
+		}
+	}
+
+	#[test]
+	fn sylt_roundtrip_no_description() {
+		for encoding in [TextEncoding::Latin1, TextEncoding::UTF16] {
+			let mut sylt = expected(encoding);
+			sylt.description = None;
+			sylt.content.push((40000, String::new()));
+
+			let parsed = SynchronizedText::parse(&sylt.as_bytes().unwrap()).unwrap();
+			assert_eq!(parsed, sylt, "{encoding:?}");
+		}
+	}
+
+	#[test]
+	fn sylt_decode_malformed() {
+		let encoded = expected(TextEncoding::UTF16).as_bytes().unwrap();
+
+		// Every truncation that cuts into an entry should be rejected, rather than panicking
+		for len in 0..encoded.len() {
+			let _ = SynchronizedText::parse(&encoded[..len]);
+		}
+
+		assert!(SynchronizedText::parse(&encoded[..encoded.len() - 1]).is_err());
+		assert!(SynchronizedText::parse(b"\x00eng\x02\x01\x00Lyric\x00\x00\x00").is_err());
+	}
 }
@@ -0,0 +1,200 @@
+use crate::error::Result;
+use crate::id3::v2::frame::read::parse_embedded_frames;
+use crate::id3::v2::frame::Frame;
+use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::write::create_embedded_frames;
+use crate::probe::ParsingMode;
+use crate::util::text::{decode_text, encode_text, TextEncoding};
+use crate::write_options::WriteOptions;
+
+use std::io::Read;
+
+use byteorder::ReadBytesExt;
+
+const ORDERED_FLAG: u8 = 0x01;
+const TOP_LEVEL_FLAG: u8 = 0x02;
+
+/// An `ID3v2` table of contents frame
+///
+/// This lists the chapters of the audio, see the [ID3v2 Chapter Frame Addendum](https://id3.org/id3v2-chapters-1.0).
+///
+/// The child elements are referenced by their element IDs, and can be [`ChapterFrame`](crate::id3::v2::ChapterFrame)s
+/// or other tables of contents, allowing for nested tables of contents. There should only be a
+/// single top-level table of contents in a tag.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TableOfContentsFrame {
+	/// A unique identifier for the table of contents within the tag, not meant to be displayed
+	pub element_id: String,
+	/// Whether this is the root of the tables of contents
+	pub top_level: bool,
+	/// Whether the child elements are in order
+	pub ordered: bool,
+	/// The element IDs of the chapters and tables of contents this contains
+	pub child_element_ids: Vec<String>,
+	/// The frames describing the table of contents, usually a `TIT2`
+	pub frames: Vec<Frame<'static>>,
+}
+
+impl TableOfContentsFrame {
+	/// Read a [`TableOfContentsFrame`]
+	///
+	/// NOTE: This expects the frame header to have already been skipped
+	///
+	/// The embedded frames are expected to be laid out as they are in an `ID3v2` tag of `version`.
+	///
+	/// # Errors
+	///
+	/// * Failure to read from `reader`
+	/// * An embedded frame is invalid, or is another chapter frame, with [`ParsingMode::Strict`]
+	pub fn parse<R>(
+		reader: &mut R,
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read,
+	{
+		let mut content = Vec::new();
+		reader.read_to_end(&mut content)?;
+
+		Self::parse_slice(&content, version, parse_mode)
+	}
+
+	// The embedded frames are parsed from `content` in place
+	pub(in crate::id3::v2) fn parse_slice(
+		mut content: &[u8],
+		version: Id3v2Version,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>> {
+		let reader = &mut content;
+
+		let Ok(element_id) = decode_text(reader, TextEncoding::Latin1, true) else {
+			return Ok(None);
+		};
+
+		let (Ok(flags), Ok(entry_count)) = (reader.read_u8(), reader.read_u8()) else {
+			return Ok(None);
+		};
+
+		let mut child_element_ids = Vec::with_capacity(usize::from(entry_count));
+		for _ in 0..entry_count {
+			let child_element_id = match decode_text(reader, TextEncoding::Latin1, true) {
+				// Nothing left to read, the frame is missing some of its child elements
+				Ok(child_element_id) if child_element_id.bytes_read > 0 => child_element_id,
+				_ => return Ok(None),
+			};
+
+			child_element_ids.push(child_element_id.content);
+		}
+
+		Ok(Some(TableOfContentsFrame {
+			element_id: element_id.content,
+			top_level: flags & TOP_LEVEL_FLAG == TOP_LEVEL_FLAG,
+			ordered: flags & ORDERED_FLAG == ORDERED_FLAG,
+			child_element_ids,
+			frames: parse_embedded_frames(content, version, parse_mode)?,
+		}))
+	}
+
+	/// Convert a [`TableOfContentsFrame`] to a byte vec
+	///
+	/// The embedded frames are written for the `ID3v2` version specified in `write_options`.
+	///
+	/// NOTE: Only the first 255 child elements will be written
+	///
+	/// # Errors
+	///
+	/// * An embedded frame is invalid, or too large
+	pub fn as_bytes(&self, write_options: WriteOptions) -> Result<Vec<u8>> {
+		let mut content = encode_text(&self.element_id, TextEncoding::Latin1, true);
+
+		let mut flags = 0;
+		if self.top_level {
+			flags |= TOP_LEVEL_FLAG;
+		}
+		if self.ordered {
+			flags |= ORDERED_FLAG;
+		}
+		content.push(flags);
+
+		let child_element_ids = &self.child_element_ids[..self.child_element_ids.len().min(255)];
+		if child_element_ids.len() < self.child_element_ids.len() {
+			log::warn!(
+				"Table of contents `{}` has more than 255 child elements, discarding the rest",
+				self.element_id
+			);
+		}
+
+		content.push(child_element_ids.len() as u8);
+		for child_element_id in child_element_ids {
+			content.extend(encode_text(child_element_id, TextEncoding::Latin1, true));
+		}
+
+		content.extend(create_embedded_frames(&self.frames, write_options)?);
+
+		Ok(content)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::id3::v2::header::Id3v2Version;
+	use crate::id3::v2::{Frame, FrameFlags, TableOfContentsFrame, TextInformationFrame};
+	use crate::util::text::TextEncoding;
+	use crate::{ParsingMode, WriteOptions};
+
+	fn expected() -> TableOfContentsFrame {
+		TableOfContentsFrame {
+			element_id: String::from("toc"),
+			top_level: true,
+			ordered: true,
+			child_element_ids: vec![String::from("chp0"), String::from("toc1")],
+			frames: vec![Frame::new(
+				"TIT2",
+				TextInformationFrame {
+					encoding: TextEncoding::Latin1,
+					value: String::from("Contents"),
+				},
+				FrameFlags::default(),
+			)
+			.unwrap()],
+		}
+	}
+
+	fn expected_bytes() -> Vec<u8> {
+		let mut bytes = b"toc\0\x03\x02chp0\0toc1\0".to_vec();
+		bytes.extend(b"TIT2\0\0\0\x09\0\0\0Contents");
+		bytes
+	}
+
+	#[test]
+	fn ctoc_decode() {
+		let parsed = TableOfContentsFrame::parse(
+			&mut &expected_bytes()[..],
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap()
+		.unwrap();
+
+		assert_eq!(parsed, expected());
+	}
+
+	#[test]
+	fn ctoc_encode() {
+		let encoded = expected().as_bytes(WriteOptions::default()).unwrap();
+		assert_eq!(encoded, expected_bytes());
+	}
+
+	#[test]
+	fn ctoc_missing_child_elements() {
+		let parsed = TableOfContentsFrame::parse(
+			&mut &b"toc\0\x03\x02chp0\0"[..],
+			Id3v2Version::V4,
+			ParsingMode::Strict,
+		)
+		.unwrap();
+
+		assert!(parsed.is_none());
+	}
+}
@@ -1,7 +1,7 @@
 use crate::error::Result;
 use crate::id3::v2::frame::content::verify_encoding;
 use crate::id3::v2::header::Id3v2Version;
-use crate::util::text::{decode_text, encode_text, TextEncoding};
+use crate::util::text::{decode_text, decode_text_slice, encode_text, TextEncoding};
 
 use byteorder::ReadBytesExt;
 
@@ -42,6 +42,43 @@ impl TextInformationFrame {
 		Ok(Some(TextInformationFrame { encoding, value }))
 	}
 
+	/// Same as [`TextInformationFrame::parse`], decoding the text directly from the frame content
+	pub(crate) fn parse_slice(content: &[u8], version: Id3v2Version) -> Result<Option<Self>> {
+		let Some((&encoding_byte, text)) = content.split_first() else {
+			return Ok(None);
+		};
+
+		let encoding = verify_encoding(encoding_byte, version)?;
+		let value = decode_text_slice(text, encoding)?;
+
+		Ok(Some(TextInformationFrame { encoding, value }))
+	}
+
+	/// The values of the frame
+	///
+	/// ID3v2.4 allows for multiple values in a single text frame, separated by null (`'\0'`) characters.
+	/// Frames from older versions will typically hold a single value.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::TextInformationFrame;
+	/// use lofty::TextEncoding;
+	///
+	/// let frame = TextInformationFrame {
+	/// 	encoding: TextEncoding::UTF8,
+	/// 	value: String::from("Foo artist\0Bar artist"),
+	/// };
+	///
+	/// assert_eq!(
+	/// 	frame.values().collect::<Vec<_>>(),
+	/// 	["Foo artist", "Bar artist"]
+	/// );
+	/// ```
+	pub fn values(&self) -> impl Iterator<Item = &str> + Clone {
+		self.value.split('\0')
+	}
+
 	/// Convert an [`TextInformationFrame`] to a byte vec
 	pub fn as_bytes(&self) -> Vec<u8> {
 		let mut content = encode_text(&self.value, self.encoding, false);
@@ -0,0 +1,515 @@
+use super::frame::{Frame, FrameValue};
+use super::items::{
+	CommentFrame, ExtendedTextFrame, TextInformationFrame, UnsynchronizedTextFrame,
+};
+use super::restrictions::{TagRestrictions, TextSizeRestrictions};
+use super::tag::Id3v2Tag;
+use super::write::tag_overhead;
+use crate::error::Result;
+use crate::traits::TagExt;
+use crate::util::text::{truncate_to_fit, TextEncoding};
+use crate::write_options::WriteOptions;
+
+// Text fields will never be truncated below this many characters to save space,
+// matching the strictest text size restriction
+const MIN_TRUNCATED_TEXT_LEN: usize = 30;
+
+// The frames to keep for as long as possible when dropping frames, most important first
+const FRAME_PRIORITY: &[&str] = &[
+	"TIT2", "TPE1", "TALB", "TRCK", "TPOS", "TDRC", "TCON", "TPE2", "TCOM", "TSRC", "USLT", "COMM",
+];
+
+/// Limits on the size of an ID3v2 tag
+///
+/// See [`Id3v2Tag::fit_to_limits`].
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TagLimits {
+	pub(crate) size: Option<usize>,
+	pub(crate) frame_count: Option<usize>,
+	pub(crate) text_len: Option<usize>,
+}
+
+impl TagLimits {
+	/// Creates a new `TagLimits`, with no limits
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::TagLimits;
+	///
+	/// let limits = TagLimits::new();
+	/// ```
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			size: None,
+			frame_count: None,
+			text_len: None,
+		}
+	}
+
+	/// The maximum size of the tag in bytes, including its header
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::TagLimits;
+	///
+	/// // My device can only read 40 KB
+	/// let limits = TagLimits::new().max_size(40_000);
+	/// ```
+	pub fn max_size(&mut self, max_size: usize) -> Self {
+		self.size = Some(max_size);
+		*self
+	}
+
+	/// The maximum number of frames in the tag
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::TagLimits;
+	///
+	/// let limits = TagLimits::new().max_frames(32);
+	/// ```
+	pub fn max_frames(&mut self, max_frames: usize) -> Self {
+		self.frame_count = Some(max_frames);
+		*self
+	}
+
+	/// The maximum number of characters in a text field
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::TagLimits;
+	///
+	/// let limits = TagLimits::new().max_text_len(128);
+	/// ```
+	pub fn max_text_len(&mut self, max_text_len: usize) -> Self {
+		self.text_len = Some(max_text_len);
+		*self
+	}
+}
+
+impl From<TagRestrictions> for TagLimits {
+	/// Converts the size and text field size restrictions into `TagLimits`
+	///
+	/// NOTE: The encoding and image restrictions are not considered.
+	fn from(restrictions: TagRestrictions) -> Self {
+		Self {
+			size: Some(restrictions.size.max_size()),
+			frame_count: Some(restrictions.size.max_frames()),
+			text_len: restrictions
+				.text_fields_size
+				.map(TextSizeRestrictions::max_len),
+		}
+	}
+}
+
+/// What was changed to fit an ID3v2 tag within its [`TagLimits`]
+///
+/// See [`Id3v2Tag::fit_to_limits`].
+#[derive(Default, Clone, Debug)]
+#[non_exhaustive]
+pub struct FitReport {
+	/// The frames that were removed, in the order they were removed
+	pub removed: Vec<Frame<'static>>,
+	/// The original contents of the frames that were truncated, and kept
+	pub truncated: Vec<Frame<'static>>,
+}
+
+impl FitReport {
+	/// Whether the tag was left unchanged
+	pub fn is_empty(&self) -> bool {
+		self.removed.is_empty() && self.truncated.is_empty()
+	}
+}
+
+struct Planner<'a> {
+	tag: &'a mut Id3v2Tag,
+	limits: TagLimits,
+	write_options: WriteOptions,
+	overhead: usize,
+	// The sizes of each frame when written
+	sizes: Vec<usize>,
+	// The original frames, for those that were truncated
+	originals: Vec<Option<Frame<'static>>>,
+	report: FitReport,
+}
+
+impl Planner<'_> {
+	fn size(&self) -> usize {
+		self.overhead + self.sizes.iter().sum::<usize>()
+	}
+
+	// Frames that won't be written aren't counted
+	fn frame_count(&self) -> usize {
+		self.sizes.iter().filter(|size| **size > 0).count()
+	}
+
+	fn excess_size(&self) -> usize {
+		match self.limits.size {
+			// An empty tag isn't written at all
+			Some(_) if self.frame_count() == 0 => 0,
+			Some(max_size) => self.size().saturating_sub(max_size),
+			None => 0,
+		}
+	}
+
+	fn fits(&self) -> bool {
+		let frame_count_fits = self
+			.limits
+			.frame_count
+			.is_none_or(|max_frames| self.frame_count() <= max_frames);
+
+		frame_count_fits && self.excess_size() == 0
+	}
+
+	fn remove(&mut self, idx: usize) {
+		let frame = self.tag.frames.remove(idx);
+		self.sizes.remove(idx);
+
+		let original = self.originals.remove(idx);
+		self.report.removed.push(original.unwrap_or(frame));
+	}
+
+	// Frames not in `FRAME_PRIORITY` are the least important, and later frames are removed first
+	fn remove_least_important(&mut self) {
+		let idx = (0..self.tag.frames.len())
+			.filter(|idx| self.sizes[*idx] > 0)
+			.max_by_key(|idx| {
+				let id = self.tag.frames[*idx].id_str();
+				FRAME_PRIORITY
+					.iter()
+					.position(|p| *p == id)
+					.unwrap_or(FRAME_PRIORITY.len())
+			})
+			.expect("a tag that doesn't fit should have frames");
+
+		self.remove(idx);
+	}
+
+	fn truncate(&mut self, idx: usize, max_len: usize) -> Result<bool> {
+		let frame = &mut self.tag.frames[idx];
+		if is_cuesheet(&frame.value) {
+			return Ok(false);
+		}
+
+		let Some(text) = text_mut(&mut frame.value) else {
+			return Ok(false);
+		};
+
+		// The limits are in characters, which is the same as the Latin-1 size
+		let end = truncate_to_fit(text, max_len, TextEncoding::Latin1).len();
+		if end == text.len() {
+			return Ok(false);
+		}
+
+		let original = frame.clone();
+		text_mut(&mut frame.value)
+			.expect("text field should exist")
+			.truncate(end);
+
+		self.sizes[idx] = frame.encoded_size(self.write_options)?;
+		self.originals[idx].get_or_insert(original);
+
+		Ok(true)
+	}
+
+	fn fit(mut self) -> Result<FitReport> {
+		// Step 1: Text field size limits always apply
+		if let Some(max_text_len) = self.limits.text_len {
+			for idx in 0..self.tag.frames.len() {
+				self.truncate(idx, max_text_len)?;
+			}
+		}
+
+		// Step 2: Remove pictures, starting from the last
+		while !self.fits() {
+			let Some(idx) = self
+				.tag
+				.frames
+				.iter()
+				.rposition(|frame| matches!(frame.value, FrameValue::Picture(_)))
+			else {
+				break;
+			};
+
+			self.remove(idx);
+		}
+
+		// Step 3: Truncate text fields, starting from the largest
+		while self.excess_size() > 0 {
+			let largest = (0..self.tag.frames.len())
+				.filter(|idx| {
+					let value = &self.tag.frames[*idx].value;
+					!is_cuesheet(value)
+						&& text(value)
+							.is_some_and(|text| text.chars().count() > MIN_TRUNCATED_TEXT_LEN)
+				})
+				.max_by_key(|idx| self.sizes[*idx]);
+
+			let Some(idx) = largest else {
+				break;
+			};
+
+			// Every character takes at least one byte, so this removes at least enough bytes to fit
+			let len = text(&self.tag.frames[idx].value).map_or(0, |text| text.chars().count());
+			let max_len = len
+				.saturating_sub(self.excess_size())
+				.max(MIN_TRUNCATED_TEXT_LEN);
+
+			self.truncate(idx, max_len)?;
+		}
+
+		// Step 4: Remove frames, starting with the least important
+		while !self.fits() {
+			self.remove_least_important();
+		}
+
+		// Some frames are derived from others while writing (e.g. `TBPM` from `TXXX:BPM`), so the
+		// actual size may differ slightly from our estimate
+		if let Some(max_size) = self.limits.size {
+			loop {
+				let mut tag_bytes = Vec::new();
+				self.tag.dump_to(&mut tag_bytes, self.write_options)?;
+
+				if tag_bytes.len() <= max_size {
+					break;
+				}
+
+				self.remove_least_important();
+			}
+		}
+
+		self.report.truncated = self.originals.into_iter().flatten().collect();
+		Ok(self.report)
+	}
+}
+
+// Cue sheets are never truncated, only removed whole
+pub(super) fn is_cuesheet(value: &FrameValue) -> bool {
+	matches!(value, FrameValue::UserText(frame) if frame.is_cuesheet())
+}
+
+pub(super) fn text(value: &FrameValue) -> Option<&str> {
+	match value {
+		FrameValue::Text(TextInformationFrame { value, .. })
+		| FrameValue::UserText(ExtendedTextFrame { content: value, .. })
+		| FrameValue::Comment(CommentFrame { content: value, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { content: value, .. }) => Some(value),
+		_ => None,
+	}
+}
+
+fn text_mut(value: &mut FrameValue) -> Option<&mut String> {
+	match value {
+		FrameValue::Text(TextInformationFrame { value, .. })
+		| FrameValue::UserText(ExtendedTextFrame { content: value, .. })
+		| FrameValue::Comment(CommentFrame { content: value, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { content: value, .. }) => Some(value),
+		_ => None,
+	}
+}
+
+pub(super) fn fit_to_limits(
+	tag: &mut Id3v2Tag,
+	limits: TagLimits,
+	write_options: WriteOptions,
+) -> Result<FitReport> {
+	let overhead = tag_overhead(*tag.flags(), write_options)?;
+	let sizes = tag
+		.frames
+		.iter()
+		.map(|frame| frame.encoded_size(write_options))
+		.collect::<Result<Vec<_>>>()?;
+	let originals = vec![None; tag.frames.len()];
+
+	Planner {
+		tag,
+		limits,
+		write_options,
+		overhead,
+		sizes,
+		originals,
+		report: FitReport::default(),
+	}
+	.fit()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::id3::v2::{FrameValue, Id3v2Tag, TagLimits};
+	use crate::{Accessor as _, MimeType, Picture, PictureType, TagExt as _, WriteOptions};
+
+	fn written_size(tag: &Id3v2Tag) -> usize {
+		let mut tag_bytes = Vec::new();
+		tag.dump_to(&mut tag_bytes, WriteOptions::default())
+			.unwrap();
+		tag_bytes.len()
+	}
+
+	fn picture(pic_type: PictureType, len: usize) -> Picture {
+		Picture::new_unchecked(pic_type, Some(MimeType::Png), None, vec![0; len])
+	}
+
+	#[test]
+	fn within_limits() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.set_artist(String::from("Bar artist"));
+
+		let original = tag.clone();
+		let report = tag
+			.fit_to_limits(
+				TagLimits::new().max_size(1024).max_frames(2),
+				WriteOptions::default(),
+			)
+			.unwrap();
+
+		assert!(report.is_empty());
+		assert_eq!(tag, original);
+	}
+
+	#[test]
+	fn max_text_len() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.set_artist("🎵".repeat(40));
+
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_text_len(30), WriteOptions::default())
+			.unwrap();
+
+		assert!(report.removed.is_empty());
+		assert_eq!(report.truncated.len(), 1);
+		assert_eq!(report.truncated[0].id_str(), "TPE1");
+
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some(&*"🎵".repeat(30)));
+	}
+
+	#[test]
+	fn cuesheet_removed_whole() {
+		let cuesheet = "TRACK 01 AUDIO\nINDEX 01 00:00:00\n".repeat(64);
+
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.insert_user_text(String::from("CUESHEET"), cuesheet.clone());
+
+		// Text length limits don't apply
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_text_len(30), WriteOptions::default())
+			.unwrap();
+		assert!(report.is_empty());
+		assert_eq!(tag.get_user_text("CUESHEET"), Some(&*cuesheet));
+
+		// Rather than truncating it to fit, it's removed
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_size(512), WriteOptions::default())
+			.unwrap();
+		assert!(report.truncated.is_empty());
+		assert_eq!(report.removed.len(), 1);
+		assert!(matches!(
+			&report.removed[0].value,
+			FrameValue::UserText(frame) if frame.content == cuesheet
+		));
+
+		assert_eq!(tag.get_user_text("CUESHEET"), None);
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+	}
+
+	#[test]
+	fn pictures_removed_first() {
+		let mut tag = Id3v2Tag::default();
+		tag.insert_picture(picture(PictureType::CoverFront, 2048));
+		tag.set_title(String::from("Foo title"));
+		tag.set_comment("A".repeat(1024));
+		tag.insert_picture(picture(PictureType::CoverBack, 2048));
+
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_size(3500), WriteOptions::default())
+			.unwrap();
+
+		// Only the last picture needs to go
+		assert_eq!(report.removed.len(), 1);
+		assert!(matches!(
+			&report.removed[0].value,
+			FrameValue::Picture(frame) if frame.picture.pic_type() == PictureType::CoverBack
+		));
+		assert!(report.truncated.is_empty());
+
+		assert_eq!(tag.len(), 3);
+		assert!(written_size(&tag) <= 3500);
+	}
+
+	#[test]
+	fn text_truncated_before_frames_removed() {
+		let mut tag = Id3v2Tag::default();
+		tag.insert_picture(picture(PictureType::CoverFront, 2048));
+		tag.set_title(String::from("Foo title"));
+		tag.set_comment("A".repeat(1024));
+		tag.insert_user_text(String::from("FOO"), String::from("Bar"));
+
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_size(512), WriteOptions::default())
+			.unwrap();
+
+		assert_eq!(report.removed.len(), 1);
+		assert_eq!(report.removed[0].id_str(), "APIC");
+
+		// The original comment is kept in the report
+		assert_eq!(report.truncated.len(), 1);
+		assert_eq!(report.truncated[0].id_str(), "COMM");
+		let FrameValue::Comment(comment) = &report.truncated[0].value else {
+			unreachable!()
+		};
+		assert_eq!(comment.content.len(), 1024);
+
+		assert_eq!(tag.len(), 3);
+		assert!(tag.comment().unwrap().len() < 1024);
+		assert_eq!(tag.get_user_text("FOO"), Some("Bar"));
+
+		let size = written_size(&tag);
+		assert!(size <= 512);
+		// Only as much as needed was truncated
+		assert!(size > 500);
+	}
+
+	#[test]
+	fn frames_removed_by_priority() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_comment(String::from("Foo comment"));
+		for i in 0..40 {
+			tag.insert_user_text(format!("FOO{i}"), String::from("Bar"));
+		}
+		tag.set_title(String::from("Foo title"));
+		tag.set_artist(String::from("Bar artist"));
+
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_frames(32), WriteOptions::default())
+			.unwrap();
+
+		assert_eq!(report.removed.len(), 11);
+		assert_eq!(tag.len(), 32);
+
+		// The last user text frames are removed first
+		assert_eq!(tag.get_user_text("FOO28"), Some("Bar"));
+		assert_eq!(tag.get_user_text("FOO29"), None);
+		assert_eq!(tag.comment().as_deref(), Some("Foo comment"));
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+
+		// Once only the important frames are left, they go in reverse order of importance
+		let report = tag
+			.fit_to_limits(TagLimits::new().max_frames(2), WriteOptions::default())
+			.unwrap();
+
+		assert_eq!(report.removed.last().unwrap().id_str(), "COMM");
+		assert_eq!(tag.len(), 2);
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+	}
+}
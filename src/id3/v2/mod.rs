@@ -10,6 +10,7 @@
 mod frame;
 pub(crate) mod header;
 mod items;
+mod limits;
 pub(crate) mod read;
 mod restrictions;
 pub(crate) mod tag;
@@ -18,16 +19,19 @@ pub(crate) mod write;
 
 // Exports
 
-pub use header::{Id3v2TagFlags, Id3v2Version};
+pub use header::{Id3v2Crc, Id3v2TagFlags, Id3v2Version};
 pub use util::upgrade::{upgrade_v2, upgrade_v3};
 
-pub use tag::Id3v2Tag;
+pub use tag::{DuplicateBehavior, Id3v2Tag};
 
 pub use items::*;
 
 pub use frame::id::FrameId;
 pub use frame::{Frame, FrameFlags, FrameValue};
 
+pub use limits::{FitReport, TagLimits};
+
 pub use restrictions::{
-	ImageSizeRestrictions, TagRestrictions, TagSizeRestrictions, TextSizeRestrictions,
+	ImageSizeRestrictions, RestrictionViolation, TagRestrictions, TagSizeRestrictions,
+	TextSizeRestrictions,
 };
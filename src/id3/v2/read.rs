@@ -1,39 +1,116 @@
 use super::frame::read::ParsedFrame;
-use super::header::Id3v2Header;
-use super::tag::Id3v2Tag;
+use super::frame::{Frame, FrameValue};
+use super::header::{Id3v2Crc, Id3v2Header, Id3v2Version};
+use super::items::TextInformationFrame;
+use super::tag::{Id3v2Tag, ONE_PER_TAG_FRAMES};
+use super::FrameId;
 use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
 use crate::id3::v2::util::synchsafe::UnsynchronizedStream;
-use crate::probe::ParsingMode;
+use crate::id3::v2::write::crc32;
+use crate::probe::{ParseOptions, ParsingMode};
 
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
 use std::io::Read;
 
+// Used to estimate the number of frames in a tag, to avoid repeatedly growing the frame list
+const ESTIMATED_FRAME_SIZE: u64 = 64;
+
 pub(crate) fn parse_id3v2<R>(
 	bytes: &mut R,
 	header: Id3v2Header,
-	parse_mode: ParsingMode,
+	parse_options: ParseOptions,
 ) -> Result<Id3v2Tag>
 where
 	R: Read,
 {
-	let mut tag_bytes = bytes.take(u64::from(header.size - header.extended_size));
+	// A malformed extended header has already been skipped, which is only acceptable
+	// if we aren't being strict
+	if header.bad_extended_header && parse_options.parsing_mode == ParsingMode::Strict {
+		return Err(Id3v2Error::new(Id3v2ErrorKind::BadExtendedHeaderSize).into());
+	}
 
-	let ret;
-	if header.flags.unsynchronisation {
-		// Unsynchronize the entire tag
-		let mut unsynchronized_reader = UnsynchronizedStream::new(tag_bytes);
-		ret = read_all_frames_into_tag(&mut unsynchronized_reader, header, parse_mode)?;
+	let tag_size = u64::from(header.size - header.extended_size);
+	let mut tag_bytes = bytes.take(tag_size);
+
+	// The size of the tag is known ahead of time, so as long as it fits within the allocation
+	// limit, read it all at once rather than going back to the reader for every frame.
+	let ret = if tag_size <= parse_options.allocation_limit as u64 {
+		let mut content = Vec::new();
+		content.try_reserve_exact(tag_size as usize)?;
+		tag_bytes.read_to_end(&mut content)?;
+
+		let mut tag = read_all_frames(&mut &*content, tag_size, header, parse_options)?;
+		tag.crc = header.crc.map(|stored| Id3v2Crc {
+			stored,
+			calculated: calculate_crc(&content, header),
+		});
 
-		// Get the `Take` back from the `UnsynchronizedStream`
-		tag_bytes = unsynchronized_reader.into_inner();
+		tag
 	} else {
-		ret = read_all_frames_into_tag(&mut tag_bytes, header, parse_mode)?;
+		let mut tag = read_all_frames(&mut tag_bytes, tag_size, header, parse_options)?;
+		tag.crc = header.crc.map(|stored| Id3v2Crc {
+			stored,
+			calculated: None,
+		});
+
+		tag
 	};
 
+	if ret.crc.and_then(|crc| crc.is_valid()) == Some(false) {
+		log::warn!("ID3v2 tag CRC-32 doesn't match its frame data");
+	}
+
 	// Throw away the rest of the tag (padding, bad frames)
 	std::io::copy(&mut tag_bytes, &mut std::io::sink())?;
 	Ok(ret)
 }
 
+// ID3v2.4 covers everything following the extended header, ID3v2.3 excludes the padding and is
+// calculated prior to unsynchronisation
+fn calculate_crc(content: &[u8], header: Id3v2Header) -> Option<u32> {
+	if header.version != Id3v2Version::V3 {
+		return Some(crc32(content));
+	}
+
+	let content = &content[..content.len().saturating_sub(header.padding_size as usize)];
+	if !header.flags.unsynchronisation {
+		return Some(crc32(content));
+	}
+
+	let mut synchronized = Vec::new();
+	UnsynchronizedStream::new(content)
+		.read_to_end(&mut synchronized)
+		.ok()?;
+	Some(crc32(&synchronized))
+}
+
+fn read_all_frames<R>(
+	reader: &mut R,
+	tag_size: u64,
+	header: Id3v2Header,
+	parse_options: ParseOptions,
+) -> Result<Id3v2Tag>
+where
+	R: Read,
+{
+	// ID3v2.4 unsynchronises each frame individually, see `ParsedFrame::read`
+	if header.flags.unsynchronisation && header.version != Id3v2Version::V4 {
+		// Unsynchronize the entire tag
+		let mut unsynchronized_reader = UnsynchronizedStream::new(reader);
+		return read_all_frames_into_tag(
+			&mut unsynchronized_reader,
+			tag_size,
+			header,
+			parse_options,
+		);
+	}
+
+	read_all_frames_into_tag(reader, tag_size, header, parse_options)
+}
+
 fn skip_frame(reader: &mut impl Read, size: u32) -> Result<()> {
 	let size = u64::from(size);
 	let mut reader = reader.take(size);
@@ -47,19 +124,59 @@ fn skip_frame(reader: &mut impl Read, size: u32) -> Result<()> {
 
 fn read_all_frames_into_tag<R>(
 	reader: &mut R,
+	tag_size: u64,
 	header: Id3v2Header,
-	parse_mode: ParsingMode,
+	parse_options: ParseOptions,
 ) -> Result<Id3v2Tag>
 where
 	R: Read,
 {
+	let max_frames = parse_options.max_id3v2_frames;
+
 	let mut tag = Id3v2Tag::default();
 	tag.original_version = header.version;
 	tag.set_flags(header.flags);
 
+	let estimated_frame_count = ((tag_size / ESTIMATED_FRAME_SIZE) as usize).min(max_frames);
+	tag.frames.reserve(estimated_frame_count);
+
+	// Hashes of all frames read so far, see below
+	let hash_builder = RandomState::new();
+	let mut frame_hashes = HashSet::with_capacity(estimated_frame_count);
+
+	// Shared between all frames, see `ParsedFrame::read`
+	let mut content = Vec::new();
+
+	let mut frame_count = 0;
 	loop {
-		match ParsedFrame::read(reader, header.version, parse_mode)? {
-			ParsedFrame::Next(frame) => drop(tag.insert(frame)),
+		match ParsedFrame::read(
+			reader,
+			&mut content,
+			header.version,
+			header.flags.unsynchronisation,
+			parse_options.parsing_mode,
+		)? {
+			ParsedFrame::Next(frame) => {
+				// A tag made up of an absurd number of frames needs to be cut off
+				if frame_count == max_frames {
+					log::warn!(
+						"ID3v2 tag exceeds the maximum of {max_frames} frames, skipping the \
+						 remaining frames"
+					);
+					break;
+				}
+
+				// Only a frame equal to one that was already read can replace it, so there's
+				// no need to search the entire tag for every new frame
+				let unique = frame_hashes.insert(hash_builder.hash_one(&frame));
+				if unique && !ONE_PER_TAG_FRAMES.contains(&frame.id_str()) {
+					tag.frames.push(frame);
+				} else {
+					drop(tag.insert(frame));
+				}
+
+				frame_count += 1;
+			},
 			// No frame content found or ignored due to errors, but we can expect more frames
 			ParsedFrame::Skip { size } => {
 				skip_frame(reader, size)?;
@@ -69,24 +186,106 @@ where
 		}
 	}
 
+	if header.version == Id3v2Version::V3 {
+		merge_v3_recording_time(&mut tag);
+	}
+
+	if parse_options.parsing_mode == ParsingMode::Strict {
+		tag.verify_timestamps()?;
+	}
+
+	if parse_options.resolve_id3v2_genres {
+		tag.resolve_genres();
+	}
+
 	Ok(tag)
 }
 
+// ID3v2.3 spreads the recording time across `TYER` (already upgraded to `TDRC`), `TDAT` (DDMM),
+// and `TIME` (HHMM), which are merged into a single ID3v2.4 timestamp
+fn merge_v3_recording_time(tag: &mut Id3v2Tag) {
+	let date_time = |id: &'static str| {
+		tag.get_text(&FrameId::Valid(Cow::Borrowed(id)))
+			.filter(|text| text.len() == 4 && text.bytes().all(|b| b.is_ascii_digit()))
+			.map(Cow::into_owned)
+	};
+
+	let Some(date) = date_time("TDAT") else {
+		return;
+	};
+	let time = date_time("TIME");
+
+	let Some(Frame {
+		value: FrameValue::Text(TextInformationFrame { value: year, .. }),
+		..
+	}) = tag.frames.iter_mut().find(|frame| frame.id_str() == "TDRC")
+	else {
+		return;
+	};
+
+	if year.len() != 4 || !year.bytes().all(|b| b.is_ascii_digit()) {
+		return;
+	}
+
+	*year = match &time {
+		Some(time) => format!(
+			"{year}-{}-{}T{}:{}",
+			&date[2..],
+			&date[..2],
+			&time[..2],
+			&time[2..]
+		),
+		None => format!("{year}-{}-{}", &date[2..], &date[..2]),
+	};
+
+	let _ = tag.remove(&FrameId::Valid(Cow::Borrowed("TDAT")));
+	if time.is_some() {
+		let _ = tag.remove(&FrameId::Valid(Cow::Borrowed("TIME")));
+	}
+}
+
+// Combines a tag appended to the end of a file with the one at the start of it, if any
+//
+// The appended tag is only used if the leading tag points to it with a `SEEK` frame, in which
+// case its frames take precedence. Otherwise, the leading tag is the one we write to.
+pub(crate) fn merge_appended_id3v2(leading: &mut Option<Id3v2Tag>, appended: Id3v2Tag) {
+	match leading {
+		None => *leading = Some(appended),
+		Some(leading)
+			if leading
+				.get(&FrameId::Valid(Cow::Borrowed("SEEK")))
+				.is_some() =>
+		{
+			for frame in appended.frames {
+				leading.insert(frame);
+			}
+		},
+		Some(_) => {
+			log::warn!("Ignoring an appended ID3v2 tag, the leading tag doesn't point to it")
+		},
+	}
+}
+
 #[test]
 fn zero_size_id3v2() {
 	use crate::id3::v2::header::Id3v2Header;
-	use crate::ParsingMode;
+	use crate::{ParseOptions, ParsingMode};
 	use std::io::Cursor;
 
 	let mut f = Cursor::new(std::fs::read("tests/tags/assets/id3v2/zero.id3v2").unwrap());
 	let header = Id3v2Header::parse(&mut f).unwrap();
-	assert!(parse_id3v2(&mut f, header, ParsingMode::Strict).is_ok());
+	assert!(parse_id3v2(
+		&mut f,
+		header,
+		ParseOptions::new().parsing_mode(ParsingMode::Strict)
+	)
+	.is_ok());
 }
 
 #[test]
 fn bad_frame_id_relaxed_id3v2() {
 	use crate::id3::v2::header::Id3v2Header;
-	use crate::{Accessor, ParsingMode, TagExt};
+	use crate::{Accessor, ParseOptions, ParsingMode, TagExt};
 	use std::io::Cursor;
 
 	// Contains a frame with a "+" in the ID, which is invalid.
@@ -95,7 +294,11 @@ fn bad_frame_id_relaxed_id3v2() {
 		std::fs::read("tests/tags/assets/id3v2/bad_frame_otherwise_valid.id3v24").unwrap(),
 	);
 	let header = Id3v2Header::parse(&mut f).unwrap();
-	let id3v2 = parse_id3v2(&mut f, header, ParsingMode::Relaxed);
+	let id3v2 = parse_id3v2(
+		&mut f,
+		header,
+		ParseOptions::new().parsing_mode(ParsingMode::Relaxed),
+	);
 	assert!(id3v2.is_ok());
 
 	let id3v2 = id3v2.unwrap();
@@ -110,3 +313,337 @@ fn bad_frame_id_relaxed_id3v2() {
 	assert_eq!(id3v2.track(), Some(1));
 	assert_eq!(id3v2.genre().as_deref(), Some("Classical"));
 }
+
+#[test]
+fn frame_exceeds_allocation_limit() {
+	use crate::error::{ErrorKind, Id3v2ErrorKind};
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::{ParseOptions, ParsingMode};
+	use std::io::Cursor;
+
+	#[rustfmt::skip]
+	let tag = [
+		b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, 115,
+		// Encrypted frame with a data length indicator
+		b'T', b'I', b'T', b'2', 0, 0, 0, 105, 0x00, 0x05,
+		// Encryption method symbol
+		0x80,
+		// Data length indicator
+		0, 0, 0, 100,
+	];
+
+	let mut content = tag.to_vec();
+	content.extend([0; 100]);
+
+	let mut reader = Cursor::new(content);
+	let header = Id3v2Header::parse(&mut reader).unwrap();
+
+	let _limit = crate::util::alloc::AllocationLimitGuard::new(50);
+	let parse_options = ParseOptions::new()
+		.parsing_mode(ParsingMode::Strict)
+		.allocation_limit(50);
+	let result = parse_id3v2(&mut reader, header, parse_options);
+
+	let Err(err) = result else {
+		panic!("Expected the frame to exceed the allocation limit");
+	};
+	let ErrorKind::Id3v2(id3v2_err) = err.kind() else {
+		panic!("Expected an ID3v2 error, got: {err}");
+	};
+	let Id3v2ErrorKind::FrameExceedsAllocationLimit(id, size) = id3v2_err.kind() else {
+		panic!("Expected `FrameExceedsAllocationLimit`, got: {id3v2_err}");
+	};
+
+	assert_eq!(id.as_str(), "TIT2");
+	assert_eq!(*size, 100);
+}
+
+#[test]
+fn max_id3v2_frames() {
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::{ParseOptions, ParsingMode, TagExt};
+	use std::io::Cursor;
+
+	let mut frames = Vec::new();
+	for i in 0..20u8 {
+		frames.extend(b"TXXX");
+		frames.extend([0, 0, 0, 4, 0, 0]);
+		frames.extend([0, b'A' + i, 0, b'a']);
+	}
+
+	// A duplicate of the first frame, which replaces it
+	frames.extend(b"TXXX");
+	frames.extend([0, 0, 0, 4, 0, 0]);
+	frames.extend([0, b'A', 0, b'b']);
+
+	let size = frames.len();
+	let mut content = vec![b'I', b'D', b'3', 4, 0, 0, 0, 0];
+	content.extend([(size >> 7) as u8, (size & 0x7F) as u8]);
+	content.extend(frames);
+
+	let read = |max_frames| {
+		let mut reader = Cursor::new(&content);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let parse_options = ParseOptions::new()
+			.parsing_mode(ParsingMode::Strict)
+			.max_id3v2_frames(max_frames);
+		parse_id3v2(&mut reader, header, parse_options).unwrap()
+	};
+
+	let id3v2 = read(ParseOptions::DEFAULT_MAX_ID3V2_FRAMES);
+	assert_eq!(id3v2.len(), 20);
+	assert_eq!(id3v2.get_user_text("A"), Some("b"));
+
+	// The remaining frames are skipped once the limit is reached
+	let id3v2 = read(5);
+	assert_eq!(id3v2.len(), 5);
+	assert_eq!(id3v2.get_user_text("A"), Some("a"));
+	assert_eq!(id3v2.get_user_text("E"), Some("a"));
+	assert_eq!(id3v2.get_user_text("F"), None);
+}
+
+#[test]
+fn unparsed_frame_relaxed() {
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::id3::v2::{FrameId, FrameValue};
+	use crate::{Accessor, ParseOptions, ParsingMode, TagExt, WriteOptions};
+	use std::borrow::Cow;
+	use std::io::Cursor;
+
+	#[rustfmt::skip]
+	let frames = [
+		b'T', b'I', b'T', b'2', 0, 0, 0, 4, 0, 0,
+		0, b'F', b'o', b'o',
+		// Invalid text encoding
+		b'T', b'X', b'X', b'X', 0, 0, 0, 4, 0, 0,
+		9, b'A', 0, b'a',
+	];
+
+	let mut content = vec![b'I', b'D', b'3', 4, 0, 0, 0, 0, 0, frames.len() as u8];
+	content.extend(frames);
+
+	let read = |content: &[u8], parse_mode| {
+		let mut reader = Cursor::new(content);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(parse_mode),
+		)
+	};
+
+	assert!(read(&content, ParsingMode::Strict).is_err());
+
+	let id3v2 = read(&content, ParsingMode::Relaxed).unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Foo"));
+
+	let user_text = id3v2.get(&FrameId::Valid(Cow::Borrowed("TXXX"))).unwrap();
+	assert_eq!(user_text.value, FrameValue::Binary(vec![9, b'A', 0, b'a']));
+
+	// The frame is written back untouched
+	let mut writer = Vec::new();
+	id3v2.dump_to(&mut writer, WriteOptions::default()).unwrap();
+	assert_eq!(read(&writer, ParsingMode::Relaxed).unwrap(), id3v2);
+}
+
+#[cfg(all(test, feature = "id3v2_compression_support"))]
+fn compressed_tag(title: &[u8], data_length_indicator: u32) -> Vec<u8> {
+	use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+
+	let mut frames = Vec::new();
+
+	// Compressed frame with a data length indicator
+	frames.extend(b"TIT2");
+	frames.extend((title.len() as u32 + 4).synch().unwrap().to_be_bytes());
+	frames.extend([0x00, 0x09]);
+	frames.extend(data_length_indicator.synch().unwrap().to_be_bytes());
+	frames.extend(title);
+
+	frames.extend(b"TALB\0\0\0\x04\0\0\0Bar");
+
+	let mut content = b"ID3\x04\0\0".to_vec();
+	content.extend((frames.len() as u32).synch().unwrap().to_be_bytes());
+	content.extend(frames);
+	content
+}
+
+#[cfg(all(test, feature = "id3v2_compression_support"))]
+fn zlib(data: &[u8]) -> Vec<u8> {
+	use std::io::Write;
+
+	let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+	encoder.write_all(data).unwrap();
+	encoder.finish().unwrap()
+}
+
+#[test]
+#[cfg(feature = "id3v2_compression_support")]
+fn compressed_frame() {
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::id3::v2::{FrameId, FrameValue};
+	use crate::{Accessor, ParseOptions, ParsingMode, TagExt, WriteOptions};
+	use std::borrow::Cow;
+	use std::io::Cursor;
+
+	let read = |content: &[u8], parse_mode| {
+		let mut reader = Cursor::new(content);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(parse_mode),
+		)
+	};
+
+	let content = compressed_tag(&zlib(b"\0Foo title"), 10);
+
+	let id3v2 = read(&content, ParsingMode::Strict).unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+	assert_eq!(id3v2.album().as_deref(), Some("Bar"));
+
+	// The frame is written back uncompressed
+	let title = id3v2.get(&FrameId::Valid(Cow::Borrowed("TIT2"))).unwrap();
+	assert!(!title.flags().compression);
+	assert_eq!(title.flags().data_length_indicator, None);
+
+	let mut writer = Vec::new();
+	id3v2.dump_to(&mut writer, WriteOptions::default()).unwrap();
+	assert_eq!(read(&writer, ParsingMode::Strict).unwrap(), id3v2);
+
+	// Corrupt data only discards the frame, unless we're being strict
+	let content = compressed_tag(b"\x78\x9C\xFF\xFF\xFF\xFF", 10);
+	assert!(read(&content, ParsingMode::Strict).is_err());
+
+	let id3v2 = read(&content, ParsingMode::BestAttempt).unwrap();
+	assert_eq!(id3v2.title(), None);
+	assert_eq!(id3v2.album().as_deref(), Some("Bar"));
+	assert!(id3v2
+		.into_iter()
+		.all(|frame| !matches!(frame.content(), FrameValue::Binary(_))));
+}
+
+#[test]
+#[cfg(feature = "id3v2_compression_support")]
+fn compressed_frame_exceeds_allocation_limit() {
+	use crate::error::{ErrorKind, Id3v2ErrorKind};
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::{ParseOptions, ParsingMode};
+	use std::io::Cursor;
+
+	let mut title = vec![0];
+	title.extend([b'a'; 100]);
+	let compressed = zlib(&title);
+
+	for data_length_indicator in [
+		// Too large to begin with
+		101, // Only too large once decompressed
+		10,
+	] {
+		let content = compressed_tag(&compressed, data_length_indicator);
+		let mut reader = Cursor::new(content);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+
+		let _limit = crate::util::alloc::AllocationLimitGuard::new(50);
+		let parse_options = ParseOptions::new()
+			.parsing_mode(ParsingMode::BestAttempt)
+			.allocation_limit(50);
+		let result = parse_id3v2(&mut reader, header, parse_options);
+
+		let Err(err) = result else {
+			panic!("Expected the frame to exceed the allocation limit");
+		};
+		let ErrorKind::Id3v2(id3v2_err) = err.kind() else {
+			panic!("Expected an ID3v2 error, got: {err}");
+		};
+		assert!(matches!(
+			id3v2_err.kind(),
+			Id3v2ErrorKind::FrameExceedsAllocationLimit(..)
+		));
+	}
+}
+
+#[test]
+fn decrypt_frames() {
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+	use crate::id3::v2::{FrameId, FrameValue};
+	use crate::{Accessor, ParseOptions, ParsingMode, TagExt, WriteOptions};
+	use std::borrow::Cow;
+	use std::io::Cursor;
+
+	let xor = |content: &[u8]| content.iter().map(|b| b ^ 0xAA).collect::<Vec<u8>>();
+
+	let mut frames = Vec::new();
+
+	let encr = b"https://example.com/xor\0\x80";
+	frames.extend(b"ENCR");
+	frames.extend((encr.len() as u32).synch().unwrap().to_be_bytes());
+	frames.extend([0, 0]);
+	frames.extend(encr);
+
+	// Encrypted frame with a data length indicator
+	let title = xor(b"\0Foo title");
+	frames.extend(b"TIT2");
+	frames.extend((title.len() as u32 + 5).synch().unwrap().to_be_bytes());
+	frames.extend([0x00, 0x05]);
+	frames.push(0x80);
+	frames.extend((title.len() as u32).synch().unwrap().to_be_bytes());
+	frames.extend(title);
+
+	let mut content = b"ID3\x04\0\0".to_vec();
+	content.extend((frames.len() as u32).synch().unwrap().to_be_bytes());
+	content.extend(frames);
+
+	let read = |content: &[u8]| {
+		let mut reader = Cursor::new(content);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap()
+	};
+
+	let mut id3v2 = read(&content);
+
+	// Without the method, the frame stays encrypted
+	let title_id = FrameId::Valid(Cow::Borrowed("TIT2"));
+	assert!(matches!(
+		id3v2.get(&title_id).unwrap().content(),
+		FrameValue::Binary(_)
+	));
+	assert_eq!(id3v2.title(), None);
+	assert_eq!(id3v2.encryption_method("https://example.com/rot13"), None);
+	assert_eq!(
+		id3v2
+			.decrypt_frames(0x81, |content| Ok(xor(content)))
+			.unwrap(),
+		0
+	);
+
+	// A failure leaves the frames untouched
+	let method_symbol = id3v2.encryption_method("https://example.com/xor").unwrap();
+	assert_eq!(method_symbol, 0x80);
+	assert!(id3v2
+		.decrypt_frames(method_symbol, |_| Ok(b"\xFFFoo".to_vec()))
+		.is_err());
+	assert_eq!(id3v2.title(), None);
+
+	assert_eq!(
+		id3v2
+			.decrypt_frames(method_symbol, |content| Ok(xor(content)))
+			.unwrap(),
+		1
+	);
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+
+	// The frame is written back unencrypted
+	let title = id3v2.get(&title_id).unwrap();
+	assert_eq!(title.flags().encryption, None);
+	assert_eq!(title.flags().data_length_indicator, None);
+
+	let mut writer = Vec::new();
+	id3v2.dump_to(&mut writer, WriteOptions::default()).unwrap();
+	assert_eq!(read(&writer).title().as_deref(), Some("Foo title"));
+}
@@ -1,3 +1,8 @@
+use crate::picture::MimeType;
+use crate::util::text::TextEncoding;
+
+use std::fmt::{Display, Formatter};
+
 /// Restrictions on the tag size
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -13,6 +18,27 @@ pub enum TagSizeRestrictions {
 	S_32F_4K,
 }
 
+impl TagSizeRestrictions {
+	/// The maximum number of frames in the tag
+	pub fn max_frames(self) -> usize {
+		match self {
+			TagSizeRestrictions::S_128F_1M => 128,
+			TagSizeRestrictions::S_64F_128K => 64,
+			TagSizeRestrictions::S_32F_40K | TagSizeRestrictions::S_32F_4K => 32,
+		}
+	}
+
+	/// The maximum size of the tag in bytes, including its header
+	pub fn max_size(self) -> usize {
+		match self {
+			TagSizeRestrictions::S_128F_1M => 1024 * 1024,
+			TagSizeRestrictions::S_64F_128K => 128 * 1024,
+			TagSizeRestrictions::S_32F_40K => 40 * 1024,
+			TagSizeRestrictions::S_32F_4K => 4 * 1024,
+		}
+	}
+}
+
 /// Restrictions on text field sizes
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -25,6 +51,17 @@ pub enum TextSizeRestrictions {
 	C_30,
 }
 
+impl TextSizeRestrictions {
+	/// The maximum number of characters in a text field
+	pub fn max_len(self) -> usize {
+		match self {
+			TextSizeRestrictions::C_1024 => 1024,
+			TextSizeRestrictions::C_128 => 128,
+			TextSizeRestrictions::C_30 => 30,
+		}
+	}
+}
+
 /// Restrictions on all image sizes
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
@@ -37,6 +74,17 @@ pub enum ImageSizeRestrictions {
 	P_64_64,
 }
 
+impl ImageSizeRestrictions {
+	/// Whether an image of the given dimensions is allowed
+	pub fn allows(self, width: u32, height: u32) -> bool {
+		match self {
+			ImageSizeRestrictions::P_256 => width <= 256 && height <= 256,
+			ImageSizeRestrictions::P_64 => width <= 64 && height <= 64,
+			ImageSizeRestrictions::P_64_64 => width == 64 && height == 64,
+		}
+	}
+}
+
 /// Restrictions on the content of an ID3v2 tag
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TagRestrictions {
@@ -68,7 +116,7 @@ impl TagRestrictions {
 		let restriction_flags = byte;
 
 		// xx000000
-		match restriction_flags & 0x0C {
+		match restriction_flags & 0xC0 {
 			64 => restrictions.size = TagSizeRestrictions::S_64F_128K,
 			128 => restrictions.size = TagSizeRestrictions::S_32F_40K,
 			192 => restrictions.size = TagSizeRestrictions::S_32F_4K,
@@ -113,7 +161,7 @@ impl TagRestrictions {
 			TagSizeRestrictions::S_128F_1M => {},
 			TagSizeRestrictions::S_64F_128K => byte |= 0x40,
 			TagSizeRestrictions::S_32F_40K => byte |= 0x80,
-			TagSizeRestrictions::S_32F_4K => byte |= 0x0C,
+			TagSizeRestrictions::S_32F_4K => byte |= 0xC0,
 		}
 
 		if self.text_encoding {
@@ -141,3 +189,133 @@ impl TagRestrictions {
 		byte
 	}
 }
+
+/// A way in which an ID3v2 tag violates its [`TagRestrictions`]
+///
+/// See [`ErrorKind::Id3v2Restrictions`](crate::error::ErrorKind::Id3v2Restrictions).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[allow(missing_docs)]
+pub enum RestrictionViolation {
+	/// The tag is larger than its [`TagSizeRestrictions`] allow, in bytes
+	TagSize { size: usize, max_size: usize },
+	/// The tag has more frames than its [`TagSizeRestrictions`] allow
+	FrameCount { count: usize, max_frames: usize },
+	/// A text field is longer than the [`TextSizeRestrictions`] allow, in characters
+	TextSize {
+		frame_id: String,
+		len: usize,
+		max_len: usize,
+	},
+	/// A frame is encoded with something other than [`TextEncoding::Latin1`] or [`TextEncoding::UTF8`]
+	TextEncoding {
+		frame_id: String,
+		encoding: TextEncoding,
+	},
+	/// A picture is neither a PNG nor a JPEG
+	ImageEncoding { mime_type: Option<MimeType> },
+	/// A picture's dimensions aren't allowed by the [`ImageSizeRestrictions`]
+	///
+	/// The dimensions are `None` if they couldn't be determined.
+	ImageSize { dimensions: Option<(u32, u32)> },
+}
+
+impl Display for RestrictionViolation {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RestrictionViolation::TagSize { size, max_size } => {
+				write!(f, "the tag is {size} bytes, exceeding {max_size} bytes")
+			},
+			RestrictionViolation::FrameCount { count, max_frames } => {
+				write!(
+					f,
+					"the tag has {count} frames, exceeding {max_frames} frames"
+				)
+			},
+			RestrictionViolation::TextSize {
+				frame_id,
+				len,
+				max_len,
+			} => write!(
+				f,
+				"`{frame_id}` has a text field of {len} characters, exceeding {max_len} characters"
+			),
+			RestrictionViolation::TextEncoding { frame_id, encoding } => {
+				write!(f, "`{frame_id}` is encoded with {encoding:?}")
+			},
+			RestrictionViolation::ImageEncoding { mime_type } => match mime_type {
+				Some(mime_type) => write!(
+					f,
+					"a picture has the unsupported type {}",
+					mime_type.as_str()
+				),
+				None => write!(f, "a picture has no type"),
+			},
+			RestrictionViolation::ImageSize {
+				dimensions: Some((width, height)),
+			} => write!(f, "a picture is {width}x{height}, which isn't allowed"),
+			RestrictionViolation::ImageSize { dimensions: None } => {
+				write!(f, "a picture has unknown dimensions")
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{
+		ImageSizeRestrictions, TagRestrictions, TagSizeRestrictions, TextSizeRestrictions,
+	};
+
+	#[test]
+	fn byte_round_trip() {
+		for size in [
+			TagSizeRestrictions::S_128F_1M,
+			TagSizeRestrictions::S_64F_128K,
+			TagSizeRestrictions::S_32F_40K,
+			TagSizeRestrictions::S_32F_4K,
+		] {
+			for text_fields_size in [
+				None,
+				Some(TextSizeRestrictions::C_1024),
+				Some(TextSizeRestrictions::C_128),
+				Some(TextSizeRestrictions::C_30),
+			] {
+				for image_size in [
+					None,
+					Some(ImageSizeRestrictions::P_256),
+					Some(ImageSizeRestrictions::P_64),
+					Some(ImageSizeRestrictions::P_64_64),
+				] {
+					for (text_encoding, image_encoding) in
+						[(false, false), (true, false), (false, true), (true, true)]
+					{
+						let restrictions = TagRestrictions {
+							size,
+							text_encoding,
+							text_fields_size,
+							image_encoding,
+							image_size,
+						};
+
+						assert_eq!(
+							TagRestrictions::from_byte(restrictions.as_bytes()),
+							restrictions
+						);
+					}
+				}
+			}
+		}
+
+		// Tag size (xx), text encoding (x), text size (xx), image encoding (x), image size (xx)
+		let restrictions = TagRestrictions::from_byte(0b1111_0110);
+		assert_eq!(restrictions.size, TagSizeRestrictions::S_32F_4K);
+		assert!(restrictions.text_encoding);
+		assert_eq!(
+			restrictions.text_fields_size,
+			Some(TextSizeRestrictions::C_128)
+		);
+		assert!(restrictions.image_encoding);
+		assert_eq!(restrictions.image_size, Some(ImageSizeRestrictions::P_64));
+	}
+}
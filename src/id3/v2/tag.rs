@@ -1,12 +1,20 @@
 use super::frame::id::FrameId;
-use super::frame::{Frame, FrameFlags, FrameValue, EMPTY_CONTENT_DESCRIPTOR, UNKNOWN_LANGUAGE};
-use super::header::{Id3v2TagFlags, Id3v2Version};
-use crate::error::{LoftyError, Result};
+use super::frame::{
+	Frame, FrameFlags, FrameValue, EMPTY_CONTENT_DESCRIPTOR, UNDETERMINED_LANGUAGE,
+	UNKNOWN_LANGUAGE,
+};
+use super::header::{Id3v2Crc, Id3v2TagFlags, Id3v2Version};
+use super::limits::{fit_to_limits, FitReport, TagLimits};
+use crate::error::{Id3v2Error, Id3v2ErrorKind, LoftyError, Result};
 use crate::id3::v1::GENRES;
-use crate::id3::v2::frame::{FrameRef, MUSICBRAINZ_UFID_OWNER};
+use crate::id3::v2::frame::content::parse_content;
+use crate::id3::v2::frame::read::decompress;
+use crate::id3::v2::frame::{FrameRef, MUSICBRAINZ_UFID_OWNER, PODCAST_FLAG_FRAME_ID};
+use crate::id3::v2::items::language_frame::verify_language;
 use crate::id3::v2::items::{
-	AttachedPictureFrame, CommentFrame, ExtendedTextFrame, ExtendedUrlFrame, TextInformationFrame,
-	UniqueFileIdentifierFrame, UnsynchronizedTextFrame, UrlLinkFrame,
+	AttachedPictureFrame, ChapterFrame, CommentFrame, ExtendedTextFrame, ExtendedUrlFrame,
+	TableOfContentsFrame, TextInformationFrame, UniqueFileIdentifierFrame, UnsynchronizedTextFrame,
+	UrlLinkFrame,
 };
 use crate::id3::v2::util::mappings::TIPL_MAPPINGS;
 use crate::id3::v2::util::pairs::{
@@ -14,25 +22,79 @@ use crate::id3::v2::util::pairs::{
 };
 use crate::id3::v2::KeyValueFrame;
 use crate::picture::{Picture, PictureType, TOMBSTONE_PICTURE};
+use crate::probe::ParsingMode;
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
-use crate::tag::{try_parse_year, Tag, TagType};
+use crate::tag::rating::convert_rating_items;
+use crate::tag::timestamp::Timestamp;
+use crate::tag::{retain_precise_bpm, split_bpm, Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
-use crate::util::text::{decode_text, TextEncoding};
+use crate::util::io::FileLike;
+use crate::util::text::{utf8_decode_str, TextEncoding};
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
 use std::convert::TryInto;
+#[cfg(feature = "fs")]
 use std::fs::File;
-use std::io::{Cursor, Write};
+use std::io::Write;
 use std::ops::Deref;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
 
 const USER_DEFINED_TEXT_FRAME_ID: &str = "TXXX";
 const COMMENT_FRAME_ID: &str = "COMM";
+const LYRICS_FRAME_ID: &str = "USLT";
 
 const V4_MULTI_VALUE_SEPARATOR: char = '\0';
 
+// Frames that can only appear once in a tag
+pub(super) const ONE_PER_TAG_FRAMES: [&str; 11] = [
+	"MCDI", "ETCO", "MLLT", "SYTC", "RVRB", "PCNT", "RBUF", "POSS", "OWNE", "SEEK", "ASPI",
+];
+
+/// How [`Id3v2Tag::insert_with`] handles a frame that duplicates an existing one
+///
+/// ID3v2.4 only allows one of each kind of frame in a tag. Frames are duplicates when:
+///
+/// * They are text frames with the same ID
+/// * They are `TXXX`/`WXXX` frames with the same description
+/// * They are `COMM`/`USLT` frames with the same language and description
+/// * They are `APIC` frames with the same picture type and description, see [`AttachedPictureFrame`]
+/// * They are frames that can only appear once in a tag, such as `PCNT`
+///
+/// Otherwise, two frames with the same ID are duplicates if their contents are equal, as specified by their
+/// `PartialEq` implementations.
+///
+/// Duplicates can be kept in the tag with [`DuplicateBehavior::KeepBoth`], but only the last one
+/// inserted will be written.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DuplicateBehavior {
+	/// Replace the existing frame(s), this is what [`Id3v2Tag::insert`] does
+	#[default]
+	Replace,
+	/// Keep the existing frame(s) alongside the new one
+	KeepBoth,
+	/// Leave the tag unchanged, and return [`Id3v2ErrorKind::DuplicateFrame`]
+	Error,
+}
+
+// Whether two frames can't appear in the same tag, see `DuplicateBehavior`
+pub(super) fn is_duplicate_frame(
+	(id, value): (&FrameId<'_>, &FrameValue),
+	(other_id, other_value): (&FrameId<'_>, &FrameValue),
+) -> bool {
+	if id != other_id {
+		return false;
+	}
+
+	ONE_PER_TAG_FRAMES.contains(&id.as_str())
+		|| matches!(value, FrameValue::Text(_))
+		|| value == other_value
+}
+
 macro_rules! impl_accessor {
 	($($name:ident => $id:literal;)+) => {
 		paste::paste! {
@@ -67,25 +129,41 @@ macro_rules! impl_accessor {
 ///
 /// * [`ItemKey::Comment`] and [`ItemKey::Lyrics`] - Unlike a normal text frame, these require a language. See [`CommentFrame`] and [`UnsynchronizedTextFrame`] respectively.
 /// An attempt is made to create this information, but it may be incorrect.
-///    * `language` - Unknown and set to "XXX"
+///    * `language` - Kept from the original frame when merging with a [`SplitTagRemainder`], as long as it is valid.
+///    Otherwise, it is unknown and set to "XXX" for comments, and "und" (undetermined) for lyrics.
 ///    * `description` - Left empty, which is invalid if there are more than one of these frames. These frames can only be identified
 ///    by their descriptions, and as such they are expected to be unique for each.
 /// * [`ItemKey::Unknown("WXXX" | "TXXX")`](ItemKey::Unknown) - These frames are also identified by their descriptions.
+/// A locator is always written to a URL frame, so any key that doesn't map to one is used as a `WXXX` description.
+/// * [`ItemKey::FlagPodcast`] - Written as Apple's `PCST` frame only when set to `"1"`, since the frame holds no data.
+/// * [`ItemKey::PodcastURL`] - Written as Apple's `WFED` frame, which is a text frame despite its ID.
+/// * [`ItemKey::FlagCompilation`] - Written as iTunes' `TCMP` frame with a value of `"1"` or `"0"`. Other spellings, such as `"true"` or `"no"`,
+/// are normalized, and anything else is dropped.
+/// * [`ItemKey::Work`] - Written as a `TXXX:WORK` frame, since there is no dedicated frame for it.
+/// * [`ItemKey::MusicBrainzRecordingId`] - Written as a `UFID` frame owned by `http://musicbrainz.org`, the way MusicBrainz Picard does.
 ///
 /// ### To `Tag`
 ///
 /// * TXXX/WXXX - These frames will be stored as an [`ItemKey`] by their description. Some variants exist for these descriptions, such as the one for `ReplayGain`,
 /// otherwise [`ItemKey::Unknown`] will be used.
 /// * Frames that require a language (COMM/USLT) - With ID3v2 being the only format that allows for language-specific items, this information is not retained.
+/// When using [`SplitTag`](crate::SplitTag), the language of the general comment is remembered, and used again when merging.
+/// * COMM - Only the first frame with an empty description is stored as [`ItemKey::Comment`]. Any others (such as iTunes' `iTunNORM`)
+/// are retained in the tag, see [`Id3v2Tag::comment_with`].
+/// * USLT - Only the first frame with an empty description is stored as [`ItemKey::Lyrics`]. Any others (such as translations) are
+/// retained in the tag, see [`Id3v2Tag::all_lyrics`].
+/// * URL frames (W...) - These frames will be stored as [`ItemValue::Locator`], such as [`ItemKey::TrackArtistUrl`] for `WOAR`.
 /// * POPM - These frames will be stored as a raw [`ItemValue::Binary`] value under the [`ItemKey::Popularimeter`] key.
+/// * PCST - The presence of this frame is stored as [`ItemKey::FlagPodcast`] with a value of `"1"`.
+/// * PCNT - The play counter is stored as text under the [`ItemKey::PlayCount`] key.
+/// * UFID - Only a frame owned by `http://musicbrainz.org` is stored, as [`ItemKey::MusicBrainzRecordingId`]. Frames with any other owner
+/// are retained in the tag.
 ///
 /// ## Special Frames
 ///
-/// ID3v2 has `GEOB` and `SYLT` frames, which are not parsed by default, instead storing them as [`FrameValue::Binary`].
-/// They can easily be parsed with [`GeneralEncapsulatedObject::parse`](crate::id3::v2::GeneralEncapsulatedObject::parse)
-/// and [`SynchronizedText::parse`](crate::id3::v2::SynchronizedText::parse) respectively, and converted back to binary with
-/// [`GeneralEncapsulatedObject::as_bytes`](crate::id3::v2::GeneralEncapsulatedObject::as_bytes) and
-/// [`SynchronizedText::as_bytes`](crate::id3::v2::SynchronizedText::as_bytes) for writing.
+/// `SYLT` and `GEOB` frames are parsed into [`FrameValue::SynchronizedText`] and [`FrameValue::GeneralEncapsulatedObject`]
+/// respectively. If one is malformed, it will be stored as [`FrameValue::Binary`] instead, unless using
+/// [`ParsingMode::Strict`](crate::ParsingMode::Strict).
 #[derive(PartialEq, Eq, Debug, Clone)]
 #[tag(
 	description = "An `ID3v2` tag",
@@ -94,6 +172,7 @@ macro_rules! impl_accessor {
 pub struct Id3v2Tag {
 	flags: Id3v2TagFlags,
 	pub(super) original_version: Id3v2Version,
+	pub(super) crc: Option<Id3v2Crc>,
 	pub(crate) frames: Vec<Frame<'static>>,
 }
 
@@ -120,6 +199,7 @@ impl Default for Id3v2Tag {
 		Self {
 			flags: Id3v2TagFlags::default(),
 			original_version: Id3v2Version::V4,
+			crc: None,
 			frames: Vec::new(),
 		}
 	}
@@ -158,6 +238,86 @@ impl Id3v2Tag {
 	pub fn original_version(&self) -> Id3v2Version {
 		self.original_version
 	}
+
+	/// The CRC-32 from the tag's extended header, if it had one
+	///
+	/// This is the CRC as it was read, it isn't updated as the tag is modified. To write a new one,
+	/// see [`Id3v2TagFlags::crc`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	/// use lofty::mpeg::MpegFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path = "tests/files/assets/minimal/full_test.mp3";
+	/// let mut file = std::fs::File::open(path)?;
+	/// let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// if let Some(crc) = mpeg_file.id3v2().and_then(Id3v2Tag::crc) {
+	/// 	if crc.is_valid() == Some(false) {
+	/// 		println!("The ID3v2 tag is corrupt!");
+	/// 	}
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn crc(&self) -> Option<Id3v2Crc> {
+		self.crc
+	}
+
+	/// Removes and truncates frames until the tag fits within `limits` when written
+	///
+	/// This is for devices that can only read small tags, see [`TagLimits`]. The size of the tag is
+	/// measured as it would be written with `write_options`. Frames are sacrificed in the following order:
+	///
+	/// 1. Text fields longer than [`TagLimits::max_text_len`] are always truncated.
+	/// 2. Pictures are removed, starting from the last.
+	/// 3. Text fields are truncated, starting from the largest frame. Text fields will not be truncated below 30 characters.
+	/// 4. Frames are removed, starting from the last. The following frames are kept the longest, and removed in this order:
+	///    `COMM`, `USLT`, `TSRC`, `TCOM`, `TPE2`, `TCON`, `TDRC`, `TPOS`, `TRCK`, `TALB`, `TPE1`, `TIT2`.
+	///
+	/// The returned [`FitReport`] contains everything that was removed or truncated.
+	///
+	/// NOTE: Text fields are truncated on character boundaries
+	///
+	/// # Errors
+	///
+	/// * A frame is invalid, and could not be written anyway
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::{Id3v2Tag, TagLimits, TagRestrictions, TagSizeRestrictions};
+	/// use lofty::{Accessor, TagExt, WriteOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_title(String::from("Foo title"));
+	/// tag.set_comment("A".repeat(8192));
+	///
+	/// // My device can only read tags up to 4 KB, with at most 32 frames
+	/// let restrictions = TagRestrictions {
+	/// 	size: TagSizeRestrictions::S_32F_4K,
+	/// 	..TagRestrictions::default()
+	/// };
+	///
+	/// let report = tag.fit_to_limits(TagLimits::from(restrictions), WriteOptions::default())?;
+	/// assert_eq!(report.truncated.len(), 1);
+	///
+	/// let mut tag_bytes = Vec::new();
+	/// tag.dump_to(&mut tag_bytes, WriteOptions::default())?;
+	/// assert!(tag_bytes.len() <= 4 * 1024);
+	/// # Ok(()) }
+	/// ```
+	pub fn fit_to_limits(
+		&mut self,
+		limits: TagLimits,
+		write_options: WriteOptions,
+	) -> Result<FitReport> {
+		fit_to_limits(self, limits, write_options)
+	}
 }
 
 impl Id3v2Tag {
@@ -216,11 +376,11 @@ impl Id3v2Tag {
 	/// ```
 	pub fn get_texts(&self, id: &FrameId<'_>) -> Option<impl Iterator<Item = &str>> {
 		if let Some(Frame {
-			value: FrameValue::Text(TextInformationFrame { value, .. }),
+			value: FrameValue::Text(text_frame),
 			..
 		}) = self.get(id)
 		{
-			return Some(value.split('\0'));
+			return Some(text_frame.values());
 		}
 
 		None
@@ -304,27 +464,99 @@ impl Id3v2Tag {
 
 	/// Inserts a [`Frame`]
 	///
-	/// This will replace any frame of the same id (**or description!** See [`ExtendedTextFrame`])
+	/// This will replace any frame of the same id (**or description!** See [`ExtendedTextFrame`]),
+	/// see [`DuplicateBehavior`] for what counts as a duplicate. If several frames are replaced, the
+	/// first one is returned.
 	pub fn insert(&mut self, frame: Frame<'static>) -> Option<Frame<'static>> {
-		// Some frames can only appear once in a tag, handle them separately
-		const ONE_PER_TAG: [&str; 11] = [
-			"MCDI", "ETCO", "MLLT", "SYTC", "RVRB", "PCNT", "RBUF", "POSS", "OWNE", "SEEK", "ASPI",
-		];
+		let replaced = self.take_duplicates(&frame);
+		self.frames.push(frame);
+		replaced
+	}
 
-		if ONE_PER_TAG.contains(&frame.id_str()) {
-			let ret = self.remove(&frame.id).next();
-			self.frames.push(frame);
-			return ret;
-		}
+	/// Inserts a [`Frame`], handling any duplicates according to a [`DuplicateBehavior`]
+	///
+	/// With [`DuplicateBehavior::Replace`], this is the same as [`Id3v2Tag::insert`]. Otherwise, no
+	/// frame is returned.
+	///
+	/// # Errors
+	///
+	/// * `duplicate_behavior` is [`DuplicateBehavior::Error`], and the frame duplicates an existing one
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::{DuplicateBehavior, ExtendedTextFrame, Frame, FrameFlags, Id3v2Tag};
+	/// use lofty::{TagExt, TextEncoding};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let user_text = |content: &str| {
+	/// 	Frame::new(
+	/// 		"TXXX",
+	/// 		ExtendedTextFrame {
+	/// 			encoding: TextEncoding::UTF8,
+	/// 			description: String::from("FOO"),
+	/// 			content: String::from(content),
+	/// 		},
+	/// 		FrameFlags::default(),
+	/// 	)
+	/// };
+	///
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.insert_with(user_text("Foo")?, DuplicateBehavior::Error)?;
+	///
+	/// // There is already a `TXXX` frame with this description
+	/// assert!(tag
+	/// 	.insert_with(user_text("Bar")?, DuplicateBehavior::Error)
+	/// 	.is_err());
+	/// assert_eq!(tag.len(), 1);
+	///
+	/// // Both frames are kept, though only the last one will be written
+	/// tag.insert_with(user_text("Bar")?, DuplicateBehavior::KeepBoth)?;
+	/// assert_eq!(tag.len(), 2);
+	/// # Ok(()) }
+	/// ```
+	pub fn insert_with(
+		&mut self,
+		frame: Frame<'static>,
+		duplicate_behavior: DuplicateBehavior,
+	) -> Result<Option<Frame<'static>>> {
+		match duplicate_behavior {
+			DuplicateBehavior::Replace => return Ok(self.insert(frame)),
+			DuplicateBehavior::KeepBoth => {},
+			DuplicateBehavior::Error => {
+				let duplicate = self.frames.iter().any(|existing| {
+					is_duplicate_frame((&existing.id, &existing.value), (&frame.id, &frame.value))
+				});
 
-		let replaced = self
-			.frames
-			.iter()
-			.position(|f| f == &frame)
-			.map(|pos| self.frames.remove(pos));
+				if duplicate {
+					return Err(Id3v2Error::new(Id3v2ErrorKind::DuplicateFrame(
+						frame.id.into_owned(),
+					))
+					.into());
+				}
+			},
+		}
 
 		self.frames.push(frame);
-		replaced
+		Ok(None)
+	}
+
+	// Removes every frame that `frame` duplicates, returning the first
+	fn take_duplicates(&mut self, frame: &Frame<'_>) -> Option<Frame<'static>> {
+		let mut first = None;
+		let mut idx = 0;
+		while idx < self.frames.len() {
+			let existing = &self.frames[idx];
+			if is_duplicate_frame((&existing.id, &existing.value), (&frame.id, &frame.value)) {
+				let removed = self.frames.remove(idx);
+				first.get_or_insert(removed);
+				continue;
+			}
+
+			idx += 1;
+		}
+
+		first
 	}
 
 	/// Removes a user-defined text frame (`TXXX`) by its description
@@ -437,41 +669,11 @@ impl Id3v2Tag {
 
 	/// Inserts a [`Picture`]
 	///
-	/// According to spec, there can only be one picture of type [`PictureType::Icon`] and [`PictureType::OtherIcon`].
-	/// When attempting to insert these types, if another is found it will be removed and returned.
+	/// This will replace any picture of the same type and description. According to spec, there can only be one
+	/// picture of type [`PictureType::Icon`] and [`PictureType::OtherIcon`], so these replace any picture of the same
+	/// type. See [`AttachedPictureFrame`].
 	pub fn insert_picture(&mut self, picture: Picture) -> Option<Frame<'static>> {
-		let ret = if picture.pic_type == PictureType::Icon
-			|| picture.pic_type == PictureType::OtherIcon
-		{
-			let mut pos = None;
-
-			for (i, frame) in self.frames.iter().enumerate() {
-				match frame {
-					Frame {
-						id: FrameId::Valid(id),
-						value:
-							FrameValue::Picture(AttachedPictureFrame {
-								picture: Picture { pic_type, .. },
-								..
-							}),
-						..
-					} if id == "APIC" && pic_type == &picture.pic_type => {
-						pos = Some(i);
-						break;
-					},
-					_ => {},
-				}
-			}
-
-			pos.map(|p| self.frames.remove(p))
-		} else {
-			None
-		};
-
-		self.frames
-			.push(new_picture_frame(picture, FrameFlags::default()));
-
-		ret
+		self.insert(new_picture_frame(picture, FrameFlags::default()))
 	}
 
 	/// Removes a certain [`PictureType`]
@@ -502,6 +704,108 @@ impl Id3v2Tag {
 		})
 	}
 
+	/// Gets the lyrics (`USLT`) for a language and description
+	///
+	/// `language` is an ISO-639-2 language code, with `None` matching lyrics in any language.
+	/// The default lyrics have an empty `description`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_lyrics(Some(*b"eng"), String::new(), String::from("Original lyrics"))?;
+	/// tag.set_lyrics(Some(*b"deu"), String::new(), String::from("Übersetzter Liedtext"))?;
+	///
+	/// assert_eq!(tag.lyrics(Some(*b"deu"), ""), Some("Übersetzter Liedtext"));
+	/// assert_eq!(tag.lyrics(None, ""), Some("Original lyrics"));
+	/// # Ok(()) }
+	/// ```
+	pub fn lyrics(&self, language: Option<[u8; 3]>, description: &str) -> Option<&str> {
+		self.unsync_text()
+			.find(|frame| {
+				language.is_none_or(|language| frame.language == language)
+					&& frame.description == description
+			})
+			.map(|frame| frame.content.as_str())
+	}
+
+	/// Sets the lyrics (`USLT`) for a language and description
+	///
+	/// `language` is an ISO-639-2 language code, with `None` using `"und"` (undetermined).
+	///
+	/// NOTE: The encoding will be UTF-8
+	///
+	/// This will replace any `USLT` frame with the same language and description, see [`Id3v2Tag::insert`].
+	///
+	/// # Errors
+	///
+	/// * `language` contains invalid characters (Only `'a'..='z'` and `'A'..='Z'` allowed)
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_lyrics(None, String::new(), String::from("Some lyrics"))?;
+	///
+	/// assert_eq!(tag.lyrics(Some(*b"und"), ""), Some("Some lyrics"));
+	///
+	/// // Not a valid language code
+	/// assert!(tag
+	/// 	.set_lyrics(Some(*b"e\0g"), String::new(), String::from("Some lyrics"))
+	/// 	.is_err());
+	/// # Ok(()) }
+	/// ```
+	pub fn set_lyrics(
+		&mut self,
+		language: Option<[u8; 3]>,
+		description: String,
+		content: String,
+	) -> Result<Option<Frame<'static>>> {
+		let language = language.unwrap_or(UNDETERMINED_LANGUAGE);
+		verify_language(language)?;
+
+		Ok(self.insert(new_lyrics_frame(
+			language,
+			description,
+			content,
+			FrameFlags::default(),
+		)))
+	}
+
+	/// Returns all lyrics (`USLT`) as `(language, description, content)`
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_lyrics(Some(*b"eng"), String::new(), String::from("Original lyrics"))?;
+	/// tag.set_lyrics(Some(*b"eng"), String::from("Live"), String::from("Live lyrics"))?;
+	///
+	/// let mut lyrics = tag.all_lyrics();
+	/// assert_eq!(lyrics.next(), Some((*b"eng", "", "Original lyrics")));
+	/// assert_eq!(lyrics.next(), Some((*b"eng", "Live", "Live lyrics")));
+	/// assert_eq!(lyrics.next(), None);
+	/// # Ok(()) }
+	/// ```
+	pub fn all_lyrics(&self) -> impl Iterator<Item = ([u8; 3], &str, &str)> + Clone {
+		self.unsync_text().map(|frame| {
+			(
+				frame.language,
+				frame.description.as_str(),
+				frame.content.as_str(),
+			)
+		})
+	}
+
 	/// Returns all `COMM` frames with an empty content descriptor
 	pub fn comments(&self) -> impl Iterator<Item = &CommentFrame> {
 		self.frames.iter().filter_map(|frame| {
@@ -509,67 +813,514 @@ impl Id3v2Tag {
 		})
 	}
 
-	fn split_num_pair(&self, id: &FrameId<'_>) -> (Option<u32>, Option<u32>) {
-		if let Some(Frame {
-			value: FrameValue::Text(TextInformationFrame { ref value, .. }),
-			..
-		}) = self.get(id)
-		{
-			let mut split = value
-				.split(&[V4_MULTI_VALUE_SEPARATOR, NUMBER_PAIR_SEPARATOR][..])
-				.flat_map(str::parse::<u32>);
-			return (split.next(), split.next());
-		}
-
-		(None, None)
+	/// Gets a comment (`COMM`) for a language and description
+	///
+	/// `language` is an ISO-639-2 language code, with `None` matching comments in any language.
+	/// The general comment has an empty `description`, others are typically used by applications,
+	/// such as iTunes' `iTunNORM`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	/// use lofty::Accessor;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_comment(String::from("A comment"));
+	/// tag.set_comment_with(
+	/// 	Some(*b"eng"),
+	/// 	String::from("iTunNORM"),
+	/// 	String::from(" 00000A2B 00000A2B"),
+	/// )?;
+	///
+	/// assert_eq!(tag.comment_with(None, ""), Some("A comment"));
+	/// assert_eq!(
+	/// 	tag.comment_with(Some(*b"eng"), "iTunNORM"),
+	/// 	Some(" 00000A2B 00000A2B")
+	/// );
+	/// assert_eq!(tag.comment_with(Some(*b"deu"), "iTunNORM"), None);
+	/// # Ok(()) }
+	/// ```
+	pub fn comment_with(&self, language: Option<[u8; 3]>, description: &str) -> Option<&str> {
+		self.frames
+			.iter()
+			.filter_map(|frame| filter_comment_frame_by_description(frame, description))
+			.find(|frame| language.is_none_or(|language| frame.language == language))
+			.map(|frame| frame.content.as_str())
 	}
 
-	fn insert_item(&mut self, item: TagItem) {
-		match item.key() {
-			ItemKey::TrackNumber => set_number(&item, |number| self.set_track(number)),
-			ItemKey::TrackTotal => set_number(&item, |number| self.set_track_total(number)),
-			ItemKey::DiscNumber => set_number(&item, |number| self.set_disk(number)),
-			ItemKey::DiscTotal => set_number(&item, |number| self.set_disk_total(number)),
-			_ => {
-				if let Some(frame) = item.into() {
-					if let Some(replaced) = self.insert(frame) {
-						log::warn!("Replaced frame: {replaced:?}");
-					}
-				}
+	/// Sets a comment (`COMM`) for a language and description
+	///
+	/// `language` is an ISO-639-2 language code. With `None`, the language of an existing comment with
+	/// the same description is kept, otherwise `"und"` (undetermined) is used.
+	///
+	/// NOTE: The encoding will be UTF-8
+	///
+	/// This will replace any `COMM` frame with the same language and description, see [`Id3v2Tag::insert`].
+	///
+	/// # Errors
+	///
+	/// * `language` contains invalid characters (Only `'a'..='z'` and `'A'..='Z'` allowed)
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_comment_with(Some(*b"eng"), String::new(), String::from("A comment"))?;
+	///
+	/// // The existing comment keeps its language
+	/// tag.set_comment_with(None, String::new(), String::from("Another comment"))?;
+	/// assert_eq!(tag.comment_with(Some(*b"eng"), ""), Some("Another comment"));
+	///
+	/// // Not a valid language code
+	/// assert!(tag
+	/// 	.set_comment_with(Some(*b"e\0g"), String::new(), String::from("A comment"))
+	/// 	.is_err());
+	/// # Ok(()) }
+	/// ```
+	pub fn set_comment_with(
+		&mut self,
+		language: Option<[u8; 3]>,
+		description: String,
+		content: String,
+	) -> Result<Option<Frame<'static>>> {
+		let language = match language {
+			Some(language) => {
+				verify_language(language)?;
+				language
 			},
+			None => self
+				.frames
+				.iter()
+				.find_map(|frame| filter_comment_frame_by_description(frame, &description))
+				.map_or(UNDETERMINED_LANGUAGE, |frame| frame.language),
 		};
+
+		Ok(self.insert(new_comment_frame(
+			language,
+			description,
+			content,
+			FrameFlags::default(),
+		)))
 	}
 
-	/// Returns all genres contained in a `TCON` frame.
+	/// Returns all `CHAP` frames, in the order they appear in the tag
 	///
-	/// This will translate any numeric genre IDs to their textual equivalent.
-	/// ID3v2.4-style multi-value fields will be split as normal.
-	pub fn genres(&self) -> Option<impl Iterator<Item = &str>> {
-		if let Some(Frame {
-			value: FrameValue::Text(TextInformationFrame { ref value, .. }),
-			..
-		}) = self.get(&GENRE_ID)
-		{
-			return Some(GenresIter::new(value));
-		}
+	/// The intended order of the chapters is specified by the tables of contents, if there are any.
+	/// See [`Id3v2Tag::tables_of_contents`].
+	pub fn chapters(&self) -> impl Iterator<Item = &ChapterFrame> + Clone {
+		self.frames
+			.iter()
+			.filter_map(|frame| match frame.content() {
+				FrameValue::Chapter(chapter) => Some(chapter),
+				_ => None,
+			})
+	}
 
-		None
+	/// Inserts a [`ChapterFrame`]
+	///
+	/// Element IDs are unique within a tag, so this will replace (and return) any chapter or table of
+	/// contents with the same element ID.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::{ChapterFrame, Id3v2Tag};
+	///
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.insert_chapter(ChapterFrame::new(String::from("chp0"), 0, 5000));
+	/// tag.insert_chapter(ChapterFrame::new(String::from("chp1"), 5000, 10000));
+	///
+	/// // Replaces the existing "chp1"
+	/// let replaced = tag.insert_chapter(ChapterFrame::new(String::from("chp1"), 5000, 12000));
+	/// assert!(replaced.is_some());
+	///
+	/// assert_eq!(tag.chapters().count(), 2);
+	/// ```
+	pub fn insert_chapter(&mut self, chapter: ChapterFrame) -> Option<Frame<'static>> {
+		let element_id = chapter.element_id.clone();
+		self.insert_element(
+			Frame {
+				id: FrameId::Valid(Cow::Borrowed("CHAP")),
+				value: FrameValue::Chapter(chapter),
+				flags: FrameFlags::default(),
+			},
+			&element_id,
+		)
 	}
 
-	fn insert_number_pair(
-		&mut self,
-		id: FrameId<'static>,
-		number: Option<u32>,
-		total: Option<u32>,
-	) {
-		if let Some(content) = format_number_pair(number, total) {
-			self.insert(Frame::text(id.into_inner(), content));
+	/// Returns all `CTOC` frames, in the order they appear in the tag
+	///
+	/// There should only be a single top-level table of contents, see
+	/// [`TableOfContentsFrame::top_level`]. Any others are nested within it.
+	pub fn tables_of_contents(&self) -> impl Iterator<Item = &TableOfContentsFrame> + Clone {
+		self.frames
+			.iter()
+			.filter_map(|frame| match frame.content() {
+				FrameValue::TableOfContents(toc) => Some(toc),
+				_ => None,
+			})
+	}
+
+	/// Inserts a [`TableOfContentsFrame`]
+	///
+	/// Element IDs are unique within a tag, so this will replace (and return) any chapter or table of
+	/// contents with the same element ID.
+	pub fn insert_table_of_contents(
+		&mut self,
+		toc: TableOfContentsFrame,
+	) -> Option<Frame<'static>> {
+		let element_id = toc.element_id.clone();
+		self.insert_element(
+			Frame {
+				id: FrameId::Valid(Cow::Borrowed("CTOC")),
+				value: FrameValue::TableOfContents(toc),
+				flags: FrameFlags::default(),
+			},
+			&element_id,
+		)
+	}
+
+	/// Returns the method symbol registered by the `ENCR` frame of `owner`, if there is one
+	///
+	/// An `ENCR` frame ties an encryption method, identified by its owner (usually a URL or email address),
+	/// to the method symbol of the frames encrypted with it. See [`FrameFlags::encryption`].
+	pub fn encryption_method(&self, owner: &str) -> Option<u8> {
+		self.frames.iter().find_map(|frame| match frame.content() {
+			FrameValue::Binary(content) if frame.id_str() == "ENCR" => {
+				let owner_len = content.iter().position(|b| *b == 0)?;
+				if &content[..owner_len] != owner.as_bytes() {
+					return None;
+				}
+
+				content.get(owner_len + 1).copied()
+			},
+			_ => None,
+		})
+	}
+
+	/// Decrypts all frames encrypted with `method_symbol`
+	///
+	/// Lofty doesn't know of any encryption methods, so encrypted frames are stored as [`FrameValue::Binary`]
+	/// when reading. `decryptor` is given the content of each of them, and returns the decrypted content,
+	/// which is then parsed as the frame it really is. If the frame is also compressed, it will be decompressed
+	/// after being decrypted.
+	///
+	/// The decrypted frames will be written unencrypted.
+	///
+	/// Returns the number of decrypted frames.
+	///
+	/// # Errors
+	///
+	/// * `decryptor` fails
+	/// * A decrypted frame fails to decompress
+	/// * A decrypted frame is invalid
+	///
+	/// If any frame fails to be decrypted, the tag is left untouched.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut tag = Id3v2Tag::new();
+	/// // Some very secure encryption, registered by an `ENCR` frame
+	/// if let Some(method_symbol) = tag.encryption_method("https://example.com/xor") {
+	/// 	tag.decrypt_frames(method_symbol, |content| {
+	/// 		Ok(content.iter().map(|b| b ^ 0xFF).collect())
+	/// 	})?;
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn decrypt_frames<F>(&mut self, method_symbol: u8, mut decryptor: F) -> Result<usize>
+	where
+		F: FnMut(&[u8]) -> Result<Vec<u8>>,
+	{
+		let mut decrypted = Vec::new();
+		for (idx, frame) in self.frames.iter().enumerate() {
+			let FrameValue::Binary(content) = &frame.value else {
+				continue;
+			};
+
+			if frame.flags.encryption != Some(method_symbol) {
+				continue;
+			}
+
+			let mut content = decryptor(content)?;
+			if frame.flags.compression {
+				content = decompress(&content, frame.flags.data_length_indicator)?;
+			}
+
+			let Some(value) = parse_content(
+				&content,
+				frame.id_str(),
+				self.original_version,
+				crate::ParsingMode::Strict,
+			)?
+			else {
+				return Err(Id3v2Error::new(Id3v2ErrorKind::EmptyFrame(frame.id.clone())).into());
+			};
+
+			decrypted.push((idx, value));
+		}
+
+		let decrypted_count = decrypted.len();
+		for (idx, value) in decrypted {
+			let frame = &mut self.frames[idx];
+			frame.value = value;
+			frame.flags.encryption = None;
+			frame.flags.compression = false;
+			frame.flags.data_length_indicator = None;
+		}
+
+		Ok(decrypted_count)
+	}
+
+	fn insert_element(
+		&mut self,
+		frame: Frame<'static>,
+		element_id: &str,
+	) -> Option<Frame<'static>> {
+		let replaced = self
+			.frames
+			.iter()
+			.position(|frame| match frame.content() {
+				FrameValue::Chapter(ChapterFrame { element_id: id, .. })
+				| FrameValue::TableOfContents(TableOfContentsFrame { element_id: id, .. }) => id == element_id,
+				_ => false,
+			})
+			.map(|pos| self.frames.remove(pos));
+
+		self.frames.push(frame);
+		replaced
+	}
+
+	fn split_num_pair(&self, id: &FrameId<'_>) -> (Option<u32>, Option<u32>) {
+		if let Some(Frame {
+			value: FrameValue::Text(TextInformationFrame { ref value, .. }),
+			..
+		}) = self.get(id)
+		{
+			let mut split = value
+				.split(&[V4_MULTI_VALUE_SEPARATOR, NUMBER_PAIR_SEPARATOR][..])
+				.flat_map(str::parse::<u32>);
+			return (split.next(), split.next());
+		}
+
+		(None, None)
+	}
+
+	fn insert_item(&mut self, item: TagItem) {
+		match item.key() {
+			ItemKey::TrackNumber => set_number(&item, |number| self.set_track(number)),
+			ItemKey::TrackTotal => set_number(&item, |number| self.set_track_total(number)),
+			ItemKey::DiscNumber => set_number(&item, |number| self.set_disk(number)),
+			ItemKey::DiscTotal => set_number(&item, |number| self.set_disk_total(number)),
+			ItemKey::MovementNumber => set_number(&item, |number| {
+				let total = self.split_num_pair(&MOVEMENT_ID).1;
+				self.insert_number_pair(MOVEMENT_ID, Some(number), total);
+			}),
+			ItemKey::MovementTotal => set_number(&item, |total| {
+				let number = self.split_num_pair(&MOVEMENT_ID).0;
+				self.insert_number_pair(MOVEMENT_ID, number, Some(total));
+			}),
+			_ => {
+				if let Some(frame) = item.into() {
+					if let Some(replaced) = self.insert(frame) {
+						log::warn!("Replaced frame: {replaced:?}");
+					}
+				}
+			},
+		};
+	}
+
+	/// Returns all genres contained in a `TCON` frame.
+	///
+	/// This will translate any numeric genre IDs to their textual equivalent.
+	/// ID3v2.4-style multi-value fields will be split as normal.
+	pub fn genres(&self) -> Option<impl Iterator<Item = &str>> {
+		if let Some(Frame {
+			value: FrameValue::Text(TextInformationFrame { ref value, .. }),
+			..
+		}) = self.get(&GENRE_ID)
+		{
+			return Some(GenresIter::new(value));
+		}
+
+		None
+	}
+
+	// Replaces any numeric genre IDs in the `TCON` frame with their names, see `ParseOptions::resolve_id3v2_genres`
+	pub(crate) fn resolve_genres(&mut self) {
+		let Some(Frame {
+			value: FrameValue::Text(TextInformationFrame { value, .. }),
+			..
+		}) = self.frames.iter_mut().find(|frame| frame.id == GENRE_ID)
+		else {
+			return;
+		};
+
+		let resolved = GenresIter::new(value).collect::<Vec<_>>().join("\0");
+		*value = resolved;
+	}
+
+	/// Gets the timestamp from a frame, such as `TDRC`, `TDRL`, or `TDOR`
+	///
+	/// The timestamp is parsed with [`ParsingMode::BestAttempt`](crate::ParsingMode::BestAttempt), so
+	/// any out of range or malformed components are dropped. If the frame has multiple values, only the
+	/// first is used.
+	///
+	/// This will return `None` if the frame doesn't exist, or it doesn't start with a four digit year.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::{FrameId, Id3v2Tag};
+	/// use lofty::{Accessor, Timestamp};
+	/// use std::borrow::Cow;
+	///
+	/// const RECORDING_TIME_ID: FrameId<'_> = FrameId::Valid(Cow::Borrowed("TDRC"));
+	///
+	/// let mut tag = Id3v2Tag::new();
+	/// tag.set_timestamp(
+	/// 	RECORDING_TIME_ID,
+	/// 	Timestamp {
+	/// 		year: 2023,
+	/// 		month: Some(10),
+	/// 		..Timestamp::default()
+	/// 	},
+	/// );
+	///
+	/// assert_eq!(tag.get_text(&RECORDING_TIME_ID).as_deref(), Some("2023-10"));
+	/// assert_eq!(tag.timestamp(&RECORDING_TIME_ID).unwrap().month, Some(10));
+	/// assert_eq!(tag.year(), Some(2023));
+	/// ```
+	pub fn timestamp(&self, id: &FrameId<'_>) -> Option<Timestamp> {
+		let first_value = self.get_texts(id)?.next()?;
+		Timestamp::parse(first_value, ParsingMode::BestAttempt).ok()
+	}
+
+	/// Sets a timestamp frame, such as `TDRC`, `TDRL`, or `TDOR`
+	///
+	/// Only the components up to the first `None` are written, see [`Timestamp`].
+	pub fn set_timestamp(&mut self, id: FrameId<'static>, timestamp: Timestamp) {
+		self.insert(Frame::text(id.into_inner(), timestamp.to_string()));
+	}
+
+	/// Gets the play counter (`PCNT`)
+	///
+	/// See [`Id3v2Tag::increment_play_count`].
+	pub fn play_count(&self) -> Option<u64> {
+		match self.get(&PLAY_COUNTER_ID)?.content() {
+			FrameValue::PlayCounter(counter) => Some(*counter),
+			_ => None,
+		}
+	}
+
+	/// Increments the play counter (`PCNT`), returning the new count
+	///
+	/// If there is no play counter, one is created starting at 1. The counter saturates at `u64::MAX`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	///
+	/// let mut tag = Id3v2Tag::new();
+	/// assert_eq!(tag.play_count(), None);
+	///
+	/// assert_eq!(tag.increment_play_count(), 1);
+	/// assert_eq!(tag.increment_play_count(), 2);
+	/// assert_eq!(tag.play_count(), Some(2));
+	/// ```
+	pub fn increment_play_count(&mut self) -> u64 {
+		let existing = self.frames.iter_mut().find_map(|frame| match frame {
+			Frame {
+				id,
+				value: FrameValue::PlayCounter(counter),
+				..
+			} if *id == PLAY_COUNTER_ID => Some(counter),
+			_ => None,
+		});
+
+		if let Some(counter) = existing {
+			*counter = counter.saturating_add(1);
+			return *counter;
+		}
+
+		self.insert(Frame {
+			id: PLAY_COUNTER_ID,
+			value: FrameValue::PlayCounter(1),
+			flags: FrameFlags::default(),
+		});
+
+		1
+	}
+
+	// Validates the timestamp frames, see `ParsingMode::Strict`
+	pub(crate) fn verify_timestamps(&self) -> Result<()> {
+		for frame in &self.frames {
+			if !TIMESTAMP_FRAME_IDS.contains(&frame.id_str()) {
+				continue;
+			}
+
+			if let FrameValue::Text(text_frame) = &frame.value {
+				for value in text_frame.values() {
+					Timestamp::parse(strip_utc_offset(value), ParsingMode::Strict)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	fn insert_number_pair(
+		&mut self,
+		id: FrameId<'static>,
+		number: Option<u32>,
+		total: Option<u32>,
+	) {
+		if let Some(content) = format_number_pair(number, total) {
+			self.insert(Frame::text(id.into_inner(), content));
 		} else {
 			log::warn!("{id} is not set. number: {number:?}, total: {total:?}");
 		}
 	}
 }
 
+// ID3v2.4 timestamps have no time zone, but many taggers write one anyway (`Z`, `+01:00`, etc.).
+// `Timestamp` can't represent it, though it's no reason to reject the timestamp.
+fn strip_utc_offset(value: &str) -> &str {
+	let Some(time_start) = value.find('T') else {
+		return value;
+	};
+
+	let time = &value[time_start..];
+	let Some(offset_start) = time.find(['Z', '+', '-']) else {
+		return value;
+	};
+
+	let valid_offset = match &time.as_bytes()[offset_start..] {
+		[b'Z'] => true,
+		[b'+' | b'-', h1, h2] => h1.is_ascii_digit() && h2.is_ascii_digit(),
+		[b'+' | b'-', h1, h2, m1, m2] | [b'+' | b'-', h1, h2, b':', m1, m2] => {
+			[h1, h2, m1, m2].iter().all(|b| b.is_ascii_digit())
+		},
+		_ => false,
+	};
+
+	if valid_offset {
+		&value[..time_start + offset_start]
+	} else {
+		value
+	}
+}
+
 struct GenresIter<'a> {
 	value: &'a str,
 	pos: usize,
@@ -671,13 +1422,36 @@ fn new_text_frame(id: FrameId<'_>, value: String, flags: FrameFlags) -> Frame<'_
 	}
 }
 
-fn new_comment_frame(content: String, flags: FrameFlags) -> Frame<'static> {
+fn new_comment_frame(
+	language: [u8; 3],
+	description: String,
+	content: String,
+	flags: FrameFlags,
+) -> Frame<'static> {
 	Frame {
 		id: FrameId::Valid(Cow::Borrowed(COMMENT_FRAME_ID)),
 		value: FrameValue::Comment(CommentFrame {
 			encoding: TextEncoding::UTF8,
-			language: UNKNOWN_LANGUAGE,
-			description: EMPTY_CONTENT_DESCRIPTOR,
+			language,
+			description,
+			content,
+		}),
+		flags,
+	}
+}
+
+fn new_lyrics_frame(
+	language: [u8; 3],
+	description: String,
+	content: String,
+	flags: FrameFlags,
+) -> Frame<'static> {
+	Frame {
+		id: FrameId::Valid(Cow::Borrowed(LYRICS_FRAME_ID)),
+		value: FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
+			encoding: TextEncoding::UTF8,
+			language,
+			description,
 			content,
 		}),
 		flags,
@@ -695,13 +1469,40 @@ fn new_picture_frame(picture: Picture, flags: FrameFlags) -> Frame<'static> {
 	}
 }
 
+// `TBPM` can only hold an integer, so a fractional BPM is additionally stored in a `TXXX:BPM` frame
+fn new_bpm_frames(bpm: &str) -> impl Iterator<Item = Frame<'static>> + Clone {
+	let (integer, precise) = match split_bpm(bpm) {
+		Some((integer, precise)) => (integer.to_string(), precise),
+		None => (bpm.to_owned(), None),
+	};
+
+	let precise = precise.map(|precise| Frame {
+		id: FrameId::Valid(Cow::Borrowed(USER_DEFINED_TEXT_FRAME_ID)),
+		value: FrameValue::UserText(ExtendedTextFrame {
+			encoding: TextEncoding::UTF8,
+			description: String::from(BPM_DESCRIPTION),
+			content: precise.to_owned(),
+		}),
+		flags: FrameFlags::default(),
+	});
+
+	std::iter::once(new_text_frame(BPM_ID, integer, FrameFlags::default())).chain(precise)
+}
+
 const TITLE_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TIT2"));
 const ARTIST_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TPE1"));
 const ALBUM_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TALB"));
 const GENRE_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TCON"));
 const TRACK_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TRCK"));
 const DISC_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TPOS"));
+const MOVEMENT_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("MVIN"));
 const RECORDING_TIME_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TDRC"));
+const PLAY_COUNTER_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("PCNT"));
+const TIMESTAMP_FRAME_IDS: [&str; 3] = ["TDRC", "TDRL", "TDOR"];
+pub(in crate::id3::v2) const BPM_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TBPM"));
+pub(in crate::id3::v2) const BPM_DESCRIPTION: &str = "BPM";
+pub(in crate::id3::v2) const INITIAL_KEY_ID: FrameId<'static> =
+	FrameId::Valid(Cow::Borrowed("TKEY"));
 
 impl Accessor for Id3v2Tag {
 	impl_accessor!(
@@ -794,15 +1595,8 @@ impl Accessor for Id3v2Tag {
 	}
 
 	fn year(&self) -> Option<u32> {
-		if let Some(Frame {
-			value: FrameValue::Text(TextInformationFrame { value, .. }),
-			..
-		}) = self.get(&RECORDING_TIME_ID)
-		{
-			return try_parse_year(value);
-		}
-
-		None
+		self.timestamp(&RECORDING_TIME_ID)
+			.map(|timestamp| u32::from(timestamp.year))
 	}
 
 	fn set_year(&mut self, value: u32) {
@@ -838,8 +1632,12 @@ impl Accessor for Id3v2Tag {
 			}
 		});
 		if let Some(value) = value {
-			self.frames
-				.push(new_comment_frame(value, FrameFlags::default()));
+			self.frames.push(new_comment_frame(
+				UNKNOWN_LANGUAGE,
+				EMPTY_CONTENT_DESCRIPTOR,
+				value,
+				FrameFlags::default(),
+			));
 		}
 	}
 
@@ -866,6 +1664,7 @@ impl TagExt for Id3v2Tag {
 		self.frames.is_empty()
 	}
 
+	#[cfg(feature = "fs")]
 	/// Writes the tag to a file
 	///
 	/// # Errors
@@ -873,12 +1672,17 @@ impl TagExt for Id3v2Tag {
 	/// * Attempting to write the tag to a format that does not support it
 	/// * Attempting to write an encrypted frame without a valid method symbol or data length indicator
 	/// * Attempting to write an invalid [`FrameId`]/[`FrameValue`] pairing
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
+	/// * [`ErrorKind::Id3v2Restrictions`](crate::error::ErrorKind::Id3v2Restrictions)
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		Id3v2TagRef {
 			flags: self.flags,
 			frames: self.frames.iter().filter_map(Frame::as_opt_ref),
 		}
-		.write_to(file)
+		.write_to(file, write_options)
 	}
 
 	/// Dumps the tag to a writer
@@ -887,18 +1691,25 @@ impl TagExt for Id3v2Tag {
 	///
 	/// * [`std::io::Error`]
 	/// * [`ErrorKind::TooMuchData`](crate::error::ErrorKind::TooMuchData)
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
+	/// * [`ErrorKind::Id3v2Restrictions`](crate::error::ErrorKind::Id3v2Restrictions)
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		Id3v2TagRef {
 			flags: self.flags,
 			frames: self.frames.iter().filter_map(Frame::as_opt_ref),
 		}
-		.dump_to(writer)
+		.dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::Id3v2.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::Id3v2.remove_from(file)
 	}
@@ -909,11 +1720,17 @@ impl TagExt for Id3v2Tag {
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct SplitTagRemainder(Id3v2Tag);
+pub struct SplitTagRemainder {
+	tag: Id3v2Tag,
+	// The language of the comment that became `ItemKey::Comment`, so it can be written back as-is
+	pub(crate) comment_language: Option<[u8; 3]>,
+	// Same as `comment_language`, for the lyrics that became `ItemKey::Lyrics`
+	pub(crate) lyrics_language: Option<[u8; 3]>,
+}
 
 impl From<SplitTagRemainder> for Id3v2Tag {
 	fn from(from: SplitTagRemainder) -> Self {
-		from.0
+		from.tag
 	}
 }
 
@@ -921,7 +1738,7 @@ impl Deref for SplitTagRemainder {
 	type Target = Id3v2Tag;
 
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		&self.tag
 	}
 }
 
@@ -977,6 +1794,8 @@ impl SplitTag for Id3v2Tag {
 		}
 
 		let mut tag = Tag::new(TagType::Id3v2);
+		let mut comment_language = None;
+		let mut lyrics_language = None;
 
 		self.frames.retain_mut(|frame| {
 			let id = &frame.id;
@@ -1039,6 +1858,59 @@ impl SplitTag for Id3v2Tag {
 
 					!key_value_pairs.is_empty() // Frame is consumed if we consumed all items
 				},
+				// Only the general comment can be represented by `ItemKey::Comment`, any others
+				// (other languages, application data such as `iTunNORM`) are kept as-is
+				(
+					COMMENT_FRAME_ID,
+					FrameValue::Comment(CommentFrame {
+						language,
+						ref description,
+						ref content,
+						..
+					}),
+				) => {
+					if comment_language.is_some() || *description != EMPTY_CONTENT_DESCRIPTOR {
+						return true; // Keep frame
+					}
+
+					comment_language = Some(*language);
+					for c in content.split(V4_MULTI_VALUE_SEPARATOR) {
+						tag.items.push(TagItem::new(
+							ItemKey::Comment,
+							ItemValue::Text(c.to_string()),
+						));
+					}
+					false // Frame consumed
+				},
+				// Only the default lyrics can be represented by `ItemKey::Lyrics`, any others
+				// (translations, alternate descriptions) are kept as-is
+				(
+					LYRICS_FRAME_ID,
+					FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
+						language,
+						ref description,
+						ref mut content,
+						..
+					}),
+				) => {
+					if lyrics_language.is_some() || *description != EMPTY_CONTENT_DESCRIPTOR {
+						return true; // Keep frame
+					}
+
+					lyrics_language = Some(*language);
+					tag.items.push(TagItem::new(
+						ItemKey::Lyrics,
+						ItemValue::Text(std::mem::take(content)),
+					));
+					false // Frame consumed
+				},
+				(PODCAST_FLAG_FRAME_ID, FrameValue::Binary(_)) => {
+					tag.items.push(TagItem::new(
+						ItemKey::FlagPodcast,
+						ItemValue::Text(String::from("1")),
+					));
+					false // Frame consumed
+				},
 				// Store TXXX/WXXX frames by their descriptions, rather than their IDs
 				(
 					"TXXX",
@@ -1083,15 +1955,12 @@ impl SplitTag for Id3v2Tag {
 					}),
 				) => {
 					if owner == MUSICBRAINZ_UFID_OWNER {
-						let mut identifier = Cursor::new(identifier);
-						let Ok(recording_id) =
-							decode_text(&mut identifier, TextEncoding::Latin1, false)
-						else {
+						let Ok(recording_id) = utf8_decode_str(identifier) else {
 							return true; // Keep frame
 						};
 						tag.items.push(TagItem::new(
 							ItemKey::MusicBrainzRecordingId,
-							ItemValue::Text(recording_id.content),
+							ItemValue::Text(recording_id.to_owned()),
 						));
 						false // Frame consumed
 					} else {
@@ -1155,13 +2024,19 @@ impl SplitTag for Id3v2Tag {
 						FrameValue::Popularimeter(popularimeter) => {
 							ItemValue::Binary(popularimeter.as_bytes())
 						},
+						FrameValue::PlayCounter(counter) => ItemValue::Text(counter.to_string()),
 						FrameValue::Binary(binary) => ItemValue::Binary(std::mem::take(binary)),
 						FrameValue::KeyValue(_)
 						| FrameValue::UniqueFileIdentifier(_)
 						| FrameValue::RelativeVolumeAdjustment(_)
 						| FrameValue::Ownership(_)
 						| FrameValue::EventTimingCodes(_)
-						| FrameValue::Private(_) => {
+						| FrameValue::Private(_)
+						| FrameValue::Chapter(_)
+						| FrameValue::TableOfContents(_)
+						| FrameValue::SynchronizedText(_)
+						| FrameValue::GeneralEncapsulatedObject(_)
+						| FrameValue::Seek(_) => {
 							return true; // Keep unsupported frame
 						},
 					};
@@ -1172,8 +2047,17 @@ impl SplitTag for Id3v2Tag {
 			}
 		});
 
-		(SplitTagRemainder(self), tag)
-	}
+		retain_precise_bpm(&mut tag);
+
+		(
+			SplitTagRemainder {
+				tag: self,
+				comment_language,
+				lyrics_language,
+			},
+			tag,
+		)
+	}
 }
 
 impl MergeTag for SplitTagRemainder {
@@ -1215,9 +2099,15 @@ impl MergeTag for SplitTagRemainder {
 			concatenated
 		}
 
-		let Self(mut merged) = self;
+		let Self {
+			tag: mut merged,
+			comment_language,
+			lyrics_language,
+		} = self;
 		merged.frames.reserve(tag.item_count() as usize);
 
+		convert_rating_items(&mut tag.items, TagType::Id3v2);
+
 		// Multi-valued text key-to-frame mappings
 		// TODO: Extend this list of item keys as needed or desired
 		for item_key in [
@@ -1237,18 +2127,16 @@ impl MergeTag for SplitTagRemainder {
 			&ItemKey::Composer,
 			&ItemKey::Conductor,
 			&ItemKey::Writer,
-			&ItemKey::Director,
 			&ItemKey::Lyricist,
 			&ItemKey::MusicianCredits,
 			&ItemKey::InternetRadioStationName,
 			&ItemKey::InternetRadioStationOwner,
 			&ItemKey::Remixer,
-			&ItemKey::Work,
 			&ItemKey::Movement,
 			&ItemKey::FileOwner,
 			&ItemKey::CopyrightMessage,
 			&ItemKey::Language,
-			&ItemKey::Lyrics,
+			&ItemKey::Isrc,
 		] {
 			let frame_id = item_key
 				.map_key(TagType::Id3v2, false)
@@ -1286,13 +2174,51 @@ impl MergeTag for SplitTagRemainder {
 			}
 		}
 
+		// Multi-valued Work key-to-frame mapping
+		//
+		// `WORK` isn't a frame ID, it's the description of a `TXXX` frame
+		if let Some(content) = join_text_items(&mut tag, &[ItemKey::Work]) {
+			let description = ItemKey::Work
+				.map_key(TagType::Id3v2, false)
+				.expect("valid description");
+			merged.insert(Frame {
+				id: FrameId::Valid(Cow::Borrowed(USER_DEFINED_TEXT_FRAME_ID)),
+				value: FrameValue::UserText(ExtendedTextFrame {
+					encoding: TextEncoding::UTF8,
+					description: String::from(description),
+					content,
+				}),
+				flags: FrameFlags::default(),
+			});
+		}
+
 		// Multi-valued Comment key-to-frame mapping
+		//
+		// Other `COMM` frames may have been retained, so this has to go through `insert`
 		if let Some(text) = join_text_items(&mut tag, &[ItemKey::Comment]) {
-			let frame = new_comment_frame(text, FrameFlags::default());
-			// Optimization: No duplicate checking according to the preconditions
-			debug_assert!(!merged.frames.contains(&frame));
-			merged.frames.push(frame);
-		};
+			merged.insert(new_comment_frame(
+				comment_language
+					.filter(|language| verify_language(*language).is_ok())
+					.unwrap_or(UNKNOWN_LANGUAGE),
+				EMPTY_CONTENT_DESCRIPTOR,
+				text,
+				FrameFlags::default(),
+			));
+		}
+
+		// Lyrics key-to-frame mapping
+		//
+		// Other `USLT` frames may have been retained, so this has to go through `insert`
+		if let Some(text) = join_text_items(&mut tag, &[ItemKey::Lyrics]) {
+			merged.insert(new_lyrics_frame(
+				lyrics_language
+					.filter(|language| verify_language(*language).is_ok())
+					.unwrap_or(UNDETERMINED_LANGUAGE),
+				EMPTY_CONTENT_DESCRIPTOR,
+				text,
+				FrameFlags::default(),
+			));
+		}
 
 		// TIPL key-value mappings
 		'tipl: {
@@ -1333,6 +2259,13 @@ impl MergeTag for SplitTagRemainder {
 			});
 		}
 
+		// BPM key-to-frames mapping
+		if let Some(bpm) = tag.take_strings(&ItemKey::Bpm).next() {
+			for frame in new_bpm_frames(&bpm) {
+				merged.insert(frame);
+			}
+		}
+
 		// Insert all remaining items as single frames and deduplicate as needed
 		for item in tag.items {
 			merged.insert_item(item);
@@ -1396,9 +2329,27 @@ pub(crate) fn tag_frames(tag: &Tag) -> impl Iterator<Item = FrameRef<'_>> + Clon
 
 	let items = tag
 		.items()
-		.filter(|item| !NUMBER_PAIR_KEYS.contains(item.key()))
+		.filter(|item| {
+			!NUMBER_PAIR_KEYS.contains(item.key())
+				&& item.key() != &ItemKey::MovementNumber
+				&& item.key() != &ItemKey::MovementTotal
+				&& item.key() != &ItemKey::Bpm
+				&& item.key() != &ItemKey::Isrc
+		})
 		.map(TryInto::<FrameRef<'_>>::try_into)
 		.filter_map(Result::ok)
+		// A medley has an ISRC for each of its tracks, which have to share a single `TSRC` frame
+		.chain(isrc_frame(tag))
+		.chain(
+			tag.get_string(&ItemKey::Bpm)
+				.into_iter()
+				.flat_map(new_bpm_frames)
+				.map(|frame| FrameRef {
+					id: frame.id,
+					value: Cow::Owned(frame.value),
+					flags: frame.flags,
+				}),
+		)
 		.chain(create_frameref_for_number_pair(
 			tag.get_string(&ItemKey::TrackNumber),
 			tag.get_string(&ItemKey::TrackTotal),
@@ -1408,6 +2359,11 @@ pub(crate) fn tag_frames(tag: &Tag) -> impl Iterator<Item = FrameRef<'_>> + Clon
 			tag.get_string(&ItemKey::DiscNumber),
 			tag.get_string(&ItemKey::DiscTotal),
 			"TPOS",
+		))
+		.chain(create_frameref_for_number_pair(
+			tag.get_string(&ItemKey::MovementNumber),
+			tag.get_string(&ItemKey::MovementTotal),
+			"MVIN",
 		));
 
 	let pictures = tag.pictures().iter().map(|p| FrameRef {
@@ -1422,13 +2378,37 @@ pub(crate) fn tag_frames(tag: &Tag) -> impl Iterator<Item = FrameRef<'_>> + Clon
 	items.chain(pictures)
 }
 
+fn isrc_frame(tag: &Tag) -> Option<FrameRef<'static>> {
+	let isrcs = tag.get_strings(&ItemKey::Isrc).collect::<Vec<_>>();
+	if isrcs.is_empty() {
+		return None;
+	}
+
+	let frame = Frame::text(
+		Cow::Borrowed("TSRC"),
+		isrcs.join(&V4_MULTI_VALUE_SEPARATOR.to_string()),
+	);
+	Some(FrameRef {
+		id: frame.id,
+		value: Cow::Owned(frame.value),
+		flags: frame.flags,
+	})
+}
+
 impl<'a, I: Iterator<Item = FrameRef<'a>> + Clone + 'a> Id3v2TagRef<'a, I> {
-	pub(crate) fn write_to(&mut self, file: &mut File) -> Result<()> {
-		super::write::write_id3v2(file, self)
+	pub(crate) fn write_to<F>(&mut self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		super::write::write_id3v2(file, self, write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let temp = super::write::create_tag(self)?;
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let temp = super::write::create_tag(self, write_options, 0)?;
 		writer.write_all(&temp)?;
 
 		Ok(())
@@ -1437,35 +2417,49 @@ impl<'a, I: Iterator<Item = FrameRef<'a>> + Clone + 'a> Id3v2TagRef<'a, I> {
 
 #[cfg(test)]
 mod tests {
-	use crate::ParsingMode;
+	use crate::{ParseOptions, ParsingMode};
 	use std::borrow::Cow;
 
-	use crate::id3::v2::frame::MUSICBRAINZ_UFID_OWNER;
+	use crate::id3::v2::frame::{MUSICBRAINZ_UFID_OWNER, UNDETERMINED_LANGUAGE};
 	use crate::id3::v2::header::{Id3v2Header, Id3v2Version};
 	use crate::id3::v2::items::{ExtendedUrlFrame, Popularimeter, UniqueFileIdentifierFrame};
-	use crate::id3::v2::tag::{filter_comment_frame_by_description, new_text_frame};
+	use crate::id3::v2::tag::{
+		filter_comment_frame_by_description, new_lyrics_frame, new_text_frame,
+	};
 	use crate::id3::v2::util::mappings::TIPL_MAPPINGS;
 	use crate::id3::v2::util::pairs::DEFAULT_NUMBER_IN_PAIR;
+	use crate::id3::v2::util::synchsafe::SynchsafeInteger as _;
 	use crate::id3::v2::{
-		AttachedPictureFrame, CommentFrame, ExtendedTextFrame, Frame, FrameFlags, FrameId,
-		FrameValue, Id3v2Tag, KeyValueFrame, TextInformationFrame, UrlLinkFrame,
+		AttachedPictureFrame, ChapterFrame, CommentFrame, EventType, ExtendedTextFrame, Frame,
+		FrameFlags, FrameId, FrameValue, GeneralEncapsulatedObject, Id3v2Tag, KeyValueFrame,
+		SyncTextContentType, SynchronizedText, TableOfContentsFrame, TextInformationFrame,
+		TimestampFormat, UrlLinkFrame,
 	};
+	use crate::mp4::{AtomData, AtomIdent, Ilst};
 	use crate::tag::utils::test_utils::read_path;
 	use crate::util::text::TextEncoding;
 	use crate::{
-		Accessor as _, ItemKey, ItemValue, MergeTag as _, MimeType, Picture, PictureType,
-		SplitTag as _, Tag, TagExt as _, TagItem, TagType,
+		Accessor as _, BpmRounding, ItemKey, ItemValue, MergeTag as _, MimeType, Picture,
+		PictureType, SplitTag as _, Tag, TagExt as _, TagItem, TagType, WriteOptions,
 	};
 
-	use super::{COMMENT_FRAME_ID, EMPTY_CONTENT_DESCRIPTOR, GENRE_ID};
+	use super::{COMMENT_FRAME_ID, EMPTY_CONTENT_DESCRIPTOR, GENRE_ID, RECORDING_TIME_ID};
 
 	fn read_tag(path: &str) -> Id3v2Tag {
 		let tag_bytes = crate::tag::utils::test_utils::read_path(path);
+		read_tag_bytes(&tag_bytes)
+	}
 
-		let mut reader = std::io::Cursor::new(&tag_bytes[..]);
+	fn read_tag_bytes(tag_bytes: &[u8]) -> Id3v2Tag {
+		let mut reader = std::io::Cursor::new(tag_bytes);
 
 		let header = Id3v2Header::parse(&mut reader).unwrap();
-		crate::id3::v2::read::parse_id3v2(&mut reader, header, ParsingMode::Strict).unwrap()
+		crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap()
 	}
 
 	#[test]
@@ -1571,14 +2565,19 @@ mod tests {
 		let parsed_tag = read_tag("tests/tags/assets/id3v2/test.id3v24");
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let temp_reader = &mut &*writer;
 
 		let temp_header = Id3v2Header::parse(temp_reader).unwrap();
-		let temp_parsed_tag =
-			crate::id3::v2::read::parse_id3v2(temp_reader, temp_header, ParsingMode::Strict)
-				.unwrap();
+		let temp_parsed_tag = crate::id3::v2::read::parse_id3v2(
+			temp_reader,
+			temp_header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
 
 		assert_eq!(parsed_tag, temp_parsed_tag);
 	}
@@ -1653,7 +2652,7 @@ mod tests {
 			flags: FrameFlags::default(),
 		});
 
-		let res = tag.dump_to(&mut Vec::<u8>::new());
+		let res = tag.dump_to(&mut Vec::<u8>::new(), WriteOptions::default());
 
 		assert!(res.is_err());
 		assert_eq!(
@@ -1697,7 +2696,7 @@ mod tests {
 			frame.content(),
 			&FrameValue::Comment(CommentFrame {
 				encoding: TextEncoding::Latin1,
-				language: *b"eng",
+				language: *b"XXX",
 				description: EMPTY_CONTENT_DESCRIPTOR,
 				content: String::from("Qux comment")
 			})
@@ -1811,6 +2810,250 @@ mod tests {
 		assert_eq!(tag, parsed_tag);
 	}
 
+	#[test]
+	fn id3v23_extended_header() {
+		// Unlike ID3v2.4, the extended header size isn't synchsafe and excludes itself,
+		// and is followed by a padding size and a CRC
+		let tag = read_tag("tests/tags/assets/id3v2/extended_header.id3v23");
+
+		assert_eq!(tag.len(), 4);
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+		assert_eq!(tag.album().as_deref(), Some("Baz album"));
+		assert_eq!(tag.track(), Some(1));
+		assert_eq!(tag.track_total(), Some(10));
+		assert!(tag.flags().crc);
+		assert_eq!(tag.crc().unwrap().is_valid(), Some(true));
+	}
+
+	#[test]
+	fn id3v23_malformed_extended_header() {
+		let mut tag_bytes = read_path("tests/tags/assets/id3v2/extended_header.id3v23");
+
+		// Clear the CRC flag, the size (10) no longer matches
+		tag_bytes[14] = 0;
+
+		let mut reader = std::io::Cursor::new(&tag_bytes[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		assert!(crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict)
+		)
+		.is_err());
+
+		// The extended header should just be skipped
+		let mut reader = std::io::Cursor::new(&tag_bytes[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let tag = crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::BestAttempt),
+		)
+		.unwrap();
+
+		assert_eq!(tag.len(), 4);
+		assert_eq!(tag.artist().as_deref(), Some("Bar artist"));
+		assert!(!tag.flags().crc);
+	}
+
+	// The frame IDs of an ID3v2.3 tag, walking the frames by their (non-synchsafe) sizes
+	fn v3_frame_ids(tag_bytes: &[u8]) -> Vec<String> {
+		assert_eq!(&tag_bytes[..4], b"ID3\x03");
+
+		let mut ids = Vec::new();
+		let mut content = &tag_bytes[10..];
+		while content.len() >= 10 && content[0] != 0 {
+			ids.push(String::from_utf8(content[..4].to_vec()).unwrap());
+
+			let size = u32::from_be_bytes(content[4..8].try_into().unwrap()) as usize;
+			content = &content[10 + size..];
+		}
+
+		assert!(content.iter().all(|b| *b == 0));
+		ids
+	}
+
+	#[test]
+	fn id3v23_write_full() {
+		let tag = read_tag("tests/tags/assets/id3v2/test_full.id3v23");
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::new().use_id3v23(true))
+			.unwrap();
+
+		// Same frames, in the same order, as the reference file
+		let reference = read_path("tests/tags/assets/id3v2/test_full.id3v23");
+		assert_eq!(v3_frame_ids(&writer), v3_frame_ids(&reference));
+
+		let parsed_tag = read_tag_bytes(&writer);
+		assert_eq!(parsed_tag, create_full_test_tag(Id3v2Version::V3));
+	}
+
+	#[test]
+	fn id3v24_to_id3v23() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.insert(new_text_frame(
+			FrameId::Valid(Cow::Borrowed("TPE1")),
+			String::from("Bar artist\0Baz artist"),
+			FrameFlags::default(),
+		));
+		tag.insert(new_text_frame(
+			FrameId::Valid(Cow::Borrowed("TDRC")),
+			String::from("2017-03-04T12:30:59"),
+			FrameFlags::default(),
+		));
+		tag.insert(new_text_frame(
+			FrameId::Valid(Cow::Borrowed("TDOR")),
+			String::from("2016-01-01"),
+			FrameFlags::default(),
+		));
+		tag.insert(new_text_frame(
+			FrameId::Valid(Cow::Borrowed("TSOA")),
+			String::from("Qux album"),
+			FrameFlags::default(),
+		));
+		tag.insert(new_text_frame(
+			FrameId::Valid(Cow::Borrowed("TMOO")),
+			String::from("Happy"),
+			FrameFlags::default(),
+		));
+		for (id, key, value) in [("TIPL", "producer", "Foo"), ("TMCL", "guitar", "Bar")] {
+			tag.insert(Frame {
+				id: FrameId::Valid(Cow::Borrowed(id)),
+				value: FrameValue::KeyValue(KeyValueFrame {
+					encoding: TextEncoding::UTF8,
+					key_value_pairs: vec![(String::from(key), String::from(value))],
+				}),
+				flags: FrameFlags::default(),
+			});
+		}
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::new().use_id3v23(true))
+			.unwrap();
+
+		assert_eq!(
+			v3_frame_ids(&writer),
+			["TIT2", "TPE1", "TYER", "TDAT", "TIME", "TORY", "TSOA", "IPLS"]
+		);
+
+		let parsed_tag = read_tag_bytes(&writer);
+		assert_eq!(parsed_tag.original_version(), Id3v2Version::V3);
+		assert_eq!(parsed_tag.len(), 6);
+
+		// UTF-8 isn't available in ID3v2.3
+		let Some(Frame {
+			value: FrameValue::Text(TextInformationFrame { encoding, value }),
+			..
+		}) = parsed_tag.get(&FrameId::Valid(Cow::Borrowed("TIT2")))
+		else {
+			panic!("Expected a TIT2 frame");
+		};
+		assert_eq!(*encoding, TextEncoding::UTF16);
+		assert_eq!(value, "Foo title");
+
+		assert_eq!(
+			parsed_tag.artist().as_deref(),
+			Some("Bar artist/Baz artist")
+		);
+
+		// The seconds are lost, ID3v2.3 only stores the hours and minutes
+		assert_eq!(
+			parsed_tag
+				.get_text(&FrameId::Valid(Cow::Borrowed("TDRC")))
+				.as_deref(),
+			Some("2017-03-04T12:30")
+		);
+		assert_eq!(
+			parsed_tag
+				.get_text(&FrameId::Valid(Cow::Borrowed("TDOR")))
+				.as_deref(),
+			Some("2016")
+		);
+		assert_eq!(
+			parsed_tag
+				.get_text(&FrameId::Valid(Cow::Borrowed("TSOA")))
+				.as_deref(),
+			Some("Qux album")
+		);
+
+		// `TIPL` and `TMCL` come back as a single `TIPL` frame
+		let Some(Frame {
+			value: FrameValue::KeyValue(KeyValueFrame {
+				key_value_pairs, ..
+			}),
+			..
+		}) = parsed_tag.get(&FrameId::Valid(Cow::Borrowed("TIPL")))
+		else {
+			panic!("Expected a TIPL frame");
+		};
+		assert_eq!(
+			key_value_pairs,
+			&[
+				(String::from("producer"), String::from("Foo")),
+				(String::from("guitar"), String::from("Bar"))
+			]
+		);
+	}
+
+	#[test]
+	fn multi_value_text_frames() {
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.push(TagItem::new(
+			ItemKey::TrackArtist,
+			ItemValue::Text(String::from("Foo artist")),
+		));
+		tag.push(TagItem::new(
+			ItemKey::TrackArtist,
+			ItemValue::Text(String::from("Bar artist")),
+		));
+
+		// Joined into a single frame
+		let id3v2 = Id3v2Tag::from(tag);
+		let Some(Frame {
+			value: FrameValue::Text(text_frame),
+			..
+		}) = id3v2.get(&FrameId::Valid(Cow::Borrowed("TPE1")))
+		else {
+			panic!("Expected a TPE1 frame");
+		};
+		assert_eq!(
+			text_frame.values().collect::<Vec<_>>(),
+			["Foo artist", "Bar artist"]
+		);
+
+		let mut writer = Vec::new();
+		id3v2.dump_to(&mut writer, WriteOptions::default()).unwrap();
+		let parsed_tag = read_tag_bytes(&writer);
+
+		// And split back into an item per value
+		let tag = Tag::from(parsed_tag);
+		assert_eq!(
+			tag.get_strings(&ItemKey::TrackArtist).collect::<Vec<_>>(),
+			["Foo artist", "Bar artist"]
+		);
+
+		for (write_options, expected) in [
+			(
+				WriteOptions::new().use_id3v23(true),
+				"Foo artist/Bar artist",
+			),
+			(
+				WriteOptions::new().use_id3v23(true).id3v23_separator("; "),
+				"Foo artist; Bar artist",
+			),
+		] {
+			let mut writer = Vec::new();
+			id3v2.dump_to(&mut writer, write_options).unwrap();
+
+			let parsed_tag = read_tag_bytes(&writer);
+			assert_eq!(parsed_tag.original_version(), Id3v2Version::V3);
+			assert_eq!(parsed_tag.artist().as_deref(), Some(expected));
+		}
+	}
+
 	#[test]
 	fn id3v22_full() {
 		let tag = create_full_test_tag(Id3v2Version::V2);
@@ -1825,12 +3068,17 @@ mod tests {
 		tag.flags.footer = true;
 
 		let mut writer = Vec::new();
-		tag.dump_to(&mut writer).unwrap();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
 
 		let mut reader = &mut &writer[..];
 
 		let header = Id3v2Header::parse(&mut reader).unwrap();
-		assert!(crate::id3::v2::read::parse_id3v2(reader, header, ParsingMode::Strict).is_ok());
+		assert!(crate::id3::v2::read::parse_id3v2(
+			reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict)
+		)
+		.is_ok());
 
 		assert_eq!(writer[3..10], writer[writer.len() - 7..])
 	}
@@ -1850,12 +3098,17 @@ mod tests {
 		tag.push_picture(picture.clone());
 
 		let mut writer = Vec::new();
-		tag.dump_to(&mut writer).unwrap();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
 
 		let mut reader = &mut &writer[..];
 
 		let header = Id3v2Header::parse(&mut reader).unwrap();
-		let tag = crate::id3::v2::read::parse_id3v2(reader, header, ParsingMode::Strict).unwrap();
+		let tag = crate::id3::v2::read::parse_id3v2(
+			reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
 
 		assert_eq!(tag.len(), 1);
 		assert_eq!(
@@ -1889,6 +3142,52 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn pcnt_frame() {
+		let mut tag = Id3v2Tag::default();
+		tag.insert(Frame {
+			id: FrameId::Valid(Cow::Borrowed("PCNT")),
+			value: FrameValue::PlayCounter(u64::from(u32::MAX) + 1),
+			flags: FrameFlags::default(),
+		});
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		// The counter grows past 4 bytes when needed
+		assert!(writer.ends_with(b"PCNT\0\0\0\x05\0\0\x01\0\0\0\0"));
+
+		let mut parsed_tag = read_tag_bytes(&writer);
+		assert_eq!(parsed_tag.play_count(), Some(u64::from(u32::MAX) + 1));
+		assert_eq!(parsed_tag.increment_play_count(), u64::from(u32::MAX) + 2);
+
+		let (split_remainder, split_tag) = parsed_tag.split_tag();
+		assert!(split_remainder.is_empty());
+		assert_eq!(
+			split_tag.get_string(&ItemKey::PlayCount),
+			Some("4294967297")
+		);
+
+		let merged = split_remainder.merge_tag(split_tag);
+		assert_eq!(merged.play_count(), Some(u64::from(u32::MAX) + 2));
+	}
+
+	#[test]
+	fn increment_play_count() {
+		let mut tag = Id3v2Tag::default();
+		assert_eq!(tag.increment_play_count(), 1);
+		assert_eq!(tag.play_count(), Some(1));
+		assert_eq!(tag.len(), 1);
+
+		tag.insert(Frame {
+			id: FrameId::Valid(Cow::Borrowed("PCNT")),
+			value: FrameValue::PlayCounter(u64::MAX),
+			flags: FrameFlags::default(),
+		});
+		assert_eq!(tag.increment_play_count(), u64::MAX);
+		assert_eq!(tag.len(), 1);
+	}
+
 	#[test]
 	fn multi_value_frame_to_tag() {
 		use crate::traits::Accessor;
@@ -2016,13 +3315,152 @@ mod tests {
 		let id3v2 = Id3v2Tag::from(tag.clone());
 		let (split_remainder, split_tag) = id3v2.split_tag();
 
-		assert_eq!(0, split_remainder.0.len());
+		assert_eq!(0, split_remainder.len());
 		assert_eq!(tag.len(), split_tag.len());
 		// The ordering of items/frames matters, see above!
 		// TODO: Replace with an unordered comparison.
 		assert_eq!(tag.items, split_tag.items);
 	}
 
+	#[test]
+	fn credits_and_language_roundtrip() {
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.insert_text(ItemKey::Lyricist, "Lyricist".to_owned());
+		tag.insert_text(ItemKey::Director, "Director".to_owned());
+		tag.insert_text(ItemKey::OriginalMediaType, "CD".to_owned());
+		tag.insert_text(ItemKey::Language, "eng".to_owned());
+		tag.push(TagItem::new(
+			ItemKey::Language,
+			ItemValue::Text("deu".to_owned()),
+		));
+
+		let id3v2 = Id3v2Tag::from(tag.clone());
+		assert_eq!(
+			id3v2
+				.get_text(&FrameId::Valid(Cow::Borrowed("TEXT")))
+				.as_deref(),
+			Some("Lyricist")
+		);
+		assert_eq!(id3v2.get_user_text("DIRECTOR"), Some("Director"));
+		assert_eq!(
+			id3v2
+				.get_text(&FrameId::Valid(Cow::Borrowed("TMED")))
+				.as_deref(),
+			Some("CD")
+		);
+		assert_eq!(
+			id3v2
+				.get_texts(&FrameId::Valid(Cow::Borrowed("TLAN")))
+				.unwrap()
+				.collect::<Vec<_>>(),
+			["eng", "deu"]
+		);
+
+		// Every frame must be writable
+		let mut writer = Vec::new();
+		id3v2.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let (split_remainder, split_tag) = id3v2.split_tag();
+		assert_eq!(0, split_remainder.len());
+		assert_eq!(tag.len(), split_tag.len());
+		for key in [
+			ItemKey::Lyricist,
+			ItemKey::Director,
+			ItemKey::OriginalMediaType,
+		] {
+			assert_eq!(tag.get_string(&key), split_tag.get_string(&key));
+		}
+
+		let languages = split_tag
+			.get_strings(&ItemKey::Language)
+			.collect::<Vec<_>>();
+		assert_eq!(languages, ["eng", "deu"]);
+	}
+
+	#[test]
+	fn fractional_bpm() {
+		let bpm_id = FrameId::Valid(Cow::Borrowed("TBPM"));
+
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.set_bpm(126.5);
+
+		let id3v2 = Id3v2Tag::from(tag.clone());
+		assert_eq!(id3v2.get_text(&bpm_id).as_deref(), Some("127"));
+		assert_eq!(id3v2.get_user_text("BPM"), Some("126.5"));
+
+		// `TBPM` is derived from `TXXX:BPM` on write
+		let mut writer = Vec::new();
+		id3v2
+			.dump_to(
+				&mut writer,
+				WriteOptions::new().bpm_rounding(BpmRounding::Down),
+			)
+			.unwrap();
+
+		let re_read = read_tag_bytes(&writer);
+		assert_eq!(re_read.get_text(&bpm_id).as_deref(), Some("126"));
+
+		// Only the precise BPM is kept
+		let (_, split_tag) = re_read.split_tag();
+		assert_eq!(split_tag.get_strings(&ItemKey::Bpm).count(), 1);
+		assert_eq!(split_tag.bpm(), Some(126.5));
+
+		// Same for the generic `Tag` write path
+		let mut writer = Vec::new();
+		tag.dump_to(
+			&mut writer,
+			WriteOptions::new().bpm_rounding(BpmRounding::Up),
+		)
+		.unwrap();
+
+		let re_read = read_tag_bytes(&writer);
+		assert_eq!(re_read.get_text(&bpm_id).as_deref(), Some("127"));
+		assert_eq!(re_read.get_user_text("BPM"), Some("126.5"));
+
+		// An integer BPM doesn't need a `TXXX` frame
+		tag.set_bpm(128.0);
+
+		let id3v2 = Id3v2Tag::from(tag);
+		assert_eq!(id3v2.get_text(&bpm_id).as_deref(), Some("128"));
+		assert!(id3v2.get_user_text("BPM").is_none());
+	}
+
+	#[test]
+	fn initial_key_normalized() {
+		let key_id = FrameId::Valid(Cow::Borrowed("TKEY"));
+
+		for (key, expected) in [
+			("G# minor", "G#m"),
+			("01A", "Abm"),
+			("Off", "o"),
+			("Eb", "Eb"),
+		] {
+			let mut tag = Id3v2Tag::default();
+			tag.insert(new_text_frame(
+				key_id.clone(),
+				key.to_string(),
+				FrameFlags::default(),
+			));
+
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+			let re_read = read_tag_bytes(&writer);
+			assert_eq!(re_read.get_text(&key_id).as_deref(), Some(expected));
+		}
+
+		// Unrecognized keys are written as-is
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.insert_text(ItemKey::InitialKey, String::from("Weird key"));
+		assert!(tag.initial_key().is_none());
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let re_read = read_tag_bytes(&writer);
+		assert_eq!(re_read.get_text(&key_id).as_deref(), Some("Weird key"));
+	}
+
 	#[test]
 	fn comments() {
 		let mut tag = Id3v2Tag::default();
@@ -2140,7 +3578,7 @@ mod tests {
 		);
 
 		let (split_remainder, split_tag) = id3v2.split_tag();
-		assert_eq!(split_remainder.0.len(), 0);
+		assert_eq!(split_remainder.len(), 0);
 		assert_eq!(split_tag.len(), 1);
 
 		let id3v2 = split_remainder.merge_tag(split_tag);
@@ -2165,15 +3603,19 @@ mod tests {
 		assert_eq!(tag.len(), 1);
 
 		let mut content = Vec::new();
-		tag.dump_to(&mut content).unwrap();
+		tag.dump_to(&mut content, WriteOptions::default()).unwrap();
 		assert!(!content.is_empty());
 
 		// And verify we can reread the tag
 		let mut reader = std::io::Cursor::new(&content[..]);
 
 		let header = Id3v2Header::parse(&mut reader).unwrap();
-		let reparsed =
-			crate::id3::v2::read::parse_id3v2(&mut reader, header, ParsingMode::Strict).unwrap();
+		let reparsed = crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
 
 		assert_eq!(id3v2, reparsed);
 	}
@@ -2484,7 +3926,7 @@ mod tests {
 		assert_eq!(2, id3v2.len());
 
 		let (split_remainder, split_tag) = id3v2.split_tag();
-		assert_eq!(split_remainder.0.len(), 1);
+		assert_eq!(split_remainder.len(), 1);
 		assert_eq!(split_tag.len(), 1);
 		assert_eq!(
 			ItemValue::Text(String::from_utf8(musicbrainz_recording_id.to_vec()).unwrap()),
@@ -2720,22 +4162,123 @@ mod tests {
 	}
 
 	#[test]
-	fn tipl_round_trip() {
-		let mut tag = Id3v2Tag::default();
-		let mut tipl = KeyValueFrame {
-			encoding: TextEncoding::UTF8,
-			key_value_pairs: Vec::new(),
-		};
+	fn resolve_genres() {
+		for (genre, expected) in [
+			("(17)", &["Rock"][..]),
+			("17", &["Rock"]),
+			("(17)(RX)(CR)", &["Rock", "Remix", "Cover"]),
+			("(4)Eurodisco", &["Disco", "Eurodisco"]),
+			("Rock\017", &["Rock", "Rock"]),
+			("Samba-rock", &["Samba-rock"]),
+			("(255)", &["255"]),
+		] {
+			let mut tag = id3v2_tag_with_genre(genre);
+			tag.resolve_genres();
 
-		// Add all supported keys
-		for (_, key) in TIPL_MAPPINGS {
-			tipl.key_value_pairs
-				.push((String::from(*key), String::from("Serial-ATA")));
+			let genres = tag.get_texts(&GENRE_ID).unwrap().collect::<Vec<_>>();
+			assert_eq!(genres, expected);
 		}
+	}
 
-		// Add one unsupported key
-		tipl.key_value_pairs
-			.push((String::from("Foo"), String::from("Bar")));
+	#[test]
+	fn timestamps() {
+		let mut tag = Id3v2Tag::default();
+		tag.insert(Frame::text(
+			Cow::Borrowed("TDRC"),
+			String::from("2023-02-30T12"),
+		));
+		tag.insert(Frame::text(
+			Cow::Borrowed("TDOR"),
+			String::from("1998-05\01999"),
+		));
+
+		// Out of range components are dropped
+		let recording_time = tag.timestamp(&RECORDING_TIME_ID).unwrap();
+		assert_eq!(recording_time.to_string(), "2023-02");
+		assert_eq!(tag.year(), Some(2023));
+
+		// Only the first value is used
+		let original_release_time = tag
+			.timestamp(&FrameId::Valid(Cow::Borrowed("TDOR")))
+			.unwrap();
+		assert_eq!(original_release_time.month, Some(5));
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let reader = &mut &*writer;
+		let header = Id3v2Header::parse(reader).unwrap();
+		let err = crate::id3::v2::read::parse_id3v2(
+			reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap_err();
+		assert_eq!(err.to_string(), "Timestamp: Invalid day");
+
+		// Partial timestamps are fine
+		tag.set_timestamp(RECORDING_TIME_ID, recording_time);
+		assert_eq!(tag.get_text(&RECORDING_TIME_ID).as_deref(), Some("2023-02"));
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+		assert_eq!(read_tag_bytes(&writer).year(), Some(2023));
+	}
+
+	#[test]
+	fn timestamps_with_utc_offset() {
+		let parse_strict = |timestamp: &str| {
+			let mut tag = Id3v2Tag::default();
+			tag.insert(Frame::text(Cow::Borrowed("TDRC"), String::from(timestamp)));
+
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+			let reader = &mut &*writer;
+			let header = Id3v2Header::parse(reader).unwrap();
+			crate::id3::v2::read::parse_id3v2(
+				reader,
+				header,
+				ParseOptions::new().parsing_mode(ParsingMode::Strict),
+			)
+		};
+
+		for timestamp in [
+			"2019-05-01T10:24:08Z",
+			"2019-05-01T10:24:08+01:00",
+			"2019-05-01T10:24-0530",
+			"2019-05-01T10+09",
+		] {
+			let tag = parse_strict(timestamp).unwrap();
+			assert_eq!(tag.get_text(&RECORDING_TIME_ID).as_deref(), Some(timestamp));
+		}
+
+		for timestamp in [
+			"2019-05-01T10:24:08ZZ",
+			"2019-05-01T10:24:08+1",
+			"2019-05-01Z",
+		] {
+			assert!(parse_strict(timestamp).is_err());
+		}
+	}
+
+	#[test]
+	fn tipl_round_trip() {
+		let mut tag = Id3v2Tag::default();
+		let mut tipl = KeyValueFrame {
+			encoding: TextEncoding::UTF8,
+			key_value_pairs: Vec::new(),
+		};
+
+		// Add all supported keys
+		for (_, key) in TIPL_MAPPINGS {
+			tipl.key_value_pairs
+				.push((String::from(*key), String::from("Serial-ATA")));
+		}
+
+		// Add one unsupported key
+		tipl.key_value_pairs
+			.push((String::from("Foo"), String::from("Bar")));
 
 		tag.insert(
 			Frame::new(
@@ -2747,7 +4290,7 @@ mod tests {
 		);
 
 		let (split_remainder, split_tag) = tag.split_tag();
-		assert_eq!(split_remainder.0.len(), 1); // "Foo" is not supported
+		assert_eq!(split_remainder.len(), 1); // "Foo" is not supported
 		assert_eq!(split_tag.len(), TIPL_MAPPINGS.len()); // All supported keys are present
 
 		for (item_key, _) in TIPL_MAPPINGS {
@@ -2776,4 +4319,886 @@ mod tests {
 			_ => unreachable!(),
 		}
 	}
+
+	#[test]
+	fn lyrics_in_multiple_languages() {
+		let mut tag = Id3v2Tag::default();
+		assert!(tag
+			.set_lyrics(Some(*b"eng"), String::new(), String::from("English"))
+			.unwrap()
+			.is_none());
+		assert!(tag
+			.set_lyrics(Some(*b"deu"), String::new(), String::from("Deutsch"))
+			.unwrap()
+			.is_none());
+		assert!(tag
+			.set_lyrics(None, String::from("Live"), String::from("Undetermined"))
+			.unwrap()
+			.is_none());
+
+		assert_eq!(tag.len(), 3);
+		assert_eq!(tag.lyrics(Some(*b"eng"), ""), Some("English"));
+		assert_eq!(tag.lyrics(Some(*b"deu"), ""), Some("Deutsch"));
+		assert_eq!(tag.lyrics(Some(*b"und"), "Live"), Some("Undetermined"));
+		assert_eq!(tag.lyrics(None, "Live"), Some("Undetermined"));
+		assert_eq!(tag.lyrics(Some(*b"fra"), ""), None);
+
+		// Same language and description, replaces the existing frame
+		assert!(tag
+			.set_lyrics(Some(*b"deu"), String::new(), String::from("Neu"))
+			.unwrap()
+			.is_some());
+		assert_eq!(tag.len(), 3);
+		assert_eq!(tag.lyrics(Some(*b"deu"), ""), Some("Neu"));
+
+		assert_eq!(
+			tag.all_lyrics().collect::<Vec<_>>(),
+			[
+				(*b"eng", "", "English"),
+				(*b"und", "Live", "Undetermined"),
+				(*b"deu", "", "Neu"),
+			]
+		);
+	}
+
+	#[test]
+	fn lyrics_invalid_language() {
+		let mut tag = Id3v2Tag::default();
+		for language in [*b"en\0", *b"123", *b"e g"] {
+			assert!(tag
+				.set_lyrics(Some(language), String::new(), String::from("Lyrics"))
+				.is_err());
+		}
+
+		assert!(tag.is_empty());
+	}
+
+	#[test]
+	fn lyrics_language_split_merge() {
+		for (language, expected) in [(*b"deu", *b"deu"), (*b"\0\0\0", UNDETERMINED_LANGUAGE)] {
+			let mut tag = Id3v2Tag::default();
+			tag.insert(new_lyrics_frame(
+				language,
+				String::new(),
+				String::from("Lyrics"),
+				FrameFlags::default(),
+			));
+
+			let (split_remainder, split_tag) = tag.split_tag();
+			assert_eq!(split_tag.get_string(&ItemKey::Lyrics), Some("Lyrics"));
+
+			let id3v2 = split_remainder.merge_tag(split_tag);
+			assert_eq!(
+				id3v2.all_lyrics().collect::<Vec<_>>(),
+				[(expected, "", "Lyrics")]
+			);
+		}
+	}
+
+	#[test]
+	fn multiple_lyrics_split_merge_round_trip() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_lyrics(Some(*b"eng"), String::new(), String::from("English"))
+			.unwrap();
+		tag.set_lyrics(Some(*b"deu"), String::new(), String::from("Deutsch"))
+			.unwrap();
+		tag.set_lyrics(Some(*b"eng"), String::from("Live"), String::from("Live"))
+			.unwrap();
+
+		let (split_remainder, split_tag) = tag.split_tag();
+
+		// Only the first lyrics without a description are converted
+		assert_eq!(split_tag.len(), 1);
+		assert_eq!(split_tag.get_string(&ItemKey::Lyrics), Some("English"));
+		assert_eq!(split_remainder.len(), 2);
+
+		let id3v2 = split_remainder.merge_tag(split_tag);
+		assert_eq!(id3v2.len(), 3);
+		assert_eq!(id3v2.lyrics(None, ""), Some("Deutsch"));
+		assert_eq!(id3v2.lyrics(Some(*b"deu"), ""), Some("Deutsch"));
+		assert_eq!(id3v2.lyrics(Some(*b"eng"), "Live"), Some("Live"));
+		assert_eq!(id3v2.lyrics(Some(*b"eng"), ""), Some("English"));
+
+		// All of the frames are valid for writing
+		let mut content = Vec::new();
+		id3v2
+			.dump_to(&mut content, WriteOptions::default())
+			.unwrap();
+
+		let mut reader = std::io::Cursor::new(&content[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let reparsed = crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
+		assert_eq!(
+			reparsed.all_lyrics().collect::<Vec<_>>(),
+			id3v2.all_lyrics().collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn comments_in_multiple_languages() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_comment_with(Some(*b"eng"), String::new(), String::from("English"))
+			.unwrap();
+		tag.set_comment_with(Some(*b"deu"), String::new(), String::from("Deutsch"))
+			.unwrap();
+		tag.set_comment_with(
+			None,
+			String::from("iTunNORM"),
+			String::from(" 00000A2B 00000A2B"),
+		)
+		.unwrap();
+
+		assert_eq!(tag.len(), 3);
+		assert_eq!(tag.comment_with(Some(*b"eng"), ""), Some("English"));
+		assert_eq!(tag.comment_with(Some(*b"deu"), ""), Some("Deutsch"));
+		assert_eq!(
+			tag.comment_with(Some(*b"und"), "iTunNORM"),
+			Some(" 00000A2B 00000A2B")
+		);
+		assert_eq!(tag.comment_with(Some(*b"fra"), ""), None);
+		assert_eq!(tag.comment().as_deref(), Some("English"));
+
+		// An existing comment keeps its language
+		assert!(tag
+			.set_comment_with(None, String::new(), String::from("Updated"))
+			.unwrap()
+			.is_some());
+		assert_eq!(tag.len(), 3);
+		assert_eq!(tag.comment_with(Some(*b"eng"), ""), Some("Updated"));
+
+		assert!(tag
+			.set_comment_with(Some(*b"e g"), String::new(), String::from("Invalid"))
+			.is_err());
+	}
+
+	#[test]
+	fn insert_duplicate_behavior() {
+		use crate::error::{ErrorKind, Id3v2ErrorKind};
+		use crate::id3::v2::DuplicateBehavior;
+
+		let user_text = |description: &str, content: &str| Frame {
+			id: FrameId::Valid(Cow::Borrowed("TXXX")),
+			value: FrameValue::UserText(ExtendedTextFrame {
+				encoding: TextEncoding::UTF8,
+				description: String::from(description),
+				content: String::from(content),
+			}),
+			flags: FrameFlags::default(),
+		};
+
+		let mut tag = Id3v2Tag::default();
+		assert!(tag
+			.insert_with(user_text("FOO", "Foo"), DuplicateBehavior::Error)
+			.unwrap()
+			.is_none());
+		assert!(tag
+			.insert_with(user_text("BAR", "Bar"), DuplicateBehavior::Error)
+			.unwrap()
+			.is_none());
+
+		let err = tag
+			.insert_with(user_text("FOO", "Baz"), DuplicateBehavior::Error)
+			.unwrap_err();
+		assert!(matches!(
+			err.kind(),
+			ErrorKind::Id3v2(ref e) if matches!(e.kind(), Id3v2ErrorKind::DuplicateFrame(_))
+		));
+		assert_eq!(tag.get_user_text("FOO"), Some("Foo"));
+
+		assert!(tag
+			.insert_with(user_text("FOO", "Baz"), DuplicateBehavior::KeepBoth)
+			.unwrap()
+			.is_none());
+		assert_eq!(tag.len(), 3);
+
+		// Replacing removes every duplicate
+		let replaced = tag
+			.insert_with(user_text("FOO", "Qux"), DuplicateBehavior::Replace)
+			.unwrap();
+		assert_eq!(replaced, Some(user_text("FOO", "Foo")));
+		assert_eq!(tag.len(), 2);
+		assert_eq!(tag.get_user_text("FOO"), Some("Qux"));
+		assert_eq!(tag.get_user_text("BAR"), Some("Bar"));
+	}
+
+	#[test]
+	fn insert_picture_duplicates() {
+		let picture = |pic_type, description: Option<&str>| {
+			Picture::new_unchecked(
+				pic_type,
+				Some(MimeType::Png),
+				description.map(String::from),
+				vec![1, 2, 3],
+			)
+		};
+
+		let mut tag = Id3v2Tag::default();
+		assert!(tag
+			.insert_picture(picture(PictureType::CoverFront, None))
+			.is_none());
+		assert!(tag
+			.insert_picture(picture(PictureType::CoverBack, None))
+			.is_none());
+
+		// Same type and description
+		assert!(tag
+			.insert_picture(picture(PictureType::CoverFront, None))
+			.is_some());
+		assert_eq!(tag.len(), 2);
+
+		// Same type, different description
+		assert!(tag
+			.insert_picture(picture(PictureType::CoverFront, Some("Alternate")))
+			.is_none());
+		assert_eq!(tag.len(), 3);
+
+		// There can only be one of each icon, regardless of the description
+		assert!(tag
+			.insert_picture(picture(PictureType::Icon, Some("Foo")))
+			.is_none());
+		assert!(tag
+			.insert_picture(picture(PictureType::Icon, Some("Bar")))
+			.is_some());
+		assert_eq!(tag.len(), 4);
+	}
+
+	#[test]
+	fn duplicate_frames_not_written() {
+		use crate::id3::v2::DuplicateBehavior;
+
+		let mut tag = Id3v2Tag::default();
+		for (title, comment) in [("Foo title", "Foo comment"), ("Bar title", "Bar comment")] {
+			tag.insert_with(
+				new_text_frame(
+					FrameId::Valid(Cow::Borrowed("TIT2")),
+					String::from(title),
+					FrameFlags::default(),
+				),
+				DuplicateBehavior::KeepBoth,
+			)
+			.unwrap();
+			tag.insert_with(
+				Frame {
+					id: FrameId::Valid(Cow::Borrowed(COMMENT_FRAME_ID)),
+					value: FrameValue::Comment(CommentFrame {
+						encoding: TextEncoding::UTF8,
+						language: *b"eng",
+						description: String::new(),
+						content: String::from(comment),
+					}),
+					flags: FrameFlags::default(),
+				},
+				DuplicateBehavior::KeepBoth,
+			)
+			.unwrap();
+		}
+		tag.set_comment_with(Some(*b"deu"), String::new(), String::from("Kommentar"))
+			.unwrap();
+		assert_eq!(tag.len(), 5);
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		// Only the last of each duplicate is written
+		let re_read = read_tag_bytes(&writer);
+		assert_eq!(re_read.len(), 3);
+		assert_eq!(re_read.title().as_deref(), Some("Bar title"));
+		assert_eq!(re_read.comment_with(Some(*b"eng"), ""), Some("Bar comment"));
+		assert_eq!(re_read.comment_with(Some(*b"deu"), ""), Some("Kommentar"));
+	}
+
+	#[test]
+	fn multiple_comments_split_merge_round_trip() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_comment_with(Some(*b"eng"), String::new(), String::from("English"))
+			.unwrap();
+		tag.set_comment_with(Some(*b"deu"), String::new(), String::from("Deutsch"))
+			.unwrap();
+		tag.set_comment_with(
+			Some(*b"eng"),
+			String::from("iTunNORM"),
+			String::from(" 00000A2B 00000A2B"),
+		)
+		.unwrap();
+
+		let (split_remainder, mut split_tag) = tag.split_tag();
+
+		// Only the first comment without a description is converted
+		assert_eq!(split_tag.len(), 1);
+		assert_eq!(split_tag.get_string(&ItemKey::Comment), Some("English"));
+		assert_eq!(split_remainder.len(), 2);
+
+		split_tag.set_comment(String::from("Edited"));
+
+		// The comment keeps its original language
+		let id3v2 = split_remainder.merge_tag(split_tag);
+		assert_eq!(id3v2.len(), 3);
+		assert_eq!(id3v2.comment_with(Some(*b"eng"), ""), Some("Edited"));
+		assert_eq!(id3v2.comment_with(Some(*b"deu"), ""), Some("Deutsch"));
+		assert_eq!(
+			id3v2.comment_with(Some(*b"eng"), "iTunNORM"),
+			Some(" 00000A2B 00000A2B")
+		);
+
+		// Without a remainder, the language is unknown
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.set_comment(String::from("Comment"));
+
+		let id3v2 = Id3v2Tag::from(tag);
+		assert_eq!(id3v2.comment_with(Some(*b"XXX"), ""), Some("Comment"));
+	}
+
+	#[test]
+	fn lyrics_item_to_id3v2() {
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.insert_text(ItemKey::Lyrics, String::from("Lyrics"));
+
+		let mut content = Vec::new();
+		tag.dump_to(&mut content, WriteOptions::default()).unwrap();
+
+		let mut reader = std::io::Cursor::new(&content[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let id3v2 = crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
+		assert_eq!(id3v2.lyrics(None, ""), Some("Lyrics"));
+	}
+
+	#[test]
+	fn apple_podcast_frames() {
+		// An ID3v2.3 tag, as written by iTunes
+		fn frame(id: &[u8; 4], content: &[u8]) -> Vec<u8> {
+			let mut frame = id.to_vec();
+			frame.extend((content.len() as u32).to_be_bytes());
+			frame.extend([0, 0]);
+			frame.extend(content);
+			frame
+		}
+
+		let frames = [
+			frame(b"PCST", &[0, 0, 0, 0]),
+			frame(b"TGID", b"\0https://example.com/episodes/1"),
+			frame(b"WFED", b"\0https://example.com/feed.xml\0"),
+			frame(b"TDES", b"\0Foo description"),
+		]
+		.concat();
+
+		let mut tag_bytes = b"ID3\x03\0\0".to_vec();
+		tag_bytes.extend((frames.len() as u32).synch().unwrap().to_be_bytes());
+		tag_bytes.extend(frames);
+
+		let id3v2 = read_tag_bytes(&tag_bytes);
+		assert_eq!(
+			id3v2
+				.get_text(&FrameId::Valid(Cow::Borrowed("WFED")))
+				.as_deref(),
+			Some("https://example.com/feed.xml")
+		);
+
+		let tag: Tag = id3v2.clone().into();
+		assert_eq!(tag.item_count(), 4);
+		assert_eq!(tag.get_string(&ItemKey::FlagPodcast), Some("1"));
+		assert_eq!(
+			tag.get_string(&ItemKey::PodcastGlobalUniqueID),
+			Some("https://example.com/episodes/1")
+		);
+		assert_eq!(
+			tag.get_string(&ItemKey::PodcastURL),
+			Some("https://example.com/feed.xml")
+		);
+		assert_eq!(
+			tag.get_string(&ItemKey::PodcastDescription),
+			Some("Foo description")
+		);
+
+		// Through both conversion paths, the frames are written back the way iTunes wrote them
+		let mut content = Vec::new();
+		tag.dump_to(&mut content, WriteOptions::default()).unwrap();
+
+		for converted in [Id3v2Tag::from(tag.clone()), read_tag_bytes(&content)] {
+			assert_eq!(converted.len(), 4);
+			for id in ["PCST", "WFED"] {
+				let id = FrameId::Valid(Cow::Borrowed(id));
+				assert_eq!(converted.get(&id), id3v2.get(&id));
+			}
+			for id in ["TGID", "TDES"] {
+				let id = FrameId::Valid(Cow::Borrowed(id));
+				assert_eq!(converted.get_text(&id), id3v2.get_text(&id));
+			}
+		}
+
+		// And map to their MP4 equivalents
+		let ilst: Ilst = tag.clone().into();
+		assert_eq!(
+			ilst.get(&AtomIdent::Fourcc(*b"pcst"))
+				.unwrap()
+				.data()
+				.next(),
+			Some(&AtomData::Bool(true))
+		);
+		for (fourcc, value) in [
+			(b"egid", "https://example.com/episodes/1"),
+			(b"purl", "https://example.com/feed.xml"),
+			(b"ldes", "Foo description"),
+		] {
+			assert_eq!(
+				ilst.get(&AtomIdent::Fourcc(*fourcc)).unwrap().data().next(),
+				Some(&AtomData::UTF8(String::from(value)))
+			);
+		}
+
+		// A cleared flag isn't written at all
+		let mut tag = tag;
+		tag.insert_text(ItemKey::FlagPodcast, String::from("0"));
+		let converted: Id3v2Tag = tag.into();
+		assert!(converted
+			.get(&FrameId::Valid(Cow::Borrowed("PCST")))
+			.is_none());
+	}
+
+	#[test]
+	fn podcast_url_frame_without_encoding() {
+		// Some software writes `WFED` as a URL frame, missing the text encoding
+		let url = b"https://example.com/feed.xml";
+
+		let mut tag_bytes = b"ID3\x04\0\0".to_vec();
+		tag_bytes.extend((url.len() as u32 + 10).synch().unwrap().to_be_bytes());
+		tag_bytes.extend(b"WFED");
+		tag_bytes.extend((url.len() as u32).synch().unwrap().to_be_bytes());
+		tag_bytes.extend([0, 0]);
+		tag_bytes.extend(url);
+
+		let id3v2 = read_tag_bytes(&tag_bytes);
+		assert_eq!(
+			id3v2
+				.get(&FrameId::Valid(Cow::Borrowed("WFED")))
+				.unwrap()
+				.content(),
+			&FrameValue::Text(TextInformationFrame {
+				encoding: TextEncoding::Latin1,
+				value: String::from("https://example.com/feed.xml"),
+			})
+		);
+	}
+
+	#[test]
+	fn chapters_roundtrip() {
+		let title = |title: &str| {
+			new_text_frame(
+				FrameId::Valid(Cow::Borrowed("TIT2")),
+				String::from(title),
+				FrameFlags::default(),
+			)
+		};
+
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+
+		// A top-level table of contents, with a nested one for the second half
+		tag.insert_table_of_contents(TableOfContentsFrame {
+			element_id: String::from("toc"),
+			top_level: true,
+			ordered: true,
+			child_element_ids: vec![String::from("chp0"), String::from("toc1")],
+			frames: vec![title("Contents")],
+		});
+		tag.insert_table_of_contents(TableOfContentsFrame {
+			element_id: String::from("toc1"),
+			top_level: false,
+			ordered: true,
+			child_element_ids: vec![String::from("chp1")],
+			frames: Vec::new(),
+		});
+
+		let mut chp0 = ChapterFrame::new(String::from("chp0"), 0, 5000);
+		chp0.frames.push(title("Introduction"));
+		chp0.frames.push(
+			Frame::new(
+				"APIC",
+				AttachedPictureFrame {
+					encoding: TextEncoding::Latin1,
+					picture: Picture::new_unchecked(
+						PictureType::Other,
+						Some(MimeType::Png),
+						None,
+						vec![0x89, b'P', b'N', b'G'],
+					),
+				},
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+		tag.insert_chapter(chp0);
+
+		let mut chp1 = ChapterFrame::new(String::from("chp1"), 5000, 10000);
+		chp1.frames.push(title("Outro"));
+		tag.insert_chapter(chp1);
+
+		for write_options in [
+			WriteOptions::default(),
+			WriteOptions::new().use_id3v23(true),
+		] {
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, write_options).unwrap();
+
+			let parsed_tag = read_tag_bytes(&writer);
+			assert_eq!(parsed_tag.title().as_deref(), Some("Foo title"));
+
+			let chapters = parsed_tag.chapters().collect::<Vec<_>>();
+			assert_eq!(chapters.len(), 2);
+			assert_eq!(chapters[0].title(), Some("Introduction"));
+			assert_eq!(chapters[0].end_time, 5000);
+			assert!(matches!(
+				chapters[0].frames[1].content(),
+				FrameValue::Picture(AttachedPictureFrame { picture, .. }) if picture.data() == [0x89, b'P', b'N', b'G']
+			));
+			assert_eq!(chapters[1].title(), Some("Outro"));
+
+			let tocs = parsed_tag.tables_of_contents().collect::<Vec<_>>();
+			assert_eq!(tocs.len(), 2);
+			assert!(tocs[0].top_level);
+			assert_eq!(tocs[0].child_element_ids, ["chp0", "toc1"]);
+			assert!(!tocs[1].top_level);
+			assert_eq!(tocs[1].child_element_ids, ["chp1"]);
+			assert!(tocs[1].frames.is_empty());
+		}
+
+		// Replaced by its element ID
+		let replaced = tag.insert_chapter(ChapterFrame::new(String::from("chp1"), 5000, 12000));
+		assert!(matches!(
+			replaced.unwrap().content(),
+			FrameValue::Chapter(ChapterFrame {
+				end_time: 10000,
+				..
+			})
+		));
+		assert_eq!(tag.chapters().count(), 2);
+	}
+
+	#[test]
+	fn etco_roundtrip() {
+		let mut content = vec![2]; // MS
+		for (event_type, timestamp) in [(0x02_u8, 0_u32), (0x10, 5000), (0x03, 5000), (0x20, 9000)]
+		{
+			content.push(event_type);
+			content.extend(timestamp.to_be_bytes());
+		}
+
+		let mut tag_bytes = b"ID3\x04\x00\x00".to_vec();
+		tag_bytes.extend((10 + content.len() as u32).synch().unwrap().to_be_bytes());
+		tag_bytes.extend(b"ETCO");
+		tag_bytes.extend((content.len() as u32).synch().unwrap().to_be_bytes());
+		tag_bytes.extend([0, 0]);
+		tag_bytes.extend(&content);
+
+		let tag = read_tag_bytes(&tag_bytes);
+		let Some(FrameValue::EventTimingCodes(etco)) = tag
+			.get(&FrameId::Valid(Cow::Borrowed("ETCO")))
+			.map(Frame::content)
+		else {
+			panic!("Expected an ETCO frame");
+		};
+
+		assert_eq!(etco.timestamp_format, TimestampFormat::MS);
+		assert_eq!(
+			etco.events
+				.iter()
+				.map(|event| (event.event_type, event.timestamp))
+				.collect::<Vec<_>>(),
+			[
+				(EventType::IntroStart, 0),
+				(EventType::IntroEnd, 5000),
+				(EventType::MainPartStart, 5000),
+				(EventType::Other(0x20), 9000),
+			]
+		);
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+		assert_eq!(writer, tag_bytes);
+	}
+
+	#[test]
+	fn trim_multi_value_text_frames() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_artist(String::from(" Foo artist\0 \0Bar artist "));
+		tag.set_title(String::from("  "));
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::new().trim_whitespace(true))
+			.unwrap();
+
+		let parsed_tag = read_tag_bytes(&writer);
+		assert_eq!(
+			parsed_tag.artist().as_deref(),
+			Some("Foo artist/Bar artist")
+		);
+		assert!(parsed_tag.title().is_none());
+
+		// Empty values are kept in place when asked for
+		let mut writer = Vec::new();
+		tag.dump_to(
+			&mut writer,
+			WriteOptions::new()
+				.trim_whitespace(true)
+				.remove_empty_items(false),
+		)
+		.unwrap();
+
+		let parsed_tag = read_tag_bytes(&writer);
+		assert_eq!(
+			parsed_tag.artist().as_deref(),
+			Some("Foo artist//Bar artist")
+		);
+		assert_eq!(parsed_tag.title().as_deref(), Some(""));
+	}
+
+	#[test]
+	fn sylt_roundtrip() {
+		let sylt = SynchronizedText {
+			encoding: TextEncoding::UTF8,
+			language: *b"eng",
+			timestamp_format: TimestampFormat::MS,
+			content_type: SyncTextContentType::Lyrics,
+			description: None,
+			content: vec![(0, String::from("Foo")), (1500, String::from("Bär"))],
+		};
+
+		let mut tag = Id3v2Tag::default();
+		tag.insert(Frame::new("SYLT", sylt.clone(), FrameFlags::default()).unwrap());
+
+		for (write_options, encoding) in [
+			(WriteOptions::default(), TextEncoding::UTF8),
+			(WriteOptions::new().use_id3v23(true), TextEncoding::UTF16),
+		] {
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, write_options).unwrap();
+
+			let parsed_tag = read_tag_bytes(&writer);
+			let Some(FrameValue::SynchronizedText(parsed)) = parsed_tag
+				.get(&FrameId::Valid(Cow::Borrowed("SYLT")))
+				.map(Frame::content)
+			else {
+				panic!("Expected a SYLT frame");
+			};
+
+			assert_eq!(parsed.encoding, encoding);
+			assert_eq!(parsed.content, sylt.content);
+		}
+
+		// A malformed frame is only kept as binary outside of strict mode
+		let mut tag = Id3v2Tag::default();
+		tag.insert(
+			Frame::new(
+				"SYLT",
+				FrameValue::Binary(b"\x00eng\x02\x01\x00Foo\x00\x00".to_vec()),
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let mut reader = std::io::Cursor::new(&writer[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		assert!(crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.is_err());
+
+		let mut reader = std::io::Cursor::new(&writer[..]);
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let parsed_tag = crate::id3::v2::read::parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().parsing_mode(ParsingMode::BestAttempt),
+		)
+		.unwrap();
+		assert_eq!(
+			parsed_tag
+				.get(&FrameId::Valid(Cow::Borrowed("SYLT")))
+				.map(Frame::content),
+			Some(&FrameValue::Binary(
+				b"\x00eng\x02\x01\x00Foo\x00\x00".to_vec()
+			))
+		);
+	}
+
+	#[test]
+	fn geob_roundtrip() {
+		let markers = GeneralEncapsulatedObject::parse(&read_path(
+			"tests/tags/assets/id3v2/serato_markers2.geob",
+		))
+		.unwrap();
+		let overview = GeneralEncapsulatedObject {
+			encoding: TextEncoding::UTF8,
+			mime_type: Some(String::from("application/octet-stream")),
+			file_name: None,
+			description: Some(String::from("Serato Overview")),
+			data: vec![0x01, 0x05, 0xFF, 0xE0, 0x00, 0xFF, 0x00, 0x00, 0xFF],
+		};
+
+		let mut tag = Id3v2Tag::default();
+		for geob in [markers.clone(), overview.clone()] {
+			tag.insert(Frame::new("GEOB", geob, FrameFlags::default()).unwrap());
+		}
+
+		// Objects with different descriptions can coexist, but replace those with the same description
+		let mut replaced = overview.clone();
+		replaced.data.push(0);
+		tag.insert(Frame::new("GEOB", replaced, FrameFlags::default()).unwrap());
+		tag.insert(Frame::new("GEOB", overview.clone(), FrameFlags::default()).unwrap());
+		assert_eq!(tag.len(), 2);
+
+		let (tag, _) = tag.split_tag();
+
+		for (write_options, encoding) in [
+			(WriteOptions::default(), TextEncoding::UTF8),
+			(WriteOptions::new().use_id3v23(true), TextEncoding::UTF16),
+		] {
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, write_options).unwrap();
+
+			let parsed_tag = read_tag_bytes(&writer);
+			let parsed = parsed_tag
+				.into_iter()
+				.filter_map(|frame| match frame.content() {
+					FrameValue::GeneralEncapsulatedObject(geob) => Some(geob.clone()),
+					_ => None,
+				})
+				.collect::<Vec<_>>();
+
+			assert_eq!(parsed.len(), 2);
+
+			assert_eq!(parsed[0].encoding, TextEncoding::Latin1);
+			assert_eq!(parsed[0].as_bytes(), markers.as_bytes());
+
+			assert_eq!(parsed[1].encoding, encoding);
+			assert_eq!(parsed[1].mime_type, overview.mime_type);
+			assert_eq!(parsed[1].description, overview.description);
+			assert_eq!(parsed[1].data, overview.data);
+		}
+	}
+
+	fn url_frames() -> Id3v2Tag {
+		let mut tag = Id3v2Tag::default();
+		for (id, url) in [
+			("WOAR", "https://foo.example/artist"),
+			("WOAR", "https://bar.example/artist"),
+			("WOAS", "https://foo.example/source"),
+			("WOAF", "https://foo.example/file"),
+			("WCOM", "https://foo.example/buy"),
+			("WCOP", "https://foo.example/copyright"),
+			("WORS", "https://foo.example/radio"),
+			("WPAY", "https://foo.example/pay"),
+			("WPUB", "https://foo.example/publisher"),
+		] {
+			tag.insert(Frame {
+				id: FrameId::Valid(Cow::Borrowed(id)),
+				value: FrameValue::Url(UrlLinkFrame(String::from(url))),
+				flags: FrameFlags::default(),
+			});
+		}
+
+		for (description, url) in [
+			("Homepage", "https://foo.example"),
+			("", "https://foo.example/other"),
+			("SHOP", "https://foo.example/shop"),
+		] {
+			tag.insert(Frame {
+				id: FrameId::Valid(Cow::Borrowed("WXXX")),
+				value: FrameValue::UserUrl(ExtendedUrlFrame {
+					encoding: TextEncoding::UTF8,
+					description: String::from(description),
+					content: String::from(url),
+				}),
+				flags: FrameFlags::default(),
+			});
+		}
+
+		tag
+	}
+
+	#[test]
+	fn url_frames_to_tag() {
+		let (split_remainder, tag) = url_frames().split_tag();
+		assert!(split_remainder.is_empty());
+
+		let artist_urls = tag
+			.get_locators(&ItemKey::TrackArtistUrl)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			artist_urls,
+			["https://foo.example/artist", "https://bar.example/artist"]
+		);
+		assert_eq!(
+			tag.get_locators(&ItemKey::PaymentUrl).next(),
+			Some("https://foo.example/pay")
+		);
+		assert_eq!(
+			tag.get_locators(&ItemKey::Unknown(String::from("Homepage")))
+				.next(),
+			Some("https://foo.example")
+		);
+		assert_eq!(
+			tag.get_locators(&ItemKey::Unknown(String::from("SHOP")))
+				.next(),
+			Some("https://foo.example/shop")
+		);
+	}
+
+	#[test]
+	fn url_frames_roundtrip() {
+		let original = url_frames();
+
+		let (split_remainder, tag) = original.clone().split_tag();
+		let merged = split_remainder.merge_tag(tag.clone());
+		assert_eq!(merged.len(), original.len());
+		for frame in &original {
+			assert!(merged.frames.contains(frame), "{frame:?}");
+		}
+
+		// Writing the `Tag` directly
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let written = read_tag_bytes(&writer);
+		assert_eq!(written.len(), original.len());
+		for frame in &original {
+			assert!(written.frames.contains(frame), "{frame:?}");
+		}
+	}
+
+	#[test]
+	fn url_set_as_text() {
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.insert_text(
+			ItemKey::TrackArtistUrl,
+			String::from("https://foo.example/artist"),
+		);
+
+		let expected = Frame {
+			id: FrameId::Valid(Cow::Borrowed("WOAR")),
+			value: FrameValue::Url(UrlLinkFrame(String::from("https://foo.example/artist"))),
+			flags: FrameFlags::default(),
+		};
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+		assert_eq!(read_tag_bytes(&writer).frames, [expected.clone()]);
+
+		let id3v2 = Id3v2Tag::from(tag);
+		assert_eq!(id3v2.frames, [expected]);
+	}
 }
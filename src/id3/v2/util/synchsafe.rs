@@ -128,6 +128,28 @@ impl<R: Read> Read for UnsynchronizedStream<R> {
 	}
 }
 
+/// Apply the unsynchronisation scheme, the inverse of [`UnsynchronizedStream`]
+///
+/// A zero byte is inserted after every `0xFF` that is followed by a byte that could be mistaken for
+/// a frame sync (`0b111xxxxx`), or by an existing zero byte. Content ending in `0xFF` is also given a
+/// trailing zero byte, as it may be followed by anything.
+pub(crate) fn unsynchronise(content: &[u8]) -> Vec<u8> {
+	let mut unsynchronised = Vec::with_capacity(content.len());
+	for (idx, byte) in content.iter().enumerate() {
+		unsynchronised.push(*byte);
+		if *byte != 0xFF {
+			continue;
+		}
+
+		match content.get(idx + 1) {
+			Some(next) if *next != 0 && *next < 0xE0 => {},
+			_ => unsynchronised.push(0),
+		}
+	}
+
+	unsynchronised
+}
+
 /// An integer that can be converted to and from synchsafe variants
 pub trait SynchsafeInteger: Sized {
 	/// The integer type that this can be widened to for use in [`SynchsafeInteger::widening_synch`]
@@ -345,6 +367,27 @@ mod tests {
 		assert_eq!(final_content, ORIGINAL_CONTENT);
 	}
 
+	#[test]
+	fn unsynchronise() {
+		assert_eq!(super::unsynchronise(EXPECTED), UNSYNCHRONIZED_CONTENT);
+		assert_eq!(
+			super::unsynchronise(&[0xFF, 0x1A, 0xFF, 0xE0, 0xFF]),
+			[0xFF, 0x1A, 0xFF, 0x00, 0xE0, 0xFF, 0x00]
+		);
+
+		let content = (0..=u8::MAX).rev().cycle().take(4096).collect::<Vec<_>>();
+		let unsynchronised = super::unsynchronise(&content);
+		assert!(!unsynchronised
+			.windows(2)
+			.any(|window| window[0] == 0xFF && window[1] >= 0xE0));
+
+		let mut resynchronised = Vec::new();
+		UnsynchronizedStream::new(Cursor::new(unsynchronised))
+			.read_to_end(&mut resynchronised)
+			.unwrap();
+		assert_eq!(resynchronised, content);
+	}
+
 	use crate::id3::v2::util::synchsafe::{SynchsafeInteger, UnsynchronizedStream};
 	use std::io::{Cursor, Read};
 	macro_rules! synchsafe_integer_tests {
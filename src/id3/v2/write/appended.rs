@@ -0,0 +1,239 @@
+//! ID3v2 tags appended to the end of MPEG and AAC files, see [`WriteOptions::append_id3v2`]
+
+use super::create_tag;
+use super::frame::create_items;
+use crate::error::{FileEncodingError, Result};
+use crate::id3::v2::frame::{FrameFlags, FrameRef, FrameValue};
+use crate::id3::v2::header::{Id3v2Header, Id3v2Version};
+use crate::id3::v2::items::SeekFrame;
+use crate::id3::v2::tag::Id3v2TagRef;
+use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+use crate::id3::v2::FrameId;
+use crate::id3::{
+	find_id3v1, find_id3v2, find_id3v2_footer, find_lyrics3v2, FindId3v2Config, ID3FindResults,
+};
+use crate::macros::err;
+use crate::probe::{ParseOptions, ParsingMode};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
+
+use std::borrow::Cow;
+use std::io::SeekFrom;
+use std::ops::Range;
+
+pub(super) fn write_appended_id3v2<'a, F, I>(
+	data: &mut F,
+	tag: &mut Id3v2TagRef<'a, I>,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	I: Iterator<Item = FrameRef<'a>> + Clone + 'a,
+{
+	if write_options.use_id3v23 {
+		return Err(FileEncodingError::from_description(
+			"Appended ID3v2 tags require a footer, which ID3v2.3 doesn't support",
+		)
+		.into());
+	}
+
+	data.rewind()?;
+	let ID3FindResults(leading_header, leading_content) = find_id3v2(
+		data,
+		FindId3v2Config::READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+	let leading_end = data.stream_position()?;
+
+	let (existing, insert_at) = locate_appended_tag(data, leading_end)?;
+
+	tag.flags.footer = true;
+	let appended = create_tag(tag, write_options, 0)?;
+
+	// The tag at the start of the file has to point to the appended one, without changing size
+	let mut leading = None;
+	if let (Some(header), Some(content)) = (leading_header, leading_content) {
+		let mut seek = None;
+		if !appended.is_empty() {
+			let Ok(offset) = u32::try_from(insert_at - leading_end) else {
+				err!(TooMuchData);
+			};
+
+			seek = Some(SeekFrame { offset });
+		}
+
+		let footer_len = if header.flags.footer { 10 } else { 0 };
+		let content_start = leading_end - footer_len - content.len() as u64;
+
+		leading = seek_leading_tag(&header, &content, seek, write_options)?
+			.map(|content| (content_start, content));
+		if leading.is_none() && seek.is_some() {
+			return Err(FileEncodingError::from_description(
+				"The ID3v2 tag at the start of the file can't hold a SEEK frame",
+			)
+			.into());
+		}
+	}
+
+	// Everything before the appended tag is left untouched
+	let replaced = existing.unwrap_or(insert_at..insert_at);
+	replace_range(data, replaced, &appended)?;
+
+	if let Some((content_start, content)) = leading {
+		data.seek(SeekFrom::Start(content_start))?;
+		data.write_all(&content)?;
+	}
+
+	Ok(())
+}
+
+// The frames of the tag at the start of the file, with its SEEK frame replaced by `seek`
+//
+// The frames are copied as is, rather than rebuilding the tag, so its version and everything else
+// in it are kept byte for byte. This returns `None` if the result doesn't fit in the existing tag.
+fn seek_leading_tag(
+	header: &Id3v2Header,
+	content: &[u8],
+	seek: Option<SeekFrame>,
+	write_options: WriteOptions,
+) -> Result<Option<Vec<u8>>> {
+	// SEEK frames only exist in ID3v2.4, and the CRC would have to be recalculated
+	if header.version != Id3v2Version::V4 || header.crc.is_some() {
+		return Ok(None);
+	}
+
+	let mut seek_frame = Vec::new();
+	if let Some(seek) = seek {
+		let frame = FrameRef {
+			id: FrameId::Valid(Cow::Borrowed("SEEK")),
+			value: Cow::Owned(FrameValue::Seek(seek)),
+			flags: FrameFlags {
+				unsynchronisation: header.flags.unsynchronisation,
+				..FrameFlags::default()
+			},
+		};
+
+		create_items(&mut seek_frame, &mut std::iter::once(frame), write_options)?;
+	}
+
+	let mut frames = Vec::with_capacity(content.len());
+	let mut remaining = content;
+	// Anything past the last frame is padding
+	while remaining.len() >= 10 && remaining[0] != 0 {
+		let size = u32::from_be_bytes([remaining[4], remaining[5], remaining[6], remaining[7]]);
+		let Some(frame) = remaining.get(..10 + size.unsynch() as usize) else {
+			log::warn!("Unable to find the frames of the ID3v2 tag at the start of the file");
+			return Ok(None);
+		};
+
+		if &frame[..4] == b"SEEK" {
+			frames.append(&mut seek_frame);
+		} else {
+			frames.extend_from_slice(frame);
+		}
+
+		remaining = &remaining[frame.len()..];
+	}
+	frames.append(&mut seek_frame);
+
+	// Padding isn't allowed in a tag with a footer, so it has to fit exactly
+	if frames.len() > content.len() || (header.flags.footer && frames.len() != content.len()) {
+		return Ok(None);
+	}
+
+	frames.resize(content.len(), 0);
+	Ok(Some(frames))
+}
+
+pub(super) fn remove_appended_id3v2<F>(data: &mut F) -> Result<()>
+where
+	F: FileLike,
+{
+	data.rewind()?;
+	find_id3v2(
+		data,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+	let leading_end = data.stream_position()?;
+
+	if let (Some(existing), _) = locate_appended_tag(data, leading_end)? {
+		replace_range(data, existing, &[])?;
+	}
+
+	data.rewind()?;
+	Ok(())
+}
+
+// The range of the appended tag (if any), and where a new one should be written
+//
+// Trailing tags are laid out as: ID3v2, APE, Lyrics3v2, ID3v1. An appended tag following an APE tag
+// is also found, and replaced where it is. The APE writer skips over it in either position.
+fn locate_appended_tag<F>(data: &mut F, leading_end: u64) -> Result<(Option<Range<u64>>, u64)>
+where
+	F: FileLike,
+{
+	data.seek(SeekFrom::End(0))?;
+
+	// This will seek us to the start of the ID3v1 tag, or the end of the file
+	find_id3v1(data, false)?;
+	if data.stream_position()? >= 15 {
+		find_lyrics3v2(data)?;
+	}
+
+	if let Some(existing) = find_appended_tag(data, leading_end)? {
+		return Ok((Some(existing.clone()), existing.start));
+	}
+
+	let mut insert_at = data.stream_position()?;
+	if insert_at >= leading_end + 32 {
+		data.seek(SeekFrom::Start(insert_at - 32))?;
+
+		if let Some((_, header)) =
+			crate::ape::tag::read::read_ape_tag(data, true, ParsingMode::BestAttempt)?
+		{
+			insert_at = insert_at.saturating_sub(u64::from(header.size));
+			data.seek(SeekFrom::Start(insert_at))?;
+
+			if let Some(existing) = find_appended_tag(data, leading_end)? {
+				return Ok((Some(existing.clone()), existing.start));
+			}
+		}
+	}
+
+	Ok((None, insert_at))
+}
+
+// The range of an appended tag ending at the current position
+fn find_appended_tag<F>(data: &mut F, leading_end: u64) -> Result<Option<Range<u64>>>
+where
+	F: FileLike,
+{
+	let end = data.stream_position()?;
+
+	let ID3FindResults(header, _) = find_id3v2_footer(data, leading_end, false)?;
+	if header.is_none() {
+		return Ok(None);
+	}
+
+	Ok(Some(data.stream_position()?..end))
+}
+
+fn replace_range<F>(data: &mut F, range: Range<u64>, content: &[u8]) -> Result<()>
+where
+	F: FileLike,
+{
+	if range.end - range.start != content.len() as u64 {
+		data.mark_moved(range.end);
+	}
+
+	data.seek(SeekFrom::Start(range.end))?;
+
+	let mut rest = Vec::new();
+	data.read_to_end(&mut rest)?;
+
+	data.seek(SeekFrom::Start(range.start))?;
+	data.write_all(content)?;
+	data.write_all(&rest)?;
+	data.set_len(range.start + (content.len() + rest.len()) as u64)?;
+
+	Ok(())
+}
@@ -1,28 +1,34 @@
 use crate::error::Result;
 use crate::iff::chunk::Chunks;
+use crate::util::io::FileLike;
 
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::SeekFrom;
 
-use byteorder::{ByteOrder, WriteBytesExt};
+use byteorder::ByteOrder;
 
-pub(in crate::id3::v2) fn write_to_chunk_file<B>(data: &mut File, tag: &[u8]) -> Result<()>
+pub(in crate::id3::v2) fn write_to_chunk_file<F, B>(data: &mut F, tag: &[u8]) -> Result<()>
 where
+	F: FileLike,
 	B: ByteOrder,
 {
 	// RIFF....WAVE
 	data.seek(SeekFrom::Current(12))?;
 
-	let file_len = data.metadata()?.len().saturating_sub(12);
+	let file_len = data.len()?.saturating_sub(12);
 
-	let mut id3v2_chunk = (None, None);
+	// Every existing tag is removed, the new one is always written to the end of the file
+	let mut id3v2_chunks = Vec::new();
 
 	let mut chunks = Chunks::<B>::new(file_len);
 
 	while chunks.next(data).is_ok() {
 		if &chunks.fourcc == b"ID3 " || &chunks.fourcc == b"id3 " {
-			id3v2_chunk = (Some(data.stream_position()? - 8), Some(chunks.size));
-			break;
+			let chunk_start = data.stream_position()? - 8;
+
+			// We need to remove the padding byte if it exists
+			let chunk_size = u64::from(chunks.size) + u64::from(chunks.size % 2);
+
+			id3v2_chunks.push(chunk_start..chunk_start + chunk_size + 8);
 		}
 
 		data.seek(SeekFrom::Current(i64::from(chunks.size)))?;
@@ -30,45 +36,45 @@ where
 		chunks.correct_position(data)?;
 	}
 
-	if let (Some(chunk_start), Some(mut chunk_size)) = id3v2_chunk {
-		data.rewind()?;
+	// Nothing to remove or write
+	if id3v2_chunks.is_empty() && tag.is_empty() {
+		return Ok(());
+	}
 
-		// We need to remove the padding byte if it exists
-		if chunk_size % 2 != 0 {
-			chunk_size += 1;
-		}
+	data.rewind()?;
 
-		let mut file_bytes = Vec::new();
-		data.read_to_end(&mut file_bytes)?;
+	let mut file_bytes = Vec::new();
+	data.read_to_end(&mut file_bytes)?;
 
-		file_bytes.splice(
-			chunk_start as usize..(chunk_start + u64::from(chunk_size) + 8) as usize,
-			[],
-		);
+	// Any chunks following the tags are moved up in their place
+	for chunk in id3v2_chunks.into_iter().rev() {
+		data.mark_moved(chunk.end);
 
-		data.rewind()?;
-		data.set_len(0)?;
-		data.write_all(&file_bytes)?;
+		let chunk_end = (chunk.end as usize).min(file_bytes.len());
+		file_bytes.drain(chunk.start as usize..chunk_end);
 	}
 
 	if !tag.is_empty() {
-		data.seek(SeekFrom::End(0))?;
-		data.write_all(b"ID3 ")?;
-		data.write_u32::<B>(tag.len() as u32)?;
-		data.write_all(tag)?;
+		let mut chunk_size = [0; 4];
+		B::write_u32(&mut chunk_size, tag.len() as u32);
+
+		file_bytes.extend(b"ID3 ");
+		file_bytes.extend(chunk_size);
+		file_bytes.extend(tag);
 
 		// It is required an odd length chunk be padded with a 0
 		// The 0 isn't included in the chunk size, however
 		if tag.len() % 2 != 0 {
-			data.write_u8(0)?;
+			file_bytes.push(0);
 		}
+	}
 
-		let total_size = data.stream_position()? - 8;
-
-		data.seek(SeekFrom::Start(4))?;
+	let total_size = file_bytes.len() - 8;
+	B::write_u32(&mut file_bytes[4..8], total_size as u32);
 
-		data.write_u32::<B>(total_size as u32)?;
-	}
+	data.rewind()?;
+	data.set_len(0)?;
+	data.write_all(&file_bytes)?;
 
 	Ok(())
 }
@@ -0,0 +1,210 @@
+//! Conversions from ID3v2.4 to ID3v2.3, see [`WriteOptions::use_id3v23`](crate::WriteOptions::use_id3v23)
+
+use crate::id3::v1::GENRES;
+use crate::id3::v2::frame::{FrameRef, FrameValue};
+use crate::id3::v2::items::{
+	AttachedPictureFrame, CommentFrame, ExtendedTextFrame, ExtendedUrlFrame,
+	GeneralEncapsulatedObject, KeyValueFrame, OwnershipFrame, SynchronizedText,
+	TextInformationFrame, UnsynchronizedTextFrame,
+};
+use crate::id3::v2::FrameId;
+use crate::probe::ParsingMode;
+use crate::tag::timestamp::Timestamp;
+use crate::util::text::TextEncoding;
+use crate::write_options::WriteOptions;
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+// Frames that are new in ID3v2.4, with no ID3v2.3 equivalent
+const ID3V24_ONLY_FRAMES: [&str; 11] = [
+	"ASPI", "EQU2", "RVA2", "SEEK", "SIGN", "TDEN", "TDRL", "TDTG", "TMOO", "TPRO", "TSST",
+];
+
+pub(super) fn downgrade_frames(
+	frames: Vec<FrameRef<'_>>,
+	write_options: WriteOptions,
+) -> Vec<FrameRef<'_>> {
+	let separator = write_options.id3v23_separator;
+
+	let mut downgraded = Vec::with_capacity(frames.len());
+
+	// `TIPL` and `TMCL` are both merged into a single `IPLS` frame
+	let mut involved_people_idx: Option<usize> = None;
+
+	for mut frame in frames {
+		match frame.id.as_str() {
+			id if ID3V24_ONLY_FRAMES.contains(&id) => {
+				log::warn!("Dropping `{id}` frame, it has no ID3v2.3 equivalent");
+				continue;
+			},
+			"TDRC" => {
+				split_recording_time(&frame, &mut downgraded);
+				continue;
+			},
+			"TDOR" => {
+				if let FrameValue::Text(TextInformationFrame { encoding, value }) = &*frame.value {
+					frame.id = FrameId::Valid(Cow::Borrowed("TORY"));
+					frame.value = Cow::Owned(FrameValue::Text(TextInformationFrame {
+						encoding: *encoding,
+						value: year(value),
+					}));
+				}
+			},
+			"TCON" if write_options.id3v23_numeric_genres => {
+				if let FrameValue::Text(TextInformationFrame { encoding, value }) = &*frame.value {
+					frame.value = Cow::Owned(FrameValue::Text(TextInformationFrame {
+						encoding: *encoding,
+						value: numeric_genres(value, separator),
+					}));
+				}
+			},
+			"TIPL" | "TMCL" => {
+				if let Some(idx) = involved_people_idx {
+					let (
+						FrameValue::KeyValue(KeyValueFrame {
+							key_value_pairs, ..
+						}),
+						FrameValue::KeyValue(KeyValueFrame {
+							key_value_pairs: new_pairs,
+							..
+						}),
+					) = (Cow::to_mut(&mut downgraded[idx].value), &*frame.value)
+					else {
+						continue;
+					};
+
+					key_value_pairs.extend(new_pairs.iter().cloned());
+					continue;
+				}
+
+				frame.id = FrameId::Valid(Cow::Borrowed("IPLS"));
+				involved_people_idx = Some(downgraded.len());
+			},
+			_ => {},
+		}
+
+		downgrade_text(&mut frame.value, separator);
+		downgraded.push(frame);
+	}
+
+	downgraded
+}
+
+// ID3v2.3 only supports Latin-1 and UTF-16 (with a BOM), and has no concept of multiple values
+fn downgrade_text(value: &mut Cow<'_, FrameValue>, separator: &str) {
+	let needs_downgrade = match &**value {
+		FrameValue::Text(TextInformationFrame { encoding, value }) => {
+			downgrade_encoding(*encoding) != *encoding || value.contains('\0')
+		},
+		FrameValue::UserText(ExtendedTextFrame { encoding, .. })
+		| FrameValue::UserUrl(ExtendedUrlFrame { encoding, .. })
+		| FrameValue::Comment(CommentFrame { encoding, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { encoding, .. })
+		| FrameValue::Picture(AttachedPictureFrame { encoding, .. })
+		| FrameValue::KeyValue(KeyValueFrame { encoding, .. })
+		| FrameValue::Ownership(OwnershipFrame { encoding, .. })
+		| FrameValue::SynchronizedText(SynchronizedText { encoding, .. })
+		| FrameValue::GeneralEncapsulatedObject(GeneralEncapsulatedObject { encoding, .. }) => {
+			downgrade_encoding(*encoding) != *encoding
+		},
+		_ => false,
+	};
+
+	if !needs_downgrade {
+		return;
+	}
+
+	match value.to_mut() {
+		FrameValue::Text(TextInformationFrame { encoding, value }) => {
+			*encoding = downgrade_encoding(*encoding);
+			*value = value.replace('\0', separator);
+		},
+		FrameValue::UserText(ExtendedTextFrame { encoding, .. })
+		| FrameValue::UserUrl(ExtendedUrlFrame { encoding, .. })
+		| FrameValue::Comment(CommentFrame { encoding, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { encoding, .. })
+		| FrameValue::Picture(AttachedPictureFrame { encoding, .. })
+		| FrameValue::KeyValue(KeyValueFrame { encoding, .. })
+		| FrameValue::Ownership(OwnershipFrame { encoding, .. })
+		| FrameValue::SynchronizedText(SynchronizedText { encoding, .. })
+		| FrameValue::GeneralEncapsulatedObject(GeneralEncapsulatedObject { encoding, .. }) => {
+			*encoding = downgrade_encoding(*encoding);
+		},
+		_ => {},
+	}
+}
+
+// Genres from the ID3v1 genre list are written as references, such as `(17)` for "Rock", with
+// any others following as text (`(17)Eurodisco`)
+fn numeric_genres(value: &str, separator: &str) -> String {
+	let mut references = String::new();
+	let mut names = Vec::new();
+
+	for genre in value.split('\0') {
+		match genre {
+			"Remix" => references.push_str("(RX)"),
+			"Cover" => references.push_str("(CR)"),
+			_ => match GENRES.iter().position(|g| g.eq_ignore_ascii_case(genre)) {
+				Some(index) => {
+					let _ = write!(references, "({index})");
+				},
+				None => names.push(genre),
+			},
+		}
+	}
+
+	let names = names.join(separator);
+
+	// A name starting with a parenthesis needs to be escaped, so it isn't read as a reference
+	if names.starts_with('(') {
+		references.push('(');
+	}
+
+	references + &names
+}
+
+fn downgrade_encoding(encoding: TextEncoding) -> TextEncoding {
+	match encoding {
+		TextEncoding::Latin1 => TextEncoding::Latin1,
+		_ => TextEncoding::UTF16,
+	}
+}
+
+// The year of an ID3v2.4 timestamp, or the entire timestamp if it's malformed
+fn year(timestamp: &str) -> String {
+	match Timestamp::parse(timestamp, ParsingMode::BestAttempt) {
+		Ok(timestamp) => format!("{:04}", timestamp.year),
+		Err(_) => timestamp.to_owned(),
+	}
+}
+
+// `TDRC` holds an entire timestamp, which ID3v2.3 spreads across `TYER` (yyyy), `TDAT` (DDMM),
+// and `TIME` (HHMM)
+fn split_recording_time<'a>(frame: &FrameRef<'a>, downgraded: &mut Vec<FrameRef<'a>>) {
+	let FrameValue::Text(TextInformationFrame { encoding, value }) = &*frame.value else {
+		return;
+	};
+
+	let encoding = downgrade_encoding(*encoding);
+	let mut push = |id: &'static str, value: String| {
+		downgraded.push(FrameRef {
+			id: FrameId::Valid(Cow::Borrowed(id)),
+			value: Cow::Owned(FrameValue::Text(TextInformationFrame { encoding, value })),
+			flags: frame.flags,
+		});
+	};
+
+	push("TYER", year(value));
+
+	let Ok(timestamp) = Timestamp::parse(value, ParsingMode::BestAttempt) else {
+		return;
+	};
+
+	if let (Some(month), Some(day)) = (timestamp.month, timestamp.day) {
+		push("TDAT", format!("{day:02}{month:02}"));
+	}
+	if let (Some(hour), Some(minute)) = (timestamp.hour, timestamp.minute) {
+		push("TIME", format!("{hour:02}{minute:02}"));
+	}
+}
@@ -1,28 +1,294 @@
 use crate::error::{Id3v2Error, Id3v2ErrorKind, Result};
 use crate::id3::v2::frame::{FrameFlags, FrameRef, FrameValue};
-use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+use crate::id3::v2::header::Id3v2Version;
+use crate::id3::v2::items::{
+	CommentFrame, ExtendedTextFrame, TextInformationFrame, UnsynchronizedTextFrame,
+};
+use crate::id3::v2::tag::{is_duplicate_frame, BPM_DESCRIPTION, BPM_ID, INITIAL_KEY_ID};
+use crate::id3::v2::util::synchsafe::{unsynchronise, SynchsafeInteger};
+use crate::id3::v2::FrameId;
+use crate::picture::check_picture_size;
+use crate::probe::ParsingMode;
+use crate::tag::musical_key::MusicalKey;
+use crate::tag::try_parse_bpm;
+use crate::util::text::{truncate_to_fit, TextEncoding};
+use crate::write_options::{BpmRounding, WriteOptions};
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::Write;
 
 use byteorder::{BigEndian, WriteBytesExt};
 
+// Frame sizes are stored as 28-bit synchsafe integers
+const MAX_FRAME_SIZE: usize = 0x0FFF_FFFF;
+
+// The most bytes the frame flags can add to the frame content
+// (encryption method symbol + group identifier + data length indicator)
+const MAX_FLAG_DATA_SIZE: usize = 6;
+
 pub(in crate::id3::v2) fn create_items<W>(
 	writer: &mut W,
 	frames: &mut dyn Iterator<Item = FrameRef<'_>>,
+	write_options: WriteOptions,
 ) -> Result<()>
 where
 	W: Write,
 {
+	let version = if write_options.use_id3v23 {
+		Id3v2Version::V3
+	} else {
+		Id3v2Version::V4
+	};
+
 	for frame in frames {
 		verify_frame(&frame)?;
-		let value = frame.value.as_bytes()?;
+		check_language_codes(&frame, write_options)?;
+		check_isrcs(&frame, write_options);
+
+		// Binary payloads can't be split, no need to serialize them to know they won't fit
+		check_frame_size(frame.id.as_str(), frame.value.min_size())?;
+
+		let value = frame.value.as_bytes(write_options)?;
+
+		if let FrameValue::Picture(_) = &*frame.value {
+			// Frame header
+			check_picture_size(value.len() as u64 + 10, write_options)?;
+		}
+
+		if value.len() + MAX_FLAG_DATA_SIZE > MAX_FRAME_SIZE
+			&& write_options.split_oversized_id3v2_frames
+		{
+			if let Some(parts) =
+				split_text_frame(&frame.value, MAX_FRAME_SIZE - MAX_FLAG_DATA_SIZE)?
+			{
+				log::debug!(
+					"Splitting oversized frame `{}` into {} parts",
+					frame.id,
+					parts.len()
+				);
+
+				for part in parts {
+					write_frame(writer, frame.id.as_str(), frame.flags, &part, version)?;
+				}
 
-		write_frame(writer, frame.id.as_str(), frame.flags, &value)?;
+				continue;
+			}
+		}
+
+		write_frame(writer, frame.id.as_str(), frame.flags, &value, version)?;
 	}
 
 	Ok(())
 }
 
+// `WriteOptions::trim_whitespace` and `WriteOptions::remove_empty_items` only apply to frames holding
+// text. Each value of a multi-value text frame is handled separately.
+pub(in crate::id3::v2) fn normalize_text_frames(
+	frames: &mut Vec<FrameRef<'_>>,
+	write_options: WriteOptions,
+) {
+	frames.retain_mut(|frame| {
+		let (original_len, normalized) = match &*frame.value {
+			FrameValue::Text(TextInformationFrame { value, .. }) => {
+				let values = value
+					.split('\0')
+					.filter_map(|value| write_options.text_item(value))
+					.collect::<Vec<_>>();
+
+				if values.is_empty() {
+					return false;
+				}
+
+				(value.len(), values.join("\0"))
+			},
+			FrameValue::UserText(ExtendedTextFrame { content, .. })
+			| FrameValue::Comment(CommentFrame { content, .. })
+			| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { content, .. }) => {
+				let Some(normalized) = write_options.text_item(content) else {
+					return false;
+				};
+
+				(content.len(), normalized.to_owned())
+			},
+			_ => return true,
+		};
+
+		if normalized.len() == original_len {
+			return true;
+		}
+
+		match frame.value.to_mut() {
+			FrameValue::Text(TextInformationFrame { value, .. }) => *value = normalized,
+			FrameValue::UserText(ExtendedTextFrame { content, .. })
+			| FrameValue::Comment(CommentFrame { content, .. })
+			| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { content, .. }) => {
+				*content = normalized
+			},
+			_ => unreachable!(),
+		}
+
+		true
+	});
+}
+
+// ID3v2.4 only allows one of each kind of frame, see `DuplicateBehavior`. A tag can still hold duplicates
+// (e.g. `DuplicateBehavior::KeepBoth`), in which case only the last one is written.
+pub(in crate::id3::v2) fn remove_duplicate_frames(frames: &mut Vec<FrameRef<'_>>) {
+	let mut keep = vec![true; frames.len()];
+
+	// Only frames with the same ID can be duplicates
+	let mut kept_by_id: HashMap<&str, Vec<usize>> = HashMap::new();
+	for (idx, frame) in frames.iter().enumerate().rev() {
+		let kept = kept_by_id.entry(frame.id.as_str()).or_default();
+		let duplicate = kept.iter().any(|&kept_idx| {
+			let kept_frame = &frames[kept_idx];
+			is_duplicate_frame(
+				(&frame.id, &*frame.value),
+				(&kept_frame.id, &*kept_frame.value),
+			)
+		});
+
+		if duplicate {
+			log::warn!(
+				"Dropping a duplicate `{}` frame, only the last one is written",
+				frame.id
+			);
+			keep[idx] = false;
+			continue;
+		}
+
+		kept.push(idx);
+	}
+
+	let mut keep = keep.into_iter();
+	frames.retain(|_| keep.next().unwrap_or(true));
+}
+
+// A fractional BPM stored in a `TXXX:BPM` frame takes precedence, `TBPM` is derived from it using
+// the requested rounding mode.
+pub(in crate::id3::v2) fn derive_integer_bpm(frames: &mut [FrameRef<'_>], rounding: BpmRounding) {
+	let Some(integer) = frames
+		.iter()
+		.find_map(|frame| match &*frame.value {
+			FrameValue::UserText(ExtendedTextFrame {
+				description,
+				content,
+				..
+			}) if description.eq_ignore_ascii_case(BPM_DESCRIPTION) => try_parse_bpm(content),
+			_ => None,
+		})
+		.filter(|bpm| bpm.fract() != 0.0)
+		.and_then(|bpm| rounding.round(bpm))
+	else {
+		return;
+	};
+
+	for frame in frames.iter_mut().filter(|frame| frame.id == BPM_ID) {
+		let encoding = match &*frame.value {
+			FrameValue::Text(TextInformationFrame { encoding, .. }) => *encoding,
+			_ => TextEncoding::UTF8,
+		};
+
+		frame.value = Cow::Owned(FrameValue::Text(TextInformationFrame {
+			encoding,
+			value: integer.to_string(),
+		}));
+	}
+}
+
+// `TKEY` only allows keys in the form "Abm", "C#", or "o", so keys in any other notation
+// (e.g. "G# minor" or Camelot "1A") are normalized. Unrecognized keys are written as-is.
+pub(in crate::id3::v2) fn normalize_initial_key(frames: &mut [FrameRef<'_>]) {
+	for frame in frames.iter_mut().filter(|frame| frame.id == INITIAL_KEY_ID) {
+		let FrameValue::Text(TextInformationFrame { encoding, value }) = &*frame.value else {
+			continue;
+		};
+
+		let Some(key) = MusicalKey::parse(value) else {
+			log::warn!("Writing an unrecognized musical key in `TKEY`: {value:?}");
+			continue;
+		};
+
+		let normalized = key.to_string();
+		if *value != normalized {
+			frame.value = Cow::Owned(FrameValue::Text(TextInformationFrame {
+				encoding: *encoding,
+				value: normalized,
+			}));
+		}
+	}
+}
+
+// `USLT` and `TXXX` frames are allowed to repeat, so long as their content descriptors differ.
+//
+// This will split the content into parts no larger than `max_size` when encoded, appending the
+// part number to the description of every part after the first. Returns `None` if the frame
+// cannot be split.
+fn split_text_frame(value: &FrameValue, max_size: usize) -> Result<Option<Vec<Vec<u8>>>> {
+	let (encoding, description, content) = match value {
+		FrameValue::UnsynchronizedText(frame) => {
+			(frame.encoding, &frame.description, &frame.content)
+		},
+		FrameValue::UserText(frame) if !frame.is_cuesheet() => {
+			(frame.encoding, &frame.description, &frame.content)
+		},
+		_ => return Ok(None),
+	};
+
+	let new_part = |description: String, content: &str| -> Result<Vec<u8>> {
+		match value {
+			FrameValue::UnsynchronizedText(frame) => {
+				FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
+					encoding,
+					language: frame.language,
+					description,
+					content: content.to_owned(),
+				})
+				.as_bytes(WriteOptions::default())
+			},
+			_ => FrameValue::UserText(ExtendedTextFrame {
+				encoding,
+				description,
+				content: content.to_owned(),
+			})
+			.as_bytes(WriteOptions::default()),
+		}
+	};
+
+	let mut parts = Vec::new();
+	let mut remaining = content.as_str();
+	let mut part_number = 1;
+	while !remaining.is_empty() {
+		let description = if part_number == 1 {
+			description.clone()
+		} else {
+			format!("{description} ({part_number})")
+		};
+
+		// The size of the frame without any content (encoding, language, description, BOM, etc.)
+		let overhead = new_part(description.clone(), "")?.len();
+		let Some(budget) = max_size.checked_sub(overhead) else {
+			return Ok(None);
+		};
+
+		let part_content = truncate_to_fit(remaining, budget, encoding);
+
+		// Not even a single character fits, nothing we can do
+		if part_content.is_empty() {
+			return Ok(None);
+		}
+
+		let rest = &remaining[part_content.len()..];
+		parts.push(new_part(description, part_content)?);
+
+		remaining = rest;
+		part_number += 1;
+	}
+
+	Ok(Some(parts))
+}
+
 fn verify_frame(frame: &FrameRef<'_>) -> Result<()> {
 	match (frame.id.as_str(), frame.value.as_ref()) {
 		("APIC", FrameValue::Picture { .. })
@@ -33,7 +299,17 @@ fn verify_frame(frame: &FrameRef<'_>) -> Result<()> {
 		| (_, FrameValue::Binary(_))
 		| ("UFID", FrameValue::UniqueFileIdentifier(_))
 		| ("POPM", FrameValue::Popularimeter(_))
-		| ("TIPL" | "TMCL", FrameValue::KeyValue { .. })
+		| ("PCNT", FrameValue::PlayCounter(_))
+		| ("ETCO", FrameValue::EventTimingCodes(_))
+		| ("CHAP", FrameValue::Chapter(_))
+		| ("CTOC", FrameValue::TableOfContents(_))
+		| ("SYLT", FrameValue::SynchronizedText(_))
+		| ("GEOB", FrameValue::GeneralEncapsulatedObject(_))
+		| ("PRIV", FrameValue::Private(_))
+		| ("SEEK", FrameValue::Seek(_))
+		| ("OWNE", FrameValue::Ownership(_))
+		| ("RVA2", FrameValue::RelativeVolumeAdjustment(_))
+		| ("TIPL" | "TMCL" | "IPLS", FrameValue::KeyValue { .. })
 		| ("WFED" | "GRP1" | "MVNM" | "MVIN", FrameValue::Text { .. }) => Ok(()),
 		(id, FrameValue::Text { .. }) if id.starts_with('T') => Ok(()),
 		(id, FrameValue::Url(_)) if id.starts_with('W') => Ok(()),
@@ -45,16 +321,66 @@ fn verify_frame(frame: &FrameRef<'_>) -> Result<()> {
 	}
 }
 
-fn write_frame<W>(writer: &mut W, name: &str, flags: FrameFlags, value: &[u8]) -> Result<()>
+// `TLAN` frames are expected to contain ISO-639-2 language codes, possibly multiple. Invalid codes are
+// still written outside of `ParsingMode::Strict`, see `WriteOptions::parsing_mode`
+fn check_language_codes(frame: &FrameRef<'_>, write_options: WriteOptions) -> Result<()> {
+	let ("TLAN", FrameValue::Text(text_frame)) = (frame.id.as_str(), frame.value.as_ref()) else {
+		return Ok(());
+	};
+
+	let invalid_codes = text_frame
+		.value
+		.split('\0')
+		.filter(|code| code.len() != 3 || !code.bytes().all(|c| c.is_ascii_alphabetic()));
+	for code in invalid_codes {
+		if write_options.parsing_mode == ParsingMode::Strict {
+			return Err(
+				Id3v2Error::new(Id3v2ErrorKind::InvalidLanguageCode(code.to_owned())).into(),
+			);
+		}
+
+		log::warn!("`TLAN` frame contains an invalid ISO-639-2 language code: {code:?}");
+	}
+
+	Ok(())
+}
+
+// `TSRC` frames may hold multiple ISRCs, such as for medleys, see `WriteOptions::validate_isrc`
+fn check_isrcs(frame: &FrameRef<'_>, write_options: WriteOptions) {
+	let ("TSRC", FrameValue::Text(text_frame)) = (frame.id.as_str(), frame.value.as_ref()) else {
+		return;
+	};
+
+	for isrc in text_frame.value.split('\0') {
+		write_options.check_isrc(isrc);
+	}
+}
+
+fn write_frame<W>(
+	writer: &mut W,
+	name: &str,
+	flags: FrameFlags,
+	value: &[u8],
+	version: Id3v2Version,
+) -> Result<()>
 where
 	W: Write,
 {
+	// ID3v2.4 unsynchronises each frame individually, ID3v2.3 unsynchronises the entire tag at once
+	let unsynchronised;
+	let value = if version == Id3v2Version::V4 && flags.unsynchronisation {
+		unsynchronised = unsynchronise(value);
+		unsynchronised.as_slice()
+	} else {
+		value
+	};
+
 	if flags.encryption.is_some() {
-		write_encrypted(writer, name, value, flags)?;
+		write_encrypted(writer, name, value, flags, version)?;
 		return Ok(());
 	}
 
-	let len = value.len() as u32;
+	let len = value.len();
 	let is_grouping_identity = flags.grouping_identity.is_some();
 
 	write_frame_header(
@@ -62,6 +388,7 @@ where
 		name,
 		if is_grouping_identity { len + 1 } else { len },
 		flags,
+		version,
 	)?;
 
 	if is_grouping_identity {
@@ -74,7 +401,13 @@ where
 	Ok(())
 }
 
-fn write_encrypted<W>(writer: &mut W, name: &str, value: &[u8], flags: FrameFlags) -> Result<()>
+fn write_encrypted<W>(
+	writer: &mut W,
+	name: &str,
+	value: &[u8],
+	flags: FrameFlags,
+	version: Id3v2Version,
+) -> Result<()>
 where
 	W: Write,
 {
@@ -87,9 +420,18 @@ where
 		);
 	}
 
+	// ID3v2.3 has no data length indicator
+	if version == Id3v2Version::V3 {
+		write_frame_header(writer, name, value.len() + 1, flags, version)?;
+		writer.write_u8(method_symbol)?;
+		writer.write_all(value)?;
+
+		return Ok(());
+	}
+
 	if let Some(len) = flags.data_length_indicator {
 		if len > 0 {
-			write_frame_header(writer, name, (value.len() + 1) as u32, flags)?;
+			write_frame_header(writer, name, value.len() + 1, flags, version)?;
 			writer.write_u32::<BigEndian>(len.synch()?)?;
 			writer.write_u8(method_symbol)?;
 			writer.write_all(value)?;
@@ -101,17 +443,73 @@ where
 	Err(Id3v2Error::new(Id3v2ErrorKind::MissingDataLengthIndicator).into())
 }
 
-fn write_frame_header<W>(writer: &mut W, name: &str, len: u32, flags: FrameFlags) -> Result<()>
+fn write_frame_header<W>(
+	writer: &mut W,
+	name: &str,
+	len: usize,
+	flags: FrameFlags,
+	version: Id3v2Version,
+) -> Result<()>
 where
 	W: Write,
 {
+	check_frame_size(name, len)?;
+
 	writer.write_all(name.as_bytes())?;
-	writer.write_u32::<BigEndian>(len.synch()?)?;
-	writer.write_u16::<BigEndian>(get_flags(flags))?;
+	if version == Id3v2Version::V3 {
+		writer.write_u32::<BigEndian>(len as u32)?;
+		writer.write_u16::<BigEndian>(get_v3_flags(flags))?;
+	} else {
+		writer.write_u32::<BigEndian>((len as u32).synch()?)?;
+		writer.write_u16::<BigEndian>(get_flags(flags))?;
+	}
+
+	Ok(())
+}
+
+fn check_frame_size(name: &str, len: usize) -> Result<()> {
+	if len > MAX_FRAME_SIZE {
+		return Err(Id3v2Error::new(Id3v2ErrorKind::FrameTooLarge(
+			FrameId::Valid(Cow::Owned(name.to_owned())),
+			len,
+		))
+		.into());
+	}
 
 	Ok(())
 }
 
+// ID3v2.3 has no unsynchronisation or data length indicator flags
+fn get_v3_flags(tag_flags: FrameFlags) -> u16 {
+	let mut flags = 0;
+
+	if tag_flags.tag_alter_preservation {
+		flags |= 0x8000
+	}
+
+	if tag_flags.file_alter_preservation {
+		flags |= 0x4000
+	}
+
+	if tag_flags.read_only {
+		flags |= 0x2000
+	}
+
+	if tag_flags.compression {
+		flags |= 0x0080
+	}
+
+	if tag_flags.encryption.is_some() {
+		flags |= 0x0040
+	}
+
+	if tag_flags.grouping_identity.is_some() {
+		flags |= 0x0020
+	}
+
+	flags
+}
+
 fn get_flags(tag_flags: FrameFlags) -> u16 {
 	let mut flags = 0;
 
@@ -153,3 +551,109 @@ fn get_flags(tag_flags: FrameFlags) -> u16 {
 
 	flags
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{check_frame_size, split_text_frame, MAX_FRAME_SIZE};
+	use crate::error::{ErrorKind, Id3v2ErrorKind};
+	use crate::id3::v2::header::Id3v2Version;
+	use crate::id3::v2::items::{ExtendedTextFrame, UnsynchronizedTextFrame};
+	use crate::id3::v2::FrameValue;
+	use crate::util::text::TextEncoding;
+
+	#[test]
+	fn frame_too_large() {
+		check_frame_size("PRIV", MAX_FRAME_SIZE).unwrap();
+
+		let err = check_frame_size("PRIV", MAX_FRAME_SIZE + 1).unwrap_err();
+		let ErrorKind::Id3v2(id3v2_err) = err.kind() else {
+			panic!("Expected an ID3v2 error, got: {err}");
+		};
+		let Id3v2ErrorKind::FrameTooLarge(id, size) = id3v2_err.kind() else {
+			panic!("Expected `FrameTooLarge`, got: {id3v2_err}");
+		};
+
+		assert_eq!(id.as_str(), "PRIV");
+		assert_eq!(*size, MAX_FRAME_SIZE + 1);
+	}
+
+	#[test]
+	fn split_oversized_uslt() {
+		let content = "l\u{00f8}ft\u{00a5} ".repeat(20);
+		let frame = FrameValue::UnsynchronizedText(UnsynchronizedTextFrame {
+			encoding: TextEncoding::UTF16,
+			language: *b"eng",
+			description: String::from("Lyrics"),
+			content: content.clone(),
+		});
+
+		let max_size = 64;
+		let parts = split_text_frame(&frame, max_size).unwrap().unwrap();
+		assert!(parts.len() > 1);
+
+		let mut joined = String::new();
+		for (idx, part) in parts.iter().enumerate() {
+			assert!(part.len() <= max_size);
+
+			let part = UnsynchronizedTextFrame::parse(&mut &part[..], Id3v2Version::V4)
+				.unwrap()
+				.unwrap();
+			assert_eq!(part.language, *b"eng");
+			if idx == 0 {
+				assert_eq!(part.description, "Lyrics");
+			} else {
+				assert_eq!(part.description, format!("Lyrics ({})", idx + 1));
+			}
+
+			joined.push_str(&part.content);
+		}
+
+		assert_eq!(joined, content);
+	}
+
+	#[test]
+	fn split_oversized_txxx() {
+		let content = "a".repeat(100);
+		let frame = FrameValue::UserText(ExtendedTextFrame {
+			encoding: TextEncoding::UTF8,
+			description: String::from("FOO"),
+			content: content.clone(),
+		});
+
+		let parts = split_text_frame(&frame, 32).unwrap().unwrap();
+
+		let mut joined = String::new();
+		for part in parts {
+			assert!(part.len() <= 32);
+
+			let part = ExtendedTextFrame::parse(&mut &part[..], Id3v2Version::V4)
+				.unwrap()
+				.unwrap();
+			joined.push_str(&part.content);
+		}
+
+		assert_eq!(joined, content);
+	}
+
+	#[test]
+	fn split_unsplittable_frame() {
+		let frame = FrameValue::Binary(vec![0; 100]);
+		assert!(split_text_frame(&frame, 32).unwrap().is_none());
+
+		// The description alone is larger than the limit
+		let frame = FrameValue::UserText(ExtendedTextFrame {
+			encoding: TextEncoding::UTF8,
+			description: "a".repeat(64),
+			content: "a".repeat(100),
+		});
+		assert!(split_text_frame(&frame, 32).unwrap().is_none());
+
+		// Cue sheets are never split
+		let frame = FrameValue::UserText(ExtendedTextFrame {
+			encoding: TextEncoding::UTF8,
+			description: String::from("CUESHEET"),
+			content: "a".repeat(100),
+		});
+		assert!(split_text_frame(&frame, 32).unwrap().is_none());
+	}
+}
@@ -1,18 +1,27 @@
+mod appended;
 mod chunk_file;
+mod downgrade;
 mod frame;
+mod restrictions;
 
-use super::Id3v2TagFlags;
-use crate::error::Result;
+use super::header::Id3v2Version;
+use super::{Id3v2TagFlags, RestrictionViolation};
+use crate::error::{ErrorKind, LoftyError, Result};
 use crate::file::FileType;
-use crate::id3::find_id3v2;
-use crate::id3::v2::frame::FrameRef;
+use crate::id3::v2::frame::{Frame, FrameRef, FrameValue};
+use crate::id3::v2::read::parse_id3v2;
 use crate::id3::v2::tag::Id3v2TagRef;
-use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+use crate::id3::v2::util::synchsafe::{unsynchronise, SynchsafeInteger};
 use crate::id3::v2::Id3v2Tag;
+use crate::id3::{find_id3v2, FindId3v2Config, ID3FindResults};
+use crate::iff::aiff::AiffFile;
+use crate::iff::wav::WavFile;
 use crate::macros::err;
-use crate::probe::Probe;
+use crate::probe::{ParseOptions, ParsingMode, Probe};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
+use crate::AudioFile;
 
-use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::ops::Not;
 
@@ -33,10 +42,15 @@ static CRC_32_TABLE: once_cell::sync::Lazy<[u32; 256]> = once_cell::sync::Lazy::
 });
 
 #[allow(clippy::shadow_unrelated)]
-pub(crate) fn write_id3v2<'a, I: Iterator<Item = FrameRef<'a>> + Clone + 'a>(
-	data: &mut File,
+pub(crate) fn write_id3v2<'a, F, I>(
+	data: &mut F,
 	tag: &mut Id3v2TagRef<'a, I>,
-) -> Result<()> {
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+	I: Iterator<Item = FrameRef<'a>> + Clone + 'a,
+{
 	let probe = Probe::new(data).guess_file_type()?;
 	let file_type = probe.file_type();
 
@@ -62,23 +76,76 @@ pub(crate) fn write_id3v2<'a, I: Iterator<Item = FrameRef<'a>> + Clone + 'a>(
 		}
 	}
 
+	let mut frames = tag.frames.clone().collect::<Vec<_>>();
+
+	// `SEEK` frames depend on the layout of the file, they're only written alongside an appended tag
+	frames.retain(|frame| frame.id.as_str() != "SEEK");
+
+	if write_options.preserve_id3v2_frame_order {
+		let original = original_frames(data, file_type)?;
+		frames = preserve_frame_order(frames, &original);
+	}
+
+	let tag = &mut Id3v2TagRef {
+		flags: tag.flags,
+		frames: frames.into_iter(),
+	};
+
+	let padding = write_options.preferred_padding.unwrap_or(0);
+
 	match file_type {
 		// Formats such as WAV and AIFF store the ID3v2 tag in an 'ID3 ' chunk rather than at the beginning of the file
 		FileType::Wav => {
 			tag.flags.footer = false;
-			return chunk_file::write_to_chunk_file::<LittleEndian>(data, &create_tag(tag)?);
+			return chunk_file::write_to_chunk_file::<_, LittleEndian>(
+				data,
+				&create_tag(tag, write_options, padding)?,
+			);
 		},
 		FileType::Aiff => {
 			tag.flags.footer = false;
-			return chunk_file::write_to_chunk_file::<BigEndian>(data, &create_tag(tag)?);
+			return chunk_file::write_to_chunk_file::<_, BigEndian>(
+				data,
+				&create_tag(tag, write_options, padding)?,
+			);
+		},
+		FileType::Mpeg | FileType::Aac => {
+			if write_options.append_id3v2 {
+				return appended::write_appended_id3v2(data, tag, write_options);
+			}
+
+			// The tag at the start of the file replaces any appended one
+			appended::remove_appended_id3v2(data)?;
 		},
 		_ => {},
 	}
 
-	let id3v2 = create_tag(tag)?;
-
 	// find_id3v2 will seek us to the end of the tag
-	find_id3v2(data, false)?;
+	find_id3v2(
+		data,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+
+	let existing_len = data.stream_position()?;
+
+	// If the new tag fits in the space of the old one (including its padding), it can be written
+	// in place, leaving the rest of the file untouched
+	let frames = tag.frames.clone();
+	if let Some(id3v2) = create_tag_in_place(tag, write_options, existing_len)? {
+		data.rewind()?;
+		data.write_all(&id3v2)?;
+		return Ok(());
+	}
+
+	let tag = &mut Id3v2TagRef {
+		flags: tag.flags,
+		frames,
+	};
+	let id3v2 = create_tag(tag, write_options, padding)?;
+
+	if id3v2.len() as u64 != existing_len {
+		data.mark_moved(existing_len);
+	}
 
 	let mut file_bytes = Vec::new();
 	data.read_to_end(&mut file_bytes)?;
@@ -92,41 +159,239 @@ pub(crate) fn write_id3v2<'a, I: Iterator<Item = FrameRef<'a>> + Clone + 'a>(
 	Ok(())
 }
 
+// The frames of the tag currently in the file, see `WriteOptions::preserve_id3v2_frame_order`
+//
+// A tag that can't be read is treated as missing, it's about to be replaced anyway.
+fn original_frames<F>(data: &mut F, file_type: FileType) -> Result<Vec<Frame<'static>>>
+where
+	F: FileLike,
+{
+	let start = data.stream_position()?;
+
+	let parse_options = ParseOptions::new()
+		.read_properties(false)
+		.parsing_mode(ParsingMode::Relaxed);
+
+	let tag = match file_type {
+		FileType::Wav => WavFile::read_from(data, parse_options)
+			.ok()
+			.and_then(|file| file.id3v2_tag),
+		FileType::Aiff => AiffFile::read_from(data, parse_options)
+			.ok()
+			.and_then(|file| file.id3v2_tag),
+		_ => match find_id3v2(
+			data,
+			FindId3v2Config::READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+		)? {
+			ID3FindResults(Some(header), Some(content)) => {
+				parse_id3v2(&mut &*content, header, parse_options).ok()
+			},
+			_ => None,
+		},
+	};
+
+	data.seek(SeekFrom::Start(start))?;
+
+	Ok(tag.map(|tag| tag.frames).unwrap_or_default())
+}
+
+// Frames that exist in `original` are moved to their original positions, and new frames are placed after them
+fn preserve_frame_order<'a>(
+	frames: Vec<FrameRef<'a>>,
+	original: &[Frame<'_>],
+) -> Vec<FrameRef<'a>> {
+	let mut matched = vec![false; original.len()];
+
+	let mut existing = Vec::with_capacity(frames.len());
+	let mut new = Vec::new();
+	for frame in frames {
+		let original_position = original
+			.iter()
+			.enumerate()
+			.position(|(idx, original)| !matched[idx] && is_same_frame(&frame, original));
+
+		match original_position {
+			Some(idx) => {
+				matched[idx] = true;
+				existing.push((idx, frame));
+			},
+			None => new.push(frame),
+		}
+	}
+
+	existing.sort_by_key(|(idx, _)| *idx);
+	existing
+		.into_iter()
+		.map(|(_, frame)| frame)
+		.chain(new)
+		.collect()
+}
+
+// Matches frames the same way as `Id3v2Tag::insert`
+fn is_same_frame(frame: &FrameRef<'_>, original: &Frame<'_>) -> bool {
+	if frame.id.as_str() != original.id_str() {
+		return false;
+	}
+
+	match &*frame.value {
+		FrameValue::Text(_) => true,
+		value => value == original.content(),
+	}
+}
+
+// Creates a tag to take the place of an existing one of `existing_len` bytes, with the rest of that
+// space as padding
+//
+// This is `None` if the tag is empty, or doesn't fit.
+fn create_tag_in_place<'a, I>(
+	tag: &mut Id3v2TagRef<'a, I>,
+	write_options: WriteOptions,
+	existing_len: u64,
+) -> Result<Option<Vec<u8>>>
+where
+	I: Iterator<Item = FrameRef<'a>> + Clone + 'a,
+{
+	let frames = tag.frames.clone();
+	let id3v2 = create_tag(tag, write_options, 0)?;
+
+	let has_footer = tag.flags.footer && write_version(write_options) == Id3v2Version::V4;
+	let remaining_space = match existing_len.checked_sub(id3v2.len() as u64) {
+		_ if id3v2.is_empty() => return Ok(None),
+		Some(0) => return Ok(Some(id3v2)),
+		// Padding isn't allowed in a tag with a footer, so it has to fit exactly
+		Some(remaining_space) if !has_footer => remaining_space as u32,
+		_ => return Ok(None),
+	};
+
+	let tag = &mut Id3v2TagRef {
+		flags: tag.flags,
+		frames,
+	};
+	// The padding may have been limited by the tag restrictions
+	let id3v2 = create_tag(tag, write_options, remaining_space)?;
+	Ok((id3v2.len() as u64 == existing_len).then_some(id3v2))
+}
+
 pub(super) fn create_tag<'a, I: Iterator<Item = FrameRef<'a>> + 'a>(
 	tag: &mut Id3v2TagRef<'a, I>,
+	write_options: WriteOptions,
+	mut padding: u32,
 ) -> Result<Vec<u8>> {
-	let frames = &mut tag.frames;
-	let mut peek = frames.peekable();
+	let mut frames = tag.frames.by_ref().collect::<Vec<_>>();
+	frame::remove_duplicate_frames(&mut frames);
+	frame::normalize_text_frames(&mut frames, write_options);
 
 	// We are stripping the tag
-	if peek.peek().is_none() {
+	if frames.is_empty() {
 		return Ok(Vec::new());
 	}
 
-	let has_footer = tag.flags.footer;
+	let version = write_version(write_options);
+	if version == Id3v2Version::V3 && tag.flags.restrictions.is_some() {
+		log::warn!("Dropping the ID3v2 tag restrictions, they don't exist in ID3v2.3");
+	}
+
+	let has_footer = tag.flags.footer && version == Id3v2Version::V4;
 	let needs_crc = tag.flags.crc;
 	let has_restrictions = tag.flags.restrictions.is_some();
 
-	let (mut id3v2, extended_header_len) = create_tag_header(tag.flags)?;
+	let (mut id3v2, extended_header_len) = create_tag_header(tag.flags, version)?;
 	let header_len = id3v2.get_ref().len();
 
 	// Write the items
-	frame::create_items(&mut id3v2, &mut peek)?;
+	frame::derive_integer_bpm(&mut frames, write_options.bpm_rounding);
+	frame::normalize_initial_key(&mut frames);
+
+	let restrictions = tag
+		.flags
+		.restrictions
+		.filter(|_| version == Id3v2Version::V4);
+	let mut violations = Vec::new();
+	if let Some(restrictions) = restrictions {
+		if write_options.fit_id3v2_restrictions {
+			frames = restrictions::fit_frames(frames, tag.flags, restrictions, write_options)?;
+		}
+
+		violations = restrictions::check_frames(&frames, restrictions);
+	}
+
+	if version == Id3v2Version::V3 {
+		frames = downgrade::downgrade_frames(frames, write_options);
+	} else if tag.flags.unsynchronisation {
+		// In ID3v2.4, the tag flag only signals that every frame is unsynchronised
+		for frame in &mut frames {
+			frame.flags.unsynchronisation = true;
+		}
+	}
+
+	frame::create_items(&mut id3v2, &mut frames.into_iter(), write_options)?;
+
+	if let Some(restrictions) = restrictions {
+		let mut size = id3v2.get_ref().len();
+		if has_footer {
+			size += 10;
+		}
+
+		let max_size = restrictions.size.max_size();
+		if size > max_size {
+			violations.push(RestrictionViolation::TagSize { size, max_size });
+		}
 
+		if !violations.is_empty() {
+			return Err(LoftyError::new(ErrorKind::Id3v2Restrictions(violations)));
+		}
+
+		// The padding counts towards the size of the tag
+		padding = padding.min((max_size - size) as u32);
+	}
+
+	if version == Id3v2Version::V3 {
+		if needs_crc {
+			// ID3v2.3 stores the CRC as a plain 32-bit integer at the end of the extended header,
+			// calculated prior to unsynchronisation
+			//
+			// Normal ID3v2 header (10)
+			// Extended header size (4) + flags (2) + padding size (4)
+			id3v2.seek(SeekFrom::Start(20))?;
+
+			let crc = crc32(&id3v2.get_ref()[header_len..]);
+			id3v2.write_u32::<BigEndian>(crc)?;
+		}
+
+		if tag.flags.unsynchronisation {
+			let frames = id3v2.get_mut().split_off(header_len);
+			id3v2.get_mut().extend(unsynchronise(&frames));
+		}
+	}
+
+	// Padding isn't allowed in a tag with a footer
+	if padding > 0 && !has_footer {
+		let new_len = id3v2.get_ref().len() + padding as usize;
+		id3v2.get_mut().resize(new_len, 0);
+
+		if version == Id3v2Version::V3 && needs_crc {
+			// Normal ID3v2 header (10)
+			// Extended header size (4) + flags (2)
+			id3v2.seek(SeekFrom::Start(16))?;
+			id3v2.write_u32::<BigEndian>(padding)?;
+		}
+	}
+
+	// The size is that of the tag *after* unsynchronisation
 	let len = id3v2.get_ref().len() - header_len;
 
 	// Go back to the start and write the final size
 	id3v2.seek(SeekFrom::Start(6))?;
 	id3v2.write_u32::<BigEndian>((extended_header_len + len as u32).synch()?)?;
 
-	if needs_crc {
+	if needs_crc && version == Id3v2Version::V4 {
 		// The CRC is calculated on all the data between the header and footer
-		#[allow(unused_mut)]
 		// Past the CRC
 		let mut content_start_idx = 22;
 
+		// Past the restrictions (2)
 		if has_restrictions {
-			content_start_idx += 3;
+			content_start_idx += 2;
 		}
 
 		// Skip 16 bytes
@@ -157,7 +422,75 @@ pub(super) fn create_tag<'a, I: Iterator<Item = FrameRef<'a>> + 'a>(
 	Ok(id3v2.into_inner())
 }
 
-fn create_tag_header(flags: Id3v2TagFlags) -> Result<(Cursor<Vec<u8>>, u32)> {
+// The size of a single frame when written, including its header
+pub(in crate::id3::v2) fn frame_size(
+	frame: FrameRef<'_>,
+	write_options: WriteOptions,
+) -> Result<usize> {
+	let frames = if write_version(write_options) == Id3v2Version::V3 {
+		downgrade::downgrade_frames(vec![frame], write_options)
+	} else {
+		vec![frame]
+	};
+
+	let mut bytes = Vec::new();
+	frame::create_items(&mut bytes, &mut frames.into_iter(), write_options)?;
+
+	Ok(bytes.len())
+}
+
+// Frames embedded in another frame, such as a `CHAP` frame, see `parse_embedded_frames`
+pub(in crate::id3::v2) fn create_embedded_frames(
+	frames: &[Frame<'_>],
+	write_options: WriteOptions,
+) -> Result<Vec<u8>> {
+	let mut frames = frames.iter().filter_map(Frame::as_opt_ref).collect();
+	frame::normalize_text_frames(&mut frames, write_options);
+
+	let frames = if write_version(write_options) == Id3v2Version::V3 {
+		downgrade::downgrade_frames(frames, write_options)
+	} else {
+		frames
+	};
+
+	let mut bytes = Vec::new();
+	frame::create_items(&mut bytes, &mut frames.into_iter(), write_options)?;
+
+	Ok(bytes)
+}
+
+// The size of everything in a tag other than its frames (header, extended header, and footer)
+pub(in crate::id3::v2) fn tag_overhead(
+	flags: Id3v2TagFlags,
+	write_options: WriteOptions,
+) -> Result<usize> {
+	let version = write_version(write_options);
+	let (header, _) = create_tag_header(flags, version)?;
+
+	let mut overhead = header.get_ref().len();
+	if flags.footer && version == Id3v2Version::V4 {
+		overhead += 10;
+	}
+
+	Ok(overhead)
+}
+
+fn write_version(write_options: WriteOptions) -> Id3v2Version {
+	if write_options.use_id3v23 {
+		Id3v2Version::V3
+	} else {
+		Id3v2Version::V4
+	}
+}
+
+fn create_tag_header(
+	flags: Id3v2TagFlags,
+	version: Id3v2Version,
+) -> Result<(Cursor<Vec<u8>>, u32)> {
+	if version == Id3v2Version::V3 {
+		return create_v3_tag_header(flags);
+	}
+
 	let mut header = Cursor::new(Vec::new());
 
 	header.write_all(&[b'I', b'D', b'3'])?;
@@ -169,6 +502,10 @@ fn create_tag_header(flags: Id3v2TagFlags) -> Result<(Cursor<Vec<u8>>, u32)> {
 
 	let extended_header = flags.crc || flags.restrictions.is_some();
 
+	if flags.unsynchronisation {
+		tag_flags |= 0x80
+	}
+
 	if flags.footer {
 		tag_flags |= 0x10
 	}
@@ -227,14 +564,65 @@ fn create_tag_header(flags: Id3v2TagFlags) -> Result<(Cursor<Vec<u8>>, u32)> {
 	Ok((header, extended_header_size))
 }
 
+fn create_v3_tag_header(flags: Id3v2TagFlags) -> Result<(Cursor<Vec<u8>>, u32)> {
+	let mut header = Cursor::new(Vec::new());
+
+	header.write_all(b"ID3")?;
+
+	// Version 3, rev 0
+	header.write_all(&[3, 0])?;
+
+	let mut tag_flags = 0;
+
+	if flags.unsynchronisation {
+		tag_flags |= 0x80
+	}
+
+	if flags.experimental {
+		tag_flags |= 0x20
+	}
+
+	// The CRC is the only thing that can be stored in an ID3v2.3 extended header
+	if flags.crc {
+		tag_flags |= 0x40
+	}
+
+	header.write_u8(tag_flags)?;
+	header.write_u32::<BigEndian>(0)?;
+
+	let mut extended_header_size = 0;
+	if flags.crc {
+		// Structure of extended header:
+		//
+		// Size (4) (Not synchsafe, and excludes itself)
+		// Flags (2)
+		// Padding size (4)
+		// CRC (4)
+		header.write_u32::<BigEndian>(10)?;
+		header.write_u16::<BigEndian>(0x8000)?;
+		header.write_u32::<BigEndian>(0)?;
+
+		// Zeroed until the frames are written
+		header.write_u32::<BigEndian>(0)?;
+
+		extended_header_size = 14;
+	}
+
+	Ok((header, extended_header_size))
+}
+
 // https://github.com/rstemmer/id3edit/blob/0246f3dc1a7a80a64461eeeb7b9ee88379003eb1/encoding/crc.c#L6:6
-fn calculate_crc(content: &[u8]) -> [u8; 5] {
-	let crc: u32 = content
+pub(crate) fn crc32(content: &[u8]) -> u32 {
+	content
 		.iter()
 		.fold(!0, |crc, octet| {
 			(crc >> 8) ^ CRC_32_TABLE[(((crc & 0xFF) ^ u32::from(*octet)) & 0xFF) as usize]
 		})
-		.not();
+		.not()
+}
+
+fn calculate_crc(content: &[u8]) -> [u8; 5] {
+	let crc = crc32(content);
 
 	// The CRC-32 is stored as an 35 bit synchsafe integer, leaving the upper
 	// four bits always zeroed.
@@ -253,8 +641,22 @@ fn calculate_crc(content: &[u8]) -> [u8; 5] {
 
 #[cfg(test)]
 mod tests {
-	use crate::id3::v2::{Id3v2Tag, Id3v2TagFlags};
-	use crate::{Accessor, TagExt};
+	use super::crc32;
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::id3::v2::read::parse_id3v2;
+	use crate::id3::v2::tag::Id3v2TagRef;
+	use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+	use crate::id3::v2::{Frame, FrameId, FrameValue, Id3v2Tag, Id3v2TagFlags, TagRestrictions};
+	use crate::{Accessor, MimeType, ParseOptions, Picture, PictureType, TagExt, WriteOptions};
+
+	use std::borrow::Cow;
+
+	#[cfg(feature = "fs")]
+	use crate::{mpeg::MpegFile, AudioFile};
+	#[cfg(feature = "fs")]
+	use std::fs::File;
+	#[cfg(feature = "fs")]
+	use std::io::{Read, Seek, Write};
 
 	#[test]
 	fn id3v2_write_crc32() {
@@ -268,7 +670,7 @@ mod tests {
 		tag.set_flags(flags);
 
 		let mut writer = Vec::new();
-		tag.dump_to(&mut writer).unwrap();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
 
 		let crc_content = &writer[16..22];
 		assert_eq!(crc_content, &[5, 0x06, 0x35, 0x69, 0x7D, 0x14]);
@@ -286,4 +688,311 @@ mod tests {
 
 		assert_eq!(unsynch_crc, 0x66BA_7E94);
 	}
+
+	#[test]
+	fn id3v2_crc32_roundtrip() {
+		for restrictions in [None, Some(TagRestrictions::default())] {
+			let mut tag = Id3v2Tag::default();
+			tag.set_artist(String::from("Foo artist"));
+			tag.set_title(String::from("Bar title"));
+			tag.set_flags(Id3v2TagFlags {
+				crc: true,
+				restrictions,
+				..Id3v2TagFlags::default()
+			});
+
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+			let mut reader = &writer[..];
+			let header = Id3v2Header::parse(&mut reader).unwrap();
+			let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+
+			let crc = read_tag.crc().unwrap();
+			assert_eq!(crc.is_valid(), Some(true));
+
+			// The CRC covers every byte following the extended header
+			let extended_header_len = if restrictions.is_some() { 14 } else { 12 };
+			assert_eq!(crc.stored(), crc32(&writer[10 + extended_header_len..]));
+
+			assert_eq!(read_tag.artist().as_deref(), Some("Foo artist"));
+			assert_eq!(read_tag.flags().restrictions, restrictions);
+		}
+	}
+
+	#[test]
+	fn id3v23_crc32_roundtrip() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_artist(String::from("Foo artist"));
+		tag.set_flags(Id3v2TagFlags {
+			crc: true,
+			// Doesn't exist in ID3v2.3, and will be dropped
+			restrictions: Some(TagRestrictions::default()),
+			..Id3v2TagFlags::default()
+		});
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::new().use_id3v23(true))
+			.unwrap();
+
+		// Extended header size (10), flags (CRC present), padding size (0)
+		assert_eq!(&writer[3..6], &[3, 0, 0x40]);
+		assert_eq!(&writer[10..20], &[0, 0, 0, 10, 0x80, 0, 0, 0, 0, 0]);
+
+		let mut reader = &writer[..];
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+
+		let crc = read_tag.crc().unwrap();
+		assert_eq!(crc.is_valid(), Some(true));
+		assert_eq!(crc.stored(), crc32(&writer[24..]));
+
+		assert_eq!(read_tag.artist().as_deref(), Some("Foo artist"));
+		assert_eq!(read_tag.flags().restrictions, None);
+	}
+
+	#[test]
+	fn crc32_with_padding() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_artist(String::from("Foo artist"));
+		tag.set_flags(Id3v2TagFlags {
+			crc: true,
+			..Id3v2TagFlags::default()
+		});
+
+		for use_id3v23 in [false, true] {
+			let tag_bytes = super::create_tag(
+				&mut Id3v2TagRef {
+					flags: *tag.flags(),
+					frames: tag.frames.iter().filter_map(Frame::as_opt_ref),
+				},
+				WriteOptions::new().use_id3v23(use_id3v23),
+				64,
+			)
+			.unwrap();
+			assert!(tag_bytes.ends_with(&[0; 64]));
+
+			let mut reader = &tag_bytes[..];
+			let header = Id3v2Header::parse(&mut reader).unwrap();
+			let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+			assert_eq!(read_tag.artist().as_deref(), Some("Foo artist"));
+
+			let crc = read_tag.crc().unwrap();
+			assert_eq!(crc.is_valid(), Some(true));
+
+			if use_id3v23 {
+				// ID3v2.3 stores the padding size in the extended header, and excludes it from the CRC
+				assert_eq!(&tag_bytes[16..20], &64_u32.to_be_bytes());
+				assert_eq!(crc.stored(), crc32(&tag_bytes[24..tag_bytes.len() - 64]));
+			} else {
+				assert_eq!(crc.stored(), crc32(&tag_bytes[22..]));
+			}
+		}
+	}
+
+	#[test]
+	fn id3v23_numeric_genres() {
+		let mut tag = Id3v2Tag::default();
+		tag.set_genre(String::from("Rock\0Remix\0Eurodisco"));
+
+		let write_options = WriteOptions::new()
+			.use_id3v23(true)
+			.id3v23_numeric_genres(true);
+
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, write_options).unwrap();
+
+		let genre_id = FrameId::Valid(Cow::Borrowed("TCON"));
+
+		let mut reader = &writer[..];
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+		assert_eq!(
+			read_tag.get_text(&genre_id).as_deref(),
+			Some("(17)(RX)Eurodisco")
+		);
+
+		let mut reader = &writer[..];
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let read_tag = parse_id3v2(
+			&mut reader,
+			header,
+			ParseOptions::new().resolve_id3v2_genres(true),
+		)
+		.unwrap();
+		assert_eq!(
+			read_tag.get_texts(&genre_id).unwrap().collect::<Vec<_>>(),
+			["Rock", "Remix", "Eurodisco"]
+		);
+	}
+
+	#[test]
+	fn invalid_language_code() {
+		use crate::error::{ErrorKind, Id3v2ErrorKind};
+		use crate::ParsingMode;
+
+		let write = |languages: &str, parsing_mode| {
+			let mut tag = Id3v2Tag::default();
+			tag.insert(Frame::text(Cow::Borrowed("TLAN"), languages.to_owned()));
+
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, WriteOptions::new().parsing_mode(parsing_mode))
+				.map(|()| writer)
+		};
+
+		assert!(write("eng\0deu", ParsingMode::Strict).is_ok());
+
+		let err = write("eng\0english", ParsingMode::Strict).unwrap_err();
+		let ErrorKind::Id3v2(err) = err.kind() else {
+			panic!("{err}");
+		};
+		assert!(
+			matches!(err.kind(), Id3v2ErrorKind::InvalidLanguageCode(code) if code == "english"),
+			"{err}"
+		);
+
+		// The frame is written as-is otherwise
+		let writer = write("eng\0english", ParsingMode::BestAttempt).unwrap();
+
+		let mut reader = &writer[..];
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+		assert_eq!(
+			read_tag
+				.get_texts(&FrameId::Valid(Cow::Borrowed("TLAN")))
+				.unwrap()
+				.collect::<Vec<_>>(),
+			["eng", "english"]
+		);
+	}
+
+	#[test]
+	fn unsynchronisation_roundtrip() {
+		// A JPEG is full of `0xFF` markers, some followed by bytes that look like a frame sync
+		let mut jpeg = vec![
+			0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00,
+		];
+		for i in 0..=u8::MAX {
+			jpeg.extend([0xFF, i, 0xFF, 0xFF, 0x00]);
+		}
+		jpeg.extend([0xFF, 0xD9, 0xFF]);
+
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.insert_picture(Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Jpeg),
+			None,
+			jpeg.clone(),
+		));
+		tag.set_flags(Id3v2TagFlags {
+			unsynchronisation: true,
+			crc: true,
+			..Id3v2TagFlags::default()
+		});
+
+		for write_options in [WriteOptions::new(), WriteOptions::new().use_id3v23(true)] {
+			let mut writer = Vec::new();
+			tag.dump_to(&mut writer, write_options).unwrap();
+
+			assert_eq!(writer[5] & 0x80, 0x80);
+
+			// Header (10) + tag size
+			let size = u32::from_be_bytes(writer[6..10].try_into().unwrap()).unsynch();
+			assert_eq!(size as usize, writer.len() - 10);
+
+			// Skip the extended header, the CRC isn't unsynchronised
+			let extended_header_len = if write_options.use_id3v23 { 14 } else { 12 };
+			let frames = &writer[10 + extended_header_len..];
+			assert!(!frames
+				.windows(2)
+				.any(|window| window[0] == 0xFF && window[1] >= 0xE0));
+
+			let mut reader = &writer[..];
+			let header = Id3v2Header::parse(&mut reader).unwrap();
+			let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+
+			assert_eq!(read_tag.crc().unwrap().is_valid(), Some(true));
+			assert!(read_tag.flags().unsynchronisation);
+			assert_eq!(read_tag.title().as_deref(), Some("Foo title"));
+
+			let pictures = read_tag.into_iter().find(|frame| frame.id_str() == "APIC");
+			let Some(FrameValue::Picture(picture)) = pictures.map(|frame| frame.value) else {
+				panic!("Expected an APIC frame");
+			};
+			assert_eq!(picture.picture.data(), jpeg);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn preserve_frame_order() {
+		fn tag_bytes(file: &mut File) -> Vec<u8> {
+			file.rewind().unwrap();
+			let header = Id3v2Header::parse(file).unwrap();
+
+			file.rewind().unwrap();
+			let mut tag = vec![0; 10 + header.size as usize];
+			file.read_exact(&mut tag).unwrap();
+
+			file.rewind().unwrap();
+			tag
+		}
+
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(&std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap())
+			.unwrap();
+		file.rewind().unwrap();
+
+		let mut tag = Id3v2Tag::default();
+		tag.set_artist(String::from("Foo artist"));
+		tag.set_title(String::from("Foo title"));
+		tag.set_comment(String::from("Foo comment"));
+		tag.set_album(String::from("Foo album"));
+		tag.insert_user_text(String::from("FOO"), String::from("Bar"));
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		let original_tag = tag_bytes(&mut file);
+
+		// Only the title changes, which moves its frame to the end of the tag
+		let mut tag = MpegFile::read_from(&mut file, ParseOptions::new())
+			.unwrap()
+			.id3v2()
+			.cloned()
+			.unwrap();
+		tag.set_title(String::from("Baz title"));
+		file.rewind().unwrap();
+
+		let expected = String::from_utf8_lossy(&original_tag).replace("Foo title", "Baz title");
+
+		let mut reordered_tag = Vec::new();
+		tag.dump_to(&mut reordered_tag, WriteOptions::default())
+			.unwrap();
+		assert_ne!(reordered_tag, expected.as_bytes());
+
+		// Unless the original order is preserved, in which case every other frame stays at the same offset
+		tag.save_to(
+			&mut file,
+			WriteOptions::new().preserve_id3v2_frame_order(true),
+		)
+		.unwrap();
+		assert_eq!(tag_bytes(&mut file), expected.as_bytes());
+
+		// New frames are written after the existing ones
+		tag.set_genre(String::from("Foo genre"));
+		tag.save_to(
+			&mut file,
+			WriteOptions::new().preserve_id3v2_frame_order(true),
+		)
+		.unwrap();
+		file.rewind().unwrap();
+
+		let tag = MpegFile::read_from(&mut file, ParseOptions::new())
+			.unwrap()
+			.id3v2()
+			.cloned()
+			.unwrap();
+		let ids = tag.frames.iter().map(Frame::id_str).collect::<Vec<_>>();
+		assert_eq!(ids, ["TPE1", "TIT2", "COMM", "TALB", "TXXX", "TCON"]);
+	}
 }
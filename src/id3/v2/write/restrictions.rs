@@ -0,0 +1,406 @@
+//! Enforcing the restrictions of ID3v2.4 tags, see [`Id3v2TagFlags::restrictions`]
+
+use super::tag_overhead;
+use crate::error::Result;
+use crate::id3::v2::frame::{FrameRef, FrameValue};
+use crate::id3::v2::items::{
+	AttachedPictureFrame, CommentFrame, ExtendedTextFrame, ExtendedUrlFrame,
+	GeneralEncapsulatedObject, KeyValueFrame, OwnershipFrame, SynchronizedText,
+	TextInformationFrame, UnsynchronizedTextFrame,
+};
+use crate::id3::v2::limits::{fit_to_limits, text, TagLimits};
+use crate::id3::v2::restrictions::{RestrictionViolation, TagRestrictions};
+use crate::id3::v2::{Id3v2Tag, Id3v2TagFlags};
+use crate::picture::{MimeType, Picture, PictureInformation};
+use crate::util::text::TextEncoding;
+use crate::write_options::WriteOptions;
+
+// Everything that violates the restrictions, other than the size of the tag
+pub(super) fn check_frames(
+	frames: &[FrameRef<'_>],
+	restrictions: TagRestrictions,
+) -> Vec<RestrictionViolation> {
+	let mut violations = Vec::new();
+
+	let max_frames = restrictions.size.max_frames();
+	if frames.len() > max_frames {
+		violations.push(RestrictionViolation::FrameCount {
+			count: frames.len(),
+			max_frames,
+		});
+	}
+
+	for frame in frames {
+		if let Some((len, max_len)) = text_len(&frame.value, restrictions) {
+			violations.push(RestrictionViolation::TextSize {
+				frame_id: frame.id.as_str().to_owned(),
+				len,
+				max_len,
+			});
+		}
+
+		if let Some(encoding) = encoding(&frame.value).filter(|_| restrictions.text_encoding) {
+			if !is_allowed_encoding(encoding) {
+				violations.push(RestrictionViolation::TextEncoding {
+					frame_id: frame.id.as_str().to_owned(),
+					encoding,
+				});
+			}
+		}
+
+		if let FrameValue::Picture(AttachedPictureFrame { picture, .. }) = &*frame.value {
+			violations.extend(check_picture(picture, restrictions));
+		}
+	}
+
+	violations
+}
+
+// Changes the frames to fit the restrictions, see `WriteOptions::fit_id3v2_restrictions`
+pub(super) fn fit_frames(
+	mut frames: Vec<FrameRef<'_>>,
+	flags: Id3v2TagFlags,
+	restrictions: TagRestrictions,
+	write_options: WriteOptions,
+) -> Result<Vec<FrameRef<'_>>> {
+	if restrictions.text_encoding {
+		for frame in &mut frames {
+			if encoding(&frame.value).is_some_and(|encoding| !is_allowed_encoding(encoding)) {
+				set_encoding(frame.value.to_mut(), TextEncoding::UTF8);
+			}
+		}
+	}
+
+	frames.retain(|frame| {
+		let FrameValue::Picture(AttachedPictureFrame { picture, .. }) = &*frame.value else {
+			return true;
+		};
+
+		let violations = check_picture(picture, restrictions);
+		for violation in &violations {
+			log::warn!("Removing a picture to fit the ID3v2 tag restrictions: {violation}");
+		}
+
+		violations.is_empty()
+	});
+
+	// The rest is the same as fitting the tag to its limits. The tag is measured without the
+	// restrictions, so it isn't checked against them while fitting.
+	let unrestricted_flags = Id3v2TagFlags {
+		restrictions: None,
+		..flags
+	};
+	let restrictions_len =
+		tag_overhead(flags, write_options)? - tag_overhead(unrestricted_flags, write_options)?;
+
+	let mut limits = TagLimits::from(restrictions);
+	limits.size = limits
+		.size
+		.map(|size| size.saturating_sub(restrictions_len));
+
+	let mut tag = Id3v2Tag::new();
+	tag.set_flags(unrestricted_flags);
+	tag.frames = frames.into_iter().map(FrameRef::into_owned).collect();
+
+	let report = fit_to_limits(&mut tag, limits, write_options)?;
+	for frame in report.truncated {
+		log::warn!(
+			"Truncating `{}` to fit the ID3v2 tag restrictions",
+			frame.id_str()
+		);
+	}
+	for frame in report.removed {
+		log::warn!(
+			"Removing `{}` to fit the ID3v2 tag restrictions",
+			frame.id_str()
+		);
+	}
+
+	// Cue sheets are never truncated, so they have to go entirely
+	tag.frames.retain(|frame| {
+		let Some((len, max_len)) = text_len(frame.content(), restrictions) else {
+			return true;
+		};
+
+		log::warn!(
+			"Removing `{}` to fit the ID3v2 tag restrictions, its text is {len} characters, exceeding \
+			 {max_len} characters",
+			frame.id_str()
+		);
+		false
+	});
+
+	Ok(tag.frames.into_iter().map(FrameRef::from).collect())
+}
+
+// The length of a text field exceeding the text size restriction, and the maximum length
+fn text_len(value: &FrameValue, restrictions: TagRestrictions) -> Option<(usize, usize)> {
+	let max_len = restrictions.text_fields_size?.max_len();
+	let len = text(value)?.chars().count();
+
+	(len > max_len).then_some((len, max_len))
+}
+
+fn check_picture(picture: &Picture, restrictions: TagRestrictions) -> Vec<RestrictionViolation> {
+	let mut violations = Vec::new();
+
+	if restrictions.image_encoding
+		&& !matches!(picture.mime_type(), Some(MimeType::Png | MimeType::Jpeg))
+	{
+		violations.push(RestrictionViolation::ImageEncoding {
+			mime_type: picture.mime_type().cloned(),
+		});
+	}
+
+	if let Some(image_size) = restrictions.image_size {
+		// The dimensions are zeroed for anything other than PNG and JPEG
+		let dimensions = PictureInformation::from_picture(picture)
+			.ok()
+			.filter(|info| info.width > 0 && info.height > 0)
+			.map(|info| (info.width, info.height));
+
+		if !dimensions.is_some_and(|(width, height)| image_size.allows(width, height)) {
+			violations.push(RestrictionViolation::ImageSize { dimensions });
+		}
+	}
+
+	violations
+}
+
+fn is_allowed_encoding(encoding: TextEncoding) -> bool {
+	matches!(encoding, TextEncoding::Latin1 | TextEncoding::UTF8)
+}
+
+fn encoding(value: &FrameValue) -> Option<TextEncoding> {
+	match value {
+		FrameValue::Text(TextInformationFrame { encoding, .. })
+		| FrameValue::UserText(ExtendedTextFrame { encoding, .. })
+		| FrameValue::UserUrl(ExtendedUrlFrame { encoding, .. })
+		| FrameValue::Comment(CommentFrame { encoding, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { encoding, .. })
+		| FrameValue::Picture(AttachedPictureFrame { encoding, .. })
+		| FrameValue::KeyValue(KeyValueFrame { encoding, .. })
+		| FrameValue::Ownership(OwnershipFrame { encoding, .. })
+		| FrameValue::SynchronizedText(SynchronizedText { encoding, .. })
+		| FrameValue::GeneralEncapsulatedObject(GeneralEncapsulatedObject { encoding, .. }) => {
+			Some(*encoding)
+		},
+		_ => None,
+	}
+}
+
+fn set_encoding(value: &mut FrameValue, new_encoding: TextEncoding) {
+	match value {
+		FrameValue::Text(TextInformationFrame { encoding, .. })
+		| FrameValue::UserText(ExtendedTextFrame { encoding, .. })
+		| FrameValue::UserUrl(ExtendedUrlFrame { encoding, .. })
+		| FrameValue::Comment(CommentFrame { encoding, .. })
+		| FrameValue::UnsynchronizedText(UnsynchronizedTextFrame { encoding, .. })
+		| FrameValue::Picture(AttachedPictureFrame { encoding, .. })
+		| FrameValue::KeyValue(KeyValueFrame { encoding, .. })
+		| FrameValue::Ownership(OwnershipFrame { encoding, .. })
+		| FrameValue::SynchronizedText(SynchronizedText { encoding, .. })
+		| FrameValue::GeneralEncapsulatedObject(GeneralEncapsulatedObject { encoding, .. }) => {
+			*encoding = new_encoding;
+		},
+		_ => {},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::error::ErrorKind;
+	use crate::id3::v2::header::Id3v2Header;
+	use crate::id3::v2::read::parse_id3v2;
+	use crate::id3::v2::restrictions::{
+		ImageSizeRestrictions, RestrictionViolation, TagRestrictions, TagSizeRestrictions,
+	};
+	use crate::id3::v2::tag::Id3v2TagRef;
+	use crate::id3::v2::{Frame, FrameFlags, Id3v2Tag, Id3v2TagFlags, TextInformationFrame};
+	use crate::util::text::TextEncoding;
+	use crate::{
+		Accessor as _, MimeType, ParseOptions, Picture, PictureType, TagExt as _, WriteOptions,
+	};
+
+	fn restricted_tag(restrictions: TagRestrictions) -> Id3v2Tag {
+		let mut tag = Id3v2Tag::default();
+		tag.set_title(String::from("Foo title"));
+		tag.set_flags(Id3v2TagFlags {
+			restrictions: Some(restrictions),
+			..Id3v2TagFlags::default()
+		});
+		tag
+	}
+
+	// A PNG with only its signature and the start of the IHDR chunk, enough to read its dimensions
+	fn png(width: u32, height: u32, len: usize) -> Picture {
+		let mut data = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+		data.extend(13_u32.to_be_bytes());
+		data.extend(b"IHDR");
+		data.extend(width.to_be_bytes());
+		data.extend(height.to_be_bytes());
+		data.extend([8, 2, 0, 0, 0]);
+		data.resize(len.max(data.len()), 0);
+
+		Picture::new_unchecked(PictureType::CoverFront, Some(MimeType::Png), None, data)
+	}
+
+	fn violations(tag: &Id3v2Tag, write_options: WriteOptions) -> Vec<RestrictionViolation> {
+		let err = tag.dump_to(&mut Vec::new(), write_options).unwrap_err();
+		match err.kind() {
+			ErrorKind::Id3v2Restrictions(violations) => violations.clone(),
+			_ => panic!("Expected a restrictions error, got: {err}"),
+		}
+	}
+
+	fn write_and_read(tag: &Id3v2Tag, write_options: WriteOptions) -> (usize, Id3v2Tag) {
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, write_options).unwrap();
+
+		let mut reader = &writer[..];
+		let header = Id3v2Header::parse(&mut reader).unwrap();
+		let read_tag = parse_id3v2(&mut reader, header, ParseOptions::new()).unwrap();
+
+		(writer.len(), read_tag)
+	}
+
+	#[test]
+	fn frame_count() {
+		let restrictions = TagRestrictions {
+			size: TagSizeRestrictions::S_128F_1M,
+			..TagRestrictions::default()
+		};
+
+		let mut tag = restricted_tag(restrictions);
+		for i in 0..128 {
+			tag.insert_user_text(format!("DESCRIPTION {i}"), String::from("Foo"));
+		}
+
+		assert_eq!(
+			violations(&tag, WriteOptions::default()),
+			[RestrictionViolation::FrameCount {
+				count: 129,
+				max_frames: 128
+			}]
+		);
+
+		let (_, read_tag) = write_and_read(&tag, WriteOptions::new().fit_id3v2_restrictions(true));
+		assert_eq!(read_tag.len(), 128);
+		assert_eq!(read_tag.flags().restrictions, Some(restrictions));
+	}
+
+	#[test]
+	fn tag_size() {
+		let restrictions = TagRestrictions {
+			size: TagSizeRestrictions::S_128F_1M,
+			..TagRestrictions::default()
+		};
+
+		let mut tag = restricted_tag(restrictions);
+		tag.insert_picture(png(64, 64, 1024 * 1024));
+
+		let violations = violations(&tag, WriteOptions::default());
+		assert!(matches!(
+			violations[..],
+			[RestrictionViolation::TagSize { size, max_size: 1_048_576 }] if size > 1_048_576
+		));
+
+		// The picture has to go, the title is all that's left
+		let (_, read_tag) = write_and_read(&tag, WriteOptions::new().fit_id3v2_restrictions(true));
+		assert_eq!(read_tag.len(), 1);
+		assert_eq!(read_tag.title().as_deref(), Some("Foo title"));
+
+		// Padding doesn't go past the maximum size either
+		let restrictions = TagRestrictions {
+			size: TagSizeRestrictions::S_32F_4K,
+			..TagRestrictions::default()
+		};
+
+		let tag = restricted_tag(restrictions);
+		let tag_ref = &mut Id3v2TagRef {
+			flags: *tag.flags(),
+			frames: tag.frames.iter().filter_map(Frame::as_opt_ref),
+		};
+		let tag_bytes = super::super::create_tag(tag_ref, WriteOptions::default(), 8192).unwrap();
+		assert_eq!(tag_bytes.len(), 4096);
+	}
+
+	#[test]
+	fn image_size() {
+		let restrictions = TagRestrictions {
+			image_size: Some(ImageSizeRestrictions::P_64),
+			..TagRestrictions::default()
+		};
+
+		let mut tag = restricted_tag(restrictions);
+		tag.insert_picture(png(256, 256, 64));
+
+		assert_eq!(
+			violations(&tag, WriteOptions::default()),
+			[RestrictionViolation::ImageSize {
+				dimensions: Some((256, 256))
+			}]
+		);
+
+		let (_, read_tag) = write_and_read(&tag, WriteOptions::new().fit_id3v2_restrictions(true));
+		assert_eq!(read_tag.len(), 1);
+		assert_eq!(read_tag.title().as_deref(), Some("Foo title"));
+
+		// Pictures that fit are left alone
+		let mut tag = restricted_tag(restrictions);
+		tag.insert_picture(png(64, 32, 64));
+
+		let (_, read_tag) = write_and_read(&tag, WriteOptions::default());
+		assert_eq!(read_tag.len(), 2);
+	}
+
+	#[test]
+	fn every_violation_reported() {
+		let restrictions = TagRestrictions {
+			size: TagSizeRestrictions::S_32F_4K,
+			text_encoding: true,
+			image_encoding: true,
+			..TagRestrictions::default()
+		};
+
+		let mut tag = restricted_tag(restrictions);
+		tag.insert(
+			Frame::new(
+				"TALB",
+				TextInformationFrame {
+					encoding: TextEncoding::UTF16,
+					value: String::from("Foo album"),
+				},
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+		tag.insert_picture(Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Bmp),
+			None,
+			vec![0; 8192],
+		));
+
+		let violations = violations(&tag, WriteOptions::default());
+		assert_eq!(violations.len(), 3, "{violations:?}");
+		assert!(violations.contains(&RestrictionViolation::TextEncoding {
+			frame_id: String::from("TALB"),
+			encoding: TextEncoding::UTF16
+		}));
+		assert!(violations.contains(&RestrictionViolation::ImageEncoding {
+			mime_type: Some(MimeType::Bmp)
+		}));
+		assert!(matches!(
+			violations.last(),
+			Some(RestrictionViolation::TagSize { max_size: 4096, .. })
+		));
+
+		// The text is re-encoded, and the picture removed
+		let (len, read_tag) =
+			write_and_read(&tag, WriteOptions::new().fit_id3v2_restrictions(true));
+		assert!(len <= 4096);
+		assert_eq!(read_tag.len(), 2);
+		assert_eq!(read_tag.album().as_deref(), Some("Foo album"));
+	}
+}
@@ -16,7 +16,6 @@ pub use tag::{AIFFTextChunks, Comment};
 /// An AIFF file
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct AiffFile {
 	/// Any text chunks included in the file
 	#[lofty(tag_type = "AiffText")]
@@ -26,4 +25,7 @@ pub struct AiffFile {
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
 	/// The file's audio properties
 	pub(crate) properties: AiffProperties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
@@ -1,7 +1,8 @@
 use super::read::CompressionPresent;
 use crate::error::Result;
+use crate::iff::SampleFormat;
 use crate::macros::{decode_err, try_vec};
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 use crate::util::text::utf8_decode;
 
 use std::borrow::Cow;
@@ -87,6 +88,33 @@ impl AiffCompressionType {
 	}
 }
 
+impl AiffCompressionType {
+	/// The [`SampleFormat`] for this compression type
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::aiff::AiffCompressionType;
+	/// use lofty::iff::SampleFormat;
+	///
+	/// assert_eq!(AiffCompressionType::fl64.sample_format(), SampleFormat::FloatPcm);
+	/// assert_eq!(AiffCompressionType::alaw.sample_format(), SampleFormat::Compressed);
+	/// ```
+	pub fn sample_format(&self) -> SampleFormat {
+		match self {
+			AiffCompressionType::None | AiffCompressionType::sowt => SampleFormat::IntPcm,
+			AiffCompressionType::fl32 | AiffCompressionType::fl64 | AiffCompressionType::FL32 => {
+				SampleFormat::FloatPcm
+			},
+			// Other PCM variants, which don't have a dedicated `AiffCompressionType`
+			AiffCompressionType::Other {
+				compression_type, ..
+			} if matches!(compression_type, b"twos" | b"in24" | b"in32" | b"raw ") => SampleFormat::IntPcm,
+			_ => SampleFormat::Compressed,
+		}
+	}
+}
+
 /// A AIFF file's audio properties
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 #[non_exhaustive]
@@ -104,12 +132,15 @@ impl From<AiffProperties> for FileProperties {
 	fn from(value: AiffProperties) -> Self {
 		Self {
 			duration: value.duration,
-			overall_bitrate: Some(value.overall_bitrate),
-			audio_bitrate: Some(value.audio_bitrate),
-			sample_rate: Some(value.sample_rate),
-			bit_depth: Some(value.sample_size as u8),
-			channels: Some(value.channels as u8),
+			overall_bitrate: non_zero(value.overall_bitrate),
+			audio_bitrate: non_zero(value.audio_bitrate),
+			sample_rate: non_zero(value.sample_rate),
+			bit_depth: non_zero(value.sample_size as u8),
+			channels: non_zero(value.channels as u8),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(value.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -149,6 +180,15 @@ impl AiffProperties {
 	pub fn compression_type(&self) -> Option<&AiffCompressionType> {
 		self.compression_type.as_ref()
 	}
+
+	/// Sample format, see [`AiffCompressionType::sample_format`]
+	///
+	/// Files without a compression type (plain AIFF) always store integer samples.
+	pub fn sample_format(&self) -> SampleFormat {
+		self.compression_type
+			.as_ref()
+			.map_or(SampleFormat::IntPcm, AiffCompressionType::sample_format)
+	}
 }
 
 pub(super) fn read_properties(
@@ -6,6 +6,7 @@ use crate::id3::v2::tag::Id3v2Tag;
 use crate::iff::chunk::Chunks;
 use crate::macros::{decode_err, err};
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -40,6 +41,8 @@ pub(crate) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<
 where
 	R: Read + Seek,
 {
+	let data = &mut OffsetFile::new(data, parse_options.start_offset)?;
+
 	// TODO: Maybe one day the `Seek` bound can be removed?
 	// let file_size = verify_aiff(data)?;
 	let compression_present = verify_aiff(data)?;
@@ -63,7 +66,7 @@ where
 	while chunks.next(data).is_ok() {
 		match &chunks.fourcc {
 			b"ID3 " | b"id3 " => {
-				let tag = chunks.id3_chunk(data, parse_options.parsing_mode)?;
+				let tag = chunks.id3_chunk(data, parse_options)?;
 				if let Some(existing_tag) = id3v2_tag.as_mut() {
 					// https://github.com/Serial-ATA/lofty-rs/issues/87
 					// Duplicate tags should have their frames appended to the previous
@@ -169,5 +172,7 @@ where
 			_ => Some(text_chunks),
 		},
 		id3v2_tag,
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	})
 }
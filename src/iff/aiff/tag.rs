@@ -4,11 +4,15 @@ use crate::macros::err;
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
 use crate::tag::{Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
 use std::convert::TryFrom;
+#[cfg(feature = "fs")]
 use std::fs::File;
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{SeekFrom, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use byteorder::BigEndian;
@@ -189,7 +193,12 @@ impl TagExt for AIFFTextChunks {
 		)
 	}
 
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
+	#[cfg(feature = "fs")]
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		AiffTextChunksRef {
 			name: self.name.as_deref(),
 			author: self.author.as_deref(),
@@ -197,10 +206,14 @@ impl TagExt for AIFFTextChunks {
 			annotations: self.annotations.as_deref(),
 			comments: self.comments.as_deref(),
 		}
-		.write_to(file)
+		.write_to(file, write_options)
 	}
 
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		AiffTextChunksRef {
 			name: self.name.as_deref(),
 			author: self.author.as_deref(),
@@ -208,13 +221,15 @@ impl TagExt for AIFFTextChunks {
 			annotations: self.annotations.as_deref(),
 			comments: self.comments.as_deref(),
 		}
-		.dump_to(writer)
+		.dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::AiffText.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::AiffText.remove_from(file)
 	}
@@ -309,20 +324,30 @@ where
 	T: AsRef<str>,
 	AI: IntoIterator<Item = T>,
 {
-	pub(crate) fn write_to(self, file: &mut File) -> Result<()> {
-		AiffTextChunksRef::write_to_inner(file, self)
-	}
-
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let temp = Self::create_text_chunks(self)?;
+	pub(crate) fn write_to<F>(self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		AiffTextChunksRef::write_to_inner(file, self, write_options)
+	}
+
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let temp = Self::create_text_chunks(self, write_options)?;
 		writer.write_all(&temp)?;
 
 		Ok(())
 	}
 
-	fn create_text_chunks(tag: &mut AiffTextChunksRef<'_, T, AI>) -> Result<Vec<u8>> {
-		fn write_chunk(writer: &mut Vec<u8>, key: &str, value: Option<&str>) {
-			if let Some(val) = value {
+	fn create_text_chunks(
+		tag: &mut AiffTextChunksRef<'_, T, AI>,
+		write_options: WriteOptions,
+	) -> Result<Vec<u8>> {
+		let write_chunk = |writer: &mut Vec<u8>, key: &str, value: Option<&str>| {
+			if let Some(val) = value.and_then(|value| write_options.text_item(value)) {
 				if let Ok(len) = u32::try_from(val.len()) {
 					writer.extend(key.as_bytes());
 					writer.extend(len.to_be_bytes());
@@ -335,7 +360,7 @@ where
 					}
 				}
 			}
-		}
+		};
 
 		let mut text_chunks = Vec::new();
 
@@ -400,11 +425,18 @@ where
 		Ok(text_chunks)
 	}
 
-	fn write_to_inner(data: &mut File, mut tag: AiffTextChunksRef<'_, T, AI>) -> Result<()> {
+	fn write_to_inner<F>(
+		data: &mut F,
+		mut tag: AiffTextChunksRef<'_, T, AI>,
+		write_options: WriteOptions,
+	) -> Result<()>
+	where
+		F: FileLike,
+	{
 		super::read::verify_aiff(data)?;
-		let file_len = data.metadata()?.len().saturating_sub(12);
+		let file_len = data.len()?.saturating_sub(12);
 
-		let text_chunks = Self::create_text_chunks(&mut tag)?;
+		let text_chunks = Self::create_text_chunks(&mut tag, write_options)?;
 
 		let mut chunks_remove = Vec::new();
 
@@ -440,6 +472,10 @@ where
 			data.read_exact(&mut size)?;
 
 			let comm_end = (20 + u32::from_le_bytes(size)) as usize;
+			if !text_chunks.is_empty() {
+				data.mark_moved(comm_end as u64);
+			}
+
 			file_bytes.splice(comm_end..comm_end, text_chunks);
 		} else {
 			chunks_remove.sort_unstable();
@@ -448,9 +484,14 @@ where
 			let first = chunks_remove.pop().unwrap(); // Infallible
 
 			for (s, e) in &chunks_remove {
+				data.mark_moved(*e as u64);
 				file_bytes.drain(*s..*e);
 			}
 
+			if first.1 - first.0 != text_chunks.len() {
+				data.mark_moved(first.1 as u64);
+			}
+
 			file_bytes.splice(first.0..first.1, text_chunks);
 		}
 
@@ -468,7 +509,7 @@ where
 #[cfg(test)]
 mod tests {
 	use crate::iff::aiff::{AIFFTextChunks, Comment};
-	use crate::{ItemKey, ItemValue, Tag, TagExt, TagItem, TagType};
+	use crate::{ItemKey, ItemValue, Tag, TagExt, TagItem, TagType, WriteOptions};
 
 	use crate::probe::ParseOptions;
 	use std::io::Cursor;
@@ -525,7 +566,9 @@ mod tests {
 		let mut writer = vec![
 			b'F', b'O', b'R', b'M', 0, 0, 0, 0xC6, b'A', b'I', b'F', b'F',
 		];
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let temp_parsed_tag = super::super::read::read_from(
 			&mut Cursor::new(writer),
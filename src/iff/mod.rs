@@ -2,3 +2,22 @@
 pub mod aiff;
 pub(crate) mod chunk;
 pub mod wav;
+
+/// The format of the samples in a WAV or AIFF file
+///
+/// This is derived from [`WavFormat`](wav::WavFormat) and [`AiffCompressionType`](aiff::AiffCompressionType),
+/// for callers that only need to know how the samples are stored.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum SampleFormat {
+	/// Uncompressed integer samples
+	#[default]
+	IntPcm,
+	/// Uncompressed IEEE floating point samples
+	FloatPcm,
+	/// Compressed samples, such as A-law or µ-law
+	///
+	/// See [`WavProperties::format`](wav::WavProperties::format) or [`AiffProperties::compression_type`](aiff::AiffProperties::compression_type)
+	/// for the specific format.
+	Compressed,
+}
@@ -0,0 +1,351 @@
+#[cfg(feature = "fs")]
+use super::read::verify_wav;
+use crate::error::Result;
+#[cfg(feature = "fs")]
+use crate::iff::chunk::Chunks;
+use crate::macros::{decode_err, err};
+use crate::util::text::{truncate_to_fit, TextEncoding};
+
+#[cfg(feature = "fs")]
+use std::fs::File;
+#[cfg(feature = "fs")]
+use std::io::{Read, Seek, Write};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+// Description (256)
+// Originator (32)
+// Originator reference (32)
+// Origination date (10)
+// Origination time (8)
+// Time reference (8)
+// Version (2)
+// UMID (64)
+// Loudness values (10)
+// Reserved (180)
+const FIXED_SIZE: usize = 602;
+
+const UMID_OFFSET: usize = 348;
+const LOUDNESS_OFFSET: usize = 412;
+
+// The loudness values were added in version 2
+const LOUDNESS_VERSION: u16 = 2;
+
+// A loudness value of 0x7FFF means the value isn't set
+const LOUDNESS_NOT_SET: i16 = 0x7FFF;
+
+/// A Broadcast Wave Format `bext` chunk
+///
+/// See [EBU Tech 3285](https://tech.ebu.ch/docs/tech/tech3285.pdf)
+///
+/// The text fields are fixed size ASCII strings, any text exceeding their size will be truncated
+/// when writing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BextChunk {
+	/// A description of the sound sequence (max 256 bytes)
+	pub description: String,
+	/// The name of the originator or producer (max 32 bytes)
+	pub originator: String,
+	/// An unambiguous reference allocated by the originating organisation (max 32 bytes)
+	pub originator_reference: String,
+	/// The date of creation, as `yyyy-mm-dd` (max 10 bytes)
+	pub origination_date: String,
+	/// The time of creation, as `hh:mm:ss` (max 8 bytes)
+	pub origination_time: String,
+	/// The first sample count since midnight
+	pub time_reference: u64,
+	/// The version of the chunk
+	///
+	/// This is bumped to 2 when writing if any loudness values are set.
+	pub version: u16,
+	/// A SMPTE UMID (available since version 1)
+	pub umid: [u8; 64],
+	/// Integrated loudness (LUFS)
+	pub loudness_value: Option<f32>,
+	/// Loudness range (LU)
+	pub loudness_range: Option<f32>,
+	/// Maximum true peak level (dBTP)
+	pub max_true_peak_level: Option<f32>,
+	/// Highest value of the momentary loudness (LUFS)
+	pub max_momentary_loudness: Option<f32>,
+	/// Highest value of the short-term loudness (LUFS)
+	pub max_short_term_loudness: Option<f32>,
+	/// The coding history
+	pub coding_history: String,
+}
+
+impl Default for BextChunk {
+	fn default() -> Self {
+		Self {
+			description: String::new(),
+			originator: String::new(),
+			originator_reference: String::new(),
+			origination_date: String::new(),
+			origination_time: String::new(),
+			time_reference: 0,
+			version: 0,
+			umid: [0; 64],
+			loudness_value: None,
+			loudness_range: None,
+			max_true_peak_level: None,
+			max_momentary_loudness: None,
+			max_short_term_loudness: None,
+			coding_history: String::new(),
+		}
+	}
+}
+
+impl BextChunk {
+	pub(crate) fn parse(content: &[u8]) -> Result<Self> {
+		if content.len() < FIXED_SIZE {
+			decode_err!(@BAIL Wav, "\"bext\" chunk is too small");
+		}
+
+		let version = LittleEndian::read_u16(&content[346..348]);
+
+		let mut umid = [0; 64];
+		umid.copy_from_slice(&content[UMID_OFFSET..UMID_OFFSET + 64]);
+
+		// Before version 2, the loudness values were reserved
+		let loudness = |index: usize| {
+			if version < LOUDNESS_VERSION {
+				return None;
+			}
+
+			let start = LOUDNESS_OFFSET + index * 2;
+			let value = LittleEndian::read_i16(&content[start..start + 2]);
+
+			(value != LOUDNESS_NOT_SET).then(|| f32::from(value) / 100.0)
+		};
+
+		Ok(Self {
+			description: read_string(&content[..256]),
+			originator: read_string(&content[256..288]),
+			originator_reference: read_string(&content[288..320]),
+			origination_date: read_string(&content[320..330]),
+			origination_time: read_string(&content[330..338]),
+			time_reference: LittleEndian::read_u64(&content[338..346]),
+			version,
+			umid,
+			loudness_value: loudness(0),
+			loudness_range: loudness(1),
+			max_true_peak_level: loudness(2),
+			max_momentary_loudness: loudness(3),
+			max_short_term_loudness: loudness(4),
+			coding_history: read_string(&content[FIXED_SIZE..]),
+		})
+	}
+
+	fn loudness_values(&self) -> [Option<f32>; 5] {
+		[
+			self.loudness_value,
+			self.loudness_range,
+			self.max_true_peak_level,
+			self.max_momentary_loudness,
+			self.max_short_term_loudness,
+		]
+	}
+
+	/// Convert the chunk to its content, excluding the chunk header
+	///
+	/// # Errors
+	///
+	/// * The chunk is too large for a RIFF chunk
+	pub fn as_bytes(&self) -> Result<Vec<u8>> {
+		let loudness = self.loudness_values();
+
+		let mut version = self.version;
+		if version < LOUDNESS_VERSION && loudness.iter().any(Option::is_some) {
+			version = LOUDNESS_VERSION;
+		}
+
+		let mut content = vec![0; FIXED_SIZE];
+
+		write_string(&mut content[..256], &self.description);
+		write_string(&mut content[256..288], &self.originator);
+		write_string(&mut content[288..320], &self.originator_reference);
+		write_string(&mut content[320..330], &self.origination_date);
+		write_string(&mut content[330..338], &self.origination_time);
+		LittleEndian::write_u64(&mut content[338..346], self.time_reference);
+		LittleEndian::write_u16(&mut content[346..348], version);
+		content[UMID_OFFSET..UMID_OFFSET + 64].copy_from_slice(&self.umid);
+
+		// Before version 2, the loudness values are reserved, and must be zeroed
+		if version >= LOUDNESS_VERSION {
+			for (index, value) in loudness.into_iter().enumerate() {
+				let start = LOUDNESS_OFFSET + index * 2;
+
+				// The values are stored as hundredths
+				let value = value.map_or(LOUDNESS_NOT_SET, |value| {
+					(value * 100.0)
+						.round()
+						.clamp(f32::from(i16::MIN), f32::from(LOUDNESS_NOT_SET - 1)) as i16
+				});
+
+				LittleEndian::write_i16(&mut content[start..start + 2], value);
+			}
+		}
+
+		content.extend_from_slice(self.coding_history.as_bytes());
+
+		if u32::try_from(content.len()).is_err() {
+			err!(TooMuchData);
+		}
+
+		Ok(content)
+	}
+
+	#[cfg(feature = "fs")]
+	/// Write the chunk to a WAV file
+	///
+	/// This will replace any existing `bext` chunk. Otherwise, the chunk is inserted as the first chunk
+	/// in the file, as recommended by the specification.
+	///
+	/// # Errors
+	///
+	/// * `file` is not a valid WAV file
+	/// * [`BextChunk::as_bytes`]
+	/// * [`std::io::Error`]
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::iff::wav::{BextChunk, WavFile};
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut file = std::fs::OpenOptions::new().read(true).write(true).open("foo.wav")?;
+	/// let wav_file = WavFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// let mut bext = wav_file.bext().cloned().unwrap_or_default();
+	/// bext.loudness_value = Some(-23.0);
+	/// bext.max_true_peak_level = Some(-1.0);
+	///
+	/// bext.write_to(&mut file)?;
+	/// # Ok(()) }
+	/// ```
+	pub fn write_to(&self, file: &mut File) -> Result<()> {
+		let content = self.as_bytes()?;
+
+		file.rewind()?;
+		verify_wav(file)?;
+
+		let file_len = file.metadata()?.len();
+
+		// Default to the start of the first chunk, after "RIFF....WAVE"
+		let mut replace_range = 12..12;
+
+		let mut chunks = Chunks::<LittleEndian>::new(file_len - 12);
+		while chunks.next(file).is_ok() {
+			if &chunks.fourcc == b"bext" {
+				let start = file.stream_position()? - 8;
+				let end = start + 8 + u64::from(chunks.size) + u64::from(chunks.size % 2);
+				replace_range = start as usize..end.min(file_len) as usize;
+				break;
+			}
+
+			chunks.skip(file)?;
+		}
+
+		let mut chunk = Vec::with_capacity(content.len() + 9);
+		chunk.extend_from_slice(b"bext");
+		chunk.extend_from_slice(&(content.len() as u32).to_le_bytes());
+		chunk.extend_from_slice(&content);
+
+		// It is required an odd length chunk be padded with a 0
+		if content.len() % 2 != 0 {
+			chunk.push(0);
+		}
+
+		file.rewind()?;
+
+		let mut file_bytes = Vec::new();
+		file.read_to_end(&mut file_bytes)?;
+
+		file_bytes.splice(replace_range, chunk);
+
+		let Ok(total_size) = u32::try_from(file_bytes.len() - 8) else {
+			err!(TooMuchData);
+		};
+		file_bytes.splice(4..8, total_size.to_le_bytes());
+
+		file.rewind()?;
+		file.set_len(0)?;
+		file.write_all(&file_bytes)?;
+
+		Ok(())
+	}
+}
+
+// The text fields are null padded, though not necessarily null terminated if they fill the field
+fn read_string(field: &[u8]) -> String {
+	let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+	String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn write_string(field: &mut [u8], value: &str) {
+	let value = truncate_to_fit(value, field.len(), TextEncoding::UTF8);
+	field[..value.len()].copy_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{BextChunk, FIXED_SIZE, LOUDNESS_OFFSET};
+
+	use byteorder::{ByteOrder, LittleEndian};
+
+	#[test]
+	fn loudness_round_trip() {
+		let mut bext = BextChunk {
+			description: String::from("Foo description"),
+			coding_history: String::from("A=PCM,F=48000,W=24,M=stereo\r\n"),
+			..BextChunk::default()
+		};
+
+		// No loudness values, the version stays at 0 and the fields remain reserved
+		let content = bext.as_bytes().unwrap();
+		assert_eq!(content.len(), FIXED_SIZE + bext.coding_history.len());
+		assert_eq!(LittleEndian::read_u16(&content[346..348]), 0);
+		assert!(content[LOUDNESS_OFFSET..FIXED_SIZE].iter().all(|b| *b == 0));
+		assert_eq!(BextChunk::parse(&content).unwrap(), bext);
+
+		bext.loudness_value = Some(-23.0);
+		bext.max_true_peak_level = Some(-1.5);
+		bext.max_short_term_loudness = Some(-18.27);
+
+		let content = bext.as_bytes().unwrap();
+		assert_eq!(LittleEndian::read_u16(&content[346..348]), 2);
+		assert_eq!(
+			LittleEndian::read_i16(&content[LOUDNESS_OFFSET..LOUDNESS_OFFSET + 2]),
+			-2300
+		);
+		// Unset values are stored as 0x7FFF
+		assert_eq!(
+			LittleEndian::read_i16(&content[LOUDNESS_OFFSET + 2..LOUDNESS_OFFSET + 4]),
+			0x7FFF
+		);
+
+		let parsed = BextChunk::parse(&content).unwrap();
+		assert_eq!(parsed.version, 2);
+		assert_eq!(parsed.loudness_value, Some(-23.0));
+		assert_eq!(parsed.loudness_range, None);
+		assert_eq!(parsed.max_true_peak_level, Some(-1.5));
+		assert_eq!(parsed.max_momentary_loudness, None);
+		assert_eq!(parsed.max_short_term_loudness, Some(-18.27));
+		assert_eq!(parsed.description, "Foo description");
+		assert_eq!(parsed.coding_history, bext.coding_history);
+	}
+
+	#[test]
+	fn loudness_ignored_before_v2() {
+		let mut content = BextChunk::default().as_bytes().unwrap();
+
+		// Version 1, with garbage in the reserved loudness fields
+		LittleEndian::write_u16(&mut content[346..348], 1);
+		LittleEndian::write_i16(&mut content[LOUDNESS_OFFSET..LOUDNESS_OFFSET + 2], -2300);
+
+		let parsed = BextChunk::parse(&content).unwrap();
+		assert_eq!(parsed.version, 1);
+		assert_eq!(parsed.loudness_value, None);
+	}
+}
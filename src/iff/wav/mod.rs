@@ -1,5 +1,6 @@
 //! WAV specific items
 
+mod bext;
 mod properties;
 mod read;
 pub(crate) mod tag;
@@ -10,12 +11,15 @@ use lofty_attr::LoftyFile;
 
 // Exports
 pub use crate::iff::wav::properties::{WavFormat, WavProperties};
+pub use bext::BextChunk;
 pub use tag::RIFFInfoList;
 
 /// A WAV file
+///
+/// If the file has multiple `fmt ` chunks, the properties are read from the first valid one. The others
+/// are ignored, and are left untouched when writing tags to the file.
 #[derive(LoftyFile)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct WavFile {
 	/// A RIFF INFO LIST
 	#[lofty(tag_type = "RiffInfo")]
@@ -25,4 +29,85 @@ pub struct WavFile {
 	pub(crate) id3v2_tag: Option<Id3v2Tag>,
 	/// The file's audio properties
 	pub(crate) properties: WavProperties,
+	/// Any `LIST` chunks that aren't RIFF INFO lists
+	pub(crate) list_chunks: Vec<ListChunk>,
+	/// The Broadcast Wave Format `bext` chunk
+	pub(crate) bext: Option<BextChunk>,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
+}
+
+impl WavFile {
+	/// The `LIST` chunks in the file that aren't RIFF INFO lists
+	///
+	/// These lists (such as `LIST wavl` or `LIST adtl`) aren't parsed, and are left untouched when writing
+	/// tags to the file. They are listed in the order they appear in the file.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::iff::wav::WavFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut wav_file_reader = std::fs::File::open("foo.wav")?;
+	/// let wav_file = WavFile::read_from(&mut wav_file_reader, ParseOptions::new())?;
+	///
+	/// for list in wav_file.list_chunks() {
+	/// 	println!(
+	/// 		"Found a \"{}\" list ({} bytes)",
+	/// 		String::from_utf8_lossy(&list.list_type()),
+	/// 		list.size()
+	/// 	);
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn list_chunks(&self) -> &[ListChunk] {
+		&self.list_chunks
+	}
+
+	/// The Broadcast Wave Format `bext` chunk, if it exists
+	///
+	/// This is left untouched when writing tags to the file, see [`BextChunk::write_to`] to change it.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::iff::wav::WavFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::error::Result<()> {
+	/// # let mut wav_file_reader = std::fs::File::open("foo.wav")?;
+	/// let wav_file = WavFile::read_from(&mut wav_file_reader, ParseOptions::new())?;
+	///
+	/// if let Some(loudness) = wav_file.bext().and_then(|bext| bext.loudness_value) {
+	/// 	println!("Integrated loudness: {loudness} LUFS");
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn bext(&self) -> Option<&BextChunk> {
+		self.bext.as_ref()
+	}
+}
+
+/// A `LIST` chunk that isn't a RIFF INFO list
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ListChunk {
+	pub(crate) list_type: [u8; 4],
+	pub(crate) size: u32,
+}
+
+impl ListChunk {
+	/// The list type (e.g. `wavl`)
+	pub fn list_type(&self) -> [u8; 4] {
+		self.list_type
+	}
+
+	/// The size of the chunk, as stored in its header
+	///
+	/// This includes the 4 byte list type.
+	pub fn size(&self) -> u32 {
+		self.size
+	}
 }
@@ -1,22 +1,31 @@
 use crate::error::Result;
+use crate::iff::SampleFormat;
 use crate::macros::decode_err;
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 use crate::ChannelMask;
 
 use std::time::Duration;
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 
 const PCM: u16 = 0x0001;
 const IEEE_FLOAT: u16 = 0x0003;
+const FLAC: u16 = 0xF1AC;
 const EXTENSIBLE: u16 = 0xFFFE;
 
+// The "fLaC" marker, followed by the STREAMINFO block header (4) and the block itself (34)
+pub(super) const FLAC_STREAM_INFO_LEN: usize = 42;
+
 /// A WAV file's format
 #[allow(missing_docs, non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum WavFormat {
 	PCM,
 	IEEE_FLOAT,
+	/// FLAC frames stored in the "data" chunk (`0xF1AC`)
+	///
+	/// The properties are read from the FLAC `STREAMINFO` block at the start of the chunk.
+	Flac,
 	Other(u16),
 }
 
@@ -26,6 +35,26 @@ impl Default for WavFormat {
 	}
 }
 
+impl WavFormat {
+	/// The [`SampleFormat`] for this format
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::iff::wav::WavFormat;
+	/// use lofty::iff::SampleFormat;
+	///
+	/// assert_eq!(WavFormat::IEEE_FLOAT.sample_format(), SampleFormat::FloatPcm);
+	/// ```
+	pub fn sample_format(&self) -> SampleFormat {
+		match self {
+			WavFormat::PCM => SampleFormat::IntPcm,
+			WavFormat::IEEE_FLOAT => SampleFormat::FloatPcm,
+			WavFormat::Flac | WavFormat::Other(_) => SampleFormat::Compressed,
+		}
+	}
+}
+
 /// A WAV file's audio properties
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
 #[non_exhaustive]
@@ -36,8 +65,11 @@ pub struct WavProperties {
 	pub(crate) audio_bitrate: u32,
 	pub(crate) sample_rate: u32,
 	pub(crate) bit_depth: u8,
+	pub(crate) container_bit_depth: u8,
+	pub(crate) valid_bit_depth: Option<u8>,
 	pub(crate) channels: u8,
 	pub(crate) channel_mask: Option<ChannelMask>,
+	pub(crate) audio_bitrate_source: BitrateSource,
 }
 
 impl From<WavProperties> for FileProperties {
@@ -51,15 +83,21 @@ impl From<WavProperties> for FileProperties {
 			channels,
 			channel_mask,
 			format: _,
+			container_bit_depth: _,
+			valid_bit_depth: _,
+			audio_bitrate_source,
 		} = input;
 		Self {
 			duration,
-			overall_bitrate: Some(overall_bitrate),
-			audio_bitrate: Some(audio_bitrate),
-			sample_rate: Some(sample_rate),
-			bit_depth: Some(bit_depth),
-			channels: Some(channels),
+			overall_bitrate: non_zero(overall_bitrate),
+			audio_bitrate: non_zero(audio_bitrate),
+			sample_rate: non_zero(sample_rate),
+			bit_depth: non_zero(bit_depth),
+			channels: non_zero(channels),
 			channel_mask,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(audio_bitrate, audio_bitrate_source),
 		}
 	}
 }
@@ -86,10 +124,29 @@ impl WavProperties {
 	}
 
 	/// Bits per sample
+	///
+	/// This is the number of bits actually used by each sample. For `WAVE_FORMAT_EXTENSIBLE` files,
+	/// this is the valid bits per sample when present, which may be less than the
+	/// [container size](Self::container_bit_depth) (e.g. 24-bit samples stored in 32 bits).
 	pub fn bit_depth(&self) -> u8 {
 		self.bit_depth
 	}
 
+	/// Bits per sample of the sample containers
+	///
+	/// This is the `wBitsPerSample` field of the `fmt ` chunk, the space each sample occupies in
+	/// the stream. It will only differ from [`Self::bit_depth`] when the samples are padded.
+	pub fn container_bit_depth(&self) -> u8 {
+		self.container_bit_depth
+	}
+
+	/// Valid bits per sample
+	///
+	/// This is only available for `WAVE_FORMAT_EXTENSIBLE` files, and will be `None` if the field is zero.
+	pub fn valid_bit_depth(&self) -> Option<u8> {
+		self.valid_bit_depth
+	}
+
 	/// Channel count
 	pub fn channels(&self) -> u8 {
 		self.channels
@@ -104,6 +161,32 @@ impl WavProperties {
 	pub fn format(&self) -> &WavFormat {
 		&self.format
 	}
+
+	/// Sample format, see [`WavFormat::sample_format`]
+	pub fn sample_format(&self) -> SampleFormat {
+		self.format.sample_format()
+	}
+}
+
+// Whether a "fmt " chunk is complete, and describes at least one channel
+pub(super) fn is_valid_fmt(fmt: &[u8]) -> bool {
+	if fmt.len() < 16 || LittleEndian::read_u16(&fmt[2..4]) == 0 {
+		return false;
+	}
+
+	LittleEndian::read_u16(&fmt[..2]) != EXTENSIBLE || fmt.len() >= 40
+}
+
+// Whether a "fmt " chunk describes FLAC data, in which case the "data" chunk starts with a STREAMINFO block
+pub(super) fn is_flac_fmt(fmt: &[u8]) -> bool {
+	let format_tag = match fmt.get(..2).map(LittleEndian::read_u16) {
+		// The format tag is the start of the sub-format GUID
+		Some(EXTENSIBLE) if fmt.len() >= 26 => LittleEndian::read_u16(&fmt[24..26]),
+		Some(format_tag) => format_tag,
+		None => return false,
+	};
+
+	format_tag == FLAC
 }
 
 pub(super) fn read_properties(
@@ -111,6 +194,7 @@ pub(super) fn read_properties(
 	mut total_samples: u32,
 	stream_len: u32,
 	file_length: u64,
+	flac_stream_info: Option<&[u8]>,
 ) -> Result<WavProperties> {
 	let mut format_tag = fmt.read_u16::<LittleEndian>()?;
 	let channels = fmt.read_u16::<LittleEndian>()? as u8;
@@ -127,13 +211,14 @@ pub(super) fn read_properties(
 	let bits_per_sample = fmt.read_u16::<LittleEndian>()?;
 	let bytes_per_sample = block_align / u16::from(channels);
 
-	let mut bit_depth = if bits_per_sample > 0 {
+	let container_bit_depth = if bits_per_sample > 0 {
 		bits_per_sample as u8
 	} else {
 		(bytes_per_sample * 8) as u8
 	};
 
 	let channel_mask;
+	let mut valid_bit_depth = None;
 	if format_tag == EXTENSIBLE {
 		if fmt.len() + 16 < 40 {
 			decode_err!(@BAIL Wav, "Extensible format identified, invalid \"fmt \" chunk size found (< 40)");
@@ -147,13 +232,23 @@ pub(super) fn read_properties(
 		channel_mask = Some(ChannelMask(fmt.read_u32::<LittleEndian>()?));
 
 		if valid_bits_per_sample > 0 {
-			bit_depth = valid_bits_per_sample as u8;
+			valid_bit_depth = Some(valid_bits_per_sample as u8);
 		}
 		format_tag = fmt.read_u16::<LittleEndian>()?;
 	} else {
 		channel_mask = None;
 	}
 
+	let bit_depth = valid_bit_depth.unwrap_or(container_bit_depth);
+
+	if format_tag == FLAC {
+		let Some(stream_info) = flac_stream_info else {
+			decode_err!(@BAIL Wav, "FLAC format identified, no STREAMINFO block found in the \"data\" chunk");
+		};
+
+		return read_flac_properties(stream_info, channel_mask, stream_len, file_length);
+	}
+
 	let non_pcm = format_tag != PCM && format_tag != IEEE_FLOAT;
 
 	if non_pcm && total_samples == 0 {
@@ -166,6 +261,7 @@ pub(super) fn read_properties(
 		total_samples = 0
 	}
 
+	let mut audio_bitrate_source = BitrateSource::Computed;
 	let (duration, overall_bitrate, audio_bitrate) = if sample_rate > 0 && total_samples > 0 {
 		let length = (u64::from(total_samples) * 1000) / u64::from(sample_rate);
 		if length == 0 {
@@ -187,6 +283,7 @@ pub(super) fn read_properties(
 		} else {
 			let overall_bitrate = ((file_length * 8) / length) as u32;
 			let audio_bitrate = (bytes_per_second * 8) / 1000;
+			audio_bitrate_source = BitrateSource::Declared;
 
 			(
 				Duration::from_millis(length),
@@ -202,6 +299,7 @@ pub(super) fn read_properties(
 		format: match format_tag {
 			PCM => WavFormat::PCM,
 			IEEE_FLOAT => WavFormat::IEEE_FLOAT,
+			FLAC => WavFormat::Flac,
 			other => WavFormat::Other(other),
 		},
 		duration,
@@ -209,7 +307,45 @@ pub(super) fn read_properties(
 		audio_bitrate,
 		sample_rate,
 		bit_depth,
+		container_bit_depth,
+		valid_bit_depth,
 		channels,
 		channel_mask,
+		audio_bitrate_source,
+	})
+}
+
+// The "fmt " chunk of a FLAC file is only a rough description, the STREAMINFO block is used instead
+fn read_flac_properties(
+	stream_info: &[u8],
+	channel_mask: Option<ChannelMask>,
+	stream_len: u32,
+	file_length: u64,
+) -> Result<WavProperties> {
+	if stream_info.len() < FLAC_STREAM_INFO_LEN
+		|| &stream_info[..4] != b"fLaC"
+		|| stream_info[4] & 0x7F != 0
+	{
+		decode_err!(@BAIL Wav, "FLAC format identified, no STREAMINFO block found in the \"data\" chunk");
+	}
+
+	let properties = crate::flac::properties::read_properties(
+		&mut &stream_info[8..],
+		u64::from(stream_len),
+		file_length,
+	)?;
+
+	Ok(WavProperties {
+		format: WavFormat::Flac,
+		duration: properties.duration,
+		overall_bitrate: properties.overall_bitrate,
+		audio_bitrate: properties.audio_bitrate,
+		sample_rate: properties.sample_rate,
+		bit_depth: properties.bit_depth,
+		container_bit_depth: properties.bit_depth,
+		valid_bit_depth: None,
+		channels: properties.channels,
+		channel_mask,
+		audio_bitrate_source: BitrateSource::Computed,
 	})
 }
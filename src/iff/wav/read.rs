@@ -1,11 +1,12 @@
-use super::properties::WavProperties;
+use super::properties::{is_flac_fmt, is_valid_fmt, WavProperties, FLAC_STREAM_INFO_LEN};
 use super::tag::RIFFInfoList;
-use super::WavFile;
+use super::{BextChunk, ListChunk, WavFile};
 use crate::error::Result;
 use crate::id3::v2::tag::Id3v2Tag;
 use crate::iff::chunk::Chunks;
 use crate::macros::decode_err;
-use crate::probe::ParseOptions;
+use crate::probe::{ParseOptions, ParsingMode};
+use crate::util::io::{read_up_to, OffsetFile};
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -33,6 +34,8 @@ pub(super) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<
 where
 	R: Read + Seek,
 {
+	let data = &mut OffsetFile::new(data, parse_options.start_offset)?;
+
 	verify_wav(data)?;
 
 	let current_pos = data.stream_position()?;
@@ -43,19 +46,32 @@ where
 	let mut stream_len = 0_u32;
 	let mut total_samples = 0_u32;
 	let mut fmt = Vec::new();
+	let mut flac_stream_info = None;
 
 	let mut riff_info = RIFFInfoList::default();
 	let mut id3v2_tag: Option<Id3v2Tag> = None;
+	let mut list_chunks = Vec::new();
+	let mut bext = None;
 
 	let mut chunks = Chunks::<LittleEndian>::new(file_len);
 
 	while chunks.next(data).is_ok() {
 		match &chunks.fourcc {
+			// The first valid "fmt " chunk is used, any others are likely left over from an edit
 			b"fmt " if parse_options.read_properties => {
-				if fmt.is_empty() {
-					fmt = chunks.content(data)?;
-				} else {
+				if !fmt.is_empty() {
+					log::warn!("Ignoring an additional \"fmt \" chunk");
 					chunks.skip(data)?;
+					continue;
+				}
+
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				if is_valid_fmt(&content) {
+					fmt = content;
+				} else {
+					log::warn!("Skipping invalid \"fmt \" chunk");
 				}
 			},
 			b"fact" if parse_options.read_properties => {
@@ -67,7 +83,20 @@ where
 			},
 			b"data" if parse_options.read_properties => {
 				if stream_len == 0 {
-					stream_len += chunks.size
+					stream_len += chunks.size;
+
+					// FLAC data starts with a STREAMINFO block, which has the actual properties
+					if fmt.is_empty() || is_flac_fmt(&fmt) {
+						let mut stream_info = vec![0; FLAC_STREAM_INFO_LEN];
+						let len = read_up_to(
+							&mut data.by_ref().take(u64::from(chunks.size)),
+							&mut stream_info,
+						)?;
+						data.seek(SeekFrom::Current(-(len as i64)))?;
+
+						stream_info.truncate(len);
+						flac_stream_info = Some(stream_info);
+					}
 				}
 
 				chunks.skip(data)?;
@@ -82,13 +111,28 @@ where
 						super::tag::read::parse_riff_info(data, &mut chunks, end, &mut riff_info)?;
 					},
 					_ => {
+						list_chunks.push(ListChunk {
+							list_type,
+							size: chunks.size,
+						});
+
 						data.seek(SeekFrom::Current(-4))?;
 						chunks.skip(data)?;
 					},
 				}
 			},
+			b"bext" if bext.is_none() => {
+				let content = chunks.content(data)?;
+				chunks.correct_position(data)?;
+
+				match BextChunk::parse(&content) {
+					Ok(chunk) => bext = Some(chunk),
+					Err(e) if parse_options.parsing_mode == ParsingMode::Strict => return Err(e),
+					Err(_) => log::warn!("Skipping invalid \"bext\" chunk"),
+				}
+			},
 			b"ID3 " | b"id3 " => {
-				let tag = chunks.id3_chunk(data, parse_options.parsing_mode)?;
+				let tag = chunks.id3_chunk(data, parse_options)?;
 				if let Some(existing_tag) = id3v2_tag.as_mut() {
 					// https://github.com/Serial-ATA/lofty-rs/issues/87
 					// Duplicate tags should have their frames appended to the previous
@@ -104,7 +148,7 @@ where
 	}
 
 	let properties = if parse_options.read_properties {
-		if fmt.len() < 16 {
+		if fmt.is_empty() {
 			decode_err!(@BAIL Wav, "File does not contain a valid \"fmt \" chunk");
 		}
 
@@ -114,7 +158,13 @@ where
 
 		let file_length = data.stream_position()?;
 
-		super::properties::read_properties(&mut &*fmt, total_samples, stream_len, file_length)?
+		super::properties::read_properties(
+			&mut &*fmt,
+			total_samples,
+			stream_len,
+			file_length,
+			flac_stream_info.as_deref(),
+		)?
 	} else {
 		WavProperties::default()
 	};
@@ -123,5 +173,9 @@ where
 		properties,
 		riff_info_tag: (!riff_info.items.is_empty()).then_some(riff_info),
 		id3v2_tag,
+		list_chunks,
+		bext,
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	})
 }
@@ -1,14 +1,19 @@
-pub(super) mod read;
+pub(crate) mod read;
 mod write;
 
 use crate::error::{LoftyError, Result};
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::rating::convert_rating_items;
 use crate::tag::{try_parse_year, Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::Write;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
@@ -203,20 +208,31 @@ impl TagExt for RIFFInfoList {
 		self.items.is_empty()
 	}
 
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
+	#[cfg(feature = "fs")]
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		RIFFInfoListRef::new(self.items.iter().map(|(k, v)| (k.as_str(), v.as_str())))
-			.write_to(file)
+			.write_to(file, write_options)
 	}
 
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		RIFFInfoListRef::new(self.items.iter().map(|(k, v)| (k.as_str(), v.as_str())))
-			.dump_to(writer)
+			.dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::RiffInfo.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::RiffInfo.remove_from(file)
 	}
@@ -263,9 +279,11 @@ impl From<RIFFInfoList> for Tag {
 }
 
 impl From<Tag> for RIFFInfoList {
-	fn from(input: Tag) -> Self {
+	fn from(mut input: Tag) -> Self {
 		let mut riff_info = RIFFInfoList::default();
 
+		convert_rating_items(&mut input.items, TagType::RiffInfo);
+
 		for item in input.items {
 			if let ItemValue::Text(val) | ItemValue::Locator(val) = item.item_value {
 				match item.item_key {
@@ -302,13 +320,20 @@ where
 		RIFFInfoListRef { items }
 	}
 
-	pub(crate) fn write_to(&mut self, file: &mut File) -> Result<()> {
-		write::write_riff_info(file, self)
+	pub(crate) fn write_to<F>(&mut self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		write::write_riff_info(file, self, write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
 		let mut temp = Vec::new();
-		write::create_riff_info(&mut self.items, &mut temp)?;
+		write::create_riff_info(&mut self.items, &mut temp, write_options)?;
 
 		writer.write_all(&temp)?;
 
@@ -336,7 +361,7 @@ pub(crate) fn tagitems_into_riff<'a>(
 #[cfg(test)]
 mod tests {
 	use crate::iff::wav::RIFFInfoList;
-	use crate::{Tag, TagExt, TagType};
+	use crate::{Tag, TagExt, TagType, WriteOptions};
 
 	use crate::iff::chunk::Chunks;
 	use byteorder::LittleEndian;
@@ -381,7 +406,9 @@ mod tests {
 		.unwrap();
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let mut temp_parsed_tag = RIFFInfoList::default();
 
@@ -4,10 +4,30 @@ use crate::iff::chunk::Chunks;
 use crate::macros::decode_err;
 use crate::util::text::utf8_decode_str;
 
-use std::io::{Read, Seek};
+use std::io::{Cursor, Read, Seek};
 
 use byteorder::LittleEndian;
 
+// An entire `LIST` chunk, as written by `RIFFInfoList::dump_to`
+pub(crate) fn parse_riff_info_list(bytes: &[u8]) -> Result<RIFFInfoList> {
+	let Some(content) = bytes
+		.get(12..)
+		.filter(|_| bytes.get(8..12) == Some(b"INFO"))
+	else {
+		decode_err!(@BAIL Wav, "Expected a RIFF INFO list");
+	};
+
+	let mut tag = RIFFInfoList::default();
+	parse_riff_info(
+		&mut Cursor::new(content),
+		&mut Chunks::<LittleEndian>::new(content.len() as u64),
+		content.len() as u64,
+		&mut tag,
+	)?;
+
+	Ok(tag)
+}
+
 pub(in crate::iff::wav) fn parse_riff_info<R>(
 	data: &mut R,
 	chunks: &mut Chunks<LittleEndian>,
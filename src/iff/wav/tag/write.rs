@@ -1,90 +1,137 @@
 use super::RIFFInfoListRef;
 use crate::error::Result;
-use crate::iff::chunk::Chunks;
 use crate::iff::wav::read::verify_wav;
 use crate::macros::err;
+use crate::util::io::FileLike;
+use crate::write_options::{RiffInfoPlacement, WriteOptions};
 
-use std::fs::File;
-use std::io::{Read, Seek, SeekFrom, Write};
+use byteorder::{ByteOrder, LittleEndian};
 
-use byteorder::{LittleEndian, WriteBytesExt};
-
-pub(in crate::iff::wav) fn write_riff_info<'a, I>(
-	data: &mut File,
+pub(in crate::iff::wav) fn write_riff_info<'a, F, I>(
+	data: &mut F,
 	tag: &mut RIFFInfoListRef<'a, I>,
+	write_options: WriteOptions,
 ) -> Result<()>
 where
+	F: FileLike,
 	I: Iterator<Item = (&'a str, &'a str)>,
 {
 	verify_wav(data)?;
-	let file_len = data.metadata()?.len().saturating_sub(12);
 
 	let mut riff_info_bytes = Vec::new();
-	create_riff_info(&mut tag.items, &mut riff_info_bytes)?;
-
-	if let Some(info_list_size) = find_info_list(data, file_len)? {
-		let info_list_start = data.seek(SeekFrom::Current(-12))? as usize;
-		let info_list_end = info_list_start + 8 + info_list_size as usize;
+	create_riff_info(&mut tag.items, &mut riff_info_bytes, write_options)?;
 
-		data.rewind()?;
+	data.rewind()?;
 
-		let mut file_bytes = Vec::new();
-		data.read_to_end(&mut file_bytes)?;
+	let mut file_bytes = Vec::new();
+	data.read_to_end(&mut file_bytes)?;
 
-		let _ = file_bytes.splice(info_list_start..info_list_end, riff_info_bytes);
+	let chunks = top_level_chunks(&file_bytes);
 
-		let total_size = (file_bytes.len() - 8) as u32;
-		let _ = file_bytes.splice(4..8, total_size.to_le_bytes());
+	if let Some(info_list) = chunks.iter().find(|chunk| {
+		&chunk.fourcc == b"LIST" && file_bytes[chunk.start + 8..].starts_with(b"INFO")
+	}) {
+		let info_list_end = info_list.end.min(file_bytes.len());
+		if info_list_end - info_list.start != riff_info_bytes.len() {
+			data.mark_moved(info_list_end as u64);
+		}
 
-		data.rewind()?;
-		data.set_len(0)?;
-		data.write_all(&file_bytes)?;
+		let _ = file_bytes.splice(info_list.start..info_list_end, riff_info_bytes);
+	} else if riff_info_bytes.is_empty() {
+		// Nothing to write or remove
+		return Ok(());
 	} else {
-		data.seek(SeekFrom::End(0))?;
-
-		data.write_all(&riff_info_bytes)?;
+		let preceding_chunk = match write_options.riff_info_placement {
+			RiffInfoPlacement::AfterData => chunks
+				.iter()
+				.find(|chunk| &chunk.fourcc == b"data")
+				.or(chunks.last()),
+			RiffInfoPlacement::EndOfFile => chunks.last(),
+		};
+
+		// A truncated chunk extends to the end of the file, so nothing can follow it
+		let insert_pos = preceding_chunk.map_or(12, |chunk| {
+			if chunk.truncated {
+				chunk.start
+			} else {
+				chunk.end
+			}
+		});
 
-		let len = (data.stream_position()? - 8) as u32;
+		// A chunk with an odd size at the end of the file may be missing its padding byte
+		if insert_pos > file_bytes.len() {
+			file_bytes.resize(insert_pos, 0);
+		}
 
-		data.seek(SeekFrom::Start(4))?;
-		data.write_u32::<LittleEndian>(len)?;
+		data.mark_moved(insert_pos as u64);
+		let _ = file_bytes.splice(insert_pos..insert_pos, riff_info_bytes);
 	}
 
+	// The stored RIFF size can't be trusted, it's recalculated from the chunks that are actually present
+	let riff_end = top_level_chunks(&file_bytes)
+		.last()
+		.map_or(12, |chunk| chunk.end.min(file_bytes.len()));
+
+	let Ok(riff_size) = u32::try_from(riff_end - 8) else {
+		err!(TooMuchData);
+	};
+
+	let _ = file_bytes.splice(4..8, riff_size.to_le_bytes());
+
+	data.rewind()?;
+	data.set_len(0)?;
+	data.write_all(&file_bytes)?;
+
 	Ok(())
 }
 
-fn find_info_list<R>(data: &mut R, file_size: u64) -> Result<Option<u32>>
-where
-	R: Read + Seek,
-{
-	let mut info = None;
+struct ChunkBounds {
+	fourcc: [u8; 4],
+	start: usize,
+	// The end of the chunk, including the padding byte for odd sized chunks
+	end: usize,
+	truncated: bool,
+}
 
-	let mut chunks = Chunks::<LittleEndian>::new(file_size);
+// Walks the chunks following the RIFF header, ignoring the RIFF size
+fn top_level_chunks(file_bytes: &[u8]) -> Vec<ChunkBounds> {
+	let mut chunks = Vec::new();
 
-	while chunks.next(data).is_ok() {
-		if &chunks.fourcc == b"LIST" {
-			let mut list_type = [0; 4];
-			data.read_exact(&mut list_type)?;
+	let mut pos = 12;
+	while pos + 8 <= file_bytes.len() {
+		let mut fourcc = [0; 4];
+		fourcc.copy_from_slice(&file_bytes[pos..pos + 4]);
 
-			if &list_type == b"INFO" {
-				info = Some(chunks.size);
-				break;
-			}
+		let size = LittleEndian::read_u32(&file_bytes[pos + 4..pos + 8]) as usize;
+		let mut end = pos + 8 + size + (size % 2);
 
-			data.seek(SeekFrom::Current(-8))?;
+		// Only a missing padding byte is tolerated, otherwise the chunk is cut off at the end of the file
+		let truncated = end - (size % 2) > file_bytes.len();
+		if truncated {
+			end = file_bytes.len();
 		}
 
-		chunks.skip(data)?;
+		chunks.push(ChunkBounds {
+			fourcc,
+			start: pos,
+			end,
+			truncated,
+		});
+
+		pos = end;
 	}
 
-	Ok(info)
+	chunks
 }
 
 pub(super) fn create_riff_info(
 	items: &mut dyn Iterator<Item = (&str, &str)>,
 	bytes: &mut Vec<u8>,
+	write_options: WriteOptions,
 ) -> Result<()> {
-	let mut items = items.peekable();
+	let mut items = items
+		.filter_map(|(k, v)| write_options.text_item(v).map(|v| (k, v)))
+		.peekable();
 
 	if items.peek().is_none() {
 		return Ok(());
@@ -94,10 +141,6 @@ pub(super) fn create_riff_info(
 	bytes.extend(b"INFO");
 
 	for (k, v) in items {
-		if v.is_empty() {
-			continue;
-		}
-
 		let val_b = v.as_bytes();
 		// Account for null terminator
 		let len = val_b.len() + 1;
@@ -17,6 +17,7 @@
 //!
 //! ```rust,no_run
 //! # use lofty::LoftyError;
+//! # #[cfg(feature = "fs")]
 //! # fn main() -> Result<(), LoftyError> {
 //! use lofty::{read_from_path, Probe};
 //!
@@ -30,12 +31,15 @@
 //! let tagged_file2 = Probe::open(path)?.guess_file_type()?.read()?;
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "fs"))]
+//! # fn main() {}
 //! ```
 //!
 //! ### Using an existing reader
 //!
 //! ```rust,no_run
 //! # use lofty::LoftyError;
+//! # #[cfg(feature = "fs")]
 //! # fn main() -> Result<(), LoftyError> {
 //! use lofty::{read_from, ParseOptions};
 //! use std::fs::File;
@@ -48,12 +52,15 @@
 //! let tagged_file = read_from(&mut file)?;
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "fs"))]
+//! # fn main() {}
 //! ```
 //!
 //! ### Accessing tags
 //!
 //! ```rust,no_run
 //! # use lofty::LoftyError;
+//! # #[cfg(feature = "fs")]
 //! # fn main() -> Result<(), LoftyError> {
 //! use lofty::{read_from_path, ParseOptions, TaggedFileExt};
 //!
@@ -68,6 +75,8 @@
 //! let unknown_first_tag = tagged_file.first_tag();
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "fs"))]
+//! # fn main() {}
 //! ```
 //!
 //! ## Using concrete file types
@@ -152,10 +161,28 @@
 extern crate self as lofty;
 pub(crate) mod _this_is_internal {}
 
+// Used by `LoftyFile` for the parts of `AudioFile` that require the "fs" feature
+#[cfg(feature = "fs")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fs_only {
+	($($item:item)*) => { $($item)* };
+}
+
+#[cfg(not(feature = "fs"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fs_only {
+	($($item:item)*) => {};
+}
+
 pub mod aac;
 pub mod ape;
+mod archive;
 pub mod error;
 pub(crate) mod file;
+#[cfg(feature = "fs")]
+mod fingerprint;
 pub mod flac;
 pub mod id3;
 pub mod iff;
@@ -169,23 +196,46 @@ pub(crate) mod picture;
 mod probe;
 pub(crate) mod properties;
 pub mod resolve;
+mod save_plan;
 pub(crate) mod tag;
 mod traits;
 mod util;
+pub mod verify;
 pub mod wavpack;
+mod write_options;
 
+#[cfg(feature = "fs")]
+pub use crate::archive::{export_tags, import_tags};
+pub use crate::archive::{ArchivedTag, TagArchive};
 pub use crate::error::{LoftyError, Result};
 
-pub use crate::probe::{read_from, read_from_path, ParseOptions, ParsingMode, Probe};
+#[cfg(feature = "fs")]
+pub use crate::probe::{read_from, read_from_path};
+pub use crate::probe::{read_from_head_and_tail, ParseOptions, ParsingMode, Probe};
 
-pub use crate::file::{AudioFile, BoundTaggedFile, FileType, TaggedFile, TaggedFileExt};
+#[cfg(feature = "fs")]
+pub use crate::file::BoundTaggedFile;
+pub use crate::file::{AudioFile, FileType, TaggedFile, TaggedFileExt};
+#[cfg(feature = "fs")]
+pub use crate::fingerprint::FileFingerprint;
 pub use crate::picture::{MimeType, Picture, PictureType};
-pub use crate::properties::{ChannelMask, FileProperties};
+pub use crate::properties::{
+	BitrateSource, ChannelMask, FileProperties, GaplessInfo, GaplessSource,
+};
+pub use crate::save_plan::SavePlan;
 pub use crate::tag::{Tag, TagType};
+pub use tag::conflict::{Conflict, ReconcileAction, ReconcilePolicy};
+pub use tag::coverage::{coverage_report, CoverageReport, KeyCoverage, PictureCoverage};
+pub use tag::cuesheet::{CueIndex, CueTrack, Cuesheet};
+pub use tag::custom_key::ItemKeyMappings;
+pub use tag::isrc::Isrc;
 pub use tag::item::{ItemKey, ItemValue, TagItem};
+pub use tag::musical_key::{Accidental, KeyMode, KeyNote, MusicalKey};
+pub use tag::timestamp::Timestamp;
 pub use util::text::TextEncoding;
 
 pub use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+pub use crate::write_options::{BpmRounding, RiffInfoPlacement, WriteOptions};
 
 pub use picture::PictureInformation;
 
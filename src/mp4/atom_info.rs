@@ -204,6 +204,15 @@ impl AtomInfo {
 			ident: atom_ident,
 		}))
 	}
+
+	// The length of the size and identifier, including the 64-bit size if present
+	pub(crate) fn header_len(&self) -> u64 {
+		if self.extended {
+			ATOM_HEADER_LEN + 8
+		} else {
+			ATOM_HEADER_LEN
+		}
+	}
 }
 
 fn parse_freeform<R>(
@@ -21,6 +21,13 @@ impl AtomDataStorage {
 		}
 	}
 
+	pub(super) fn as_mut_slice(&mut self) -> &mut [AtomData] {
+		match self {
+			AtomDataStorage::Single(val) => std::slice::from_mut(val),
+			AtomDataStorage::Multiple(data) => data,
+		}
+	}
+
 	pub(super) fn is_pictures(&self) -> bool {
 		match self {
 			AtomDataStorage::Single(v) => matches!(v, AtomData::Picture(_)),
@@ -247,13 +254,17 @@ impl Debug for Atom<'_> {
 /// NOTES:
 ///
 /// * This only covers the most common data types.
-/// See the list of [well-known data types](https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/Metadata/Metadata.html#//apple_ref/doc/uid/TP40000939-CH1-SW34)
-/// for codes.
-/// * There are only two variants for integers, which
-/// will come from codes `21` and `22`. All other integer
-/// types will be stored as [`AtomData::Unknown`], refer
-/// to the link above for codes.
-#[derive(Debug, PartialEq, Eq, Clone)]
+///   See the list of [well-known data types](https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/Metadata/Metadata.html#//apple_ref/doc/uid/TP40000939-CH1-SW34)
+///   for codes.
+/// * Codes `21` and `22` are variable width integers, and will be stored as [`AtomData::SignedInteger`]
+///   and [`AtomData::UnsignedInteger`]. The fixed width integer and floating point codes each have their own
+///   variant.
+/// * Any other code (or fixed width data of the wrong size) will be stored as [`AtomData::Unknown`], and
+///   written back exactly as it was read.
+///
+/// [`AtomData::Float32`] and [`AtomData::Float64`] are compared by their bits, so `NaN` values are equal
+/// if their bit patterns are.
+#[derive(Debug, Clone)]
 pub enum AtomData {
 	/// A UTF-8 encoded string
 	UTF8(String),
@@ -277,6 +288,26 @@ pub enum AtomData {
 	///
 	/// NOTE: See [`AtomData::SignedInteger`]
 	UnsignedInteger(u32),
+	/// A big endian 32-bit floating point value (code `23`)
+	Float32(f32),
+	/// A big endian 64-bit floating point value (code `24`)
+	Float64(f64),
+	/// An 8-bit signed integer (code `65`)
+	SignedInteger8(i8),
+	/// A big endian 16-bit signed integer (code `66`)
+	SignedInteger16(i16),
+	/// A big endian 32-bit signed integer (code `67`)
+	SignedInteger32(i32),
+	/// A big endian 64-bit signed integer (code `74`)
+	SignedInteger64(i64),
+	/// An 8-bit unsigned integer (code `75`)
+	UnsignedInteger8(u8),
+	/// A big endian 16-bit unsigned integer (code `76`)
+	UnsignedInteger16(u16),
+	/// A big endian 32-bit unsigned integer (code `77`)
+	UnsignedInteger32(u32),
+	/// A big endian 64-bit unsigned integer (code `78`)
+	UnsignedInteger64(u64),
 	/// A boolean value
 	///
 	/// NOTE: This isn't an official data type, but multiple flag atoms exist,
@@ -288,6 +319,8 @@ pub enum AtomData {
 	/// Due to the number of possible types, there are many
 	/// **specified** types that are going to fall into this
 	/// variant.
+	///
+	/// The data will be written back as-is, with the same code.
 	Unknown {
 		/// The code, or type of the item
 		code: u32,
@@ -296,6 +329,43 @@ pub enum AtomData {
 	},
 }
 
+impl PartialEq for AtomData {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(AtomData::UTF8(a), AtomData::UTF8(b)) | (AtomData::UTF16(a), AtomData::UTF16(b)) => {
+				a == b
+			},
+			(AtomData::Picture(a), AtomData::Picture(b)) => a == b,
+			(AtomData::SignedInteger(a), AtomData::SignedInteger(b))
+			| (AtomData::SignedInteger32(a), AtomData::SignedInteger32(b)) => a == b,
+			(AtomData::UnsignedInteger(a), AtomData::UnsignedInteger(b))
+			| (AtomData::UnsignedInteger32(a), AtomData::UnsignedInteger32(b)) => a == b,
+			(AtomData::Float32(a), AtomData::Float32(b)) => a.to_bits() == b.to_bits(),
+			(AtomData::Float64(a), AtomData::Float64(b)) => a.to_bits() == b.to_bits(),
+			(AtomData::SignedInteger8(a), AtomData::SignedInteger8(b)) => a == b,
+			(AtomData::SignedInteger16(a), AtomData::SignedInteger16(b)) => a == b,
+			(AtomData::SignedInteger64(a), AtomData::SignedInteger64(b)) => a == b,
+			(AtomData::UnsignedInteger8(a), AtomData::UnsignedInteger8(b)) => a == b,
+			(AtomData::UnsignedInteger16(a), AtomData::UnsignedInteger16(b)) => a == b,
+			(AtomData::UnsignedInteger64(a), AtomData::UnsignedInteger64(b)) => a == b,
+			(AtomData::Bool(a), AtomData::Bool(b)) => a == b,
+			(
+				AtomData::Unknown {
+					code: a_code,
+					data: a_data,
+				},
+				AtomData::Unknown {
+					code: b_code,
+					data: b_data,
+				},
+			) => a_code == b_code && a_data == b_data,
+			_ => false,
+		}
+	}
+}
+
+impl Eq for AtomData {}
+
 /// The parental advisory rating
 ///
 /// See also:
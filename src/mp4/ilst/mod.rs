@@ -1,6 +1,6 @@
 pub(super) mod atom;
 pub(super) mod constants;
-pub(super) mod read;
+pub(crate) mod read;
 mod r#ref;
 pub(crate) mod write;
 
@@ -8,15 +8,21 @@ use super::AtomIdent;
 use crate::error::LoftyError;
 use crate::mp4::ilst::atom::AtomDataStorage;
 use crate::picture::{Picture, PictureType, TOMBSTONE_PICTURE};
+use crate::properties::{GaplessInfo, GaplessSource};
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
-use crate::tag::{try_parse_year, Tag, TagType};
+use crate::tag::rating::convert_rating_items;
+use crate::tag::{retain_precise_bpm, split_bpm, try_parse_year, Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::text::parse_flag;
+use crate::write_options::WriteOptions;
 use atom::{AdvisoryRating, Atom, AtomData};
 
 use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::{File, OpenOptions};
 use std::io::Write;
 use std::ops::Deref;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
@@ -28,6 +34,167 @@ const GENRE: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9gen");
 const COMMENT: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9cmt");
 const ADVISORY_RATING: AtomIdent<'_> = AtomIdent::Fourcc(*b"rtng");
 const COVR: AtomIdent<'_> = AtomIdent::Fourcc(*b"covr");
+const BPM: AtomIdent<'_> = AtomIdent::Fourcc(*b"tmpo");
+const MOVEMENT_NUMBER: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9mvi");
+const MOVEMENT_COUNT: AtomIdent<'_> = AtomIdent::Fourcc(*b"\xa9mvc");
+const PRECISE_BPM: AtomIdent<'_> = AtomIdent::Freeform {
+	mean: Cow::Borrowed("com.apple.iTunes"),
+	name: Cow::Borrowed("BPM"),
+};
+
+// iTunes' gapless playback information
+const ITUNSMPB: AtomIdent<'_> = AtomIdent::Freeform {
+	mean: Cow::Borrowed("com.apple.iTunes"),
+	name: Cow::Borrowed("iTunSMPB"),
+};
+
+// iTunes Store purchase information, see `Ilst::remove_purchase_metadata`
+const PURCHASE_METADATA: [[u8; 4]; 7] = [
+	*b"apID", *b"ownr", *b"purd", *b"cnID", *b"atID", *b"plID", *b"geID",
+];
+
+// MP4 has no picture types, so they're stored in a freeform atom of our own, see `Ilst`
+const PICTURE_TYPES: AtomIdent<'_> = AtomIdent::Freeform {
+	mean: Cow::Borrowed("io.github.serial-ata.lofty"),
+	name: Cow::Borrowed("PICTURE_TYPES"),
+};
+
+fn is_purchase_metadata(ident: &AtomIdent<'_>) -> bool {
+	matches!(ident, AtomIdent::Fourcc(fourcc) if PURCHASE_METADATA.contains(fourcc))
+}
+
+// The picture types are stored as a comma separated list of their ID3v2 values, in the same order as
+// the pictures in `covr`. Nothing is stored if every picture is `PictureType::Other`.
+fn encode_picture_types<'a>(types: impl IntoIterator<Item = &'a PictureType>) -> Option<String> {
+	let types = types.into_iter().collect::<Vec<_>>();
+	if types.iter().all(|ty| **ty == PictureType::Other) {
+		return None;
+	}
+
+	let encoded = types
+		.iter()
+		.map(|ty| ty.as_u8().to_string())
+		.collect::<Vec<_>>();
+	Some(encoded.join(","))
+}
+
+fn decode_picture_types(value: &str) -> Option<Vec<PictureType>> {
+	value
+		.split(',')
+		.map(|ty| ty.trim().parse().ok().map(PictureType::from_u8))
+		.collect()
+}
+
+// Restores the picture types stored by `encode_picture_types`, removing the atom holding them
+fn apply_picture_types(ilst: &mut Ilst) {
+	let Some(pos) = ilst
+		.atoms
+		.iter()
+		.position(|atom| atom.ident == PICTURE_TYPES)
+	else {
+		return;
+	};
+
+	let mut atom = ilst.atoms.remove(pos);
+	let AtomData::UTF8(value) = atom.data.first_mut() else {
+		return;
+	};
+
+	let Some(types) = decode_picture_types(value) else {
+		log::warn!("MP4: Invalid picture types atom, ignoring");
+		return;
+	};
+
+	let mut pictures = ilst
+		.atoms
+		.iter_mut()
+		.filter(|atom| atom.ident == COVR)
+		.flat_map(|atom| atom.data.as_mut_slice())
+		.filter_map(|data| match data {
+			AtomData::Picture(picture) => Some(picture),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	// The pictures were changed by something else, the types can no longer be trusted
+	if pictures.len() != types.len() {
+		log::debug!("MP4: Picture types don't match the pictures in `covr`, ignoring");
+		return;
+	}
+
+	for (picture, ty) in pictures.iter_mut().zip(types) {
+		picture.pic_type = ty;
+	}
+}
+
+// `tmpo` can only hold an integer, a fractional BPM is stored in a freeform atom
+fn is_precise_bpm(ident: &AtomIdent<'_>) -> bool {
+	match ident {
+		AtomIdent::Freeform { mean, name } => {
+			mean == "com.apple.iTunes" && name.eq_ignore_ascii_case("BPM")
+		},
+		AtomIdent::Fourcc(_) => false,
+	}
+}
+
+// The ISRC is stored in either a freeform atom or the `isrc` atom
+fn is_isrc(ident: &AtomIdent<'_>) -> bool {
+	match ident {
+		AtomIdent::Freeform { mean, name } => {
+			mean == "com.apple.iTunes" && name.eq_ignore_ascii_case("ISRC")
+		},
+		AtomIdent::Fourcc(fourcc) => fourcc == b"isrc",
+	}
+}
+
+// iTunes also stores the ISRC in the `xid ` atom, in the form "provider:isrc:VALUE"
+fn isrc_from_xid(xid: &str) -> Option<&str> {
+	let mut parts = xid.splitn(3, ':');
+	let (_provider, kind, isrc) = (parts.next()?, parts.next()?, parts.next()?);
+
+	(kind.eq_ignore_ascii_case("isrc") && !isrc.is_empty()).then_some(isrc)
+}
+
+// Both BPM atoms are expected to hold text or integers, though some software will store the
+// precise BPM as a float
+fn numeric_bpm(ident: &AtomIdent<'_>, data: &AtomData) -> Option<String> {
+	if *ident != BPM && !is_precise_bpm(ident) {
+		return None;
+	}
+
+	numeric_text(data)
+}
+
+// iTunes stores the movement number and count as integers
+fn numeric_movement(ident: &AtomIdent<'_>, data: &AtomData) -> Option<(ItemKey, String)> {
+	let key = if *ident == MOVEMENT_NUMBER {
+		ItemKey::MovementNumber
+	} else if *ident == MOVEMENT_COUNT {
+		ItemKey::MovementTotal
+	} else {
+		return None;
+	};
+
+	Some((key, numeric_text(data)?))
+}
+
+fn numeric_text(data: &AtomData) -> Option<String> {
+	let text = match *data {
+		AtomData::SignedInteger(int) | AtomData::SignedInteger32(int) => int.to_string(),
+		AtomData::UnsignedInteger(uint) | AtomData::UnsignedInteger32(uint) => uint.to_string(),
+		AtomData::SignedInteger8(int) => int.to_string(),
+		AtomData::SignedInteger16(int) => int.to_string(),
+		AtomData::SignedInteger64(int) => int.to_string(),
+		AtomData::UnsignedInteger8(uint) => uint.to_string(),
+		AtomData::UnsignedInteger16(uint) => uint.to_string(),
+		AtomData::UnsignedInteger64(uint) => uint.to_string(),
+		AtomData::Float32(float) => float.to_string(),
+		AtomData::Float64(float) => float.to_string(),
+		_ => return None,
+	};
+
+	Some(text)
+}
 
 macro_rules! impl_accessor {
 	($($name:ident => $const:ident;)+) => {
@@ -60,8 +227,15 @@ macro_rules! impl_accessor {
 
 /// ## Pictures
 ///
-/// Unlike other formats, ilst does not store a [`PictureType`]. All pictures will have
-/// [`PictureType::Other`].
+/// Unlike other formats, ilst does not store a [`PictureType`]. To keep the types of pictures when
+/// converting between formats, Lofty stores them in a freeform atom named
+/// `----:io.github.serial-ata.lofty:PICTURE_TYPES`, as a comma separated list of their ID3v2 values
+/// (e.g. `3,19`) in the same order as the pictures in `covr`.
+///
+/// This atom is only written when a picture has a type other than [`PictureType::Other`], and it is
+/// never exposed as an [`Atom`]. Other software will only see the pictures in `covr`, and will likely
+/// treat all of them as front covers. If the pictures are changed by other software, the atom is
+/// ignored and all pictures will have [`PictureType::Other`].
 ///
 /// ## Conversions
 ///
@@ -70,7 +244,10 @@ macro_rules! impl_accessor {
 /// When converting to [`Tag`], only atoms with a value of [`AtomData::UTF8`] and [`AtomData::UTF16`],
 /// with the exception of the `trkn` and `disk` atoms, as well as pictures, will be preserved.
 ///
-/// Do note, all pictures will be [`PictureType::Other`](crate::PictureType::Other)
+/// iTunes Store purchase information is never converted, as it identifies the purchaser. See
+/// [`Ilst::remove_purchase_metadata`].
+///
+/// Pictures keep their types, see [Pictures](#pictures).
 ///
 /// ### From `Tag`
 ///
@@ -321,10 +498,7 @@ impl Ilst {
 	/// assert_eq!(ilst.len(), 1);
 	/// assert_eq!(ilst.pictures().unwrap().count(), 2);
 	/// ```
-	pub fn insert_picture(&mut self, mut picture: Picture) {
-		// This is just for correctness, it doesn't really matter.
-		picture.pic_type = PictureType::Other;
-
+	pub fn insert_picture(&mut self, picture: Picture) {
 		let data = AtomData::Picture(picture);
 		let Some(existing_covr) = self.get_mut(&COVR) else {
 			self.atoms.push(Atom {
@@ -366,6 +540,134 @@ impl Ilst {
 		})
 	}
 
+	/// Returns the account that purchased the track from the iTunes Store, according to the `apID` atom
+	///
+	/// This is usually an email address.
+	pub fn purchase_account(&self) -> Option<&str> {
+		self.text(*b"apID")
+	}
+
+	/// Returns the name of the account owner, according to the `ownr` atom
+	pub fn purchase_owner(&self) -> Option<&str> {
+		self.text(*b"ownr")
+	}
+
+	/// Returns the date the track was purchased, according to the `purd` atom
+	///
+	/// This is stored as text, usually in the form `YYYY-MM-DD HH:MM:SS`.
+	pub fn purchase_date(&self) -> Option<&str> {
+		self.text(*b"purd")
+	}
+
+	/// Returns the iTunes Store catalog ID of the track, according to the `cnID` atom
+	pub fn catalog_id(&self) -> Option<u32> {
+		self.integer(*b"cnID").and_then(|id| u32::try_from(id).ok())
+	}
+
+	/// Returns the iTunes Store ID of the artist, according to the `atID` atom
+	pub fn artist_id(&self) -> Option<u32> {
+		self.integer(*b"atID").and_then(|id| u32::try_from(id).ok())
+	}
+
+	/// Returns the iTunes Store ID of the album, according to the `plID` atom
+	pub fn playlist_id(&self) -> Option<u64> {
+		self.integer(*b"plID")
+	}
+
+	/// Returns the iTunes Store ID of the genre, according to the `geID` atom
+	pub fn genre_id(&self) -> Option<u32> {
+		self.integer(*b"geID").and_then(|id| u32::try_from(id).ok())
+	}
+
+	/// Removes all iTunes Store purchase information
+	///
+	/// This information identifies the purchaser, and is often stripped before sharing files.
+	///
+	/// The following atoms are removed:
+	///
+	/// * `apID` - See [`Ilst::purchase_account`]
+	/// * `ownr` - See [`Ilst::purchase_owner`]
+	/// * `purd` - See [`Ilst::purchase_date`]
+	/// * `cnID` - See [`Ilst::catalog_id`]
+	/// * `atID` - See [`Ilst::artist_id`]
+	/// * `plID` - See [`Ilst::playlist_id`]
+	/// * `geID` - See [`Ilst::genre_id`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mp4::{Atom, AtomData, AtomIdent, Ilst};
+	///
+	/// let mut ilst = Ilst::new();
+	/// ilst.insert(Atom::new(
+	/// 	AtomIdent::Fourcc(*b"apID"),
+	/// 	AtomData::UTF8(String::from("foo@example.com")),
+	/// ));
+	/// assert_eq!(ilst.purchase_account(), Some("foo@example.com"));
+	///
+	/// ilst.remove_purchase_metadata();
+	/// assert!(ilst.purchase_account().is_none());
+	/// ```
+	pub fn remove_purchase_metadata(&mut self) {
+		self.atoms.retain(|atom| !is_purchase_metadata(&atom.ident));
+	}
+
+	fn text(&self, fourcc: [u8; 4]) -> Option<&str> {
+		self.get(&AtomIdent::Fourcc(fourcc))?
+			.data()
+			.find_map(|data| match data {
+				AtomData::UTF8(text) | AtomData::UTF16(text) => Some(text.as_str()),
+				_ => None,
+			})
+	}
+
+	// The store IDs are read as-is (see `parse_ilst`), though they may have been inserted as any integer type
+	fn integer(&self, fourcc: [u8; 4]) -> Option<u64> {
+		self.get(&AtomIdent::Fourcc(fourcc))?
+			.data()
+			.find_map(|data| match *data {
+				AtomData::Unknown { ref data, .. } if (1..=8).contains(&data.len()) => Some(
+					data.iter()
+						.fold(0_u64, |acc, byte| (acc << 8) | u64::from(*byte)),
+				),
+				AtomData::SignedInteger(i) | AtomData::SignedInteger32(i) => u64::try_from(i).ok(),
+				AtomData::SignedInteger64(i) => u64::try_from(i).ok(),
+				AtomData::UnsignedInteger(u) | AtomData::UnsignedInteger32(u) => Some(u64::from(u)),
+				AtomData::UnsignedInteger64(u) => Some(u),
+				_ => None,
+			})
+	}
+
+	// Reads the encoder delay and padding from the `iTunSMPB` atom
+	//
+	// The atom holds a list of hexadecimal numbers, such as:
+	// " 00000000 00000840 000001CA 00000000003F31F6 ..."
+	//
+	// Where the 2nd, 3rd, and 4th are the delay, padding, and number of valid samples.
+	pub(crate) fn gapless_info(&self) -> Option<GaplessInfo> {
+		let smpb = self.get(&ITUNSMPB)?.data().find_map(|data| match data {
+			AtomData::UTF8(text) => Some(text),
+			_ => None,
+		})?;
+
+		let mut fields = smpb.split_whitespace().skip(1);
+
+		let encoder_delay_samples = u32::from_str_radix(fields.next()?, 16).ok()?;
+		let encoder_padding_samples = u32::from_str_radix(fields.next()?, 16).ok()?;
+		let valid_samples = u64::from_str_radix(fields.next()?, 16).ok()?;
+
+		if valid_samples == 0 {
+			return None;
+		}
+
+		Some(GaplessInfo {
+			encoder_delay_samples,
+			encoder_padding_samples: Some(encoder_padding_samples),
+			valid_samples,
+			source: GaplessSource::ITunSmpb,
+		})
+	}
+
 	// Extracts a u16 from an integer pair
 	fn extract_number(&self, fourcc: [u8; 4], expected_size: usize) -> Option<u16> {
 		if let Some(atom) = self.get(&AtomIdent::Fourcc(fourcc)) {
@@ -529,23 +831,39 @@ impl TagExt for Ilst {
 		self.atoms.is_empty()
 	}
 
-	fn save_to_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
+	#[cfg(feature = "fs")]
+	fn save_to_path<P: AsRef<Path>>(
+		&self,
+		path: P,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		let mut f = OpenOptions::new().read(true).write(true).open(path)?;
-		self.save_to(&mut f)
+		self.save_to(&mut f, write_options)
 	}
 
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
-		self.as_ref().write_to(file)
+	#[cfg(feature = "fs")]
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		self.as_ref().write_to(file, write_options)
 	}
 
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
-		self.as_ref().dump_to(writer)
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		self.as_ref().dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::Mp4Ilst.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::Mp4Ilst.remove_from(file)
 	}
@@ -580,13 +898,34 @@ impl SplitTag for Ilst {
 
 		self.atoms.retain_mut(|atom| {
 			let Atom { ident, data } = atom;
+
+			// Purchase information is identifying, so it's kept out of the generic tag
+			if is_purchase_metadata(ident) {
+				return true; // Keep atom
+			}
+
+			if let Some(bpm) = numeric_bpm(ident, data.first_mut()) {
+				tag.items
+					.push(TagItem::new(ItemKey::Bpm, ItemValue::Text(bpm)));
+				return false; // Atom consumed
+			}
+
+			if let Some((key, number)) = numeric_movement(ident, data.first_mut()) {
+				tag.items.push(TagItem::new(key, ItemValue::Text(number)));
+				return false; // Atom consumed
+			}
+
 			let value = match data.first_mut() {
 				AtomData::UTF8(text) | AtomData::UTF16(text) => {
 					ItemValue::Text(std::mem::take(text))
 				},
-				AtomData::Picture(picture) => {
-					tag.pictures
-						.push(std::mem::replace(picture, TOMBSTONE_PICTURE));
+				AtomData::Picture(_) => {
+					for data in data.as_mut_slice() {
+						if let AtomData::Picture(picture) = data {
+							tag.pictures
+								.push(std::mem::replace(picture, TOMBSTONE_PICTURE));
+						}
+					}
 					return false; // Atom consumed
 				},
 				AtomData::Bool(b) => {
@@ -636,10 +975,40 @@ impl SplitTag for Ilst {
 				},
 			);
 
-			tag.items.push(TagItem::new(key, value));
+			let is_text = matches!(value, ItemValue::Text(_));
+			tag.items.push(TagItem::new(key.clone(), value));
+
+			// Text atoms can hold multiple values, such as the ISRCs of a medley
+			if is_text {
+				for data in data.as_mut_slice().iter_mut().skip(1) {
+					if let AtomData::UTF8(text) | AtomData::UTF16(text) = data {
+						tag.items.push(TagItem::new(
+							key.clone(),
+							ItemValue::Text(std::mem::take(text)),
+						));
+					}
+				}
+			}
+
 			false // Atom consumed
 		});
 
+		retain_precise_bpm(&mut tag);
+
+		// iTunes purchases may only have the ISRC in the `xid ` atom
+		if tag.get(&ItemKey::Isrc).is_none() {
+			let isrcs = tag
+				.get_strings(&ItemKey::AppleXid)
+				.filter_map(isrc_from_xid)
+				.map(str::to_owned)
+				.collect::<Vec<_>>();
+
+			for isrc in isrcs {
+				tag.items
+					.push(TagItem::new(ItemKey::Isrc, ItemValue::Text(isrc)));
+			}
+		}
+
 		(SplitTagRemainder(self), tag)
 	}
 }
@@ -647,7 +1016,7 @@ impl SplitTag for Ilst {
 impl MergeTag for SplitTagRemainder {
 	type Merged = Ilst;
 
-	fn merge_tag(self, tag: Tag) -> Self::Merged {
+	fn merge_tag(self, mut tag: Tag) -> Self::Merged {
 		fn convert_to_uint(space: &mut Option<u16>, cont: &str) {
 			if let Ok(num) = cont.parse::<u16>() {
 				*space = Some(num);
@@ -674,10 +1043,46 @@ impl MergeTag for SplitTagRemainder {
 
 		let Self(mut merged) = self;
 
+		convert_rating_items(&mut tag.items, TagType::Mp4Ilst);
+
+		// An ISRC taken from the `xid ` atom (see `split_tag`) doesn't need its own atom
+		let xid_isrcs = tag
+			.get_strings(&ItemKey::AppleXid)
+			.filter_map(isrc_from_xid)
+			.map(str::to_owned)
+			.collect::<Vec<_>>();
+		if !xid_isrcs.is_empty() {
+			tag.items.retain(|item| {
+				!(item.key() == &ItemKey::Isrc
+					&& matches!(item.value(), ItemValue::Text(isrc) if xid_isrcs.contains(isrc)))
+			});
+		}
+
 		// Storage for integer pairs
 		let mut tracks: (Option<u16>, Option<u16>) = (None, None);
 		let mut discs: (Option<u16>, Option<u16>) = (None, None);
 
+		if let Some(bpm) = tag.take_strings(&ItemKey::Bpm).next() {
+			let (integer, precise) = match split_bpm(&bpm) {
+				Some((integer, precise)) => (Some(integer), precise.map(str::to_owned)),
+				None => (None, Some(bpm)),
+			};
+
+			if let Some(integer) = integer {
+				merged.atoms.push(Atom {
+					ident: BPM,
+					data: AtomDataStorage::Single(AtomData::SignedInteger(i32::from(integer))),
+				});
+			}
+
+			if let Some(precise) = precise {
+				merged.atoms.push(Atom {
+					ident: PRECISE_BPM,
+					data: AtomDataStorage::Single(AtomData::UTF8(precise)),
+				});
+			}
+		}
+
 		for item in tag.items {
 			let key = item.item_key;
 
@@ -692,16 +1097,19 @@ impl MergeTag for SplitTagRemainder {
 					ItemKey::TrackTotal => convert_to_uint(&mut tracks.1, data.as_str()),
 					ItemKey::DiscNumber => convert_to_uint(&mut discs.0, data.as_str()),
 					ItemKey::DiscTotal => convert_to_uint(&mut discs.1, data.as_str()),
-					ItemKey::FlagCompilation => {
-						if let Ok(num) = data.as_str().parse::<u8>() {
-							let data = match num {
-								0 => false,
-								1 => true,
-								_ => {
-									// Ignore all other, unexpected values
-									continue;
-								},
-							};
+					ItemKey::MovementNumber | ItemKey::MovementTotal => {
+						if let Ok(number) = data.trim().parse::<u16>() {
+							merged.atoms.push(Atom {
+								ident: ident.into_owned(),
+								data: AtomDataStorage::Single(AtomData::SignedInteger(i32::from(
+									number,
+								))),
+							})
+						}
+					},
+					ItemKey::FlagCompilation | ItemKey::FlagPodcast => {
+						// Ignore all other, unexpected values
+						if let Some(data) = parse_flag(&data) {
 							merged.atoms.push(Atom {
 								ident: ident.into_owned(),
 								data: AtomDataStorage::Single(AtomData::Bool(data)),
@@ -716,15 +1124,8 @@ impl MergeTag for SplitTagRemainder {
 			}
 		}
 
-		for mut picture in tag.pictures {
-			// Just for correctness, since we can't actually
-			// assign a picture type in this format
-			picture.pic_type = PictureType::Other;
-
-			merged.atoms.push(Atom {
-				ident: AtomIdent::Fourcc([b'c', b'o', b'v', b'r']),
-				data: AtomDataStorage::Single(AtomData::Picture(picture)),
-			})
+		for picture in tag.pictures {
+			merged.insert_picture(picture);
 		}
 
 		create_int_pair(&mut merged, *b"trkn", tracks);
@@ -755,10 +1156,13 @@ mod tests {
 	use crate::tag::utils::test_utils;
 	use crate::tag::utils::test_utils::read_path;
 	use crate::{
-		Accessor as _, AudioFile, ItemKey, ItemValue, ParseOptions, ParsingMode, SplitTag as _,
-		Tag, TagExt as _, TagItem, TagType,
+		Accessor as _, AudioFile, BpmRounding, ItemKey, ItemValue, ParseOptions, ParsingMode,
+		SplitTag as _, Tag, TagExt as _, TagItem, TagType, WriteOptions,
 	};
-	use std::io::{Cursor, Read as _, Seek as _, Write as _};
+	use std::borrow::Cow;
+	use std::io::Cursor;
+	#[cfg(feature = "fs")]
+	use std::io::{Read as _, Seek as _, Write as _};
 
 	fn read_ilst(path: &str, parse_mode: ParsingMode) -> Ilst {
 		let tag = std::fs::read(path).unwrap();
@@ -854,7 +1258,9 @@ mod tests {
 		let parsed_tag = read_ilst_strict("tests/tags/assets/ilst/test.ilst");
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let cursor = Cursor::new(&writer[8..]);
 		let mut reader = AtomReader::new(cursor, crate::ParsingMode::Strict).unwrap();
@@ -974,6 +1380,7 @@ mod tests {
 	}
 
 	#[test]
+	#[cfg(feature = "fs")]
 	fn trailing_padding() {
 		const ILST_START: usize = 97;
 		const ILST_END: usize = 131;
@@ -1011,7 +1418,7 @@ mod tests {
 		file.rewind().unwrap();
 
 		ilst.set_title(String::from("Exactly 21 Characters"));
-		ilst.save_to(&mut file).unwrap();
+		ilst.save_to(&mut file, WriteOptions::default()).unwrap();
 
 		// Now verify the free atom
 		file.rewind().unwrap();
@@ -1041,16 +1448,30 @@ mod tests {
 	#[test]
 	fn read_non_full_meta_atom() {
 		let file_bytes = read_path("tests/files/assets/non_full_meta_atom.m4a");
-		let file = Mp4File::read_from(
-			&mut Cursor::new(file_bytes),
-			ParseOptions::new().read_properties(false),
-		)
-		.unwrap();
+		for parsing_mode in [ParsingMode::BestAttempt, ParsingMode::Relaxed] {
+			let file = Mp4File::read_from(
+				&mut Cursor::new(&file_bytes),
+				ParseOptions::new()
+					.read_properties(false)
+					.parsing_mode(parsing_mode),
+			)
+			.unwrap();
+
+			assert!(file.ilst_tag.is_some());
+		}
 
-		assert!(file.ilst_tag.is_some());
+		// The version and flags are required by spec
+		assert!(Mp4File::read_from(
+			&mut Cursor::new(&file_bytes),
+			ParseOptions::new()
+				.read_properties(false)
+				.parsing_mode(ParsingMode::Strict),
+		)
+		.is_err());
 	}
 
 	#[test]
+	#[cfg(feature = "fs")]
 	fn write_non_full_meta_atom() {
 		// This is testing writing to a file with a non-full meta atom
 		// We will *not* write a non-full meta atom
@@ -1066,7 +1487,7 @@ mod tests {
 			data: AtomDataStorage::Single(AtomData::UTF8(String::from("Foo artist"))),
 		});
 
-		tag.save_to(&mut file).unwrap();
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
 		file.rewind().unwrap();
 
 		let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
@@ -1218,4 +1639,484 @@ mod tests {
 
 		assert!(ilst.album().is_none());
 	}
+
+	#[test]
+	fn read_gnre() {
+		let ilst = read_ilst_strict("tests/tags/assets/ilst/gnre.ilst");
+
+		// `gnre` is upgraded to a `\xa9gen` atom
+		assert_eq!(ilst.len(), 2);
+		assert_eq!(ilst.genre().as_deref(), Some("Classical"));
+	}
+
+	#[test]
+	fn read_gnre_and_gen() {
+		let ilst = read_ilst_strict("tests/tags/assets/ilst/gnre_and_gen.ilst");
+
+		// The `\xa9gen` atom takes priority, regardless of order
+		assert_eq!(ilst.len(), 2);
+		assert_eq!(
+			ilst.get(&AtomIdent::Fourcc(*b"\xa9gen"))
+				.unwrap()
+				.data()
+				.collect::<Vec<_>>(),
+			vec![&AtomData::UTF8(String::from("Baz genre"))]
+		);
+	}
+
+	#[test]
+	fn write_gnre() {
+		fn dump(ilst: &Ilst, write_options: WriteOptions) -> Vec<u8> {
+			let mut writer = Vec::new();
+			ilst.dump_to(&mut writer, write_options).unwrap();
+			writer
+		}
+
+		fn contains(bytes: &[u8], ident: &[u8; 4]) -> bool {
+			bytes.windows(4).any(|w| w == ident)
+		}
+
+		let mut ilst = Ilst::new();
+		ilst.set_genre(String::from("Classical"));
+
+		// `\xa9gen` is the default
+		let bytes = dump(&ilst, WriteOptions::default());
+		assert!(contains(&bytes, b"\xa9gen"));
+		assert!(!contains(&bytes, b"gnre"));
+
+		let bytes = dump(&ilst, WriteOptions::new().prefer_mp4_gnre(true));
+		assert!(contains(&bytes, b"gnre"));
+		assert!(!contains(&bytes, b"\xa9gen"));
+
+		let mut reader = AtomReader::new(Cursor::new(&bytes[8..]), ParsingMode::Strict).unwrap();
+		let re_read =
+			super::read::parse_ilst(&mut reader, ParsingMode::Strict, (bytes.len() - 8) as u64)
+				.unwrap();
+		assert_eq!(re_read, ilst);
+
+		// Not an ID3v1 genre
+		ilst.set_genre(String::from("classical"));
+		let bytes = dump(&ilst, WriteOptions::new().prefer_mp4_gnre(true));
+		assert!(contains(&bytes, b"\xa9gen"));
+		assert!(!contains(&bytes, b"gnre"));
+
+		// Never write both
+		ilst.insert(Atom::new(
+			AtomIdent::Fourcc(*b"gnre"),
+			AtomData::Unknown {
+				code: 0,
+				data: vec![0, 33],
+			},
+		));
+		let bytes = dump(&ilst, WriteOptions::default());
+		assert!(contains(&bytes, b"\xa9gen"));
+		assert!(!contains(&bytes, b"gnre"));
+	}
+
+	#[test]
+	fn data_type_round_trip() {
+		use super::constants::{
+			BE_16BIT_SIGNED_INTEGER, BE_16BIT_UNSIGNED_INTEGER, BE_32BIT_SIGNED_INTEGER,
+			BE_32BIT_UNSIGNED_INTEGER, BE_64BIT_SIGNED_INTEGER, BE_64BIT_UNSIGNED_INTEGER,
+			BE_FLOAT32, BE_FLOAT64, BE_POINT_F32, SIGNED_8BIT_INTEGER, UNSIGNED_8BIT_INTEGER,
+		};
+
+		let values = [
+			(BE_FLOAT32, AtomData::Float32(126.5)),
+			(BE_FLOAT64, AtomData::Float64(-126.25)),
+			// Floats are compared by their bits, so this should still equal itself after a round trip
+			(BE_FLOAT64, AtomData::Float64(f64::NAN)),
+			(SIGNED_8BIT_INTEGER, AtomData::SignedInteger8(-1)),
+			(BE_16BIT_SIGNED_INTEGER, AtomData::SignedInteger16(i16::MIN)),
+			(BE_32BIT_SIGNED_INTEGER, AtomData::SignedInteger32(-2)),
+			(BE_64BIT_SIGNED_INTEGER, AtomData::SignedInteger64(i64::MIN)),
+			(UNSIGNED_8BIT_INTEGER, AtomData::UnsignedInteger8(u8::MAX)),
+			(BE_16BIT_UNSIGNED_INTEGER, AtomData::UnsignedInteger16(1)),
+			(
+				BE_32BIT_UNSIGNED_INTEGER,
+				AtomData::UnsignedInteger32(u32::MAX),
+			),
+			(
+				BE_64BIT_UNSIGNED_INTEGER,
+				AtomData::UnsignedInteger64(u64::MAX),
+			),
+			// No variant for this one, it should be kept as-is
+			(
+				BE_POINT_F32,
+				AtomData::Unknown {
+					code: BE_POINT_F32,
+					data: vec![0x42, 0xFD, 0, 0, 0x42, 0xFD, 0, 0],
+				},
+			),
+			// Too small to be a float, should also be kept as-is
+			(
+				BE_FLOAT32,
+				AtomData::Unknown {
+					code: BE_FLOAT32,
+					data: vec![0x42, 0xFD, 0],
+				},
+			),
+		];
+
+		let mut ilst = Ilst::new();
+		for (i, (_, value)) in values.iter().enumerate() {
+			ilst.insert(Atom::new(
+				AtomIdent::Freeform {
+					mean: Cow::Borrowed("com.apple.iTunes"),
+					name: Cow::Owned(format!("VALUE{i}")),
+				},
+				value.clone(),
+			));
+		}
+
+		let mut bytes = Vec::new();
+		ilst.dump_to(&mut bytes, WriteOptions::default()).unwrap();
+
+		// Verify the written codes
+		let codes = bytes
+			.windows(8)
+			.filter(|window| window.starts_with(b"data"))
+			.map(|window| u32::from_be_bytes([0, window[5], window[6], window[7]]))
+			.collect::<Vec<_>>();
+		let expected_codes = values.iter().map(|(code, _)| *code).collect::<Vec<_>>();
+		assert_eq!(codes, expected_codes);
+
+		let mut reader = AtomReader::new(Cursor::new(&bytes[8..]), ParsingMode::Strict).unwrap();
+		let re_read =
+			super::read::parse_ilst(&mut reader, ParsingMode::Strict, (bytes.len() - 8) as u64)
+				.unwrap();
+		assert_eq!(re_read, ilst);
+	}
+
+	#[test]
+	fn fractional_bpm() {
+		let mut tag = Tag::new(TagType::Mp4Ilst);
+		tag.set_bpm(126.5);
+
+		let ilst = Ilst::from(tag.clone());
+		verify_atom(&ilst, *b"tmpo", &AtomData::SignedInteger(127));
+		assert_eq!(
+			ilst.get(&super::PRECISE_BPM)
+				.and_then(|atom| atom.data().next()),
+			Some(&AtomData::UTF8(String::from("126.5")))
+		);
+
+		// `tmpo` is derived from the freeform atom on write, and stored as a 16-bit integer
+		let mut bytes = Vec::new();
+		ilst.dump_to(
+			&mut bytes,
+			WriteOptions::new().bpm_rounding(BpmRounding::Down),
+		)
+		.unwrap();
+
+		let mut reader = AtomReader::new(Cursor::new(&bytes[8..]), ParsingMode::Strict).unwrap();
+		let re_read =
+			super::read::parse_ilst(&mut reader, ParsingMode::Strict, (bytes.len() - 8) as u64)
+				.unwrap();
+		verify_atom(&re_read, *b"tmpo", &AtomData::SignedInteger(126));
+
+		// Only the precise BPM is kept
+		let (_, split_tag) = re_read.split_tag();
+		assert_eq!(split_tag.get_strings(&ItemKey::Bpm).count(), 1);
+		assert_eq!(split_tag.bpm(), Some(126.5));
+
+		// Some software stores the precise BPM as a float
+		let mut ilst = Ilst::new();
+		ilst.insert(Atom::new(
+			AtomIdent::Fourcc(*b"tmpo"),
+			AtomData::SignedInteger(127),
+		));
+		ilst.insert(Atom::new(super::PRECISE_BPM, AtomData::Float32(126.5)));
+
+		let (_, split_tag) = ilst.split_tag();
+		assert_eq!(split_tag.get_strings(&ItemKey::Bpm).count(), 1);
+		assert_eq!(split_tag.bpm(), Some(126.5));
+
+		// An integer BPM doesn't need a freeform atom
+		tag.set_bpm(128.0);
+
+		let ilst = Ilst::from(tag);
+		verify_atom(&ilst, *b"tmpo", &AtomData::SignedInteger(128));
+		assert!(ilst.get(&super::PRECISE_BPM).is_none());
+	}
+
+	#[test]
+	fn itunsmpb_gapless_info() {
+		let mut ilst = Ilst::default();
+		assert!(ilst.gapless_info().is_none());
+
+		ilst.insert(Atom::new(
+			AtomIdent::Freeform {
+				mean: Cow::Borrowed("com.apple.iTunes"),
+				name: Cow::Borrowed("iTunSMPB"),
+			},
+			AtomData::UTF8(String::from(
+				" 00000000 00000840 000001CA 00000000003F31F6 00000000 00000000 00000000 00000000",
+			)),
+		));
+
+		let gapless_info = ilst.gapless_info().unwrap();
+		assert_eq!(gapless_info.encoder_delay_samples(), 0x840);
+		assert_eq!(gapless_info.encoder_padding_samples(), Some(0x1CA));
+		assert_eq!(gapless_info.valid_samples(), 0x003F_31F6);
+		assert_eq!(gapless_info.source(), crate::GaplessSource::ITunSmpb);
+	}
+
+	#[test]
+	fn isrc_atoms() {
+		// A medley, with both of its ISRCs in the `isrc` atom
+		let mut ilst = Ilst::default();
+		ilst.insert(Atom {
+			ident: AtomIdent::Fourcc(*b"isrc"),
+			data: AtomDataStorage::Multiple(vec![
+				AtomData::UTF8(String::from("USRC17607839")),
+				AtomData::UTF8(String::from("GBAYE6800011")),
+			]),
+		});
+
+		let tag: Tag = ilst.into();
+		assert_eq!(
+			tag.get_strings(&ItemKey::Isrc).collect::<Vec<_>>(),
+			["USRC17607839", "GBAYE6800011"]
+		);
+
+		// The ISRC is taken from the `xid ` atom, without being duplicated in its own atom
+		let mut ilst = Ilst::default();
+		ilst.insert(Atom::new(
+			AtomIdent::Fourcc(*b"xid "),
+			AtomData::UTF8(String::from("SonyBMG:isrc:USRC17607839")),
+		));
+
+		let tag: Tag = ilst.into();
+		assert_eq!(tag.get_string(&ItemKey::Isrc), Some("USRC17607839"));
+
+		let ilst: Ilst = tag.into();
+		assert_eq!(ilst.len(), 1);
+		assert!(ilst.get(&AtomIdent::Fourcc(*b"xid ")).is_some());
+
+		// Anything else in `xid ` isn't an ISRC
+		let mut ilst = Ilst::default();
+		ilst.insert(Atom::new(
+			AtomIdent::Fourcc(*b"xid "),
+			AtomData::UTF8(String::from("SonyBMG:upc:886443927087")),
+		));
+
+		let tag: Tag = ilst.into();
+		assert!(tag.get(&ItemKey::Isrc).is_none());
+	}
+
+	#[test]
+	fn purchase_metadata() {
+		let mut ilst = Ilst::default();
+		ilst.set_title(String::from("Foo title"));
+		for (fourcc, data) in [
+			(*b"apID", AtomData::UTF8(String::from("foo@example.com"))),
+			(*b"ownr", AtomData::UTF8(String::from("Foo Bar"))),
+			(
+				*b"purd",
+				AtomData::UTF8(String::from("2009-07-17 14:44:08")),
+			),
+			(
+				*b"cnID",
+				AtomData::Unknown {
+					code: 21,
+					data: 5_u32.to_be_bytes().to_vec(),
+				},
+			),
+			(
+				*b"atID",
+				AtomData::Unknown {
+					code: 21,
+					data: 300_u32.to_be_bytes().to_vec(),
+				},
+			),
+			(
+				*b"plID",
+				AtomData::Unknown {
+					code: 21,
+					data: 88888_u64.to_be_bytes().to_vec(),
+				},
+			),
+			(*b"geID", AtomData::SignedInteger(20)),
+		] {
+			ilst.insert(Atom::new(AtomIdent::Fourcc(fourcc), data));
+		}
+
+		let mut writer = Vec::new();
+		ilst.dump_to(&mut writer, WriteOptions::default()).unwrap();
+
+		let cursor = Cursor::new(&writer[8..]);
+		let mut reader = AtomReader::new(cursor, crate::ParsingMode::Strict).unwrap();
+		let ilst = super::read::parse_ilst(
+			&mut reader,
+			crate::ParsingMode::Strict,
+			(writer.len() - 8) as u64,
+		)
+		.unwrap();
+
+		assert_eq!(ilst.purchase_account(), Some("foo@example.com"));
+		assert_eq!(ilst.purchase_owner(), Some("Foo Bar"));
+		assert_eq!(ilst.purchase_date(), Some("2009-07-17 14:44:08"));
+		assert_eq!(ilst.catalog_id(), Some(5));
+		assert_eq!(ilst.artist_id(), Some(300));
+		assert_eq!(ilst.playlist_id(), Some(88888));
+		assert_eq!(ilst.genre_id(), Some(20));
+
+		// The IDs are written back exactly as they were read, rather than being shrunk
+		verify_atom(
+			&ilst,
+			*b"cnID",
+			&AtomData::Unknown {
+				code: 21,
+				data: vec![0, 0, 0, 5],
+			},
+		);
+		let mut rewritten = Vec::new();
+		ilst.dump_to(&mut rewritten, WriteOptions::default())
+			.unwrap();
+		assert_eq!(rewritten, writer);
+
+		// None of it makes it into a generic tag
+		let (remainder, tag) = ilst.clone().split_tag();
+		assert_eq!(tag.len(), 1);
+		assert_eq!(remainder.purchase_account(), Some("foo@example.com"));
+		assert_eq!(remainder.catalog_id(), Some(5));
+
+		let mut ilst = ilst;
+		ilst.remove_purchase_metadata();
+		assert_eq!(ilst.len(), 1);
+		assert!(ilst.purchase_account().is_none());
+		assert!(ilst.catalog_id().is_none());
+	}
+
+	// The end of the `ilst` atom, and the ident and size of the atom following it
+	#[cfg(feature = "fs")]
+	fn atom_after_ilst(file_bytes: &[u8]) -> (usize, Option<([u8; 4], u32)>) {
+		let ilst_start = file_bytes.windows(4).position(|w| w == b"ilst").unwrap() - 4;
+		let ilst_len =
+			u32::from_be_bytes(file_bytes[ilst_start..ilst_start + 4].try_into().unwrap());
+
+		let ilst_end = ilst_start + ilst_len as usize;
+		let next_atom = file_bytes.get(ilst_end..ilst_end + 8).map(|atom| {
+			(
+				atom[4..].try_into().unwrap(),
+				u32::from_be_bytes(atom[..4].try_into().unwrap()),
+			)
+		});
+
+		(ilst_end, next_atom)
+	}
+
+	#[cfg(feature = "fs")]
+	fn save_with(file_bytes: &[u8], ilst: &Ilst, write_options: WriteOptions) -> Vec<u8> {
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(file_bytes).unwrap();
+		file.rewind().unwrap();
+
+		ilst.save_to(&mut file, write_options).unwrap();
+		file.rewind().unwrap();
+
+		let mut file_bytes = Vec::new();
+		file.read_to_end(&mut file_bytes).unwrap();
+		file_bytes
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn padding_in_place() {
+		let file_bytes = read_path("tests/files/assets/minimal/m4a_codec_aac.m4a");
+		let moov_start = file_bytes.windows(4).position(|w| w == b"moov").unwrap() - 4;
+
+		let (old_ilst_end, Some((ident, old_free_size))) = atom_after_ilst(&file_bytes) else {
+			panic!("Expected an atom after `ilst`");
+		};
+		assert_eq!(&ident, b"free");
+
+		let mut ilst = Mp4File::read_from(
+			&mut Cursor::new(&file_bytes),
+			ParseOptions::new().read_properties(false),
+		)
+		.unwrap()
+		.ilst_tag
+		.unwrap();
+
+		// Growing the tag takes its space from the `free` atom
+		ilst.set_comment(String::from("Foo comment"));
+		let grown = save_with(&file_bytes, &ilst, WriteOptions::default());
+
+		assert_eq!(grown.len(), file_bytes.len());
+		// Everything up to `moov`, including `mdat`, is untouched
+		assert!(grown[..moov_start] == file_bytes[..moov_start]);
+
+		let (ilst_end, Some((ident, free_size))) = atom_after_ilst(&grown) else {
+			panic!("Expected an atom after `ilst`");
+		};
+		assert_eq!(&ident, b"free");
+		assert!(free_size < old_free_size);
+		assert_eq!(
+			ilst_end + free_size as usize,
+			old_ilst_end + old_free_size as usize
+		);
+
+		// And shrinking it gives the space back
+		ilst.remove_comment();
+		let shrunk = save_with(&grown, &ilst, WriteOptions::default());
+		assert_eq!(shrunk.len(), file_bytes.len());
+		assert_eq!(
+			atom_after_ilst(&shrunk),
+			(old_ilst_end, Some((*b"free", old_free_size)))
+		);
+
+		// `skip` atoms are padding as well
+		let mut skip_bytes = file_bytes.clone();
+		skip_bytes[old_ilst_end + 4..old_ilst_end + 8].copy_from_slice(b"skip");
+
+		ilst.set_comment(String::from("Foo comment"));
+		let grown = save_with(&skip_bytes, &ilst, WriteOptions::default());
+		assert_eq!(grown.len(), file_bytes.len());
+		assert_eq!(atom_after_ilst(&grown).1, Some((*b"free", free_size)));
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn padding_full_rewrite() {
+		let file_bytes = read_path("tests/files/assets/minimal/m4a_codec_aac.m4a");
+		let (_, Some((_, old_free_size))) = atom_after_ilst(&file_bytes) else {
+			panic!("Expected an atom after `ilst`");
+		};
+
+		let mut ilst = Mp4File::read_from(
+			&mut Cursor::new(&file_bytes),
+			ParseOptions::new().read_properties(false),
+		)
+		.unwrap()
+		.ilst_tag
+		.unwrap();
+
+		// Too large for the existing padding, a new `free` atom is created
+		ilst.set_comment("A".repeat(old_free_size as usize * 2));
+		let rewritten = save_with(
+			&file_bytes,
+			&ilst,
+			WriteOptions::new().preferred_padding(2048),
+		);
+		assert_eq!(atom_after_ilst(&rewritten).1, Some((*b"free", 2048)));
+
+		// Which the next write can use
+		ilst.set_title(String::from("Foo title"));
+		let grown = save_with(&rewritten, &ilst, WriteOptions::default());
+		assert_eq!(grown.len(), rewritten.len());
+
+		let file = Mp4File::read_from(
+			&mut Cursor::new(&grown),
+			ParseOptions::new().read_properties(false),
+		)
+		.unwrap();
+		assert_eq!(file.ilst_tag.unwrap().title().as_deref(), Some("Foo title"));
+
+		// The existing padding is consumed, and no new padding is added
+		ilst.set_comment("A".repeat(old_free_size as usize * 8));
+		let rewritten = save_with(&grown, &ilst, WriteOptions::new().preferred_padding(0));
+		assert_eq!(atom_after_ilst(&rewritten).1, None);
+	}
 }
@@ -1,5 +1,8 @@
 use super::constants::{
-	BE_SIGNED_INTEGER, BE_UNSIGNED_INTEGER, BMP, JPEG, PNG, RESERVED, UTF16, UTF8,
+	BE_16BIT_SIGNED_INTEGER, BE_16BIT_UNSIGNED_INTEGER, BE_32BIT_SIGNED_INTEGER,
+	BE_32BIT_UNSIGNED_INTEGER, BE_64BIT_SIGNED_INTEGER, BE_64BIT_UNSIGNED_INTEGER, BE_FLOAT32,
+	BE_FLOAT64, BE_SIGNED_INTEGER, BE_UNSIGNED_INTEGER, BMP, JPEG, PNG, RESERVED,
+	SIGNED_8BIT_INTEGER, UNSIGNED_8BIT_INTEGER, UTF16, UTF8,
 };
 use super::{Atom, AtomData, AtomIdent, Ilst};
 use crate::error::{LoftyError, Result};
@@ -9,12 +12,22 @@ use crate::mp4::atom_info::AtomInfo;
 use crate::mp4::ilst::atom::AtomDataStorage;
 use crate::mp4::read::{skip_unneeded, AtomReader};
 use crate::picture::{MimeType, Picture, PictureType};
-use crate::util::text::{utf16_decode_bytes, utf8_decode};
+use crate::util::text::{trim_end_nulls, utf16_decode_bytes};
 use crate::ParsingMode;
 
 use std::borrow::Cow;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
+// An entire `ilst` atom, as written by `Ilst::dump_to`
+pub(crate) fn parse_ilst_atom(bytes: &[u8], parsing_mode: ParsingMode) -> Result<Ilst> {
+	let Some(content) = bytes.get(8..).filter(|_| bytes.get(4..8) == Some(b"ilst")) else {
+		err!(BadAtom("Expected an `ilst` atom"));
+	};
+
+	let mut reader = AtomReader::new(Cursor::new(content), parsing_mode)?;
+	parse_ilst(&mut reader, parsing_mode, content.len() as u64)
+}
+
 pub(in crate::mp4) fn parse_ilst<R>(
 	reader: &mut AtomReader<R>,
 	parsing_mode: ParsingMode,
@@ -32,6 +45,9 @@ where
 
 	let mut tag = Ilst::default();
 
+	// A `gnre` atom is only used if there's no `\xa9gen` atom
+	let mut gnre = None;
+
 	while let Ok(Some(atom)) = ilst_reader.next() {
 		if let AtomIdent::Fourcc(ref fourcc) = atom.ident {
 			match fourcc {
@@ -65,10 +81,10 @@ where
 								_ => AtomDataStorage::Multiple(data),
 							};
 
-							tag.atoms.push(Atom {
+							gnre = Some(Atom {
 								ident: AtomIdent::Fourcc(*b"\xa9gen"),
 								data: storage,
-							})
+							});
 						}
 					}
 
@@ -106,15 +122,42 @@ where
 
 					continue;
 				},
+				// The store IDs are integers of varying widths. They're kept as-is, so they're written
+				// back exactly as they were read.
+				b"cnID" | b"atID" | b"geID" => {
+					if let Some(atom_data) =
+						parse_data_inner(&mut ilst_reader, parsing_mode, &atom)?
+					{
+						let mut data = atom_data
+							.into_iter()
+							.map(|(code, content)| AtomData::Unknown {
+								code,
+								data: content,
+							})
+							.collect::<Vec<_>>();
+
+						if !data.is_empty() {
+							let storage = match data.len() {
+								1 => AtomDataStorage::Single(data.remove(0)),
+								_ => AtomDataStorage::Multiple(data),
+							};
+
+							tag.atoms.push(Atom {
+								ident: AtomIdent::Fourcc(*fourcc),
+								data: storage,
+							})
+						}
+					}
+
+					continue;
+				},
 				b"cpil" | b"hdvd" | b"pcst" | b"pgap" | b"shwm" => {
 					if let Some(atom_data) =
 						parse_data_inner(&mut ilst_reader, parsing_mode, &atom)?
 					{
 						if let Some((_, content)) = atom_data.first() {
-							let data = match content[..] {
-								[0, ..] => AtomData::Bool(false),
-								_ => AtomData::Bool(true),
-							};
+							// Flags may be stored as integers of any size
+							let data = AtomData::Bool(content.iter().any(|&b| b != 0));
 
 							tag.atoms.push(Atom {
 								ident: AtomIdent::Fourcc(*fourcc),
@@ -132,6 +175,14 @@ where
 		parse_data(&mut ilst_reader, parsing_mode, &mut tag, atom)?;
 	}
 
+	if let Some(gnre) = gnre {
+		if tag.get(&gnre.ident).is_none() {
+			tag.atoms.push(gnre);
+		}
+	}
+
+	super::apply_picture_types(&mut tag);
+
 	Ok(tag)
 }
 
@@ -158,7 +209,7 @@ where
 		// Most atoms we encounter are only going to have 1 value, so store them as such
 		if atom_data.len() == 1 {
 			let (flags, content) = atom_data.remove(0);
-			let data = match interpret_atom_content(flags, content) {
+			let data = match interpret_or_retain(flags, content, parsing_mode) {
 				Ok(data) => data,
 				Err(err) => return handle_error(err, parsing_mode),
 			};
@@ -173,7 +224,7 @@ where
 
 		let mut data = Vec::new();
 		for (flags, content) in atom_data {
-			let value = match interpret_atom_content(flags, content) {
+			let value = match interpret_or_retain(flags, content, parsing_mode) {
 				Ok(data) => data,
 				Err(err) => return handle_error(err, parsing_mode),
 			};
@@ -229,6 +280,9 @@ where
 					let mut content = try_vec![0; content_len];
 					reader.read_exact(&mut content)?;
 					ret.push((flags, content));
+				} else if flags == UTF8 || flags == UTF16 {
+					// Empty text is kept, see `WriteOptions::remove_empty_items`
+					ret.push((flags, Vec::new()));
 				} else {
 					log::warn!("Skipping empty \"data\" atom");
 				}
@@ -331,16 +385,106 @@ where
 	Ok(())
 }
 
-fn interpret_atom_content(flags: u32, content: Vec<u8>) -> Result<AtomData> {
+// With `ParsingMode::Relaxed`, content that can't be interpreted is kept as `AtomData::Unknown`,
+// so it can be written back as-is
+fn interpret_or_retain(
+	flags: u32,
+	content: Vec<u8>,
+	parsing_mode: ParsingMode,
+) -> Result<AtomData> {
+	match interpret_atom_content(flags, content) {
+		Ok(data) => Ok(data),
+		Err((err, content)) if parsing_mode == ParsingMode::Relaxed => {
+			log::warn!("Failed to interpret atom content, retaining it as-is: {err}");
+			Ok(AtomData::Unknown {
+				code: flags,
+				data: content,
+			})
+		},
+		Err((err, _)) => Err(err),
+	}
+}
+
+// The content is handed back on failure, so it can be retained without copying it up front
+fn interpret_atom_content(
+	flags: u32,
+	content: Vec<u8>,
+) -> std::result::Result<AtomData, (LoftyError, Vec<u8>)> {
 	// https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/Metadata/Metadata.html#//apple_ref/doc/uid/TP40000939-CH1-SW35
-	Ok(match flags {
-		UTF8 => AtomData::UTF8(utf8_decode(content)?),
-		UTF16 => AtomData::UTF16(utf16_decode_bytes(&content, u16::from_be_bytes)?),
-		BE_SIGNED_INTEGER => AtomData::SignedInteger(parse_int(&content)?),
-		BE_UNSIGNED_INTEGER => AtomData::UnsignedInteger(parse_uint(&content)?),
+	let parsed = match flags {
+		UTF8 => {
+			return match String::from_utf8(content) {
+				Ok(mut text) => {
+					trim_end_nulls(&mut text);
+					Ok(AtomData::UTF8(text))
+				},
+				Err(err) => Err((err.utf8_error().into(), err.into_bytes())),
+			};
+		},
+		UTF16 => utf16_decode_bytes(&content, u16::from_be_bytes).map(AtomData::UTF16),
+		BE_SIGNED_INTEGER => parse_int(&content).map(AtomData::SignedInteger),
+		BE_UNSIGNED_INTEGER => parse_uint(&content).map(AtomData::UnsignedInteger),
+		_ => return Ok(interpret_infallible(flags, content)),
+	};
+
+	parsed.map_err(|err| (err, content))
+}
+
+fn interpret_infallible(flags: u32, content: Vec<u8>) -> AtomData {
+	match flags {
+		BE_FLOAT32 => parse_fixed(flags, content, |b| AtomData::Float32(f32::from_be_bytes(b))),
+		BE_FLOAT64 => parse_fixed(flags, content, |b| AtomData::Float64(f64::from_be_bytes(b))),
+		SIGNED_8BIT_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::SignedInteger8(i8::from_be_bytes(b))
+		}),
+		BE_16BIT_SIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::SignedInteger16(i16::from_be_bytes(b))
+		}),
+		BE_32BIT_SIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::SignedInteger32(i32::from_be_bytes(b))
+		}),
+		BE_64BIT_SIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::SignedInteger64(i64::from_be_bytes(b))
+		}),
+		UNSIGNED_8BIT_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::UnsignedInteger8(u8::from_be_bytes(b))
+		}),
+		BE_16BIT_UNSIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::UnsignedInteger16(u16::from_be_bytes(b))
+		}),
+		BE_32BIT_UNSIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::UnsignedInteger32(u32::from_be_bytes(b))
+		}),
+		BE_64BIT_UNSIGNED_INTEGER => parse_fixed(flags, content, |b| {
+			AtomData::UnsignedInteger64(u64::from_be_bytes(b))
+		}),
 		code => AtomData::Unknown {
 			code,
 			data: content,
 		},
-	})
+	}
+}
+
+// Unlike the variable width integers, a size mismatch isn't an error here. The data is just
+// kept as-is, so it can still be written back.
+fn parse_fixed<const N: usize>(
+	code: u32,
+	content: Vec<u8>,
+	interpret: fn([u8; N]) -> AtomData,
+) -> AtomData {
+	match <[u8; N]>::try_from(content.as_slice()) {
+		Ok(bytes) => interpret(bytes),
+		Err(_) => {
+			log::warn!(
+				"Data with code {code} has an unexpected size (expected: {N}, got: {}), storing as \
+				 unknown",
+				content.len()
+			);
+
+			AtomData::Unknown {
+				code,
+				data: content,
+			}
+		},
+	}
 }
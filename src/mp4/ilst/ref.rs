@@ -4,8 +4,10 @@
 
 use crate::error::Result;
 use crate::mp4::{Atom, AtomData, AtomIdent, Ilst};
+#[cfg(feature = "fs")]
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
-use std::fs::File;
 use std::io::Write;
 
 impl Ilst {
@@ -24,12 +26,20 @@ impl<'a, I: 'a> IlstRef<'a, I>
 where
 	I: IntoIterator<Item = &'a AtomData>,
 {
-	pub(crate) fn write_to(&mut self, file: &mut File) -> Result<()> {
-		super::write::write_to(file, self)
+	#[cfg(feature = "fs")]
+	pub(crate) fn write_to<F>(&mut self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		super::write::write_to(file, self, write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let temp = super::write::build_ilst(&mut self.atoms)?;
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let temp = super::write::build_ilst(&mut self.atoms, write_options)?;
 		writer.write_all(&temp)?;
 
 		Ok(())
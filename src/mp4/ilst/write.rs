@@ -1,16 +1,26 @@
 use super::r#ref::IlstRef;
 use crate::error::{FileEncodingError, Result};
 use crate::file::FileType;
+use crate::id3::v1::constants::GENRES;
 use crate::macros::{err, try_vec};
 use crate::mp4::atom_info::{AtomIdent, AtomInfo, ATOM_HEADER_LEN, FOURCC_LEN, IDENTIFIER_LEN};
+use crate::mp4::ilst::constants::{
+	BE_16BIT_SIGNED_INTEGER, BE_16BIT_UNSIGNED_INTEGER, BE_32BIT_SIGNED_INTEGER,
+	BE_32BIT_UNSIGNED_INTEGER, BE_64BIT_SIGNED_INTEGER, BE_64BIT_UNSIGNED_INTEGER, BE_FLOAT32,
+	BE_FLOAT64, BE_SIGNED_INTEGER, RESERVED, SIGNED_8BIT_INTEGER, UNSIGNED_8BIT_INTEGER,
+};
 use crate::mp4::ilst::r#ref::AtomRef;
+use crate::mp4::ilst::{encode_picture_types, is_isrc, is_precise_bpm, COVR, PICTURE_TYPES};
 use crate::mp4::moov::Moov;
 use crate::mp4::read::{atom_tree, meta_is_full, nested_atom, verify_mp4, AtomReader};
 use crate::mp4::AtomData;
-use crate::picture::{MimeType, Picture};
+use crate::picture::{check_picture_size, MimeType, Picture};
 use crate::probe::ParseOptions;
+use crate::tag::try_parse_bpm;
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
-use std::fs::File;
+use std::borrow::Cow;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{BigEndian, WriteBytesExt};
@@ -20,9 +30,14 @@ const FULL_ATOM_SIZE: u64 = ATOM_HEADER_LEN + 4;
 const HDLR_SIZE: u64 = ATOM_HEADER_LEN + 25;
 
 // TODO: We are forcing the use of ParseOptions::DEFAULT_PARSING_MODE. This is not good. It should be caller-specified.
-pub(crate) fn write_to<'a, I: 'a>(data: &mut File, tag: &mut IlstRef<'a, I>) -> Result<()>
+pub(crate) fn write_to<'a, F, I>(
+	data: &mut F,
+	tag: &mut IlstRef<'a, I>,
+	write_options: WriteOptions,
+) -> Result<()>
 where
-	I: IntoIterator<Item = &'a AtomData>,
+	F: FileLike,
+	I: IntoIterator<Item = &'a AtomData> + 'a,
 {
 	let mut reader = AtomReader::new(data, ParseOptions::DEFAULT_PARSING_MODE)?;
 
@@ -39,9 +54,19 @@ where
 	let mut cursor = Cursor::new(file_bytes);
 	cursor.seek(SeekFrom::Start(pos))?;
 
-	let ilst = build_ilst(&mut tag.atoms)?;
+	let ilst = build_ilst(&mut tag.atoms, write_options)?;
 	let remove_tag = ilst.is_empty();
 
+	// The `moov` atom of a fragmented file can't be resized, as it would move all of the fragments
+	let fragmented = nested_atom(
+		&mut cursor,
+		moov.len,
+		b"mvex",
+		ParseOptions::DEFAULT_PARSING_MODE,
+	)?
+	.is_some();
+	cursor.seek(SeekFrom::Start(pos))?;
+
 	let udta = nested_atom(
 		&mut cursor,
 		moov.len,
@@ -54,6 +79,11 @@ where
 		return Ok(());
 	}
 
+	// Where the writes start, everything following it moves if `moov` changes size
+	let udta_start = udta
+		.as_ref()
+		.map_or(moov.start + ATOM_HEADER_LEN, |udta| udta.start);
+
 	// Total size of new atoms
 	let mut new_udta_size;
 	// Size of the existing udta atom
@@ -73,7 +103,7 @@ where
 		match meta {
 			Some(meta) => {
 				// We may encounter a non-full `meta` atom
-				meta_is_full(&mut cursor)?;
+				meta_is_full(&mut cursor, ParseOptions::DEFAULT_PARSING_MODE)?;
 
 				// We can use the existing `udta` and `meta` atoms
 				save_to_existing(
@@ -82,6 +112,7 @@ where
 					&mut new_udta_size,
 					ilst,
 					remove_tag,
+					write_options,
 				)?
 			},
 			// Nothing to do
@@ -90,6 +121,8 @@ where
 			None => {
 				existing_udta_size = udta.len;
 
+				let ilst = pad_ilst(ilst, write_options)?;
+
 				// `meta` + `ilst`
 				let capacity = FULL_ATOM_SIZE as usize + ilst.len();
 				let buf = Vec::with_capacity(capacity);
@@ -113,7 +146,7 @@ where
 		}
 	} else {
 		// We have to create the `udta` atom
-		let bytes = create_udta(&ilst)?;
+		let bytes = create_udta(&pad_ilst(ilst, write_options)?)?;
 		new_udta_size = bytes.len() as u64;
 
 		// We'll put the new `udta` atom right at the start of `moov`
@@ -121,18 +154,29 @@ where
 		cursor.get_mut().splice(udta_pos..udta_pos, bytes);
 	}
 
+	let new_moov_size = (moov.len - existing_udta_size) + new_udta_size;
+	if fragmented && new_moov_size != moov.len {
+		err!(FragmentedFile);
+	}
+
 	cursor.seek(SeekFrom::Start(moov.start))?;
 
 	// Change the size of the moov atom
-	write_size(
-		moov.start,
-		(moov.len - existing_udta_size) + new_udta_size,
-		moov.extended,
-		&mut cursor,
-	)?;
+	write_size(moov.start, new_moov_size, moov.extended, &mut cursor)?;
 
 	let data = reader.into_inner();
 
+	// The tag fit in the existing space, nothing outside of `moov` has to move
+	if new_moov_size == moov.len {
+		let moov_range = moov.start as usize..(moov.start + moov.len) as usize;
+
+		data.seek(SeekFrom::Start(moov.start))?;
+		data.write_all(&cursor.get_ref()[moov_range])?;
+		return Ok(());
+	}
+
+	data.mark_moved(udta_start);
+
 	data.rewind()?;
 	data.set_len(0)?;
 	data.write_all(&cursor.into_inner())?;
@@ -147,6 +191,7 @@ fn save_to_existing(
 	new_udta_size: &mut u64,
 	ilst: Vec<u8>,
 	remove_tag: bool,
+	write_options: WriteOptions,
 ) -> Result<()> {
 	let replacement;
 	let range;
@@ -166,14 +211,14 @@ fn save_to_existing(
 
 		let meta_end = (meta.start + meta.len) as usize;
 
-		replacement = ilst;
+		replacement = pad_ilst(ilst, write_options)?;
 		range = meta_end..meta_end;
 	} else {
 		let existing_ilst = &tree[ilst_idx];
 		let existing_ilst_size = existing_ilst.len;
 
 		let mut range_start = existing_ilst.start;
-		let range_end = existing_ilst.start + existing_ilst_size;
+		let mut range_end = existing_ilst.start + existing_ilst_size;
 
 		if remove_tag {
 			// We just need to strip out the `ilst` atom
@@ -188,7 +233,7 @@ fn save_to_existing(
 			if ilst_idx > 0 {
 				let previous_atom = &tree[ilst_idx - 1];
 
-				if previous_atom.ident == AtomIdent::Fourcc(*b"free") {
+				if is_padding(previous_atom) {
 					range_start = previous_atom.start;
 					available_space += previous_atom.len;
 				}
@@ -198,15 +243,19 @@ fn save_to_existing(
 			if ilst_idx != tree.len() - 1 {
 				let next_atom = &tree[ilst_idx + 1];
 
-				if next_atom.ident == AtomIdent::Fourcc(*b"free") {
+				if is_padding(next_atom) {
+					range_end = next_atom.start + next_atom.len;
 					available_space += next_atom.len;
 				}
 			}
 
 			let ilst_len = ilst.len() as u64;
 
-			// Check if we have enough padding to fit the `ilst` atom and a new `free` atom
-			if available_space > ilst_len && available_space - ilst_len > 8 {
+			// Check if we have enough space to fit the `ilst` atom, and a new `free` atom if there's
+			// any space left over
+			if available_space == ilst_len
+				|| (available_space > ilst_len && available_space - ilst_len >= ATOM_HEADER_LEN)
+			{
 				// We have enough space to make use of the padding
 
 				let remaining_space = available_space - ilst_len;
@@ -214,21 +263,19 @@ fn save_to_existing(
 					err!(TooMuchData);
 				}
 
-				let remaining_space = remaining_space as u32;
-
 				cursor.seek(SeekFrom::Start(range_start))?;
 				cursor.write_all(&ilst)?;
 
 				// Write the remaining padding
-				cursor.write_u32::<BigEndian>(remaining_space)?;
-				cursor.write_all(b"free")?;
-				cursor
-					.write_all(&try_vec![1; (remaining_space - ATOM_HEADER_LEN as u32) as usize])?;
+				if remaining_space > 0 {
+					write_free(cursor, remaining_space as u32)?;
+				}
 
 				return Ok(());
 			}
 
-			replacement = ilst;
+			// The padding is used up, so we'll replace it along with the `ilst` atom
+			replacement = pad_ilst(ilst, write_options)?;
 			range = range_start as usize..range_end as usize;
 		}
 	}
@@ -253,6 +300,36 @@ fn save_to_existing(
 	Ok(())
 }
 
+// `free` and `skip` atoms are both just unused space
+fn is_padding(atom: &AtomInfo) -> bool {
+	matches!(atom.ident, AtomIdent::Fourcc(ref fourcc) if fourcc == b"free" || fourcc == b"skip")
+}
+
+// Appends a `free` atom of `WriteOptions::preferred_padding` bytes to a new `ilst` atom, so the next
+// write can happen in place
+fn pad_ilst(mut ilst: Vec<u8>, write_options: WriteOptions) -> Result<Vec<u8>> {
+	match write_options.preferred_padding {
+		Some(padding) if u64::from(padding) >= ATOM_HEADER_LEN && !ilst.is_empty() => {
+			let mut cursor = Cursor::new(ilst);
+			cursor.seek(SeekFrom::End(0))?;
+			write_free(&mut cursor, padding)?;
+
+			ilst = cursor.into_inner();
+		},
+		_ => {},
+	}
+
+	Ok(ilst)
+}
+
+fn write_free(cursor: &mut Cursor<Vec<u8>>, size: u32) -> Result<()> {
+	cursor.write_u32::<BigEndian>(size)?;
+	cursor.write_all(b"free")?;
+	cursor.write_all(&try_vec![0; (size - ATOM_HEADER_LEN as u32) as usize])?;
+
+	Ok(())
+}
+
 fn create_udta(ilst: &[u8]) -> Result<Vec<u8>> {
 	// `udta` + `meta` + `hdlr` + `ilst`
 	let capacity = ATOM_HEADER_LEN + FULL_ATOM_SIZE + HDLR_SIZE + ilst.len() as u64;
@@ -328,32 +405,141 @@ fn write_size(start: u64, size: u64, extended: bool, writer: &mut Cursor<Vec<u8>
 
 pub(super) fn build_ilst<'a, I: 'a>(
 	atoms: &mut dyn Iterator<Item = AtomRef<'a, I>>,
+	write_options: WriteOptions,
 ) -> Result<Vec<u8>>
 where
 	I: IntoIterator<Item = &'a AtomData>,
 {
-	let mut peek = atoms.peekable();
-
-	if peek.peek().is_none() {
+	let atoms = atoms
+		.map(|atom| (atom.ident, atom.data.into_iter().collect::<Vec<_>>()))
+		.filter(|(ident, _)| *ident != PICTURE_TYPES)
+		.collect::<Vec<_>>();
+
+	let normalized_data = atoms
+		.iter()
+		.map(|(_, data)| normalize_text_data(data, write_options))
+		.collect::<Vec<_>>();
+	let mut atoms = atoms
+		.into_iter()
+		.zip(&normalized_data)
+		.filter(|(_, data)| !data.is_empty())
+		.map(|((ident, _), data)| (ident, data.iter().map(|data| &**data).collect::<Vec<_>>()))
+		.collect::<Vec<_>>();
+
+	if atoms.is_empty() && write_options.remove_tags_if_empty {
 		return Ok(Vec::new());
 	}
 
 	let mut writer = Cursor::new(vec![0, 0, 0, 0, b'i', b'l', b's', b't']);
 	writer.seek(SeekFrom::End(0))?;
 
-	for atom in peek {
+	// The picture types are always rewritten, as the pictures may have changed
+	let picture_types = encode_picture_types(
+		atoms
+			.iter()
+			.filter(|(ident, _)| *ident == COVR)
+			.flat_map(|(_, data)| data)
+			.filter_map(|data| match data {
+				AtomData::Picture(picture) => Some(&picture.pic_type),
+				_ => None,
+			}),
+	)
+	.map(AtomData::UTF8);
+
+	if let Some(picture_types) = &picture_types {
+		atoms.push((PICTURE_TYPES, vec![picture_types]));
+	}
+
+	for (_, data) in atoms.iter().filter(|(ident, _)| *ident == COVR) {
+		for picture in data.iter().filter_map(|data| match data {
+			AtomData::Picture(picture) => Some(picture),
+			_ => None,
+		}) {
+			// Atom header, version, flags, locale
+			check_picture_size(picture.data.len() as u64 + 16, write_options)?;
+		}
+	}
+
+	// The `\xa9gen` and `gnre` atoms should never both be written. A text genre can represent
+	// everything, so it wins.
+	let has_text_genre = atoms
+		.iter()
+		.any(|(ident, _)| *ident == AtomIdent::Fourcc(*b"\xa9gen"));
+
+	// A fractional BPM takes precedence, `tmpo` is derived from it using the requested rounding mode
+	let precise_bpm = atoms
+		.iter()
+		.find_map(|(ident, data)| match data.first() {
+			Some(AtomData::UTF8(bpm)) if is_precise_bpm(ident) => try_parse_bpm(bpm),
+			Some(AtomData::Float32(bpm)) if is_precise_bpm(ident) => Some(f64::from(*bpm)),
+			Some(AtomData::Float64(bpm)) if is_precise_bpm(ident) => Some(*bpm),
+			_ => None,
+		})
+		.filter(|bpm| bpm.fract() != 0.0)
+		.and_then(|bpm| write_options.bpm_rounding.round(bpm));
+
+	for (ident, data) in atoms {
+		if has_text_genre && ident == AtomIdent::Fourcc(*b"gnre") {
+			continue;
+		}
+
+		if is_isrc(&ident) {
+			for data in &data {
+				if let AtomData::UTF8(isrc) | AtomData::UTF16(isrc) = data {
+					write_options.check_isrc(isrc);
+				}
+			}
+		}
+
 		let start = writer.stream_position()?;
 
 		// Empty size, we get it later
 		writer.write_all(&[0; FOURCC_LEN as usize])?;
 
-		match atom.ident {
-			AtomIdent::Fourcc(ref fourcc) => writer.write_all(fourcc)?,
-			AtomIdent::Freeform { mean, name } => write_freeform(&mean, &name, &mut writer)?,
+		match ident {
+			AtomIdent::Fourcc(ref fourcc)
+				if fourcc == b"\xa9gen" && write_options.prefer_mp4_gnre =>
+			{
+				match id3v1_genre_index(&data) {
+					Some(index) => {
+						writer.write_all(b"gnre")?;
+						// The index is offset by 1
+						write_data(RESERVED, &(index + 1).to_be_bytes(), &mut writer)?;
+					},
+					None => {
+						writer.write_all(fourcc)?;
+						write_atom_data(data, &mut writer)?;
+					},
+				}
+			},
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"tmpo" => {
+				writer.write_all(fourcc)?;
+				// `tmpo` is expected to be a 16-bit integer
+				match precise_bpm.or_else(|| short_integer(&data)) {
+					Some(bpm) => write_data(BE_SIGNED_INTEGER, &bpm.to_be_bytes(), &mut writer)?,
+					None => write_atom_data(data, &mut writer)?,
+				}
+			},
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"\xa9mvi" || fourcc == b"\xa9mvc" => {
+				writer.write_all(fourcc)?;
+				// iTunes also expects the movement number and count to be 16-bit integers
+				match short_integer(&data) {
+					Some(number) => {
+						write_data(BE_SIGNED_INTEGER, &number.to_be_bytes(), &mut writer)?
+					},
+					None => write_atom_data(data, &mut writer)?,
+				}
+			},
+			AtomIdent::Fourcc(ref fourcc) => {
+				writer.write_all(fourcc)?;
+				write_atom_data(data, &mut writer)?;
+			},
+			AtomIdent::Freeform { mean, name } => {
+				write_freeform(&mean, &name, &mut writer)?;
+				write_atom_data(data, &mut writer)?;
+			},
 		}
 
-		write_atom_data(atom.data, &mut writer)?;
-
 		let end = writer.stream_position()?;
 
 		let size = end - start;
@@ -374,6 +560,50 @@ where
 	Ok(writer.into_inner())
 }
 
+// See `WriteOptions::trim_whitespace` and `WriteOptions::remove_empty_items`
+fn normalize_text_data<'a>(
+	data: &[&'a AtomData],
+	write_options: WriteOptions,
+) -> Vec<Cow<'a, AtomData>> {
+	data.iter()
+		.filter_map(|&data| match data {
+			AtomData::UTF8(text) | AtomData::UTF16(text) => {
+				let normalized = write_options.text_item(text)?;
+				if normalized.len() == text.len() {
+					return Some(Cow::Borrowed(data));
+				}
+
+				let normalized = normalized.to_owned();
+				Some(Cow::Owned(match data {
+					AtomData::UTF8(_) => AtomData::UTF8(normalized),
+					_ => AtomData::UTF16(normalized),
+				}))
+			},
+			_ => Some(Cow::Borrowed(data)),
+		})
+		.collect()
+}
+
+fn short_integer(data: &[&AtomData]) -> Option<u16> {
+	match *data {
+		[AtomData::SignedInteger(int)] => u16::try_from(*int).ok(),
+		[AtomData::UnsignedInteger(uint)] => u16::try_from(*uint).ok(),
+		[AtomData::UnsignedInteger16(uint)] => Some(*uint),
+		_ => None,
+	}
+}
+
+// A `gnre` atom can only be used for a single genre that exactly matches one in the ID3v1 genre list
+fn id3v1_genre_index(data: &[&AtomData]) -> Option<u16> {
+	match data {
+		[AtomData::UTF8(genre)] => GENRES
+			.iter()
+			.position(|g| g == genre)
+			.map(|index| index as u16),
+		_ => None,
+	}
+}
+
 fn write_freeform(mean: &str, name: &str, writer: &mut Cursor<Vec<u8>>) -> Result<()> {
 	// ---- : ???? : ????
 
@@ -404,7 +634,34 @@ where
 			AtomData::Picture(ref pic) => write_picture(pic, writer)?,
 			AtomData::SignedInteger(int) => write_signed_int(*int, writer)?,
 			AtomData::UnsignedInteger(uint) => write_unsigned_int(*uint, writer)?,
-			AtomData::Bool(b) => write_signed_int(i32::from(*b), writer)?,
+			AtomData::Float32(f) => write_data(BE_FLOAT32, &f.to_be_bytes(), writer)?,
+			AtomData::Float64(f) => write_data(BE_FLOAT64, &f.to_be_bytes(), writer)?,
+			AtomData::SignedInteger8(int) => {
+				write_data(SIGNED_8BIT_INTEGER, &int.to_be_bytes(), writer)?
+			},
+			AtomData::SignedInteger16(int) => {
+				write_data(BE_16BIT_SIGNED_INTEGER, &int.to_be_bytes(), writer)?
+			},
+			AtomData::SignedInteger32(int) => {
+				write_data(BE_32BIT_SIGNED_INTEGER, &int.to_be_bytes(), writer)?
+			},
+			AtomData::SignedInteger64(int) => {
+				write_data(BE_64BIT_SIGNED_INTEGER, &int.to_be_bytes(), writer)?
+			},
+			AtomData::UnsignedInteger8(uint) => {
+				write_data(UNSIGNED_8BIT_INTEGER, &uint.to_be_bytes(), writer)?
+			},
+			AtomData::UnsignedInteger16(uint) => {
+				write_data(BE_16BIT_UNSIGNED_INTEGER, &uint.to_be_bytes(), writer)?
+			},
+			AtomData::UnsignedInteger32(uint) => {
+				write_data(BE_32BIT_UNSIGNED_INTEGER, &uint.to_be_bytes(), writer)?
+			},
+			AtomData::UnsignedInteger64(uint) => {
+				write_data(BE_64BIT_UNSIGNED_INTEGER, &uint.to_be_bytes(), writer)?
+			},
+			// iTunes writes flags as a single byte
+			AtomData::Bool(b) => write_data(BE_SIGNED_INTEGER, &[u8::from(*b)], writer)?,
 			AtomData::Unknown { code, ref data } => write_data(*code, data, writer)?,
 		};
 	}
@@ -3,8 +3,12 @@
 //! ## File notes
 //!
 //! The only supported tag format is [`Ilst`].
+//!
+//! Fragmented files are supported for reading. Tags can only be written to them if the new tag fits in the
+//! space already available in the `moov` atom, see [`Mp4File::is_fragmented`].
 mod atom_info;
 pub(crate) mod ilst;
+mod moof;
 mod moov;
 mod properties;
 mod read;
@@ -26,6 +30,8 @@ pub use ilst::atom::{AdvisoryRating, Atom, AtomData};
 pub use ilst::Ilst;
 
 pub(crate) use properties::SAMPLE_RATES;
+#[cfg(feature = "fs")]
+pub(crate) use read::ilst_range;
 
 /// An MP4 file
 #[derive(LoftyFile)]
@@ -33,11 +39,16 @@ pub(crate) use properties::SAMPLE_RATES;
 pub struct Mp4File {
 	/// The file format from ftyp's "major brand" (Ex. "M4A ")
 	pub(crate) ftyp: String,
+	/// Whether the file is fragmented (has a `moov.mvex` atom)
+	pub(crate) fragmented: bool,
 	#[lofty(tag_type = "Mp4Ilst")]
 	/// The parsed `ilst` (metadata) atom, if it exists
 	pub(crate) ilst_tag: Option<Ilst>,
 	/// The file's audio properties
 	pub(crate) properties: Mp4Properties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
 
 impl Mp4File {
@@ -59,4 +70,29 @@ impl Mp4File {
 	pub fn ftyp(&self) -> &str {
 		self.ftyp.as_ref()
 	}
+
+	/// Whether the file is fragmented
+	///
+	/// A fragmented file (such as one produced by `ffmpeg -movflags frag_keyframe`) stores its samples
+	/// in `moof` atoms following the `moov` atom. Tags can only be written to these files if they fit in
+	/// the existing space, see [`ErrorKind::FragmentedFile`](crate::error::ErrorKind::FragmentedFile).
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::mp4::Mp4File;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut m4a_reader = std::io::Cursor::new(&[]);
+	/// let m4a_file = Mp4File::read_from(&mut m4a_reader, ParseOptions::new())?;
+	///
+	/// if m4a_file.is_fragmented() {
+	/// 	println!("This file is fragmented");
+	/// }
+	/// # Ok(()) }
+	/// ```
+	pub fn is_fragmented(&self) -> bool {
+		self.fragmented
+	}
 }
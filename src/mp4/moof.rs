@@ -0,0 +1,260 @@
+use super::atom_info::{AtomIdent, AtomInfo};
+use super::moov::Moov;
+use super::read::{skip_unneeded, AtomReader};
+use crate::error::Result;
+use crate::macros::{decode_err, try_vec};
+use crate::ParsingMode;
+
+use std::io::{Read, Seek, SeekFrom};
+
+// tfhd flags
+const BASE_DATA_OFFSET_PRESENT: u32 = 0x01;
+const SAMPLE_DESCRIPTION_INDEX_PRESENT: u32 = 0x02;
+const DEFAULT_SAMPLE_DURATION_PRESENT: u32 = 0x08;
+
+// trun flags
+const DATA_OFFSET_PRESENT: u32 = 0x01;
+const FIRST_SAMPLE_FLAGS_PRESENT: u32 = 0x04;
+const SAMPLE_DURATION_PRESENT: u32 = 0x100;
+const SAMPLE_SIZE_PRESENT: u32 = 0x200;
+const SAMPLE_FLAGS_PRESENT: u32 = 0x400;
+const SAMPLE_COMPOSITION_TIME_OFFSET_PRESENT: u32 = 0x800;
+
+/// The information we need from the fragments of a fragmented file
+pub(super) struct Fragments {
+	/// The duration of the entire movie from moov.mvex.mehd, in the movie timescale
+	pub(super) fragment_duration: Option<u64>,
+	/// The sum of the track's sample durations across all moof.traf atoms, in the media timescale
+	pub(super) sample_duration: u64,
+	/// The total length of all mdat atoms
+	pub(super) mdat_length: u64,
+}
+
+impl Fragments {
+	/// Reads the fragments of the track `track_id`
+	///
+	/// If `track_id` is `None`, every track's fragments will be used.
+	pub(super) fn read<R>(
+		reader: &mut AtomReader<R>,
+		moov: &Moov,
+		track_id: Option<u32>,
+		parse_mode: ParsingMode,
+	) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let Some(mvex) = &moov.mvex else {
+			decode_err!(@BAIL Mp4, "Expected atom \"moov.mvex\" in a fragmented file");
+		};
+
+		let (fragment_duration, default_sample_duration) =
+			read_mvex(reader, mvex, track_id, parse_mode)?;
+
+		let mut sample_duration = 0;
+		let mut mdat_length = 0;
+
+		reader.rewind()?;
+		loop {
+			let atom = match reader.next() {
+				Ok(Some(atom)) => atom,
+				Ok(None) => break,
+				Err(e) if parse_mode == ParsingMode::Strict => return Err(e),
+				Err(e) => {
+					log::warn!(
+						"Failed to read a top-level atom, ignoring the rest of the file: {e}"
+					);
+					break;
+				},
+			};
+
+			match atom.ident {
+				AtomIdent::Fourcc(ref fourcc) if fourcc == b"moof" => {
+					let end = atom.start + atom.len;
+					while reader.stream_position()? < end {
+						let Some(traf) = reader.next()? else {
+							break;
+						};
+
+						if traf.ident == AtomIdent::Fourcc(*b"traf") {
+							sample_duration +=
+								traf_duration(reader, &traf, track_id, default_sample_duration)?;
+						}
+
+						reader.seek(SeekFrom::Start(traf.start + traf.len))?;
+					}
+
+					reader.seek(SeekFrom::Start(end))?;
+				},
+				AtomIdent::Fourcc(ref fourcc) if fourcc == b"mdat" => {
+					mdat_length += atom.len;
+					skip_unneeded(reader, atom.extended, atom.len)?;
+				},
+				_ => skip_unneeded(reader, atom.extended, atom.len)?,
+			}
+		}
+
+		Ok(Self {
+			fragment_duration,
+			sample_duration,
+			mdat_length,
+		})
+	}
+}
+
+// Returns the fragment duration from mehd, and the default sample duration for the track from trex
+fn read_mvex<R>(
+	reader: &mut AtomReader<R>,
+	mvex: &AtomInfo,
+	track_id: Option<u32>,
+	parse_mode: ParsingMode,
+) -> Result<(Option<u64>, u32)>
+where
+	R: Read + Seek,
+{
+	let mut fragment_duration = None;
+	let mut default_sample_duration = None;
+
+	reader.seek(SeekFrom::Start(mvex.start + 8))?;
+
+	let mut read = 8;
+	while read < mvex.len {
+		let Some(atom) = AtomInfo::read(reader, mvex.len - read, parse_mode)? else {
+			break;
+		};
+
+		read += atom.len;
+
+		match atom.ident {
+			AtomIdent::Fourcc(ref fourcc) if fourcc == b"mehd" => {
+				let version = reader.read_u8()?;
+				let _flags = reader.read_uint(3)?;
+
+				let duration = if version == 1 {
+					reader.read_u64()?
+				} else {
+					u64::from(reader.read_u32()?)
+				};
+
+				// A zero duration means it's unknown
+				if duration > 0 {
+					fragment_duration = Some(duration);
+				}
+			},
+			AtomIdent::Fourcc(ref fourcc)
+				if fourcc == b"trex" && default_sample_duration.is_none() =>
+			{
+				// Version (1)
+				// Flags (3)
+				let _version_flags = reader.read_u32()?;
+				let trex_track_id = reader.read_u32()?;
+
+				if track_id.is_none_or(|track_id| track_id == trex_track_id) {
+					let _default_sample_description_index = reader.read_u32()?;
+					default_sample_duration = Some(reader.read_u32()?);
+				}
+			},
+			_ => {},
+		}
+
+		reader.seek(SeekFrom::Start(atom.start + atom.len))?;
+	}
+
+	Ok((fragment_duration, default_sample_duration.unwrap_or(0)))
+}
+
+fn traf_duration<R>(
+	reader: &mut AtomReader<R>,
+	traf: &AtomInfo,
+	track_id: Option<u32>,
+	mut default_sample_duration: u32,
+) -> Result<u64>
+where
+	R: Read + Seek,
+{
+	let mut duration = 0;
+
+	let end = traf.start + traf.len;
+	while reader.stream_position()? < end {
+		let Some(atom) = reader.next()? else {
+			break;
+		};
+
+		let AtomIdent::Fourcc(ref fourcc) = atom.ident else {
+			skip_unneeded(reader, atom.extended, atom.len)?;
+			continue;
+		};
+
+		match fourcc {
+			b"tfhd" => {
+				let flags = reader.read_u32()? & 0x00FF_FFFF;
+				let traf_track_id = reader.read_u32()?;
+
+				if track_id.is_some_and(|track_id| track_id != traf_track_id) {
+					// This fragment belongs to another track
+					return Ok(0);
+				}
+
+				if flags & BASE_DATA_OFFSET_PRESENT != 0 {
+					reader.seek(SeekFrom::Current(8))?;
+				}
+
+				if flags & SAMPLE_DESCRIPTION_INDEX_PRESENT != 0 {
+					reader.seek(SeekFrom::Current(4))?;
+				}
+
+				if flags & DEFAULT_SAMPLE_DURATION_PRESENT != 0 {
+					default_sample_duration = reader.read_u32()?;
+				}
+			},
+			b"trun" => {
+				let flags = reader.read_u32()? & 0x00FF_FFFF;
+				let sample_count = reader.read_u32()?;
+
+				if flags & SAMPLE_DURATION_PRESENT == 0 {
+					duration += u64::from(sample_count) * u64::from(default_sample_duration);
+				} else {
+					// The atom header, followed by the version, flags, and sample count
+					let mut header_size = atom.header_len() + 8;
+					if flags & DATA_OFFSET_PRESENT != 0 {
+						header_size += 4;
+					}
+					if flags & FIRST_SAMPLE_FLAGS_PRESENT != 0 {
+						header_size += 4;
+					}
+
+					reader.seek(SeekFrom::Start(atom.start + header_size))?;
+
+					// Each sample has a duration, possibly followed by its size, flags, and composition time offset
+					let sample_size = 4
+						* (1 + [
+							SAMPLE_SIZE_PRESENT,
+							SAMPLE_FLAGS_PRESENT,
+							SAMPLE_COMPOSITION_TIME_OFFSET_PRESENT,
+						]
+						.into_iter()
+						.filter(|flag| flags & flag != 0)
+						.count() as u64);
+
+					let table_size = u64::from(sample_count) * sample_size;
+					if table_size > atom.len.saturating_sub(header_size) {
+						decode_err!(@BAIL Mp4, "\"trun\" atom is too small for its sample count");
+					}
+
+					let mut table = try_vec![0; table_size as usize];
+					reader.read_exact(&mut table)?;
+
+					for sample in table.chunks_exact(sample_size as usize) {
+						let sample_duration =
+							u32::from_be_bytes([sample[0], sample[1], sample[2], sample[3]]);
+						duration += u64::from(sample_duration);
+					}
+				}
+			},
+			_ => {},
+		}
+
+		reader.seek(SeekFrom::Start(atom.start + atom.len))?;
+	}
+
+	Ok(duration)
+}
@@ -1,20 +1,32 @@
 use super::atom_info::{AtomIdent, AtomInfo};
 use super::ilst::read::parse_ilst;
 use super::ilst::Ilst;
-use super::read::{meta_is_full, nested_atom, skip_unneeded, AtomReader};
+use super::read::{meta_is_full, skip_unneeded, AtomReader};
 use crate::error::Result;
 use crate::macros::decode_err;
 use crate::ParsingMode;
 
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 
 pub(crate) struct Moov {
-	// Represents the trak.mdia atom
-	pub(crate) traks: Vec<AtomInfo>,
+	pub(crate) traks: Vec<Trak>,
+	// Represents the mvhd atom, needed for the movie timescale
+	pub(crate) mvhd: Option<AtomInfo>,
+	// Represents the mvex atom, only present in fragmented files
+	pub(crate) mvex: Option<AtomInfo>,
 	// Represents a parsed moov.udta.meta.ilst
 	pub(crate) meta: Option<Ilst>,
 }
 
+pub(crate) struct Trak {
+	// The track ID from trak.tkhd, used to find the track's fragments
+	pub(crate) id: Option<u32>,
+	// Represents the trak.mdia atom
+	pub(crate) mdia: AtomInfo,
+	// Represents the trak.edts atom, holding the edit list
+	pub(crate) edts: Option<AtomInfo>,
+}
+
 impl Moov {
 	pub(super) fn find<R>(reader: &mut AtomReader<R>) -> Result<AtomInfo>
 	where
@@ -43,18 +55,26 @@ impl Moov {
 		R: Read + Seek,
 	{
 		let mut traks = Vec::new();
+		let mut mvhd = None;
+		let mut mvex = None;
 		let mut meta = None;
 
 		while let Ok(Some(atom)) = reader.next() {
 			if let AtomIdent::Fourcc(fourcc) = atom.ident {
 				match &fourcc {
 					b"trak" if read_properties => {
-						// All we need from here is trak.mdia
-						if let Some(mdia) = nested_atom(reader, atom.len, b"mdia", parse_mode)? {
-							skip_unneeded(reader, mdia.extended, mdia.len)?;
-							traks.push(mdia);
+						if let Some(trak) = Trak::parse(reader, atom.len, parse_mode)? {
+							traks.push(trak);
 						}
 					},
+					b"mvhd" if read_properties => {
+						skip_unneeded(reader, atom.extended, atom.len)?;
+						mvhd = Some(atom);
+					},
+					b"mvex" => {
+						skip_unneeded(reader, atom.extended, atom.len)?;
+						mvex = Some(atom);
+					},
 					b"udta" => {
 						meta = meta_from_udta(reader, parse_mode, atom.len - 8)?;
 					},
@@ -67,7 +87,77 @@ impl Moov {
 			skip_unneeded(reader, atom.extended, atom.len)?
 		}
 
-		Ok(Self { traks, meta })
+		Ok(Self {
+			traks,
+			mvhd,
+			mvex,
+			meta,
+		})
+	}
+
+	/// Whether the file is fragmented, with its samples described in `moof` atoms rather than the `moov`
+	pub(crate) fn is_fragmented(&self) -> bool {
+		self.mvex.is_some()
+	}
+}
+
+impl Trak {
+	fn parse<R>(
+		reader: &mut AtomReader<R>,
+		len: u64,
+		parse_mode: ParsingMode,
+	) -> Result<Option<Self>>
+	where
+		R: Read + Seek,
+	{
+		// All we need from here is trak.tkhd, trak.mdia, and trak.edts
+		let mut id = None;
+		let mut mdia = None;
+		let mut edts = None;
+
+		let mut read = 8;
+		while read < len {
+			let Some(atom) = AtomInfo::read(reader, len - read, parse_mode)? else {
+				break;
+			};
+
+			read += atom.len;
+
+			match atom.ident {
+				AtomIdent::Fourcc(ref fourcc) if fourcc == b"tkhd" => {
+					let version = reader.read_u8()?;
+					let _flags = reader.read_uint(3)?;
+
+					// Skipping the creation and modification times
+					if version == 1 {
+						reader.seek(SeekFrom::Current(16))?;
+					} else {
+						reader.seek(SeekFrom::Current(8))?;
+					}
+
+					id = Some(reader.read_u32()?);
+
+					let read_size = if version == 1 { 24 } else { 16 };
+					let Some(remaining) = atom.len.checked_sub(read_size).filter(|r| *r >= 8)
+					else {
+						decode_err!(@BAIL Mp4, "Track header atom is too small");
+					};
+
+					skip_unneeded(reader, atom.extended, remaining)?;
+				},
+				AtomIdent::Fourcc(ref fourcc) if fourcc == b"mdia" => {
+					skip_unneeded(reader, atom.extended, atom.len)?;
+					mdia = Some(atom);
+				},
+				AtomIdent::Fourcc(ref fourcc) if fourcc == b"edts" => {
+					skip_unneeded(reader, atom.extended, atom.len)?;
+					edts = Some(atom);
+				},
+				_ => skip_unneeded(reader, atom.extended, atom.len)?,
+			}
+		}
+
+		Ok(mdia.map(|mdia| Self { id, mdia, edts }))
 	}
 }
 
@@ -104,7 +194,7 @@ where
 
 	// It's possible for the `meta` atom to be non-full,
 	// so we have to check for that case
-	let full_meta_atom = meta_is_full(reader)?;
+	let full_meta_atom = meta_is_full(reader, parsing_mode)?;
 
 	if full_meta_atom {
 		read = 12;
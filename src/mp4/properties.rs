@@ -1,10 +1,14 @@
 use super::atom_info::{AtomIdent, AtomInfo};
+use super::moof::Fragments;
+use super::moov::Moov;
 use super::read::{nested_atom, skip_unneeded, AtomReader};
 use crate::error::{LoftyError, Result};
 use crate::macros::{decode_err, err, try_vec};
 use crate::math::RoundedDivision;
 use crate::probe::ParsingMode;
-use crate::properties::FileProperties;
+use crate::properties::{
+	bitrate_source, non_zero, BitrateSource, FileProperties, GaplessInfo, GaplessSource,
+};
 
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::time::Duration;
@@ -143,18 +147,23 @@ pub struct Mp4Properties {
 	pub(crate) bit_depth: Option<u8>,
 	pub(crate) channels: u8,
 	pub(crate) drm_protected: bool,
+	pub(crate) gapless_info: Option<GaplessInfo>,
+	pub(crate) audio_bitrate_source: BitrateSource,
 }
 
 impl From<Mp4Properties> for FileProperties {
 	fn from(input: Mp4Properties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
 			bit_depth: input.bit_depth,
-			channels: Some(input.channels),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: input.gapless_info,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, input.audio_bitrate_source),
 		}
 	}
 }
@@ -205,14 +214,28 @@ impl Mp4Properties {
 	}
 
 	/// Whether or not the file is DRM protected
+	///
+	/// This is the case for old iTunes purchases (`.m4p`), which have a `drms` sample entry rather than `mp4a`.
+	/// The tags of these files are not encrypted, and can be read and written as usual, but all audio properties
+	/// other than the [duration](Mp4Properties::duration) will be zeroed.
 	pub fn is_drm_protected(&self) -> bool {
 		self.drm_protected
 	}
+
+	/// Encoder delay and padding
+	///
+	/// This is read from the iTunes `iTunSMPB` atom, falling back to the audio track's edit list. The
+	/// edit list is only used when it trims the track.
+	///
+	/// See [`GaplessInfo`]
+	pub fn gapless_info(&self) -> Option<GaplessInfo> {
+		self.gapless_info
+	}
 }
 
 pub(super) fn read_properties<R>(
 	reader: &mut AtomReader<R>,
-	traks: &[AtomInfo],
+	moov: &Moov,
 	file_length: u64,
 	parse_mode: ParsingMode,
 ) -> Result<Mp4Properties>
@@ -221,15 +244,21 @@ where
 {
 	// We need the mdhd and minf atoms from the audio track
 	let mut audio_track = false;
+	let mut audio_track_id = None;
+	let mut audio_track_edts = None;
 	let mut mdhd = None;
 	let mut minf = None;
 
 	// We have to search through the traks with a mdia atom to find the audio track
-	for mdia in traks.iter() {
+	for trak in &moov.traks {
 		if audio_track {
 			break;
 		}
 
+		let mdia = &trak.mdia;
+		audio_track_id = trak.id;
+		audio_track_edts = trak.edts.as_ref();
+
 		reader.seek(SeekFrom::Start(mdia.start + 8))?;
 
 		let mut read = 8;
@@ -303,7 +332,38 @@ where
 		(timescale, u64::from(duration))
 	};
 
-	let duration_millis = (duration * 1000).div_round(u64::from(timescale));
+	// In a fragmented file, the samples (and their durations) are spread across the `moof` atoms
+	let fragments = if moov.is_fragmented() {
+		Some(Fragments::read(reader, moov, audio_track_id, parse_mode)?)
+	} else {
+		None
+	};
+
+	let duration_millis = match fragments {
+		Some(Fragments {
+			fragment_duration: Some(fragment_duration),
+			..
+		}) => {
+			// The fragment duration uses the movie timescale, rather than the media timescale
+			match movie_timescale(reader, moov)? {
+				Some(movie_timescale) => {
+					(fragment_duration * 1000).div_round(u64::from(movie_timescale))
+				},
+				None => 0,
+			}
+		},
+		Some(Fragments {
+			sample_duration, ..
+		}) => ((duration + sample_duration) * 1000).div_round(u64::from(timescale)),
+		None => (duration * 1000).div_round(u64::from(timescale)),
+	};
+
+	// The track's duration in the media timescale, used for the edit list
+	let media_duration = duration
+		+ fragments
+			.as_ref()
+			.map_or(0, |fragments| fragments.sample_duration);
+
 	let duration = Duration::from_millis(duration_millis);
 
 	// We create the properties here, since it is possible the other information isn't available
@@ -356,10 +416,17 @@ where
 			// TODO: dops (opus)
 			// TODO: wave (https://developer.apple.com/library/archive/documentation/QuickTime/QTFF/QTFFChap3/qtff3.html#//apple_ref/doc/uid/TP40000939-CH205-134202)
 
-			// Special case to detect encrypted files
-			b"drms" => {
+			// Special case to detect encrypted files (iTunes FairPlay)
+			//
+			// The sample entry itself isn't encrypted, but the values within it can't be trusted
+			// to describe the audio, so they're left zeroed.
+			b"drms" | b"drmi" => {
+				log::warn!(
+					"File is DRM protected ({:?} sample entry), audio properties are unavailable",
+					fourcc.escape_ascii().to_string()
+				);
 				properties.drm_protected = true;
-				skip_unneeded(reader, atom.extended, atom.len)?;
+				skip_unneeded(&mut stsd_reader, atom.extended, atom.len)?;
 				continue;
 			},
 			_ => {
@@ -367,7 +434,7 @@ where
 					"Found unsupported sample entry: {:?}",
 					fourcc.escape_ascii().to_string()
 				);
-				skip_unneeded(reader, atom.extended, atom.len)?;
+				skip_unneeded(&mut stsd_reader, atom.extended, atom.len)?;
 				continue;
 			},
 		}
@@ -379,8 +446,13 @@ where
 			properties.overall_bitrate = overall_bitrate as u32;
 
 			if properties.audio_bitrate == 0 {
-				properties.audio_bitrate =
-					(u128::from(mdat_length(reader)? * 8) / duration_millis) as u32;
+				let mdat_length = match fragments {
+					Some(ref fragments) => fragments.mdat_length,
+					None => mdat_length(reader)?,
+				};
+
+				properties.audio_bitrate = (u128::from(mdat_length * 8) / duration_millis) as u32;
+				properties.audio_bitrate_source = BitrateSource::Computed;
 			}
 		}
 
@@ -389,9 +461,111 @@ where
 		break;
 	}
 
+	if let Some(edts) = audio_track_edts {
+		properties.gapless_info = edit_list_gapless_info(
+			reader,
+			edts,
+			moov,
+			(timescale, media_duration),
+			properties.sample_rate,
+			parse_mode,
+		)?;
+	}
+
 	Ok(properties)
 }
 
+// Reads the encoder delay and padding from trak.edts.elst
+//
+// Encoders that can't write an `iTunSMPB` atom (e.g. FFmpeg) trim the delay and padding with a single
+// edit, starting at the first valid sample and lasting for the duration of the valid samples.
+fn edit_list_gapless_info<R>(
+	reader: &mut AtomReader<R>,
+	edts: &AtomInfo,
+	moov: &Moov,
+	(media_timescale, media_duration): (u32, u64),
+	sample_rate: u32,
+	parse_mode: ParsingMode,
+) -> Result<Option<GaplessInfo>>
+where
+	R: Read + Seek,
+{
+	reader.seek(SeekFrom::Start(edts.start + 8))?;
+
+	let Some(_elst) = nested_atom(reader, edts.len, b"elst", parse_mode)? else {
+		return Ok(None);
+	};
+
+	let version = reader.read_u8()?;
+	let _flags = reader.read_uint(3)?;
+
+	let entry_count = reader.read_u32()?;
+
+	let mut edit = None;
+	for _ in 0..entry_count {
+		let (segment_duration, media_time) = if version == 1 {
+			(reader.read_u64()?, reader.read_u64()? as i64)
+		} else {
+			(
+				u64::from(reader.read_u32()?),
+				i64::from(reader.read_u32()? as i32),
+			)
+		};
+
+		// Media rate
+		let _media_rate = reader.read_u32()?;
+
+		// Empty edits (-1) only delay the presentation, they don't trim anything
+		if media_time == -1 {
+			continue;
+		}
+
+		// Anything beyond a single edit is not a gapless edit list
+		if edit.is_some() || media_time < 0 {
+			return Ok(None);
+		}
+
+		edit = Some((segment_duration, media_time as u64));
+	}
+
+	let Some((segment_duration, media_time)) = edit else {
+		return Ok(None);
+	};
+
+	// The segment duration uses the movie timescale, rather than the media timescale
+	let Some(movie_timescale) = movie_timescale(reader, moov)? else {
+		return Ok(None);
+	};
+
+	let valid_samples = (u128::from(segment_duration) * u128::from(media_timescale))
+		.div_round(u128::from(movie_timescale)) as u64;
+
+	// An edit that covers the entire track doesn't trim anything
+	if media_time == 0 && valid_samples >= media_duration {
+		return Ok(None);
+	}
+
+	let padding = media_duration
+		.saturating_sub(media_time)
+		.saturating_sub(valid_samples);
+
+	// The media timescale is almost always the sample rate, but not necessarily
+	let to_samples = |value: u64| -> u64 {
+		if sample_rate == 0 || sample_rate == media_timescale {
+			return value;
+		}
+
+		(u128::from(value) * u128::from(sample_rate)).div_round(u128::from(media_timescale)) as u64
+	};
+
+	Ok(Some(GaplessInfo {
+		encoder_delay_samples: to_samples(media_time) as u32,
+		encoder_padding_samples: Some(to_samples(padding) as u32),
+		valid_samples: to_samples(valid_samples),
+		source: GaplessSource::EditList,
+	}))
+}
+
 // https://wiki.multimedia.cx/index.php?title=MPEG-4_Audio#Sampling_Frequencies
 pub(crate) const SAMPLE_RATES: [u32; 15] = [
 	96000, 88200, 64000, 48000, 44100, 32000, 24000, 22050, 16000, 12000, 11025, 8000, 7350, 0, 0,
@@ -559,6 +733,7 @@ where
 
 			if average_bitrate > 0 || properties.duration.is_zero() {
 				properties.audio_bitrate = average_bitrate / 1000;
+				properties.audio_bitrate_source = BitrateSource::Declared;
 			}
 		}
 	}
@@ -618,6 +793,7 @@ where
 	stsd.seek(SeekFrom::Current(6))?;
 
 	properties.audio_bitrate = stsd.read_u32()? / 1000;
+	properties.audio_bitrate_source = BitrateSource::Declared;
 	properties.sample_rate = stsd.read_u32()?;
 
 	Ok(())
@@ -684,6 +860,35 @@ where
 	Ok(())
 }
 
+// Reads the timescale from moov.mvhd
+fn movie_timescale<R>(reader: &mut AtomReader<R>, moov: &Moov) -> Result<Option<u32>>
+where
+	R: Read + Seek,
+{
+	let Some(mvhd) = &moov.mvhd else {
+		return Ok(None);
+	};
+
+	reader.seek(SeekFrom::Start(mvhd.start + 8))?;
+
+	let version = reader.read_u8()?;
+	let _flags = reader.read_uint(3)?;
+
+	// Skipping the creation and modification times
+	if version == 1 {
+		reader.seek(SeekFrom::Current(16))?;
+	} else {
+		reader.seek(SeekFrom::Current(8))?;
+	}
+
+	let timescale = reader.read_u32()?;
+	if timescale == 0 {
+		return Ok(None);
+	}
+
+	Ok(Some(timescale))
+}
+
 // Used to calculate the bitrate, when it isn't readily available to us
 fn mdat_length<R>(reader: &mut AtomReader<R>) -> Result<u64>
 where
@@ -1,11 +1,12 @@
 use super::atom_info::{AtomIdent, AtomInfo};
 use super::moov::Moov;
 use super::properties::Mp4Properties;
-use super::Mp4File;
+use super::{Ilst, Mp4File};
 use crate::error::{ErrorKind, LoftyError, Result};
 use crate::macros::{decode_err, err};
 use crate::probe::{ParseOptions, ParsingMode};
 use crate::traits::SeekStreamLen;
+use crate::util::io::OffsetFile;
 use crate::util::text::utf8_decode_str;
 
 use std::io::{Read, Seek, SeekFrom};
@@ -179,6 +180,8 @@ pub(crate) fn read_from<R>(data: &mut R, parse_options: ParseOptions) -> Result<
 where
 	R: Read + Seek,
 {
+	let data = &mut OffsetFile::new(data, parse_options.start_offset)?;
+
 	let mut reader = AtomReader::new(data, parse_options.parsing_mode)?;
 	let file_length = reader.stream_len()?;
 
@@ -194,24 +197,67 @@ where
 		parse_options.read_properties,
 	)?;
 
+	let properties = if parse_options.read_properties {
+		// Remove the length restriction
+		reader.reset_bounds(0, file_length);
+		let mut properties = super::properties::read_properties(
+			&mut reader,
+			&moov,
+			file_length,
+			parse_options.parsing_mode,
+		)?;
+
+		// iTunes stores the gapless information in the tag, which is preferred over the edit list
+		if let Some(gapless_info) = moov.meta.as_ref().and_then(Ilst::gapless_info) {
+			properties.gapless_info = Some(gapless_info);
+		}
+
+		properties
+	} else {
+		Mp4Properties::default()
+	};
+
 	Ok(Mp4File {
 		ftyp,
+		fragmented: moov.is_fragmented(),
 		ilst_tag: moov.meta,
-		properties: if parse_options.read_properties {
-			// Remove the length restriction
-			reader.reset_bounds(0, file_length);
-			super::properties::read_properties(
-				&mut reader,
-				&moov.traks,
-				file_length,
-				parse_options.parsing_mode,
-			)?
-		} else {
-			Mp4Properties::default()
-		},
+		properties,
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	})
 }
 
+// The position of the `moov.udta.meta.ilst` atom, if there is one
+#[cfg(feature = "fs")]
+pub(crate) fn ilst_range<R>(data: &mut R) -> Result<Option<std::ops::Range<u64>>>
+where
+	R: Read + Seek,
+{
+	let parse_mode = ParseOptions::DEFAULT_PARSING_MODE;
+
+	let mut reader = AtomReader::new(data, parse_mode)?;
+	verify_mp4(&mut reader)?;
+
+	let moov = Moov::find(&mut reader)?;
+	let Some(udta) = nested_atom(&mut reader, moov.len - 8, b"udta", parse_mode)? else {
+		return Ok(None);
+	};
+
+	let Some(meta) = nested_atom(&mut reader, udta.len - 8, b"meta", parse_mode)? else {
+		return Ok(None);
+	};
+
+	// We may encounter a non-full `meta` atom
+	let meta_header_len = if meta_is_full(&mut reader, parse_mode)? {
+		12
+	} else {
+		8
+	};
+
+	let ilst = nested_atom(&mut reader, meta.len - meta_header_len, b"ilst", parse_mode)?;
+	Ok(ilst.map(|ilst| ilst.start..ilst.start + ilst.len))
+}
+
 pub(super) fn skip_unneeded<R>(reader: &mut R, ext: bool, len: u64) -> Result<()>
 where
 	R: Read + Seek,
@@ -301,7 +347,7 @@ where
 	Ok((found_idx, buf))
 }
 
-pub(super) fn meta_is_full<R>(reader: &mut R) -> Result<bool>
+pub(super) fn meta_is_full<R>(reader: &mut R, parse_mode: ParsingMode) -> Result<bool>
 where
 	R: Read + Seek,
 {
@@ -310,7 +356,7 @@ where
 	// Version (1)
 	// Flags (3)
 	//
-	// However, it's possible that it is written as a normal atom,
+	// However, it's possible that it is written as a normal atom (e.g. by some Android muxers),
 	// meaning this would be the size of the next atom.
 	let _version_flags = reader.read_u32::<BigEndian>()?;
 
@@ -319,7 +365,11 @@ where
 	reader.read_exact(&mut possible_ident)?;
 
 	match &possible_ident {
-		b"hdlr" | b"ilst" | b"mhdr" | b"ctry" | b"lang" => {
+		b"hdlr" | b"ilst" | b"mhdr" | b"ctry" | b"lang" | b"keys" | b"free" => {
+			if parse_mode == ParsingMode::Strict {
+				decode_err!(@BAIL Mp4, "\"meta\" atom is missing its version and flags");
+			}
+
 			reader.seek(SeekFrom::Current(-8))?;
 			Ok(false)
 		},
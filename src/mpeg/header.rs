@@ -1,4 +1,5 @@
 use super::constants::{BITRATES, PADDING_SIZES, SAMPLES, SAMPLE_RATES, SIDE_INFORMATION_SIZES};
+use super::lame::LameHeader;
 use crate::error::Result;
 use crate::macros::decode_err;
 
@@ -271,9 +272,13 @@ impl Header {
 	}
 }
 
+// The largest Xing header, with all optional fields and a LAME tag present
+pub(super) const MAX_XING_HEADER_SIZE: usize = 156;
+
 pub(super) struct XingHeader {
 	pub frames: u32,
 	pub size: u32,
+	pub lame: Option<LameHeader>,
 }
 
 impl XingHeader {
@@ -302,7 +307,9 @@ impl XingHeader {
 				let frames = reader.read_u32::<BigEndian>()?;
 				let size = reader.read_u32::<BigEndian>()?;
 
-				Ok(Some(Self { frames, size }))
+				let lame = Self::read_lame_tag(reader, flags[3]);
+
+				Ok(Some(Self { frames, size, lame }))
 			},
 			b"VBRI" => {
 				if reader_len < 32 {
@@ -318,12 +325,34 @@ impl XingHeader {
 				let size = reader.read_u32::<BigEndian>()?;
 				let frames = reader.read_u32::<BigEndian>()?;
 
-				Ok(Some(Self { frames, size }))
+				Ok(Some(Self {
+					frames,
+					size,
+					lame: None,
+				}))
 			},
 			_ => Ok(None),
 		}
 	}
 
+	fn read_lame_tag(reader: &mut &[u8], flags: u8) -> Option<LameHeader> {
+		// The TOC (100) and quality indicator (4) are optional
+		let mut offset = 0;
+		if flags & 0x04 == 0x04 {
+			offset += 100;
+		}
+
+		let mut quality = None;
+		if flags & 0x08 == 0x08 {
+			let quality_bytes = reader.get(offset..offset + 4)?;
+			let quality_indicator = u32::from_be_bytes(quality_bytes.try_into().ok()?);
+			quality = u8::try_from(quality_indicator).ok().filter(|q| *q <= 100);
+			offset += 4;
+		}
+
+		LameHeader::parse(reader.get(offset..)?, quality)
+	}
+
 	pub(super) fn is_valid(&self) -> bool {
 		self.frames > 0 && self.size > 0
 	}
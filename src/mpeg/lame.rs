@@ -0,0 +1,269 @@
+// http://gabriel.mp3-tech.org/mp3infotag.html
+
+/// The LAME tag, found after the Xing header of the first MPEG frame
+///
+/// This describes how the stream was encoded. FFmpeg writes the same tag with its own encoder
+/// string, but leaves most of the fields zeroed.
+///
+/// This is useful for identifying transcodes, for example:
+///
+/// * A [`LameHeader::source_sample_rate`] that doesn't match the stream's sample rate
+/// * A [`LameHeader::lowpass`] far below what the bitrate would normally use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct LameHeader {
+	pub(crate) encoder: [u8; 9],
+	pub(crate) revision: u8,
+	pub(crate) vbr_method: u8,
+	pub(crate) lowpass: u8,
+	pub(crate) quality: Option<u8>,
+	pub(crate) bitrate: u8,
+	pub(crate) encoder_delay: u16,
+	pub(crate) encoder_padding: u16,
+	pub(crate) noise_shaping: u8,
+	pub(crate) source_sample_rate: SourceSampleRate,
+	pub(crate) preset: u16,
+}
+
+impl LameHeader {
+	// The size of the tag, starting at the encoder string
+	pub(super) const SIZE: usize = 36;
+
+	pub(super) fn parse(lame_tag: &[u8], quality: Option<u8>) -> Option<Self> {
+		let lame_tag = lame_tag.get(..Self::SIZE)?;
+
+		if !matches!(&lame_tag[..4], b"LAME" | b"Lavc" | b"Lavf") {
+			return None;
+		}
+
+		let mut encoder = [0; 9];
+		encoder.copy_from_slice(&lame_tag[..9]);
+
+		// Skipping the ReplayGain (8) and encoding flags (1)
+		//
+		// Followed by 12 bits each for the delay and padding
+		let delay_and_padding = &lame_tag[21..24];
+		let encoder_delay =
+			(u16::from(delay_and_padding[0]) << 4) | (u16::from(delay_and_padding[1]) >> 4);
+		let encoder_padding =
+			(u16::from(delay_and_padding[1] & 0x0F) << 8) | u16::from(delay_and_padding[2]);
+
+		let misc = lame_tag[24];
+		let source_sample_rate = match misc >> 6 {
+			0 => SourceSampleRate::UpTo32000,
+			1 => SourceSampleRate::Hz44100,
+			2 => SourceSampleRate::Hz48000,
+			_ => SourceSampleRate::Above48000,
+		};
+
+		// Skipping the MP3 gain (1), the top 5 bits are unused and surround info
+		let preset = u16::from_be_bytes([lame_tag[26], lame_tag[27]]) & 0x07FF;
+
+		Some(Self {
+			encoder,
+			revision: lame_tag[9] >> 4,
+			vbr_method: lame_tag[9] & 0x0F,
+			lowpass: lame_tag[10],
+			quality,
+			bitrate: lame_tag[20],
+			encoder_delay,
+			encoder_padding,
+			noise_shaping: misc & 0x03,
+			source_sample_rate,
+			preset,
+		})
+	}
+
+	/// The encoder and its version, such as `LAME3.100` or `Lavc58.91`
+	///
+	/// This is at most 9 characters, so longer versions are cut off.
+	pub fn encoder(&self) -> &str {
+		let end = self
+			.encoder
+			.iter()
+			.position(|b| *b == 0)
+			.unwrap_or(self.encoder.len());
+
+		match std::str::from_utf8(&self.encoder[..end]) {
+			Ok(encoder) => encoder.trim_end(),
+			Err(e) => std::str::from_utf8(&self.encoder[..e.valid_up_to()]).unwrap_or_default(),
+		}
+	}
+
+	/// The revision of the LAME tag
+	pub fn revision(&self) -> u8 {
+		self.revision
+	}
+
+	/// The VBR method used
+	///
+	/// | Value | Method                  |
+	/// |-------|-------------------------|
+	/// | 0     | Unknown                 |
+	/// | 1     | CBR                     |
+	/// | 2     | ABR                     |
+	/// | 3     | VBR (`--vbr-old`)       |
+	/// | 4     | VBR (`--vbr-mtrh`)      |
+	/// | 5     | VBR (`--vbr-mt`)        |
+	/// | 8     | CBR (2 pass)            |
+	/// | 9     | ABR (2 pass)            |
+	pub fn vbr_method(&self) -> u8 {
+		self.vbr_method
+	}
+
+	/// The lowpass filter frequency (Hz)
+	///
+	/// This is rounded to the nearest 100 Hz, and is `None` if unknown.
+	pub fn lowpass(&self) -> Option<u32> {
+		match self.lowpass {
+			0 => None,
+			lowpass => Some(u32::from(lowpass) * 100),
+		}
+	}
+
+	/// The quality indicator from the Xing header, from 0 (best) to 100 (worst)
+	///
+	/// For LAME, this is derived from the VBR quality (`-V`) and algorithm quality (`-q`) used.
+	pub fn quality(&self) -> Option<u8> {
+		self.quality
+	}
+
+	/// The bitrate (kbps)
+	///
+	/// For ABR this is the target bitrate, for CBR this is the bitrate, and for VBR this is the
+	/// minimum bitrate. A value of 255 means the bitrate is 255 or higher.
+	pub fn bitrate(&self) -> u8 {
+		self.bitrate
+	}
+
+	/// The noise shaping used, from 0 to 3
+	pub fn noise_shaping(&self) -> u8 {
+		self.noise_shaping
+	}
+
+	/// The sample rate of the audio given to the encoder
+	pub fn source_sample_rate(&self) -> SourceSampleRate {
+		self.source_sample_rate
+	}
+
+	/// The preset used, if any
+	pub fn preset(&self) -> Option<LamePreset> {
+		let preset = match self.preset {
+			0 => return None,
+			bitrate @ 8..=320 => LamePreset::Abr(bitrate),
+			level @ 410..=500 if level % 10 == 0 => LamePreset::Vbr(((500 - level) / 10) as u8),
+			1000 => LamePreset::R3mix,
+			1001 => LamePreset::Standard,
+			1002 => LamePreset::Extreme,
+			1003 => LamePreset::Insane,
+			1004 => LamePreset::StandardFast,
+			1005 => LamePreset::ExtremeFast,
+			1006 => LamePreset::Medium,
+			1007 => LamePreset::MediumFast,
+			other => LamePreset::Unknown(other),
+		};
+
+		Some(preset)
+	}
+}
+
+/// The sample rate of the audio given to LAME, see [`LameHeader::source_sample_rate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceSampleRate {
+	/// 32 kHz or lower
+	UpTo32000,
+	/// 44.1 kHz
+	Hz44100,
+	/// 48 kHz
+	Hz48000,
+	/// Higher than 48 kHz
+	Above48000,
+}
+
+/// A LAME preset, see [`LameHeader::preset`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LamePreset {
+	/// An ABR preset, with the target bitrate (kbps)
+	Abr(u16),
+	/// A VBR preset (`-V0` through `-V9`), with the quality level
+	Vbr(u8),
+	/// `--r3mix`
+	R3mix,
+	/// `--preset standard`
+	Standard,
+	/// `--preset extreme`
+	Extreme,
+	/// `--preset insane`
+	Insane,
+	/// `--preset fast standard`
+	StandardFast,
+	/// `--preset fast extreme`
+	ExtremeFast,
+	/// `--preset medium`
+	Medium,
+	/// `--preset fast medium`
+	MediumFast,
+	/// An unrecognized preset
+	Unknown(u16),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{LameHeader, LamePreset, SourceSampleRate};
+
+	#[test]
+	fn lame_tag() {
+		let mut lame_tag = [0; LameHeader::SIZE];
+		lame_tag[..9].copy_from_slice(b"LAME3.100");
+		// Revision 0, VBR (--vbr-mtrh)
+		lame_tag[9] = 0x04;
+		// Lowpass of 19.5 kHz
+		lame_tag[10] = 195;
+		lame_tag[20] = 32;
+		// Delay of 576, padding of 1150
+		lame_tag[21..24].copy_from_slice(&[0x24, 0x04, 0x7E]);
+		// 44.1 kHz source, noise shaping 1
+		lame_tag[24] = 0b0100_0001;
+		// -V2
+		lame_tag[26..28].copy_from_slice(&480_u16.to_be_bytes());
+
+		let header = LameHeader::parse(&lame_tag, Some(78)).unwrap();
+		assert_eq!(header.encoder(), "LAME3.100");
+		assert_eq!(header.revision(), 0);
+		assert_eq!(header.vbr_method(), 4);
+		assert_eq!(header.lowpass(), Some(19500));
+		assert_eq!(header.quality(), Some(78));
+		assert_eq!(header.bitrate(), 32);
+		assert_eq!((header.encoder_delay, header.encoder_padding), (576, 1150));
+		assert_eq!(header.noise_shaping(), 1);
+		assert_eq!(header.source_sample_rate(), SourceSampleRate::Hz44100);
+		assert_eq!(header.preset(), Some(LamePreset::Vbr(2)));
+	}
+
+	#[test]
+	fn presets() {
+		let mut lame_tag = [0; LameHeader::SIZE];
+		lame_tag[..9].copy_from_slice(b"LAME3.100");
+
+		let mut header = LameHeader::parse(&lame_tag, None).unwrap();
+
+		for (preset, expected) in [
+			(0, None),
+			(128, Some(LamePreset::Abr(128))),
+			(410, Some(LamePreset::Vbr(9))),
+			(500, Some(LamePreset::Vbr(0))),
+			(415, Some(LamePreset::Unknown(415))),
+			(1001, Some(LamePreset::Standard)),
+			(1007, Some(LamePreset::MediumFast)),
+			(2000, Some(LamePreset::Unknown(2000))),
+		] {
+			header.preset = preset;
+			assert_eq!(header.preset(), expected);
+		}
+	}
+
+	#[test]
+	fn unknown_encoder() {
+		assert!(LameHeader::parse(&[0; LameHeader::SIZE], None).is_none());
+	}
+}
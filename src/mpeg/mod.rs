@@ -1,10 +1,12 @@
 //! MP3 specific items
 mod constants;
 pub(crate) mod header;
+mod lame;
 mod properties;
 mod read;
 
 pub use header::{ChannelMode, Emphasis, Layer, MpegVersion};
+pub use lame::{LameHeader, LamePreset, SourceSampleRate};
 pub use properties::MpegProperties;
 
 use crate::ape::tag::ApeTag;
@@ -16,7 +18,6 @@ use lofty_attr::LoftyFile;
 /// An MPEG file
 #[derive(LoftyFile, Default)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct MpegFile {
 	/// An ID3v2 tag
 	#[lofty(tag_type = "Id3v2")]
@@ -29,4 +30,62 @@ pub struct MpegFile {
 	pub(crate) ape_tag: Option<ApeTag>,
 	/// The file's audio properties
 	pub(crate) properties: MpegProperties,
+	/// The offset of the first MPEG frame
+	pub(crate) first_frame_offset: Option<u64>,
+	/// The offset where the audio begins
+	pub(crate) audio_start_offset: u64,
+	/// The offset where the audio ends
+	pub(crate) audio_end_offset: u64,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
+}
+
+impl MpegFile {
+	/// The offset of the first MPEG frame in the stream
+	///
+	/// This is where the audio begins, after any leading tags and junk. The search is limited to
+	/// [`ParseOptions::max_junk_bytes`](crate::ParseOptions::max_junk_bytes), reading the file will fail if
+	/// no frame is found within it. Unless the properties aren't being read, in which case this will be
+	/// `None`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::mpeg::MpegFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let path = "tests/files/assets/minimal/full_test.mp3";
+	/// let mut file = std::fs::File::open(path)?;
+	/// let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new())?;
+	///
+	/// // This file has a 31 byte ID3v2 tag preceding the audio
+	/// assert_eq!(mpeg_file.first_frame_offset(), Some(31));
+	/// # Ok(()) }
+	/// ```
+	pub fn first_frame_offset(&self) -> Option<u64> {
+		self.first_frame_offset
+	}
+
+	/// The offset where the audio begins
+	///
+	/// This is the same as [`MpegFile::first_frame_offset`] when a frame was found. Unlike it, this
+	/// is always available, as the audio is assumed to start directly after any leading tags and zero
+	/// padding otherwise. This is the case for a file with a single frame, which can't be verified
+	/// against the one following it.
+	///
+	/// Along with [`MpegFile::audio_end_offset`], this allows for copying the MPEG frames
+	/// without having to locate them again.
+	pub fn audio_start_offset(&self) -> u64 {
+		self.audio_start_offset
+	}
+
+	/// The offset where the audio ends
+	///
+	/// This is the offset of the first byte of any trailing APE, Lyrics3v2, or ID3v1 tag, or the
+	/// end of the stream if there are none.
+	pub fn audio_end_offset(&self) -> u64 {
+		self.audio_end_offset
+	}
 }
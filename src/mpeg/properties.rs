@@ -1,7 +1,10 @@
 use super::header::{ChannelMode, Emphasis, Header, Layer, MpegVersion, XingHeader};
+use super::lame::LameHeader;
 use crate::error::Result;
 use crate::mpeg::header::{cmp_header, rev_search_for_frame_sync, HeaderCmpResult, HEADER_MASK};
-use crate::properties::FileProperties;
+use crate::properties::{
+	bitrate_source, non_zero, BitrateSource, FileProperties, GaplessInfo, GaplessSource,
+};
 use crate::ChannelMask;
 
 use std::io::{Read, Seek, SeekFrom};
@@ -25,6 +28,9 @@ pub struct MpegProperties {
 	pub(crate) copyright: bool,
 	pub(crate) original: bool,
 	pub(crate) emphasis: Option<Emphasis>,
+	pub(crate) gapless_info: Option<GaplessInfo>,
+	pub(crate) lame_header: Option<LameHeader>,
+	pub(crate) audio_bitrate_source: BitrateSource,
 }
 
 impl From<MpegProperties> for FileProperties {
@@ -42,6 +48,9 @@ impl From<MpegProperties> for FileProperties {
 			emphasis: _,
 			mode_extension: _,
 			original: _,
+			gapless_info,
+			lame_header: _,
+			audio_bitrate_source,
 		} = input;
 		let channel_mask = match channel_mode {
 			ChannelMode::SingleChannel => Some(ChannelMask::mono()),
@@ -50,12 +59,15 @@ impl From<MpegProperties> for FileProperties {
 		};
 		Self {
 			duration,
-			overall_bitrate: Some(overall_bitrate),
-			audio_bitrate: Some(audio_bitrate),
-			sample_rate: Some(sample_rate),
+			overall_bitrate: non_zero(overall_bitrate),
+			audio_bitrate: non_zero(audio_bitrate),
+			sample_rate: non_zero(sample_rate),
 			bit_depth: None,
-			channels: Some(channels),
+			channels: non_zero(channels),
 			channel_mask,
+			gapless_info,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(audio_bitrate, audio_bitrate_source),
 		}
 	}
 }
@@ -120,6 +132,20 @@ impl MpegProperties {
 	pub fn emphasis(&self) -> Option<Emphasis> {
 		self.emphasis
 	}
+
+	/// Encoder delay and padding, from the LAME tag
+	///
+	/// See [`GaplessInfo`]
+	pub fn gapless_info(&self) -> Option<GaplessInfo> {
+		self.gapless_info
+	}
+
+	/// The LAME tag, if the stream has one
+	///
+	/// See [`LameHeader`]
+	pub fn lame_header(&self) -> Option<&LameHeader> {
+		self.lame_header.as_ref()
+	}
 }
 
 pub(super) fn read_properties<R>(
@@ -159,9 +185,28 @@ where
 			properties.duration = Duration::from_millis(length);
 			properties.overall_bitrate = ((file_length * 8) / length) as u32;
 			properties.audio_bitrate = ((u64::from(xing_header.size) * 8) / length) as u32;
+			properties.audio_bitrate_source = BitrateSource::Computed;
+
+			if let Some(lame_header) = xing_header.lame {
+				let (delay, padding) = (lame_header.encoder_delay, lame_header.encoder_padding);
+				let total_samples =
+					u64::from(xing_header.frames) * u64::from(first_frame_header.samples);
+
+				properties.gapless_info = Some(GaplessInfo {
+					encoder_delay_samples: u32::from(delay),
+					encoder_padding_samples: Some(u32::from(padding)),
+					valid_samples: total_samples
+						.saturating_sub(u64::from(delay))
+						.saturating_sub(u64::from(padding)),
+					source: GaplessSource::LameTag,
+				});
+				properties.lame_header = Some(lame_header);
+			}
 		},
 		_ if first_frame_header.bitrate > 0 => {
+			// Without a Xing/VBRI header, we have to assume the stream is CBR
 			properties.audio_bitrate = first_frame_header.bitrate;
+			properties.audio_bitrate_source = BitrateSource::Declared;
 
 			// Search for the last frame, starting at the end of the frames
 			reader.seek(SeekFrom::Start(last_frame_offset))?;
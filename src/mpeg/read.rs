@@ -1,13 +1,16 @@
-use super::header::{cmp_header, search_for_frame_sync, Header, HeaderCmpResult, XingHeader};
+use super::header::{
+	cmp_header, search_for_frame_sync, Header, HeaderCmpResult, XingHeader, MAX_XING_HEADER_SIZE,
+};
 use super::{MpegFile, MpegProperties};
 use crate::ape::header::read_ape_header;
 use crate::error::Result;
 use crate::id3::v2::header::Id3v2Header;
-use crate::id3::v2::read::parse_id3v2;
-use crate::id3::{find_id3v1, find_lyrics3v2, ID3FindResults};
+use crate::id3::v2::read::{merge_appended_id3v2, parse_id3v2};
+use crate::id3::{find_id3v1, find_id3v2_footer, find_lyrics3v2, ID3FindResults};
 use crate::macros::{decode_err, err};
 use crate::mpeg::header::HEADER_MASK;
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -17,6 +20,8 @@ pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Resul
 where
 	R: Read + Seek,
 {
+	let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 	let mut file = MpegFile::default();
 
 	let mut first_frame_offset = 0;
@@ -39,7 +44,7 @@ where
 				let header = Id3v2Header::parse(reader)?;
 				let skip_footer = header.flags.footer;
 
-				let id3v2 = parse_id3v2(reader, header, parse_options.parsing_mode)?;
+				let id3v2 = parse_id3v2(reader, header, parse_options)?;
 				if let Some(existing_tag) = &mut file.id3v2_tag {
 					// https://github.com/Serial-ATA/lofty-rs/issues/87
 					// Duplicate tags should have their frames appended to the previous
@@ -65,7 +70,9 @@ where
 					let ape_header = read_ape_header(reader, false)?;
 
 					file.ape_tag = Some(crate::ape::tag::read::read_ape_tag_with_header(
-						reader, ape_header,
+						reader,
+						ape_header,
+						parse_options.parsing_mode,
 					)?);
 
 					continue;
@@ -78,14 +85,27 @@ where
 				// Seek back the length of the temporary header buffer, to include them
 				// in the frame sync search
 				#[allow(clippy::neg_multiply)]
-				reader.seek(SeekFrom::Current(-1 * header.len() as i64))?;
+				let search_start = reader.seek(SeekFrom::Current(-1 * header.len() as i64))?;
+				file.audio_start_offset = search_start + parse_options.start_offset;
 
 				#[allow(clippy::used_underscore_binding)]
-				if let Some((_first_first_header, _first_frame_offset)) = find_next_frame(reader)? {
-					first_frame_offset = _first_frame_offset;
-					first_frame_header = Some(_first_first_header);
-					break;
+				match find_next_frame(reader, parse_options.max_junk_bytes as u64) {
+					Ok(Some((_first_first_header, _first_frame_offset))) => {
+						first_frame_offset = _first_frame_offset;
+						first_frame_header = Some(_first_first_header);
+						// Reported from the start of the reader, see `ParseOptions::start_offset`
+						file.first_frame_offset =
+							Some(_first_frame_offset + parse_options.start_offset);
+						file.audio_start_offset = _first_frame_offset + parse_options.start_offset;
+					},
+					Ok(None) => {},
+					// The frames are only needed for the properties, the tags can still be read
+					Err(_) if !parse_options.read_properties => {},
+					Err(err) => return Err(err),
 				}
+
+				// Either we found the first frame, or there is nothing left to search
+				break;
 			},
 		}
 	}
@@ -99,15 +119,25 @@ where
 
 	let _ = find_lyrics3v2(reader)?;
 
+	// An ID3v2 tag can also be appended after the audio, on either side of an APE tag
+	let audio_start = file
+		.audio_start_offset
+		.saturating_sub(parse_options.start_offset);
+	let mut appended_id3v2 = find_id3v2_footer(reader, audio_start, true)?;
+
 	reader.seek(SeekFrom::Current(-32))?;
 
-	match crate::ape::tag::read::read_ape_tag(reader, true)? {
+	match crate::ape::tag::read::read_ape_tag(reader, true, parse_options.parsing_mode)? {
 		Some((tag, header)) => {
 			file.ape_tag = Some(tag);
 
 			// Seek back to the start of the tag
 			let pos = reader.stream_position()?;
 			reader.seek(SeekFrom::Start(pos - u64::from(header.size)))?;
+
+			if appended_id3v2.0.is_none() {
+				appended_id3v2 = find_id3v2_footer(reader, audio_start, true)?;
+			}
 		},
 		None => {
 			// Correct the position (APE header - Preamble)
@@ -115,7 +145,13 @@ where
 		},
 	}
 
+	if let ID3FindResults(Some(header), Some(content)) = appended_id3v2 {
+		let id3v2 = parse_id3v2(&mut &*content, header, parse_options)?;
+		merge_appended_id3v2(&mut file.id3v2_tag, id3v2);
+	}
+
 	let last_frame_offset = reader.stream_position()?;
+	file.audio_end_offset = last_frame_offset + parse_options.start_offset;
 	file.properties = MpegProperties::default();
 
 	if parse_options.read_properties {
@@ -135,9 +171,15 @@ where
 		let xing_header_location = first_frame_offset + u64::from(first_frame_header.data_start);
 		reader.seek(SeekFrom::Start(xing_header_location))?;
 
-		let mut xing_reader = [0; 32];
+		let mut xing_reader = vec![0; 32];
 		reader.read_exact(&mut xing_reader)?;
 
+		// The rest of the header, which is only present if it has a LAME tag
+		reader
+			.by_ref()
+			.take((MAX_XING_HEADER_SIZE - 32) as u64)
+			.read_to_end(&mut xing_reader)?;
+
 		let xing_header = XingHeader::read(&mut &xing_reader[..])?;
 
 		let file_length = reader.seek(SeekFrom::End(0))?;
@@ -156,13 +198,21 @@ where
 }
 
 // Searches for the next frame, comparing it to the following one
-fn find_next_frame<R>(reader: &mut R) -> Result<Option<(Header, u64)>>
+//
+// The search is limited to `max_junk_bytes`, same as `Probe::guess_file_type`. Reaching the limit
+// is an error, as opposed to reaching the end of the stream.
+fn find_next_frame<R>(reader: &mut R, max_junk_bytes: u64) -> Result<Option<(Header, u64)>>
 where
 	R: Read + Seek,
 {
-	let mut pos = reader.stream_position()?;
+	let search_start = reader.stream_position()?;
+	let search_end = search_start.saturating_add(max_junk_bytes);
+
+	let mut pos = search_start;
 
-	while let Ok(Some(first_mp3_frame_start_relative)) = search_for_frame_sync(reader) {
+	while let Ok(Some(first_mp3_frame_start_relative)) =
+		search_for_frame_sync(&mut reader.by_ref().take(search_end.saturating_sub(pos)))
+	{
 		let first_mp3_frame_start_absolute = pos + first_mp3_frame_start_relative;
 
 		// Seek back to the start of the frame and read the header
@@ -179,7 +229,13 @@ where
 			}
 		}
 
-		pos = reader.stream_position()?;
+		// This was a false frame sync, the real one can be anywhere after it. We can't skip
+		// ahead by the length of the false frame.
+		pos = reader.seek(SeekFrom::Start(first_mp3_frame_start_absolute + 1))?;
+	}
+
+	if reader.seek(SeekFrom::End(0))? > search_end {
+		decode_err!(@BAIL Mpeg, "No frame found within `ParseOptions::max_junk_bytes`");
 	}
 
 	Ok(None)
@@ -56,7 +56,6 @@ pub enum MpcStreamVersion {
 /// An MPC file
 #[derive(LoftyFile, Default)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct MpcFile {
 	/// The stream version
 	pub(crate) stream_version: MpcStreamVersion,
@@ -71,6 +70,13 @@ pub struct MpcFile {
 	pub(crate) ape_tag: Option<ApeTag>,
 	/// The file's audio properties
 	pub(crate) properties: MpcProperties,
+	/// The offset where the audio begins
+	pub(crate) audio_start_offset: u64,
+	/// The offset where the audio ends
+	pub(crate) audio_end_offset: u64,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
 
 impl MpcFile {
@@ -78,4 +84,20 @@ impl MpcFile {
 	pub fn stream_version(&self) -> MpcStreamVersion {
 		self.stream_version
 	}
+
+	/// The offset where the audio begins
+	///
+	/// This is the offset of the stream's signature (`MPCK` or `MP+`), after any leading tags. Stream
+	/// versions 4 to 6 have no signature, so this is simply where the stream header starts.
+	pub fn audio_start_offset(&self) -> u64 {
+		self.audio_start_offset
+	}
+
+	/// The offset where the audio ends
+	///
+	/// This is the offset of the first byte of any trailing APE, Lyrics3v2, or ID3v1 tag, or the
+	/// end of the stream if there are none.
+	pub fn audio_end_offset(&self) -> u64 {
+		self.audio_end_offset
+	}
 }
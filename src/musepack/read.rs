@@ -4,9 +4,10 @@ use super::sv8::MpcSv8Properties;
 use super::{MpcFile, MpcProperties, MpcStreamVersion};
 use crate::error::Result;
 use crate::id3::v2::read::parse_id3v2;
-use crate::id3::{find_id3v1, find_id3v2, find_lyrics3v2, ID3FindResults};
+use crate::id3::{find_id3v1, find_id3v2, find_lyrics3v2, FindId3v2Config, ID3FindResults};
 use crate::probe::ParseOptions;
 use crate::traits::SeekStreamLen;
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -14,22 +15,29 @@ pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Resul
 where
 	R: Read + Seek,
 {
+	let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 	// Sv4to6 is the default, as it doesn't have a marker like Sv8's b'MPCK' or Sv7's b'MP+'
 	let mut version = MpcStreamVersion::Sv4to6;
 	let mut file = MpcFile::default();
 
 	#[allow(unstable_name_collisions)]
-	let mut stream_length = reader.stream_len()?;
+	let file_length = reader.stream_len()?;
+	let mut stream_length = file_length;
 
 	// ID3v2 tags are unsupported in MPC files, but still possible
 	#[allow(unused_variables)]
-	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(reader, true)? {
+	if let ID3FindResults(Some(header), Some(content)) = find_id3v2(
+		reader,
+		FindId3v2Config::READ_TAG.allowed_padding(parse_options.max_junk_bytes as u64),
+	)? {
 		let reader = &mut &*content;
 
-		let id3v2 = parse_id3v2(reader, header, parse_options.parsing_mode)?;
+		let id3v2 = parse_id3v2(reader, header, parse_options)?;
 		file.id3v2_tag = Some(id3v2);
 
-		let mut size = header.size;
+		// The size doesn't include the header itself
+		let mut size = header.size + 10;
 		if header.flags.footer {
 			size += 10;
 		}
@@ -51,9 +59,11 @@ where
 	let ID3FindResults(_, lyrics3v2_size) = find_lyrics3v2(reader)?;
 	stream_length -= u64::from(lyrics3v2_size);
 
-	reader.seek(SeekFrom::Current(-32))?;
+	let mut audio_end = reader.seek(SeekFrom::Current(-32))? + 32;
 
-	if let Some((tag, header)) = crate::ape::tag::read::read_ape_tag(reader, true)? {
+	if let Some((tag, header)) =
+		crate::ape::tag::read::read_ape_tag(reader, true, parse_options.parsing_mode)?
+	{
 		file.ape_tag = Some(tag);
 
 		// Seek back to the start of the tag
@@ -61,8 +71,13 @@ where
 		reader.seek(SeekFrom::Start(pos - u64::from(header.size)))?;
 
 		stream_length -= u64::from(header.size);
+		audio_end -= u64::from(header.size);
 	}
 
+	// Reported from the start of the reader, see `ParseOptions::start_offset`
+	file.audio_start_offset = pos_past_id3v2 + parse_options.start_offset;
+	file.audio_end_offset = audio_end + parse_options.start_offset;
+
 	// Restore the position of the magic signature
 	reader.seek(SeekFrom::Start(pos_past_id3v2))?;
 
@@ -87,17 +102,22 @@ where
 	if parse_options.read_properties {
 		match version {
 			MpcStreamVersion::Sv8 => {
-				file.properties =
-					MpcProperties::Sv8(MpcSv8Properties::read(reader, parse_options.parsing_mode)?)
+				file.properties = MpcProperties::Sv8(MpcSv8Properties::read(
+					reader,
+					parse_options.parsing_mode,
+					file_length,
+				)?)
 			},
 			MpcStreamVersion::Sv7 => {
-				file.properties = MpcProperties::Sv7(MpcSv7Properties::read(reader, stream_length)?)
+				file.properties =
+					MpcProperties::Sv7(MpcSv7Properties::read(reader, stream_length, file_length)?)
 			},
 			MpcStreamVersion::Sv4to6 => {
 				file.properties = MpcProperties::Sv4to6(MpcSv4to6Properties::read(
 					reader,
 					parse_options.parsing_mode,
 					stream_length,
+					file_length,
 				)?)
 			},
 		}
@@ -2,7 +2,7 @@ use crate::error::Result;
 use crate::macros::{decode_err, parse_mode_choice};
 use crate::musepack::constants::{MPC_DECODER_SYNTH_DELAY, MPC_FRAME_LENGTH};
 use crate::probe::ParsingMode;
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::io::Read;
 use std::time::Duration;
@@ -15,6 +15,8 @@ pub struct MpcSv4to6Properties {
 	pub(crate) duration: Duration,
 	pub(crate) channels: u8,     // NOTE: always 2
 	pub(crate) sample_rate: u32, // NOTE: always 44100
+	pub(crate) overall_bitrate: u32,
+	pub(crate) audio_bitrate_source: BitrateSource,
 
 	// Fields actually contained in the header
 	pub(crate) audio_bitrate: u32,
@@ -28,12 +30,15 @@ impl From<MpcSv4to6Properties> for FileProperties {
 	fn from(input: MpcSv4to6Properties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.audio_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
 			bit_depth: None,
-			channels: Some(input.channels),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, input.audio_bitrate_source),
 		}
 	}
 }
@@ -54,7 +59,14 @@ impl MpcSv4to6Properties {
 		self.sample_rate
 	}
 
+	/// Overall bitrate (kbps)
+	pub fn overall_bitrate(&self) -> u32 {
+		self.overall_bitrate
+	}
+
 	/// Audio bitrate (kbps)
+	///
+	/// This is the bitrate from the header for CBR streams, otherwise it is computed from the stream length.
 	pub fn audio_bitrate(&self) -> u32 {
 		self.audio_bitrate
 	}
@@ -83,6 +95,7 @@ impl MpcSv4to6Properties {
 		reader: &mut R,
 		parse_mode: ParsingMode,
 		stream_length: u64,
+		file_length: u64,
 	) -> Result<Self>
 	where
 		R: Read,
@@ -135,16 +148,23 @@ impl MpcSv4to6Properties {
 		properties.sample_rate = 44100;
 		properties.channels = 2;
 
+		properties.audio_bitrate_source = BitrateSource::Declared;
+
 		if properties.frame_count > 0 {
 			let samples =
 				(properties.frame_count * MPC_FRAME_LENGTH).saturating_sub(MPC_DECODER_SYNTH_DELAY);
-			let length = f64::from(samples) / f64::from(properties.sample_rate);
+			let length = f64::from(samples) * 1000.0 / f64::from(properties.sample_rate);
 			properties.duration = Duration::from_millis(length.ceil() as u64);
 
-			let pcm_frames = 1152 * u64::from(properties.frame_count) - 576;
-			properties.audio_bitrate = ((stream_length as f64
-				* 8.0 * f64::from(properties.sample_rate))
-				/ pcm_frames as f64) as u32;
+			if length > 0.0 {
+				properties.overall_bitrate = ((file_length as f64) * 8.0 / length + 0.5) as u32;
+
+				// Only VBR streams need their bitrate computed
+				if properties.audio_bitrate == 0 {
+					properties.audio_bitrate = ((stream_length as f64) * 8.0 / length + 0.5) as u32;
+					properties.audio_bitrate_source = BitrateSource::Computed;
+				}
+			}
 		}
 
 		Ok(properties)
@@ -1,7 +1,7 @@
 use crate::error::Result;
 use crate::macros::decode_err;
 use crate::musepack::constants::{FREQUENCY_TABLE, MPC_OLD_GAIN_REF};
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::io::Read;
 use std::time::Duration;
@@ -135,12 +135,15 @@ impl From<MpcSv7Properties> for FileProperties {
 	fn from(input: MpcSv7Properties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_freq),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_freq),
 			bit_depth: None,
-			channels: Some(input.channels),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -266,7 +269,7 @@ impl MpcSv7Properties {
 		self.encoder_version
 	}
 
-	pub(crate) fn read<R>(reader: &mut R, stream_length: u64) -> Result<Self>
+	pub(crate) fn read<R>(reader: &mut R, stream_length: u64, file_length: u64) -> Result<Self>
 	where
 		R: Read,
 	{
@@ -369,7 +372,7 @@ impl MpcSv7Properties {
 				(f64::from(total_samples) * 1000.0 / f64::from(properties.sample_freq)).ceil();
 			properties.duration = Duration::from_millis(length as u64);
 			properties.audio_bitrate = (stream_length * 8 / length as u64) as u32;
-			properties.overall_bitrate = properties.audio_bitrate;
+			properties.overall_bitrate = (file_length * 8 / length as u64) as u32;
 		}
 
 		Ok(properties)
@@ -2,7 +2,7 @@ use super::read::PacketReader;
 use crate::error::Result;
 use crate::musepack::constants::FREQUENCY_TABLE;
 use crate::probe::ParsingMode;
-use crate::properties::FileProperties;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::io::Read;
 use std::time::Duration;
@@ -27,12 +27,15 @@ impl From<MpcSv8Properties> for FileProperties {
 	fn from(input: MpcSv8Properties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.stream_header.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.stream_header.sample_rate),
 			bit_depth: None,
-			channels: Some(input.stream_header.channels),
+			channels: non_zero(input.stream_header.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -68,8 +71,12 @@ impl MpcSv8Properties {
 		self.stream_header.stream_version
 	}
 
-	pub(crate) fn read<R: Read>(reader: &mut R, parse_mode: ParsingMode) -> Result<Self> {
-		super::read::read_from(reader, parse_mode)
+	pub(crate) fn read<R: Read>(
+		reader: &mut R,
+		parse_mode: ParsingMode,
+		file_length: u64,
+	) -> Result<Self> {
+		super::read::read_from(reader, parse_mode, file_length)
 	}
 }
 
@@ -15,7 +15,11 @@ const ENCODER_INFO_KEY: [u8; 2] = *b"EI";
 const AUDIO_PACKET_KEY: [u8; 2] = *b"AP";
 const STREAM_END_KEY: [u8; 2] = *b"SE";
 
-pub(crate) fn read_from<R>(data: &mut R, parse_mode: ParsingMode) -> Result<MpcSv8Properties>
+pub(crate) fn read_from<R>(
+	data: &mut R,
+	parse_mode: ParsingMode,
+	file_length: u64,
+) -> Result<MpcSv8Properties>
 where
 	R: Read,
 {
@@ -101,7 +105,7 @@ where
 
 		properties.duration = Duration::from_millis(length as u64);
 		properties.audio_bitrate = ((stream_length * 8) / length as u64) as u32;
-		properties.overall_bitrate = properties.audio_bitrate;
+		properties.overall_bitrate = ((file_length * 8) / length as u64) as u32;
 	}
 
 	Ok(properties)
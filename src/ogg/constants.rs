@@ -8,3 +8,6 @@ pub const OPUSHEAD: &[u8] = &[79, 112, 117, 115, 72, 101, 97, 100];
 
 // https://www.speex.org/docs/manual/speex-manual/node8.html
 pub const SPEEXHEADER: &[u8] = &[83, 112, 101, 101, 120, 32, 32, 32];
+
+// https://wiki.xiph.org/Ogg_Skeleton_4
+pub const SKELETON_HEAD: &[u8] = &[102, 105, 115, 104, 101, 97, 100, 0];
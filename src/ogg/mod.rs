@@ -3,6 +3,10 @@
 //! ## File notes
 //!
 //! The only supported tag format is [`VorbisComments`]
+//!
+//! Files may multiplex other logical streams alongside the audio stream, such as an Ogg Skeleton
+//! stream. Only the audio stream's pages are read and rewritten, the pages of any other streams
+//! are left untouched.
 pub(crate) mod constants;
 pub(crate) mod opus;
 mod picture_storage;
@@ -13,15 +17,19 @@ pub(crate) mod vorbis;
 pub(crate) mod write;
 
 use crate::error::Result;
+use crate::file::FileType;
 use crate::macros::decode_err;
+use crate::probe::ParsingMode;
+use crate::properties::ChannelMask;
+use constants::{OPUSHEAD, SPEEXHEADER, VORBIS_IDENT_HEAD};
 
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 
-use ogg_pager::{Page, PageHeader};
+use ogg_pager::{Packets, Page, PageHeader, CONTAINS_FIRST_PAGE_OF_BITSTREAM, PAGE_HEADER_SIZE};
 
 // Exports
 
-pub use opus::properties::OpusProperties;
+pub use opus::properties::{OpusChannelMapping, OpusProperties};
 pub use opus::OpusFile;
 pub use picture_storage::OggPictureStorage;
 pub use speex::properties::SpeexProperties;
@@ -40,18 +48,234 @@ fn verify_signature(content: &[u8], sig: &[u8]) -> Result<()> {
 	Ok(())
 }
 
-fn find_last_page<R>(data: &mut R) -> Result<Page>
+// All of the first pages of the logical streams in a file must come before any other pages,
+// so we only have to search until we find a page that doesn't begin a stream.
+//
+// https://www.xiph.org/ogg/doc/oggstream.html
+fn find_stream_start<R>(data: &mut R, header_sig: &[u8]) -> Result<PageHeader>
+where
+	R: Read + Seek,
+{
+	while let Some((header, content)) = read_first_page_of_stream(data) {
+		if content.starts_with(header_sig) {
+			data.seek(SeekFrom::Start(header.start))?;
+			return Ok(header);
+		}
+	}
+
+	decode_err!(@BAIL Vorbis, "File missing magic signature");
+}
+
+// https://xiph.org/vorbis/doc/Vorbis_I_spec.html#x1-810004.3.9
+//
+// Streams with more than 8 channels have an application defined order
+pub(crate) fn vorbis_channel_mask(channels: u8) -> Option<ChannelMask> {
+	let mask = match channels {
+		1 => ChannelMask::mono(),
+		2 => ChannelMask::stereo(),
+		// front left, front center, front right
+		3 => ChannelMask(0x7),
+		// front left, front right, rear left, rear right
+		4 => ChannelMask(0x33),
+		// front left, front center, front right, rear left, rear right
+		5 => ChannelMask(0x37),
+		// 5.1: front left, front center, front right, rear left, rear right, LFE
+		6 => ChannelMask(0x3F),
+		// 6.1: front left, front center, front right, side left, side right, rear center, LFE
+		7 => ChannelMask(0x70F),
+		// 7.1: front left, front center, front right, side left, side right, rear left, rear right, LFE
+		8 => ChannelMask(0x63F),
+		_ => return None,
+	};
+
+	Some(mask)
+}
+
+/// Guesses the type of a file that begins with an Ogg Skeleton stream, using the first pages of
+/// the other logical streams
+pub(crate) fn guess_multiplexed_type<R>(data: &mut R) -> Option<FileType>
+where
+	R: Read + Seek,
+{
+	while let Some((_, content)) = read_first_page_of_stream(data) {
+		if content.starts_with(VORBIS_IDENT_HEAD) {
+			return Some(FileType::Vorbis);
+		}
+
+		if content.starts_with(OPUSHEAD) {
+			return Some(FileType::Opus);
+		}
+
+		if content.starts_with(SPEEXHEADER) {
+			return Some(FileType::Speex);
+		}
+	}
+
+	None
+}
+
+// Reads the next page, if it is the first page of a logical stream
+fn read_first_page_of_stream<R>(data: &mut R) -> Option<(PageHeader, Vec<u8>)>
+where
+	R: Read + Seek,
+{
+	let page = Page::read(data).ok()?;
+
+	if page.header().header_type_flag() & CONTAINS_FIRST_PAGE_OF_BITSTREAM == 0 {
+		return None;
+	}
+
+	let header = page.header().clone();
+	Some((header, page.take_content()))
+}
+
+/// Reads `count` packets from the logical stream `stream_serial`, skipping the pages of any other streams
+// The pages holding the comment header of the stream starting with `header_sig`
+//
+// The comment header is the second packet of the stream, its last page may be shared with the
+// packets that follow it.
+#[cfg(feature = "fs")]
+pub(crate) fn comment_header_pages<R>(
+	data: &mut R,
+	header_sig: &[u8],
+) -> Result<Vec<std::ops::Range<u64>>>
+where
+	R: Read + Seek,
+{
+	let first_page_header = find_stream_start(data, header_sig)?;
+
+	let mut pages = Vec::new();
+	let mut packets_read = 0;
+	while packets_read < 2 {
+		let header = PageHeader::read(data)?;
+		let page_end = data.stream_position()? + header.content_size() as u64;
+
+		if header.stream_serial == first_page_header.stream_serial {
+			// The first packet is always alone on the first page
+			if packets_read > 0 {
+				pages.push(header.start..page_end);
+			}
+
+			// Every segment shorter than 255 bytes ends a packet
+			data.seek(SeekFrom::Start(header.start + PAGE_HEADER_SIZE as u64 - 1))?;
+			let mut segment_count = [0];
+			data.read_exact(&mut segment_count)?;
+			let mut segment_table = vec![0; usize::from(segment_count[0])];
+			data.read_exact(&mut segment_table)?;
+			packets_read += segment_table.iter().filter(|s| **s < 255).count();
+		}
+
+		data.seek(SeekFrom::Start(page_end))?;
+	}
+
+	Ok(pages)
+}
+
+fn read_stream_packets<R>(data: &mut R, stream_serial: u32, count: isize) -> Result<Packets>
 where
 	R: Read + Seek,
 {
-	let mut last_page_header = PageHeader::read(data)?;
-	data.seek(SeekFrom::Current(last_page_header.content_size() as i64))?;
+	let mut stream_pages = Vec::new();
+
+	let mut packets_read = 0;
+	while packets_read < count {
+		let header = PageHeader::read(data)?;
+		let page_end = data.stream_position()? + header.content_size() as u64;
+
+		if header.stream_serial != stream_serial {
+			data.seek(SeekFrom::Start(page_end))?;
+			continue;
+		}
+
+		let page_start = stream_pages.len();
+		data.seek(SeekFrom::Start(header.start))?;
+		data.take(page_end - header.start)
+			.read_to_end(&mut stream_pages)?;
+
+		// Every segment shorter than 255 bytes ends a packet
+		let segment_count = usize::from(stream_pages[page_start + PAGE_HEADER_SIZE - 1]);
+		let segment_table = &stream_pages
+			[page_start + PAGE_HEADER_SIZE..page_start + PAGE_HEADER_SIZE + segment_count];
+		packets_read += segment_table.iter().filter(|s| **s < 255).count() as isize;
+	}
+
+	Ok(Packets::read_count(&mut Cursor::new(stream_pages), count)?)
+}
+
+/// The last page of a logical stream, see [`find_last_page`]
+struct LastPage {
+	page: Page,
+	// Whether the end of the file is corrupt, meaning this may not be the real last page
+	estimated: bool,
+}
+
+/// Finds the last page of the logical stream `stream_serial` with a valid granule position
+///
+/// A granule position of -1 means that no packet finishes on the page, so such pages are skipped.
+///
+/// Files that were cut off (e.g. an interrupted download) end with a truncated or otherwise corrupt
+/// page. With [`ParsingMode::Strict`], this is an error. Otherwise, the last page with a valid CRC is
+/// used instead, and it's marked as an estimate.
+///
+/// Returns `None` if there is no such page.
+fn find_last_page<R>(
+	data: &mut R,
+	stream_serial: u32,
+	parsing_mode: ParsingMode,
+) -> Result<Option<LastPage>>
+where
+	R: Read + Seek,
+{
+	let start = data.stream_position()?;
+	let file_length = data.seek(SeekFrom::End(0))?;
+	data.seek(SeekFrom::Start(start))?;
+
+	let mut candidates = Vec::new();
+	let mut corrupt = false;
+
+	while data.stream_position()? < file_length {
+		let Ok(header) = PageHeader::read(data) else {
+			corrupt = true;
+			break;
+		};
+
+		if header.stream_serial == stream_serial && header.abgp != u64::MAX {
+			candidates.push(header.start);
+		}
+
+		let page_end = data.stream_position()? + header.content_size() as u64;
+		if page_end > file_length {
+			corrupt = true;
+			break;
+		}
+
+		data.seek(SeekFrom::Start(page_end))?;
+	}
+
+	// Work backwards until we find a page that's intact
+	let mut last_page = None;
+	while let Some(page_start) = candidates.pop() {
+		data.seek(SeekFrom::Start(page_start))?;
+		if let Ok(mut page) = Page::read(data) {
+			let content = page.as_bytes();
+			page.gen_crc();
+
+			if page.as_bytes() == content {
+				last_page = Some(page);
+				break;
+			}
+		}
+
+		log::warn!("OGG: Skipping a corrupt page at offset {page_start}");
+		corrupt = true;
+	}
 
-	while let Ok(header) = PageHeader::read(data) {
-		last_page_header = header;
-		data.seek(SeekFrom::Current(last_page_header.content_size() as i64))?;
+	if corrupt && parsing_mode == ParsingMode::Strict {
+		decode_err!(@BAIL "OGG: The file ends with a corrupt page");
 	}
 
-	data.seek(SeekFrom::Start(last_page_header.start))?;
-	Ok(Page::read(data)?)
+	Ok(last_page.map(|page| LastPage {
+		page,
+		estimated: corrupt,
+	}))
 }
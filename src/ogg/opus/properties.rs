@@ -1,36 +1,89 @@
-use super::find_last_page;
 use crate::error::Result;
 use crate::macros::decode_err;
-use crate::properties::FileProperties;
+use crate::ogg::vorbis_channel_mask;
+use crate::ogg::{find_last_page, LastPage};
+use crate::probe::ParsingMode;
+use crate::properties::{
+	bitrate_source, non_zero, BitrateSource, ChannelMask, FileProperties, GaplessInfo,
+	GaplessSource,
+};
 
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
 
 use byteorder::{LittleEndian, ReadBytesExt};
-use ogg_pager::{Packets, PageHeader};
+use ogg_pager::{Packets, PageHeader, PAGE_HEADER_SIZE};
+
+/// The channel mapping of an Opus stream
+///
+/// This describes how the decoded channels are assembled from the Opus streams in each packet.
+/// See <https://datatracker.ietf.org/doc/html/rfc7845#section-5.1.1>
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OpusChannelMapping {
+	pub(crate) family: u8,
+	pub(crate) stream_count: u8,
+	pub(crate) coupled_stream_count: u8,
+	pub(crate) mapping: Vec<u8>,
+}
+
+impl OpusChannelMapping {
+	/// The channel mapping family
+	///
+	/// * `0`: Mono or stereo
+	/// * `1`: Up to 8 channels, in the Vorbis channel order
+	/// * `255`: Unidentified channels, with no defined order or meaning (e.g. ambisonics)
+	pub fn family(&self) -> u8 {
+		self.family
+	}
+
+	/// The number of Opus streams in each packet
+	pub fn stream_count(&self) -> u8 {
+		self.stream_count
+	}
+
+	/// The number of streams that decode to two (coupled) channels, rather than one
+	pub fn coupled_stream_count(&self) -> u8 {
+		self.coupled_stream_count
+	}
+
+	/// The decoded channel each output channel is taken from
+	///
+	/// Indices below `2 * coupled_stream_count` refer to the left and right channels of the coupled
+	/// streams, the rest to the uncoupled streams. An index of `255` is a silent channel.
+	pub fn mapping(&self) -> &[u8] {
+		&self.mapping
+	}
+}
 
 /// An Opus file's audio properties
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[non_exhaustive]
 pub struct OpusProperties {
 	pub(crate) duration: Duration,
 	pub(crate) overall_bitrate: u32,
 	pub(crate) audio_bitrate: u32,
 	pub(crate) channels: u8,
+	pub(crate) channel_mask: Option<ChannelMask>,
+	pub(crate) channel_mapping: OpusChannelMapping,
 	pub(crate) version: u8,
 	pub(crate) input_sample_rate: u32,
+	pub(crate) gapless_info: Option<GaplessInfo>,
+	pub(crate) duration_estimated: bool,
 }
 
 impl From<OpusProperties> for FileProperties {
 	fn from(input: OpusProperties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.input_sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.input_sample_rate),
 			bit_depth: None,
-			channels: Some(input.channels),
-			channel_mask: None,
+			channels: non_zero(input.channels),
+			channel_mask: input.channel_mask,
+			gapless_info: input.gapless_info,
+			duration_estimated: input.duration_estimated,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -41,6 +94,11 @@ impl OpusProperties {
 		self.duration
 	}
 
+	/// Whether the duration is an estimate, see [`FileProperties::is_duration_estimated`]
+	pub fn is_duration_estimated(&self) -> bool {
+		self.duration_estimated
+	}
+
 	/// Overall bitrate (kbps)
 	pub fn overall_bitrate(&self) -> u32 {
 		self.overall_bitrate
@@ -56,6 +114,19 @@ impl OpusProperties {
 		self.channels
 	}
 
+	/// Channel mask
+	///
+	/// This is only available for mapping families `0` and `1`, where the channels follow the Vorbis
+	/// channel order. See [`VorbisProperties::channel_mask`](crate::ogg::VorbisProperties::channel_mask).
+	pub fn channel_mask(&self) -> Option<ChannelMask> {
+		self.channel_mask
+	}
+
+	/// Channel mapping
+	pub fn channel_mapping(&self) -> &OpusChannelMapping {
+		&self.channel_mapping
+	}
+
 	/// Opus version
 	pub fn version(&self) -> u8 {
 		self.version
@@ -65,12 +136,20 @@ impl OpusProperties {
 	pub fn input_sample_rate(&self) -> u32 {
 		self.input_sample_rate
 	}
+
+	/// Encoder delay (pre-skip) and valid samples
+	///
+	/// See [`GaplessInfo`]
+	pub fn gapless_info(&self) -> Option<GaplessInfo> {
+		self.gapless_info
+	}
 }
 
 pub(in crate::ogg) fn read_properties<R>(
 	data: &mut R,
 	first_page_header: &PageHeader,
 	packets: &Packets,
+	parsing_mode: ParsingMode,
 ) -> Result<OpusProperties>
 where
 	R: Read + Seek,
@@ -102,15 +181,38 @@ where
 		decode_err!(@BAIL Opus, "Invalid channel count for mapping family");
 	}
 
-	let last_page = find_last_page(data);
+	properties.channel_mapping = read_channel_mapping(
+		identification_packet_reader,
+		channel_mapping_family,
+		properties.channels,
+	)?;
+
+	if channel_mapping_family <= 1 {
+		properties.channel_mask = vorbis_channel_mask(properties.channels);
+	}
+
+	let audio_start = data.stream_position()?;
+	let first_sample = match first_sample_position(data, first_page_header.stream_serial) {
+		Ok(first_sample) => first_sample,
+		Err(e) => {
+			log::debug!("Opus: Unable to read the first audio page: {e}");
+			0
+		},
+	};
+
+	data.seek(SeekFrom::Start(audio_start))?;
+	let last_page = find_last_page(data, first_page_header.stream_serial, parsing_mode)?;
 	let file_length = data.seek(SeekFrom::End(0))?;
 
-	if let Ok(last_page) = last_page {
-		let first_page_abgp = first_page_header.abgp;
+	if let Some(LastPage {
+		page: last_page,
+		estimated,
+	}) = last_page
+	{
 		let last_page_abgp = last_page.header().abgp;
 
 		let total_samples = last_page_abgp
-			.saturating_sub(first_page_abgp)
+			.saturating_sub(first_sample)
 			// https://datatracker.ietf.org/doc/html/draft-terriberry-oggopus-01#section-4.1:
 			//
 			// A 'pre-skip' field in the ID header (see Section 5.1) signals the
@@ -129,8 +231,16 @@ where
 			let stream_len = file_length - header_size as u64;
 
 			properties.duration = Duration::from_millis(length as u64);
+			properties.duration_estimated = estimated;
 			properties.overall_bitrate = ((file_length as f64) * 8.0 / length) as u32;
 			properties.audio_bitrate = ((stream_len as f64) * 8.0 / length) as u32;
+
+			properties.gapless_info = Some(GaplessInfo {
+				encoder_delay_samples: u32::from(pre_skip),
+				encoder_padding_samples: None,
+				valid_samples: total_samples,
+				source: GaplessSource::OpusHeader,
+			});
 		} else {
 			log::debug!("Opus: The file contains invalid PCM values, unable to calculate length");
 		}
@@ -138,3 +248,132 @@ where
 
 	Ok(properties)
 }
+
+fn read_channel_mapping(
+	reader: &mut &[u8],
+	family: u8,
+	channels: u8,
+) -> Result<OpusChannelMapping> {
+	// Family 0 has an implicit mapping of a single stream, which is coupled for stereo
+	if family == 0 {
+		return Ok(OpusChannelMapping {
+			family,
+			stream_count: 1,
+			coupled_stream_count: u8::from(channels == 2),
+			mapping: (0..channels).collect(),
+		});
+	}
+
+	let stream_count = reader.read_u8()?;
+	let coupled_stream_count = reader.read_u8()?;
+
+	let mut mapping = vec![0; usize::from(channels)];
+	reader.read_exact(&mut mapping)?;
+
+	if stream_count == 0 || coupled_stream_count > stream_count {
+		decode_err!(@BAIL Opus, "Invalid stream counts in channel mapping table");
+	}
+
+	let decoded_channels = u16::from(stream_count) + u16::from(coupled_stream_count);
+	if mapping
+		.iter()
+		.any(|&index| index != 255 && u16::from(index) >= decoded_channels)
+	{
+		decode_err!(@BAIL Opus, "Channel mapping table references a nonexistent channel");
+	}
+
+	Ok(OpusChannelMapping {
+		family,
+		stream_count,
+		coupled_stream_count,
+		mapping,
+	})
+}
+
+// https://datatracker.ietf.org/doc/html/rfc7845.html#section-4.5:
+//
+// The granule position of the first audio page is the number of samples that will have been decoded
+// by the end of it. A stream that doesn't start at 0 (e.g. one captured mid-broadcast) is offset by
+// the difference between the two.
+fn first_sample_position<R>(data: &mut R, stream_serial: u32) -> Result<u64>
+where
+	R: Read + Seek,
+{
+	let (header, segment_table, content) = loop {
+		let header = PageHeader::read(data)?;
+		if header.stream_serial != stream_serial {
+			data.seek(SeekFrom::Current(header.content_size() as i64))?;
+			continue;
+		}
+
+		// The segment table isn't exposed by `PageHeader`, it needs to be read again
+		data.seek(SeekFrom::Start(header.start + PAGE_HEADER_SIZE as u64 - 1))?;
+		let segment_count = data.read_u8()?;
+
+		let mut segment_table = vec![0; usize::from(segment_count)];
+		data.read_exact(&mut segment_table)?;
+
+		let mut content = vec![0; header.content_size()];
+		data.read_exact(&mut content)?;
+
+		break (header, segment_table, content);
+	};
+
+	// No packet finishes on the page
+	if header.abgp == u64::MAX {
+		return Ok(0);
+	}
+
+	// A packet continued from a previous page was already counted by that page's granule position
+	let mut skip_packet = header.header_type_flag() & 0x01 != 0;
+
+	let mut samples = 0;
+	let mut packet_start = 0;
+	let mut packet_end = 0;
+	for segment in segment_table {
+		packet_end += usize::from(segment);
+
+		// Every segment shorter than 255 bytes ends a packet
+		if segment == 255 {
+			continue;
+		}
+
+		if skip_packet {
+			skip_packet = false;
+		} else {
+			samples += packet_samples(&content[packet_start..packet_end]);
+		}
+
+		packet_start = packet_end;
+	}
+
+	// A granule position smaller than the number of samples on the page is invalid, the stream is
+	// treated as starting at 0
+	Ok(header.abgp.saturating_sub(samples))
+}
+
+// https://datatracker.ietf.org/doc/html/rfc6716#section-3.1
+fn packet_samples(packet: &[u8]) -> u64 {
+	let Some(toc) = packet.first() else {
+		return 0;
+	};
+
+	let config = toc >> 3;
+	let frame_size: u64 = match config {
+		// SILK-only, 10, 20, 40, or 60 ms
+		0..=11 => [480, 960, 1920, 2880][usize::from(config & 0x03)],
+		// Hybrid, 10 or 20 ms
+		12..=15 => [480, 960][usize::from(config & 0x01)],
+		// CELT-only, 2.5, 5, 10, or 20 ms
+		_ => [120, 240, 480, 960][usize::from(config & 0x03)],
+	};
+
+	let frame_count = match toc & 0x03 {
+		0 => 1,
+		1 | 2 => 2,
+		// An arbitrary number of frames, stored in the following byte
+		_ => packet.get(1).map_or(0, |count| count & 0x3F),
+	};
+
+	frame_size * u64::from(frame_count)
+}
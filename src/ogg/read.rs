@@ -1,5 +1,5 @@
 use super::tag::VorbisComments;
-use super::verify_signature;
+use super::{find_stream_start, read_stream_packets, verify_signature};
 use crate::error::{ErrorKind, LoftyError, Result};
 use crate::macros::{decode_err, err, parse_mode_choice};
 use crate::picture::{MimeType, Picture, PictureInformation, PictureType};
@@ -7,7 +7,7 @@ use crate::probe::ParsingMode;
 use crate::util::text::{utf16_decode, utf8_decode, utf8_decode_str};
 
 use std::borrow::Cow;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek};
 
 use base64::Engine;
 use byteorder::{LittleEndian, ReadBytesExt};
@@ -72,6 +72,7 @@ where
 		vendor,
 		items: Vec::with_capacity(comments_total_len as usize),
 		pictures: Vec::new(),
+		unparsed_items: Vec::new(),
 	};
 
 	for _ in 0..comments_total_len {
@@ -96,6 +97,7 @@ where
 		// Make sure there was a separator present, otherwise just move on
 		let Some(value) = comment_split.next() else {
 			log::warn!("No separator found, discarding field");
+			discard_comment(&mut tag, &comment_bytes, parse_mode);
 			continue;
 		};
 
@@ -109,6 +111,7 @@ where
 						}
 
 						log::warn!("Failed to decode FLAC picture, discarding field");
+						discard_comment(&mut tag, &comment_bytes, parse_mode);
 						continue;
 					},
 				}
@@ -140,6 +143,7 @@ where
 						}
 
 						log::warn!("Failed to decode FLAC picture, discarding field");
+						discard_comment(&mut tag, &comment_bytes, parse_mode);
 						continue;
 					},
 				}
@@ -157,6 +161,7 @@ where
 						}
 
 						log::warn!("Non UTF-8 value found, discarding field {key:?}");
+						discard_comment(&mut tag, &comment_bytes, parse_mode);
 						continue;
 					},
 				}
@@ -166,7 +171,9 @@ where
 					parse_mode,
 					STRICT: decode_err!(@BAIL "OGG: Vorbis comments contain an invalid key"),
 					// Otherwise discard invalid keys
-				)
+				);
+
+				discard_comment(&mut tag, &comment_bytes, parse_mode);
 			},
 		}
 	}
@@ -174,6 +181,14 @@ where
 	Ok(tag)
 }
 
+// Comments that can't be parsed are discarded, unless using `ParsingMode::Relaxed`, where they're
+// retained as-is so they can be written back
+fn discard_comment(tag: &mut VorbisComments, comment: &[u8], parse_mode: ParsingMode) {
+	if parse_mode == ParsingMode::Relaxed {
+		tag.unparsed_items.push(comment.to_vec());
+	}
+}
+
 pub(crate) fn read_from<T>(
 	data: &mut T,
 	header_sig: &[u8],
@@ -186,14 +201,12 @@ where
 {
 	debug_assert!(packets_to_read >= 2);
 
-	// TODO: Would be nice if we didn't have to read just to seek and reread immediately
-	let start = data.stream_position()?;
-	let first_page_header = PageHeader::read(data)?;
-
-	data.seek(SeekFrom::Start(start))?;
+	// The file may contain other logical streams (e.g. Ogg Skeleton), so we need to find the first
+	// page of the stream we're interested in
+	let first_page_header = find_stream_start(data, header_sig)?;
 
 	// Read the header packets
-	let packets = Packets::read_count(data, packets_to_read)?;
+	let packets = read_stream_packets(data, first_page_header.stream_serial, packets_to_read)?;
 
 	let identification_packet = packets
 		.get(0)
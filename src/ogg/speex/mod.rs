@@ -4,6 +4,7 @@ use super::tag::VorbisComments;
 use crate::error::Result;
 use crate::ogg::constants::SPEEXHEADER;
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 use properties::SpeexProperties;
 
 use std::io::{Read, Seek};
@@ -14,6 +15,8 @@ use lofty_attr::LoftyFile;
 #[derive(LoftyFile)]
 #[lofty(read_fn = "Self::read_from")]
 pub struct SpeexFile {
+	/// The serial number of the audio stream
+	pub(crate) stream_serial: u32,
 	/// The vorbis comments contained in the file
 	///
 	/// NOTE: While a metadata packet is required, it isn't required to actually have any data.
@@ -21,6 +24,9 @@ pub struct SpeexFile {
 	pub(crate) vorbis_comments_tag: VorbisComments,
 	/// The file's audio properties
 	pub(crate) properties: SpeexProperties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
 
 impl SpeexFile {
@@ -28,17 +34,48 @@ impl SpeexFile {
 	where
 		R: Read + Seek,
 	{
+		let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 		let file_information =
 			super::read::read_from(reader, SPEEXHEADER, &[], 2, parse_options.parsing_mode)?;
 
 		Ok(Self {
+			stream_serial: file_information.1.stream_serial,
 			properties: if parse_options.read_properties {
-				properties::read_properties(reader, &file_information.1, &file_information.2)?
+				properties::read_properties(
+					reader,
+					&file_information.1,
+					&file_information.2,
+					parse_options.parsing_mode,
+				)?
 			} else {
 				SpeexProperties::default()
 			},
 			// Safe to unwrap, a metadata packet is mandatory in Speex
 			vorbis_comments_tag: file_information.0.unwrap(),
+			#[cfg(feature = "fs")]
+			fingerprint: crate::fingerprint::FingerprintCell::default(),
 		})
 	}
+
+	/// Returns the serial number of the audio stream
+	///
+	/// This is preserved when writing tags.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::ogg::SpeexFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut reader = std::io::Cursor::new(&[]);
+	/// let speex_file = SpeexFile::read_from(&mut reader, ParseOptions::new())?;
+	///
+	/// println!("Audio stream serial: {}", speex_file.stream_serial());
+	/// # Ok(()) }
+	/// ```
+	pub fn stream_serial(&self) -> u32 {
+		self.stream_serial
+	}
 }
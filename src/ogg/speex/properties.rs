@@ -1,7 +1,8 @@
 use crate::error::Result;
 use crate::macros::decode_err;
-use crate::ogg::find_last_page;
-use crate::properties::FileProperties;
+use crate::ogg::{find_last_page, LastPage};
+use crate::probe::ParsingMode;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, FileProperties};
 
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
@@ -22,18 +23,22 @@ pub struct SpeexProperties {
 	pub(crate) overall_bitrate: u32,
 	pub(crate) audio_bitrate: u32,
 	pub(crate) nominal_bitrate: i32,
+	pub(crate) duration_estimated: bool,
 }
 
 impl From<SpeexProperties> for FileProperties {
 	fn from(input: SpeexProperties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
 			bit_depth: None,
-			channels: Some(input.channels),
+			channels: non_zero(input.channels),
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: input.duration_estimated,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Declared),
 		}
 	}
 }
@@ -44,6 +49,11 @@ impl SpeexProperties {
 		self.duration
 	}
 
+	/// Whether the duration is an estimate, see [`FileProperties::is_duration_estimated`]
+	pub fn is_duration_estimated(&self) -> bool {
+		self.duration_estimated
+	}
+
 	/// Speex version
 	pub fn version(&self) -> u32 {
 		self.version
@@ -89,6 +99,7 @@ pub(in crate::ogg) fn read_properties<R>(
 	data: &mut R,
 	first_page_header: &PageHeader,
 	packets: &Packets,
+	parsing_mode: ParsingMode,
 ) -> Result<SpeexProperties>
 where
 	R: Read + Seek,
@@ -135,13 +146,17 @@ where
 
 	properties.vbr = identification_packet_reader.read_u32::<LittleEndian>()? == 1;
 
-	let last_page = find_last_page(data);
+	let last_page = find_last_page(data, first_page_header.stream_serial, parsing_mode)?;
 	let file_length = data.seek(SeekFrom::End(0))?;
 
 	// This is used for bitrate calculation, it should be the length in
 	// milliseconds, but if we can't determine it then we'll just use 1000.
 	let mut length = 1000;
-	if let Ok(last_page) = last_page {
+	if let Some(LastPage {
+		page: last_page,
+		estimated,
+	}) = last_page
+	{
 		let first_page_abgp = first_page_header.abgp;
 		let last_page_abgp = last_page.header().abgp;
 
@@ -152,6 +167,7 @@ where
 			if total_samples > 0 {
 				length = total_samples * 1000 / u64::from(properties.sample_rate);
 				properties.duration = Duration::from_millis(length);
+				properties.duration_estimated = estimated;
 			} else {
 				log::debug!(
 					"Speex: The file contains invalid PCM values, unable to calculate length"
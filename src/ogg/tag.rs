@@ -1,22 +1,27 @@
 use crate::error::{LoftyError, Result};
-use crate::file::FileType;
-use crate::macros::err;
 use crate::ogg::picture_storage::OggPictureStorage;
-use crate::ogg::write::OGGFormat;
 use crate::picture::{Picture, PictureInformation};
-use crate::probe::Probe;
 use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::rating::convert_rating_items;
 use crate::tag::{try_parse_year, Tag, TagType};
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::write_options::WriteOptions;
+#[cfg(feature = "fs")]
+use crate::{file::FileType, macros::err, ogg::write::OGGFormat, probe::Probe, util::io::FileLike};
 
 use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::Write;
 use std::ops::Deref;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 use lofty_attr::tag;
 
+pub(crate) const ALBUM_ARTIST_KEY: &str = "ALBUMARTIST";
+pub(crate) const LEGACY_ALBUM_ARTIST_KEY: &str = "ALBUM ARTIST";
+
 macro_rules! impl_accessor {
 	($($name:ident => $key:literal;)+) => {
 		paste::paste! {
@@ -69,6 +74,8 @@ pub struct VorbisComments {
 	pub(crate) items: Vec<(String, String)>,
 	/// A collection of all pictures
 	pub(crate) pictures: Vec<(Picture, PictureInformation)>,
+	/// Comments that couldn't be parsed, see [`VorbisComments::unparsed_items`]
+	pub(crate) unparsed_items: Vec<Vec<u8>>,
 }
 
 impl VorbisComments {
@@ -299,11 +306,35 @@ impl VorbisComments {
 
 		self.items.drain(..split_idx).map(|(_, v)| v)
 	}
+
+	/// Returns the raw contents of all comments that couldn't be parsed
+	///
+	/// These are only retained when reading with [`ParsingMode::Relaxed`](crate::ParsingMode::Relaxed),
+	/// and are written back as-is. They can be discarded with [`VorbisComments::remove_unparsed_items`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ogg::VorbisComments;
+	///
+	/// let tag = VorbisComments::default();
+	/// assert_eq!(tag.unparsed_items().count(), 0);
+	/// ```
+	pub fn unparsed_items(&self) -> impl Iterator<Item = &[u8]> + Clone {
+		self.unparsed_items.iter().map(Vec::as_slice)
+	}
+
+	/// Removes all comments that couldn't be parsed
+	///
+	/// See [`VorbisComments::unparsed_items`]
+	pub fn remove_unparsed_items(&mut self) {
+		self.unparsed_items.clear();
+	}
 }
 
 // A case-insensitive field name that may consist of ASCII 0x20 through 0x7D, 0x3D ('=') excluded.
 // ASCII 0x41 through 0x5A inclusive (A-Z) is to be considered equivalent to ASCII 0x61 through 0x7A inclusive (a-z).
-fn verify_key(key: &str) -> bool {
+pub(crate) fn verify_key(key: &str) -> bool {
 	if key.is_empty() {
 		return false;
 	}
@@ -443,9 +474,10 @@ impl TagExt for VorbisComments {
 	}
 
 	fn is_empty(&self) -> bool {
-		self.items.is_empty() && self.pictures.is_empty()
+		self.items.is_empty() && self.pictures.is_empty() && self.unparsed_items.is_empty()
 	}
 
+	#[cfg(feature = "fs")]
 	/// Writes the tag to a file
 	///
 	/// # Errors
@@ -454,13 +486,18 @@ impl TagExt for VorbisComments {
 	/// * The file does not contain valid packets
 	/// * [`PictureInformation::from_picture`]
 	/// * [`std::io::Error`]
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		VorbisCommentsRef {
 			vendor: self.vendor.as_str(),
 			items: self.items.iter().map(|(k, v)| (k.as_str(), v.as_str())),
 			pictures: self.pictures.iter().map(|(p, i)| (p, *i)),
+			unparsed_items: &self.unparsed_items,
 		}
-		.write_to(file)
+		.write_to(file, write_options)
 	}
 
 	/// Dumps the tag to a writer
@@ -472,19 +509,26 @@ impl TagExt for VorbisComments {
 	///
 	/// * [`PictureInformation::from_picture`]
 	/// * [`std::io::Error`]
-	fn dump_to<W: Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err> {
+	fn dump_to<W: Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		VorbisCommentsRef {
 			vendor: self.vendor.as_str(),
 			items: self.items.iter().map(|(k, v)| (k.as_str(), v.as_str())),
 			pictures: self.pictures.iter().map(|(p, i)| (p, *i)),
+			unparsed_items: &self.unparsed_items,
 		}
-		.dump_to(writer)
+		.dump_to(writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
 		TagType::VorbisComments.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	fn remove_from(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
 		TagType::VorbisComments.remove_from(file)
 	}
@@ -492,6 +536,7 @@ impl TagExt for VorbisComments {
 	fn clear(&mut self) {
 		self.items.clear();
 		self.pictures.clear();
+		self.unparsed_items.clear();
 	}
 }
 
@@ -518,7 +563,17 @@ impl SplitTag for VorbisComments {
 	fn split_tag(mut self) -> (Self::Remainder, Tag) {
 		let mut tag = Tag::new(TagType::VorbisComments);
 
+		// The legacy "ALBUM ARTIST" is only used if there's no "ALBUMARTIST"
+		let has_album_artist = self
+			.items
+			.iter()
+			.any(|(k, _)| k.eq_ignore_ascii_case(ALBUM_ARTIST_KEY));
+
 		for (k, v) in std::mem::take(&mut self.items) {
+			if has_album_artist && k.eq_ignore_ascii_case(LEGACY_ALBUM_ARTIST_KEY) {
+				continue;
+			}
+
 			tag.items.push(TagItem::new(
 				ItemKey::from_key(TagType::VorbisComments, &k),
 				ItemValue::Text(v),
@@ -552,6 +607,8 @@ impl MergeTag for SplitTagRemainder {
 	fn merge_tag(self, mut tag: Tag) -> Self::Merged {
 		let Self(mut merged) = self;
 
+		convert_rating_items(&mut tag.items, TagType::VorbisComments);
+
 		if let Some(TagItem {
 			item_value: ItemValue::Text(val),
 			..
@@ -618,6 +675,7 @@ where
 	pub vendor: &'a str,
 	pub items: II,
 	pub pictures: IP,
+	pub unparsed_items: &'a [Vec<u8>],
 }
 
 impl<'a, II, IP> VorbisCommentsRef<'a, II, IP>
@@ -625,8 +683,12 @@ where
 	II: Iterator<Item = (&'a str, &'a str)>,
 	IP: Iterator<Item = (&'a Picture, PictureInformation)>,
 {
+	#[cfg(feature = "fs")]
 	#[allow(clippy::shadow_unrelated)]
-	pub(crate) fn write_to(&mut self, file: &mut File) -> Result<()> {
+	pub(crate) fn write_to<F>(&mut self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
 		let probe = Probe::new(file).guess_file_type()?;
 		let f_ty = probe.file_type();
 
@@ -639,17 +701,26 @@ where
 
 		// FLAC has its own special writing needs :)
 		if file_type == FileType::Flac {
-			return crate::flac::write::write_to_inner(file, self);
+			return crate::flac::write::write_to_inner(file, self, write_options);
 		}
 
 		let (format, header_packet_count) = OGGFormat::from_filetype(file_type);
 
-		super::write::write(file, self, format, header_packet_count)
+		super::write::write(file, self, format, header_packet_count, write_options)
 	}
 
-	pub(crate) fn dump_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
-		let metadata_packet =
-			super::write::create_metadata_packet(self, &[], self.vendor.as_bytes(), false)?;
+	pub(crate) fn dump_to<W: Write>(
+		&mut self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> Result<()> {
+		let metadata_packet = super::write::create_metadata_packet(
+			self,
+			&[],
+			self.vendor.as_bytes(),
+			false,
+			write_options,
+		)?;
 		writer.write_all(&metadata_packet)?;
 		Ok(())
 	}
@@ -684,7 +755,7 @@ mod tests {
 	use crate::ogg::{OggPictureStorage, VorbisComments};
 	use crate::{
 		ItemKey, ItemValue, MergeTag as _, ParsingMode, SplitTag as _, Tag, TagExt as _, TagItem,
-		TagType,
+		TagType, WriteOptions,
 	};
 
 	fn read_tag(tag: &[u8]) -> VorbisComments {
@@ -722,13 +793,58 @@ mod tests {
 		parsed_tag.vendor = String::new();
 
 		let mut writer = Vec::new();
-		parsed_tag.dump_to(&mut writer).unwrap();
+		parsed_tag
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
 
 		let temp_parsed_tag = read_tag(&writer);
 
 		assert_eq!(parsed_tag, temp_parsed_tag);
 	}
 
+	#[test]
+	fn legacy_album_artist() {
+		let mut vorbis_comments = VorbisComments::default();
+		vorbis_comments.push(String::from("ALBUMARTIST"), String::from("Foo artist"));
+		vorbis_comments.push(String::from("ALBUM ARTIST"), String::from("Bar artist"));
+
+		// Written as-is by default
+		let mut writer = Vec::new();
+		vorbis_comments
+			.dump_to(&mut writer, WriteOptions::default())
+			.unwrap();
+		assert_eq!(read_tag(&writer), vorbis_comments);
+
+		// The legacy key is replaced with a copy of the canonical one
+		let mut writer = Vec::new();
+		vorbis_comments
+			.dump_to(
+				&mut writer,
+				WriteOptions::new().legacy_vorbis_album_artist(true),
+			)
+			.unwrap();
+
+		let parsed = read_tag(&writer);
+		assert_eq!(
+			parsed.get_all("ALBUMARTIST").collect::<Vec<_>>(),
+			["Foo artist"]
+		);
+		assert_eq!(
+			parsed.get_all("ALBUM ARTIST").collect::<Vec<_>>(),
+			["Foo artist"]
+		);
+
+		// Nothing is added without an album artist
+		let mut writer = Vec::new();
+		VorbisComments::default()
+			.dump_to(
+				&mut writer,
+				WriteOptions::new().legacy_vorbis_album_artist(true),
+			)
+			.unwrap();
+		assert!(read_tag(&writer).is_empty());
+	}
+
 	#[test]
 	fn vorbis_comments_to_tag() {
 		let tag_bytes = std::fs::read("tests/tags/assets/test.vorbis").unwrap();
@@ -865,4 +981,44 @@ mod tests {
 			assert_eq!(Some("Cmin"), vorbis_comments.get("INITIALKEY"));
 		}
 	}
+
+	#[test]
+	fn unparsed_items_relaxed() {
+		let comments: [&[u8]; 3] = [b"TITLE=Foo title", b"ARTIST=\xFF\xFE", b"NOSEPARATOR"];
+
+		let mut tag_bytes = Vec::new();
+		tag_bytes.extend(0u32.to_le_bytes());
+		tag_bytes.extend((comments.len() as u32).to_le_bytes());
+		for comment in comments {
+			tag_bytes.extend((comment.len() as u32).to_le_bytes());
+			tag_bytes.extend(comment);
+		}
+
+		let read_relaxed = |tag_bytes: &[u8]| {
+			let mut reader = std::io::Cursor::new(tag_bytes);
+			crate::ogg::read::read_comments(
+				&mut reader,
+				tag_bytes.len() as u64,
+				ParsingMode::Relaxed,
+			)
+			.unwrap()
+		};
+
+		let tag = read_relaxed(&tag_bytes);
+		assert_eq!(tag.get("TITLE"), Some("Foo title"));
+		assert_eq!(
+			tag.unparsed_items().collect::<Vec<_>>(),
+			[&comments[1][..], &comments[2][..]]
+		);
+
+		// The unparsed items are written back untouched
+		let mut writer = Vec::new();
+		tag.dump_to(&mut writer, WriteOptions::default()).unwrap();
+		assert_eq!(read_relaxed(&writer), tag);
+
+		let mut tag = tag;
+		tag.remove_unparsed_items();
+		assert_eq!(tag.unparsed_items().count(), 0);
+		assert!(!tag.is_empty());
+	}
 }
@@ -1,10 +1,10 @@
 pub(super) mod properties;
 
-use super::find_last_page;
 use super::tag::VorbisComments;
 use crate::error::Result;
 use crate::ogg::constants::{VORBIS_COMMENT_HEAD, VORBIS_IDENT_HEAD};
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 use properties::VorbisProperties;
 
 use std::io::{Read, Seek};
@@ -15,6 +15,8 @@ use lofty_attr::LoftyFile;
 #[derive(LoftyFile)]
 #[lofty(read_fn = "Self::read_from")]
 pub struct VorbisFile {
+	/// The serial number of the audio stream
+	pub(crate) stream_serial: u32,
 	/// The Vorbis Comments contained in the file
 	///
 	/// NOTE: While a metadata packet is required, it isn't required to actually have any data.
@@ -22,6 +24,9 @@ pub struct VorbisFile {
 	pub(crate) vorbis_comments_tag: VorbisComments,
 	/// The file's audio properties
 	pub(crate) properties: VorbisProperties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
 
 impl VorbisFile {
@@ -29,6 +34,8 @@ impl VorbisFile {
 	where
 		R: Read + Seek,
 	{
+		let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 		let file_information = super::read::read_from(
 			reader,
 			VORBIS_IDENT_HEAD,
@@ -38,13 +45,42 @@ impl VorbisFile {
 		)?;
 
 		Ok(Self {
+			stream_serial: file_information.1.stream_serial,
 			properties: if parse_options.read_properties {
-				properties::read_properties(reader, &file_information.1, &file_information.2)?
+				properties::read_properties(
+					reader,
+					&file_information.1,
+					&file_information.2,
+					parse_options.parsing_mode,
+				)?
 			} else {
 				VorbisProperties::default()
 			},
 			// Safe to unwrap, a metadata packet is mandatory in OGG Vorbis
 			vorbis_comments_tag: file_information.0.unwrap(),
+			#[cfg(feature = "fs")]
+			fingerprint: crate::fingerprint::FingerprintCell::default(),
 		})
 	}
+
+	/// Returns the serial number of the audio stream
+	///
+	/// This is preserved when writing tags.
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::ogg::VorbisFile;
+	/// use lofty::{AudioFile, ParseOptions};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let mut reader = std::io::Cursor::new(&[]);
+	/// let vorbis_file = VorbisFile::read_from(&mut reader, ParseOptions::new())?;
+	///
+	/// println!("Audio stream serial: {}", vorbis_file.stream_serial());
+	/// # Ok(()) }
+	/// ```
+	pub fn stream_serial(&self) -> u32 {
+		self.stream_serial
+	}
 }
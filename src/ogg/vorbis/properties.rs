@@ -1,6 +1,8 @@
-use super::find_last_page;
 use crate::error::Result;
-use crate::properties::FileProperties;
+use crate::ogg::vorbis_channel_mask;
+use crate::ogg::{find_last_page, LastPage};
+use crate::probe::ParsingMode;
+use crate::properties::{bitrate_source, non_zero, BitrateSource, ChannelMask, FileProperties};
 
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
@@ -17,22 +19,27 @@ pub struct VorbisProperties {
 	pub(crate) audio_bitrate: u32,
 	pub(crate) sample_rate: u32,
 	pub(crate) channels: u8,
+	pub(crate) channel_mask: Option<ChannelMask>,
 	pub(crate) version: u32,
 	pub(crate) bitrate_maximum: i32,
 	pub(crate) bitrate_nominal: i32,
 	pub(crate) bitrate_minimum: i32,
+	pub(crate) duration_estimated: bool,
 }
 
 impl From<VorbisProperties> for FileProperties {
 	fn from(input: VorbisProperties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
 			bit_depth: None,
-			channels: Some(input.channels),
-			channel_mask: None,
+			channels: non_zero(input.channels),
+			channel_mask: input.channel_mask,
+			gapless_info: None,
+			duration_estimated: input.duration_estimated,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Declared),
 		}
 	}
 }
@@ -43,6 +50,11 @@ impl VorbisProperties {
 		self.duration
 	}
 
+	/// Whether the duration is an estimate, see [`FileProperties::is_duration_estimated`]
+	pub fn is_duration_estimated(&self) -> bool {
+		self.duration_estimated
+	}
+
 	/// Overall bitrate (kbps)
 	pub fn overall_bitrate(&self) -> u32 {
 		self.overall_bitrate
@@ -63,6 +75,25 @@ impl VorbisProperties {
 		self.channels
 	}
 
+	/// Channel mask
+	///
+	/// Vorbis defines the order of up to 8 channels, and this is the set of speakers they map to. In
+	/// stream order, they are:
+	///
+	/// 1. Mono
+	/// 2. Front left, front right
+	/// 3. Front left, front center, front right
+	/// 4. Front left, front right, rear left, rear right
+	/// 5. Front left, front center, front right, rear left, rear right
+	/// 6. Front left, front center, front right, rear left, rear right, LFE
+	/// 7. Front left, front center, front right, side left, side right, rear center, LFE
+	/// 8. Front left, front center, front right, side left, side right, rear left, rear right, LFE
+	///
+	/// Streams with more than 8 channels have an application defined order, so this will be `None`.
+	pub fn channel_mask(&self) -> Option<ChannelMask> {
+		self.channel_mask
+	}
+
 	/// Vorbis version
 	pub fn version(&self) -> u32 {
 		self.version
@@ -88,6 +119,7 @@ pub(in crate::ogg) fn read_properties<R>(
 	data: &mut R,
 	first_page_header: &PageHeader,
 	packets: &Packets,
+	parsing_mode: ParsingMode,
 ) -> Result<VorbisProperties>
 where
 	R: Read + Seek,
@@ -103,19 +135,24 @@ where
 	properties.version = first_page_content.read_u32::<LittleEndian>()?;
 
 	properties.channels = first_page_content.read_u8()?;
+	properties.channel_mask = vorbis_channel_mask(properties.channels);
 	properties.sample_rate = first_page_content.read_u32::<LittleEndian>()?;
 
 	properties.bitrate_maximum = first_page_content.read_i32::<LittleEndian>()?;
 	properties.bitrate_nominal = first_page_content.read_i32::<LittleEndian>()?;
 	properties.bitrate_minimum = first_page_content.read_i32::<LittleEndian>()?;
 
-	let last_page = find_last_page(data);
+	let last_page = find_last_page(data, first_page_header.stream_serial, parsing_mode)?;
 	let file_length = data.seek(SeekFrom::End(0))?;
 
 	// This is used for bitrate calculation, it should be the length in
 	// milliseconds, but if we can't determine it then we'll just use 1000.
 	let mut length = 1000;
-	if let Ok(last_page) = last_page {
+	if let Some(LastPage {
+		page: last_page,
+		estimated,
+	}) = last_page
+	{
 		let first_page_abgp = first_page_header.abgp;
 		let last_page_abgp = last_page.header().abgp;
 
@@ -126,6 +163,7 @@ where
 			if total_samples > 0 {
 				length = total_samples * 1000 / u64::from(properties.sample_rate);
 				properties.duration = Duration::from_millis(length);
+				properties.duration_estimated = estimated;
 			} else {
 				log::debug!(
 					"Vorbis: The file contains invalid PCM values, unable to calculate length"
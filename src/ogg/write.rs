@@ -1,18 +1,23 @@
-use super::verify_signature;
+use super::{find_stream_start, read_stream_packets, verify_signature};
 use crate::error::Result;
 use crate::file::FileType;
 use crate::macros::{decode_err, err, try_vec};
-use crate::ogg::constants::{OPUSTAGS, VORBIS_COMMENT_HEAD};
-use crate::ogg::tag::{create_vorbis_comments_ref, VorbisCommentsRef};
-use crate::picture::{Picture, PictureInformation};
+use crate::ogg::constants::{
+	OPUSHEAD, OPUSTAGS, SPEEXHEADER, VORBIS_COMMENT_HEAD, VORBIS_IDENT_HEAD,
+};
+use crate::ogg::tag::{
+	create_vorbis_comments_ref, VorbisCommentsRef, ALBUM_ARTIST_KEY, LEGACY_ALBUM_ARTIST_KEY,
+};
+use crate::picture::{check_picture_size, Picture, PictureInformation};
 use crate::tag::{Tag, TagType};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
 
 use std::convert::TryFrom;
-use std::fs::File;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use ogg_pager::{Packets, Page, PageHeader, CONTAINS_FIRST_PAGE_OF_BITSTREAM};
+use ogg_pager::{Page, CONTAINS_FIRST_PAGE_OF_BITSTREAM};
 
 #[derive(PartialEq, Copy, Clone)]
 pub(crate) enum OGGFormat {
@@ -30,6 +35,14 @@ impl OGGFormat {
 		}
 	}
 
+	pub(crate) fn ident_signature(self) -> &'static [u8] {
+		match self {
+			OGGFormat::Opus => OPUSHEAD,
+			OGGFormat::Vorbis => VORBIS_IDENT_HEAD,
+			OGGFormat::Speex => SPEEXHEADER,
+		}
+	}
+
 	pub(super) fn from_filetype(file_type: FileType) -> (Self, isize) {
 		match file_type {
 			FileType::Opus => (OGGFormat::Opus, 2),
@@ -40,7 +53,15 @@ impl OGGFormat {
 	}
 }
 
-pub(crate) fn write_to(file: &mut File, tag: &Tag, file_type: FileType) -> Result<()> {
+pub(crate) fn write_to<F>(
+	file: &mut F,
+	tag: &Tag,
+	file_type: FileType,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+{
 	if tag.tag_type() != TagType::VorbisComments {
 		err!(UnsupportedTag);
 	}
@@ -51,36 +72,39 @@ pub(crate) fn write_to(file: &mut File, tag: &Tag, file_type: FileType) -> Resul
 		vendor,
 		items,
 		pictures,
+		unparsed_items: &[],
 	};
 
 	let (format, header_packet_count) = OGGFormat::from_filetype(file_type);
 
-	write(file, &mut comments_ref, format, header_packet_count)
+	write(
+		file,
+		&mut comments_ref,
+		format,
+		header_packet_count,
+		write_options,
+	)
 }
 
-pub(super) fn write<'a, II, IP>(
-	file: &mut File,
+pub(super) fn write<'a, F, II, IP>(
+	file: &mut F,
 	tag: &mut VorbisCommentsRef<'a, II, IP>,
 	format: OGGFormat,
 	header_packet_count: isize,
+	write_options: WriteOptions,
 ) -> Result<()>
 where
+	F: FileLike,
 	II: Iterator<Item = (&'a str, &'a str)>,
 	IP: Iterator<Item = (&'a Picture, PictureInformation)>,
 {
-	// TODO: Would be nice if we didn't have to read just to seek and reread immediately
-
-	// Read the first page header to get the stream serial number
-	let start = file.stream_position()?;
-	let first_page_header = PageHeader::read(file)?;
-
+	// The file may contain other logical streams (e.g. Ogg Skeleton), only the pages belonging to
+	// the audio stream will be touched.
+	let first_page_header = find_stream_start(file, format.ident_signature())?;
 	let stream_serial = first_page_header.stream_serial;
 
-	file.seek(SeekFrom::Start(start))?;
-	let mut packets = Packets::read_count(file, header_packet_count)?;
-
-	let mut remaining_file_content = Vec::new();
-	file.read_to_end(&mut remaining_file_content)?;
+	let mut packets = read_stream_packets(file, stream_serial, header_packet_count)?;
+	let header_end = file.stream_position()?;
 
 	let comment_packet = packets
 		.get(1)
@@ -101,28 +125,111 @@ where
 	md_reader.read_exact(&mut vendor)?;
 
 	let add_framing_bit = format == OGGFormat::Vorbis;
-	let new_metadata_packet =
-		create_metadata_packet(tag, comment_signature, &vendor, add_framing_bit)?;
+	let new_metadata_packet = create_metadata_packet(
+		tag,
+		comment_signature,
+		&vendor,
+		add_framing_bit,
+		write_options,
+	)?;
 
 	// Replace the old comment packet
 	packets.set(1, new_metadata_packet);
 
+	// The new header pages keep the stream's original numbering
+	let mut new_header_pages =
+		packets.paginate(stream_serial, 0, CONTAINS_FIRST_PAGE_OF_BITSTREAM)?;
+	for (idx, page) in new_header_pages.iter_mut().enumerate() {
+		page.header_mut().sequence_number =
+			first_page_header.sequence_number.wrapping_add(idx as u32);
+		page.gen_crc();
+	}
+
+	let pages_written = new_header_pages.len() as u32;
+	let new_header_len = new_header_pages
+		.iter()
+		.map(|page| page.as_bytes().len() as u64)
+		.sum::<u64>();
+	let mut new_header_pages = new_header_pages.into_iter();
+
 	file.rewind()?;
-	file.set_len(0)?;
+	let mut file_content = Vec::new();
+	file.read_to_end(&mut file_content)?;
+
+	// The pages following the header only move if it changed in size
+	let mut header_len = 0;
+	let mut pages_reader = Cursor::new(&file_content[..]);
+	while let Ok(page) = Page::read(&mut pages_reader) {
+		let header = page.header();
+		if header.start >= header_end {
+			break;
+		}
 
-	let pages_written =
-		packets.write_to(file, stream_serial, 0, CONTAINS_FIRST_PAGE_OF_BITSTREAM)? as u32;
+		if header.stream_serial == stream_serial {
+			header_len += page.end - header.start;
+		}
+	}
+
+	if header_len != new_header_len {
+		file.mark_moved(header_end);
+	}
 
-	// Correct all remaining page sequence numbers
-	let mut pages_reader = Cursor::new(&remaining_file_content[..]);
-	let mut idx = 0;
+	file.rewind()?;
+	file.set_len(0)?;
+
+	// The first page of the stream stays in place, with the remaining header pages taking the
+	// place of the old ones. Pages from other streams are written back as-is, in their original order.
+	let mut header_pages_seen = 0_u32;
+	let mut pages_reader = Cursor::new(&file_content[..]);
 	while let Ok(mut page) = Page::read(&mut pages_reader) {
+		let header = page.header();
+		let original_page = &file_content[header.start as usize..page.end as usize];
+
+		if header.stream_serial != stream_serial {
+			file.write_all(original_page)?;
+			continue;
+		}
+
+		if header.start < header_end {
+			header_pages_seen += 1;
+			match header_pages_seen {
+				1 => {
+					if let Some(first_page) = new_header_pages.next() {
+						file.write_all(&first_page.as_bytes())?;
+					}
+				},
+				2 => {
+					for page in new_header_pages.by_ref() {
+						file.write_all(&page.as_bytes())?;
+					}
+				},
+				_ => {},
+			}
+
+			continue;
+		}
+
+		// The old header only occupied a single page
+		for page in new_header_pages.by_ref() {
+			file.write_all(&page.as_bytes())?;
+		}
+
+		// The remaining pages only need to be renumbered if the header changed in size
+		let sequence_offset = pages_written.wrapping_sub(header_pages_seen);
+		if sequence_offset == 0 {
+			file.write_all(original_page)?;
+			continue;
+		}
+
 		let header = page.header_mut();
-		header.sequence_number = pages_written + idx;
+		header.sequence_number = header.sequence_number.wrapping_add(sequence_offset);
 		page.gen_crc();
 		file.write_all(&page.as_bytes())?;
+	}
 
-		idx += 1;
+	// The stream has no pages following its header
+	for page in new_header_pages {
+		file.write_all(&page.as_bytes())?;
 	}
 
 	Ok(())
@@ -133,6 +240,7 @@ pub(super) fn create_metadata_packet<'a, II, IP>(
 	comment_signature: &[u8],
 	vendor: &[u8],
 	add_framing_bit: bool,
+	write_options: WriteOptions,
 ) -> Result<Vec<u8>>
 where
 	II: Iterator<Item = (&'a str, &'a str)>,
@@ -149,8 +257,19 @@ where
 	new_comment_packet.write_u32::<LittleEndian>(0)?;
 
 	let mut count = 0;
-	create_comments(&mut new_comment_packet, &mut count, &mut tag.items)?;
-	create_pictures(&mut new_comment_packet, &mut count, &mut tag.pictures)?;
+	create_comments(
+		&mut new_comment_packet,
+		&mut count,
+		&mut tag.items,
+		write_options,
+	)?;
+	create_unparsed_comments(&mut new_comment_packet, &mut count, tag.unparsed_items)?;
+	create_pictures(
+		&mut new_comment_packet,
+		&mut count,
+		&mut tag.pictures,
+		write_options,
+	)?;
 
 	// Seek back and write the item count
 	new_comment_packet.seek(SeekFrom::Start(item_count_pos))?;
@@ -171,23 +290,70 @@ pub(crate) fn create_comments(
 	packet: &mut impl Write,
 	count: &mut u32,
 	items: &mut dyn Iterator<Item = (&str, &str)>,
+	write_options: WriteOptions,
 ) -> Result<()> {
+	let items = items.collect::<Vec<_>>();
+
+	// Any existing legacy album artists get replaced with copies of the canonical ones
+	let write_legacy_album_artist = write_options.legacy_vorbis_album_artist
+		&& items
+			.iter()
+			.any(|(k, _)| k.eq_ignore_ascii_case(ALBUM_ARTIST_KEY));
+
 	for (k, v) in items {
-		if v.is_empty() {
+		let Some(v) = write_options.text_item(v) else {
+			continue;
+		};
+
+		if write_legacy_album_artist && k.eq_ignore_ascii_case(LEGACY_ALBUM_ARTIST_KEY) {
 			continue;
 		}
 
-		let comment = format!("{k}={v}");
-		let comment_bytes = comment.as_bytes();
+		if k.eq_ignore_ascii_case("ISRC") {
+			write_options.check_isrc(v);
+		}
+
+		create_comment(packet, count, k, v)?;
 
-		let Ok(bytes_len) = u32::try_from(comment_bytes.len()) else {
+		if write_legacy_album_artist && k.eq_ignore_ascii_case(ALBUM_ARTIST_KEY) {
+			create_comment(packet, count, LEGACY_ALBUM_ARTIST_KEY, v)?;
+		}
+	}
+
+	Ok(())
+}
+
+fn create_comment(packet: &mut impl Write, count: &mut u32, key: &str, value: &str) -> Result<()> {
+	let comment = format!("{key}={value}");
+	let comment_bytes = comment.as_bytes();
+
+	let Ok(bytes_len) = u32::try_from(comment_bytes.len()) else {
+		err!(TooMuchData);
+	};
+
+	*count += 1;
+
+	packet.write_u32::<LittleEndian>(bytes_len)?;
+	packet.write_all(comment_bytes)?;
+
+	Ok(())
+}
+
+// Comments that failed to parse are written back exactly as they were read
+pub(crate) fn create_unparsed_comments(
+	packet: &mut impl Write,
+	count: &mut u32,
+	unparsed_items: &[Vec<u8>],
+) -> Result<()> {
+	for comment in unparsed_items {
+		let Ok(bytes_len) = u32::try_from(comment.len()) else {
 			err!(TooMuchData);
 		};
 
 		*count += 1;
 
 		packet.write_u32::<LittleEndian>(bytes_len)?;
-		packet.write_all(comment_bytes)?;
+		packet.write_all(comment)?;
 	}
 
 	Ok(())
@@ -197,11 +363,16 @@ fn create_pictures(
 	packet: &mut impl Write,
 	count: &mut u32,
 	pictures: &mut dyn Iterator<Item = (&Picture, PictureInformation)>,
+	write_options: WriteOptions,
 ) -> Result<()> {
 	const PICTURE_KEY: &str = "METADATA_BLOCK_PICTURE=";
 
 	for (pic, info) in pictures {
 		let picture = pic.as_flac_bytes(info, true);
+		check_picture_size(
+			(4 + PICTURE_KEY.len() + picture.len()) as u64,
+			write_options,
+		)?;
 
 		let Ok(bytes_len) = u32::try_from(picture.len() + PICTURE_KEY.len()) else {
 			err!(TooMuchData);
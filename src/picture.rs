@@ -1,7 +1,9 @@
 use crate::error::{ErrorKind, LoftyError, Result};
 use crate::macros::err;
-use crate::probe::ParsingMode;
+use crate::probe::{ParseOptions, ParsingMode};
+use crate::tag::TagType;
 use crate::util::text::utf8_decode_str;
+use crate::write_options::WriteOptions;
 
 use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
@@ -230,32 +232,42 @@ impl PictureType {
 	}
 
 	/// Get a `PictureType` from an APE item key
+	///
+	/// APE item keys are case-insensitive, so `"COVER ART (FRONT)"` is also a [`PictureType::CoverFront`].
 	pub fn from_ape_key(key: &str) -> Self {
-		match key {
-			"Cover Art (Other)" => Self::Other,
-			"Cover Art (Png Icon)" => Self::Icon,
-			"Cover Art (Icon)" => Self::OtherIcon,
-			"Cover Art (Front)" => Self::CoverFront,
-			"Cover Art (Back)" => Self::CoverBack,
-			"Cover Art (Leaflet)" => Self::Leaflet,
-			"Cover Art (Media)" => Self::Media,
-			"Cover Art (Lead Artist)" => Self::LeadArtist,
-			"Cover Art (Artist)" => Self::Artist,
-			"Cover Art (Conductor)" => Self::Conductor,
-			"Cover Art (Band)" => Self::Band,
-			"Cover Art (Composer)" => Self::Composer,
-			"Cover Art (Lyricist)" => Self::Lyricist,
-			"Cover Art (Recording Location)" => Self::RecordingLocation,
-			"Cover Art (During Recording)" => Self::DuringRecording,
-			"Cover Art (During Performance)" => Self::DuringPerformance,
-			"Cover Art (Video Capture)" => Self::ScreenCapture,
-			"Cover Art (Fish)" => Self::BrightFish,
-			"Cover Art (Illustration)" => Self::Illustration,
-			"Cover Art (Band Logotype)" => Self::BandLogo,
-			"Cover Art (Publisher Logotype)" => Self::PublisherLogo,
-			_ => Self::Undefined(0),
+		// The keys are in the same order as the ID3v2 picture types
+		APE_PICTURE_TYPES
+			.iter()
+			.position(|ape_key| ape_key.eq_ignore_ascii_case(key))
+			.map_or(Self::Undefined(0), |index| Self::from_u8(index as u8))
+	}
+}
+
+// Checks the encoded size of a picture prior to writing it, see `WriteOptions::max_picture_size`
+pub(crate) fn check_picture_size(encoded_size: u64, write_options: WriteOptions) -> Result<()> {
+	if encoded_size > ParseOptions::DEFAULT_ALLOCATION_LIMIT as u64 {
+		log::warn!(
+			"Writing a picture of {encoded_size} bytes, which exceeds the default allocation limit. It \
+			 will not be readable unless `ParseOptions::allocation_limit` is raised."
+		);
+	}
+
+	let Some(max_picture_size) = write_options.max_picture_size else {
+		return Ok(());
+	};
+
+	if encoded_size > max_picture_size {
+		if write_options.error_on_oversized_pictures {
+			return Err(LoftyError::new(ErrorKind::PictureTooLarge(encoded_size)));
 		}
+
+		log::warn!(
+			"Writing a picture of {encoded_size} bytes, which exceeds the maximum of \
+			 {max_picture_size} bytes"
+		);
 	}
+
+	Ok(())
 }
 
 /// Information about a [`Picture`]
@@ -442,6 +454,24 @@ impl PictureInformation {
 }
 
 /// Represents a picture.
+///
+/// # Parsing modes
+///
+/// [`Picture::from_reader`], [`Picture::from_flac_bytes`], and [`Picture::from_ape_bytes`] handle
+/// malformed input according to their [`ParsingMode`]:
+///
+/// | Input                                                  | `Strict` | `BestAttempt`               | `Relaxed`                                |
+/// |--------------------------------------------------------|----------|-----------------------------|------------------------------------------|
+/// | Unrecognized image format (reader, APE)                | Error    | No MIME type                | No MIME type                             |
+/// | MIME type isn't valid UTF-8 (FLAC)                     | Error    | Lossy [`MimeType::Unknown`] | Lossy [`MimeType::Unknown`]              |
+/// | Description isn't valid UTF-8 (FLAC, APE)              | Error    | No description              | Lossily decoded                          |
+/// | Picture type above 255 (FLAC)                          | Error    | Truncated to a byte         | Truncated to a byte                      |
+/// | Description longer than the remaining data (FLAC, APE) | Error    | Error                       | Truncated, the rest of the picture empty |
+/// | Picture data longer than the remaining data (FLAC)     | Error    | Error                       | Truncated                                |
+/// | No picture data                                        | Error    | Error                       | An empty picture                         |
+///
+/// For APE, a description longer than the remaining data is one without a null terminator. For FLAC,
+/// any fields missing from the end of the block are zeroed in `Relaxed` mode.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Picture {
 	/// The picture type according to ID3v2 APIC
@@ -477,25 +507,42 @@ impl Picture {
 	///
 	/// # Errors
 	///
-	/// * `reader` contains less than 8 bytes
+	/// * `reader` is empty
 	/// * `reader` does not contain a supported format.
 	/// See [`MimeType`] for valid formats
-	pub fn from_reader<R>(reader: &mut R) -> Result<Self>
+	///
+	/// See [the parsing mode matrix](Picture#parsing-modes) for how each is handled by `parse_mode`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{MimeType, ParsingMode, Picture};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let png_data = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+	/// let picture = Picture::from_reader(&mut &png_data[..], ParsingMode::Strict)?;
+	/// assert_eq!(picture.mime_type(), Some(&MimeType::Png));
+	///
+	/// // An unrecognized format is only an error in `Strict` mode
+	/// assert!(Picture::from_reader(&mut &b"Foo"[..], ParsingMode::Strict).is_err());
+	///
+	/// let picture = Picture::from_reader(&mut &b"Foo"[..], ParsingMode::BestAttempt)?;
+	/// assert_eq!(picture.mime_type(), None);
+	/// # Ok(()) }
+	/// ```
+	pub fn from_reader<R>(reader: &mut R, parse_mode: ParsingMode) -> Result<Self>
 	where
 		R: Read,
 	{
 		let mut data = Vec::new();
 		reader.read_to_end(&mut data)?;
 
-		if data.len() < 8 {
-			err!(NotAPicture);
-		}
-
-		let mime_type = Self::mimetype_from_bin(&data[..8])?;
+		check_picture_data(&data, parse_mode)?;
+		let mime_type = sniff_mime_type(&data, parse_mode)?;
 
 		Ok(Self {
 			pic_type: PictureType::Other,
-			mime_type: Some(mime_type),
+			mime_type,
 			description: None,
 			data: data.into(),
 		})
@@ -566,6 +613,139 @@ impl Picture {
 		self.data.into_owned()
 	}
 
+	/// Whether two pictures have the same data
+	///
+	/// This ignores the [`PictureType`], MIME type, and description, which is useful for finding the same
+	/// image stored multiple times, such as a front cover also used as the artist picture.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{MimeType, Picture, PictureType};
+	///
+	/// let front_cover = Picture::new_unchecked(
+	/// 	PictureType::CoverFront,
+	/// 	Some(MimeType::Png),
+	/// 	None,
+	/// 	vec![1, 2, 3, 4],
+	/// );
+	/// let artist = Picture::new_unchecked(
+	/// 	PictureType::Artist,
+	/// 	None,
+	/// 	Some(String::from("The artist")),
+	/// 	vec![1, 2, 3, 4],
+	/// );
+	///
+	/// assert!(front_cover.eq_data(&artist));
+	/// ```
+	pub fn eq_data(&self, other: &Picture) -> bool {
+		self.data == other.data
+	}
+
+	/// The number of bytes the picture will occupy when written to a tag
+	///
+	/// This includes everything needed to store the picture, such as the frame header for ID3v2, or the
+	/// key and base64 encoding for Vorbis Comments. This can be used to predict the size of a file prior
+	/// to writing it, see also [`WriteOptions::max_picture_size`].
+	///
+	/// NOTES:
+	///
+	/// * For [`TagType::VorbisComments`], this is the size of a base64 encoded `METADATA_BLOCK_PICTURE`
+	///   comment, as used in OGG files. FLAC files always store pictures in native `PICTURE` blocks, which are
+	///   roughly 3/4 of the size, as they aren't base64 encoded.
+	/// * For [`TagType::Id3v2`], this assumes the description will be encoded as UTF-8.
+	///
+	/// Returns `None` if the tag can't store the picture, such as an MP4 tag and a TIFF picture.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{MimeType, Picture, PictureType, TagType};
+	///
+	/// let picture = Picture::new_unchecked(
+	/// 	PictureType::CoverFront,
+	/// 	Some(MimeType::Png),
+	/// 	None,
+	/// 	vec![0; 12_000_000],
+	/// );
+	///
+	/// // Base64 inflates the picture by a third
+	/// let encoded_size = picture.encoded_size_for(TagType::VorbisComments).unwrap();
+	/// assert!(encoded_size > 16_000_000);
+	///
+	/// // ID3v1 can't store pictures at all
+	/// assert!(picture.encoded_size_for(TagType::Id3v1).is_none());
+	/// ```
+	pub fn encoded_size_for(&self, tag_type: TagType) -> Option<u64> {
+		let data_len = self.data.len() as u64;
+		let mime_len = self.mime_str().len() as u64;
+		let description_len = self
+			.description
+			.as_ref()
+			.map_or(0, |desc| desc.len() as u64);
+
+		match tag_type {
+			TagType::Id3v2 => {
+				// Frame header, encoding, MIME type + null, picture type, description + null
+				Some(10 + 1 + mime_len + 1 + 1 + description_len + 1 + data_len)
+			},
+			TagType::Ape => {
+				let key = self.pic_type.as_ape_key()?;
+
+				// Item size, item flags, key + null, description + null
+				Some(4 + 4 + key.len() as u64 + 1 + description_len + 1 + data_len)
+			},
+			TagType::Mp4Ilst => match self.mime_type {
+				// Atom header, version, flags, locale
+				Some(MimeType::Gif | MimeType::Jpeg | MimeType::Png | MimeType::Bmp) | None => {
+					Some(8 + 1 + 3 + 4 + data_len)
+				},
+				_ => None,
+			},
+			TagType::VorbisComments => {
+				// Picture type, MIME type length, description length, picture information, data length
+				let block_len = 4 + 4 + mime_len + 4 + description_len + 16 + 4 + data_len;
+
+				// Comment length, "METADATA_BLOCK_PICTURE=", base64 encoded block
+				Some(4 + 23 + block_len.div_ceil(3) * 4)
+			},
+			_ => None,
+		}
+	}
+
+	pub(crate) fn shrink_to_fit(&mut self) {
+		if let Some(MimeType::Unknown(mime_type)) = &mut self.mime_type {
+			mime_type.shrink_to_fit();
+		}
+
+		if let Some(Cow::Owned(description)) = &mut self.description {
+			description.shrink_to_fit();
+		}
+
+		if let Cow::Owned(data) = &mut self.data {
+			data.shrink_to_fit();
+		}
+	}
+
+	pub(crate) fn heap_size(&self) -> usize {
+		let mime_type_size = match &self.mime_type {
+			Some(MimeType::Unknown(mime_type)) => mime_type.capacity(),
+			_ => 0,
+		};
+
+		let description_size = match &self.description {
+			Some(Cow::Owned(description)) => description.capacity(),
+			_ => 0,
+		};
+
+		let data_size = match &self.data {
+			Cow::Owned(data) => data.capacity(),
+			Cow::Borrowed(_) => 0,
+		};
+
+		mime_type_size + description_size + data_size
+	}
+
 	/// Convert a [`Picture`] to a base64 encoded FLAC `METADATA_BLOCK_PICTURE` String
 	///
 	/// Use `encode` to convert the picture to a base64 encoded String ([RFC 4648 §4](http://www.faqs.org/rfcs/rfc4648.html))
@@ -625,6 +805,8 @@ impl Picture {
 	///
 	/// This function will return [`NotAPicture`][ErrorKind::NotAPicture] if
 	/// at any point it's unable to parse the data
+	///
+	/// See [the parsing mode matrix](Picture#parsing-modes) for what is tolerated by `parse_mode`.
 	pub fn from_flac_bytes(
 		bytes: &[u8],
 		encoded: bool,
@@ -644,17 +826,14 @@ impl Picture {
 		content: &[u8],
 		parse_mode: ParsingMode,
 	) -> Result<(Self, PictureInformation)> {
-		use crate::macros::try_vec;
-
-		let mut size = content.len();
-		let mut reader = Cursor::new(content);
-
-		if size < 32 {
+		// The smallest possible block, with no MIME type, description, or picture data
+		if content.len() < 32 && parse_mode != ParsingMode::Relaxed {
 			err!(NotAPicture);
 		}
 
+		let mut reader = content;
+
 		let pic_ty = reader.read_u32::<BigEndian>()?;
-		size -= 4;
 
 		// ID3v2 APIC uses a single byte for picture type.
 		// Anything greater than that is probably invalid, so
@@ -663,69 +842,41 @@ impl Picture {
 			err!(NotAPicture);
 		}
 
-		let mime_len = reader.read_u32::<BigEndian>()? as usize;
-		size -= 4;
-
-		if mime_len > size {
-			err!(SizeMismatch);
-		}
-
-		let mime_type_str = utf8_decode_str(&content[8..8 + mime_len])?;
-		size -= mime_len;
-
-		reader.seek(SeekFrom::Current(mime_len as i64))?;
-
-		let desc_len = reader.read_u32::<BigEndian>()? as usize;
-		size -= 4;
-
-		let mut description = None;
-		if desc_len > 0 && desc_len < size {
-			let pos = 12 + mime_len;
-
-			if let Ok(desc) = utf8_decode_str(&content[pos..pos + desc_len]) {
-				description = Some(desc.to_owned().into());
-			}
+		let mime_type = read_flac_field(&mut reader, parse_mode)?;
+		let mime_type = match utf8_decode_str(mime_type) {
+			Ok(mime_type) if mime_type.is_empty() => None,
+			Ok(mime_type) => Some(MimeType::from_str(mime_type)),
+			Err(e) if parse_mode == ParsingMode::Strict => return Err(e),
+			Err(_) => {
+				log::warn!("FLAC picture MIME type is not valid UTF-8");
+				Some(MimeType::Unknown(
+					String::from_utf8_lossy(mime_type).into_owned(),
+				))
+			},
+		};
 
-			size -= desc_len;
-			reader.seek(SeekFrom::Current(desc_len as i64))?;
-		}
+		let description =
+			decode_description(read_flac_field(&mut reader, parse_mode)?, parse_mode)?;
 
-		let width = reader.read_u32::<BigEndian>()?;
-		let height = reader.read_u32::<BigEndian>()?;
-		let color_depth = reader.read_u32::<BigEndian>()?;
-		let num_colors = reader.read_u32::<BigEndian>()?;
-		let data_len = reader.read_u32::<BigEndian>()? as usize;
-		size -= 20;
-
-		if data_len <= size {
-			let mut data = try_vec![0; data_len];
-
-			if let Ok(()) = reader.read_exact(&mut data) {
-				let mime_type;
-				if mime_type_str.is_empty() {
-					mime_type = None;
-				} else {
-					mime_type = Some(MimeType::from_str(mime_type_str));
-				}
-
-				return Ok((
-					Self {
-						pic_type: PictureType::from_u8(pic_ty as u8),
-						mime_type,
-						description,
-						data: Cow::from(data),
-					},
-					PictureInformation {
-						width,
-						height,
-						color_depth,
-						num_colors,
-					},
-				));
-			}
-		}
+		let picture_information = PictureInformation {
+			width: read_flac_u32(&mut reader, parse_mode)?,
+			height: read_flac_u32(&mut reader, parse_mode)?,
+			color_depth: read_flac_u32(&mut reader, parse_mode)?,
+			num_colors: read_flac_u32(&mut reader, parse_mode)?,
+		};
 
-		err!(NotAPicture)
+		let data = read_flac_field(&mut reader, parse_mode)?;
+		check_picture_data(data, parse_mode)?;
+
+		Ok((
+			Self {
+				pic_type: PictureType::from_u8(pic_ty as u8),
+				mime_type,
+				description,
+				data: Cow::Owned(data.to_vec()),
+			},
+			picture_information,
+		))
 	}
 
 	/// Convert a [`Picture`] to an APE Cover Art byte vec:
@@ -754,53 +905,40 @@ impl Picture {
 	///
 	/// This function will return [`NotAPicture`](ErrorKind::NotAPicture)
 	/// if at any point it's unable to parse the data
-	pub fn from_ape_bytes(key: &str, bytes: &[u8]) -> Result<Self> {
-		if bytes.is_empty() {
-			err!(NotAPicture);
-		}
-
+	///
+	/// See [the parsing mode matrix](Picture#parsing-modes) for what is tolerated by `parse_mode`.
+	pub fn from_ape_bytes(key: &str, bytes: &[u8], parse_mode: ParsingMode) -> Result<Self> {
 		let pic_type = PictureType::from_ape_key(key);
 
-		let reader = &mut &*bytes;
-		let mut pos = 0;
+		let (description, data) =
+			match bytes.iter().position(|b| *b == 0) {
+				Some(end) => (&bytes[..end], &bytes[end + 1..]),
+				None => {
+					if parse_mode != ParsingMode::Relaxed {
+						err!(NotAPicture);
+					}
 
-		let mut description = None;
-		let mut desc_text = String::new();
+					log::warn!("APE picture description is not terminated, treating the item as a description");
+					(bytes, &[][..])
+				},
+			};
 
-		while let Ok(ch) = reader.read_u8() {
-			pos += 1;
+		let description = decode_description(description, parse_mode)?;
 
-			if ch == b'\0' {
-				break;
-			}
-
-			desc_text.push(char::from(ch));
-		}
-
-		if !desc_text.is_empty() {
-			description = Some(Cow::from(desc_text));
-		}
-
-		let mime_type = {
-			let mut identifier = [0; 8];
-			reader.read_exact(&mut identifier)?;
-
-			Self::mimetype_from_bin(&identifier[..])?
-		};
-
-		let data = Cow::from(bytes[pos..].to_vec());
+		check_picture_data(data, parse_mode)?;
+		let mime_type = sniff_mime_type(data, parse_mode)?;
 
 		Ok(Picture {
 			pic_type,
-			mime_type: Some(mime_type),
+			mime_type,
 			description,
-			data,
+			data: Cow::Owned(data.to_vec()),
 		})
 	}
 
 	pub(crate) fn mimetype_from_bin(bytes: &[u8]) -> Result<MimeType> {
-		match bytes[..8] {
-			[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A] => Ok(MimeType::Png),
+		match bytes {
+			[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Ok(MimeType::Png),
 			[0xFF, 0xD8, ..] => Ok(MimeType::Jpeg),
 			[b'G', b'I', b'F', 0x38, 0x37 | 0x39, b'a', ..] => Ok(MimeType::Gif),
 			[b'B', b'M', ..] => Ok(MimeType::Bmp),
@@ -810,6 +948,73 @@ impl Picture {
 	}
 }
 
+// See the parsing mode matrix on `Picture`
+fn check_picture_data(data: &[u8], parse_mode: ParsingMode) -> Result<()> {
+	if data.is_empty() {
+		if parse_mode != ParsingMode::Relaxed {
+			err!(NotAPicture);
+		}
+
+		log::warn!("Picture has no data");
+	}
+
+	Ok(())
+}
+
+fn sniff_mime_type(data: &[u8], parse_mode: ParsingMode) -> Result<Option<MimeType>> {
+	match Picture::mimetype_from_bin(data) {
+		Ok(mime_type) => Ok(Some(mime_type)),
+		Err(e) if parse_mode == ParsingMode::Strict => Err(e),
+		Err(_) => {
+			log::warn!("Unable to determine the picture's MIME type");
+			Ok(None)
+		},
+	}
+}
+
+fn decode_description(bytes: &[u8], parse_mode: ParsingMode) -> Result<Option<Cow<'static, str>>> {
+	match utf8_decode_str(bytes) {
+		Ok("") => Ok(None),
+		Ok(description) => Ok(Some(Cow::Owned(description.to_owned()))),
+		Err(e) => match parse_mode {
+			ParsingMode::Strict => Err(e),
+			ParsingMode::BestAttempt => {
+				log::warn!("Picture description is not valid UTF-8, discarding");
+				Ok(None)
+			},
+			ParsingMode::Relaxed => Ok(Some(Cow::Owned(
+				String::from_utf8_lossy(bytes).into_owned(),
+			))),
+		},
+	}
+}
+
+// In `Relaxed` mode, fields missing from the end of a truncated block are zeroed
+fn read_flac_u32(reader: &mut &[u8], parse_mode: ParsingMode) -> Result<u32> {
+	match reader.read_u32::<BigEndian>() {
+		Ok(value) => Ok(value),
+		Err(_) if parse_mode == ParsingMode::Relaxed => Ok(0),
+		Err(e) => Err(e.into()),
+	}
+}
+
+// A length-prefixed field, which is truncated in `Relaxed` mode if it runs past the end of the block
+fn read_flac_field<'a>(reader: &mut &'a [u8], parse_mode: ParsingMode) -> Result<&'a [u8]> {
+	let len = read_flac_u32(reader, parse_mode)? as usize;
+	if len > reader.len() {
+		if parse_mode != ParsingMode::Relaxed {
+			err!(SizeMismatch);
+		}
+
+		log::warn!("FLAC picture field runs past the end of the block, truncating");
+	}
+
+	let (field, rest) = reader.split_at(len.min(reader.len()));
+	*reader = rest;
+
+	Ok(field)
+}
+
 // A placeholder that is needed during conversions.
 pub(crate) const TOMBSTONE_PICTURE: Picture = Picture {
 	pic_type: PictureType::Other,
@@ -1,6 +1,6 @@
 use crate::aac::AacFile;
 use crate::ape::ApeFile;
-use crate::error::Result;
+use crate::error::{ErrorKind, LoftyError, Result};
 use crate::file::{AudioFile, FileType, FileTypeGuessResult, TaggedFile};
 use crate::flac::FlacFile;
 use crate::iff::aiff::AiffFile;
@@ -14,10 +14,17 @@ use crate::ogg::opus::OpusFile;
 use crate::ogg::speex::SpeexFile;
 use crate::ogg::vorbis::VorbisFile;
 use crate::resolve::CUSTOM_RESOLVERS;
+use crate::util::io::{read_up_to, HeadTailReader, OffsetFile, PrefixedReader};
 use crate::wavpack::WavPackFile;
 
+#[cfg(feature = "fs")]
+use std::ffi::OsStr;
+#[cfg(feature = "fs")]
 use std::fs::File;
-use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+#[cfg(feature = "fs")]
+use std::io::BufReader;
+use std::io::{Read, Seek, SeekFrom};
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 /// Options to control how Lofty parses a file
@@ -29,6 +36,9 @@ pub struct ParseOptions {
 	pub(crate) parsing_mode: ParsingMode,
 	pub(crate) max_junk_bytes: usize,
 	pub(crate) allocation_limit: usize,
+	pub(crate) max_id3v2_frames: usize,
+	pub(crate) start_offset: u64,
+	pub(crate) resolve_id3v2_genres: bool,
 }
 
 impl Default for ParseOptions {
@@ -41,7 +51,11 @@ impl Default for ParseOptions {
 	/// 	read_properties: true,
 	/// 	use_custom_resolvers: true,
 	/// 	parsing_mode: ParsingMode::BestAttempt,
-	///     max_junk_bytes: 1024
+	///     max_junk_bytes: 1024,
+	///     allocation_limit: 16 * 1024 * 1024,
+	///     max_id3v2_frames: 10_000,
+	///     start_offset: 0,
+	///     resolve_id3v2_genres: false,
 	/// }
 	/// ```
 	fn default() -> Self {
@@ -59,6 +73,9 @@ impl ParseOptions {
 	/// Default allocation limit for any single tag item
 	pub const DEFAULT_ALLOCATION_LIMIT: usize = 16 * 1024 * 1024;
 
+	/// Default maximum number of frames to read from a single ID3v2 tag
+	pub const DEFAULT_MAX_ID3V2_FRAMES: usize = 10_000;
+
 	/// Creates a new `ParseOptions`, alias for `Default` implementation
 	///
 	/// See also: [`ParseOptions::default`]
@@ -78,6 +95,9 @@ impl ParseOptions {
 			parsing_mode: Self::DEFAULT_PARSING_MODE,
 			max_junk_bytes: Self::DEFAULT_MAX_JUNK_BYTES,
 			allocation_limit: Self::DEFAULT_ALLOCATION_LIMIT,
+			max_id3v2_frames: Self::DEFAULT_MAX_ID3V2_FRAMES,
+			start_offset: 0,
+			resolve_id3v2_genres: false,
 		}
 	}
 
@@ -167,6 +187,76 @@ impl ParseOptions {
 		*self
 	}
 
+	/// The maximum number of frames to read from a single ID3v2 tag
+	///
+	/// Tags made up of a huge number of tiny frames are expensive to read, as every frame has to be
+	/// checked against those already read. Once this limit is reached, the rest of the tag is skipped
+	/// and a warning is logged.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ParseOptions;
+	///
+	/// // I only care about the first 100 frames
+	/// let parsing_options = ParseOptions::new().max_id3v2_frames(100);
+	/// ```
+	pub fn max_id3v2_frames(&mut self, max_id3v2_frames: usize) -> Self {
+		self.max_id3v2_frames = max_id3v2_frames;
+		*self
+	}
+
+	/// The offset of the file within the reader
+	///
+	/// Some files are wrapped in a fixed size header that isn't part of the format. Everything before
+	/// `start_offset` is skipped, and the file is read as if it started there. When saving a
+	/// [`TaggedFile`] that was read with a start offset, the bytes before it are left untouched.
+	///
+	/// Any offsets reported by the file types (e.g. [`MpegFile::first_frame_offset`](crate::mpeg::MpegFile::first_frame_offset))
+	/// are still from the start of the reader.
+	///
+	/// NOTE: When guessing the file type with [`Probe::guess_file_type`], be sure to set the options
+	/// with [`Probe::options`] first.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ParseOptions;
+	///
+	/// // My files have a 512 byte header before the actual audio file
+	/// let parsing_options = ParseOptions::new().start_offset(512);
+	/// ```
+	pub fn start_offset(&mut self, start_offset: u64) -> Self {
+		self.start_offset = start_offset;
+		*self
+	}
+
+	/// Whether to resolve numeric genres in ID3v2 `TCON` frames when reading
+	///
+	/// Older software refers to the ID3v1 genre list (see [`GENRES`](crate::id3::v1::GENRES)) in `TCON`
+	/// frames, either in the ID3v2.3 style of `(17)`, or in the ID3v2.4 style of `17`. With this enabled,
+	/// the frame itself is rewritten with the genre names, so the raw frame holds `"Rock"` rather than `"(17)"`,
+	/// and the names are written back when the tag is saved. This also covers `(RX)` (Remix) and `(CR)` (Cover),
+	/// and frames with multiple references, such as `(17)(RX)`, which become multiple values.
+	///
+	/// NOTE: [`Id3v2Tag::genres`](crate::id3::v2::Id3v2Tag::genres), [`Accessor::genre`](crate::Accessor::genre), and
+	///       the conversion into a [`Tag`](crate::Tag) always resolve the genres, regardless of this option.
+	///
+	/// See [`WriteOptions::id3v23_numeric_genres`](crate::WriteOptions::id3v23_numeric_genres) for the inverse.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::ParseOptions;
+	///
+	/// // I want "Rock", not "(17)"
+	/// let parsing_options = ParseOptions::new().resolve_id3v2_genres(true);
+	/// ```
+	pub fn resolve_id3v2_genres(&mut self, resolve_id3v2_genres: bool) -> Self {
+		self.resolve_id3v2_genres = resolve_id3v2_genres;
+		*self
+	}
+
 	fn finalize(self) -> Self {
 		unsafe {
 			crate::util::alloc::update_allocation_limit(self.allocation_limit);
@@ -185,11 +275,14 @@ impl ParseOptions {
 /// ```rust,no_run
 /// use lofty::{ParseOptions, ParsingMode, Probe};
 ///
+/// # #[cfg(feature = "fs")]
 /// # fn main() -> lofty::Result<()> {
 /// // We only want to read spec-compliant inputs
 /// let parsing_options = ParseOptions::new().parsing_mode(ParsingMode::Strict);
 /// let tagged_file = Probe::open("foo.mp3")?.options(parsing_options).read()?;
 /// # Ok(()) }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
 /// ```
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Default)]
 #[non_exhaustive]
@@ -217,13 +310,21 @@ pub enum ParsingMode {
 	BestAttempt,
 	/// Least eager to error, may produce invalid/partial output
 	///
-	/// This mode will discard any invalid fields, and ignore the majority of non-fatal errors.
+	/// This mode will retain any invalid fields as-is, and ignore the majority of non-fatal errors.
 	///
 	/// If the input is malformed, the resulting tags may be incomplete, and the properties zeroed.
 	///
+	/// Fields that could not be parsed are kept in their raw form, and written back unchanged.
+	/// Where they end up depends on the format:
+	///
+	/// * ID3v2 - A frame with a [`FrameValue::Binary`](crate::id3::v2::FrameValue::Binary) value
+	/// * Vorbis Comments - [`VorbisComments::unparsed_items`](crate::ogg::VorbisComments::unparsed_items)
+	/// * APE - [`ApeTag::unparsed_items`](crate::ape::ApeTag::unparsed_items)
+	/// * MP4 - An atom with [`AtomData::Unknown`](crate::mp4::AtomData::Unknown) data
+	///
 	/// ## Examples of behavior
 	///
-	/// * Unable to decode text - The entire item is discarded and the parser moves on
+	/// * Unable to decode text - The item is retained in its raw form and the parser moves on
 	/// * Unable to determine the sample rate - The sample rate will be 0
 	Relaxed,
 }
@@ -240,6 +341,7 @@ pub enum ParsingMode {
 ///
 /// ```rust,no_run
 /// # use lofty::{LoftyError, Probe};
+/// # #[cfg(feature = "fs")]
 /// # fn main() -> Result<(), LoftyError> {
 /// use lofty::FileType;
 ///
@@ -249,12 +351,15 @@ pub enum ParsingMode {
 /// assert_eq!(probe.file_type(), Some(FileType::Mpeg));
 /// # Ok(())
 /// # }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
 /// ```
 ///
 /// When a path isn't available, or is unreliable, content-based detection is also possible.
 ///
 /// ```rust,no_run
 /// # use lofty::{LoftyError, Probe};
+/// # #[cfg(feature = "fs")]
 /// # fn main() -> Result<(), LoftyError> {
 /// use lofty::FileType;
 ///
@@ -265,6 +370,8 @@ pub enum ParsingMode {
 /// assert_eq!(probe.file_type(), Some(FileType::Mpeg));
 /// # Ok(())
 /// # }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
 /// ```
 ///
 /// Or with another reader
@@ -288,8 +395,35 @@ pub struct Probe<R: Read> {
 	inner: R,
 	options: Option<ParseOptions>,
 	f_ty: Option<FileType>,
+	// The file type was guessed from an extension shared by multiple formats, see `Probe::open`
+	provisional: bool,
+	// The start of the content, kept from guessing the file type for `Probe::read`
+	prefix: Option<ProbePrefix>,
+}
+
+// The number of bytes read to guess the file type
+//
+// 36 is just a guess as to how long the data for estimating the file type might be
+const PREFIX_LEN: usize = 36;
+
+#[derive(Copy, Clone)]
+struct ProbePrefix {
+	// Where the bytes were read from, and where the reader was left
+	position: u64,
+	buf: [u8; PREFIX_LEN],
+	len: usize,
+}
+
+impl ProbePrefix {
+	fn bytes(&self) -> &[u8] {
+		&self.buf[..self.len]
+	}
 }
 
+#[cfg(feature = "fs")]
+// Extensions that don't determine the format on their own, `FileType::from_ext` gives a best guess
+const AMBIGUOUS_EXTENSIONS: &[&str] = &["oga"];
+
 impl<R: Read> Probe<R> {
 	/// Create a new `Probe`
 	///
@@ -317,6 +451,8 @@ impl<R: Read> Probe<R> {
 			inner: reader,
 			options: None,
 			f_ty: None,
+			provisional: false,
+			prefix: None,
 		}
 	}
 
@@ -347,6 +483,8 @@ impl<R: Read> Probe<R> {
 			inner: reader,
 			options: None,
 			f_ty: Some(file_type),
+			provisional: false,
+			prefix: None,
 		}
 	}
 
@@ -387,9 +525,48 @@ impl<R: Read> Probe<R> {
 	/// ```
 	pub fn set_file_type(mut self, file_type: FileType) -> Self {
 		self.f_ty = Some(file_type);
+		self.provisional = false;
 		self
 	}
 
+	/// Set the [`FileType`] to that of a registered custom resolver
+	///
+	/// This is the same as using [`Probe::set_file_type`] with a [`FileType::Custom`], though the name
+	/// is checked ahead of time and doesn't need to be `'static`. This is useful for formats that can't
+	/// be detected by [`FileResolver::guess`](crate::resolve::FileResolver::guess).
+	///
+	/// See [`registered_resolvers`](crate::resolve::registered_resolvers) for the available names.
+	///
+	/// # Errors
+	///
+	/// * No resolver is registered under `name` ([`ErrorKind::UnknownFormat`](crate::error::ErrorKind::UnknownFormat))
+	///
+	/// # Examples
+	///
+	/// ```rust,no_run
+	/// use lofty::Probe;
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// # let reader = std::io::Cursor::new(&[]);
+	/// // `MyFile` was registered with `register_custom_resolver`
+	/// let probe = Probe::new(reader).with_resolver("MyFile")?;
+	///
+	/// let parsed_file = probe.read()?;
+	/// # Ok(()) }
+	/// ```
+	pub fn with_resolver(mut self, name: &str) -> Result<Self> {
+		let Some(name) = crate::resolve::registered_resolvers()
+			.into_iter()
+			.find(|n| *n == name)
+		else {
+			err!(UnknownFormat)
+		};
+
+		self.f_ty = Some(FileType::Custom(name));
+		self.provisional = false;
+		Ok(self)
+	}
+
 	/// Set the [`ParseOptions`] for the Probe
 	///
 	/// # Examples
@@ -431,12 +608,17 @@ impl<R: Read> Probe<R> {
 	}
 }
 
+#[cfg(feature = "fs")]
 impl Probe<BufReader<File>> {
 	/// Opens a file for reading
 	///
 	/// This will initially guess the [`FileType`] from the path, but
 	/// this can be overwritten with [`Probe::guess_file_type`] or [`Probe::set_file_type`]
 	///
+	/// Some extensions are used by multiple formats (e.g. `.oga` can be Vorbis, Opus, or FLAC), in which
+	/// case the [`FileType`] is only a best guess. [`Probe::read`] will confirm it from the content
+	/// of the file, unless it has been overwritten.
+	///
 	/// # Errors
 	///
 	/// * `path` does not exist
@@ -458,11 +640,18 @@ impl Probe<BufReader<File>> {
 		P: AsRef<Path>,
 	{
 		let path = path.as_ref();
+		let provisional = path.extension().and_then(OsStr::to_str).is_some_and(|ext| {
+			AMBIGUOUS_EXTENSIONS
+				.iter()
+				.any(|ambiguous| ext.eq_ignore_ascii_case(ambiguous))
+		});
 
 		Ok(Self {
 			inner: BufReader::new(File::open(path)?),
 			options: None,
 			f_ty: FileType::from_path(path),
+			provisional,
+			prefix: None,
 		})
 	}
 }
@@ -504,60 +693,92 @@ impl<R: Read + Seek> Probe<R> {
 				options.max_junk_bytes
 			});
 
+		// Guess from the start of the file, skipping any prefix, see `ParseOptions::start_offset`
+		let start_offset = self.options.map_or(0, |options| options.start_offset);
+		if start_offset > 0 && self.inner.stream_position()? < start_offset {
+			self.inner.seek(SeekFrom::Start(start_offset))?;
+		}
+
 		let f_ty = self.guess_inner(max_junk_bytes)?;
 		self.f_ty = f_ty.or(self.f_ty);
+		self.provisional = false;
 
 		Ok(self)
 	}
 
 	#[allow(clippy::shadow_unrelated)]
 	fn guess_inner(&mut self, max_junk_bytes: usize) -> std::io::Result<Option<FileType>> {
-		// temporary buffer for storing 36 bytes
-		// (36 is just a guess as to how long the data for estimating the file type might be)
-		let mut buf = [0; 36];
-
 		let starting_position = self.inner.stream_position()?;
-		// Read (up to) 36 bytes
-		let buf_len = std::io::copy(
-			&mut self.inner.by_ref().take(buf.len() as u64),
-			&mut Cursor::new(&mut buf[..]),
-		)? as usize;
 
-		self.inner.seek(SeekFrom::Start(starting_position))?;
+		// The prefix is only read once for each position
+		let prefix = match self.prefix {
+			Some(prefix) if prefix.position == starting_position => prefix,
+			_ => {
+				let mut buf = [0; PREFIX_LEN];
+				let len = read_up_to(&mut self.inner, &mut buf)?;
+				self.inner.seek(SeekFrom::Start(starting_position))?;
+
+				ProbePrefix {
+					position: starting_position,
+					buf,
+					len,
+				}
+			},
+		};
+		self.prefix = Some(prefix);
+
+		let buf = prefix.bytes();
 
 		// Guess the file type by using these 36 bytes
-		match FileType::from_buffer_inner(&buf[..buf_len]) {
+		match FileType::from_buffer_inner(buf) {
 			// We were able to determine a file type
 			FileTypeGuessResult::Determined(file_ty) => Ok(Some(file_ty)),
 			// The file starts with an ID3v2 tag; this means other data can follow (e.g. APE or MP3 frames)
 			FileTypeGuessResult::MaybePrecededById3(id3_len) => {
 				// `id3_len` is the size of the tag, not including the header (10 bytes)
-				let position_after_id3_block = self
-					.inner
-					.seek(SeekFrom::Current(i64::from(10 + id3_len)))?;
+				let id3_block_len = 10 + id3_len as usize;
+				let position_after_id3_block = starting_position + id3_block_len as u64;
 
-				// try to guess the file type after the ID3 block by inspecting the first 4 bytes
+				// try to guess the file type after the ID3 block by inspecting the first 4 bytes,
+				// which are already in the prefix if the tag is small enough
 				let mut ident = [0; 4];
-				std::io::copy(
-					&mut self.inner.by_ref().take(ident.len() as u64),
-					&mut Cursor::new(&mut ident[..]),
-				)?;
-
-				self.inner.seek(SeekFrom::Start(position_after_id3_block))?;
+				let mut moved = false;
+				if let Some(cached) = buf.get(id3_block_len..id3_block_len + ident.len()) {
+					ident.copy_from_slice(cached);
+				} else {
+					self.inner.seek(SeekFrom::Start(position_after_id3_block))?;
+					read_up_to(&mut self.inner, &mut ident)?;
+					moved = true;
+				}
 
 				let file_type_after_id3_block = match &ident {
 					[b'M', b'A', b'C', ..] => Ok(Some(FileType::Ape)),
 					b"fLaC" => Ok(Some(FileType::Flac)),
 					b"MPCK" | [b'M', b'P', b'+', ..] => Ok(Some(FileType::Mpc)),
 					// Search for a frame sync, which may be preceded by junk
-					_ => self.check_mpeg_or_aac(max_junk_bytes),
+					_ => {
+						self.inner.seek(SeekFrom::Start(position_after_id3_block))?;
+						moved = true;
+
+						self.check_mpeg_or_aac(max_junk_bytes)
+					},
 				};
 
 				// before returning any result for a file type, seek back to the front
-				self.inner.seek(SeekFrom::Start(starting_position))?;
+				if moved {
+					self.inner.seek(SeekFrom::Start(starting_position))?;
+				}
 
 				file_type_after_id3_block
 			},
+			FileTypeGuessResult::OggSkeleton => {
+				let ret = crate::ogg::guess_multiplexed_type(&mut self.inner);
+
+				// before returning any result for a file type, seek back to the front
+				self.inner.seek(SeekFrom::Start(starting_position))?;
+
+				Ok(ret)
+			},
 			// TODO: Check more than MPEG/AAC
 			FileTypeGuessResult::MaybePrecededByJunk => {
 				let ret = self.check_mpeg_or_aac(max_junk_bytes);
@@ -571,7 +792,7 @@ impl<R: Read + Seek> Probe<R> {
 				if let Ok(lock) = CUSTOM_RESOLVERS.lock() {
 					#[allow(clippy::significant_drop_in_scrutinee)]
 					for (_, resolve) in lock.iter() {
-						if let ret @ Some(_) = resolve.guess(&buf[..buf_len]) {
+						if let ret @ Some(_) = resolve.guess(buf) {
 							return Ok(ret);
 						}
 					}
@@ -636,13 +857,25 @@ impl<R: Read + Seek> Probe<R> {
 	/// # Ok(()) }
 	/// ```
 	pub fn read(mut self) -> Result<TaggedFile> {
-		let reader = &mut self.inner;
+		// The extension wasn't enough to go on, see `Probe::open`
+		if self.provisional {
+			self = self.guess_file_type()?;
+		}
+
+		// The start of the content is still around if the file type was guessed, see `Probe::guess_file_type`
+		let reader = &mut match &self.prefix {
+			Some(prefix) => PrefixedReader::new(&mut self.inner, prefix.bytes(), prefix.position),
+			None => {
+				let position = self.inner.stream_position()?;
+				PrefixedReader::new(&mut self.inner, &[], position)
+			},
+		};
 		let options = self
 			.options
 			.map_or_else(ParseOptions::default, ParseOptions::finalize);
 
-		match self.f_ty {
-			Some(f_type) => Ok(match f_type {
+		let mut tagged_file: TaggedFile = match self.f_ty {
+			Some(f_type) => match f_type {
 				FileType::Aac => AacFile::read_from(reader, options)?.into(),
 				FileType::Aiff => AiffFile::read_from(reader, options)?.into(),
 				FileType::Ape => ApeFile::read_from(reader, options)?.into(),
@@ -660,15 +893,27 @@ impl<R: Read + Seek> Probe<R> {
 						err!(UnknownFormat)
 					}
 
+					// Custom resolvers have no way of knowing about the start offset, so they're only
+					// given what follows it
+					let reader = &mut OffsetFile::new(reader, options.start_offset)?;
+					let options = ParseOptions {
+						start_offset: 0,
+						..options
+					};
+
 					let resolver = crate::resolve::lookup_resolver(c);
 					resolver.read_from(reader, options)?
 				},
-			}),
+			},
 			None => err!(UnknownFormat),
-		}
+		};
+
+		tagged_file.start_offset = options.start_offset;
+		Ok(tagged_file)
 	}
 }
 
+#[cfg(feature = "fs")]
 /// Read a [`TaggedFile`] from a [File]
 ///
 /// # Errors
@@ -695,6 +940,7 @@ pub fn read_from(file: &mut File) -> Result<TaggedFile> {
 	Probe::new(BufReader::new(file)).guess_file_type()?.read()
 }
 
+#[cfg(feature = "fs")]
 /// Read a [`TaggedFile`] from a path
 ///
 /// NOTE: This will determine the [`FileType`] from the extension
@@ -723,9 +969,79 @@ where
 	Probe::open(path)?.read()
 }
 
+/// Read a [`TaggedFile`] from only the beginning and end of a file
+///
+/// This is useful when the file is not stored locally, and fetching the entire file is expensive.
+/// Most formats store their tags at the very beginning (ID3v2, Vorbis Comments, etc.) and/or the very end
+/// (ID3v1, APE, etc.) of the file, so only those ranges need to be fetched.
+///
+/// `head` is the first `head.len()` bytes of the file, and `tail` is the last `tail.len()` bytes of the file.
+/// `file_len` is the total length of the file. If the ranges overlap, `head` takes priority.
+///
+/// NOTE: Reading audio properties may require more data than reading tags. If only the tags are
+///       needed, consider disabling [`ParseOptions::read_properties`].
+///
+/// # Errors
+///
+/// * `head` or `tail` are larger than `file_len`
+/// * [`ErrorKind::NeedRange`](crate::error::ErrorKind::NeedRange), if data outside of the available
+///   ranges is required (for example, an MP4 file with its `moov` atom in the middle). The caller can fetch
+///   the range and try again.
+/// * See [`Probe::read`]
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::error::ErrorKind;
+/// use lofty::{FileType, ParseOptions};
+///
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.mp3";
+/// # let file = std::fs::read(path)?;
+/// # let (head, tail) = (&file[..1024], &file[file.len() - 1024..]);
+/// let file_len = file.len() as u64;
+/// let parse_options = ParseOptions::new().read_properties(false);
+///
+/// match lofty::read_from_head_and_tail(head, tail, file_len, FileType::Mpeg, parse_options) {
+/// 	Ok(tagged_file) => { /* ... */ },
+/// 	Err(e) => match e.kind() {
+/// 		ErrorKind::NeedRange(range) => { /* Fetch `range` and try again */ },
+/// 		_ => return Err(e),
+/// 	},
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_from_head_and_tail(
+	head: &[u8],
+	tail: &[u8],
+	file_len: u64,
+	file_type: FileType,
+	parse_options: ParseOptions,
+) -> Result<TaggedFile> {
+	if head.len() as u64 > file_len || tail.len() as u64 > file_len {
+		err!(SizeMismatch);
+	}
+
+	let mut reader = HeadTailReader::new(head, tail, file_len);
+	let result = Probe::with_file_type(&mut reader, file_type)
+		.options(parse_options)
+		.read();
+
+	// Even if the read succeeded, parsers may treat read failures as the end of the stream, so
+	// we can't trust the result if any unavailable data was requested.
+	if let Some(range) = reader.missing_range() {
+		return Err(LoftyError::new(ErrorKind::NeedRange(range)));
+	}
+
+	result
+}
+
 #[cfg(test)]
 mod tests {
-	use crate::{FileType, Probe};
+	use crate::{AudioFile, FileType, Probe};
+
+	#[cfg(feature = "fs")]
+	use crate::TaggedFileExt;
 
 	use lofty::ParseOptions;
 	use std::fs::File;
@@ -845,8 +1161,79 @@ mod tests {
 		assert!(probe.read().is_ok());
 	}
 
+	#[test]
+	fn head_and_tail() {
+		use crate::{TagType, TaggedFileExt};
+
+		let path = "tests/files/assets/minimal/full_test.mp3";
+		let file = std::fs::read(path).unwrap();
+		let file_len = file.len() as u64;
+
+		let (head, tail) = (&file[..2048], &file[file.len() - 2048..]);
+		let tagged_file = super::read_from_head_and_tail(
+			head,
+			tail,
+			file_len,
+			FileType::Mpeg,
+			ParseOptions::new().read_properties(false),
+		)
+		.unwrap();
+
+		let expected =
+			crate::TaggedFile::read_from(&mut File::open(path).unwrap(), ParseOptions::new())
+				.unwrap();
+		for tag_type in [TagType::Id3v2, TagType::Id3v1, TagType::Ape] {
+			assert_eq!(
+				tagged_file
+					.tag(tag_type)
+					.unwrap()
+					.items()
+					.collect::<Vec<_>>(),
+				expected.tag(tag_type).unwrap().items().collect::<Vec<_>>()
+			);
+		}
+	}
+
+	#[test]
+	fn head_and_tail_need_range() {
+		use crate::error::ErrorKind;
+
+		let file = std::fs::read("tests/files/assets/minimal/m4a_codec_aac.m4a").unwrap();
+		let file_len = file.len() as u64;
+
+		// Only the `ftyp` atom is available
+		let (head, tail) = (&file[..32], &file[file.len() - 8..]);
+		let Err(err) = super::read_from_head_and_tail(
+			head,
+			tail,
+			file_len,
+			FileType::Mp4,
+			ParseOptions::new(),
+		) else {
+			panic!("Expected an error");
+		};
+
+		let ErrorKind::NeedRange(range) = err.kind() else {
+			panic!("Expected `ErrorKind::NeedRange`, got: {err}");
+		};
+
+		assert!(range.start >= 32);
+		assert!(range.end <= file_len - 8);
+
+		// The full file is available
+		assert!(super::read_from_head_and_tail(
+			&file,
+			&[],
+			file_len,
+			FileType::Mp4,
+			ParseOptions::new()
+		)
+		.is_ok());
+	}
+
 	fn test_probe(path: &str, expected_file_type_guess: FileType) {
 		test_probe_file(path, expected_file_type_guess);
+		#[cfg(feature = "fs")]
 		test_probe_path(path, expected_file_type_guess);
 	}
 
@@ -858,6 +1245,7 @@ mod tests {
 	}
 
 	// Test from file extension
+	#[cfg(feature = "fs")]
 	fn test_probe_path(path: &str, expected_file_type_guess: FileType) {
 		let probe = Probe::open(path).unwrap();
 		assert_eq!(probe.file_type(), Some(expected_file_type_guess));
@@ -903,6 +1291,11 @@ mod tests {
 		test_probe("tests/files/assets/junk.mp3", FileType::Mpeg);
 	}
 
+	#[test]
+	fn probe_mp3_with_riff_prefix() {
+		test_probe("tests/files/assets/riff_prefix.mp3", FileType::Mpeg);
+	}
+
 	#[test]
 	fn probe_vorbis() {
 		test_probe("tests/files/assets/minimal/full_test.ogg", FileType::Vorbis);
@@ -918,6 +1311,12 @@ mod tests {
 		test_probe("tests/files/assets/minimal/full_test.spx", FileType::Speex);
 	}
 
+	#[test]
+	fn probe_ogg_with_skeleton() {
+		test_probe("tests/files/assets/skeleton.ogg", FileType::Vorbis);
+		test_probe("tests/files/assets/skeleton.opus", FileType::Opus);
+	}
+
 	#[test]
 	fn probe_mp4() {
 		test_probe(
@@ -933,4 +1332,201 @@ mod tests {
 			FileType::Wav,
 		);
 	}
+
+	// A copy of `path`, with a different extension
+	#[cfg(feature = "fs")]
+	fn renamed(path: &str, ext: &str) -> tempfile::NamedTempFile {
+		let file = tempfile::Builder::new()
+			.suffix(&format!(".{ext}"))
+			.tempfile()
+			.unwrap();
+		std::fs::copy(path, file.path()).unwrap();
+		file
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn probe_extensions() {
+		for (path, extensions, file_type) in [
+			(
+				"tests/files/assets/minimal/m4a_codec_aac.m4a",
+				&["M4A", "m4b", "m4p", "m4r", "mp4a"][..],
+				FileType::Mp4,
+			),
+			(
+				"tests/files/assets/minimal/full_test.aiff",
+				&["aifc", "AIF"],
+				FileType::Aiff,
+			),
+			(
+				"tests/files/assets/minimal/wav_format_pcm.wav",
+				&["wave", "WAV"],
+				FileType::Wav,
+			),
+			(
+				"tests/files/assets/minimal/full_test.flac",
+				&["FLAC"],
+				FileType::Flac,
+			),
+			(
+				"tests/files/assets/minimal/full_test.mp3",
+				&["Mp3"],
+				FileType::Mpeg,
+			),
+		] {
+			for ext in extensions {
+				let file = renamed(path, ext);
+				test_probe_path(file.path().to_str().unwrap(), file_type);
+
+				let tagged_file = Probe::open(file.path()).unwrap().read().unwrap();
+				assert_eq!(tagged_file.file_type(), file_type, "{ext}");
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn probe_ambiguous_extension() {
+		for (path, file_type) in [
+			("tests/files/assets/minimal/full_test.ogg", FileType::Vorbis),
+			("tests/files/assets/minimal/full_test.opus", FileType::Opus),
+		] {
+			for ext in ["oga", "OGA"] {
+				let file = renamed(path, ext);
+
+				// Only a guess until the file is read
+				test_probe_path(file.path().to_str().unwrap(), FileType::Vorbis);
+
+				let tagged_file = Probe::open(file.path()).unwrap().read().unwrap();
+				assert_eq!(tagged_file.file_type(), file_type);
+			}
+		}
+
+		// An explicit file type is always respected
+		let file = renamed("tests/files/assets/minimal/full_test.opus", "oga");
+		let probe = Probe::open(file.path())
+			.unwrap()
+			.set_file_type(FileType::Vorbis);
+		assert!(probe.read().is_err());
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn start_offset() {
+		use crate::mpeg::MpegFile;
+		use crate::{Accessor, AudioFile, WriteOptions};
+		use std::io::{Read, Seek, Write};
+
+		const PREFIX: [u8; 512] = [0xAB; 512];
+		let options = ParseOptions::new().start_offset(PREFIX.len() as u64);
+
+		for path in [
+			"tests/files/assets/minimal/full_test.mp3",
+			"tests/files/assets/minimal/m4a_codec_aac.m4a",
+			"tests/files/assets/minimal/full_test.flac",
+			"tests/files/assets/minimal/full_test.opus",
+			"tests/files/assets/minimal/full_test.ape",
+			"tests/files/assets/minimal/wav_format_pcm.wav",
+		] {
+			let original = std::fs::read(path).unwrap();
+			let expected = crate::read_from_path(path).unwrap();
+
+			let mut file = tempfile::tempfile().unwrap();
+			file.write_all(&PREFIX).unwrap();
+			file.write_all(&original).unwrap();
+			file.rewind().unwrap();
+
+			let mut tagged_file = crate::TaggedFile::read_from(&mut file, options).unwrap();
+			assert_eq!(tagged_file.file_type(), expected.file_type(), "{path}");
+			assert_eq!(tagged_file.tags().len(), expected.tags().len(), "{path}");
+			for (tag, expected) in tagged_file.tags().iter().zip(expected.tags()) {
+				assert!(tag.eq_content(expected), "{path}");
+			}
+
+			// Saving leaves the prefix alone
+			let mut tag = tagged_file
+				.primary_tag()
+				.cloned()
+				.unwrap_or_else(|| crate::Tag::new(tagged_file.primary_tag_type()));
+			tag.set_title(String::from("Foo title"));
+			tagged_file.insert_tag(tag);
+
+			file.rewind().unwrap();
+			tagged_file
+				.save_to(&mut file, WriteOptions::default())
+				.unwrap();
+
+			let mut saved = Vec::new();
+			file.rewind().unwrap();
+			file.read_to_end(&mut saved).unwrap();
+			assert_eq!(&saved[..PREFIX.len()], PREFIX, "{path}");
+
+			file.rewind().unwrap();
+			let tagged_file = Probe::new(&mut file)
+				.options(options)
+				.guess_file_type()
+				.unwrap()
+				.read()
+				.unwrap();
+			assert_eq!(
+				tagged_file.primary_tag().unwrap().title().as_deref(),
+				Some("Foo title"),
+				"{path}"
+			);
+		}
+
+		// Offsets are still from the start of the reader
+		let mut file = PREFIX.to_vec();
+		file.extend(std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap());
+		let mpeg_file = MpegFile::read_from(&mut std::io::Cursor::new(file), options).unwrap();
+		assert_eq!(mpeg_file.first_frame_offset(), Some(512 + 31));
+	}
+
+	#[test]
+	fn prefix_read_once() {
+		use std::cell::Cell;
+		use std::io::{Cursor, Read, Seek, SeekFrom};
+
+		// Counts the reads made within the first 36 bytes
+		struct CountingReader<'a> {
+			inner: Cursor<Vec<u8>>,
+			prefix_reads: &'a Cell<usize>,
+		}
+
+		impl Read for CountingReader<'_> {
+			fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+				if self.inner.position() < 36 {
+					self.prefix_reads.set(self.prefix_reads.get() + 1);
+				}
+
+				self.inner.read(buf)
+			}
+		}
+
+		impl Seek for CountingReader<'_> {
+			fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+				self.inner.seek(pos)
+			}
+		}
+
+		for path in [
+			"tests/files/assets/minimal/full_test.flac",
+			"tests/files/assets/minimal/full_test.mp3",
+			"tests/files/assets/minimal/wav_format_pcm.wav",
+		] {
+			let prefix_reads = Cell::new(0);
+			let reader = CountingReader {
+				inner: Cursor::new(std::fs::read(path).unwrap()),
+				prefix_reads: &prefix_reads,
+			};
+
+			let probe = Probe::new(reader).guess_file_type().unwrap();
+			let guess_reads = prefix_reads.get();
+			assert!(guess_reads > 0, "{path}");
+
+			// Reading the file reuses the bytes read while guessing its type
+			probe.read().unwrap();
+			assert_eq!(prefix_reads.get(), guess_reads, "{path}");
+		}
+	}
 }
@@ -1,6 +1,8 @@
 use std::time::Duration;
 
 /// Various *immutable* audio properties
+///
+/// Any value that is unknown, or not defined by the format (such as the bit depth of a lossy format), will be `None`.
 #[derive(Debug, PartialEq, Eq, Clone)]
 #[non_exhaustive]
 pub struct FileProperties {
@@ -11,6 +13,9 @@ pub struct FileProperties {
 	pub(crate) bit_depth: Option<u8>,
 	pub(crate) channels: Option<u8>,
 	pub(crate) channel_mask: Option<ChannelMask>,
+	pub(crate) gapless_info: Option<GaplessInfo>,
+	pub(crate) duration_estimated: bool,
+	pub(crate) audio_bitrate_source: Option<BitrateSource>,
 }
 
 impl Default for FileProperties {
@@ -23,6 +28,9 @@ impl Default for FileProperties {
 			bit_depth: None,
 			channels: None,
 			channel_mask: None,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: None,
 		}
 	}
 }
@@ -47,6 +55,9 @@ impl FileProperties {
 			bit_depth,
 			channels,
 			channel_mask,
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: None,
 		}
 	}
 
@@ -56,15 +67,30 @@ impl FileProperties {
 	}
 
 	/// Overall bitrate (kbps)
+	///
+	/// This is the size of the entire file, including any tags and other metadata, divided by the duration.
+	/// It is always computed.
 	pub fn overall_bitrate(&self) -> Option<u32> {
 		self.overall_bitrate
 	}
 
 	/// Audio bitrate (kbps)
+	///
+	/// This only covers the audio stream, excluding any tags and other metadata. Depending on the
+	/// format, it is either computed from the size of the stream, or read from the file.
+	///
+	/// See [`FileProperties::audio_bitrate_source`]
 	pub fn audio_bitrate(&self) -> Option<u32> {
 		self.audio_bitrate
 	}
 
+	/// Where the [audio bitrate](FileProperties::audio_bitrate) comes from
+	///
+	/// This is `None` if the audio bitrate is unknown.
+	pub fn audio_bitrate_source(&self) -> Option<BitrateSource> {
+		self.audio_bitrate_source
+	}
+
 	/// Sample rate (Hz)
 	pub fn sample_rate(&self) -> Option<u32> {
 		self.sample_rate
@@ -84,6 +110,51 @@ impl FileProperties {
 	pub fn channel_mask(&self) -> Option<ChannelMask> {
 		self.channel_mask
 	}
+
+	/// Encoder delay and padding, for gapless playback
+	///
+	/// See [`GaplessInfo`]
+	pub fn gapless_info(&self) -> Option<GaplessInfo> {
+		self.gapless_info
+	}
+
+	/// Whether the duration is an estimate
+	///
+	/// This is the case when the end of the file is corrupt, and the duration had to be calculated
+	/// from the last intact part of the stream. Currently, this only applies to OGG formats.
+	///
+	/// See [`ParsingMode`](crate::ParsingMode)
+	pub fn is_duration_estimated(&self) -> bool {
+		self.duration_estimated
+	}
+}
+
+/// The source of an [audio bitrate](FileProperties::audio_bitrate)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum BitrateSource {
+	/// The bitrate was read from the file
+	///
+	/// This is the bitrate the encoder claims to have used, such as the nominal bitrate of a Vorbis
+	/// stream, or the bitrate of the first frame of a CBR MPEG stream. It may differ from the actual
+	/// bitrate of the stream.
+	Declared,
+	/// The bitrate was computed from the size of the audio stream and its duration
+	#[default]
+	Computed,
+}
+
+/// Treat a zeroed value as unknown
+///
+/// The format-specific properties use `0` for values that aren't known or aren't defined by the format,
+/// which would be meaningless in [`FileProperties`] (there is no such thing as a 0 Hz, 0 channel stream).
+pub(crate) fn non_zero<T: Default + PartialEq>(value: T) -> Option<T> {
+	(value != T::default()).then_some(value)
+}
+
+/// The source of an audio bitrate, which is only known if the bitrate itself is
+pub(crate) fn bitrate_source(audio_bitrate: u32, source: BitrateSource) -> Option<BitrateSource> {
+	(audio_bitrate != 0).then_some(source)
 }
 
 /// Channel mask
@@ -118,6 +189,65 @@ impl ChannelMask {
 	}
 }
 
+/// Encoder delay and padding, for gapless playback
+///
+/// Lossy encoders add samples to the start (the delay) and end (the padding) of a stream. To play
+/// tracks back to back without a gap, a player needs to trim them, keeping only the valid samples.
+///
+/// All values are in samples at the stream's sample rate (48 kHz for Opus), and don't include the delay of
+/// the decoder itself (e.g. the 529 samples of an MP3 decoder).
+///
+/// See [`GaplessSource`] for where this information comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GaplessInfo {
+	pub(crate) encoder_delay_samples: u32,
+	pub(crate) encoder_padding_samples: Option<u32>,
+	pub(crate) valid_samples: u64,
+	pub(crate) source: GaplessSource,
+}
+
+impl GaplessInfo {
+	/// The number of samples to skip at the start of the stream
+	pub fn encoder_delay_samples(&self) -> u32 {
+		self.encoder_delay_samples
+	}
+
+	/// The number of samples to skip at the end of the stream
+	///
+	/// This is `None` for sources that only store the number of valid samples.
+	pub fn encoder_padding_samples(&self) -> Option<u32> {
+		self.encoder_padding_samples
+	}
+
+	/// The number of samples remaining after the delay and padding are removed
+	pub fn valid_samples(&self) -> u64 {
+		self.valid_samples
+	}
+
+	/// Where the information was read from
+	pub fn source(&self) -> GaplessSource {
+		self.source
+	}
+}
+
+/// The source of a [`GaplessInfo`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GaplessSource {
+	/// The LAME tag of an MPEG file, found in the Xing/Info header
+	///
+	/// This is written by LAME, as well as FFmpeg.
+	LameTag,
+	/// The iTunes `----:com.apple.iTunes:iTunSMPB` atom of an MP4 file
+	ITunSmpb,
+	/// The edit list (`trak.edts.elst`) of an MP4 file
+	EditList,
+	/// The pre-skip of the Opus identification header, along with the stream's granule positions
+	///
+	/// Opus doesn't store the padding, the end of the stream is instead marked by the final granule position.
+	OpusHeader,
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::aac::{AACProperties, AacFile};
@@ -126,21 +256,30 @@ mod tests {
 	use crate::iff::aiff::{AiffFile, AiffProperties};
 	use crate::iff::wav::{WavFile, WavFormat, WavProperties};
 	use crate::mp4::{AudioObjectType, Mp4Codec, Mp4File, Mp4Properties};
-	use crate::mpeg::{ChannelMode, Layer, MpegFile, MpegProperties, MpegVersion};
+	use crate::mpeg::{
+		ChannelMode, LameHeader, Layer, MpegFile, MpegProperties, MpegVersion, SourceSampleRate,
+	};
 	use crate::musepack::sv4to6::MpcSv4to6Properties;
 	use crate::musepack::sv7::{Link, MpcSv7Properties, Profile};
 	use crate::musepack::sv8::{EncoderInfo, MpcSv8Properties, ReplayGain, StreamHeader};
 	use crate::musepack::{MpcFile, MpcProperties};
 	use crate::ogg::{
-		OpusFile, OpusProperties, SpeexFile, SpeexProperties, VorbisFile, VorbisProperties,
+		OpusChannelMapping, OpusFile, OpusProperties, SpeexFile, SpeexProperties, VorbisFile,
+		VorbisProperties,
 	};
-	use crate::probe::ParseOptions;
+	use crate::probe::{ParseOptions, ParsingMode};
 	use crate::wavpack::{WavPackFile, WavPackProperties};
-	use crate::{AudioFile, ChannelMask};
+	use crate::{
+		AudioFile, BitrateSource, ChannelMask, FileProperties, GaplessInfo, GaplessSource, Result,
+		TagExt,
+	};
 
 	use std::fs::File;
+	use std::io::Cursor;
 	use std::time::Duration;
 
+	use ogg_pager::PAGE_HEADER_SIZE;
+
 	// These values are taken from FFmpeg's ffprobe
 	// There is a chance they will be +/- 1, anything greater (for real world files)
 	// is an issue.
@@ -148,7 +287,7 @@ mod tests {
 	const AAC_PROPERTIES: AACProperties = AACProperties {
 		version: MpegVersion::V4,
 		audio_object_type: AudioObjectType::AacLowComplexity,
-		duration: Duration::from_millis(1474), /* TODO: This is ~100ms greater than FFmpeg's report, can we do better? */
+		duration: Duration::from_millis(1473), /* TODO: This is ~100ms greater than FFmpeg's report, can we do better? */
 		overall_bitrate: 117,                  // 9 less than FFmpeg reports
 		audio_bitrate: 117,                    // 9 less than FFmpeg reports
 		sample_rate: 48000,
@@ -200,6 +339,9 @@ mod tests {
 		sample_rate: 32000,
 		channels: 2,
 		emphasis: None,
+		gapless_info: None,
+		lame_header: None,
+		audio_bitrate_source: BitrateSource::Declared,
 	};
 
 	const MP2_PROPERTIES: MpegProperties = MpegProperties {
@@ -215,6 +357,9 @@ mod tests {
 		sample_rate: 48000,
 		channels: 2,
 		emphasis: None,
+		gapless_info: None,
+		lame_header: None,
+		audio_bitrate_source: BitrateSource::Declared,
 	};
 
 	const MP3_PROPERTIES: MpegProperties = MpegProperties {
@@ -230,6 +375,27 @@ mod tests {
 		sample_rate: 48000,
 		channels: 2,
 		emphasis: None,
+		gapless_info: Some(GaplessInfo {
+			encoder_delay_samples: 576,
+			encoder_padding_samples: Some(1150),
+			valid_samples: 68546,
+			source: GaplessSource::LameTag,
+		}),
+		// Written by FFmpeg, which leaves most fields zeroed
+		lame_header: Some(LameHeader {
+			encoder: *b"Lavc58.91",
+			revision: 0,
+			vbr_method: 0,
+			lowpass: 0,
+			quality: Some(0),
+			bitrate: 0,
+			encoder_delay: 576,
+			encoder_padding: 1150,
+			noise_shaping: 0,
+			source_sample_rate: SourceSampleRate::UpTo32000,
+			preset: 0,
+		}),
+		audio_bitrate_source: BitrateSource::Computed,
 	};
 
 	const MP4_AAC_PROPERTIES: Mp4Properties = Mp4Properties {
@@ -242,6 +408,14 @@ mod tests {
 		bit_depth: None,
 		channels: 2,
 		drm_protected: false,
+		// The edit list uses a millisecond timescale, so the valid samples (68546) are rounded
+		gapless_info: Some(GaplessInfo {
+			encoder_delay_samples: 1024,
+			encoder_padding_samples: Some(2),
+			valid_samples: 68544,
+			source: GaplessSource::EditList,
+		}),
+		audio_bitrate_source: BitrateSource::Declared,
 	};
 
 	const MP4_ALAC_PROPERTIES: Mp4Properties = Mp4Properties {
@@ -254,6 +428,8 @@ mod tests {
 		bit_depth: Some(16),
 		channels: 2,
 		drm_protected: false,
+		gapless_info: None,
+		audio_bitrate_source: BitrateSource::Declared,
 	};
 
 	const MP4_ALS_PROPERTIES: Mp4Properties = Mp4Properties {
@@ -266,6 +442,8 @@ mod tests {
 		bit_depth: None,
 		channels: 2,
 		drm_protected: false,
+		gapless_info: None,
+		audio_bitrate_source: BitrateSource::Declared,
 	};
 
 	const MP4_FLAC_PROPERTIES: Mp4Properties = Mp4Properties {
@@ -278,22 +456,27 @@ mod tests {
 		bit_depth: Some(16),
 		channels: 2,
 		drm_protected: false,
+		gapless_info: None,
+		audio_bitrate_source: BitrateSource::Computed,
 	};
 
 	const MPC_SV5_PROPERTIES: MpcSv4to6Properties = MpcSv4to6Properties {
-		duration: Duration::from_millis(27),
-		audio_bitrate: 41,
+		// The file is truncated, only the header is intact
+		duration: Duration::from_millis(26347),
+		overall_bitrate: 0,
+		audio_bitrate: 0,
 		channels: 2,
 		frame_count: 1009,
 		mid_side_stereo: true,
 		stream_version: 5,
 		max_band: 31,
 		sample_rate: 44100,
+		audio_bitrate_source: BitrateSource::Computed,
 	};
 
 	const MPC_SV7_PROPERTIES: MpcSv7Properties = MpcSv7Properties {
 		duration: Duration::from_millis(1428),
-		overall_bitrate: 86,
+		overall_bitrate: 87,
 		audio_bitrate: 86,
 		channels: 2,
 		frame_count: 60,
@@ -316,7 +499,7 @@ mod tests {
 
 	const MPC_SV8_PROPERTIES: MpcSv8Properties = MpcSv8Properties {
 		duration: Duration::from_millis(1428),
-		overall_bitrate: 82,
+		overall_bitrate: 84,
 		audio_bitrate: 82,
 		stream_header: StreamHeader {
 			crc: 4_252_559_415,
@@ -345,14 +528,31 @@ mod tests {
 		}),
 	};
 
-	const OPUS_PROPERTIES: OpusProperties = OpusProperties {
-		duration: Duration::from_millis(1428),
-		overall_bitrate: 120,
-		audio_bitrate: 120,
-		channels: 2,
-		version: 1,
-		input_sample_rate: 48000,
-	};
+	// Not a constant, the channel mapping table is heap allocated
+	fn expected_opus_properties() -> OpusProperties {
+		OpusProperties {
+			duration: Duration::from_millis(1428),
+			overall_bitrate: 120,
+			audio_bitrate: 120,
+			channels: 2,
+			channel_mask: Some(ChannelMask::stereo()),
+			channel_mapping: OpusChannelMapping {
+				family: 0,
+				stream_count: 1,
+				coupled_stream_count: 1,
+				mapping: vec![0, 1],
+			},
+			version: 1,
+			input_sample_rate: 48000,
+			gapless_info: Some(GaplessInfo {
+				encoder_delay_samples: 312,
+				encoder_padding_samples: None,
+				valid_samples: 68546,
+				source: GaplessSource::OpusHeader,
+			}),
+			duration_estimated: false,
+		}
+	}
 
 	const SPEEX_PROPERTIES: SpeexProperties = SpeexProperties {
 		duration: Duration::from_millis(1469),
@@ -364,6 +564,7 @@ mod tests {
 		overall_bitrate: 32,
 		audio_bitrate: 29,
 		nominal_bitrate: 29600,
+		duration_estimated: false,
 	};
 
 	const VORBIS_PROPERTIES: VorbisProperties = VorbisProperties {
@@ -372,10 +573,12 @@ mod tests {
 		audio_bitrate: 112,
 		sample_rate: 48000,
 		channels: 2,
+		channel_mask: Some(ChannelMask::stereo()),
 		version: 0,
 		bitrate_maximum: 0,
 		bitrate_nominal: 112_000,
 		bitrate_minimum: 0,
+		duration_estimated: false,
 	};
 
 	const WAV_PROPERTIES: WavProperties = WavProperties {
@@ -385,8 +588,11 @@ mod tests {
 		audio_bitrate: 1536,
 		sample_rate: 48000,
 		bit_depth: 16,
+		container_bit_depth: 16,
+		valid_bit_depth: None,
 		channels: 2,
 		channel_mask: None,
+		audio_bitrate_source: BitrateSource::Computed,
 	};
 
 	const WAVPACK_PROPERTIES: WavPackProperties = WavPackProperties {
@@ -477,6 +683,29 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn mp4_fragmented_properties() {
+		// The same stream as `m4a_codec_aac.m4a`, split into two fragments
+		let expected = Mp4Properties {
+			overall_bitrate: 137,
+			// No edit list
+			gapless_info: None,
+			..MP4_AAC_PROPERTIES
+		};
+
+		// Duration from the sum of the fragments' sample durations
+		assert_eq!(
+			get_properties::<Mp4File>("tests/files/assets/fragmented.m4a"),
+			expected
+		);
+
+		// Duration from the `mehd` atom
+		assert_eq!(
+			get_properties::<Mp4File>("tests/files/assets/fragmented_mehd.m4a"),
+			expected
+		);
+	}
+
 	#[test]
 	fn mp4_alac_properties() {
 		assert_eq!(
@@ -529,10 +758,141 @@ mod tests {
 	fn opus_properties() {
 		assert_eq!(
 			get_properties::<OpusFile>("tests/files/assets/minimal/full_test.opus"),
-			OPUS_PROPERTIES
+			expected_opus_properties()
 		)
 	}
 
+	// Reads `full_test.opus`, after making changes to its pages
+	fn read_opus_pages(edit: impl FnOnce(&mut Vec<ogg_pager::Page>)) -> OpusProperties {
+		let mut reader = File::open("tests/files/assets/minimal/full_test.opus").unwrap();
+
+		let mut pages = Vec::new();
+		while let Ok(page) = ogg_pager::Page::read(&mut reader) {
+			pages.push(page);
+		}
+
+		edit(&mut pages);
+
+		let mut bytes = Vec::new();
+		for page in &mut pages {
+			page.gen_crc();
+			bytes.extend(page.as_bytes());
+		}
+
+		let file = OpusFile::read_from(&mut Cursor::new(bytes), ParseOptions::new()).unwrap();
+		file.properties().clone()
+	}
+
+	#[test]
+	fn opus_granule_positions() {
+		// A stream that starts mid-broadcast, the granule positions are offset by an hour
+		let properties = read_opus_pages(|pages| {
+			for page in &mut pages[2..] {
+				page.header_mut().abgp += 48000 * 60 * 60;
+			}
+		});
+		assert_eq!(properties.duration(), expected_opus_properties().duration);
+
+		// A trailing page that doesn't finish a packet has a granule position of -1
+		let properties = read_opus_pages(|pages| {
+			let mut trailing_page = pages.last().unwrap().clone();
+			trailing_page.header_mut().abgp = u64::MAX;
+			trailing_page.header_mut().sequence_number += 1;
+			pages.push(trailing_page);
+		});
+		assert_eq!(properties.duration(), expected_opus_properties().duration);
+	}
+
+	#[test]
+	fn ogg_truncated_final_page() {
+		// An interrupted download, cut off partway through the final page
+		fn read_truncated<T: AudioFile>(path: &str, parsing_mode: ParsingMode) -> Result<T> {
+			let mut bytes = std::fs::read(path).unwrap();
+			bytes.truncate(bytes.len() - 1000);
+
+			T::read_from(
+				&mut Cursor::new(bytes),
+				ParseOptions::new().parsing_mode(parsing_mode),
+			)
+		}
+
+		let opus_path = "tests/files/assets/minimal/full_test.opus";
+		let file = read_truncated::<OpusFile>(opus_path, ParsingMode::BestAttempt).unwrap();
+		let properties = file.properties();
+		assert!(properties.is_duration_estimated());
+		assert!(properties.duration() > Duration::ZERO);
+		assert!(properties.duration() < expected_opus_properties().duration);
+		assert!(FileProperties::from(properties.clone()).is_duration_estimated());
+		assert!(!file.vorbis_comments().is_empty());
+
+		let vorbis_path = "tests/files/assets/minimal/full_test.ogg";
+		let file = read_truncated::<VorbisFile>(vorbis_path, ParsingMode::Relaxed).unwrap();
+		assert!(file.properties().is_duration_estimated());
+		assert!(file.properties().duration() < VORBIS_PROPERTIES.duration);
+
+		let speex_path = "tests/files/assets/minimal/full_test.spx";
+		let file = read_truncated::<SpeexFile>(speex_path, ParsingMode::BestAttempt).unwrap();
+		assert!(file.properties().is_duration_estimated());
+		assert!(file.properties().duration() < SPEEX_PROPERTIES.duration);
+
+		assert!(read_truncated::<OpusFile>(opus_path, ParsingMode::Strict).is_err());
+	}
+
+	#[test]
+	fn opus_channel_mapping() {
+		fn with_channel_mapping(channels: u8, family: u8, table: &[u8]) -> Result<OpusProperties> {
+			let mut reader = File::open("tests/files/assets/minimal/full_test.opus").unwrap();
+			let mut pages = Vec::new();
+			while let Ok(page) = ogg_pager::Page::read(&mut reader) {
+				pages.push(page);
+			}
+
+			let mut identification_packet = pages[0].content()[..19].to_vec();
+			identification_packet[9] = channels;
+			identification_packet[18] = family;
+			identification_packet.extend(table);
+
+			let mut page = pages[0].as_bytes()[..PAGE_HEADER_SIZE - 1].to_vec();
+			page.extend([1, identification_packet.len() as u8]);
+			page.extend(identification_packet);
+			pages[0] = ogg_pager::Page::read(&mut Cursor::new(page)).unwrap();
+
+			let mut bytes = Vec::new();
+			for page in &mut pages {
+				page.gen_crc();
+				bytes.extend(page.as_bytes());
+			}
+
+			OpusFile::read_from(&mut Cursor::new(bytes), ParseOptions::new())
+				.map(|file| file.properties().clone())
+		}
+
+		// 5.1, with 2 coupled streams (front left/right, rear left/right) and 2 mono streams
+		let properties = with_channel_mapping(6, 1, &[4, 2, 0, 4, 1, 2, 3, 5]).unwrap();
+		assert_eq!(properties.channels(), 6);
+		assert_eq!(properties.channel_mask(), Some(ChannelMask(0x3F)));
+
+		let channel_mapping = properties.channel_mapping();
+		assert_eq!(channel_mapping.family(), 1);
+		assert_eq!(channel_mapping.stream_count(), 4);
+		assert_eq!(channel_mapping.coupled_stream_count(), 2);
+		assert_eq!(channel_mapping.mapping(), &[0, 4, 1, 2, 3, 5]);
+
+		// Ambisonics, the channels have no defined meaning
+		let properties = with_channel_mapping(4, 255, &[4, 0, 0, 1, 2, 3]).unwrap();
+		assert_eq!(properties.channels(), 4);
+		assert_eq!(properties.channel_mask(), None);
+		assert_eq!(properties.channel_mapping().family(), 255);
+		assert_eq!(properties.channel_mapping().mapping(), &[0, 1, 2, 3]);
+
+		// A silent channel
+		assert!(with_channel_mapping(2, 255, &[1, 0, 0, 255]).is_ok());
+
+		// Too many coupled streams, and a reference to a nonexistent stream
+		assert!(with_channel_mapping(2, 1, &[1, 2, 0, 1]).is_err());
+		assert!(with_channel_mapping(2, 1, &[1, 0, 0, 1]).is_err());
+	}
+
 	#[test]
 	fn speex_properties() {
 		assert_eq!(
@@ -564,4 +924,115 @@ mod tests {
 			WAVPACK_PROPERTIES
 		)
 	}
+
+	#[test]
+	fn unknown_values_are_none() {
+		let properties = FileProperties::from(expected_opus_properties());
+
+		// Opus doesn't store a bit depth
+		assert_eq!(properties.bit_depth(), None);
+		assert_eq!(properties.sample_rate(), Some(48000));
+
+		// A zeroed value is unknown, not zero
+		let properties = FileProperties::from(OpusProperties::default());
+		assert_eq!(properties.overall_bitrate(), None);
+		assert_eq!(properties.audio_bitrate(), None);
+		assert_eq!(properties.sample_rate(), None);
+		assert_eq!(properties.channels(), None);
+	}
+
+	#[test]
+	fn gapless_info() {
+		// The same stream, encoded to MP3, AAC, and Opus
+		for properties in [
+			FileProperties::from(MP3_PROPERTIES),
+			FileProperties::from(MP4_AAC_PROPERTIES),
+			FileProperties::from(expected_opus_properties()),
+		] {
+			let gapless_info = properties.gapless_info().unwrap();
+			assert!((68544..=68546).contains(&gapless_info.valid_samples()));
+		}
+
+		// Lossless formats have nothing to trim
+		assert!(FileProperties::from(FLAC_PROPERTIES)
+			.gapless_info()
+			.is_none());
+		assert!(FileProperties::from(MP4_ALAC_PROPERTIES)
+			.gapless_info()
+			.is_none());
+	}
+
+	#[test]
+	fn audio_bitrate_source() {
+		for (properties, source) in [
+			// CBR, without a Xing header
+			(
+				FileProperties::from(MP1_PROPERTIES),
+				BitrateSource::Declared,
+			),
+			(
+				FileProperties::from(MP3_PROPERTIES),
+				BitrateSource::Computed,
+			),
+			(
+				FileProperties::from(MP4_AAC_PROPERTIES),
+				BitrateSource::Declared,
+			),
+			(
+				FileProperties::from(MP4_FLAC_PROPERTIES),
+				BitrateSource::Computed,
+			),
+			(
+				FileProperties::from(VORBIS_PROPERTIES),
+				BitrateSource::Declared,
+			),
+			(
+				FileProperties::from(FLAC_PROPERTIES),
+				BitrateSource::Computed,
+			),
+		] {
+			assert_eq!(properties.audio_bitrate_source(), Some(source));
+		}
+
+		// The source of an unknown bitrate is unknown as well
+		let properties = FileProperties::from(MpcProperties::Sv4to6(MPC_SV5_PROPERTIES));
+		assert_eq!(properties.audio_bitrate(), None);
+		assert_eq!(properties.audio_bitrate_source(), None);
+	}
+
+	#[test]
+	#[cfg(feature = "fs")]
+	fn overall_bitrate_includes_metadata() {
+		for entry in std::fs::read_dir("tests/files/assets/minimal").unwrap() {
+			let path = entry.unwrap().path();
+			let file_length = std::fs::metadata(&path).unwrap().len();
+
+			let tagged_file = crate::read_from_path(&path).unwrap();
+			let properties = tagged_file.properties();
+
+			let (Some(overall_bitrate), Some(audio_bitrate)) =
+				(properties.overall_bitrate(), properties.audio_bitrate())
+			else {
+				continue;
+			};
+
+			// The overall bitrate always covers the entire file
+			let duration = properties.duration().as_millis() as u64;
+			let expected = (file_length * 8 / duration) as u32;
+			assert!(
+				overall_bitrate.abs_diff(expected) <= 1,
+				"{}: overall bitrate {overall_bitrate}, expected {expected}",
+				path.display()
+			);
+
+			// While the audio bitrate, if computed, only covers the audio stream
+			if properties.audio_bitrate_source() == Some(BitrateSource::Computed) {
+				assert!(
+					audio_bitrate <= overall_bitrate,
+					"{}: audio bitrate {audio_bitrate} > overall bitrate {overall_bitrate}",
+					path.display()
+				);
+			}
+		}
+	}
 }
@@ -31,6 +31,8 @@ pub trait FileResolver: Send + Sync + AudioFile {
 	/// NOTE: This will only provide (up to) the first 36 bytes of the file.
 	///       This number is subject to change in the future, but it will never decrease.
 	///       Such a change will **not** be considered breaking.
+	///
+	/// Files that can't be identified this way can still be read with [`Probe::with_resolver`](crate::Probe::with_resolver).
 	fn guess(buf: &[u8]) -> Option<FileType>;
 }
 
@@ -53,6 +55,21 @@ pub(crate) fn lookup_resolver(name: &'static str) -> &'static dyn ObjectSafeFile
 	);
 }
 
+/// The names of all registered custom resolvers, sorted
+///
+/// These can be used with [`Probe::with_resolver`](crate::Probe::with_resolver).
+///
+/// # Panics
+///
+/// * See [`Mutex::lock`]
+pub fn registered_resolvers() -> Vec<&'static str> {
+	let res = CUSTOM_RESOLVERS.lock().unwrap();
+
+	let mut names = res.keys().copied().collect::<Vec<_>>();
+	names.sort_unstable();
+	names
+}
+
 // A `Read + Seek` supertrait for use in [`ObjectSafeFileResolver::read_from`]
 pub(crate) trait SeekRead: Read + Seek {}
 impl<T: Seek + Read> SeekRead for T {}
@@ -127,18 +144,19 @@ pub fn register_custom_resolver<T: FileResolver + 'static>(name: &'static str) {
 	res.insert(name, Box::leak::<'static>(b));
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fs"))]
 mod tests {
 	use crate::file::{FileType, TaggedFileExt};
 	use crate::id3::v2::Id3v2Tag;
 	use crate::probe::ParseOptions;
+	use crate::probe::Probe;
 	use crate::properties::FileProperties;
-	use crate::resolve::{register_custom_resolver, FileResolver};
+	use crate::resolve::{register_custom_resolver, registered_resolvers, FileResolver};
 	use crate::tag::TagType;
 	use crate::traits::Accessor;
 
 	use std::fs::File;
-	use std::io::{Read, Seek};
+	use std::io::{Cursor, Read, Seek};
 	use std::panic;
 
 	use lofty_attr::LoftyFile;
@@ -201,6 +219,19 @@ mod tests {
 		let read_content = crate::read_from(&mut File::open(path).unwrap()).unwrap();
 		assert_eq!(read_content.file_type(), FileType::Custom("MyFile"));
 
+		assert!(registered_resolvers().contains(&"MyFile"));
+
+		// Content that can't be guessed can still be read with the resolver
+		let read_forced = Probe::new(Cursor::new([0; 128]))
+			.with_resolver("MyFile")
+			.unwrap()
+			.read()
+			.unwrap();
+		assert_eq!(read_forced.file_type(), FileType::Custom("MyFile"));
+		assert!(Probe::new(Cursor::new([0; 128]))
+			.with_resolver("NotMyFile")
+			.is_err());
+
 		assert!(
 			panic::catch_unwind(|| {
 				register_custom_resolver::<MyFile>("MyFile");
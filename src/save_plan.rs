@@ -0,0 +1,258 @@
+use crate::error::Result;
+use crate::tag::TagType;
+use crate::util::io::{FileLike, WriteOverlay};
+
+use std::io::{Read, Seek};
+use std::ops::Range;
+
+// How much of the file is compared at once
+const CHUNK_SIZE: usize = 8192;
+
+/// The changes saving a file would make, without making them
+///
+/// This is created by [`TaggedFile::plan_save`](crate::TaggedFile::plan_save), which goes through the
+/// entire save, from locating the existing tags to laying out the new ones, without modifying the file.
+/// Everything the save writes is kept in memory instead, and compared against the file.
+///
+/// Saves come in two forms:
+///
+/// * In place: the new tags fit where the old ones were, or are at the end of the file, so only the
+///   changed bytes need to be written. For example, an ID3v2 tag that fits within its existing padding.
+/// * Rewrite: the size of a tag changed, so everything following it has to be moved. For example, an
+///   ID3v2 tag at the start of the file that grew, requiring the audio to be written again.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::{Accessor, TaggedFileExt, WriteOptions};
+/// use std::fs::File;
+///
+/// # #[cfg(feature = "fs")]
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.mp3";
+/// let mut file = File::open(path)?;
+/// let mut tagged_file = lofty::read_from(&mut file)?;
+///
+/// let tag = tagged_file.primary_tag_mut().unwrap();
+/// tag.set_title(String::from("A much, much longer title than the original"));
+///
+/// let plan = tagged_file.plan_save(&file, WriteOptions::default())?;
+/// if plan.requires_rewrite() {
+/// 	println!("This will rewrite {} bytes of the file", plan.bytes_written());
+/// } else {
+/// 	println!("This is an in-place {} byte update", plan.bytes_written());
+/// }
+/// # Ok(()) }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SavePlan {
+	original_len: u64,
+	new_len: u64,
+	regions: Vec<Range<u64>>,
+	requires_rewrite: bool,
+	tags: Vec<(TagType, Vec<u8>)>,
+}
+
+impl SavePlan {
+	pub(crate) fn new<R>(saved: &mut WriteOverlay<R>, tags: Vec<(TagType, Vec<u8>)>) -> Result<Self>
+	where
+		R: Read + Seek,
+	{
+		let original_len = saved.original_len();
+		let new_len = saved.len()?;
+
+		let mut regions = Vec::new();
+		for range in saved.changed_ranges() {
+			changed_runs(saved, range, original_len, &mut regions)?;
+		}
+
+		// Everything from the first change following the moved data has to be written again
+		let requires_rewrite = saved.moved_from().is_some();
+		if let Some(moved_from) = saved.moved_from() {
+			if let Some(first_moved) = regions.iter().position(|run| run.end > moved_from) {
+				let rewrite_start = regions[first_moved].start;
+				regions.truncate(first_moved);
+				regions.push(rewrite_start..new_len);
+			}
+		}
+
+		Ok(Self {
+			original_len,
+			new_len,
+			regions,
+			requires_rewrite,
+			tags,
+		})
+	}
+
+	/// The length of the file prior to saving
+	pub fn original_len(&self) -> u64 {
+		self.original_len
+	}
+
+	/// The length of the file after saving
+	pub fn new_len(&self) -> u64 {
+		self.new_len
+	}
+
+	/// The byte ranges of the saved file that will be written
+	///
+	/// These are positions in the file **after** saving, and are sorted. If the file is only being
+	/// truncated, this may be empty, see [`SavePlan::new_len`].
+	pub fn regions(&self) -> &[Range<u64>] {
+		&self.regions
+	}
+
+	/// The total number of bytes that will be written, see [`SavePlan::regions`]
+	pub fn bytes_written(&self) -> u64 {
+		self.regions
+			.iter()
+			.map(|region| region.end - region.start)
+			.sum()
+	}
+
+	/// Whether the save moves existing data in the file
+	///
+	/// This is reported by the writers, it's `true` when a tag changes size with data following it,
+	/// such as an ID3v2 tag at the start of the file that grew. Everything from the start of the last
+	/// region to the end of the file then has to be written again. When it is `false`, the save only
+	/// overwrites the regions in place, and potentially truncates or extends the end of the file.
+	pub fn requires_rewrite(&self) -> bool {
+		self.requires_rewrite
+	}
+
+	/// Whether the save would leave the file untouched
+	pub fn is_unchanged(&self) -> bool {
+		self.regions.is_empty() && self.original_len == self.new_len
+	}
+
+	/// The serialized tags that will be written, in the order they are written
+	///
+	/// These are the tags as they would be produced by [`TagExt::dump_to`](crate::TagExt::dump_to), and
+	/// do not include any format-specific framing (e.g. the block header of a FLAC `VORBIS_COMMENT` block).
+	pub fn tags(&self) -> impl Iterator<Item = (TagType, &[u8])> + '_ {
+		self.tags
+			.iter()
+			.map(|(tag_type, bytes)| (*tag_type, bytes.as_slice()))
+	}
+}
+
+// Adds the bytes within `range` that differ from the original file to `runs`
+fn changed_runs<R>(
+	saved: &mut WriteOverlay<R>,
+	range: Range<u64>,
+	original_len: u64,
+	runs: &mut Vec<Range<u64>>,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let mut saved_chunk = [0; CHUNK_SIZE];
+	let mut original_chunk = [0; CHUNK_SIZE];
+
+	let mut pos = range.start;
+	while pos < range.end {
+		let len = CHUNK_SIZE.min((range.end - pos) as usize);
+		let read = saved.read_at(pos, &mut saved_chunk[..len])?;
+		if read == 0 {
+			break;
+		}
+
+		// Anything past the end of the original file is new
+		let original_read = if pos < original_len {
+			saved.read_original_at(pos, &mut original_chunk[..read])?
+		} else {
+			0
+		};
+
+		for idx in 0..read {
+			if idx < original_read && saved_chunk[idx] == original_chunk[idx] {
+				continue;
+			}
+
+			let byte = pos + idx as u64;
+			match runs.last_mut() {
+				Some(run) if run.end == byte => run.end += 1,
+				_ => runs.push(byte..byte + 1),
+			}
+		}
+
+		pos += read as u64;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SavePlan;
+	use crate::util::io::{FileLike, WriteOverlay};
+
+	use std::io::{Cursor, Write};
+
+	// Plans a save that leaves `original` as `saved`, the same way a writer would, moving the data
+	// from `moved_from` onward
+	fn plan_for(original: &[u8], saved: &[u8], moved_from: Option<u64>) -> SavePlan {
+		let mut file = WriteOverlay::new(Cursor::new(original)).unwrap();
+		if let Some(moved_from) = moved_from {
+			file.mark_moved(moved_from);
+		}
+
+		file.write_all(saved).unwrap();
+		file.set_len(saved.len() as u64).unwrap();
+
+		SavePlan::new(&mut file, Vec::new()).unwrap()
+	}
+
+	#[test]
+	fn in_place() {
+		let plan = plan_for(b"ID3 foo AUDIO TAG bar", b"ID3 baz AUDIO TAG qux", None);
+		assert!(!plan.requires_rewrite());
+		assert_eq!(plan.regions(), &[4..7, 18..21]);
+		assert_eq!(plan.bytes_written(), 6);
+
+		// Appending a tag
+		let plan = plan_for(b"AUDIO", b"AUDIO TAG", None);
+		assert!(!plan.requires_rewrite());
+		assert_eq!(plan.regions(), &[5..9]);
+
+		// Truncating a tag
+		let plan = plan_for(b"AUDIO TAG", b"AUDIO", None);
+		assert!(!plan.requires_rewrite());
+		assert!(plan.regions().is_empty());
+		assert!(!plan.is_unchanged());
+
+		let plan = plan_for(b"AUDIO", b"AUDIO", None);
+		assert!(plan.is_unchanged());
+	}
+
+	#[test]
+	fn rewrite() {
+		// The audio has to be moved to make room for the larger tag
+		let plan = plan_for(b"ID3 foo AUDIO", b"ID3 foobar AUDIO", Some(7));
+		assert!(plan.requires_rewrite());
+		assert_eq!(plan.regions(), &[7..16]);
+		assert_eq!(plan.original_len(), 13);
+		assert_eq!(plan.new_len(), 16);
+
+		// The trailing tag changing as well doesn't matter
+		let plan = plan_for(
+			b"ID3 foo AUDIO TAG bar",
+			b"ID3 foobar AUDIO TAG baz",
+			Some(7),
+		);
+		assert!(plan.requires_rewrite());
+		assert_eq!(plan.regions(), &[7..24]);
+
+		// Changes preceding the moved data are still written in place
+		let plan = plan_for(
+			b"ID3 foo AUDIO TAG bar END",
+			b"ID3 baz AUDIO TAG quux END",
+			Some(21),
+		);
+		assert!(plan.requires_rewrite());
+		assert_eq!(plan.regions(), &[4..7, 18..26]);
+	}
+}
@@ -0,0 +1,256 @@
+use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::{Tag, TagType};
+
+use std::fmt::{Debug, Formatter};
+
+/// An [`ItemKey`] that has different values in two or more of a file's tags
+///
+/// See [`TaggedFileExt::conflicts`](crate::TaggedFileExt::conflicts)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+	key: ItemKey,
+	values: Vec<(TagType, Vec<ItemValue>)>,
+}
+
+impl Conflict {
+	/// The key the tags disagree on
+	pub fn key(&self) -> &ItemKey {
+		&self.key
+	}
+
+	/// The values of every tag containing the key, in the order the tags appear in the file
+	///
+	/// Tags without the key are not included, as there's nothing for them to disagree on.
+	pub fn values(&self) -> &[(TagType, Vec<ItemValue>)] {
+		&self.values
+	}
+
+	/// The values of a specific tag, if it contains the key
+	pub fn values_of(&self, tag_type: TagType) -> Option<&[ItemValue]> {
+		self.values
+			.iter()
+			.find(|(ty, _)| *ty == tag_type)
+			.map(|(_, values)| values.as_slice())
+	}
+}
+
+/// How to choose the winning value of a [`Conflict`]
+///
+/// See [`TaggedFileExt::reconcile`](crate::TaggedFileExt::reconcile)
+pub enum ReconcilePolicy<'a> {
+	/// Use the value of the file's primary tag
+	///
+	/// If the primary tag doesn't contain the key, this falls back to [`ReconcilePolicy::PreferNewest`].
+	///
+	/// See [`FileType::primary_tag_type`](crate::FileType::primary_tag_type)
+	PreferPrimary,
+	/// Use the value of the most recent tag format
+	///
+	/// From newest to oldest, the formats are:
+	///
+	/// * [`TagType::Mp4Ilst`]
+	/// * [`TagType::Ape`]
+	/// * [`TagType::VorbisComments`]
+	/// * [`TagType::Id3v2`]
+	/// * [`TagType::Id3v1`]
+	/// * [`TagType::RiffInfo`]
+	/// * [`TagType::AiffText`]
+	PreferNewest,
+	/// Decide each conflict with a callback
+	///
+	/// The callback returns the [`TagType`] whose values should win, or `None` to leave the conflict
+	/// as-is. Returning a [`TagType`] that isn't part of the conflict also leaves it as-is.
+	Manual(&'a mut dyn FnMut(&Conflict) -> Option<TagType>),
+}
+
+impl Debug for ReconcilePolicy<'_> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::PreferPrimary => f.write_str("PreferPrimary"),
+			Self::PreferNewest => f.write_str("PreferNewest"),
+			Self::Manual(_) => f.write_str("Manual(..)"),
+		}
+	}
+}
+
+/// What to do with the tags that lost a [`Conflict`]
+///
+/// See [`TaggedFileExt::reconcile`](crate::TaggedFileExt::reconcile)
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReconcileAction {
+	/// Replace their values with the winning values
+	#[default]
+	Overwrite,
+	/// Remove the key from them entirely
+	Remove,
+}
+
+pub(crate) fn recency(tag_type: TagType) -> u8 {
+	match tag_type {
+		TagType::AiffText => 0,
+		TagType::RiffInfo => 1,
+		TagType::Id3v1 => 2,
+		TagType::Id3v2 => 3,
+		TagType::VorbisComments => 4,
+		TagType::Ape => 5,
+		TagType::Mp4Ilst => 6,
+	}
+}
+
+pub(crate) fn find_conflicts(tags: &[Tag]) -> Vec<Conflict> {
+	let mut keys: Vec<&ItemKey> = Vec::new();
+	for item in tags.iter().flat_map(Tag::items) {
+		if !keys.contains(&item.key()) {
+			keys.push(item.key());
+		}
+	}
+
+	let mut conflicts = Vec::new();
+	for key in keys {
+		let values = tags
+			.iter()
+			.filter_map(|tag| {
+				let values = tag
+					.get_items(key)
+					.map(|item| item.value().clone())
+					.collect::<Vec<_>>();
+
+				(!values.is_empty()).then_some((tag.tag_type(), values))
+			})
+			.collect::<Vec<_>>();
+
+		if values.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+			conflicts.push(Conflict {
+				key: key.clone(),
+				values,
+			});
+		}
+	}
+
+	conflicts
+}
+
+pub(crate) fn winner(
+	conflict: &Conflict,
+	primary_tag_type: TagType,
+	policy: &mut ReconcilePolicy<'_>,
+) -> Option<TagType> {
+	let newest = || {
+		conflict
+			.values
+			.iter()
+			.map(|(tag_type, _)| *tag_type)
+			.max_by_key(|tag_type| recency(*tag_type))
+	};
+
+	let winner = match policy {
+		ReconcilePolicy::PreferPrimary => conflict
+			.values_of(primary_tag_type)
+			.map(|_| primary_tag_type)
+			.or_else(newest),
+		ReconcilePolicy::PreferNewest => newest(),
+		ReconcilePolicy::Manual(callback) => callback(conflict),
+	}?;
+
+	conflict.values_of(winner).map(|_| winner)
+}
+
+// Applies the winning items to a losing tag
+pub(crate) fn apply(tag: &mut Tag, key: &ItemKey, winning: &[TagItem], action: ReconcileAction) {
+	tag.remove_key(key);
+
+	if action == ReconcileAction::Overwrite {
+		for item in winning {
+			tag.push_unchecked(item.clone());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{find_conflicts, winner, ReconcilePolicy};
+	use crate::{ItemKey, ItemValue, Tag, TagType};
+
+	fn tag(tag_type: TagType, title: &str) -> Tag {
+		let mut tag = Tag::new(tag_type);
+		tag.insert_text(ItemKey::TrackTitle, String::from(title));
+		tag.insert_text(ItemKey::TrackArtist, String::from("Foo artist"));
+		tag
+	}
+
+	#[test]
+	fn conflicts() {
+		let tags = [
+			tag(TagType::Id3v2, "Foo title"),
+			tag(TagType::Id3v1, "Bar title"),
+			tag(TagType::Ape, "Foo title"),
+		];
+
+		let conflicts = find_conflicts(&tags);
+		assert_eq!(conflicts.len(), 1);
+
+		let conflict = &conflicts[0];
+		assert_eq!(conflict.key(), &ItemKey::TrackTitle);
+		assert_eq!(conflict.values().len(), 3);
+		assert_eq!(
+			conflict.values_of(TagType::Id3v1),
+			Some(&[ItemValue::Text(String::from("Bar title"))][..])
+		);
+
+		// A key missing from a tag isn't a conflict
+		let mut tags = tags;
+		tags[1].remove_key(&ItemKey::TrackTitle);
+		assert!(find_conflicts(&tags).is_empty());
+	}
+
+	#[test]
+	fn winners() {
+		let tags = [
+			tag(TagType::Id3v1, "Foo title"),
+			tag(TagType::Ape, "Bar title"),
+		];
+		let conflict = &find_conflicts(&tags)[0];
+
+		assert_eq!(
+			winner(
+				conflict,
+				TagType::Id3v1,
+				&mut ReconcilePolicy::PreferPrimary
+			),
+			Some(TagType::Id3v1)
+		);
+		// The primary tag isn't part of the conflict
+		assert_eq!(
+			winner(
+				conflict,
+				TagType::Id3v2,
+				&mut ReconcilePolicy::PreferPrimary
+			),
+			Some(TagType::Ape)
+		);
+		assert_eq!(
+			winner(conflict, TagType::Id3v1, &mut ReconcilePolicy::PreferNewest),
+			Some(TagType::Ape)
+		);
+
+		let mut callback = |_: &_| Some(TagType::Id3v1);
+		assert_eq!(
+			winner(
+				conflict,
+				TagType::Ape,
+				&mut ReconcilePolicy::Manual(&mut callback)
+			),
+			Some(TagType::Id3v1)
+		);
+
+		let mut callback = |_: &_| Some(TagType::VorbisComments);
+		assert_eq!(
+			winner(
+				conflict,
+				TagType::Ape,
+				&mut ReconcilePolicy::Manual(&mut callback)
+			),
+			None
+		);
+	}
+}
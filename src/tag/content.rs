@@ -0,0 +1,369 @@
+use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::{Tag, TagType};
+
+use std::borrow::Cow;
+
+// 64-bit FNV-1a, which unlike `std`'s hashers is guaranteed to be stable
+const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+struct Fnv1a(u64);
+
+impl Fnv1a {
+	fn new() -> Self {
+		Self(FNV_OFFSET_BASIS)
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 ^= u64::from(*byte);
+			self.0 = self.0.wrapping_mul(FNV_PRIME);
+		}
+	}
+
+	// Every variable length field is prefixed with its length, so adjacent fields can't run together
+	fn write_field(&mut self, bytes: &[u8]) {
+		self.write(&(bytes.len() as u64).to_le_bytes());
+		self.write(bytes);
+	}
+}
+
+// The values are part of the hash, they must never change
+pub(crate) fn tag_type_id(tag_type: TagType) -> u8 {
+	match tag_type {
+		TagType::Ape => 0,
+		TagType::Id3v1 => 1,
+		TagType::Id3v2 => 2,
+		TagType::Mp4Ilst => 3,
+		TagType::VorbisComments => 4,
+		TagType::RiffInfo => 5,
+		TagType::AiffText => 6,
+	}
+}
+
+// The names are part of the hash, they must never change, even if a variant is renamed
+fn item_key_name(tag_type: TagType, key: &ItemKey) -> Cow<'static, str> {
+	let name = match key {
+		ItemKey::Unknown(key) if tag_type == TagType::VorbisComments => {
+			return Cow::Owned(format!("Unknown:{}", key.to_ascii_uppercase()));
+		},
+		ItemKey::Unknown(key) => return Cow::Owned(format!("Unknown:{key}")),
+		ItemKey::Custom(key) => return Cow::Owned(format!("Custom:{key}")),
+		ItemKey::AlbumTitle => "AlbumTitle",
+		ItemKey::SetSubtitle => "SetSubtitle",
+		ItemKey::ShowName => "ShowName",
+		ItemKey::ContentGroup => "ContentGroup",
+		ItemKey::TrackTitle => "TrackTitle",
+		ItemKey::TrackSubtitle => "TrackSubtitle",
+		ItemKey::OriginalAlbumTitle => "OriginalAlbumTitle",
+		ItemKey::OriginalArtist => "OriginalArtist",
+		ItemKey::OriginalLyricist => "OriginalLyricist",
+		ItemKey::AlbumTitleSortOrder => "AlbumTitleSortOrder",
+		ItemKey::AlbumArtistSortOrder => "AlbumArtistSortOrder",
+		ItemKey::TrackTitleSortOrder => "TrackTitleSortOrder",
+		ItemKey::TrackArtistSortOrder => "TrackArtistSortOrder",
+		ItemKey::ShowNameSortOrder => "ShowNameSortOrder",
+		ItemKey::ComposerSortOrder => "ComposerSortOrder",
+		ItemKey::AlbumArtist => "AlbumArtist",
+		ItemKey::TrackArtist => "TrackArtist",
+		ItemKey::Arranger => "Arranger",
+		ItemKey::Writer => "Writer",
+		ItemKey::Composer => "Composer",
+		ItemKey::Conductor => "Conductor",
+		ItemKey::Director => "Director",
+		ItemKey::Engineer => "Engineer",
+		ItemKey::Lyricist => "Lyricist",
+		ItemKey::MixDj => "MixDj",
+		ItemKey::MixEngineer => "MixEngineer",
+		ItemKey::MusicianCredits => "MusicianCredits",
+		ItemKey::Performer => "Performer",
+		ItemKey::Producer => "Producer",
+		ItemKey::Publisher => "Publisher",
+		ItemKey::Label => "Label",
+		ItemKey::InternetRadioStationName => "InternetRadioStationName",
+		ItemKey::InternetRadioStationOwner => "InternetRadioStationOwner",
+		ItemKey::Remixer => "Remixer",
+		ItemKey::DiscNumber => "DiscNumber",
+		ItemKey::DiscTotal => "DiscTotal",
+		ItemKey::TrackNumber => "TrackNumber",
+		ItemKey::TrackTotal => "TrackTotal",
+		ItemKey::Popularimeter => "Popularimeter",
+		ItemKey::PlayCount => "PlayCount",
+		ItemKey::ParentalAdvisory => "ParentalAdvisory",
+		ItemKey::RecordingDate => "RecordingDate",
+		ItemKey::Year => "Year",
+		ItemKey::OriginalReleaseDate => "OriginalReleaseDate",
+		ItemKey::Isrc => "Isrc",
+		ItemKey::Barcode => "Barcode",
+		ItemKey::CatalogNumber => "CatalogNumber",
+		ItemKey::Work => "Work",
+		ItemKey::Movement => "Movement",
+		ItemKey::MovementNumber => "MovementNumber",
+		ItemKey::MovementTotal => "MovementTotal",
+		ItemKey::MusicBrainzRecordingId => "MusicBrainzRecordingId",
+		ItemKey::MusicBrainzTrackId => "MusicBrainzTrackId",
+		ItemKey::MusicBrainzReleaseId => "MusicBrainzReleaseId",
+		ItemKey::MusicBrainzReleaseGroupId => "MusicBrainzReleaseGroupId",
+		ItemKey::MusicBrainzArtistId => "MusicBrainzArtistId",
+		ItemKey::MusicBrainzReleaseArtistId => "MusicBrainzReleaseArtistId",
+		ItemKey::MusicBrainzWorkId => "MusicBrainzWorkId",
+		ItemKey::FlagCompilation => "FlagCompilation",
+		ItemKey::FlagPodcast => "FlagPodcast",
+		ItemKey::FileType => "FileType",
+		ItemKey::FileOwner => "FileOwner",
+		ItemKey::TaggingTime => "TaggingTime",
+		ItemKey::Length => "Length",
+		ItemKey::OriginalFileName => "OriginalFileName",
+		ItemKey::OriginalMediaType => "OriginalMediaType",
+		ItemKey::EncodedBy => "EncodedBy",
+		ItemKey::EncoderSoftware => "EncoderSoftware",
+		ItemKey::EncoderSettings => "EncoderSettings",
+		ItemKey::EncodingTime => "EncodingTime",
+		ItemKey::ReplayGainAlbumGain => "ReplayGainAlbumGain",
+		ItemKey::ReplayGainAlbumPeak => "ReplayGainAlbumPeak",
+		ItemKey::ReplayGainTrackGain => "ReplayGainTrackGain",
+		ItemKey::ReplayGainTrackPeak => "ReplayGainTrackPeak",
+		ItemKey::AudioFileUrl => "AudioFileUrl",
+		ItemKey::AudioSourceUrl => "AudioSourceUrl",
+		ItemKey::CommercialInformationUrl => "CommercialInformationUrl",
+		ItemKey::CopyrightUrl => "CopyrightUrl",
+		ItemKey::TrackArtistUrl => "TrackArtistUrl",
+		ItemKey::RadioStationUrl => "RadioStationUrl",
+		ItemKey::PaymentUrl => "PaymentUrl",
+		ItemKey::PublisherUrl => "PublisherUrl",
+		ItemKey::Genre => "Genre",
+		ItemKey::InitialKey => "InitialKey",
+		ItemKey::Color => "Color",
+		ItemKey::Mood => "Mood",
+		ItemKey::Bpm => "Bpm",
+		ItemKey::CopyrightMessage => "CopyrightMessage",
+		ItemKey::License => "License",
+		ItemKey::PodcastDescription => "PodcastDescription",
+		ItemKey::PodcastSeriesDescription => "PodcastSeriesDescription",
+		ItemKey::PodcastSeriesCategory => "PodcastSeriesCategory",
+		ItemKey::PodcastURL => "PodcastURL",
+		ItemKey::PodcastReleaseDate => "PodcastReleaseDate",
+		ItemKey::PodcastGlobalUniqueID => "PodcastGlobalUniqueID",
+		ItemKey::PodcastKeywords => "PodcastKeywords",
+		ItemKey::Comment => "Comment",
+		ItemKey::Description => "Description",
+		ItemKey::Language => "Language",
+		ItemKey::Script => "Script",
+		ItemKey::Lyrics => "Lyrics",
+		ItemKey::Cuesheet => "Cuesheet",
+		ItemKey::AppleXid => "AppleXid",
+		ItemKey::AppleId3v2ContentGroup => "AppleId3v2ContentGroup",
+	};
+
+	Cow::Borrowed(name)
+}
+
+// An item, serialized with the normalization described in `Tag::content_hash`
+fn normalized_item(tag_type: TagType, item: &TagItem) -> Vec<u8> {
+	let key = item_key_name(tag_type, item.key());
+
+	let (value_type, value) = match item.value() {
+		ItemValue::Text(text) => (0, text.as_bytes()),
+		ItemValue::Locator(locator) => (1, locator.as_bytes()),
+		ItemValue::Binary(binary) => (2, binary.as_slice()),
+	};
+
+	let mut normalized = Vec::with_capacity(key.len() + value.len() + 17);
+	normalized.extend((key.len() as u64).to_le_bytes());
+	normalized.extend(key.as_bytes());
+	normalized.push(value_type);
+	normalized.extend((value.len() as u64).to_le_bytes());
+	normalized.extend(value);
+	normalized
+}
+
+fn normalized_items(tag: &Tag) -> Vec<Vec<u8>> {
+	let mut items = tag
+		.items()
+		.map(|item| normalized_item(tag.tag_type(), item))
+		.collect::<Vec<_>>();
+	items.sort_unstable();
+	items
+}
+
+fn normalized_pictures(tag: &Tag) -> Vec<(u8, &[u8])> {
+	let mut pictures = tag
+		.pictures()
+		.iter()
+		.map(|picture| (picture.pic_type().as_u8(), picture.data()))
+		.collect::<Vec<_>>();
+	pictures.sort_unstable();
+	pictures
+}
+
+pub(crate) fn eq_content(a: &Tag, b: &Tag) -> bool {
+	a.tag_type() == b.tag_type()
+		&& a.item_count() == b.item_count()
+		&& a.picture_count() == b.picture_count()
+		&& normalized_items(a) == normalized_items(b)
+		&& normalized_pictures(a) == normalized_pictures(b)
+}
+
+pub(crate) fn content_hash(tag: &Tag) -> u64 {
+	let mut hasher = Fnv1a::new();
+	hasher.write(&[tag_type_id(tag.tag_type())]);
+
+	let items = normalized_items(tag);
+	hasher.write(&(items.len() as u64).to_le_bytes());
+	for item in items {
+		hasher.write(&item);
+	}
+
+	let pictures = normalized_pictures(tag);
+	hasher.write(&(pictures.len() as u64).to_le_bytes());
+	for (pic_type, data) in pictures {
+		hasher.write(&[pic_type]);
+		hasher.write_field(data);
+	}
+
+	hasher.0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::content_hash;
+	use crate::{ItemKey, ItemValue, MimeType, Picture, PictureType, Tag, TagItem, TagType};
+
+	fn picture(description: Option<&str>) -> Picture {
+		Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Png),
+			description.map(String::from),
+			vec![1, 2, 3, 4],
+		)
+	}
+
+	fn tag(tag_type: TagType) -> Tag {
+		let mut tag = Tag::new(tag_type);
+		tag.insert_text(ItemKey::TrackTitle, String::from("Foo title"));
+		tag.push(TagItem::new(
+			ItemKey::TrackArtist,
+			ItemValue::Text(String::from("Bar artist")),
+		));
+		tag.push(TagItem::new(
+			ItemKey::TrackArtist,
+			ItemValue::Text(String::from("Baz artist")),
+		));
+		tag.push_unchecked(TagItem::new(
+			ItemKey::Unknown(String::from("CUSTOM")),
+			ItemValue::Text(String::from("Qux")),
+		));
+		tag.push_picture(picture(Some("Cover")));
+		tag
+	}
+
+	// These values are persisted by users, a change to them is a breaking change
+	#[test]
+	fn snapshots() {
+		assert_eq!(
+			content_hash(&Tag::new(TagType::Id3v2)),
+			0xEBA8_D4F0_ABA8_0485
+		);
+		assert_eq!(content_hash(&tag(TagType::Id3v2)), 0x3B5F_4178_BFAD_8D1F);
+		assert_eq!(
+			content_hash(&tag(TagType::VorbisComments)),
+			0x5BFC_7CF7_D223_D9A1
+		);
+	}
+
+	// Item keys are hashed by name, renaming a variant must not change these
+	#[test]
+	fn key_snapshots() {
+		let single_item = |key: ItemKey| {
+			let mut tag = Tag::new(TagType::Id3v2);
+			tag.push_unchecked(TagItem::new(key, ItemValue::Text(String::from("Foo"))));
+			content_hash(&tag)
+		};
+
+		assert_eq!(single_item(ItemKey::TrackTitle), 0x049A_CD19_21D3_8678);
+		assert_eq!(
+			single_item(ItemKey::MusicBrainzRecordingId),
+			0xE31E_8406_771E_4196
+		);
+		assert_eq!(
+			single_item(ItemKey::AppleId3v2ContentGroup),
+			0x9FD5_AE61_E9E6_2229
+		);
+		assert_eq!(single_item(ItemKey::Custom("FOO")), 0xEF15_1027_C98C_63FE);
+		assert_eq!(
+			single_item(ItemKey::Unknown(String::from("FOO"))),
+			0x9513_298F_1BE5_6618
+		);
+	}
+
+	#[test]
+	fn normalization() {
+		let original = tag(TagType::VorbisComments);
+
+		// Item order
+		let mut reordered = Tag::new(TagType::VorbisComments);
+		for item in original.items().collect::<Vec<_>>().into_iter().rev() {
+			reordered.push_unchecked(item.clone());
+		}
+		reordered.push_picture(picture(Some("Cover")));
+		assert!(original.eq_content(&reordered));
+		assert_eq!(original.content_hash(), reordered.content_hash());
+
+		// Vorbis comment keys are case-insensitive
+		let mut renamed = original.clone();
+		renamed.remove_key(&ItemKey::Unknown(String::from("CUSTOM")));
+		renamed.push_unchecked(TagItem::new(
+			ItemKey::Unknown(String::from("custom")),
+			ItemValue::Text(String::from("Qux")),
+		));
+		assert!(original.eq_content(&renamed));
+		assert_eq!(original.content_hash(), renamed.content_hash());
+
+		// Picture descriptions and MIME types
+		let mut redescribed = original.clone();
+		redescribed.set_picture(
+			0,
+			Picture::new_unchecked(
+				PictureType::CoverFront,
+				None,
+				Some(String::from(" Front cover ")),
+				vec![1, 2, 3, 4],
+			),
+		);
+		assert!(original.eq_content(&redescribed));
+		assert_eq!(original.content_hash(), redescribed.content_hash());
+
+		// Actual differences
+		let mut retitled = original.clone();
+		retitled.insert_text(ItemKey::TrackTitle, String::from("Foo title "));
+		assert!(!original.eq_content(&retitled));
+		assert_ne!(original.content_hash(), retitled.content_hash());
+
+		let mut retyped = original.clone();
+		retyped.set_picture(0, {
+			let mut picture = picture(None);
+			picture.set_pic_type(PictureType::CoverBack);
+			picture
+		});
+		assert!(!original.eq_content(&retyped));
+		assert_ne!(original.content_hash(), retyped.content_hash());
+
+		let mut duplicated = original.clone();
+		duplicated.push_unchecked(original.items().next().unwrap().clone());
+		assert!(!original.eq_content(&duplicated));
+		assert_ne!(original.content_hash(), duplicated.content_hash());
+
+		// Keys of other formats are case-sensitive
+		let original = tag(TagType::Ape);
+		let mut renamed = original.clone();
+		renamed.remove_key(&ItemKey::Unknown(String::from("CUSTOM")));
+		renamed.push_unchecked(TagItem::new(
+			ItemKey::Unknown(String::from("custom")),
+			ItemValue::Text(String::from("Qux")),
+		));
+		assert!(!original.eq_content(&renamed));
+
+		assert!(!original.eq_content(&tag(TagType::Id3v2)));
+		assert_ne!(original.content_hash(), tag(TagType::Id3v2).content_hash());
+	}
+}
@@ -0,0 +1,429 @@
+use super::{try_parse_bpm, try_parse_year, Tag};
+use crate::picture::PictureType;
+use crate::tag::item::{ItemKey, ItemValue, TagItem};
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Statistics on how a collection of tags makes use of each [`ItemKey`]
+///
+/// This is built with [`coverage_report`], or by adding tags one at a time with
+/// [`CoverageReport::add`].
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::{coverage_report, Accessor, ItemKey, Tag, TagType};
+///
+/// let mut tagged = Tag::new(TagType::Id3v2);
+/// tagged.set_title(String::from("Foo title"));
+///
+/// let untagged = Tag::new(TagType::Id3v2);
+///
+/// let report = coverage_report([&tagged, &untagged]);
+/// assert_eq!(report.tags, 2);
+/// assert_eq!(report.coverage(&ItemKey::TrackTitle), 0.5);
+/// assert_eq!(report.coverage(&ItemKey::AlbumTitle), 0.0);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct CoverageReport {
+	/// The number of tags included in the report
+	pub tags: usize,
+	/// The statistics for each key that appeared in at least one tag
+	pub keys: HashMap<ItemKey, KeyCoverage>,
+	/// The statistics for the pictures of every tag
+	pub pictures: PictureCoverage,
+}
+
+impl CoverageReport {
+	/// Create an empty `CoverageReport`
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a tag to the report
+	pub fn add(&mut self, tag: &Tag) {
+		self.tags += 1;
+
+		let mut items_by_key: HashMap<&ItemKey, Vec<&TagItem>> = HashMap::new();
+		for item in tag.items() {
+			items_by_key.entry(item.key()).or_default().push(item);
+		}
+
+		for (key, items) in items_by_key {
+			self.keys.entry(key.clone()).or_default().add(key, &items);
+		}
+
+		self.pictures.add(tag);
+	}
+
+	/// Get the statistics for a key, if it appeared in any tag
+	pub fn key(&self, key: &ItemKey) -> Option<&KeyCoverage> {
+		self.keys.get(key)
+	}
+
+	/// The fraction of tags with a non-empty value for `key`, from 0 to 1
+	///
+	/// This is 0 if no tags have been added.
+	pub fn coverage(&self, key: &ItemKey) -> f64 {
+		match self.keys.get(key) {
+			Some(coverage) if self.tags > 0 => coverage.present as f64 / self.tags as f64,
+			_ => 0.0,
+		}
+	}
+}
+
+impl<'a> Extend<&'a Tag> for CoverageReport {
+	fn extend<T: IntoIterator<Item = &'a Tag>>(&mut self, iter: T) {
+		for tag in iter {
+			self.add(tag);
+		}
+	}
+}
+
+impl<'a> FromIterator<&'a Tag> for CoverageReport {
+	fn from_iter<T: IntoIterator<Item = &'a Tag>>(iter: T) -> Self {
+		let mut report = Self::new();
+		report.extend(iter);
+		report
+	}
+}
+
+/// Statistics on the usage of a single [`ItemKey`], see [`CoverageReport`]
+///
+/// Value lengths are measured in characters for text and locators, and in bytes for binary values.
+/// Empty values are left out of them.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct KeyCoverage {
+	/// The number of tags with at least one non-empty value
+	pub present: usize,
+	/// The number of tags with the key, but only empty values
+	pub empty: usize,
+	/// The number of tags with more than one value
+	pub multi_valued: usize,
+	/// The number of tags with more than one value, for a key that is only expected to have one
+	///
+	/// For example, multiple [`ItemKey::TrackArtist`] values are normal, while multiple
+	/// [`ItemKey::TrackNumber`] values are not.
+	pub unexpected_multi_valued: usize,
+	/// The number of values that don't parse as a number, for a key that is expected to be numeric
+	///
+	/// This applies to the track, disc, and movement numbers and totals, [`ItemKey::Year`],
+	/// [`ItemKey::Bpm`], [`ItemKey::Length`], and [`ItemKey::PlayCount`].
+	pub non_numeric: usize,
+	/// The total number of values, across all tags
+	pub values: usize,
+	/// The length of the shortest non-empty value
+	pub min_len: Option<usize>,
+	/// The length of the longest non-empty value
+	pub max_len: Option<usize>,
+	/// The combined length of every non-empty value
+	pub total_len: usize,
+	non_empty_values: usize,
+}
+
+impl KeyCoverage {
+	fn add(&mut self, key: &ItemKey, items: &[&TagItem]) {
+		let mut non_empty = 0;
+		for item in items {
+			let value = item.value();
+			self.values += 1;
+
+			if value.is_empty() {
+				continue;
+			}
+
+			non_empty += 1;
+			self.non_empty_values += 1;
+
+			let len = match value {
+				ItemValue::Text(text) | ItemValue::Locator(text) => text.chars().count(),
+				ItemValue::Binary(binary) => binary.len(),
+			};
+
+			self.min_len = Some(self.min_len.map_or(len, |min| min.min(len)));
+			self.max_len = Some(self.max_len.map_or(len, |max| max.max(len)));
+			self.total_len += len;
+
+			if is_numeric(key) && !parses_as_number(key, value) {
+				self.non_numeric += 1;
+			}
+		}
+
+		if non_empty > 0 {
+			self.present += 1;
+		} else {
+			self.empty += 1;
+		}
+
+		if items.len() > 1 {
+			self.multi_valued += 1;
+
+			if !allows_multiple_values(key) {
+				self.unexpected_multi_valued += 1;
+			}
+		}
+	}
+
+	/// The average length of the non-empty values
+	///
+	/// This is `None` if there are no non-empty values.
+	pub fn average_len(&self) -> Option<f64> {
+		(self.non_empty_values > 0).then(|| self.total_len as f64 / self.non_empty_values as f64)
+	}
+}
+
+/// Statistics on the pictures of a collection of tags, see [`CoverageReport`]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct PictureCoverage {
+	/// The number of tags with at least one picture
+	pub tags_with_pictures: usize,
+	/// The total number of pictures
+	pub pictures: usize,
+	/// The number of tags with a given number of pictures, including tags with none
+	pub per_tag: BTreeMap<usize, usize>,
+	/// The number of pictures of each [`PictureType`]
+	pub by_type: HashMap<PictureType, usize>,
+	/// The number of pictures in each size range
+	///
+	/// Sizes are rounded up to the next power of two, so the key `65536` counts the pictures larger
+	/// than 32 KiB, up to 64 KiB.
+	pub size_distribution: BTreeMap<usize, usize>,
+	/// The size of the smallest picture, in bytes
+	pub min_size: Option<usize>,
+	/// The size of the largest picture, in bytes
+	pub max_size: Option<usize>,
+	/// The combined size of every picture, in bytes
+	pub total_size: usize,
+}
+
+impl PictureCoverage {
+	fn add(&mut self, tag: &Tag) {
+		let pictures = tag.pictures();
+
+		*self.per_tag.entry(pictures.len()).or_default() += 1;
+		if pictures.is_empty() {
+			return;
+		}
+
+		self.tags_with_pictures += 1;
+		self.pictures += pictures.len();
+
+		for picture in pictures {
+			let size = picture.data().len();
+
+			*self.by_type.entry(picture.pic_type()).or_default() += 1;
+			*self
+				.size_distribution
+				.entry(size.next_power_of_two())
+				.or_default() += 1;
+
+			self.min_size = Some(self.min_size.map_or(size, |min| min.min(size)));
+			self.max_size = Some(self.max_size.map_or(size, |max| max.max(size)));
+			self.total_size += size;
+		}
+	}
+
+	/// The average size of the pictures, in bytes
+	///
+	/// This is `None` if there are no pictures.
+	pub fn average_size(&self) -> Option<f64> {
+		(self.pictures > 0).then(|| self.total_size as f64 / self.pictures as f64)
+	}
+}
+
+/// Create a [`CoverageReport`] for a collection of tags
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use lofty::{coverage_report, ItemKey, TaggedFile, TaggedFileExt};
+///
+/// # #[cfg(feature = "fs")]
+/// # fn main() -> lofty::Result<()> {
+/// # let paths = ["foo.mp3", "bar.flac"];
+/// let files = paths
+/// 	.iter()
+/// 	.map(lofty::read_from_path)
+/// 	.collect::<lofty::Result<Vec<TaggedFile>>>()?;
+///
+/// let report = coverage_report(files.iter().filter_map(TaggedFile::primary_tag));
+/// println!(
+/// 	"{:.1}% of tracks have an album artist",
+/// 	report.coverage(&ItemKey::AlbumArtist) * 100.0
+/// );
+/// # Ok(()) }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
+/// ```
+pub fn coverage_report<'a, I>(tags: I) -> CoverageReport
+where
+	I: IntoIterator<Item = &'a Tag>,
+{
+	tags.into_iter().collect()
+}
+
+fn is_numeric(key: &ItemKey) -> bool {
+	matches!(
+		key,
+		ItemKey::TrackNumber
+			| ItemKey::TrackTotal
+			| ItemKey::DiscNumber
+			| ItemKey::DiscTotal
+			| ItemKey::MovementNumber
+			| ItemKey::MovementTotal
+			| ItemKey::Year
+			| ItemKey::Bpm
+			| ItemKey::Length
+			| ItemKey::PlayCount
+	)
+}
+
+fn parses_as_number(key: &ItemKey, value: &ItemValue) -> bool {
+	let ItemValue::Text(text) = value else {
+		return false;
+	};
+
+	match key {
+		ItemKey::Year => try_parse_year(text).is_some(),
+		ItemKey::Bpm => try_parse_bpm(text).is_some(),
+		ItemKey::PlayCount => text.trim().parse::<u64>().is_ok(),
+		_ => text.trim().parse::<u32>().is_ok(),
+	}
+}
+
+// Keys that commonly hold a list, such as the people involved or the genres
+fn allows_multiple_values(key: &ItemKey) -> bool {
+	matches!(
+		key,
+		ItemKey::AlbumArtist
+			| ItemKey::TrackArtist
+			| ItemKey::OriginalArtist
+			| ItemKey::OriginalLyricist
+			| ItemKey::Arranger
+			| ItemKey::Writer
+			| ItemKey::Composer
+			| ItemKey::Conductor
+			| ItemKey::Director
+			| ItemKey::Engineer
+			| ItemKey::Lyricist
+			| ItemKey::MixDj
+			| ItemKey::MixEngineer
+			| ItemKey::MusicianCredits
+			| ItemKey::Performer
+			| ItemKey::Producer
+			| ItemKey::Publisher
+			| ItemKey::Label
+			| ItemKey::Remixer
+			| ItemKey::MusicBrainzArtistId
+			| ItemKey::MusicBrainzReleaseArtistId
+			| ItemKey::Genre
+			| ItemKey::Mood
+			| ItemKey::Comment
+			| ItemKey::Lyrics
+			| ItemKey::Language
+			| ItemKey::Isrc
+			| ItemKey::Barcode
+			| ItemKey::CatalogNumber
+			| ItemKey::Unknown(_)
+			| ItemKey::Custom(_)
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::coverage_report;
+	use crate::picture::{MimeType, Picture, PictureType};
+	use crate::tag::{ItemKey, ItemValue, Tag, TagItem, TagType};
+
+	use std::collections::BTreeMap;
+
+	fn tag(items: &[(ItemKey, &str)]) -> Tag {
+		let mut tag = Tag::new(TagType::VorbisComments);
+		for (key, value) in items {
+			tag.push(TagItem::new(
+				key.clone(),
+				ItemValue::Text(String::from(*value)),
+			));
+		}
+
+		tag
+	}
+
+	#[test]
+	fn key_statistics() {
+		let tags = [
+			tag(&[
+				(ItemKey::TrackArtist, "Foo artist"),
+				(ItemKey::TrackArtist, "Bar"),
+				(ItemKey::TrackNumber, "1"),
+			]),
+			tag(&[
+				(ItemKey::TrackArtist, ""),
+				(ItemKey::TrackNumber, "2"),
+				(ItemKey::TrackNumber, "two"),
+			]),
+			tag(&[]),
+		];
+
+		let report = coverage_report(&tags);
+		assert_eq!(report.tags, 3);
+
+		let artist = report.key(&ItemKey::TrackArtist).unwrap();
+		assert_eq!((artist.present, artist.empty), (1, 1));
+		assert_eq!(
+			(artist.multi_valued, artist.unexpected_multi_valued),
+			(1, 0)
+		);
+		assert_eq!(artist.values, 3);
+		assert_eq!((artist.min_len, artist.max_len), (Some(3), Some(10)));
+		assert_eq!(artist.average_len(), Some(6.5));
+		assert_eq!(artist.non_numeric, 0);
+
+		let track = report.key(&ItemKey::TrackNumber).unwrap();
+		assert_eq!((track.present, track.empty), (2, 0));
+		assert_eq!((track.multi_valued, track.unexpected_multi_valued), (1, 1));
+		assert_eq!(track.non_numeric, 1);
+
+		assert!(report.key(&ItemKey::AlbumTitle).is_none());
+		assert_eq!(report.coverage(&ItemKey::TrackNumber), 2.0 / 3.0);
+		assert_eq!(report.coverage(&ItemKey::AlbumTitle), 0.0);
+	}
+
+	#[test]
+	fn picture_statistics() {
+		let picture = |pic_type, size| {
+			Picture::new_unchecked(pic_type, Some(MimeType::Png), None, vec![0; size])
+		};
+
+		let mut with_pictures = Tag::new(TagType::Id3v2);
+		with_pictures.push_picture(picture(PictureType::CoverFront, 100));
+		with_pictures.push_picture(picture(PictureType::CoverBack, 3000));
+
+		let mut with_picture = Tag::new(TagType::Id3v2);
+		with_picture.push_picture(picture(PictureType::CoverFront, 128));
+
+		let without_pictures = Tag::new(TagType::Id3v2);
+
+		let report = coverage_report([&with_pictures, &with_picture, &without_pictures]);
+		let pictures = report.pictures;
+
+		assert_eq!(pictures.tags_with_pictures, 2);
+		assert_eq!(pictures.pictures, 3);
+		assert_eq!(pictures.per_tag, BTreeMap::from([(0, 1), (1, 1), (2, 1)]));
+		assert_eq!(pictures.by_type[&PictureType::CoverFront], 2);
+		assert_eq!(pictures.by_type[&PictureType::CoverBack], 1);
+		assert_eq!(
+			pictures.size_distribution,
+			BTreeMap::from([(128, 2), (4096, 1)])
+		);
+		assert_eq!(
+			(pictures.min_size, pictures.max_size),
+			(Some(100), Some(3000))
+		);
+		assert_eq!(pictures.total_size, 3228);
+		assert_eq!(pictures.average_size(), Some(1076.0));
+	}
+}
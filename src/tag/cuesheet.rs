@@ -0,0 +1,299 @@
+use std::time::Duration;
+
+// Cue sheet positions are in CD frames, of which there are 75 per second
+const FRAMES_PER_SECOND: u32 = 75;
+
+/// An index point of a [`CueTrack`]
+///
+/// Index 0 is the start of the pregap, and index 1 is the start of the track itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CueIndex {
+	/// The index number, from 0 to 99
+	pub number: u8,
+	/// The position of the index in CD frames (1/75 of a second), relative to the start of its file
+	pub frames: u32,
+}
+
+impl CueIndex {
+	/// The position of the index, relative to the start of its file
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::CueIndex;
+	/// use std::time::Duration;
+	///
+	/// let index = CueIndex {
+	/// 	number: 1,
+	/// 	frames: 75 * 90,
+	/// };
+	/// assert_eq!(index.position(), Duration::from_secs(90));
+	/// ```
+	pub fn position(&self) -> Duration {
+		Duration::from_secs(u64::from(self.frames / FRAMES_PER_SECOND))
+			+ Duration::from_nanos(
+				u64::from(self.frames % FRAMES_PER_SECOND) * 1_000_000_000
+					/ u64::from(FRAMES_PER_SECOND),
+			)
+	}
+}
+
+/// A track of a [`Cuesheet`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CueTrack {
+	/// The track number, from 1 to 99
+	pub number: u8,
+	/// The file the track is in, from the last preceding `FILE` command
+	pub file: Option<String>,
+	/// The track's `TITLE`
+	pub title: Option<String>,
+	/// The track's `PERFORMER`
+	pub performer: Option<String>,
+	/// The track's `ISRC`
+	pub isrc: Option<String>,
+	/// The track's index points, in the order they appear
+	pub indices: Vec<CueIndex>,
+}
+
+impl CueTrack {
+	/// The start of the track, which is index 1
+	///
+	/// This will fall back to the first index if index 1 is missing.
+	pub fn start(&self) -> Option<&CueIndex> {
+		self.indices
+			.iter()
+			.find(|index| index.number == 1)
+			.or_else(|| self.indices.first())
+	}
+}
+
+/// A textual cue sheet, see [`ItemKey::Cuesheet`](crate::ItemKey::Cuesheet)
+///
+/// This is the cue sheet as embedded by rippers such as EAC in a `CUESHEET` Vorbis comment or APE
+/// `Cuesheet` item, **not** FLAC's binary [`CUESHEET` block](crate::flac::FlacFile).
+///
+/// Only the commands needed to locate the tracks are parsed: `FILE`, `TRACK`, `INDEX`, `TITLE`,
+/// `PERFORMER`, and `ISRC`. Everything else, including malformed lines, is ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::Cuesheet;
+/// use std::time::Duration;
+///
+/// let cue = "PERFORMER \"Foo artist\"
+/// TITLE \"Foo album\"
+/// FILE \"Foo album.flac\" WAVE
+///   TRACK 01 AUDIO
+///     TITLE \"Bar\"
+///     INDEX 01 00:00:00
+///   TRACK 02 AUDIO
+///     TITLE \"Baz\"
+///     INDEX 00 03:58:40
+///     INDEX 01 04:00:00
+/// ";
+///
+/// let cuesheet = Cuesheet::parse(cue).unwrap();
+/// assert_eq!(cuesheet.title.as_deref(), Some("Foo album"));
+/// assert_eq!(cuesheet.tracks.len(), 2);
+///
+/// let track = &cuesheet.tracks[1];
+/// assert_eq!(track.title.as_deref(), Some("Baz"));
+/// assert_eq!(track.start().unwrap().position(), Duration::from_secs(240));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Cuesheet {
+	/// The disc's `TITLE`
+	pub title: Option<String>,
+	/// The disc's `PERFORMER`
+	pub performer: Option<String>,
+	/// The tracks, in the order they appear
+	pub tracks: Vec<CueTrack>,
+}
+
+impl Cuesheet {
+	/// Parse a textual cue sheet
+	///
+	/// Returns `None` if the cue sheet doesn't contain any tracks.
+	pub fn parse(input: &str) -> Option<Self> {
+		let mut cuesheet = Self::default();
+		let mut file = None;
+
+		for line in input.lines() {
+			let line = line.trim();
+			let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+			let args = args.trim();
+
+			match command.to_ascii_uppercase().as_str() {
+				"FILE" => {
+					// The file type follows the name
+					let name = match args.strip_prefix('"') {
+						Some(quoted) => quoted.split('"').next(),
+						None => args.rsplit_once(char::is_whitespace).map(|(name, _)| name),
+					};
+					file = name.map(|name| name.trim().to_owned());
+				},
+				"TRACK" => {
+					let Some(number) = args
+						.split_whitespace()
+						.next()
+						.and_then(|number| number.parse::<u8>().ok())
+					else {
+						continue;
+					};
+
+					cuesheet.tracks.push(CueTrack {
+						number,
+						file: file.clone(),
+						..CueTrack::default()
+					});
+				},
+				"INDEX" => {
+					let (Some(track), Some(index)) =
+						(cuesheet.tracks.last_mut(), parse_index(args))
+					else {
+						continue;
+					};
+
+					track.indices.push(index);
+				},
+				"TITLE" | "PERFORMER" => {
+					let value = Some(string_arg(args));
+					let (title, performer) = match cuesheet.tracks.last_mut() {
+						Some(track) => (&mut track.title, &mut track.performer),
+						None => (&mut cuesheet.title, &mut cuesheet.performer),
+					};
+
+					if command.eq_ignore_ascii_case("TITLE") {
+						*title = value;
+					} else {
+						*performer = value;
+					}
+				},
+				"ISRC" => {
+					if let Some(track) = cuesheet.tracks.last_mut() {
+						track.isrc = Some(string_arg(args));
+					}
+				},
+				_ => {},
+			}
+		}
+
+		if cuesheet.tracks.is_empty() {
+			return None;
+		}
+
+		Some(cuesheet)
+	}
+}
+
+// A string argument, which is only quoted if it contains whitespace
+fn string_arg(args: &str) -> String {
+	match args.strip_prefix('"') {
+		Some(quoted) => quoted.split('"').next().unwrap_or_default().to_owned(),
+		None => args.to_owned(),
+	}
+}
+
+// `nn mm:ss:ff`, where the minutes can exceed 99
+fn parse_index(args: &str) -> Option<CueIndex> {
+	let mut args = args.split_whitespace();
+	let number = args.next()?.parse::<u8>().ok()?;
+
+	let mut timestamp = args.next()?.split(':');
+	let minutes = timestamp.next()?.parse::<u32>().ok()?;
+	let seconds = timestamp.next()?.parse::<u32>().ok()?;
+	let frames = timestamp.next()?.parse::<u32>().ok()?;
+	if timestamp.next().is_some() || seconds >= 60 || frames >= FRAMES_PER_SECOND {
+		return None;
+	}
+
+	let frames = minutes
+		.checked_mul(60)?
+		.checked_add(seconds)?
+		.checked_mul(FRAMES_PER_SECOND)?
+		.checked_add(frames)?;
+
+	Some(CueIndex { number, frames })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{CueIndex, Cuesheet};
+
+	#[test]
+	fn parse() {
+		let cue = "REM GENRE Rock\r\n\
+		           PERFORMER Foo\r\n\
+		           TITLE \"Foo album\"\r\n\
+		           FILE \"01 - Bar.wav\" WAVE\r\n\
+		           \tTRACK 01 AUDIO\r\n\
+		           \t\tTITLE \"Bar\"\r\n\
+		           \t\tISRC USXXX0000001\r\n\
+		           \t\tINDEX 01 00:00:00\r\n\
+		           \tTRACK 02 AUDIO\r\n\
+		           \t\tTITLE \"Baz\"\r\n\
+		           \t\tPERFORMER \"Qux\"\r\n\
+		           \t\tINDEX 00 03:58:74\r\n\
+		           FILE 02.wav WAVE\r\n\
+		           \t\tINDEX 01 00:00:00\r\n\
+		           \tTRACK 03 AUDIO\r\n\
+		           \t\tINDEX 01 120:00:01\r\n";
+
+		let cuesheet = Cuesheet::parse(cue).unwrap();
+		assert_eq!(cuesheet.title.as_deref(), Some("Foo album"));
+		assert_eq!(cuesheet.performer.as_deref(), Some("Foo"));
+		assert_eq!(cuesheet.tracks.len(), 3);
+
+		let bar = &cuesheet.tracks[0];
+		assert_eq!(bar.number, 1);
+		assert_eq!(bar.file.as_deref(), Some("01 - Bar.wav"));
+		assert_eq!(bar.isrc.as_deref(), Some("USXXX0000001"));
+		assert_eq!(bar.performer, None);
+
+		// The track starts in the next file, after its pregap
+		let baz = &cuesheet.tracks[1];
+		assert_eq!(baz.performer.as_deref(), Some("Qux"));
+		assert_eq!(baz.file.as_deref(), Some("01 - Bar.wav"));
+		assert_eq!(
+			baz.indices,
+			[
+				CueIndex {
+					number: 0,
+					frames: (3 * 60 + 58) * 75 + 74
+				},
+				CueIndex {
+					number: 1,
+					frames: 0
+				},
+			]
+		);
+		assert_eq!(baz.start(), Some(&baz.indices[1]));
+
+		let track_3 = &cuesheet.tracks[2];
+		assert_eq!(track_3.file.as_deref(), Some("02.wav"));
+		assert_eq!(track_3.start().unwrap().frames, 120 * 60 * 75 + 1);
+	}
+
+	#[test]
+	fn malformed() {
+		assert!(Cuesheet::parse("").is_none());
+		assert!(Cuesheet::parse("TITLE \"No tracks\"").is_none());
+
+		let cuesheet = Cuesheet::parse(
+			"TRACK AUDIO\nTRACK 01 AUDIO\nINDEX 01 00:60:00\nINDEX 01 00:00:75\nINDEX 01 0:1\nINDEX \
+			 01 00:01:00",
+		)
+		.unwrap();
+		assert_eq!(cuesheet.tracks.len(), 1);
+		assert_eq!(
+			cuesheet.tracks[0].indices,
+			[CueIndex {
+				number: 1,
+				frames: 75
+			}]
+		);
+	}
+}
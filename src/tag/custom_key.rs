@@ -0,0 +1,370 @@
+use crate::ape::ApeItem;
+use crate::tag::item::{ItemKey, ItemValue};
+use crate::tag::TagType;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// The format-specific keys of a custom [`ItemKey`]
+///
+/// See [`ItemKey::register_custom`].
+///
+/// Any mapping left as `None` means that the key can't be represented in that format, and
+/// items using it will be discarded when converting to it, same as a built-in [`ItemKey`] with no mapping.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::ItemKeyMappings;
+///
+/// let mappings = ItemKeyMappings {
+/// 	id3v2_txxx: Some("ACME_ASSET_ID"),
+/// 	mp4_freeform: Some(("com.acme", "asset_id")),
+/// 	vorbis: Some("ACME_ASSET_ID"),
+/// 	ape: Some("Acme Asset ID"),
+/// };
+/// ```
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ItemKeyMappings {
+	/// The description of the ID3v2 `TXXX` (or `WXXX`, for locators) frame
+	pub id3v2_txxx: Option<&'static str>,
+	/// The mean and name of the MP4 freeform (`----`) atom
+	pub mp4_freeform: Option<(&'static str, &'static str)>,
+	/// The Vorbis comments field name
+	pub vorbis: Option<&'static str>,
+	/// The APE item key
+	pub ape: Option<&'static str>,
+}
+
+struct CustomItemKey {
+	mappings: ItemKeyMappings,
+	// `mp4_freeform` in the "----:mean:name" form used by `ItemKey::map_key`
+	mp4_key: Option<&'static str>,
+}
+
+impl CustomItemKey {
+	fn key(&self, tag_type: TagType) -> Option<&'static str> {
+		match tag_type {
+			TagType::Id3v2 => self.mappings.id3v2_txxx,
+			TagType::Mp4Ilst => self.mp4_key,
+			TagType::VorbisComments => self.mappings.vorbis,
+			TagType::Ape => self.mappings.ape,
+			_ => None,
+		}
+	}
+}
+
+static CUSTOM_ITEM_KEYS: Lazy<Mutex<HashMap<&'static str, CustomItemKey>>> =
+	Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub(crate) fn lookup_item_key(tag_type: TagType, key: &str) -> Option<ItemKey> {
+	let keys = CUSTOM_ITEM_KEYS.lock().unwrap();
+
+	keys.iter()
+		.find(|(_, custom)| matches!(custom.key(tag_type), Some(k) if k.eq_ignore_ascii_case(key)))
+		.map(|(name, _)| ItemKey::Custom(name))
+}
+
+pub(crate) fn lookup_key(name: &str, tag_type: TagType) -> Option<&'static str> {
+	let keys = CUSTOM_ITEM_KEYS.lock().unwrap();
+	keys.get(name).and_then(|custom| custom.key(tag_type))
+}
+
+impl ItemKey {
+	/// Register a custom [`ItemKey`]
+	///
+	/// This allows proprietary keys, such as `TXXX:ACME_ASSET_ID` in ID3v2 and `----:com.acme:asset_id`
+	/// in MP4, to be treated the same as the built-in keys. Once registered, [`ItemKey::from_key`] and
+	/// [`ItemKey::map_key`] will make use of the mappings, meaning that converting between a [`Tag`](crate::Tag)
+	/// and the concrete tag types will carry the items over.
+	///
+	/// Registering the same name with the same mappings multiple times is allowed, and will return
+	/// the same key.
+	///
+	/// NOTE: Only the items read **after** registration will make use of the key. Items read
+	///       before then will remain as [`ItemKey::Unknown`].
+	///
+	/// # Panics
+	///
+	/// * A key of the same name was previously registered with different mappings
+	/// * A mapping is already used by a built-in or previously registered [`ItemKey`]
+	/// * A mapping is not valid for its format:
+	///   * `id3v2_txxx` is empty
+	///   * `mp4_freeform` has an empty mean or name, or either contains a `:`
+	///   * `vorbis` is not a valid Vorbis comments field name
+	///   * `ape` is not a valid APE item key
+	/// * See [`Mutex::lock`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, ItemKeyMappings, Tag, TagType};
+	///
+	/// let asset_id = ItemKey::register_custom(
+	/// 	"AcmeAssetId",
+	/// 	ItemKeyMappings {
+	/// 		id3v2_txxx: Some("ACME_ASSET_ID"),
+	/// 		mp4_freeform: Some(("com.acme", "asset_id")),
+	/// 		vorbis: Some("ACME_ASSET_ID"),
+	/// 		ape: None,
+	/// 	},
+	/// );
+	///
+	/// assert_eq!(asset_id, ItemKey::Custom("AcmeAssetId"));
+	/// assert_eq!(
+	/// 	ItemKey::from_key(TagType::Mp4Ilst, "----:com.acme:asset_id"),
+	/// 	asset_id
+	/// );
+	/// assert_eq!(
+	/// 	asset_id.map_key(TagType::VorbisComments, false),
+	/// 	Some("ACME_ASSET_ID")
+	/// );
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	/// assert!(tag.insert_text(asset_id.clone(), String::from("A-1234")));
+	/// ```
+	pub fn register_custom(name: &'static str, mappings: ItemKeyMappings) -> ItemKey {
+		verify_mappings(name, mappings);
+
+		// Panicking with the lock held would poison the registry
+		if let Err(message) = insert_custom_key(name, mappings) {
+			panic!("{message}");
+		}
+
+		ItemKey::Custom(name)
+	}
+}
+
+fn insert_custom_key(name: &'static str, mappings: ItemKeyMappings) -> Result<(), String> {
+	let mut keys = CUSTOM_ITEM_KEYS.lock().unwrap();
+
+	if let Some(existing) = keys.get(name) {
+		if existing.mappings != mappings {
+			return Err(format!(
+				"A custom `ItemKey` named `{name}` was already registered with different mappings"
+			));
+		}
+
+		return Ok(());
+	}
+
+	let mp4_key = mappings
+		.mp4_freeform
+		.map(|(mean, name)| &*Box::leak(format!("----:{mean}:{name}").into_boxed_str()));
+
+	let custom = CustomItemKey { mappings, mp4_key };
+
+	for tag_type in [
+		TagType::Id3v2,
+		TagType::Mp4Ilst,
+		TagType::VorbisComments,
+		TagType::Ape,
+	] {
+		let Some(key) = custom.key(tag_type) else {
+			continue;
+		};
+
+		if let Some(existing) = ItemKey::from_builtin_key(tag_type, key) {
+			return Err(format!(
+				"The {tag_type:?} key `{key}` of the custom `ItemKey` `{name}` is already used by \
+				 `ItemKey::{existing:?}`"
+			));
+		}
+
+		for (other_name, other) in keys.iter() {
+			if matches!(other.key(tag_type), Some(k) if k.eq_ignore_ascii_case(key)) {
+				return Err(format!(
+					"The {tag_type:?} key `{key}` of the custom `ItemKey` `{name}` is already used by \
+					 the custom `ItemKey` `{other_name}`"
+				));
+			}
+		}
+	}
+
+	keys.insert(name, custom);
+	Ok(())
+}
+
+fn verify_mappings(name: &str, mappings: ItemKeyMappings) {
+	if let Some(description) = mappings.id3v2_txxx {
+		assert!(
+			!description.is_empty(),
+			"The ID3v2 description of the custom `ItemKey` `{name}` is empty"
+		);
+	}
+
+	if let Some((mean, freeform_name)) = mappings.mp4_freeform {
+		assert!(
+			[mean, freeform_name]
+				.iter()
+				.all(|part| !part.is_empty() && !part.contains(':')),
+			"The MP4 freeform identifier of the custom `ItemKey` `{name}` is invalid"
+		);
+	}
+
+	if let Some(key) = mappings.vorbis {
+		assert!(
+			crate::ogg::tag::verify_key(key),
+			"The Vorbis comments key of the custom `ItemKey` `{name}` is invalid"
+		);
+	}
+
+	if let Some(key) = mappings.ape {
+		assert!(
+			ApeItem::new(String::from(key), ItemValue::Text(String::new())).is_ok(),
+			"The APE key of the custom `ItemKey` `{name}` is invalid"
+		);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::ape::ApeTag;
+	use crate::id3::v2::{FrameId, Id3v2Tag};
+	use crate::mp4::{AtomIdent, Ilst};
+	use crate::ogg::VorbisComments;
+	use crate::{ItemKey, ItemKeyMappings, Tag, TagType};
+
+	use std::borrow::Cow;
+
+	const ASSET_ID_MAPPINGS: ItemKeyMappings = ItemKeyMappings {
+		id3v2_txxx: Some("ACME_ASSET_ID"),
+		mp4_freeform: Some(("com.acme", "asset_id")),
+		vorbis: Some("ACME_ASSET_ID"),
+		ape: Some("Acme Asset ID"),
+	};
+
+	fn asset_id() -> ItemKey {
+		ItemKey::register_custom("AcmeAssetId", ASSET_ID_MAPPINGS)
+	}
+
+	#[test]
+	fn register_custom_is_idempotent() {
+		assert_eq!(asset_id(), ItemKey::Custom("AcmeAssetId"));
+		assert_eq!(asset_id(), asset_id());
+	}
+
+	#[test]
+	fn from_key_and_map_key() {
+		let key = asset_id();
+
+		assert_eq!(ItemKey::from_key(TagType::Id3v2, "acme_asset_id"), key);
+		assert_eq!(
+			ItemKey::from_key(TagType::Mp4Ilst, "----:com.acme:asset_id"),
+			key
+		);
+		assert_eq!(ItemKey::from_key(TagType::Ape, "Acme Asset ID"), key);
+		assert_eq!(
+			ItemKey::from_key(TagType::RiffInfo, "ACME_ASSET_ID"),
+			ItemKey::Unknown(String::from("ACME_ASSET_ID"))
+		);
+
+		assert_eq!(
+			key.map_key(TagType::Mp4Ilst, false),
+			Some("----:com.acme:asset_id")
+		);
+		assert_eq!(key.map_key(TagType::RiffInfo, true), None);
+	}
+
+	#[test]
+	fn conversions_carry_custom_keys() {
+		let key = asset_id();
+
+		let mut tag = Tag::new(TagType::Id3v2);
+		tag.insert_text(key.clone(), String::from("A-1234"));
+
+		let id3v2 = Id3v2Tag::from(tag);
+		assert_eq!(id3v2.get_user_text("ACME_ASSET_ID"), Some("A-1234"));
+
+		let tag = Tag::from(id3v2);
+		assert_eq!(tag.get_string(&key), Some("A-1234"));
+
+		let mut tag = tag;
+		tag.re_map(TagType::Mp4Ilst);
+		let ilst = Ilst::from(tag);
+		let ident = AtomIdent::Freeform {
+			mean: Cow::Borrowed("com.acme"),
+			name: Cow::Borrowed("asset_id"),
+		};
+		assert!(ilst.get(&ident).is_some());
+
+		let mut tag = Tag::from(ilst);
+		assert_eq!(tag.get_string(&key), Some("A-1234"));
+
+		tag.re_map(TagType::VorbisComments);
+		let vorbis_comments = VorbisComments::from(tag);
+		assert_eq!(vorbis_comments.get("ACME_ASSET_ID"), Some("A-1234"));
+
+		let mut tag = Tag::from(vorbis_comments);
+		assert_eq!(tag.get_string(&key), Some("A-1234"));
+
+		tag.re_map(TagType::Ape);
+		let ape = ApeTag::from(tag);
+		assert!(ape.get("Acme Asset ID").is_some());
+
+		let tag = Tag::from(ape);
+		assert_eq!(tag.get_string(&key), Some("A-1234"));
+
+		assert!(FrameId::try_from(&key).is_err());
+	}
+
+	#[test]
+	#[should_panic(expected = "already registered with different mappings")]
+	fn register_custom_different_mappings() {
+		ItemKey::register_custom(
+			"AcmeRevision",
+			ItemKeyMappings {
+				id3v2_txxx: Some("ACME_REVISION"),
+				..ItemKeyMappings::default()
+			},
+		);
+		ItemKey::register_custom(
+			"AcmeRevision",
+			ItemKeyMappings {
+				id3v2_txxx: Some("ACME_REV"),
+				..ItemKeyMappings::default()
+			},
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "is already used by `ItemKey::Description`")]
+	fn register_custom_builtin_collision() {
+		ItemKey::register_custom(
+			"AcmeDescription",
+			ItemKeyMappings {
+				vorbis: Some("description"),
+				..ItemKeyMappings::default()
+			},
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "is already used by the custom `ItemKey` `AcmeAssetId`")]
+	fn register_custom_custom_collision() {
+		let _ = asset_id();
+		ItemKey::register_custom(
+			"AcmeAssetId2",
+			ItemKeyMappings {
+				mp4_freeform: Some(("com.acme", "ASSET_ID")),
+				..ItemKeyMappings::default()
+			},
+		);
+	}
+
+	#[test]
+	#[should_panic(
+		expected = "The Vorbis comments key of the custom `ItemKey` `AcmeBadKey` is invalid"
+	)]
+	fn register_custom_invalid_key() {
+		ItemKey::register_custom(
+			"AcmeBadKey",
+			ItemKeyMappings {
+				vorbis: Some("ACME=KEY"),
+				..ItemKeyMappings::default()
+			},
+		);
+	}
+}
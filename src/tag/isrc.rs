@@ -0,0 +1,223 @@
+use crate::error::{ErrorKind, LoftyError, Result};
+use crate::macros::err;
+use crate::probe::ParsingMode;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// The length of each part of the code, and the message used when it is invalid
+const PARTS: [(usize, &str); 4] = [
+	(2, "Invalid country code"),
+	(3, "Invalid registrant code"),
+	(2, "Invalid year of reference"),
+	(5, "Invalid designation code"),
+];
+
+/// An International Standard Recording Code (ISRC)
+///
+/// ISRCs are 12 characters long, made up of the following parts:
+///
+/// * A two letter country code
+/// * A three character alphanumeric registrant code
+/// * The last two digits of the year of reference
+/// * A five digit designation code
+///
+/// They are commonly written with hyphens between the parts (`CC-XXX-YY-NNNNN`), but are stored
+/// without them in tags. When converted to a string, the compact form is used, see [`Isrc::hyphenated`]
+/// for the other.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::Isrc;
+///
+/// # fn main() -> lofty::Result<()> {
+/// let isrc: Isrc = "US-RC1-76-07839".parse()?;
+/// assert_eq!(isrc.country_code(), "US");
+/// assert_eq!(isrc.registrant_code(), "RC1");
+/// assert_eq!(isrc.year(), "76");
+/// assert_eq!(isrc.designation_code(), "07839");
+///
+/// assert_eq!(isrc.to_string(), "USRC17607839");
+/// assert_eq!(isrc.hyphenated(), "US-RC1-76-07839");
+/// # Ok(()) }
+/// ```
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Isrc([u8; 12]);
+
+impl Isrc {
+	/// Parse an ISRC
+	///
+	/// The code may be hyphenated between its parts. With [`ParsingMode::Strict`], it must otherwise be
+	/// exactly as it is defined. Any other mode also accepts lowercase letters, which are uppercased, and
+	/// hyphens or spaces anywhere in the code.
+	///
+	/// # Errors
+	///
+	/// * Any part of the code is malformed
+	/// * The code is too short or too long
+	/// * [`ParsingMode::Strict`] is used, and the code contains lowercase letters or misplaced separators
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{Isrc, ParsingMode};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// assert!(Isrc::parse("usrc17607839", ParsingMode::Strict).is_err());
+	///
+	/// let isrc = Isrc::parse("usrc17607839", ParsingMode::BestAttempt)?;
+	/// assert_eq!(isrc.to_string(), "USRC17607839");
+	///
+	/// // The year of reference must be numeric
+	/// assert!(Isrc::parse("USRC1ZZ07839", ParsingMode::BestAttempt).is_err());
+	/// # Ok(()) }
+	/// ```
+	pub fn parse(input: &str, parse_mode: ParsingMode) -> Result<Self> {
+		let input = input.trim();
+		let strict = parse_mode == ParsingMode::Strict;
+
+		let hyphenated = input.len() == 15;
+		let mut remaining = input.bytes().filter(|&c| {
+			if strict {
+				!(hyphenated && c == b'-')
+			} else {
+				c != b'-' && c != b' '
+			}
+		});
+
+		let mut code = [0; 12];
+		let mut pos = 0;
+		for (idx, (len, message)) in PARTS.into_iter().enumerate() {
+			for c in remaining.by_ref().take(len) {
+				let c = if strict { c } else { c.to_ascii_uppercase() };
+
+				let valid = match idx {
+					0 => c.is_ascii_uppercase(),
+					1 => c.is_ascii_uppercase() || c.is_ascii_digit(),
+					_ => c.is_ascii_digit(),
+				};
+
+				if !valid {
+					return Err(LoftyError::new(ErrorKind::BadIsrc(message)));
+				}
+
+				code[pos] = c;
+				pos += 1;
+			}
+		}
+
+		if pos < code.len() {
+			err!(BadIsrc("The code is too short"));
+		}
+
+		if remaining.next().is_some() {
+			err!(BadIsrc("The code is too long"));
+		}
+
+		let isrc = Self(code);
+		if strict && hyphenated && input != isrc.hyphenated() {
+			err!(BadIsrc("Misplaced separator"));
+		}
+
+		Ok(isrc)
+	}
+
+	/// The two letter country code
+	pub fn country_code(&self) -> &str {
+		self.part(0..2)
+	}
+
+	/// The three character registrant code
+	pub fn registrant_code(&self) -> &str {
+		self.part(2..5)
+	}
+
+	/// The last two digits of the year of reference
+	pub fn year(&self) -> &str {
+		self.part(5..7)
+	}
+
+	/// The five digit designation code
+	pub fn designation_code(&self) -> &str {
+		self.part(7..12)
+	}
+
+	/// The code with hyphens between its parts, `CC-XXX-YY-NNNNN`
+	pub fn hyphenated(&self) -> String {
+		format!(
+			"{}-{}-{}-{}",
+			self.country_code(),
+			self.registrant_code(),
+			self.year(),
+			self.designation_code()
+		)
+	}
+
+	fn part(&self, range: std::ops::Range<usize>) -> &str {
+		// The code is only ever made up of ASCII letters and digits
+		std::str::from_utf8(&self.0[range]).unwrap_or_default()
+	}
+}
+
+impl FromStr for Isrc {
+	type Err = LoftyError;
+
+	/// Parse an ISRC with [`ParsingMode::Strict`], see [`Isrc::parse`]
+	fn from_str(s: &str) -> Result<Self> {
+		Self::parse(s, ParsingMode::Strict)
+	}
+}
+
+impl Display for Isrc {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.part(0..12))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Isrc;
+	use crate::probe::ParsingMode;
+
+	#[test]
+	fn parse() {
+		for input in ["USRC17607839", "US-RC1-76-07839", " USRC17607839 "] {
+			let isrc = input.parse::<Isrc>().unwrap();
+			assert_eq!(isrc.to_string(), "USRC17607839", "{input}");
+		}
+
+		for input in [
+			"usrc17607839",
+			"US RC1 76 07839",
+			"USRC1-76-07839",
+			"USR-C1-76-07839",
+		] {
+			assert!(input.parse::<Isrc>().is_err(), "{input}");
+
+			let isrc = Isrc::parse(input, ParsingMode::BestAttempt).unwrap();
+			assert_eq!(isrc.hyphenated(), "US-RC1-76-07839", "{input}");
+		}
+	}
+
+	#[test]
+	fn malformed() {
+		for input in [
+			"",
+			"USRC1760783",
+			"USRC176078390",
+			"U1RC17607839",
+			"US_C17607839",
+			"USRC1AB07839",
+			"USRC176O7839",
+		] {
+			for parse_mode in [
+				ParsingMode::Strict,
+				ParsingMode::BestAttempt,
+				ParsingMode::Relaxed,
+			] {
+				assert!(Isrc::parse(input, parse_mode).is_err(), "{input}");
+			}
+		}
+	}
+}
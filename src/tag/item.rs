@@ -1,3 +1,4 @@
+use crate::tag::custom_key;
 use crate::tag::TagType;
 
 use std::borrow::Cow;
@@ -120,11 +121,14 @@ gen_map!(
 	"Genre"                        => Genre,
 	"Color"                        => Color,
 	"Mood"                         => Mood,
+	"BPM"                          => Bpm,
+	"Rating"                       => Popularimeter,
 	"Copyright"                    => CopyrightMessage,
 	"Comment"                      => Comment,
 	"language"                     => Language,
 	"Script"                       => Script,
 	"Lyrics"                       => Lyrics,
+	"Cuesheet"                     => Cuesheet,
 	"MUSICBRAINZ_TRACKID"          => MusicBrainzRecordingId,
 	"MUSICBRAINZ_RELEASETRACKID"   => MusicBrainzTrackId,
 	"MUSICBRAINZ_ALBUMID"          => MusicBrainzReleaseId,
@@ -169,6 +173,7 @@ gen_map!(
 	"TRCK"                         => TrackNumber,
 	"TRCK"                         => TrackTotal,
 	"POPM"                         => Popularimeter,
+	"PCNT"                         => PlayCount,
 	"TDRC"                         => RecordingDate,
 	"TDOR"                         => OriginalReleaseDate,
 	"TSRC"                         => Isrc,
@@ -206,17 +211,20 @@ gen_map!(
 	"TKEY"                         => InitialKey,
 	"COLOR"                        => Color,
 	"TMOO"                         => Mood,
-	"TBPM"                         => Bpm,
+	"TBPM" | "BPM"                 => Bpm, // integer bpm (TBPM) vs. precise bpm (TXXX:BPM)
 	"TCOP"                         => CopyrightMessage,
 	"TDES"                         => PodcastDescription,
+	"SERIESDESCRIPTION"            => PodcastSeriesDescription,
 	"TCAT"                         => PodcastSeriesCategory,
 	"WFED"                         => PodcastURL,
 	"TDRL"                         => PodcastReleaseDate,
 	"TGID"                         => PodcastGlobalUniqueID,
 	"TKWD"                         => PodcastKeywords,
 	"COMM"                         => Comment,
+	"DESCRIPTION"                  => Description,
 	"TLAN"                         => Language,
 	"USLT"                         => Lyrics,
+	"CUESHEET"                     => Cuesheet,
 	// Mapping of MusicBrainzRecordingId is implemented as a special case
 	"MusicBrainz Release Track Id" => MusicBrainzTrackId,
 	"MusicBrainz Album Id"         => MusicBrainzReleaseId,
@@ -264,7 +272,7 @@ gen_map!(
 	"rate"                                               => Popularimeter,
 	"rtng"                                               => ParentalAdvisory,
 	"\u{a9}day"                                          => RecordingDate,
-	"----:com.apple.iTunes:ISRC"                         => Isrc,
+	"----:com.apple.iTunes:ISRC" | "isrc"                => Isrc,
 	"----:com.apple.iTunes:BARCODE"                      => Barcode,
 	"----:com.apple.iTunes:CATALOGNUMBER"                => CatalogNumber,
 	"cpil"                                               => FlagCompilation,
@@ -284,6 +292,7 @@ gen_map!(
 	"cprt"                                               => CopyrightMessage,
 	"----:com.apple.iTunes:LICENSE"                      => License,
 	"ldes"                                               => PodcastDescription,
+	"sdes"                                               => PodcastSeriesDescription,
 	"catg"                                               => PodcastSeriesCategory,
 	"purl"                                               => PodcastURL,
 	"egid"                                               => PodcastGlobalUniqueID,
@@ -342,7 +351,7 @@ gen_map!(
 	"ALBUMARTISTSORT"                         => AlbumArtistSortOrder,
 	"TITLESORT"                               => TrackTitleSortOrder,
 	"ARTISTSORT"                              => TrackArtistSortOrder,
-	"ALBUMARTIST"                             => AlbumArtist,
+	"ALBUMARTIST" | "ALBUM ARTIST"            => AlbumArtist,
 	"ARTIST"                                  => TrackArtist,
 	"ARRANGER"                                => Arranger,
 	"AUTHOR" | "WRITER"                       => Writer,
@@ -389,10 +398,14 @@ gen_map!(
 	"INITIALKEY" | "KEY"                      => InitialKey,
 	"COPYRIGHT"                               => CopyrightMessage,
 	"LICENSE"                                 => License,
+	"PODCASTDESCRIPTION"                      => PodcastDescription,
+	"SERIESDESCRIPTION"                       => PodcastSeriesDescription,
 	"COMMENT"                                 => Comment,
+	"DESCRIPTION"                             => Description,
 	"LANGUAGE"                                => Language,
 	"SCRIPT"                                  => Script,
 	"LYRICS"                                  => Lyrics,
+	"CUESHEET"                                => Cuesheet,
 	"MUSICBRAINZ_TRACKID"                     => MusicBrainzRecordingId,
 	"MUSICBRAINZ_RELEASETRACKID"              => MusicBrainzTrackId,
 	"MUSICBRAINZ_ALBUMID"                     => MusicBrainzReleaseId,
@@ -427,6 +440,8 @@ macro_rules! gen_item_keys {
 				$(#[$variant_meta])*
 				$variant_ident,
 			)+
+			/// A key registered with [`ItemKey::register_custom`]
+			Custom(&'static str),
 			/// When a key couldn't be mapped to another variant
 			///
 			/// This **will not** allow writing keys that are out of spec (Eg. ID3v2.4 frame IDs **must** be 4 characters)
@@ -438,20 +453,33 @@ macro_rules! gen_item_keys {
 			///
 			/// NOTE: If used with ID3v2, this will only check against the ID3v2.4 keys.
 			/// If you wish to use a V2 or V3 key, see [`upgrade_v2`](crate::id3::v2::upgrade_v2) and [`upgrade_v3`](crate::id3::v2::upgrade_v3)
+			///
+			/// Keys registered with [`ItemKey::register_custom`] are checked after the built-in keys.
 			pub fn from_key(tag_type: TagType, key: &str) -> Self {
+				Self::from_builtin_key(tag_type, key)
+					.or_else(|| custom_key::lookup_item_key(tag_type, key))
+					.unwrap_or_else(|| Self::Unknown(key.to_string()))
+			}
+
+			pub(crate) fn from_builtin_key(tag_type: TagType, key: &str) -> Option<Self> {
 				match tag_type {
 					$(
 						$(#[$feat])?
-						$tag_type => $MAP.get_item_key(key).unwrap_or_else(|| Self::Unknown(key.to_string())),
+						$tag_type => $MAP.get_item_key(key),
 					)+
-					_ => Self::Unknown(key.to_string())
+					_ => None
 				}
 			}
+
 			/// Maps the variant to a format-specific key
 			///
 			/// Use `allow_unknown` to include [`ItemKey::Unknown`]. It is up to the caller
 			/// to determine if the unknown key actually fits the format's specifications.
 			pub fn map_key(&self, tag_type: TagType, allow_unknown: bool) -> Option<&str> {
+				if let ItemKey::Custom(name) = self {
+					return custom_key::lookup_key(name, tag_type);
+				}
+
 				match tag_type {
 					$(
 						$(#[$feat])?
@@ -538,6 +566,7 @@ gen_item_keys!(
 		TrackNumber,
 		TrackTotal,
 		Popularimeter,
+		PlayCount,
 		ParentalAdvisory,
 
 		// Dates
@@ -652,7 +681,10 @@ gen_item_keys!(
 		License,
 
 		// Podcast
+		/// The long description of a podcast episode
 		PodcastDescription,
+		/// The description of the podcast series an episode belongs to
+		PodcastSeriesDescription,
 		PodcastSeriesCategory,
 		PodcastURL,
 		PodcastReleaseDate,
@@ -661,10 +693,22 @@ gen_item_keys!(
 
 		// Miscellaneous
 		Comment,
+		/// A short description of the contents, separate from [`ItemKey::Comment`]
+		///
+		/// ID3v2 has no frame for this, so it is stored as `TXXX:DESCRIPTION`. `COMM` is left to
+		/// [`ItemKey::Comment`], and `TIT3` is [`ItemKey::TrackSubtitle`].
 		Description,
 		Language,
 		Script,
 		Lyrics,
+		/// A textual cue sheet, see [`Cuesheet`](crate::Cuesheet)
+		///
+		/// This is only supported in Vorbis comments (`CUESHEET`), APE (`Cuesheet`), and ID3v2
+		/// (`TXXX:CUESHEET`). Cue sheets are often tens of KB, but will never be truncated to fit
+		/// [`TagLimits`](crate::id3::v2::TagLimits) or split by
+		/// [`WriteOptions::split_oversized_id3v2_frames`](crate::WriteOptions::split_oversized_id3v2_frames),
+		/// they are either written whole or not at all.
+		Cuesheet,
 
 		// Vendor-specific
 		AppleXid,
@@ -735,6 +779,20 @@ impl ItemValue {
 			Self::Text(text) => text.is_empty(),
 		}
 	}
+
+	pub(crate) fn shrink_to_fit(&mut self) {
+		match self {
+			Self::Binary(binary) => binary.shrink_to_fit(),
+			Self::Locator(text) | Self::Text(text) => text.shrink_to_fit(),
+		}
+	}
+
+	pub(crate) fn heap_size(&self) -> usize {
+		match self {
+			Self::Binary(binary) => binary.capacity(),
+			Self::Locator(text) | Self::Text(text) => text.capacity(),
+		}
+	}
 }
 
 pub(crate) enum ItemValueRef<'a> {
@@ -813,6 +871,23 @@ impl TagItem {
 		(self.item_key, self.item_value)
 	}
 
+	pub(crate) fn shrink_to_fit(&mut self) {
+		if let ItemKey::Unknown(key) = &mut self.item_key {
+			key.shrink_to_fit();
+		}
+
+		self.item_value.shrink_to_fit();
+	}
+
+	pub(crate) fn heap_size(&self) -> usize {
+		let key_size = match &self.item_key {
+			ItemKey::Unknown(key) => key.capacity(),
+			_ => 0,
+		};
+
+		key_size + self.item_value.heap_size()
+	}
+
 	pub(crate) fn re_map(&self, tag_type: TagType) -> bool {
 		if tag_type == TagType::Id3v1 {
 			use crate::id3::v1::constants::VALID_ITEMKEYS;
@@ -820,6 +895,11 @@ impl TagItem {
 			return VALID_ITEMKEYS.contains(&self.item_key);
 		}
 
+		// The MusicBrainz recording ID is stored in a `UFID` frame, which has no key to map to
+		if tag_type == TagType::Id3v2 && self.item_key == ItemKey::MusicBrainzRecordingId {
+			return true;
+		}
+
 		self.item_key.map_key(tag_type, false).is_some()
 	}
 }
@@ -1,17 +1,36 @@
+pub(crate) mod conflict;
+pub(crate) mod content;
+pub(crate) mod coverage;
+pub(crate) mod cuesheet;
+pub(crate) mod custom_key;
+pub(crate) mod isrc;
 pub(crate) mod item;
+pub(crate) mod musical_key;
+pub(crate) mod rating;
+pub(crate) mod timestamp;
 pub(crate) mod utils;
 
 use crate::error::{LoftyError, Result};
+#[cfg(feature = "fs")]
 use crate::file::FileType;
+use crate::id3::v2::Popularimeter;
 use crate::macros::err;
 use crate::picture::{Picture, PictureType};
 use crate::probe::Probe;
 use crate::traits::{Accessor, MergeTag, SplitTag, TagExt};
+use crate::util::io::FileLike;
+use crate::write_options::{BpmRounding, WriteOptions};
+use cuesheet::Cuesheet;
 use item::{ItemKey, ItemValue, TagItem};
+use musical_key::MusicalKey;
 
 use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::{File, OpenOptions};
+#[cfg(feature = "fs")]
+use std::io::Seek;
 use std::io::Write;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 macro_rules! impl_accessor {
@@ -115,6 +134,50 @@ pub(crate) fn try_parse_year(input: &str) -> Option<u32> {
 	(num_digits == 4).then_some(year)
 }
 
+#[must_use]
+pub(crate) fn try_parse_bpm(input: &str) -> Option<f64> {
+	let bpm = input.trim().parse::<f64>().ok()?;
+	(bpm.is_finite() && bpm >= 0.0).then_some(bpm)
+}
+
+/// Split a BPM into the parts used by formats with an integer BPM field
+///
+/// The integer part is rounded with [`BpmRounding::default`]. The precise part is only
+/// present if the BPM is fractional.
+pub(crate) fn split_bpm(input: &str) -> Option<(u16, Option<&str>)> {
+	let bpm = try_parse_bpm(input)?;
+	let integer = BpmRounding::default().round(bpm)?;
+
+	if bpm.fract() == 0.0 {
+		return Some((integer, None));
+	}
+
+	Some((integer, Some(input.trim())))
+}
+
+// Formats with both an integer and a precise BPM field will produce an `ItemKey::Bpm` for each of them.
+// Only the most precise one is kept.
+pub(crate) fn retain_precise_bpm(tag: &mut Tag) {
+	if tag.get_strings(&ItemKey::Bpm).nth(1).is_none() {
+		return;
+	}
+
+	let preferred = tag
+		.get_strings(&ItemKey::Bpm)
+		.position(|bpm| try_parse_bpm(bpm).is_some_and(|bpm| bpm.fract() != 0.0))
+		.unwrap_or(0);
+
+	let mut index = 0;
+	tag.items.retain(|item| {
+		if item.key() != &ItemKey::Bpm || item.value().text().is_none() {
+			return true;
+		}
+
+		index += 1;
+		index - 1 == preferred
+	});
+}
+
 impl Accessor for Tag {
 	impl_accessor!(
 		TrackArtist => artist,
@@ -236,6 +299,7 @@ impl Tag {
 
 	/// Change the [`TagType`], remapping all items
 	pub fn re_map(&mut self, tag_type: TagType) {
+		rating::convert_rating_items(&mut self.items, tag_type);
 		self.retain(|i| i.re_map(tag_type));
 		self.tag_type = tag_type
 	}
@@ -351,6 +415,182 @@ impl Tag {
 		self.insert(TagItem::new(item_key, ItemValue::Text(text)))
 	}
 
+	/// Gets the BPM, see [`ItemKey::Bpm`]
+	///
+	/// Unlike most other numeric fields, this can be fractional.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	/// tag.insert_text(ItemKey::Bpm, String::from("126.5"));
+	///
+	/// assert_eq!(tag.bpm(), Some(126.5));
+	/// ```
+	pub fn bpm(&self) -> Option<f64> {
+		self.get_string(&ItemKey::Bpm).and_then(try_parse_bpm)
+	}
+
+	/// Sets the BPM, see [`ItemKey::Bpm`]
+	///
+	/// Any fractional part will be preserved, see [`WriteOptions::bpm_rounding`] for how it is handled in formats
+	/// that can only store an integer BPM.
+	pub fn set_bpm(&mut self, bpm: f64) {
+		self.insert_text(ItemKey::Bpm, bpm.to_string());
+	}
+
+	/// Gets the initial key, see [`ItemKey::InitialKey`]
+	///
+	/// This will return `None` if the key isn't in a notation recognized by [`MusicalKey::parse`]. The
+	/// original text is still available through [`Tag::get_string`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, MusicalKey, Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::VorbisComments);
+	/// tag.insert_text(ItemKey::InitialKey, String::from("01A"));
+	///
+	/// let key = tag.initial_key().unwrap();
+	/// assert_eq!(key.to_string(), "Abm");
+	/// assert_eq!(key.camelot().as_deref(), Some("1A"));
+	/// ```
+	pub fn initial_key(&self) -> Option<MusicalKey> {
+		self.get_string(&ItemKey::InitialKey)
+			.and_then(MusicalKey::parse)
+	}
+
+	/// Sets the initial key, see [`ItemKey::InitialKey`]
+	///
+	/// The key is stored in the normalized notation used by ID3v2, see [`MusicalKey`].
+	pub fn set_initial_key(&mut self, key: MusicalKey) {
+		self.insert_text(ItemKey::InitialKey, key.to_string());
+	}
+
+	/// Gets the rating, normalized to a scale of 0 to 100, see [`ItemKey::Popularimeter`]
+	///
+	/// Every 20 is a star, so a rating of 80 is 4 stars. See [`Tag::set_rating`] for how each format
+	/// is converted. The raw value is still available through [`Tag::get`].
+	///
+	/// This will return `None` if there's no rating, or it isn't in a recognized representation.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, Tag, TagType};
+	///
+	/// // MediaMonkey writes star ratings to Vorbis comments
+	/// let mut tag = Tag::new(TagType::VorbisComments);
+	/// tag.insert_text(ItemKey::Popularimeter, String::from("4"));
+	///
+	/// assert_eq!(tag.rating(), Some(80));
+	/// ```
+	pub fn rating(&self) -> Option<u8> {
+		self.get(&ItemKey::Popularimeter)
+			.and_then(|item| rating::item_rating(item.value()))
+	}
+
+	/// Sets the rating, normalized to a scale of 0 to 100, see [`ItemKey::Popularimeter`]
+	///
+	/// Ratings above 100 are clamped. ID3v2 stores the rating in a `POPM` frame, with a rating from 0 to 255.
+	/// The rating is rounded to the nearest half star when stored there:
+	///
+	/// | Stars | `POPM` | Normalized |
+	/// |-------|--------|------------|
+	/// | 0     | 0      | 0          |
+	/// | 0.5   | 13     | 10         |
+	/// | 1     | 1      | 20         |
+	/// | 1.5   | 54     | 30         |
+	/// | 2     | 64     | 40         |
+	/// | 2.5   | 118    | 50         |
+	/// | 3     | 128    | 60         |
+	/// | 3.5   | 186    | 70         |
+	/// | 4     | 196    | 80         |
+	/// | 4.5   | 242    | 90         |
+	/// | 5     | 255    | 100        |
+	///
+	/// The whole stars are the values written by Windows Media Player, and the half stars those written by
+	/// MediaMonkey and MusicBee. When reading, any other `POPM` rating falls into the bands used by Windows
+	/// Media Player: 2-31 (1 star), 32-95 (2 stars), 96-159 (3 stars), 160-223 (4 stars), and 224-254 (5 stars).
+	/// The email and play counter of an existing `POPM` frame are kept.
+	///
+	/// All other formats store the rating as text (`RATING` in Vorbis comments, `rate` in MP4, `Rating` in APE,
+	/// and `IRTD` in RIFF INFO), which is written on the normalized scale. When reading, values of `5` or less
+	/// are treated as a star rating, as written by MediaMonkey. As such, a rating of 1 to 5 will be stored as `6`.
+	///
+	/// The rating is converted between the two representations when converting between tag types.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::id3::v2::Id3v2Tag;
+	/// use lofty::{Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	/// tag.set_rating(80);
+	///
+	/// assert_eq!(tag.rating(), Some(80));
+	///
+	/// // Stored as 196 in the `POPM` frame
+	/// let id3v2 = Id3v2Tag::from(tag);
+	/// ```
+	pub fn set_rating(&mut self, rating: u8) {
+		let rating = rating.min(100);
+
+		if self.tag_type != TagType::Id3v2 {
+			self.insert_text(ItemKey::Popularimeter, rating::rating_to_text(rating));
+			return;
+		}
+
+		let existing = self
+			.get_binary(&ItemKey::Popularimeter, false)
+			.and_then(|popm| Popularimeter::parse(&mut &popm[..]).ok());
+
+		let popm = match existing {
+			Some(popm) => Popularimeter {
+				rating: rating::rating_to_popm(rating),
+				..popm
+			},
+			None => Popularimeter {
+				email: String::new(),
+				rating: rating::rating_to_popm(rating),
+				counter: 0,
+			},
+		};
+
+		self.insert(TagItem::new(
+			ItemKey::Popularimeter,
+			ItemValue::Binary(popm.as_bytes()),
+		));
+	}
+
+	/// Gets the embedded cue sheet, see [`ItemKey::Cuesheet`]
+	///
+	/// This will return `None` if there's no cue sheet, or it doesn't contain any tracks. The original
+	/// text is still available through [`Tag::get_string`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::VorbisComments);
+	/// tag.insert_text(
+	/// 	ItemKey::Cuesheet,
+	/// 	String::from("FILE \"Foo.flac\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00"),
+	/// );
+	///
+	/// let cuesheet = tag.cuesheet().unwrap();
+	/// assert_eq!(cuesheet.tracks[0].file.as_deref(), Some("Foo.flac"));
+	/// ```
+	pub fn cuesheet(&self) -> Option<Cuesheet> {
+		self.get_string(&ItemKey::Cuesheet)
+			.and_then(Cuesheet::parse)
+	}
+
 	/// Removes all items with the specified [`ItemKey`], and returns them
 	pub fn take(&mut self, key: &ItemKey) -> impl Iterator<Item = TagItem> + '_ {
 		// TODO: drain_filter
@@ -519,6 +759,119 @@ impl Tag {
 	pub fn remove_picture(&mut self, index: usize) -> Picture {
 		self.pictures.remove(index)
 	}
+
+	/// Shrinks the capacity of every item and picture as much as possible
+	///
+	/// Tags are built up incrementally while reading, which can leave them holding onto much more memory
+	/// than their contents need. This is worth calling on tags that will be kept around for a while.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ItemKey, Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	///
+	/// let mut title = String::with_capacity(1024);
+	/// title.push_str("Foo title");
+	/// tag.insert_text(ItemKey::TrackTitle, title);
+	///
+	/// let heap_size = tag.heap_size();
+	/// tag.shrink_to_fit();
+	/// assert!(tag.heap_size() < heap_size);
+	/// ```
+	pub fn shrink_to_fit(&mut self) {
+		self.items.shrink_to_fit();
+		self.pictures.shrink_to_fit();
+
+		for item in &mut self.items {
+			item.shrink_to_fit();
+		}
+
+		for picture in &mut self.pictures {
+			picture.shrink_to_fit();
+		}
+	}
+
+	/// An approximation of the number of bytes the tag has allocated on the heap
+	///
+	/// This accounts for the allocated capacity of every item and picture, not only their contents.
+	/// See [`Tag::shrink_to_fit`].
+	pub fn heap_size(&self) -> usize {
+		let items_size = self.items.capacity() * std::mem::size_of::<TagItem>()
+			+ self.items.iter().map(TagItem::heap_size).sum::<usize>();
+
+		let pictures_size = self.pictures.capacity() * std::mem::size_of::<Picture>()
+			+ self.pictures.iter().map(Picture::heap_size).sum::<usize>();
+
+		items_size + pictures_size
+	}
+
+	/// Whether two tags have the same content
+	///
+	/// Unlike comparing the tags directly, this ignores differences that don't affect the content,
+	/// making it suitable for finding duplicates. See [`Tag::content_hash`] for the rules.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{Accessor, Tag, TagType};
+	///
+	/// let mut tag = Tag::new(TagType::Id3v2);
+	/// tag.set_title(String::from("Foo title"));
+	/// tag.set_artist(String::from("Bar artist"));
+	///
+	/// let mut other = Tag::new(TagType::Id3v2);
+	/// other.set_artist(String::from("Bar artist"));
+	/// other.set_title(String::from("Foo title"));
+	///
+	/// assert!(tag.eq_content(&other));
+	/// assert_eq!(tag.content_hash(), other.content_hash());
+	/// ```
+	pub fn eq_content(&self, other: &Tag) -> bool {
+		content::eq_content(self, other)
+	}
+
+	/// A hash of the tag's content
+	///
+	/// Two tags that are equal according to [`Tag::eq_content`] will have the same hash.
+	///
+	/// The content is normalized as follows:
+	///
+	/// * The [`TagType`] is included, tags of different types never have the same content
+	/// * The order of items and pictures is ignored. Duplicate items are **not** ignored.
+	/// * Items are compared by their [`ItemKey`] and [`ItemValue`]. Values are compared exactly, no
+	///   whitespace or case normalization is done.
+	/// * For [`TagType::VorbisComments`], the keys of [`ItemKey::Unknown`] items are compared
+	///   case-insensitively, as Vorbis Comments keys are case-insensitive. Other formats are compared
+	///   case-sensitively.
+	/// * Pictures are compared by their [`PictureType`] and data, see [`Picture::eq_data`]. Their
+	///   descriptions and MIME types are ignored.
+	///
+	/// These rules, along with the hash algorithm (64-bit FNV-1a), are stable across versions. The hash
+	/// can be persisted, and any change to it will be considered breaking.
+	pub fn content_hash(&self) -> u64 {
+		content::content_hash(self)
+	}
+
+	// `TagExt::save_to`, for anything that can stand in for a file
+	pub(crate) fn write_to<F>(&self, file: &mut F, write_options: WriteOptions) -> Result<()>
+	where
+		F: FileLike,
+	{
+		let probe = Probe::new(file).guess_file_type()?;
+
+		match probe.file_type() {
+			Some(file_type) => {
+				if file_type.supports_tag_type(self.tag_type()) {
+					utils::write_tag(self, probe.into_inner(), file_type, write_options)
+				} else {
+					err!(UnsupportedTag);
+				}
+			},
+			None => err!(UnknownFormat),
+		}
+	}
 }
 
 impl TagExt for Tag {
@@ -537,31 +890,26 @@ impl TagExt for Tag {
 		self.items.is_empty() && self.pictures.is_empty()
 	}
 
+	#[cfg(feature = "fs")]
 	/// Save the `Tag` to a [`File`](std::fs::File)
 	///
 	/// # Errors
 	///
 	/// * A [`FileType`](crate::FileType) couldn't be determined from the File
 	/// * Attempting to write a tag to a format that does not support it. See [`FileType::supports_tag_type`](crate::FileType::supports_tag_type)
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err> {
-		let probe = Probe::new(file).guess_file_type()?;
-
-		match probe.file_type() {
-			Some(file_type) => {
-				if file_type.supports_tag_type(self.tag_type()) {
-					utils::write_tag(self, probe.into_inner(), file_type)
-				} else {
-					err!(UnsupportedTag);
-				}
-			},
-			None => err!(UnknownFormat),
-		}
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
+		self.write_to(file, write_options)
 	}
 
-	fn dump_to<W: Write>(&self, writer: &mut W) -> Result<()> {
-		utils::dump_tag(self, writer)
+	fn dump_to<W: Write>(&self, writer: &mut W, write_options: WriteOptions) -> Result<()> {
+		utils::dump_tag(self, writer, write_options)
 	}
 
+	#[cfg(feature = "fs")]
 	/// Remove a tag from a [`Path`]
 	///
 	/// # Errors
@@ -571,6 +919,7 @@ impl TagExt for Tag {
 		self.tag_type.remove_from_path(path)
 	}
 
+	#[cfg(feature = "fs")]
 	/// Remove a tag from a [`File`]
 	///
 	/// # Errors
@@ -626,6 +975,7 @@ pub enum TagType {
 }
 
 impl TagType {
+	#[cfg(feature = "fs")]
 	/// Remove a tag from a [`Path`]
 	///
 	/// # Errors
@@ -636,9 +986,13 @@ impl TagType {
 		self.remove_from(&mut file)
 	}
 
+	#[cfg(feature = "fs")]
 	#[allow(clippy::shadow_unrelated)]
 	/// Remove a tag from a [`File`]
 	///
+	/// [`TagType::Ape`] and [`TagType::Id3v1`] tags at the end of a file are removed by truncating it,
+	/// rather than rewriting the entire file.
+	///
 	/// # Errors
 	///
 	/// * It is unable to guess the file format
@@ -663,19 +1017,40 @@ impl TagType {
 		}
 
 		let file = probe.into_inner();
-		utils::write_tag(&Tag::new(*self), file, file_type)
+
+		// Tags at the end of the file can be removed without rewriting it
+		if matches!(self, TagType::Ape | TagType::Id3v1) {
+			let ape = *self == TagType::Ape;
+			if utils::remove_trailing_tags(file, ape, !ape)? {
+				return Ok(());
+			}
+
+			file.rewind()?;
+		}
+
+		utils::write_tag(&Tag::new(*self), file, file_type, WriteOptions::default())
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::try_parse_year;
+	use super::{split_bpm, try_parse_year};
+	use crate::{
+		Accessor, BpmRounding, ItemKey, ItemValue, MimeType, Picture, PictureType, Tag, TagItem,
+		TagType,
+	};
+
+	#[cfg(feature = "fs")]
 	use crate::tag::utils::test_utils::read_path;
-	use crate::{Accessor, Picture, PictureType, Tag, TagExt, TagType};
+	#[cfg(feature = "fs")]
+	use crate::{ParsingMode, TagExt, WriteOptions};
+	#[cfg(feature = "fs")]
 	use std::io::{Seek, Write};
+	#[cfg(feature = "fs")]
 	use std::process::Command;
 
 	#[test]
+	#[cfg(feature = "fs")]
 	fn issue_37() {
 		let file_contents = read_path("tests/files/assets/issue_37.ogg");
 		let mut temp_file = tempfile::NamedTempFile::new().unwrap();
@@ -684,12 +1059,16 @@ mod tests {
 
 		let mut tag = Tag::new(TagType::VorbisComments);
 
-		let mut picture =
-			Picture::from_reader(&mut &*read_path("tests/files/assets/issue_37.jpg")).unwrap();
+		let mut picture = Picture::from_reader(
+			&mut &*read_path("tests/files/assets/issue_37.jpg"),
+			ParsingMode::Strict,
+		)
+		.unwrap();
 		picture.set_pic_type(PictureType::CoverFront);
 
 		tag.push_picture(picture);
-		tag.save_to(temp_file.as_file_mut()).unwrap();
+		tag.save_to(temp_file.as_file_mut(), WriteOptions::default())
+			.unwrap();
 
 		let cmd_output = Command::new("ffprobe")
 			.arg(temp_file.path().to_str().unwrap())
@@ -707,6 +1086,7 @@ mod tests {
 	}
 
 	#[test]
+	#[cfg(feature = "fs")]
 	fn issue_130_huge_picture() {
 		// Verify we have opus-tools available, otherwise skip
 		match Command::new("opusinfo").output() {
@@ -726,12 +1106,16 @@ mod tests {
 		let mut tag = Tag::new(TagType::VorbisComments);
 
 		// 81KB picture, which is big enough to surpass the maximum page size
-		let mut picture =
-			Picture::from_reader(&mut &*read_path("tests/files/assets/issue_37.jpg")).unwrap();
+		let mut picture = Picture::from_reader(
+			&mut &*read_path("tests/files/assets/issue_37.jpg"),
+			ParsingMode::Strict,
+		)
+		.unwrap();
 		picture.set_pic_type(PictureType::CoverFront);
 
 		tag.push_picture(picture);
-		tag.save_to(temp_file.as_file_mut()).unwrap();
+		tag.save_to(temp_file.as_file_mut(), WriteOptions::default())
+			.unwrap();
 
 		let cmd_output = Command::new("opusinfo")
 			.arg(temp_file.path().to_str().unwrap())
@@ -777,4 +1161,70 @@ mod tests {
 		assert!(try_parse_year("19").is_none());
 		assert!(try_parse_year("1").is_none());
 	}
+
+	#[test]
+	fn split_bpm_into_integer_and_precise() {
+		assert_eq!(split_bpm("128"), Some((128, None)));
+		assert_eq!(split_bpm(" 128.0 "), Some((128, None)));
+		assert_eq!(split_bpm("126.5"), Some((127, Some("126.5"))));
+		assert_eq!(split_bpm(" 126.4\n"), Some((126, Some("126.4"))));
+		assert!(split_bpm("-1").is_none());
+		assert!(split_bpm("NaN").is_none());
+		assert!(split_bpm("fast").is_none());
+		assert!(split_bpm("65536").is_none());
+	}
+
+	#[test]
+	fn bpm_rounding() {
+		assert_eq!(BpmRounding::Nearest.round(126.5), Some(127));
+		assert_eq!(BpmRounding::Nearest.round(126.4), Some(126));
+		assert_eq!(BpmRounding::Down.round(126.9), Some(126));
+		assert_eq!(BpmRounding::Up.round(126.1), Some(127));
+		assert!(BpmRounding::Up.round(65535.5).is_none());
+	}
+
+	#[test]
+	fn shrink_to_fit() {
+		let mut tag = Tag::new(TagType::Ape);
+		tag.items.reserve(64);
+		tag.pictures.reserve(8);
+
+		let mut title = String::with_capacity(1024);
+		title.push_str("Foo title");
+		tag.insert_text(ItemKey::TrackTitle, title);
+
+		let mut key = String::with_capacity(1024);
+		key.push_str("FOO");
+		tag.insert_unchecked(TagItem::new(
+			ItemKey::Unknown(key),
+			ItemValue::Binary(Vec::with_capacity(1024)),
+		));
+
+		let mut data = Vec::with_capacity(4096);
+		data.extend_from_slice(&[0; 16]);
+		tag.push_picture(Picture::new_unchecked(
+			PictureType::CoverFront,
+			Some(MimeType::Unknown(String::with_capacity(1024))),
+			Some(String::with_capacity(1024)),
+			data,
+		));
+
+		assert!(tag.heap_size() > 1024 * 4 + 4096);
+
+		tag.shrink_to_fit();
+		assert_eq!(tag.items.capacity(), 2);
+		assert_eq!(tag.pictures.capacity(), 1);
+		assert_eq!(
+			tag.heap_size(),
+			2 * std::mem::size_of::<TagItem>()
+				+ std::mem::size_of::<Picture>()
+				+ "Foo title".len()
+				+ "FOO".len()
+				+ 16
+		);
+
+		// The contents are untouched
+		assert_eq!(tag.title().as_deref(), Some("Foo title"));
+		assert_eq!(tag.pictures()[0].data(), &[0; 16]);
+	}
 }
@@ -0,0 +1,368 @@
+use std::fmt::{Display, Formatter};
+
+/// The note of a [`MusicalKey`]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[allow(missing_docs)]
+pub enum KeyNote {
+	C,
+	D,
+	E,
+	F,
+	G,
+	A,
+	B,
+}
+
+impl KeyNote {
+	fn from_char(c: char) -> Option<Self> {
+		match c.to_ascii_uppercase() {
+			'C' => Some(Self::C),
+			'D' => Some(Self::D),
+			'E' => Some(Self::E),
+			'F' => Some(Self::F),
+			'G' => Some(Self::G),
+			'A' => Some(Self::A),
+			'B' => Some(Self::B),
+			_ => None,
+		}
+	}
+
+	fn as_char(self) -> char {
+		match self {
+			Self::C => 'C',
+			Self::D => 'D',
+			Self::E => 'E',
+			Self::F => 'F',
+			Self::G => 'G',
+			Self::A => 'A',
+			Self::B => 'B',
+		}
+	}
+
+	fn pitch_class(self) -> u8 {
+		match self {
+			Self::C => 0,
+			Self::D => 2,
+			Self::E => 4,
+			Self::F => 5,
+			Self::G => 7,
+			Self::A => 9,
+			Self::B => 11,
+		}
+	}
+}
+
+/// The accidental of a [`MusicalKey`]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Accidental {
+	/// A half step up (`#`)
+	Sharp,
+	/// A half step down (`b`)
+	Flat,
+}
+
+/// The mode of a [`MusicalKey`]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum KeyMode {
+	/// A major key
+	#[default]
+	Major,
+	/// A minor key (`m`)
+	Minor,
+}
+
+// The keys of the Camelot wheel, starting at 1
+const CAMELOT_KEYS: [(KeyNote, Option<Accidental>); 12] = [
+	(KeyNote::B, None),
+	(KeyNote::F, Some(Accidental::Sharp)),
+	(KeyNote::D, Some(Accidental::Flat)),
+	(KeyNote::A, Some(Accidental::Flat)),
+	(KeyNote::E, Some(Accidental::Flat)),
+	(KeyNote::B, Some(Accidental::Flat)),
+	(KeyNote::F, None),
+	(KeyNote::C, None),
+	(KeyNote::G, None),
+	(KeyNote::D, None),
+	(KeyNote::A, None),
+	(KeyNote::E, None),
+];
+
+/// A musical key, see [`ItemKey::InitialKey`](crate::ItemKey::InitialKey)
+///
+/// Keys are written in a variety of notations, so this can be parsed from:
+///
+/// * Standard notation, such as `Abm`, `G#m`, `A♭ minor`, or `C major`
+/// * Camelot notation, such as `1A` or `08B`
+/// * `o` or `off`, for a key that is [off key](MusicalKey::OffKey)
+///
+/// When converted to a string, the key is normalized to the representation allowed by ID3v2, which is
+/// at most 3 characters, such as `Abm`, `C#`, or `o`.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::{Accidental, KeyMode, KeyNote, MusicalKey};
+///
+/// let key = MusicalKey::parse("5A").unwrap();
+/// assert_eq!(
+/// 	key,
+/// 	MusicalKey::Key {
+/// 		note: KeyNote::C,
+/// 		accidental: None,
+/// 		mode: KeyMode::Minor
+/// 	}
+/// );
+/// assert_eq!(key.to_string(), "Cm");
+///
+/// let key = MusicalKey::parse("G# minor").unwrap();
+/// assert_eq!(key.to_string(), "G#m");
+/// assert_eq!(key.camelot().as_deref(), Some("1A"));
+/// ```
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MusicalKey {
+	/// A key with a note, optional accidental, and mode
+	#[allow(missing_docs)]
+	Key {
+		note: KeyNote,
+		accidental: Option<Accidental>,
+		mode: KeyMode,
+	},
+	/// The audio doesn't have a key (`o`)
+	OffKey,
+}
+
+impl MusicalKey {
+	/// Parse a key from standard or Camelot notation
+	///
+	/// Returns `None` if the notation isn't recognized.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::MusicalKey;
+	///
+	/// assert_eq!(MusicalKey::parse("Abm"), MusicalKey::parse("1A"));
+	/// assert_eq!(MusicalKey::parse("o"), Some(MusicalKey::OffKey));
+	/// assert!(MusicalKey::parse("H").is_none());
+	/// ```
+	pub fn parse(input: &str) -> Option<Self> {
+		let input = input.trim();
+
+		if input.eq_ignore_ascii_case("o")
+			|| input.eq_ignore_ascii_case("off")
+			|| input.eq_ignore_ascii_case("off key")
+		{
+			return Some(Self::OffKey);
+		}
+
+		Self::parse_camelot(input).or_else(|| Self::parse_standard(input))
+	}
+
+	fn parse_camelot(input: &str) -> Option<Self> {
+		let letter = input.chars().last()?;
+		let number = &input[..input.len() - letter.len_utf8()];
+		if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+			return None;
+		}
+
+		let mode = match letter.to_ascii_uppercase() {
+			'A' => KeyMode::Minor,
+			'B' => KeyMode::Major,
+			_ => return None,
+		};
+
+		let number = number.parse::<usize>().ok()?;
+		if !(1..=12).contains(&number) {
+			return None;
+		}
+
+		// The minor keys are the relative minors of the major keys at the same position
+		let (note, accidental) = match mode {
+			KeyMode::Major => CAMELOT_KEYS[number - 1],
+			KeyMode::Minor => CAMELOT_KEYS[(number + 2) % 12],
+		};
+
+		Some(Self::Key {
+			note,
+			accidental,
+			mode,
+		})
+	}
+
+	fn parse_standard(input: &str) -> Option<Self> {
+		let mut chars = input.chars();
+		let note = KeyNote::from_char(chars.next()?)?;
+
+		let mut rest = chars.as_str();
+		let mut accidental = None;
+		for (symbols, value) in [
+			(["#", "♯"], Accidental::Sharp),
+			(["b", "♭"], Accidental::Flat),
+		] {
+			if let Some(symbol) = symbols.into_iter().find(|s| rest.starts_with(s)) {
+				accidental = Some(value);
+				rest = &rest[symbol.len()..];
+				break;
+			}
+		}
+
+		let mode = match rest.trim_start() {
+			"" | "M" => KeyMode::Major,
+			"m" => KeyMode::Minor,
+			mode if mode.eq_ignore_ascii_case("maj") || mode.eq_ignore_ascii_case("major") => {
+				KeyMode::Major
+			},
+			mode if mode.eq_ignore_ascii_case("min") || mode.eq_ignore_ascii_case("minor") => {
+				KeyMode::Minor
+			},
+			_ => return None,
+		};
+
+		Some(Self::Key {
+			note,
+			accidental,
+			mode,
+		})
+	}
+
+	/// Get the key in Camelot notation, such as `8A`
+	///
+	/// Returns `None` if the key is [`MusicalKey::OffKey`].
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::MusicalKey;
+	///
+	/// let key = MusicalKey::parse("Am").unwrap();
+	/// assert_eq!(key.camelot().as_deref(), Some("8A"));
+	///
+	/// let key = MusicalKey::parse("Gb").unwrap();
+	/// assert_eq!(key.camelot().as_deref(), Some("2B"));
+	/// ```
+	pub fn camelot(&self) -> Option<String> {
+		let Self::Key {
+			note,
+			accidental,
+			mode,
+		} = *self
+		else {
+			return None;
+		};
+
+		let mut pitch_class = note.pitch_class() + 12;
+		match accidental {
+			Some(Accidental::Sharp) => pitch_class += 1,
+			Some(Accidental::Flat) => pitch_class -= 1,
+			None => {},
+		}
+
+		// Use the relative major, which shares its position on the wheel
+		let (pitch_class, letter) = match mode {
+			KeyMode::Major => (pitch_class % 12, 'B'),
+			KeyMode::Minor => ((pitch_class + 3) % 12, 'A'),
+		};
+
+		// Every step on the wheel is a perfect fifth (7 semitones), with C major at 8
+		let number = (pitch_class * 7 + 7) % 12 + 1;
+
+		Some(format!("{number}{letter}"))
+	}
+}
+
+impl Display for MusicalKey {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let Self::Key {
+			note,
+			accidental,
+			mode,
+		} = self
+		else {
+			return f.write_str("o");
+		};
+
+		write!(f, "{}", note.as_char())?;
+
+		match accidental {
+			Some(Accidental::Sharp) => f.write_str("#")?,
+			Some(Accidental::Flat) => f.write_str("b")?,
+			None => {},
+		}
+
+		if *mode == KeyMode::Minor {
+			f.write_str("m")?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Accidental, KeyMode, KeyNote, MusicalKey};
+
+	#[test]
+	fn parse_standard() {
+		let a_flat_minor = MusicalKey::Key {
+			note: KeyNote::A,
+			accidental: Some(Accidental::Flat),
+			mode: KeyMode::Minor,
+		};
+
+		for input in ["Abm", "A♭m", "Ab minor", "abMIN", " Ab m "] {
+			assert_eq!(MusicalKey::parse(input), Some(a_flat_minor), "{input}");
+		}
+
+		let b_major = MusicalKey::Key {
+			note: KeyNote::B,
+			accidental: None,
+			mode: KeyMode::Major,
+		};
+
+		for input in ["B", "b", "BM", "B maj", "B major"] {
+			assert_eq!(MusicalKey::parse(input), Some(b_major), "{input}");
+		}
+
+		for input in ["", "H", "A#b", "Am7", "C minor key"] {
+			assert!(MusicalKey::parse(input).is_none(), "{input}");
+		}
+	}
+
+	#[test]
+	fn camelot_round_trip() {
+		for number in 1..=12 {
+			for letter in ['A', 'B'] {
+				let camelot = format!("{number}{letter}");
+				let key = MusicalKey::parse(&camelot).unwrap();
+				assert_eq!(key.camelot(), Some(camelot));
+			}
+		}
+
+		assert_eq!(MusicalKey::parse("01A"), MusicalKey::parse("Abm"));
+		assert_eq!(MusicalKey::parse("12a").unwrap().to_string(), "Dbm");
+		assert!(MusicalKey::parse("13A").is_none());
+		assert!(MusicalKey::parse("0B").is_none());
+		assert!(MusicalKey::OffKey.camelot().is_none());
+
+		// Enharmonic keys share a position
+		assert_eq!(
+			MusicalKey::parse("G#m").unwrap().camelot().as_deref(),
+			Some("1A")
+		);
+		assert_eq!(
+			MusicalKey::parse("Cb").unwrap().camelot().as_deref(),
+			Some("1B")
+		);
+		assert_eq!(
+			MusicalKey::parse("B#m").unwrap().camelot().as_deref(),
+			Some("5A")
+		);
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(MusicalKey::parse("F# minor").unwrap().to_string(), "F#m");
+		assert_eq!(MusicalKey::parse("E♭ major").unwrap().to_string(), "Eb");
+		assert_eq!(MusicalKey::parse("off").unwrap().to_string(), "o");
+	}
+}
@@ -0,0 +1,227 @@
+// Conversions between the rating representations of each format, see `Tag::set_rating` for the tables
+
+use crate::id3::v2::Popularimeter;
+use crate::tag::item::{ItemKey, ItemValue, TagItem};
+use crate::tag::TagType;
+
+// The `POPM` ratings for every half star, starting at 0 stars
+const POPM_HALF_STARS: [u8; 11] = [0, 13, 1, 54, 64, 118, 128, 186, 196, 242, 255];
+
+// The upper bounds of the Windows Media Player bands, for 1 through 5 stars
+const POPM_BANDS: [u8; 5] = [31, 95, 159, 223, 255];
+
+pub(crate) fn popm_to_rating(popm: u8) -> u8 {
+	if let Some(half_stars) = POPM_HALF_STARS.iter().position(|r| *r == popm) {
+		return half_stars as u8 * 10;
+	}
+
+	let stars = POPM_BANDS
+		.iter()
+		.position(|upper| popm <= *upper)
+		.unwrap_or(4)
+		+ 1;
+	stars as u8 * 20
+}
+
+pub(crate) fn rating_to_popm(rating: u8) -> u8 {
+	let half_stars = (rating.min(100) + 5) / 10;
+	POPM_HALF_STARS[half_stars as usize]
+}
+
+pub(crate) fn parse_text_rating(text: &str) -> Option<u8> {
+	let value = text.trim().parse::<f64>().ok()?;
+	if !(0.0..=100.0).contains(&value) {
+		return None;
+	}
+
+	// A star rating
+	if value <= 5.0 {
+		return Some((value * 20.0).round() as u8);
+	}
+
+	Some(value.round() as u8)
+}
+
+pub(crate) fn rating_to_text(rating: u8) -> String {
+	match rating.min(100) {
+		// These would be read back as a star rating
+		1..=5 => String::from("6"),
+		rating => rating.to_string(),
+	}
+}
+
+pub(crate) fn item_rating(value: &ItemValue) -> Option<u8> {
+	match value {
+		ItemValue::Text(text) => parse_text_rating(text),
+		ItemValue::Binary(popm) => Popularimeter::parse(&mut &popm[..])
+			.ok()
+			.map(|popm| popm_to_rating(popm.rating)),
+		ItemValue::Locator(_) => None,
+	}
+}
+
+// A `POPM` frame for a textual rating, with no email or play counter
+pub(crate) fn text_popularimeter(text: &str) -> Option<Popularimeter> {
+	parse_text_rating(text).map(|rating| Popularimeter {
+		email: String::new(),
+		rating: rating_to_popm(rating),
+		counter: 0,
+	})
+}
+
+// Converts any `ItemKey::Popularimeter` items to the representation used by `tag_type`
+//
+// ID3v2 stores the rating in a `POPM` frame, every other format stores it as text.
+pub(crate) fn convert_rating_items(items: &mut [TagItem], tag_type: TagType) {
+	for item in items
+		.iter_mut()
+		.filter(|item| item.item_key == ItemKey::Popularimeter)
+	{
+		let converted = match (&item.item_value, tag_type) {
+			(ItemValue::Text(text), TagType::Id3v2) => {
+				text_popularimeter(text).map(|popm| ItemValue::Binary(popm.as_bytes()))
+			},
+			(ItemValue::Binary(_), tag_type) if tag_type != TagType::Id3v2 => {
+				item_rating(&item.item_value).map(|rating| ItemValue::Text(rating_to_text(rating)))
+			},
+			_ => continue,
+		};
+
+		if let Some(converted) = converted {
+			item.item_value = converted;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::id3::v2::{Frame, FrameFlags, FrameId, FrameValue, Id3v2Tag};
+	use crate::mp4::{AtomData, AtomIdent, Ilst};
+	use crate::ogg::VorbisComments;
+	use crate::Tag;
+
+	use std::borrow::Cow;
+
+	#[test]
+	fn popm_bands() {
+		// Windows Media Player
+		for (popm, rating) in [(1, 20), (64, 40), (128, 60), (196, 80), (255, 100)] {
+			assert_eq!(popm_to_rating(popm), rating);
+			assert_eq!(rating_to_popm(rating), popm);
+		}
+
+		// MediaMonkey and MusicBee half stars
+		for (popm, rating) in [(13, 10), (54, 30), (118, 50), (186, 70), (242, 90)] {
+			assert_eq!(popm_to_rating(popm), rating);
+			assert_eq!(rating_to_popm(rating), popm);
+		}
+
+		// Everything else falls into the Windows Media Player bands
+		for (popm, rating) in [
+			(2, 20),
+			(31, 20),
+			(32, 40),
+			(95, 40),
+			(96, 60),
+			(159, 60),
+			(160, 80),
+			(223, 80),
+			(224, 100),
+			(254, 100),
+		] {
+			assert_eq!(popm_to_rating(popm), rating);
+		}
+
+		assert_eq!(popm_to_rating(0), 0);
+		assert_eq!(rating_to_popm(0), 0);
+		assert_eq!(rating_to_popm(84), 196);
+		assert_eq!(rating_to_popm(200), 255);
+	}
+
+	#[test]
+	fn text_ratings() {
+		// Stars
+		assert_eq!(parse_text_rating("0"), Some(0));
+		assert_eq!(parse_text_rating("4"), Some(80));
+		assert_eq!(parse_text_rating("4.5"), Some(90));
+		assert_eq!(parse_text_rating("5"), Some(100));
+
+		// Out of 100
+		assert_eq!(parse_text_rating("80"), Some(80));
+		assert_eq!(parse_text_rating(" 73 "), Some(73));
+		assert_eq!(parse_text_rating("100"), Some(100));
+
+		assert_eq!(parse_text_rating("101"), None);
+		assert_eq!(parse_text_rating("-1"), None);
+		assert_eq!(parse_text_rating("Great"), None);
+
+		for rating in 0..=100 {
+			let expected = if (1..=5).contains(&rating) { 6 } else { rating };
+			assert_eq!(parse_text_rating(&rating_to_text(rating)), Some(expected));
+		}
+	}
+
+	#[test]
+	fn rating_survives_conversions() {
+		let mut id3v2 = Id3v2Tag::default();
+		id3v2.insert(
+			Frame::new(
+				"POPM",
+				Popularimeter {
+					email: String::from("Windows Media Player 9 Series"),
+					rating: 196,
+					counter: 3,
+				},
+				FrameFlags::default(),
+			)
+			.unwrap(),
+		);
+
+		// MP3 -> FLAC
+		let tag = Tag::from(id3v2);
+		assert_eq!(tag.rating(), Some(80));
+
+		let vorbis_comments = VorbisComments::from(tag);
+		assert_eq!(vorbis_comments.get("RATING"), Some("80"));
+
+		// FLAC -> MP4
+		let tag = Tag::from(vorbis_comments);
+		assert_eq!(tag.rating(), Some(80));
+
+		let ilst = Ilst::from(tag);
+		let rate = ilst
+			.get(&AtomIdent::Fourcc(*b"rate"))
+			.and_then(|atom| atom.data().next());
+		assert_eq!(rate, Some(&AtomData::UTF8(String::from("80"))));
+
+		// MP4 -> MP3
+		let tag = Tag::from(ilst);
+		assert_eq!(tag.rating(), Some(80));
+
+		let id3v2 = Id3v2Tag::from(tag);
+		let Some(FrameValue::Popularimeter(popm)) = id3v2
+			.get(&FrameId::Valid(Cow::Borrowed("POPM")))
+			.map(Frame::content)
+		else {
+			panic!("Expected a POPM frame");
+		};
+		assert_eq!(popm.rating, 196);
+	}
+
+	#[test]
+	fn mediamonkey_vorbis_stars() {
+		let mut vorbis_comments = VorbisComments::default();
+		vorbis_comments.push(String::from("RATING"), String::from("4"));
+
+		let mut tag = Tag::from(vorbis_comments);
+		assert_eq!(tag.rating(), Some(80));
+
+		// The raw value is untouched
+		assert_eq!(tag.get_string(&ItemKey::Popularimeter), Some("4"));
+
+		tag.re_map(TagType::Id3v2);
+		assert_eq!(tag.rating(), Some(80));
+		assert!(tag.get_binary(&ItemKey::Popularimeter, false).is_some());
+	}
+}
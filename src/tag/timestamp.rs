@@ -0,0 +1,310 @@
+use crate::error::{ErrorKind, LoftyError, Result};
+use crate::macros::err;
+use crate::probe::ParsingMode;
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// The components following the year, with the separator preceding each one and the message used
+// when it is invalid
+const COMPONENTS: [(u8, &str); 5] = [
+	(b'-', "Invalid month"),
+	(b'-', "Invalid day"),
+	(b'T', "Invalid hour"),
+	(b':', "Invalid minute"),
+	(b':', "Invalid second"),
+];
+
+/// A timestamp, as used by the ID3v2.4 `TDRC`, `TDRL`, and `TDOR` frames
+///
+/// Timestamps are a subset of ISO 8601, allowing for any amount of precision following the year:
+///
+/// * `yyyy`
+/// * `yyyy-MM`
+/// * `yyyy-MM-dd`
+/// * `yyyy-MM-ddTHH`
+/// * `yyyy-MM-ddTHH:mm`
+/// * `yyyy-MM-ddTHH:mm:ss`
+///
+/// When converted to a string, everything following the first `None` component is left out.
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::Timestamp;
+///
+/// # fn main() -> lofty::Result<()> {
+/// let timestamp: Timestamp = "2023-10-02T18:30".parse()?;
+/// assert_eq!(timestamp.year, 2023);
+/// assert_eq!(timestamp.month, Some(10));
+/// assert_eq!(timestamp.second, None);
+///
+/// let timestamp = Timestamp {
+/// 	year: 2023,
+/// 	month: Some(10),
+/// 	day: None,
+/// 	hour: Some(18),
+/// 	..Timestamp::default()
+/// };
+/// assert_eq!(timestamp.to_string(), "2023-10");
+/// # Ok(()) }
+/// ```
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[allow(missing_docs)]
+pub struct Timestamp {
+	pub year: u16,
+	pub month: Option<u8>,
+	pub day: Option<u8>,
+	pub hour: Option<u8>,
+	pub minute: Option<u8>,
+	pub second: Option<u8>,
+}
+
+impl Timestamp {
+	/// Parse a timestamp
+	///
+	/// With [`ParsingMode::Strict`], any out of range or malformed component is an error. Otherwise,
+	/// it is dropped along with everything following it, and a space is also accepted in place of the `T`.
+	///
+	/// # Errors
+	///
+	/// * The input doesn't start with a four digit year
+	/// * [`ParsingMode::Strict`] is used, and a component is invalid or followed by anything else
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ParsingMode, Timestamp};
+	///
+	/// # fn main() -> lofty::Result<()> {
+	/// // There is no 13th month
+	/// assert!(Timestamp::parse("2023-13-02", ParsingMode::Strict).is_err());
+	///
+	/// let timestamp = Timestamp::parse("2023-13-02", ParsingMode::BestAttempt)?;
+	/// assert_eq!(timestamp.to_string(), "2023");
+	/// # Ok(()) }
+	/// ```
+	pub fn parse(input: &str, parse_mode: ParsingMode) -> Result<Self> {
+		let mut remaining = input.trim().as_bytes();
+
+		let Some(year) = digits(&mut remaining, 4) else {
+			err!(BadTimestamp("Expected a four digit year"));
+		};
+
+		let mut timestamp = Self {
+			year,
+			..Self::default()
+		};
+
+		for (idx, (separator, message)) in COMPONENTS.into_iter().enumerate() {
+			let Some((&first, rest)) = remaining.split_first() else {
+				break;
+			};
+
+			let separated = first == separator
+				|| (first == b' ' && separator == b'T' && parse_mode != ParsingMode::Strict);
+
+			let value = if separated {
+				remaining = rest;
+				digits(&mut remaining, 2).and_then(|value| u8::try_from(value).ok())
+			} else {
+				None
+			};
+
+			let range = match idx {
+				0 => 1..=12,
+				1 => 1..=timestamp.days_in_month(),
+				2 => 0..=23,
+				_ => 0..=59,
+			};
+
+			match value {
+				Some(value) if range.contains(&value) => {
+					*timestamp.component_mut(idx) = Some(value)
+				},
+				_ => {
+					if parse_mode == ParsingMode::Strict {
+						return Err(LoftyError::new(ErrorKind::BadTimestamp(message)));
+					}
+
+					return Ok(timestamp);
+				},
+			}
+		}
+
+		if parse_mode == ParsingMode::Strict && !remaining.is_empty() {
+			err!(BadTimestamp("Unexpected data after the timestamp"));
+		}
+
+		Ok(timestamp)
+	}
+
+	fn component_mut(&mut self, idx: usize) -> &mut Option<u8> {
+		match idx {
+			0 => &mut self.month,
+			1 => &mut self.day,
+			2 => &mut self.hour,
+			3 => &mut self.minute,
+			_ => &mut self.second,
+		}
+	}
+
+	fn days_in_month(&self) -> u8 {
+		match self.month {
+			Some(4 | 6 | 9 | 11) => 30,
+			Some(2) if self.year % 4 == 0 && (self.year % 100 != 0 || self.year % 400 == 0) => 29,
+			Some(2) => 28,
+			_ => 31,
+		}
+	}
+}
+
+// Consumes exactly `count` ASCII digits
+fn digits(remaining: &mut &[u8], count: usize) -> Option<u16> {
+	let field = remaining.get(..count)?;
+	if !field.iter().all(u8::is_ascii_digit) {
+		return None;
+	}
+
+	*remaining = &remaining[count..];
+	Some(
+		field
+			.iter()
+			.fold(0, |value, digit| value * 10 + u16::from(digit - b'0')),
+	)
+}
+
+impl FromStr for Timestamp {
+	type Err = LoftyError;
+
+	/// Parse a timestamp with [`ParsingMode::Strict`], see [`Timestamp::parse`]
+	fn from_str(s: &str) -> Result<Self> {
+		Self::parse(s, ParsingMode::Strict)
+	}
+}
+
+impl Display for Timestamp {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:04}", self.year)?;
+
+		let components = [self.month, self.day, self.hour, self.minute, self.second];
+		for ((separator, _), component) in COMPONENTS.into_iter().zip(components) {
+			let Some(component) = component else {
+				break;
+			};
+
+			write!(f, "{}{component:02}", char::from(separator))?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Timestamp;
+	use crate::probe::ParsingMode;
+
+	fn timestamp(components: [Option<u8>; 5]) -> Timestamp {
+		let [month, day, hour, minute, second] = components;
+		Timestamp {
+			year: 2023,
+			month,
+			day,
+			hour,
+			minute,
+			second,
+		}
+	}
+
+	#[test]
+	fn round_trip() {
+		for (input, expected) in [
+			("2023", timestamp([None; 5])),
+			("2023-01", timestamp([Some(1), None, None, None, None])),
+			(
+				"2023-01-31",
+				timestamp([Some(1), Some(31), None, None, None]),
+			),
+			(
+				"2023-01-31T00",
+				timestamp([Some(1), Some(31), Some(0), None, None]),
+			),
+			(
+				"2023-01-31T00:59",
+				timestamp([Some(1), Some(31), Some(0), Some(59), None]),
+			),
+			(
+				"2023-01-31T00:59:30",
+				timestamp([Some(1), Some(31), Some(0), Some(59), Some(30)]),
+			),
+		] {
+			let parsed = input.parse::<Timestamp>().unwrap();
+			assert_eq!(parsed, expected, "{input}");
+			assert_eq!(parsed.to_string(), input);
+		}
+	}
+
+	#[test]
+	fn display_truncates_after_none() {
+		assert_eq!(
+			timestamp([Some(1), None, Some(10), Some(20), None]).to_string(),
+			"2023-01"
+		);
+		assert_eq!(
+			Timestamp {
+				year: 800,
+				..Timestamp::default()
+			}
+			.to_string(),
+			"0800"
+		);
+	}
+
+	#[test]
+	fn strict() {
+		for input in [
+			"",
+			"23",
+			"2023-1",
+			"2023-00",
+			"2023-13",
+			"2023-02-29",
+			"2023-04-31",
+			"2023-01-01T24",
+			"2023-01-01T10:60",
+			"2023-01-01T10:30:60",
+			"2023-01-01 10:30",
+			"2023-01-01T10:30:00Z",
+			"2023/01/01",
+		] {
+			assert!(
+				Timestamp::parse(input, ParsingMode::Strict).is_err(),
+				"{input}"
+			);
+		}
+
+		assert!(Timestamp::parse("2024-02-29", ParsingMode::Strict).is_ok());
+		assert!(Timestamp::parse("2000-02-29", ParsingMode::Strict).is_ok());
+		assert!(Timestamp::parse("1900-02-29", ParsingMode::Strict).is_err());
+	}
+
+	#[test]
+	fn best_attempt() {
+		for (input, expected) in [
+			(" 2023\n", "2023"),
+			("2023-13-01", "2023"),
+			("2023-1-2", "2023"),
+			("2023-04-31T10:30", "2023-04"),
+			("2023-01-01T24:00", "2023-01-01"),
+			("2023-01-01 10:30", "2023-01-01T10:30"),
+			("2023-01-01T10:30:00Z", "2023-01-01T10:30:00"),
+			("2023-01-01T10:30:00.001Z", "2023-01-01T10:30:00"),
+		] {
+			let parsed = Timestamp::parse(input, ParsingMode::BestAttempt).unwrap();
+			assert_eq!(parsed.to_string(), expected, "{input}");
+		}
+
+		assert!(Timestamp::parse("Summer '23", ParsingMode::BestAttempt).is_err());
+	}
+}
@@ -1,8 +1,18 @@
 use crate::error::Result;
 use crate::file::FileType;
+#[cfg(feature = "fs")]
+use crate::id3::{
+	find_id3v1, find_id3v2, find_id3v2_footer, find_lyrics3v2, FindId3v2Config, ID3FindResults,
+};
+#[cfg(feature = "fs")]
+use crate::macros::decode_err;
 use crate::macros::err;
+#[cfg(feature = "fs")]
+use crate::probe::{ParseOptions, ParsingMode};
 use crate::tag::{Tag, TagType};
-use crate::{aac, ape, flac, iff, mpeg, musepack, wavpack};
+use crate::util::io::FileLike;
+use crate::write_options::WriteOptions;
+use crate::{ape, flac, iff};
 
 use crate::id3::v1::tag::Id3v1TagRef;
 use crate::id3::v2::tag::Id3v2TagRef;
@@ -13,45 +23,211 @@ use ape::tag::ApeTagRef;
 use iff::aiff::tag::AiffTextChunksRef;
 use iff::wav::tag::RIFFInfoListRef;
 
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::Write;
+#[cfg(feature = "fs")]
+use std::io::{Read, Seek, SeekFrom};
+#[cfg(feature = "fs")]
+use std::ops::Range;
 
 #[allow(unreachable_patterns)]
-pub(crate) fn write_tag(tag: &Tag, file: &mut File, file_type: FileType) -> Result<()> {
+pub(crate) fn write_tag<F>(
+	tag: &Tag,
+	file: &mut F,
+	file_type: FileType,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+{
 	match file_type {
-		FileType::Aac => aac::write::write_to(file, tag),
-		FileType::Aiff => iff::aiff::write::write_to(file, tag),
-		FileType::Ape => ape::write::write_to(file, tag),
-		FileType::Flac => flac::write::write_to(file, tag),
+		FileType::Aac
+		| FileType::Aiff
+		| FileType::Ape
+		| FileType::Mpc
+		| FileType::Mpeg
+		| FileType::Wav
+		| FileType::WavPack => write_tag_generic(tag, file, file_type, write_options),
+		FileType::Flac => flac::write::write_to(file, tag, write_options),
 		FileType::Opus | FileType::Speex | FileType::Vorbis => {
-			crate::ogg::write::write_to(file, tag, file_type)
+			crate::ogg::write::write_to(file, tag, file_type, write_options)
 		},
-		FileType::Mpc => musepack::write::write_to(file, tag),
-		FileType::Mpeg => mpeg::write::write_to(file, tag),
-		FileType::Mp4 => {
-			crate::mp4::ilst::write::write_to(file, &mut Into::<Ilst>::into(tag.clone()).as_ref())
+		FileType::Mp4 => crate::mp4::ilst::write::write_to(
+			file,
+			&mut Into::<Ilst>::into(tag.clone()).as_ref(),
+			write_options,
+		),
+		_ => err!(UnsupportedTag),
+	}
+}
+
+// For formats that store their tags as-is, with no format-specific handling needed. Each tag's writer
+// verifies that the format actually supports it.
+fn write_tag_generic<F>(
+	tag: &Tag,
+	file: &mut F,
+	file_type: FileType,
+	write_options: WriteOptions,
+) -> Result<()>
+where
+	F: FileLike,
+{
+	match tag.tag_type() {
+		TagType::Ape => ApeTagRef {
+			read_only: false,
+			items: ape::tag::tagitems_into_ape(tag),
+		}
+		.write_to(file, write_options),
+		TagType::Id3v1 => Into::<Id3v1TagRef<'_>>::into(tag).write_to(file, write_options),
+		// APE files can't hold an ID3v2 tag, it can only be removed
+		TagType::Id3v2 if file_type == FileType::Ape => {
+			Id3v2TagRef::empty().write_to(file, write_options)
+		},
+		TagType::Id3v2 => Id3v2TagRef {
+			flags: Id3v2TagFlags::default(),
+			frames: v2::tag::tag_frames(tag),
+		}
+		.write_to(file, write_options),
+		TagType::RiffInfo => RIFFInfoListRef::new(iff::wav::tag::tagitems_into_riff(tag.items()))
+			.write_to(file, write_options),
+		TagType::AiffText => {
+			use crate::tag::item::ItemKey;
+
+			AiffTextChunksRef {
+				name: tag.get_string(&ItemKey::TrackTitle),
+				author: tag.get_string(&ItemKey::TrackArtist),
+				copyright: tag.get_string(&ItemKey::CopyrightMessage),
+				annotations: Some(tag.get_strings(&ItemKey::Comment)),
+				comments: None,
+			}
+			.write_to(file, write_options)
 		},
-		FileType::Wav => iff::wav::write::write_to(file, tag),
-		FileType::WavPack => wavpack::write::write_to(file, tag),
 		_ => err!(UnsupportedTag),
 	}
 }
 
+#[cfg(feature = "fs")]
+/// Remove tags from the end of a file by truncating it, rather than rewriting the entire file
+///
+/// Trailing tags are laid out as: ID3v2, APE, Lyrics3v2, ID3v1, though an appended ID3v2 tag may
+/// also follow the APE tag. Anything that follows a removed tag and isn't being removed (e.g. an
+/// ID3v1 tag after an APE tag) is moved up before truncating. Appended ID3v2 tags are never removed.
+///
+/// Returns `false` if the tags can't be removed this way, and the file has to be rewritten instead.
+pub(crate) fn remove_trailing_tags(file: &mut File, ape: bool, id3v1: bool) -> Result<bool> {
+	file.rewind()?;
+
+	// Seeks past the ID3v2 tag, if it exists
+	find_id3v2(
+		file,
+		FindId3v2Config::NO_READ_TAG.allowed_padding(ParseOptions::DEFAULT_MAX_JUNK_BYTES as u64),
+	)?;
+	let leading_end = file.stream_position()?;
+
+	if ape && has_leading_ape_tag(file) {
+		// An APE tag at the start of the file is removed along with the trailing one when writing
+		return Ok(false);
+	}
+
+	let file_len = file.seek(SeekFrom::End(0))?;
+
+	// This will seek us to the start of the ID3v1 tag, or the end of the file
+	let ID3FindResults(id3v1_header, _) = find_id3v1(file, false)?;
+	let id3v1_start = file.stream_position()?;
+
+	let mut removed: Vec<Range<u64>> = Vec::with_capacity(2);
+
+	if ape {
+		// A Lyrics3v2 tag would sit between the APE and ID3v1 tags
+		if id3v1_start >= 15 {
+			find_lyrics3v2(file)?;
+		}
+
+		// Skip over an ID3v2 tag appended after the APE tag, it's kept where it is
+		find_id3v2_footer(file, leading_end, false)?;
+
+		let ape_end = file.stream_position()?;
+		if ape_end >= 32 {
+			file.seek(SeekFrom::Start(ape_end - 32))?;
+
+			if let Some((tag, header)) =
+				ape::tag::read::read_ape_tag(file, true, ParsingMode::BestAttempt)?
+			{
+				// Read only items are preserved when writing
+				if tag.into_iter().any(|item| item.read_only) {
+					return Ok(false);
+				}
+
+				let Some(ape_start) = ape_end.checked_sub(u64::from(header.size)) else {
+					decode_err!(@BAIL Ape, "File has a tag with an invalid size");
+				};
+
+				removed.push(ape_start..ape_end);
+			}
+		}
+	}
+
+	if id3v1 && id3v1_header.is_some() {
+		removed.push(id3v1_start..file_len);
+	}
+
+	let Some(truncate_at) = removed.first().map(|range| range.start) else {
+		// Nothing to remove
+		return Ok(true);
+	};
+
+	// Keep everything between and after the removed tags
+	let mut kept = Vec::new();
+	let mut pos = truncate_at;
+	for range in removed.iter().chain(std::iter::once(&(file_len..file_len))) {
+		if range.start > pos {
+			file.mark_moved(pos);
+
+			file.seek(SeekFrom::Start(pos))?;
+			Read::by_ref(file)
+				.take(range.start - pos)
+				.read_to_end(&mut kept)?;
+		}
+
+		pos = range.end;
+	}
+
+	file.seek(SeekFrom::Start(truncate_at))?;
+	file.write_all(&kept)?;
+	file.set_len(truncate_at + kept.len() as u64)?;
+
+	Ok(true)
+}
+
+// Expects the reader to be positioned after the leading ID3v2 tag, if any
+#[cfg(feature = "fs")]
+fn has_leading_ape_tag(file: &mut File) -> bool {
+	let mut preamble = [0; 8];
+	file.read_exact(&mut preamble).is_ok() && &preamble == ape::constants::APE_PREAMBLE
+}
+
 #[allow(unreachable_patterns)]
-pub(crate) fn dump_tag<W: Write>(tag: &Tag, writer: &mut W) -> Result<()> {
+pub(crate) fn dump_tag<W: Write>(
+	tag: &Tag,
+	writer: &mut W,
+	write_options: WriteOptions,
+) -> Result<()> {
 	match tag.tag_type() {
 		TagType::Ape => ApeTagRef {
 			read_only: false,
 			items: ape::tag::tagitems_into_ape(tag),
 		}
-		.dump_to(writer),
-		TagType::Id3v1 => Into::<Id3v1TagRef<'_>>::into(tag).dump_to(writer),
+		.dump_to(writer, write_options),
+		TagType::Id3v1 => Into::<Id3v1TagRef<'_>>::into(tag).dump_to(writer, write_options),
 		TagType::Id3v2 => Id3v2TagRef {
 			flags: Id3v2TagFlags::default(),
 			frames: v2::tag::tag_frames(tag),
 		}
-		.dump_to(writer),
-		TagType::Mp4Ilst => Into::<Ilst>::into(tag.clone()).as_ref().dump_to(writer),
+		.dump_to(writer, write_options),
+		TagType::Mp4Ilst => Into::<Ilst>::into(tag.clone())
+			.as_ref()
+			.dump_to(writer, write_options),
 		TagType::VorbisComments => {
 			let (vendor, items, pictures) = create_vorbis_comments_ref(tag);
 
@@ -59,13 +235,14 @@ pub(crate) fn dump_tag<W: Write>(tag: &Tag, writer: &mut W) -> Result<()> {
 				vendor,
 				items,
 				pictures,
+				unparsed_items: &[],
 			}
-			.dump_to(writer)
+			.dump_to(writer, write_options)
 		},
 		TagType::RiffInfo => RIFFInfoListRef {
 			items: iff::wav::tag::tagitems_into_riff(tag.items()),
 		}
-		.dump_to(writer),
+		.dump_to(writer, write_options),
 		TagType::AiffText => {
 			use crate::tag::item::ItemKey;
 
@@ -77,7 +254,7 @@ pub(crate) fn dump_tag<W: Write>(tag: &Tag, writer: &mut W) -> Result<()> {
 				comments: None,
 			}
 		}
-		.dump_to(writer),
+		.dump_to(writer, write_options),
 		_ => Ok(()),
 	}
 }
@@ -129,3 +306,32 @@ pub(crate) mod test_utils {
 		tag
 	}
 }
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+	use super::remove_trailing_tags;
+	use crate::tag::utils::test_utils::read_path;
+
+	use std::io::{Read, Seek, Write};
+
+	#[test]
+	fn remove_all_trailing_tags() {
+		let original = read_path("tests/files/assets/minimal/full_test.mp3");
+
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(&original).unwrap();
+
+		assert!(remove_trailing_tags(&mut file, true, true).unwrap());
+
+		// Both tags are gone with a single truncation, nothing needs to be moved
+		let ape_start = original
+			.windows(8)
+			.position(|window| window == b"APETAGEX")
+			.unwrap();
+
+		let mut contents = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut contents).unwrap();
+		assert_eq!(contents, original[..ape_start]);
+	}
+}
@@ -119,8 +119,11 @@ accessor_trait! {
 }
 
 use crate::tag::Tag;
+use crate::write_options::WriteOptions;
 
+#[cfg(feature = "fs")]
 use std::fs::File;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 /// A set of common methods between tags
@@ -187,6 +190,7 @@ pub trait TagExt: Accessor + Into<Tag> + Sized {
 	/// ```
 	fn is_empty(&self) -> bool;
 
+	#[cfg(feature = "fs")]
 	/// Save the tag to a path
 	///
 	/// # Errors
@@ -194,29 +198,44 @@ pub trait TagExt: Accessor + Into<Tag> + Sized {
 	/// * Path doesn't exist
 	/// * Path is not writable
 	/// * See [`TagExt::save_to`]
-	fn save_to_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err> {
+	fn save_to_path<P: AsRef<Path>>(
+		&self,
+		path: P,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err> {
 		self.save_to(
 			&mut std::fs::OpenOptions::new()
 				.read(true)
 				.write(true)
 				.open(path)?,
+			write_options,
 		)
 	}
 
+	#[cfg(feature = "fs")]
 	/// Save the tag to a [`File`]
 	///
 	/// # Errors
 	///
 	/// * The file format could not be determined
 	/// * Attempting to write a tag to a format that does not support it.
-	fn save_to(&self, file: &mut File) -> std::result::Result<(), Self::Err>;
+	fn save_to(
+		&self,
+		file: &mut File,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err>;
 
 	#[allow(clippy::missing_errors_doc)]
 	/// Dump the tag to a writer
 	///
 	/// This will only write the tag, it will not produce a usable file.
-	fn dump_to<W: std::io::Write>(&self, writer: &mut W) -> std::result::Result<(), Self::Err>;
+	fn dump_to<W: std::io::Write>(
+		&self,
+		writer: &mut W,
+		write_options: WriteOptions,
+	) -> std::result::Result<(), Self::Err>;
 
+	#[cfg(feature = "fs")]
 	/// Remove a tag from a [`Path`]
 	///
 	/// # Errors
@@ -224,6 +243,7 @@ pub trait TagExt: Accessor + Into<Tag> + Sized {
 	/// See [`TagExt::remove_from`]
 	fn remove_from_path<P: AsRef<Path>>(&self, path: P) -> std::result::Result<(), Self::Err>;
 
+	#[cfg(feature = "fs")]
 	/// Remove a tag from a [`File`]
 	///
 	/// # Errors
@@ -248,8 +268,10 @@ pub trait TagExt: Accessor + Into<Tag> + Sized {
 ///
 /// ```no_run
 /// use lofty::mpeg::MpegFile;
-/// use lofty::{AudioFile, ItemKey, MergeTag as _, SplitTag as _};
+/// use lofty::{AudioFile, ItemKey, MergeTag as _, SplitTag as _, WriteOptions};
 ///
+/// # #[cfg(feature = "fs")]
+/// # fn main() -> lofty::Result<()> {
 /// // Read the tag from a file
 /// # let mut file = std::fs::OpenOptions::new().write(true).open("/path/to/file.mp3")?;
 /// # let parse_options = lofty::ParseOptions::default();
@@ -272,9 +294,10 @@ pub trait TagExt: Accessor + Into<Tag> + Sized {
 ///
 /// // Write the changes back into the file
 /// mpeg_file.set_id3v2(id3v2);
-/// mpeg_file.save_to(&mut file)?;
-///
-/// # Ok::<(), lofty::LoftyError>(())
+/// mpeg_file.save_to(&mut file, WriteOptions::default())?;
+/// # Ok(()) }
+/// # #[cfg(not(feature = "fs"))]
+/// # fn main() {}
 /// ```
 pub trait SplitTag {
 	/// The remainder of the split operation that is not represented
@@ -12,6 +12,34 @@ pub(crate) unsafe fn update_allocation_limit(limit: usize) {
 	ALLOCATION_LIMIT.with(|l| *l.get() = limit);
 }
 
+/// Changes the allocation limit until dropped, restoring the previous one
+///
+/// The limit is per-thread, so this only affects the test that holds it.
+#[cfg(test)]
+pub(crate) struct AllocationLimitGuard(usize);
+
+#[cfg(test)]
+impl AllocationLimitGuard {
+	pub(crate) fn new(limit: usize) -> Self {
+		let previous = ALLOCATION_LIMIT.with(|l| unsafe { *l.get() });
+		unsafe { update_allocation_limit(limit) };
+		Self(previous)
+	}
+}
+
+#[cfg(test)]
+impl Drop for AllocationLimitGuard {
+	fn drop(&mut self) {
+		unsafe { update_allocation_limit(self.0) };
+	}
+}
+
+/// The allocation limit of the current thread, see [`ParseOptions::allocation_limit`]
+#[cfg(feature = "id3v2_compression_support")]
+pub(crate) fn allocation_limit() -> usize {
+	ALLOCATION_LIMIT.with(|l| unsafe { *l.get() })
+}
+
 /// Provides the `fallible_repeat` method on `Vec`
 ///
 /// It is intended to be used in [`try_vec!`](crate::macros::try_vec).
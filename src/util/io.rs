@@ -0,0 +1,711 @@
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+
+/// Anything the tag writers can operate on
+///
+/// This is a [`File`] when saving, and a [`WriteOverlay`] of one when planning a save, see
+/// [`TaggedFile::plan_save`](crate::TaggedFile::plan_save).
+pub(crate) trait FileLike: Read + Write + Seek {
+	/// Truncate or extend the underlying storage, see [`File::set_len`]
+	fn set_len(&mut self, size: u64) -> std::io::Result<()>;
+
+	/// The length of the underlying storage
+	fn len(&mut self) -> std::io::Result<u64>;
+
+	/// Record that everything from `pos` to the end of the file is about to be moved
+	///
+	/// Writers call this before replacing a region of the file with one of a different size. It
+	/// only matters when planning a save (see [`SavePlan::requires_rewrite`](crate::SavePlan::requires_rewrite)),
+	/// the file itself is unaffected.
+	fn mark_moved(&mut self, _pos: u64) {}
+}
+
+#[cfg(feature = "fs")]
+impl FileLike for File {
+	fn set_len(&mut self, size: u64) -> std::io::Result<()> {
+		File::set_len(self, size)
+	}
+
+	fn len(&mut self) -> std::io::Result<u64> {
+		Ok(self.metadata()?.len())
+	}
+}
+
+impl<F: FileLike + ?Sized> FileLike for &mut F {
+	fn set_len(&mut self, size: u64) -> std::io::Result<()> {
+		(**self).set_len(size)
+	}
+
+	fn len(&mut self) -> std::io::Result<u64> {
+		(**self).len()
+	}
+
+	fn mark_moved(&mut self, pos: u64) {
+		(**self).mark_moved(pos)
+	}
+}
+
+impl FileLike for Cursor<Vec<u8>> {
+	fn set_len(&mut self, size: u64) -> std::io::Result<()> {
+		self.get_mut().resize(size as usize, 0);
+		Ok(())
+	}
+
+	fn len(&mut self) -> std::io::Result<u64> {
+		Ok(self.get_ref().len() as u64)
+	}
+}
+
+/// A view of a file that starts `offset` bytes in, see [`ParseOptions::start_offset`](crate::ParseOptions::start_offset)
+///
+/// Everything before `offset` is hidden, positions and lengths are relative to it. An offset of 0
+/// passes everything through to the inner file.
+pub(crate) struct OffsetFile<F> {
+	inner: F,
+	offset: u64,
+}
+
+impl<F: Seek> OffsetFile<F> {
+	/// Wrap `inner`, moving it to `offset` if it's currently positioned before it
+	pub(crate) fn new(mut inner: F, offset: u64) -> std::io::Result<Self> {
+		if offset > 0 && inner.stream_position()? < offset {
+			inner.seek(SeekFrom::Start(offset))?;
+		}
+
+		Ok(Self { inner, offset })
+	}
+}
+
+impl<F: Read> Read for OffsetFile<F> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.inner.read(buf)
+	}
+}
+
+impl<F: Write> Write for OffsetFile<F> {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		self.inner.write(buf)
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		self.inner.flush()
+	}
+}
+
+impl<F: Seek> Seek for OffsetFile<F> {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		if self.offset == 0 {
+			return self.inner.seek(pos);
+		}
+
+		let new_pos = match pos {
+			SeekFrom::Start(pos) => Some(pos),
+			SeekFrom::End(offset) => {
+				let end = self.inner.seek(SeekFrom::End(0))?;
+				(end - end.min(self.offset)).checked_add_signed(offset)
+			},
+			SeekFrom::Current(offset) => {
+				let current = self.inner.stream_position()?;
+				(current - current.min(self.offset)).checked_add_signed(offset)
+			},
+		};
+
+		let Some(new_pos) = new_pos.and_then(|pos| pos.checked_add(self.offset)) else {
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"invalid seek to a position before the start offset",
+			));
+		};
+
+		Ok(self.inner.seek(SeekFrom::Start(new_pos))? - self.offset)
+	}
+}
+
+impl<F: FileLike> FileLike for OffsetFile<F> {
+	fn set_len(&mut self, size: u64) -> std::io::Result<()> {
+		self.inner.set_len(self.offset + size)
+	}
+
+	fn len(&mut self) -> std::io::Result<u64> {
+		Ok(self.inner.len()?.saturating_sub(self.offset))
+	}
+
+	fn mark_moved(&mut self, pos: u64) {
+		self.inner.mark_moved(self.offset + pos)
+	}
+}
+
+/// A reader over a file that is only partially available
+///
+/// Only the first `head.len()` and last `tail.len()` bytes of the file are available. Reads within
+/// the unavailable middle of the file will fail, and the first range that was requested will be recorded.
+///
+/// See [`read_from_head_and_tail`](crate::read_from_head_and_tail).
+pub(crate) struct HeadTailReader<'a> {
+	head: &'a [u8],
+	tail: &'a [u8],
+	len: u64,
+	pos: u64,
+	missing: Option<Range<u64>>,
+}
+
+impl<'a> HeadTailReader<'a> {
+	pub(crate) fn new(head: &'a [u8], tail: &'a [u8], len: u64) -> Self {
+		Self {
+			head,
+			tail,
+			len,
+			pos: 0,
+			missing: None,
+		}
+	}
+
+	/// The first range that was requested, but not available
+	pub(crate) fn missing_range(&self) -> Option<Range<u64>> {
+		self.missing.clone()
+	}
+
+	fn tail_start(&self) -> u64 {
+		self.len - self.tail.len() as u64
+	}
+}
+
+impl Read for HeadTailReader<'_> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		if buf.is_empty() || self.pos >= self.len {
+			return Ok(0);
+		}
+
+		let tail_start = self.tail_start();
+
+		let available = if self.pos < self.head.len() as u64 {
+			&self.head[self.pos as usize..]
+		} else if self.pos >= tail_start {
+			&self.tail[(self.pos - tail_start) as usize..]
+		} else {
+			let range = self.pos..tail_start.min(self.pos + buf.len() as u64);
+			log::debug!("Attempted to read unavailable range: {range:?}");
+
+			self.missing.get_or_insert(range);
+			return Err(std::io::Error::new(
+				std::io::ErrorKind::UnexpectedEof,
+				"attempted to read outside of the available ranges",
+			));
+		};
+
+		let read = available.len().min(buf.len());
+		buf[..read].copy_from_slice(&available[..read]);
+		self.pos += read as u64;
+
+		Ok(read)
+	}
+}
+
+impl Seek for HeadTailReader<'_> {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		let new_pos = match pos {
+			SeekFrom::Start(pos) => Some(pos),
+			SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+			SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+		};
+
+		match new_pos {
+			Some(new_pos) => {
+				self.pos = new_pos;
+				Ok(new_pos)
+			},
+			None => Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"invalid seek to a negative or overflowing position",
+			)),
+		}
+	}
+}
+
+/// A file that keeps writes in memory, leaving the inner file untouched
+///
+/// Reads see the file as if the writes were made to it. Only the written bytes are held in memory,
+/// everything else is read from the inner file as needed.
+///
+/// See [`TaggedFile::plan_save`](crate::TaggedFile::plan_save).
+pub(crate) struct WriteOverlay<R> {
+	inner: R,
+	// The length of the inner file prior to any writes
+	original_len: u64,
+	// How much of the inner file is still visible, anything past it was truncated
+	inner_len: u64,
+	len: u64,
+	pos: u64,
+	// The written data by start position. These never overlap or touch, they're merged instead.
+	writes: BTreeMap<u64, Vec<u8>>,
+	// The earliest position that the writers moved data from, see `FileLike::mark_moved`
+	moved_from: Option<u64>,
+}
+
+impl<R: Read + Seek> WriteOverlay<R> {
+	pub(crate) fn new(mut inner: R) -> std::io::Result<Self> {
+		let original_len = inner.seek(SeekFrom::End(0))?;
+		inner.rewind()?;
+
+		Ok(Self {
+			inner,
+			original_len,
+			inner_len: original_len,
+			len: original_len,
+			pos: 0,
+			writes: BTreeMap::new(),
+			moved_from: None,
+		})
+	}
+
+	/// The length of the inner file prior to any writes
+	pub(crate) fn original_len(&self) -> u64 {
+		self.original_len
+	}
+
+	/// The earliest position that data was moved from, if any was moved
+	///
+	/// A move only affects the data following it, so this is also a position in the file as it is now.
+	pub(crate) fn moved_from(&self) -> Option<u64> {
+		self.moved_from
+	}
+
+	/// The ranges that may differ from the inner file, sorted
+	///
+	/// This is every write, as well as anything that was truncated and then extended again.
+	pub(crate) fn changed_ranges(&self) -> Vec<Range<u64>> {
+		let mut ranges = self
+			.writes
+			.iter()
+			.map(|(start, data)| *start..*start + data.len() as u64)
+			.collect::<Vec<_>>();
+
+		// Extending a file fills it with zeroes
+		let zeroed = self.inner_len..self.original_len.min(self.len);
+		let mut gaps = Vec::new();
+		let mut pos = zeroed.start;
+		for range in &ranges {
+			if range.start >= zeroed.end {
+				break;
+			}
+
+			if range.start > pos {
+				gaps.push(pos..range.start);
+			}
+			pos = pos.max(range.end);
+		}
+		if pos < zeroed.end {
+			gaps.push(pos..zeroed.end);
+		}
+
+		ranges.extend(gaps);
+		ranges.sort_unstable_by_key(|range| range.start);
+		ranges
+	}
+
+	/// Read from the inner file, ignoring any writes
+	///
+	/// This reads until `buf` is full, or the end of the inner file is reached.
+	pub(crate) fn read_original_at(&mut self, pos: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+		self.inner.seek(SeekFrom::Start(pos))?;
+		read_up_to(&mut self.inner, buf)
+	}
+
+	/// Read with writes applied, returning the number of bytes read
+	///
+	/// This may read less than `buf.len()` bytes, even if the end of the file hasn't been reached.
+	pub(crate) fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> std::io::Result<usize> {
+		if buf.is_empty() || pos >= self.len {
+			return Ok(0);
+		}
+
+		let available = buf.len().min((self.len - pos) as usize);
+
+		if let Some((start, data)) = self.writes.range(..=pos).next_back() {
+			let offset = (pos - start) as usize;
+			if offset < data.len() {
+				let read = available.min(data.len() - offset);
+				buf[..read].copy_from_slice(&data[offset..offset + read]);
+				return Ok(read);
+			}
+		}
+
+		let next_write = self
+			.writes
+			.range(pos..)
+			.next()
+			.map_or(self.len, |(start, _)| *start);
+		let read = available.min((next_write - pos) as usize);
+
+		if pos >= self.inner_len {
+			buf[..read].fill(0);
+			return Ok(read);
+		}
+
+		let read = read.min((self.inner_len - pos) as usize);
+		self.inner.seek(SeekFrom::Start(pos))?;
+		self.inner.read_exact(&mut buf[..read])?;
+		Ok(read)
+	}
+
+	fn write_at(&mut self, pos: u64, buf: &[u8]) {
+		if buf.is_empty() {
+			return;
+		}
+
+		let end = pos + buf.len() as u64;
+
+		// Merge with every write this overlaps or touches
+		let previous = self
+			.writes
+			.range(..=pos)
+			.next_back()
+			.filter(|(start, data)| **start + data.len() as u64 >= pos)
+			.map(|(start, _)| *start);
+		let (start, mut data) = match previous {
+			Some(start) => (start, self.writes.remove(&start).unwrap_or_default()),
+			None => (pos, Vec::new()),
+		};
+
+		let offset = (pos - start) as usize;
+		if data.len() < offset + buf.len() {
+			data.resize(offset + buf.len(), 0);
+		}
+
+		let following = self
+			.writes
+			.range(pos..=end)
+			.map(|(start, _)| *start)
+			.collect::<Vec<_>>();
+		for next_start in following {
+			let next = self.writes.remove(&next_start).unwrap_or_default();
+			let next_offset = (next_start - start) as usize;
+			if next_offset + next.len() > data.len() {
+				data.extend_from_slice(&next[data.len() - next_offset..]);
+			}
+		}
+
+		data[offset..offset + buf.len()].copy_from_slice(buf);
+		self.writes.insert(start, data);
+
+		self.len = self.len.max(end);
+	}
+}
+
+impl<R: Read + Seek> Read for WriteOverlay<R> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		let read = self.read_at(self.pos, buf)?;
+		self.pos += read as u64;
+		Ok(read)
+	}
+}
+
+impl<R: Read + Seek> Write for WriteOverlay<R> {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		self.write_at(self.pos, buf);
+		self.pos += buf.len() as u64;
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		Ok(())
+	}
+}
+
+impl<R> Seek for WriteOverlay<R> {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		let new_pos = match pos {
+			SeekFrom::Start(pos) => Some(pos),
+			SeekFrom::End(offset) => self.len.checked_add_signed(offset),
+			SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+		};
+
+		match new_pos {
+			Some(new_pos) => {
+				self.pos = new_pos;
+				Ok(new_pos)
+			},
+			None => Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"invalid seek to a negative or overflowing position",
+			)),
+		}
+	}
+}
+
+impl<R: Read + Seek> FileLike for WriteOverlay<R> {
+	fn set_len(&mut self, size: u64) -> std::io::Result<()> {
+		if size < self.len {
+			self.inner_len = self.inner_len.min(size);
+
+			let truncated = self
+				.writes
+				.range(size..)
+				.map(|(start, _)| *start)
+				.collect::<Vec<_>>();
+			for start in truncated {
+				self.writes.remove(&start);
+			}
+
+			if let Some((start, data)) = self.writes.range_mut(..size).next_back() {
+				data.truncate(data.len().min((size - start) as usize));
+			}
+		}
+
+		self.len = size;
+		Ok(())
+	}
+
+	fn len(&mut self) -> std::io::Result<u64> {
+		Ok(self.len)
+	}
+
+	fn mark_moved(&mut self, pos: u64) {
+		// Nothing to move at the end of the file
+		if pos < self.len {
+			self.moved_from = Some(
+				self.moved_from
+					.map_or(pos, |moved_from| moved_from.min(pos)),
+			);
+		}
+	}
+}
+
+// Reads until `buf` is full or the reader is exhausted, returning the number of bytes read
+pub(crate) fn read_up_to<R>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<usize>
+where
+	R: Read,
+{
+	let mut read = 0;
+	while read < buf.len() {
+		match reader.read(&mut buf[read..]) {
+			Ok(0) => break,
+			Ok(n) => read += n,
+			Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {},
+			Err(e) => return Err(e),
+		}
+	}
+
+	Ok(read)
+}
+
+/// A reader with some of its bytes already in memory
+///
+/// Reads of the `prefix`, found at `prefix_start`, are served without touching the inner reader. The
+/// inner reader is only seeked once a read goes past them.
+///
+/// See [`Probe::read`](crate::Probe::read).
+pub(crate) struct PrefixedReader<'a, R> {
+	inner: &'a mut R,
+	prefix: &'a [u8],
+	prefix_start: u64,
+	pos: u64,
+	inner_pos: u64,
+}
+
+impl<'a, R> PrefixedReader<'a, R> {
+	/// NOTE: `inner` is expected to be positioned at `prefix_start`
+	pub(crate) fn new(inner: &'a mut R, prefix: &'a [u8], prefix_start: u64) -> Self {
+		Self {
+			inner,
+			prefix,
+			prefix_start,
+			pos: prefix_start,
+			inner_pos: prefix_start,
+		}
+	}
+}
+
+impl<R: Read + Seek> Read for PrefixedReader<'_, R> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		let prefix_end = self.prefix_start + self.prefix.len() as u64;
+		if (self.prefix_start..prefix_end).contains(&self.pos) {
+			let available = &self.prefix[(self.pos - self.prefix_start) as usize..];
+
+			let read = available.len().min(buf.len());
+			buf[..read].copy_from_slice(&available[..read]);
+			self.pos += read as u64;
+
+			return Ok(read);
+		}
+
+		if self.inner_pos != self.pos {
+			self.inner.seek(SeekFrom::Start(self.pos))?;
+		}
+
+		let read = self.inner.read(buf)?;
+		self.pos += read as u64;
+		self.inner_pos = self.pos;
+
+		Ok(read)
+	}
+}
+
+impl<R: Seek> Seek for PrefixedReader<'_, R> {
+	fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+		let new_pos = match pos {
+			SeekFrom::Start(pos) => Some(pos),
+			SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+			// The length is only known to the inner reader
+			SeekFrom::End(_) => {
+				self.pos = self.inner.seek(pos)?;
+				self.inner_pos = self.pos;
+				return Ok(self.pos);
+			},
+		};
+
+		match new_pos {
+			Some(new_pos) => {
+				self.pos = new_pos;
+				Ok(new_pos)
+			},
+			None => Err(std::io::Error::new(
+				std::io::ErrorKind::InvalidInput,
+				"invalid seek to a negative or overflowing position",
+			)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{read_up_to, FileLike, HeadTailReader, OffsetFile, PrefixedReader, WriteOverlay};
+	use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+	#[test]
+	fn offset_file() {
+		let mut file = Cursor::new(b"PREFIXcontent".to_vec());
+		let mut offset_file = OffsetFile::new(&mut file, 6).unwrap();
+		assert_eq!(offset_file.stream_position().unwrap(), 0);
+		assert_eq!(offset_file.len().unwrap(), 7);
+
+		let mut buf = [0; 7];
+		offset_file.read_exact(&mut buf).unwrap();
+		assert_eq!(&buf, b"content");
+
+		assert_eq!(offset_file.seek(SeekFrom::End(-4)).unwrap(), 3);
+		assert_eq!(offset_file.seek(SeekFrom::Current(-3)).unwrap(), 0);
+		assert!(offset_file.seek(SeekFrom::Current(-1)).is_err());
+
+		// The prefix is never touched
+		offset_file.set_len(3).unwrap();
+		offset_file.rewind().unwrap();
+		offset_file.write_all(b"new").unwrap();
+		assert_eq!(file.get_ref(), b"PREFIXnew");
+	}
+
+	#[test]
+	fn head_tail_reader() {
+		let head = [1, 2, 3, 4];
+		let tail = [7, 8];
+		let mut reader = HeadTailReader::new(&head, &tail, 10);
+
+		let mut buf = [0; 2];
+		reader.read_exact(&mut buf).unwrap();
+		assert_eq!(buf, [1, 2]);
+
+		// Reads stop at the end of the head
+		let mut buf = [0; 4];
+		assert_eq!(reader.read(&mut buf).unwrap(), 2);
+		assert_eq!(&buf[..2], &[3, 4]);
+
+		reader.seek(SeekFrom::End(-2)).unwrap();
+		let mut buf = [0; 2];
+		reader.read_exact(&mut buf).unwrap();
+		assert_eq!(buf, [7, 8]);
+		assert!(reader.missing_range().is_none());
+
+		// Seeking into the middle is fine, reading is not
+		reader.seek(SeekFrom::Start(5)).unwrap();
+		let mut buf = [0; 10];
+		assert!(reader.read(&mut buf).is_err());
+		assert_eq!(reader.missing_range(), Some(5..8));
+
+		// Only the first missing range is kept
+		reader.seek(SeekFrom::Start(4)).unwrap();
+		assert!(reader.read(&mut buf).is_err());
+		assert_eq!(reader.missing_range(), Some(5..8));
+	}
+
+	#[test]
+	fn prefixed_reader() {
+		let mut inner = Cursor::new(b"PREFIXcontent".to_vec());
+
+		let mut prefix = [0; 6];
+		assert_eq!(read_up_to(&mut inner, &mut prefix).unwrap(), 6);
+		inner.rewind().unwrap();
+
+		// The inner reader is left alone while reading the prefix
+		let mut reader = PrefixedReader::new(&mut inner, &prefix, 0);
+		let mut buf = [0; 4];
+		reader.read_exact(&mut buf).unwrap();
+		assert_eq!(&buf, b"PREF");
+		assert_eq!(reader.stream_position().unwrap(), 4);
+		assert_eq!(reader.inner.position(), 0);
+
+		// Reads stop at the end of the prefix
+		let mut buf = [0; 4];
+		assert_eq!(reader.read(&mut buf).unwrap(), 2);
+		assert_eq!(&buf[..2], b"IX");
+
+		let mut rest = Vec::new();
+		reader.read_to_end(&mut rest).unwrap();
+		assert_eq!(rest, b"content");
+
+		assert_eq!(reader.seek(SeekFrom::End(-4)).unwrap(), 9);
+		assert_eq!(reader.seek(SeekFrom::Current(-9)).unwrap(), 0);
+		assert!(reader.seek(SeekFrom::Current(-1)).is_err());
+
+		let mut all = Vec::new();
+		reader.read_to_end(&mut all).unwrap();
+		assert_eq!(all, b"PREFIXcontent");
+
+		// Short readers fill as much as they can
+		let mut buf = [0; 8];
+		assert_eq!(read_up_to(&mut &b"abc"[..], &mut buf).unwrap(), 3);
+	}
+
+	#[test]
+	fn write_overlay() {
+		let mut inner = Cursor::new(b"0123456789".to_vec());
+		let mut file = WriteOverlay::new(&mut inner).unwrap();
+
+		// Overlapping and adjacent writes are merged
+		file.seek(SeekFrom::Start(2)).unwrap();
+		file.write_all(b"ab").unwrap();
+		file.seek(SeekFrom::Start(6)).unwrap();
+		file.write_all(b"cd").unwrap();
+		file.seek(SeekFrom::Start(3)).unwrap();
+		file.write_all(b"xyz").unwrap();
+		assert_eq!(file.writes.len(), 1);
+		assert_eq!(file.changed_ranges(), [2..8]);
+
+		let mut content = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut content).unwrap();
+		assert_eq!(content, b"01axyzcd89");
+
+		// Truncating and extending again leaves zeroes
+		file.set_len(4).unwrap();
+		file.set_len(6).unwrap();
+		file.seek(SeekFrom::End(0)).unwrap();
+		file.write_all(b"!").unwrap();
+		assert_eq!(file.len().unwrap(), 7);
+		assert_eq!(file.changed_ranges(), [2..4, 4..6, 6..7]);
+
+		let mut content = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut content).unwrap();
+		assert_eq!(content, b"01ax\0\0!");
+
+		let mut original = [0; 10];
+		assert_eq!(file.read_original_at(0, &mut original).unwrap(), 10);
+		assert_eq!(&original, b"0123456789");
+
+		// The inner file is never touched
+		assert_eq!(inner.get_ref(), b"0123456789");
+	}
+}
@@ -1,2 +1,3 @@
 pub(crate) mod alloc;
+pub(crate) mod io;
 pub(crate) mod text;
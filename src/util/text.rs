@@ -99,25 +99,9 @@ where
 	let read_string = match encoding {
 		TextEncoding::Latin1 => latin1_decode(&raw_bytes),
 		TextEncoding::UTF16 => {
-			if raw_bytes.len() < 2 {
-				err!(TextDecode("UTF-16 string has an invalid length (< 2)"));
-			}
-
-			if raw_bytes.len() % 2 != 0 {
-				err!(TextDecode("UTF-16 string has an odd length"));
-			}
-
-			match (raw_bytes[0], raw_bytes[1]) {
-				(0xFE, 0xFF) => {
-					bom = [0xFE, 0xFF];
-					utf16_decode_bytes(&raw_bytes[2..], u16::from_be_bytes)?
-				},
-				(0xFF, 0xFE) => {
-					bom = [0xFF, 0xFE];
-					utf16_decode_bytes(&raw_bytes[2..], u16::from_le_bytes)?
-				},
-				_ => err!(TextDecode("UTF-16 string has an invalid byte order mark")),
-			}
+			let (text, text_bom) = utf16_decode_with_bom(&raw_bytes)?;
+			bom = text_bom;
+			text
 		},
 		TextEncoding::UTF16BE => utf16_decode_bytes(raw_bytes.as_slice(), u16::from_be_bytes)?,
 		TextEncoding::UTF8 => utf8_decode(raw_bytes)
@@ -135,6 +119,47 @@ where
 	})
 }
 
+/// Decode an unterminated string that is already in memory
+///
+/// This is equivalent to calling [`decode_text`] on a reader over `bytes`, without copying
+/// the bytes out of it first.
+pub(crate) fn decode_text_slice(bytes: &[u8], encoding: TextEncoding) -> Result<String> {
+	if bytes.is_empty() {
+		return Ok(String::new());
+	}
+
+	match encoding {
+		TextEncoding::Latin1 => Ok(latin1_decode(bytes)),
+		TextEncoding::UTF16 => utf16_decode_with_bom(bytes).map(|(text, _)| text),
+		TextEncoding::UTF16BE => utf16_decode_bytes(bytes, u16::from_be_bytes),
+		TextEncoding::UTF8 => utf8_decode_str(bytes)
+			.map(str::to_owned)
+			.map_err(|_| LoftyError::new(ErrorKind::TextDecode("Expected a UTF-8 string"))),
+	}
+}
+
+fn utf16_decode_with_bom(bytes: &[u8]) -> Result<(String, [u8; 2])> {
+	if bytes.len() < 2 {
+		err!(TextDecode("UTF-16 string has an invalid length (< 2)"));
+	}
+
+	if bytes.len() % 2 != 0 {
+		err!(TextDecode("UTF-16 string has an odd length"));
+	}
+
+	match (bytes[0], bytes[1]) {
+		(0xFE, 0xFF) => Ok((
+			utf16_decode_bytes(&bytes[2..], u16::from_be_bytes)?,
+			[0xFE, 0xFF],
+		)),
+		(0xFF, 0xFE) => Ok((
+			utf16_decode_bytes(&bytes[2..], u16::from_le_bytes)?,
+			[0xFF, 0xFE],
+		)),
+		_ => err!(TextDecode("UTF-16 string has an invalid byte order mark")),
+	}
+}
+
 pub(crate) fn read_to_terminator<R>(reader: &mut R, encoding: TextEncoding) -> Option<Vec<u8>>
 where
 	R: Read,
@@ -205,7 +230,7 @@ pub(crate) fn utf16_decode_bytes(bytes: &[u8], endianness: fn([u8; 2]) -> u16) -
 		return Ok(String::new());
 	}
 
-	let unverified: Vec<u16> = bytes
+	let units = bytes
 		.chunks_exact(2)
 		// In ID3v2, it is possible to have multiple UTF-16 strings separated by null.
 		// This also makes it possible for us to encounter multiple BOMs in a single string.
@@ -213,10 +238,15 @@ pub(crate) fn utf16_decode_bytes(bytes: &[u8], endianness: fn([u8; 2]) -> u16) -
 		.filter_map(|c| match c {
 			[0xFF, 0xFE] | [0xFE, 0xFF] => None,
 			_ => Some(endianness(c.try_into().unwrap())), // Infallible
-		})
-		.collect();
+		});
 
-	utf16_decode(&unverified)
+	// Decode directly into the `String`, rather than collecting the units first
+	let mut text = char::decode_utf16(units)
+		.collect::<std::result::Result<String, _>>()
+		.map_err(|_| LoftyError::new(ErrorKind::TextDecode("Given an invalid UTF-16 string")))?;
+
+	trim_end_nulls(&mut text);
+	Ok(text)
 }
 
 pub(crate) fn encode_text(text: &str, text_encoding: TextEncoding, terminated: bool) -> Vec<u8> {
@@ -244,6 +274,68 @@ pub(crate) fn encode_text(text: &str, text_encoding: TextEncoding, terminated: b
 	}
 }
 
+/// Truncate `text` to fit within `max_bytes` when encoded with `encoding`
+///
+/// This is for fixed size fields, and will never cut a character in half, or separate a character
+/// from the marks combining with it (see `extends_grapheme`). For UTF-16, the BOM isn't included.
+pub(crate) fn truncate_to_fit(text: &str, max_bytes: usize, encoding: TextEncoding) -> &str {
+	let mut size = 0;
+	let mut end = 0;
+	let mut joined = false;
+
+	for (idx, c) in text.char_indices() {
+		if !joined && !extends_grapheme(c) {
+			end = idx;
+		}
+		joined = c == ZERO_WIDTH_JOINER;
+
+		size += match encoding {
+			TextEncoding::Latin1 => 1,
+			TextEncoding::UTF8 => c.len_utf8(),
+			TextEncoding::UTF16 | TextEncoding::UTF16BE => c.len_utf16() * 2,
+		};
+
+		if size > max_bytes {
+			return &text[..end];
+		}
+	}
+
+	text
+}
+
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+// Whether `c` belongs to the same grapheme cluster as the character before it
+//
+// This isn't a complete implementation of grapheme cluster boundaries, only the characters commonly
+// found in tags: combining marks, variation selectors, emoji modifiers and tag sequences, and joiners.
+// A character following a zero width joiner is also part of the cluster, see `truncate_to_fit`.
+fn extends_grapheme(c: char) -> bool {
+	matches!(
+		c,
+		'\u{0300}'..='\u{036F}'
+			| '\u{0483}'..='\u{0489}'
+			| '\u{0591}'..='\u{05BD}'
+			| '\u{0610}'..='\u{061A}'
+			| '\u{064B}'..='\u{065F}'
+			| '\u{0900}'..='\u{0903}'
+			| '\u{093A}'..='\u{094F}'
+			| '\u{0E31}'
+			| '\u{0E34}'..='\u{0E3A}'
+			| '\u{0E47}'..='\u{0E4E}'
+			| '\u{1AB0}'..='\u{1AFF}'
+			| '\u{1DC0}'..='\u{1DFF}'
+			| '\u{200C}'..='\u{200D}'
+			| '\u{20D0}'..='\u{20FF}'
+			| '\u{3099}'..='\u{309A}'
+			| '\u{FE00}'..='\u{FE0F}'
+			| '\u{FE20}'..='\u{FE2F}'
+			| '\u{1F3FB}'..='\u{1F3FF}'
+			| '\u{E0020}'..='\u{E007F}'
+			| '\u{E0100}'..='\u{E01EF}'
+	)
+}
+
 pub(crate) fn trim_end_nulls(text: &mut String) {
 	if text.ends_with('\0') {
 		let new_len = text.trim_end_matches('\0').len();
@@ -255,6 +347,28 @@ pub(crate) fn trim_end_nulls_str(text: &str) -> &str {
 	text.trim_end_matches('\0')
 }
 
+/// Interpret the text of a flag item, such as `ItemKey::FlagCompilation`
+///
+/// Formats store flags as `1`/`0`, but a generic `Tag` may hold anything a user set.
+pub(crate) fn parse_flag(text: &str) -> Option<bool> {
+	let text = text.trim();
+	if ["1", "true", "yes"]
+		.iter()
+		.any(|flag| text.eq_ignore_ascii_case(flag))
+	{
+		return Some(true);
+	}
+
+	if ["0", "false", "no"]
+		.iter()
+		.any(|flag| text.eq_ignore_ascii_case(flag))
+	{
+		return Some(false);
+	}
+
+	None
+}
+
 fn utf16_encode(
 	text: &str,
 	endianness: fn(u16) -> [u8; 2],
@@ -326,6 +440,31 @@ mod tests {
 		assert_eq!(utf8_decode.content, TEST_STRING.to_string());
 	}
 
+	#[test]
+	fn text_decode_slice() {
+		let utf16 = [
+			0xFF, 0xFE, 0x6C, 0x00, 0xF8, 0x00, 0x66, 0x00, 0x74, 0x00, 0xA5, 0x00, 0x00, 0x00,
+		];
+		let latin1 = [0x6C, 0xF8, 0x66, 0x74, 0xA5, 0x00];
+
+		for (bytes, encoding) in [
+			(&utf16[..], TextEncoding::UTF16),
+			(&utf16[2..], TextEncoding::UTF16BE),
+			(&latin1[..], TextEncoding::Latin1),
+			(TEST_STRING.as_bytes(), TextEncoding::UTF8),
+			(&[], TextEncoding::UTF16),
+		] {
+			let from_reader = super::decode_text(&mut &*bytes, encoding, false);
+			let from_slice = super::decode_text_slice(bytes, encoding);
+
+			assert_eq!(
+				from_reader.map(|res| res.content).ok(),
+				from_slice.ok(),
+				"{encoding:?}"
+			);
+		}
+	}
+
 	#[test]
 	fn text_encode() {
 		// No BOM
@@ -360,4 +499,70 @@ mod tests {
 
 		assert_eq!(utf8_encode.as_slice(), TEST_STRING.as_bytes());
 	}
+
+	#[test]
+	fn truncate_to_fit() {
+		use super::truncate_to_fit;
+
+		// "løft¥" is 5 characters, 7 bytes of UTF-8, and 10 bytes of UTF-16
+		assert_eq!(
+			truncate_to_fit(TEST_STRING, 5, TextEncoding::Latin1),
+			TEST_STRING
+		);
+		assert_eq!(
+			truncate_to_fit(TEST_STRING, 4, TextEncoding::Latin1),
+			"l\u{00f8}ft"
+		);
+		assert_eq!(
+			truncate_to_fit(TEST_STRING, 7, TextEncoding::UTF8),
+			TEST_STRING
+		);
+		assert_eq!(
+			truncate_to_fit(TEST_STRING, 6, TextEncoding::UTF8),
+			"l\u{00f8}ft"
+		);
+		assert_eq!(truncate_to_fit(TEST_STRING, 2, TextEncoding::UTF8), "l");
+		assert_eq!(
+			truncate_to_fit(TEST_STRING, 9, TextEncoding::UTF16),
+			"l\u{00f8}ft"
+		);
+		assert_eq!(truncate_to_fit(TEST_STRING, 0, TextEncoding::UTF8), "");
+
+		// A surrogate pair can't be split
+		assert_eq!(truncate_to_fit("a\u{1F3B5}", 4, TextEncoding::UTF16BE), "a");
+		assert_eq!(
+			truncate_to_fit("a\u{1F3B5}", 6, TextEncoding::UTF16BE),
+			"a\u{1F3B5}"
+		);
+
+		// "e" followed by a combining acute accent
+		assert_eq!(
+			truncate_to_fit("cafe\u{0301}", 5, TextEncoding::UTF8),
+			"caf"
+		);
+		assert_eq!(
+			truncate_to_fit("cafe\u{0301}", 6, TextEncoding::UTF8),
+			"cafe\u{0301}"
+		);
+
+		// An emoji ZWJ sequence (woman, ZWJ, microphone) is kept whole
+		let singer = "\u{1F469}\u{200D}\u{1F3A4}";
+		assert_eq!(truncate_to_fit(singer, 10, TextEncoding::UTF8), "");
+		assert_eq!(truncate_to_fit(singer, 11, TextEncoding::UTF8), singer);
+	}
+
+	#[test]
+	fn parse_flag() {
+		for flag in ["1", "true", " Yes "] {
+			assert_eq!(super::parse_flag(flag), Some(true), "{flag}");
+		}
+
+		for flag in ["0", "FALSE", "no"] {
+			assert_eq!(super::parse_flag(flag), Some(false), "{flag}");
+		}
+
+		for flag in ["", "2", "compilation"] {
+			assert_eq!(super::parse_flag(flag), None, "{flag}");
+		}
+	}
 }
@@ -0,0 +1,130 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+use crate::macros::try_vec;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{ByteOrder, LittleEndian};
+
+const PREAMBLE: &[u8; 8] = b"APETAGEX";
+const FOOTER_SIZE: u64 = 32;
+const ID3V1_SIZE: u64 = 128;
+const CONTAINS_HEADER: u32 = 1 << 31;
+
+// Verifies the size and item count of an APE tag at the end of the file, which may be followed by an ID3v1 tag
+pub(super) fn verify<R>(reader: &mut R, file_len: u64, issues: &mut Vec<Issue>) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let Some(footer_start) = find_footer(reader, file_len)? else {
+		return Ok(());
+	};
+
+	let mut footer = [0; FOOTER_SIZE as usize];
+	reader.seek(SeekFrom::Start(footer_start))?;
+	reader.read_exact(&mut footer)?;
+
+	// The size includes the items and the footer, but not the header
+	let size = u64::from(LittleEndian::read_u32(&footer[12..16]));
+	let item_count = LittleEndian::read_u32(&footer[16..20]);
+	let flags = LittleEndian::read_u32(&footer[20..24]);
+
+	let footer_end = footer_start + FOOTER_SIZE;
+	if size < FOOTER_SIZE || size > footer_end {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: size,
+				actual: footer_end,
+			},
+			footer_start + 12,
+			"APE tag size",
+		));
+		return Ok(());
+	}
+
+	let items_start = footer_end - size;
+	if flags & CONTAINS_HEADER != 0 {
+		let mut preamble = [0; 8];
+		let header_present = items_start >= FOOTER_SIZE && {
+			reader.seek(SeekFrom::Start(items_start - FOOTER_SIZE))?;
+			reader.read_exact(&mut preamble)?;
+			&preamble == PREAMBLE
+		};
+
+		if !header_present {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				items_start.saturating_sub(FOOTER_SIZE),
+				"APE tag header (missing)",
+			));
+		}
+	}
+
+	let mut items = try_vec![0; (size - FOOTER_SIZE) as usize];
+	reader.seek(SeekFrom::Start(items_start))?;
+	reader.read_exact(&mut items)?;
+
+	let mut actual_count = 0;
+	let mut pos = 0;
+	while pos < items.len() {
+		let Some(item_len) = item_len(&items[pos..]) else {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				items_start + pos as u64,
+				"APE tag item (extends past the end of the tag)",
+			));
+			break;
+		};
+
+		actual_count += 1;
+		pos += item_len;
+	}
+
+	if actual_count != item_count {
+		issues.push(Issue::new(
+			IssueKind::CountMismatch {
+				stored: item_count,
+				actual: actual_count,
+			},
+			footer_start + 16,
+			"APE tag item count",
+		));
+	}
+
+	Ok(())
+}
+
+fn find_footer<R>(reader: &mut R, file_len: u64) -> Result<Option<u64>>
+where
+	R: Read + Seek,
+{
+	let mut preamble = [0; 8];
+
+	for trailing in [0, ID3V1_SIZE] {
+		let Some(footer_start) = file_len.checked_sub(FOOTER_SIZE + trailing) else {
+			break;
+		};
+
+		reader.seek(SeekFrom::Start(footer_start))?;
+		reader.read_exact(&mut preamble)?;
+
+		if &preamble == PREAMBLE {
+			return Ok(Some(footer_start));
+		}
+	}
+
+	Ok(None)
+}
+
+// The total length of the item at the start of `items`, or `None` if it doesn't fit
+fn item_len(items: &[u8]) -> Option<usize> {
+	// Value size (4)
+	// Flags (4)
+	// Key (null terminated)
+	// Value
+	let value_size = LittleEndian::read_u32(items.get(..4)?) as usize;
+	let key_len = items.get(8..)?.iter().position(|b| *b == 0)?;
+
+	let len = 8 + key_len + 1 + value_size;
+	(len <= items.len()).then_some(len)
+}
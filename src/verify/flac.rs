@@ -0,0 +1,153 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
+
+const BLOCK_ID_STREAMINFO: u8 = 0;
+const BLOCK_ID_INVALID: u8 = 127;
+const STREAMINFO_SIZE: u32 = 34;
+
+// Verifies the metadata block structure, and the values in STREAMINFO
+pub(super) fn verify<R>(reader: &mut R, file_len: u64, issues: &mut Vec<Issue>) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let mut pos = reader.stream_position()?;
+
+	let mut marker = [0; 4];
+	if reader.read_exact(&mut marker).is_err() || &marker != b"fLaC" {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			pos,
+			"FLAC stream marker",
+		));
+		return Ok(());
+	}
+
+	pos += 4;
+
+	let mut first = true;
+	loop {
+		if file_len - pos < 4 {
+			// We ran out of blocks without finding the last one
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"FLAC metadata block (no last block flag found)",
+			));
+			break;
+		}
+
+		let byte = reader.read_u8()?;
+		let last = byte & 0x80 != 0;
+		let ty = byte & 0x7F;
+		let size = reader.read_u24::<BigEndian>()?;
+
+		let content_start = pos + 4;
+		let remaining = file_len - content_start;
+		if u64::from(size) > remaining {
+			issues.push(Issue::new(
+				IssueKind::SizeMismatch {
+					stored: u64::from(size),
+					actual: remaining,
+				},
+				pos,
+				"FLAC metadata block size",
+			));
+			break;
+		}
+
+		match ty {
+			BLOCK_ID_STREAMINFO if first => {
+				if size == STREAMINFO_SIZE {
+					let mut streaminfo = [0; STREAMINFO_SIZE as usize];
+					reader.read_exact(&mut streaminfo)?;
+					verify_streaminfo(&streaminfo, content_start, issues);
+				} else {
+					issues.push(Issue::new(
+						IssueKind::SizeMismatch {
+							stored: u64::from(size),
+							actual: u64::from(STREAMINFO_SIZE),
+						},
+						pos,
+						"FLAC STREAMINFO block size",
+					));
+				}
+			},
+			BLOCK_ID_STREAMINFO => issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"FLAC STREAMINFO block (multiple blocks)",
+			)),
+			_ if first => issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"FLAC STREAMINFO block (not the first block)",
+			)),
+			BLOCK_ID_INVALID => issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"FLAC metadata block type",
+			)),
+			_ => {},
+		}
+
+		first = false;
+		pos = content_start + u64::from(size);
+		reader.seek(SeekFrom::Start(pos))?;
+
+		if last {
+			break;
+		}
+	}
+
+	Ok(())
+}
+
+// https://xiph.org/flac/format.html#metadata_block_streaminfo
+fn verify_streaminfo(streaminfo: &[u8], start: u64, issues: &mut Vec<Issue>) {
+	let min_block_size = BigEndian::read_u16(&streaminfo[..2]);
+	let max_block_size = BigEndian::read_u16(&streaminfo[2..4]);
+	let min_frame_size = BigEndian::read_u24(&streaminfo[4..7]);
+	let max_frame_size = BigEndian::read_u24(&streaminfo[7..10]);
+
+	// 20 bits of sample rate, 3 bits of (channels - 1), 5 bits of (bits per sample - 1)
+	let info = BigEndian::read_u32(&streaminfo[10..14]);
+	let sample_rate = info >> 12;
+	let bits_per_sample = ((info >> 4) & 0x1F) + 1;
+
+	if min_block_size < 16 || max_block_size < min_block_size {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			start,
+			"FLAC STREAMINFO block sizes",
+		));
+	}
+
+	// A frame size of 0 means it's unknown
+	if min_frame_size != 0 && max_frame_size != 0 && max_frame_size < min_frame_size {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			start + 4,
+			"FLAC STREAMINFO frame sizes",
+		));
+	}
+
+	if sample_rate == 0 || sample_rate > 655_350 {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			start + 10,
+			"FLAC STREAMINFO sample rate",
+		));
+	}
+
+	if bits_per_sample < 4 {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			start + 12,
+			"FLAC STREAMINFO bits per sample",
+		));
+	}
+}
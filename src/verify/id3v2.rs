@@ -0,0 +1,211 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+use crate::id3::v2::util::synchsafe::SynchsafeInteger;
+use crate::id3::v2::write::crc32;
+use crate::macros::try_vec;
+
+use std::io::{Read, Seek};
+
+use byteorder::{BigEndian, ByteOrder};
+
+const TAG_HEADER_SIZE: u64 = 10;
+
+// Verifies an ID3v2 tag at the start of the reader, returning where the tag ends (or 0 if there is no tag)
+pub(super) fn verify<R>(reader: &mut R, file_len: u64, issues: &mut Vec<Issue>) -> Result<u64>
+where
+	R: Read + Seek,
+{
+	if file_len < TAG_HEADER_SIZE {
+		return Ok(0);
+	}
+
+	let mut header = [0; TAG_HEADER_SIZE as usize];
+	reader.read_exact(&mut header)?;
+
+	if &header[..3] != b"ID3" {
+		return Ok(0);
+	}
+
+	let version = header[3];
+	let flags = header[5];
+
+	if header[6..].iter().any(|b| b & 0x80 != 0) {
+		issues.push(Issue::new(IssueKind::InvalidStructure, 6, "ID3v2 tag size"));
+	}
+
+	let size = BigEndian::read_u32(&header[6..]).unsynch();
+	let has_footer = version == 4 && flags & 0x10 == 0x10;
+
+	let footer_size = if has_footer { TAG_HEADER_SIZE } else { 0 };
+	let tag_end = TAG_HEADER_SIZE + u64::from(size) + footer_size;
+
+	if tag_end > file_len {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: u64::from(size),
+				actual: file_len.saturating_sub(TAG_HEADER_SIZE + footer_size),
+			},
+			6,
+			"ID3v2 tag size",
+		));
+
+		// Nothing else in the file can be trusted
+		return Ok(file_len);
+	}
+
+	if !(2..=4).contains(&version) {
+		issues.push(Issue::new(IssueKind::InvalidStructure, 3, "ID3v2 version"));
+		return Ok(tag_end);
+	}
+
+	let mut content = try_vec![0; size as usize + footer_size as usize];
+	reader.read_exact(&mut content)?;
+
+	let (content, footer) = content.split_at(size as usize);
+	if has_footer && &footer[..3] != b"3DI" {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			TAG_HEADER_SIZE + u64::from(size),
+			"ID3v2 footer",
+		));
+	}
+
+	let has_extended_header = version >= 3 && flags & 0x40 == 0x40;
+	if has_extended_header {
+		if version == 4 {
+			verify_v4_extended_header(content, issues);
+		} else {
+			let unsynchronised = flags & 0x80 == 0x80;
+			verify_v3_extended_header(content, unsynchronised, issues);
+		}
+	}
+
+	Ok(tag_end)
+}
+
+// https://id3.org/id3v2.4.0-structure, section 3.2
+fn verify_v4_extended_header(content: &[u8], issues: &mut Vec<Issue>) {
+	const DESCRIPTION: &str = "ID3v2 extended header";
+
+	if content.len() < 6 {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			TAG_HEADER_SIZE,
+			DESCRIPTION,
+		));
+		return;
+	}
+
+	// The size includes the size field itself
+	let extended_size = BigEndian::read_u32(&content[..4]).unsynch() as usize;
+	if extended_size < 6 || extended_size > content.len() {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: extended_size as u64,
+				actual: content.len() as u64,
+			},
+			TAG_HEADER_SIZE,
+			DESCRIPTION,
+		));
+		return;
+	}
+
+	let extended_flags = content[5];
+
+	// Each flag's data is preceded by a length byte
+	let mut pos = 6;
+	if extended_flags & 0x40 == 0x40 {
+		// Tag is an update
+		pos += 1;
+	}
+
+	if extended_flags & 0x20 == 0x20 {
+		let crc_start = TAG_HEADER_SIZE + pos as u64;
+		let Some(encoded_crc) = content.get(pos + 1..pos + 6) else {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				crc_start,
+				DESCRIPTION,
+			));
+			return;
+		};
+
+		// The CRC-32 is stored as a 35 bit synchsafe integer
+		let stored = encoded_crc
+			.iter()
+			.fold(0_u64, |crc, b| (crc << 7) | u64::from(b & 0x7F)) as u32;
+
+		// The CRC covers all of the data between the extended header and the footer
+		let calculated = crc32(&content[extended_size..]);
+
+		if stored != calculated {
+			issues.push(Issue::new(
+				IssueKind::ChecksumMismatch { stored, calculated },
+				crc_start,
+				"ID3v2 extended header CRC",
+			));
+		}
+	}
+}
+
+// https://id3.org/id3v2.3.0#ID3v2_extended_header
+fn verify_v3_extended_header(content: &[u8], unsynchronised: bool, issues: &mut Vec<Issue>) {
+	const DESCRIPTION: &str = "ID3v2 extended header";
+
+	if content.len() < 10 {
+		issues.push(Issue::new(
+			IssueKind::InvalidStructure,
+			TAG_HEADER_SIZE,
+			DESCRIPTION,
+		));
+		return;
+	}
+
+	// The size doesn't include the size field itself, and is either 6 or 10 bytes
+	let extended_size = BigEndian::read_u32(&content[..4]) as usize;
+	if (extended_size != 6 && extended_size != 10) || extended_size + 4 > content.len() {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: extended_size as u64,
+				actual: content.len().saturating_sub(4) as u64,
+			},
+			TAG_HEADER_SIZE,
+			DESCRIPTION,
+		));
+		return;
+	}
+
+	let extended_flags = BigEndian::read_u16(&content[4..6]);
+	let padding_size = BigEndian::read_u32(&content[6..10]) as usize;
+
+	let frames_start = extended_size + 4;
+	if padding_size > content.len() - frames_start {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: padding_size as u64,
+				actual: (content.len() - frames_start) as u64,
+			},
+			TAG_HEADER_SIZE + 6,
+			"ID3v2 extended header padding size",
+		));
+		return;
+	}
+
+	// The CRC is calculated before unsynchronisation, which we would have to undo first
+	if extended_flags & 0x8000 == 0 || extended_size != 10 || unsynchronised {
+		return;
+	}
+
+	let stored = BigEndian::read_u32(&content[10..14]);
+
+	// The CRC covers only the frames, not the padding
+	let calculated = crc32(&content[frames_start..content.len() - padding_size]);
+
+	if stored != calculated {
+		issues.push(Issue::new(
+			IssueKind::ChecksumMismatch { stored, calculated },
+			TAG_HEADER_SIZE + 10,
+			"ID3v2 extended header CRC",
+		));
+	}
+}
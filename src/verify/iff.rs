@@ -0,0 +1,85 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{ByteOrder, ReadBytesExt};
+
+// Verifies the size of the RIFF/FORM chunk against the file size, and the sizes of the chunks within it
+pub(super) fn verify<R, B>(
+	reader: &mut R,
+	file_len: u64,
+	container_id: [u8; 4],
+	issues: &mut Vec<Issue>,
+) -> Result<()>
+where
+	R: Read + Seek,
+	B: ByteOrder,
+{
+	let description = if &container_id == b"RIFF" {
+		"RIFF chunk size"
+	} else {
+		"FORM chunk size"
+	};
+
+	if file_len < 12 {
+		issues.push(Issue::new(IssueKind::InvalidStructure, 0, description));
+		return Ok(());
+	}
+
+	let mut id = [0; 4];
+	reader.read_exact(&mut id)?;
+	if id != container_id {
+		issues.push(Issue::new(IssueKind::InvalidStructure, 0, description));
+		return Ok(());
+	}
+
+	let size = u64::from(reader.read_u32::<B>()?);
+	if size + 8 != file_len {
+		issues.push(Issue::new(
+			IssueKind::SizeMismatch {
+				stored: size,
+				actual: file_len - 8,
+			},
+			4,
+			description,
+		));
+	}
+
+	let end = (size + 8).min(file_len);
+
+	// The chunks start after the form type (e.g. "WAVE")
+	let mut pos = 12;
+
+	while pos < end {
+		let remaining = end - pos;
+		if remaining < 8 {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"Chunk (trailing data too small for a chunk)",
+			));
+			break;
+		}
+
+		reader.seek(SeekFrom::Start(pos + 4))?;
+		let chunk_size = u64::from(reader.read_u32::<B>()?);
+
+		if chunk_size > remaining - 8 {
+			issues.push(Issue::new(
+				IssueKind::SizeMismatch {
+					stored: chunk_size,
+					actual: remaining - 8,
+				},
+				pos,
+				"Chunk size",
+			));
+			break;
+		}
+
+		// Chunks are padded to an even length, though the padding may be missing on the last chunk
+		pos += 8 + chunk_size + (chunk_size % 2);
+	}
+
+	Ok(())
+}
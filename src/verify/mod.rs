@@ -0,0 +1,237 @@
+//! Structural verification of files
+//!
+//! Verification runs the cheap integrity checks that each format allows, without reading any tags
+//! or audio properties. Unlike reading with [`ParsingMode::Strict`](crate::ParsingMode::Strict),
+//! verification doesn't stop at the first problem, every [`Issue`] found is reported.
+//!
+//! The checks performed are:
+//!
+//! * **ID3v2**: The tag size against the file size, and the extended header CRC
+//! * **OGG**: The CRC of every page
+//! * **FLAC**: The metadata block structure and the `STREAMINFO` block's values
+//! * **MP4**: The sizes of the atoms, which must account for the entire file
+//! * **WAV**/**AIFF**: The `RIFF`/`FORM` size against the file size, and the chunk sizes
+//! * **APE**: The tag size, and the item count against the items actually present
+//!
+//! Any other formats will only have their ID3v2 and APE tags (where applicable) checked.
+
+mod ape;
+mod flac;
+mod id3v2;
+mod iff;
+mod mp4;
+mod ogg;
+
+use crate::error::Result;
+use crate::file::FileType;
+#[cfg(feature = "fs")]
+use crate::macros::err;
+#[cfg(feature = "fs")]
+use crate::probe::Probe;
+use crate::traits::SeekStreamLen;
+
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Seek, SeekFrom};
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use byteorder::{BigEndian, LittleEndian};
+
+/// The kind of an [`Issue`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IssueKind {
+	/// A stored checksum doesn't match the checksum of the data it covers
+	ChecksumMismatch {
+		/// The checksum stored in the file
+		stored: u32,
+		/// The checksum of the data
+		calculated: u32,
+	},
+	/// A stored size doesn't match the actual size of the data
+	SizeMismatch {
+		/// The size stored in the file
+		stored: u64,
+		/// The size that was expected, or the amount of data actually available
+		actual: u64,
+	},
+	/// A stored item count doesn't match the number of items present
+	CountMismatch {
+		/// The count stored in the file
+		stored: u32,
+		/// The number of items actually present
+		actual: u32,
+	},
+	/// The data is invalid in some other way, see [`Issue::description`]
+	InvalidStructure,
+}
+
+/// A problem found while verifying a file
+///
+/// See [`verify`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Issue {
+	pub(crate) kind: IssueKind,
+	pub(crate) offset: u64,
+	pub(crate) description: &'static str,
+}
+
+impl Issue {
+	pub(crate) fn new(kind: IssueKind, offset: u64, description: &'static str) -> Self {
+		Self {
+			kind,
+			offset,
+			description,
+		}
+	}
+
+	/// The kind of issue
+	pub fn kind(&self) -> IssueKind {
+		self.kind
+	}
+
+	/// The offset in the file of the structure with the issue
+	pub fn offset(&self) -> u64 {
+		self.offset
+	}
+
+	/// A description of the structure with the issue (e.g. "Ogg page CRC")
+	pub fn description(&self) -> &'static str {
+		self.description
+	}
+}
+
+impl Display for Issue {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} (offset {}): ", self.description, self.offset)?;
+
+		match self.kind {
+			IssueKind::ChecksumMismatch { stored, calculated } => write!(
+				f,
+				"stored checksum {stored:#010X} doesn't match calculated checksum {calculated:#010X}"
+			),
+			IssueKind::SizeMismatch { stored, actual } => {
+				write!(f, "stored size {stored} doesn't match actual size {actual}")
+			},
+			IssueKind::CountMismatch { stored, actual } => {
+				write!(
+					f,
+					"stored count {stored} doesn't match actual count {actual}"
+				)
+			},
+			IssueKind::InvalidStructure => write!(f, "invalid structure"),
+		}
+	}
+}
+
+/// Verify the structure of a file
+///
+/// See the [module documentation](self) for the checks that are performed.
+///
+/// An empty list means that no issues were found. Note that this doesn't mean the file is
+/// entirely valid, since the audio data itself isn't checked.
+///
+/// # Errors
+///
+/// * The reader could not be read from
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::verify::verify;
+/// use lofty::FileType;
+///
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.ogg";
+/// let mut file = std::fs::File::open(path)?;
+///
+/// let issues = verify(&mut file, FileType::Vorbis)?;
+/// for issue in &issues {
+/// 	println!("{issue}");
+/// }
+/// # assert!(issues.is_empty());
+/// # Ok(()) }
+/// ```
+pub fn verify<R>(reader: &mut R, file_type: FileType) -> Result<Vec<Issue>>
+where
+	R: Read + Seek,
+{
+	let mut issues = Vec::new();
+
+	#[allow(unstable_name_collisions)]
+	let file_len = reader.stream_len()?;
+	reader.rewind()?;
+
+	// These formats may be preceded by an ID3v2 tag
+	let mut content_start = 0;
+	if matches!(
+		file_type,
+		FileType::Aac
+			| FileType::Ape
+			| FileType::Flac
+			| FileType::Mpeg
+			| FileType::Mpc
+			| FileType::WavPack
+	) {
+		content_start = id3v2::verify(reader, file_len, &mut issues)?;
+	}
+
+	reader.seek(SeekFrom::Start(content_start))?;
+
+	match file_type {
+		FileType::Flac => flac::verify(reader, file_len, &mut issues)?,
+		FileType::Mp4 => mp4::verify(reader, file_len, &mut issues)?,
+		FileType::Opus | FileType::Vorbis | FileType::Speex => {
+			ogg::verify(reader, file_len, &mut issues)?
+		},
+		FileType::Wav => iff::verify::<_, LittleEndian>(reader, file_len, *b"RIFF", &mut issues)?,
+		FileType::Aiff => iff::verify::<_, BigEndian>(reader, file_len, *b"FORM", &mut issues)?,
+		_ => {},
+	}
+
+	// These formats may be followed by an APE tag
+	if matches!(
+		file_type,
+		FileType::Aac | FileType::Ape | FileType::Mpeg | FileType::Mpc | FileType::WavPack
+	) {
+		ape::verify(reader, file_len, &mut issues)?;
+	}
+
+	issues.sort_by_key(Issue::offset);
+	Ok(issues)
+}
+
+#[cfg(feature = "fs")]
+/// Verify the structure of a file at a path
+///
+/// The [`FileType`] is guessed from the file's content, falling back to its extension.
+///
+/// # Errors
+///
+/// * The file could not be opened or read
+/// * [`ErrorKind::UnknownFormat`](crate::error::ErrorKind::UnknownFormat)
+///
+/// See [`verify`]
+///
+/// # Examples
+///
+/// ```rust
+/// use lofty::verify::verify_path;
+///
+/// # fn main() -> lofty::Result<()> {
+/// # let path = "tests/files/assets/minimal/full_test.mp3";
+/// let issues = verify_path(path)?;
+/// # assert!(issues.is_empty());
+/// # Ok(()) }
+/// ```
+pub fn verify_path<P>(path: P) -> Result<Vec<Issue>>
+where
+	P: AsRef<Path>,
+{
+	let probe = Probe::open(path)?.guess_file_type()?;
+	let Some(file_type) = probe.file_type() else {
+		err!(UnknownFormat);
+	};
+
+	verify(&mut probe.into_inner(), file_type)
+}
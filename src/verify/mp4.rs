@@ -0,0 +1,104 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::{BigEndian, ReadBytesExt};
+
+// Atoms that only contain other atoms
+const CONTAINERS: &[&[u8; 4]] = &[
+	b"moov", b"trak", b"mdia", b"minf", b"stbl", b"udta", b"edts", b"dinf", b"mvex", b"moof",
+	b"traf", b"ilst",
+];
+
+// Verifies that the atoms account for every byte of the file, and that
+// the atoms in every container account for every byte of their parent
+pub(super) fn verify<R>(reader: &mut R, file_len: u64, issues: &mut Vec<Issue>) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let start = reader.stream_position()?;
+	verify_atoms(reader, start, file_len, 0, issues)
+}
+
+fn verify_atoms<R>(
+	reader: &mut R,
+	start: u64,
+	end: u64,
+	depth: u8,
+	issues: &mut Vec<Issue>,
+) -> Result<()>
+where
+	R: Read + Seek,
+{
+	// Anything deeper than this isn't something we'd reasonably expect
+	const MAX_DEPTH: u8 = 8;
+
+	let mut pos = start;
+	while pos < end {
+		let remaining = end - pos;
+		if remaining < 8 {
+			// QuickTime allows for containers to end with a 32-bit zero terminator
+			if depth > 0 && remaining == 4 {
+				reader.seek(SeekFrom::Start(pos))?;
+				if reader.read_u32::<BigEndian>()? == 0 {
+					break;
+				}
+			}
+
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"MP4 atom (trailing data too small for an atom)",
+			));
+			break;
+		}
+
+		reader.seek(SeekFrom::Start(pos))?;
+
+		let mut len = u64::from(reader.read_u32::<BigEndian>()?);
+		let mut fourcc = [0; 4];
+		reader.read_exact(&mut fourcc)?;
+
+		let mut header_len = 8;
+		match len {
+			// The atom extends to the end of the file, which is only valid for the last top level atom
+			0 if depth == 0 => len = remaining,
+			// The atom has a 64-bit length
+			1 if remaining >= 16 => {
+				len = reader.read_u64::<BigEndian>()?;
+				header_len = 16;
+			},
+			_ => {},
+		}
+
+		if len < header_len {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"MP4 atom size (smaller than its header)",
+			));
+			break;
+		}
+
+		if len > remaining {
+			issues.push(Issue::new(
+				IssueKind::SizeMismatch {
+					stored: len,
+					actual: remaining,
+				},
+				pos,
+				"MP4 atom size",
+			));
+			break;
+		}
+
+		if depth < MAX_DEPTH && CONTAINERS.contains(&&fourcc) {
+			verify_atoms(reader, pos + header_len, pos + len, depth + 1, issues)?;
+		}
+
+		pos += len;
+	}
+
+	Ok(())
+}
@@ -0,0 +1,43 @@
+use super::{Issue, IssueKind};
+use crate::error::Result;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use ogg_pager::Page;
+
+// Verifies the CRC of every page
+pub(super) fn verify<R>(reader: &mut R, file_len: u64, issues: &mut Vec<Issue>) -> Result<()>
+where
+	R: Read + Seek,
+{
+	let mut pos = reader.stream_position()?;
+	while pos < file_len {
+		let Ok(page) = Page::read(reader) else {
+			issues.push(Issue::new(
+				IssueKind::InvalidStructure,
+				pos,
+				"Ogg page (invalid or truncated)",
+			));
+			break;
+		};
+
+		let stored = page.header().checksum();
+
+		let mut recalculated = page.clone();
+		recalculated.gen_crc();
+		let calculated = recalculated.header().checksum();
+
+		if stored != calculated {
+			issues.push(Issue::new(
+				IssueKind::ChecksumMismatch { stored, calculated },
+				pos,
+				"Ogg page CRC",
+			));
+		}
+
+		pos = page.end;
+		reader.seek(SeekFrom::Start(pos))?;
+	}
+
+	Ok(())
+}
@@ -11,9 +11,13 @@ use lofty_attr::LoftyFile;
 pub use properties::WavPackProperties;
 
 /// A WavPack file
+///
+/// ## Tags
+///
+/// WavPack files are tagged with APE tags, and can also have an ID3v1 tag. Both are stored at the end of
+/// the file, and the ID3v1 tag will always be written after the APE tag.
 #[derive(LoftyFile, Default)]
 #[lofty(read_fn = "read::read_from")]
-#[lofty(internal_write_module_do_not_use_anywhere_else)]
 pub struct WavPackFile {
 	/// An ID3v1 tag
 	#[lofty(tag_type = "Id3v1")]
@@ -23,4 +27,7 @@ pub struct WavPackFile {
 	pub(crate) ape_tag: Option<ApeTag>,
 	/// The file's audio properties
 	pub(crate) properties: WavPackProperties,
+	/// The fingerprint taken when reading, see [`FileFingerprint`](crate::FileFingerprint)
+	#[cfg(feature = "fs")]
+	pub(crate) fingerprint: crate::fingerprint::FingerprintCell,
 }
@@ -1,7 +1,7 @@
 use crate::error::Result;
 use crate::macros::{decode_err, err, parse_mode_choice, try_vec};
 use crate::probe::ParsingMode;
-use crate::properties::{ChannelMask, FileProperties};
+use crate::properties::{bitrate_source, non_zero, BitrateSource, ChannelMask, FileProperties};
 
 use std::io::{Read, Seek, SeekFrom};
 use std::time::Duration;
@@ -27,12 +27,15 @@ impl From<WavPackProperties> for FileProperties {
 	fn from(input: WavPackProperties) -> Self {
 		Self {
 			duration: input.duration,
-			overall_bitrate: Some(input.overall_bitrate),
-			audio_bitrate: Some(input.audio_bitrate),
-			sample_rate: Some(input.sample_rate),
-			bit_depth: Some(input.bit_depth),
-			channels: Some(input.channels as u8),
+			overall_bitrate: non_zero(input.overall_bitrate),
+			audio_bitrate: non_zero(input.audio_bitrate),
+			sample_rate: non_zero(input.sample_rate),
+			bit_depth: non_zero(input.bit_depth),
+			channels: non_zero(input.channels as u8),
 			channel_mask: Some(input.channel_mask),
+			gapless_info: None,
+			duration_estimated: false,
+			audio_bitrate_source: bitrate_source(input.audio_bitrate, BitrateSource::Computed),
 		}
 	}
 }
@@ -126,6 +129,12 @@ where
 	let mut offset = 0;
 	let mut total_samples = 0;
 	loop {
+		// Anything past the end of the stream is tag data (APE, ID3v1), which can't be mistaken for
+		// another block
+		if offset >= stream_length {
+			break;
+		}
+
 		reader.seek(SeekFrom::Start(offset))?;
 
 		let block_header;
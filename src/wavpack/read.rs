@@ -3,6 +3,7 @@ use super::WavPackFile;
 use crate::error::Result;
 use crate::id3::{find_id3v1, find_lyrics3v2, ID3FindResults};
 use crate::probe::ParseOptions;
+use crate::util::io::OffsetFile;
 
 use std::io::{Read, Seek, SeekFrom};
 
@@ -10,6 +11,8 @@ pub(super) fn read_from<R>(reader: &mut R, parse_options: ParseOptions) -> Resul
 where
 	R: Read + Seek,
 {
+	let reader = &mut OffsetFile::new(reader, parse_options.start_offset)?;
+
 	let current_pos = reader.stream_position()?;
 	let mut stream_length = reader.seek(SeekFrom::End(0))?;
 	reader.seek(SeekFrom::Start(current_pos))?;
@@ -38,7 +41,9 @@ where
 	// Strongly recommended to be at the end of the file
 	reader.seek(SeekFrom::Current(-32))?;
 
-	if let Some((tag, header)) = crate::ape::tag::read::read_ape_tag(reader, true)? {
+	if let Some((tag, header)) =
+		crate::ape::tag::read::read_ape_tag(reader, true, parse_options.parsing_mode)?
+	{
 		stream_length -= u64::from(header.size);
 		ape_tag = Some(tag);
 	}
@@ -51,5 +56,7 @@ where
 		} else {
 			WavPackProperties::default()
 		},
+		#[cfg(feature = "fs")]
+		fingerprint: crate::fingerprint::FingerprintCell::default(),
 	})
 }
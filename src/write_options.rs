@@ -0,0 +1,711 @@
+use crate::probe::ParsingMode;
+use crate::tag::isrc::Isrc;
+
+/// Options to control how Lofty writes to a file
+///
+/// This acts as a dumping ground for all sorts of format-specific settings. As such, this is best used as
+/// an application global config that gets set once.
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[non_exhaustive]
+#[allow(clippy::struct_excessive_bools)]
+pub struct WriteOptions {
+	pub(crate) split_oversized_id3v2_frames: bool,
+	pub(crate) remove_tags_if_empty: bool,
+	pub(crate) prefer_mp4_gnre: bool,
+	pub(crate) bpm_rounding: BpmRounding,
+	pub(crate) legacy_vorbis_album_artist: bool,
+	pub(crate) ignore_stale_file: bool,
+	pub(crate) max_picture_size: Option<u64>,
+	pub(crate) error_on_oversized_pictures: bool,
+	pub(crate) riff_info_placement: RiffInfoPlacement,
+	pub(crate) error_on_lossy_id3v1_genre: bool,
+	pub(crate) use_id3v23: bool,
+	pub(crate) id3v23_separator: &'static str,
+	pub(crate) id3v23_numeric_genres: bool,
+	pub(crate) remove_empty_items: bool,
+	pub(crate) trim_whitespace: bool,
+	pub(crate) preserve_id3v2_frame_order: bool,
+	pub(crate) preferred_padding: Option<u32>,
+	pub(crate) append_id3v2: bool,
+	pub(crate) validate_isrc: bool,
+	pub(crate) fit_id3v2_restrictions: bool,
+	pub(crate) parsing_mode: ParsingMode,
+}
+
+impl Default for WriteOptions {
+	/// The default implementation for `WriteOptions`
+	///
+	/// The defaults are as follows:
+	///
+	/// ```rust,ignore
+	/// WriteOptions {
+	/// 	split_oversized_id3v2_frames: false,
+	/// 	remove_tags_if_empty: true,
+	/// 	prefer_mp4_gnre: false,
+	/// 	bpm_rounding: BpmRounding::Nearest,
+	/// 	legacy_vorbis_album_artist: false,
+	/// 	ignore_stale_file: false,
+	/// 	max_picture_size: None,
+	/// 	error_on_oversized_pictures: false,
+	/// 	riff_info_placement: RiffInfoPlacement::AfterData,
+	/// 	error_on_lossy_id3v1_genre: false,
+	/// 	use_id3v23: false,
+	/// 	id3v23_separator: "/",
+	/// 	id3v23_numeric_genres: false,
+	/// 	remove_empty_items: true,
+	/// 	trim_whitespace: false,
+	/// 	preserve_id3v2_frame_order: false,
+	/// 	preferred_padding: Some(1024),
+	/// 	append_id3v2: false,
+	/// 	validate_isrc: false,
+	/// 	fit_id3v2_restrictions: false,
+	/// 	parsing_mode: ParsingMode::BestAttempt,
+	/// }
+	/// ```
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl WriteOptions {
+	/// Creates a new `WriteOptions`, alias for `Default` implementation
+	///
+	/// See also: [`WriteOptions::default`]
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// let write_options = WriteOptions::new();
+	/// ```
+	#[must_use]
+	pub const fn new() -> Self {
+		Self {
+			split_oversized_id3v2_frames: false,
+			remove_tags_if_empty: true,
+			prefer_mp4_gnre: false,
+			bpm_rounding: BpmRounding::Nearest,
+			legacy_vorbis_album_artist: false,
+			ignore_stale_file: false,
+			max_picture_size: None,
+			error_on_oversized_pictures: false,
+			riff_info_placement: RiffInfoPlacement::AfterData,
+			error_on_lossy_id3v1_genre: false,
+			use_id3v23: false,
+			id3v23_separator: "/",
+			id3v23_numeric_genres: false,
+			remove_empty_items: true,
+			trim_whitespace: false,
+			preserve_id3v2_frame_order: false,
+			preferred_padding: Some(1024),
+			append_id3v2: false,
+			validate_isrc: false,
+			fit_id3v2_restrictions: false,
+			parsing_mode: ParsingMode::BestAttempt,
+		}
+	}
+
+	/// Whether to split ID3v2 text frames that exceed the maximum frame size
+	///
+	/// The size of an ID3v2.4 frame is stored as a 28-bit synchsafe integer, limiting frames to just under 256 MiB.
+	/// Normally, attempting to write a larger frame will result in [`Id3v2ErrorKind::FrameTooLarge`](crate::error::Id3v2ErrorKind::FrameTooLarge).
+	///
+	/// With this enabled, oversized `USLT` and `TXXX` frames will instead be split into multiple frames. Since
+	/// those frames are only unique by their description (and language for `USLT`), each part after the
+	/// first will have its part number appended to the description, e.g. `"Lyrics"`, `"Lyrics (2)"`, `"Lyrics (3)"`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // I have some *very* long lyrics, I'd rather they be split than fail to save.
+	/// let write_options = WriteOptions::new().split_oversized_id3v2_frames(true);
+	/// ```
+	pub fn split_oversized_id3v2_frames(&mut self, split_oversized_id3v2_frames: bool) -> Self {
+		self.split_oversized_id3v2_frames = split_oversized_id3v2_frames;
+		*self
+	}
+
+	/// Whether to remove a tag from the file if it is empty
+	///
+	/// By default, saving a tag with no items will remove the tag from the file entirely. With this disabled,
+	/// an empty tag will be written instead, for the formats that allow it:
+	///
+	/// * APE: A header and footer with no items
+	/// * ID3v1: A tag with all fields empty
+	/// * MP4: An empty `ilst` atom
+	/// * Vorbis Comments (FLAC): A `VORBIS_COMMENT` block containing only the vendor string
+	///
+	/// ID3v2 tags are required to have at least one frame, and RIFF INFO lists and AIFF text chunks gain
+	/// nothing from being kept around empty, so those will always be removed. OGG formats always retain
+	/// their comment header.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // I want my FLAC files to keep their vendor string, even without any comments.
+	/// let write_options = WriteOptions::new().remove_tags_if_empty(false);
+	/// ```
+	pub fn remove_tags_if_empty(&mut self, remove_tags_if_empty: bool) -> Self {
+		self.remove_tags_if_empty = remove_tags_if_empty;
+		*self
+	}
+
+	/// Whether to write MP4 genres as a `gnre` atom when possible
+	///
+	/// MP4 has two genre atoms: `\xa9gen`, which holds free text, and `gnre`, which holds an index into
+	/// the [ID3v1 genre list](crate::id3::v1::GENRES). Both are read as `\xa9gen`, and only one of them
+	/// will ever be written.
+	///
+	/// With this enabled, a single genre that exactly matches an ID3v1 genre will be written as `gnre`,
+	/// which some players prefer. Any other genre will still be written as `\xa9gen`.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My player only understands `gnre`
+	/// let write_options = WriteOptions::new().prefer_mp4_gnre(true);
+	/// ```
+	pub fn prefer_mp4_gnre(&mut self, prefer_mp4_gnre: bool) -> Self {
+		self.prefer_mp4_gnre = prefer_mp4_gnre;
+		*self
+	}
+
+	/// How to round a fractional BPM for fields that can only hold an integer
+	///
+	/// ID3v2's `TBPM` frame and MP4's `tmpo` atom can only hold integers. A fractional [`ItemKey::Bpm`](crate::ItemKey::Bpm)
+	/// is additionally stored as text, in a `TXXX:BPM` frame or a `----:com.apple.iTunes:BPM` atom, which will be
+	/// preferred when reading. When one of these exists, the integer field is derived from it on write using this
+	/// rounding mode.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{BpmRounding, WriteOptions};
+	///
+	/// // My player expects 126.5 BPM to be written as 126
+	/// let write_options = WriteOptions::new().bpm_rounding(BpmRounding::Down);
+	/// ```
+	pub fn bpm_rounding(&mut self, bpm_rounding: BpmRounding) -> Self {
+		self.bpm_rounding = bpm_rounding;
+		*self
+	}
+
+	/// Whether to also write the album artist as the legacy `ALBUM ARTIST` Vorbis comment
+	///
+	/// The album artist is stored as `ALBUMARTIST`, though some older players only understand
+	/// `ALBUM ARTIST`. Both are read as [`ItemKey::AlbumArtist`](crate::ItemKey::AlbumArtist), with
+	/// `ALBUMARTIST` preferred when both exist.
+	///
+	/// With this enabled, every `ALBUMARTIST` comment will be followed by an `ALBUM ARTIST` comment with
+	/// the same value, replacing any existing `ALBUM ARTIST` comments.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My car stereo doesn't show album artists otherwise
+	/// let write_options = WriteOptions::new().legacy_vorbis_album_artist(true);
+	/// ```
+	pub fn legacy_vorbis_album_artist(&mut self, legacy_vorbis_album_artist: bool) -> Self {
+		self.legacy_vorbis_album_artist = legacy_vorbis_album_artist;
+		*self
+	}
+
+	/// Whether to save over a file that was modified since it was read
+	///
+	/// Every file type takes a [`FileFingerprint`](crate::FileFingerprint) of its file when reading, and
+	/// verifies it in [`AudioFile::save_to`](crate::AudioFile::save_to). If another program modified the file
+	/// in the meantime, saving will fail with [`ErrorKind::StaleFile`](crate::error::ErrorKind::StaleFile),
+	/// rather than silently discarding those modifications, or writing over data that has since moved.
+	/// The fingerprint is updated after saving, so the same file can be saved again.
+	///
+	/// NOTE: This means saving the tags of one file to a different one will also fail, as it won't match.
+	///
+	/// With this enabled, the file will be saved regardless.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My tags are the only ones that matter
+	/// let write_options = WriteOptions::new().ignore_stale_file(true);
+	/// ```
+	pub fn ignore_stale_file(&mut self, ignore_stale_file: bool) -> Self {
+		self.ignore_stale_file = ignore_stale_file;
+		*self
+	}
+
+	/// The maximum number of bytes a single picture may occupy in a tag
+	///
+	/// Pictures can grow substantially when written. For example, Vorbis Comments require pictures to be
+	/// base64 encoded, inflating them by a third. The size of a picture in a specific tag can be checked
+	/// ahead of time with [`Picture::encoded_size_for`](crate::Picture::encoded_size_for).
+	///
+	/// By default, there is no maximum. When a picture exceeds it, a warning will be logged, or an error returned
+	/// if [`WriteOptions::error_on_oversized_pictures`] is enabled.
+	///
+	/// NOTE: Regardless of this setting, a warning will be logged for any picture exceeding
+	///       [`ParseOptions::DEFAULT_ALLOCATION_LIMIT`](crate::ParseOptions::DEFAULT_ALLOCATION_LIMIT), as it
+	///       will fail to be read back with the default [`ParseOptions`](crate::ParseOptions).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // Some streaming servers reject comment headers larger than 1 MiB
+	/// let write_options = WriteOptions::new().max_picture_size(Some(1024 * 1024));
+	/// ```
+	pub fn max_picture_size(&mut self, max_picture_size: Option<u64>) -> Self {
+		self.max_picture_size = max_picture_size;
+		*self
+	}
+
+	/// Whether to error when a picture exceeds [`WriteOptions::max_picture_size`]
+	///
+	/// With this enabled, writing the tag will fail with [`ErrorKind::PictureTooLarge`](crate::error::ErrorKind::PictureTooLarge),
+	/// rather than only logging a warning. Nothing will be written to the file.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // I'd rather resize my pictures than bloat my files
+	/// let write_options = WriteOptions::new()
+	/// 	.max_picture_size(Some(1024 * 1024))
+	/// 	.error_on_oversized_pictures(true);
+	/// ```
+	pub fn error_on_oversized_pictures(&mut self, error_on_oversized_pictures: bool) -> Self {
+		self.error_on_oversized_pictures = error_on_oversized_pictures;
+		*self
+	}
+
+	/// Where to place a new RIFF INFO list in a WAV file
+	///
+	/// This only applies to files without an existing INFO list, which is always replaced in place.
+	///
+	/// By default, the list is placed directly after the `data` chunk. Some readers stop looking for
+	/// the list once they reach an unknown chunk, so placing it after any trailing chunks (such as `PAD `,
+	/// or another application's proprietary chunks) would cause it to be missed.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{RiffInfoPlacement, WriteOptions};
+	///
+	/// // My files must be appended to, never shifted around
+	/// let write_options = WriteOptions::new().riff_info_placement(RiffInfoPlacement::EndOfFile);
+	/// ```
+	pub fn riff_info_placement(&mut self, riff_info_placement: RiffInfoPlacement) -> Self {
+		self.riff_info_placement = riff_info_placement;
+		*self
+	}
+
+	/// Whether to error when an ID3v1 genre would be lost
+	///
+	/// ID3v1 stores the genre as an index into the [ID3v1 genre list](crate::id3::v1::GENRES). Any other
+	/// genre is written as `255` (unknown), and a warning is logged.
+	///
+	/// With this enabled, writing the tag will fail with [`ErrorKind::LossyId3v1Genre`](crate::error::ErrorKind::LossyId3v1Genre)
+	/// instead. Nothing will be written to the file.
+	///
+	/// NOTE: When saving a [`TaggedFile`](crate::TaggedFile) that also has an ID3v2 tag, the genre is
+	///       added to the ID3v2 tag if it's missing, so nothing is lost and the ID3v1 tag is written
+	///       regardless of this setting.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My genres are too niche for ID3v1, and I don't want to lose them
+	/// let write_options = WriteOptions::new().error_on_lossy_id3v1_genre(true);
+	/// ```
+	pub fn error_on_lossy_id3v1_genre(&mut self, error_on_lossy_id3v1_genre: bool) -> Self {
+		self.error_on_lossy_id3v1_genre = error_on_lossy_id3v1_genre;
+		*self
+	}
+
+	/// Whether to write ID3v2.3 tags rather than ID3v2.4
+	///
+	/// Tags are always upgraded to ID3v2.4 when read, and written as such. Many older players (and car stereos)
+	/// can only read ID3v2.3, so with this enabled, tags will be converted on write:
+	///
+	/// * Frame sizes are no longer synchsafe integers
+	/// * UTF-8 and UTF-16BE text is written as UTF-16, as ID3v2.3 only supports Latin-1 and UTF-16
+	/// * Multiple values in a text frame are separated with `/`, rather than null terminators, see
+	///   [`WriteOptions::id3v23_separator`]
+	/// * `TDRC` is split into `TYER`, `TDAT`, and `TIME`, `TDOR` is written as `TORY`, and `TIPL` and `TMCL` are
+	///   merged into a single `IPLS` frame
+	/// * Frames that are new in ID3v2.4 and have no ID3v2.3 equivalent (`ASPI`, `EQU2`, `RVA2`, `SEEK`, `SIGN`,
+	///   `TDEN`, `TDRL`, `TDTG`, `TMOO`, `TPRO`, and `TSST`) are **dropped**. The sort order frames (`TSOA`,
+	///   `TSOP`, and `TSOT`) are written as-is, as they are commonly found in ID3v2.3 tags.
+	/// * The tag restrictions and footer are dropped, as they don't exist in ID3v2.3
+	///
+	/// `TYER`, `TDAT`, and `TIME` are merged back into `TDRC` when reading.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My car stereo can't read ID3v2.4
+	/// let write_options = WriteOptions::new().use_id3v23(true);
+	/// ```
+	pub fn use_id3v23(&mut self, use_id3v23: bool) -> Self {
+		self.use_id3v23 = use_id3v23;
+		*self
+	}
+
+	/// The separator to use for multiple values in a text frame, when writing ID3v2.3 tags
+	///
+	/// ID3v2.4 separates the values of a text frame (e.g. multiple artists in `TPE1`) with null characters,
+	/// which ID3v2.3 doesn't support. When [`WriteOptions::use_id3v23`] is enabled, the values are joined with
+	/// this separator instead. The default is `/`, which is the most widely recognized.
+	///
+	/// NOTE: The values aren't split again when reading an ID3v2.3 tag, as the separator may just as well
+	///       appear in a single value (e.g. `"AC/DC"`).
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My player splits artists on semicolons
+	/// let write_options = WriteOptions::new()
+	/// 	.use_id3v23(true)
+	/// 	.id3v23_separator("; ");
+	/// ```
+	pub fn id3v23_separator(&mut self, id3v23_separator: &'static str) -> Self {
+		self.id3v23_separator = id3v23_separator;
+		*self
+	}
+
+	/// Whether to write genres as references to the ID3v1 genre list, when writing ID3v2.3 tags
+	///
+	/// ID3v2.3 `TCON` frames are meant to refer to the ID3v1 genre list (see [`GENRES`](crate::id3::v1::GENRES)) by
+	/// index, such as `(17)` for `"Rock"`. Some strict ID3v2.3 readers only understand this form. When
+	/// [`WriteOptions::use_id3v23`] is enabled, any genre found in the list (or `"Remix"` and `"Cover"`, as `(RX)` and `(CR)`)
+	/// will be written as a reference. Genres that aren't in the list follow the references as text, joined with
+	/// [`WriteOptions::id3v23_separator`].
+	///
+	/// See [`ParseOptions::resolve_id3v2_genres`](crate::ParseOptions::resolve_id3v2_genres) for the inverse.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // "Rock" and "Eurodisco" will be written as "(17)Eurodisco"
+	/// let write_options = WriteOptions::new()
+	/// 	.use_id3v23(true)
+	/// 	.id3v23_numeric_genres(true);
+	/// ```
+	pub fn id3v23_numeric_genres(&mut self, id3v23_numeric_genres: bool) -> Self {
+		self.id3v23_numeric_genres = id3v23_numeric_genres;
+		*self
+	}
+
+	/// Whether to skip text items with empty values when writing
+	///
+	/// By default, an empty text item (e.g. a title of `""`) is treated the same as a missing one, and is
+	/// not written to any tag. With this disabled, empty items will be written as-is, for the tags that can
+	/// hold them:
+	///
+	/// * APE: An item with an empty value
+	/// * ID3v2: An empty text frame (`TIT2`, `TXXX`, `COMM`, `USLT`, etc.)
+	/// * MP4: An atom with empty data
+	/// * RIFF INFO: A chunk holding only a null terminator
+	/// * Vorbis Comments: A comment with an empty value (e.g. `TITLE=`)
+	/// * AIFF text chunks: An empty `NAME`, `AUTH`, `(c) `, or `ANNO` chunk
+	///
+	/// ID3v1 fields are fixed size, so there is no difference between an empty field and a missing one.
+	///
+	/// This applies after [`WriteOptions::trim_whitespace`], so with both enabled, items that only contain
+	/// whitespace will also be skipped.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // An empty title means something to my player
+	/// let write_options = WriteOptions::new().remove_empty_items(false);
+	/// ```
+	pub fn remove_empty_items(&mut self, remove_empty_items: bool) -> Self {
+		self.remove_empty_items = remove_empty_items;
+		*self
+	}
+
+	/// Whether to trim leading and trailing whitespace from text items when writing
+	///
+	/// By default, text is written exactly as it is stored. With this enabled, the surrounding whitespace of
+	/// every text item is removed in all tags, see [`WriteOptions::remove_empty_items`] for the items affected.
+	/// For ID3v2 text frames holding multiple values, each value is trimmed separately.
+	///
+	/// NOTE: This only affects what is written, the tag itself is left untouched.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My tags were filled in by hand, and it shows
+	/// let write_options = WriteOptions::new().trim_whitespace(true);
+	/// ```
+	pub fn trim_whitespace(&mut self, trim_whitespace: bool) -> Self {
+		self.trim_whitespace = trim_whitespace;
+		*self
+	}
+
+	/// Whether to keep the order of the frames in the ID3v2 tag being replaced
+	///
+	/// By default, frames are written in the order they are stored in the tag. Since a replaced frame
+	/// moves to the end (see [`Id3v2Tag::insert`](crate::id3::v2::Id3v2Tag::insert)), and converting from a [`Tag`](crate::tag::Tag)
+	/// doesn't retain any order, changing a single frame can shuffle the entire tag.
+	///
+	/// With this enabled, the frames of the tag currently in the file are used as a reference:
+	///
+	/// * Frames that already exist in the file keep their relative order, including those that were modified
+	/// * New frames are written after them, in the order they are stored in the tag
+	///
+	/// Frames are matched the same way [`Id3v2Tag::insert`](crate::id3::v2::Id3v2Tag::insert) matches them, by their
+	/// ID and, where applicable, their description, language, etc. This only has an effect when saving to a file
+	/// that already has an ID3v2 tag.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My backups are diffed, only the frames that changed should differ
+	/// let write_options = WriteOptions::new().preserve_id3v2_frame_order(true);
+	/// ```
+	pub fn preserve_id3v2_frame_order(&mut self, preserve_id3v2_frame_order: bool) -> Self {
+		self.preserve_id3v2_frame_order = preserve_id3v2_frame_order;
+		*self
+	}
+
+	/// The amount of padding to leave after a tag, when it has to be rewritten anyway
+	///
+	/// Padding allows a tag to grow on the next write, without having to move the rest of the file.
+	/// It is only added when a tag no longer fits in the space it (and any existing padding) occupied,
+	/// otherwise the existing space is reused.
+	///
+	/// A padding of `0` disables it.
+	///
+	/// Currently, this only applies to the following:
+	///
+	/// * MP4: The size of the `free` atom written after the `ilst` atom. Anything smaller than an
+	///   atom header (8 bytes) is treated as `0`.
+	/// * ID3v2: The zeroes written after the frames, included in the tag size. A tag at the start of
+	///   a file that fits in the space of the previous one is written in place, with the rest of that
+	///   space as padding. Tags in WAV and AIFF files always have it added. It is ignored for tags with
+	///   a footer (see [`Id3v2TagFlags::footer`](crate::id3::v2::Id3v2TagFlags::footer)), which can't
+	///   have padding, and are only written in place if they fit exactly.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // I edit my tags often, leave plenty of room
+	/// let write_options = WriteOptions::new().preferred_padding(8192);
+	/// ```
+	pub fn preferred_padding(&mut self, preferred_padding: u32) -> Self {
+		self.preferred_padding = match preferred_padding {
+			0 => None,
+			padding => Some(padding),
+		};
+		*self
+	}
+
+	/// Whether to write ID3v2 tags to the end of MPEG and AAC files, rather than the start
+	///
+	/// This leaves the start of the file untouched, which is useful for files that are still being
+	/// streamed or recorded. The tag is written with a footer (see [`Id3v2TagFlags::footer`](crate::id3::v2::Id3v2TagFlags::footer)),
+	/// directly after the audio, replacing any tag previously appended there. Any APE and ID3v1 tags
+	/// stay after it.
+	///
+	/// If the file starts with an ID3v2 tag, a `SEEK` frame (see [`SeekFrame`](crate::id3::v2::SeekFrame))
+	/// pointing to the appended tag is written to it in place. The rest of that tag, including its version, is
+	/// left as is. The frames of the appended tag take precedence over the ones in that tag when reading. Without
+	/// a `SEEK` frame, the appended tag is ignored in favor of the one at the start of the file.
+	///
+	/// Saving with this disabled removes any appended tag, leaving a single tag at the start of the file.
+	///
+	/// # Errors
+	///
+	/// When saving, this will error if:
+	///
+	/// * [`WriteOptions::use_id3v23`] is enabled, only ID3v2.4 tags can have a footer
+	/// * The tag at the start of the file has no room for the `SEEK` frame, it would have to be moved
+	/// * The tag at the start of the file isn't ID3v2.4, or has a CRC, so it can't hold the `SEEK` frame
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // The recording is still being streamed, don't touch the start of the file
+	/// let write_options = WriteOptions::new().append_id3v2(true);
+	/// ```
+	pub fn append_id3v2(&mut self, append_id3v2: bool) -> Self {
+		self.append_id3v2 = append_id3v2;
+		*self
+	}
+
+	/// Whether to warn about malformed ISRCs when writing
+	///
+	/// ISRCs are written as-is, so a typo will carry over to every tag it is converted to. With this
+	/// enabled, a warning is logged for every ISRC that can't be parsed as an [`Isrc`](crate::Isrc)
+	/// (with [`ParsingMode::BestAttempt`](crate::ParsingMode::BestAttempt)). Tracks with multiple ISRCs,
+	/// such as medleys, have each of them checked.
+	///
+	/// This applies to ID3v2 `TSRC` frames, MP4 `ISRC` atoms, and `ISRC` items in APE tags and Vorbis Comments.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My ISRCs were typed in by hand
+	/// let write_options = WriteOptions::new().validate_isrc(true);
+	/// ```
+	pub fn validate_isrc(&mut self, validate_isrc: bool) -> Self {
+		self.validate_isrc = validate_isrc;
+		*self
+	}
+
+	/// Whether to make ID3v2 tags fit their restrictions, rather than refusing to write them
+	///
+	/// An ID3v2.4 tag with [`Id3v2TagFlags::restrictions`](crate::id3::v2::Id3v2TagFlags::restrictions) set is
+	/// checked against them when written. By default, any violation results in [`ErrorKind::Id3v2Restrictions`](crate::error::ErrorKind::Id3v2Restrictions).
+	///
+	/// With this enabled, the tag is changed to fit instead, with a warning logged for everything that was changed:
+	///
+	/// * Text encoded as UTF-16 is written as UTF-8
+	/// * Pictures with a disallowed format or dimensions are removed
+	/// * Text fields are truncated, and frames removed, the same way as [`Id3v2Tag::fit_to_limits`](crate::id3::v2::Id3v2Tag::fit_to_limits)
+	/// * Cue sheets that are too long are removed, as they can't be truncated
+	///
+	/// NOTE: This only affects what is written, the tag itself is left untouched.
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::WriteOptions;
+	///
+	/// // My tags are restricted, but I'd rather lose some of them than fail to save
+	/// let write_options = WriteOptions::new().fit_id3v2_restrictions(true);
+	/// ```
+	pub fn fit_id3v2_restrictions(&mut self, fit_id3v2_restrictions: bool) -> Self {
+		self.fit_id3v2_restrictions = fit_id3v2_restrictions;
+		*self
+	}
+
+	/// How strictly to validate values when writing, see [`ParsingMode`]
+	///
+	/// Values that are readable, but not spec-compliant, are written as-is with a warning logged. With
+	/// [`ParsingMode::Strict`], writing the tag will fail instead. Nothing will
+	/// be written to the file.
+	///
+	/// Currently, this only applies to the following:
+	///
+	/// * ID3v2: `TLAN` frames containing something other than ISO-639-2 language codes, resulting in
+	///   [`Id3v2ErrorKind::InvalidLanguageCode`](crate::error::Id3v2ErrorKind::InvalidLanguageCode)
+	///
+	/// # Examples
+	///
+	/// ```rust
+	/// use lofty::{ParsingMode, WriteOptions};
+	///
+	/// // By default, `parsing_mode` is ParsingMode::BestAttempt. Here, only spec-compliant tags may be written.
+	/// let write_options = WriteOptions::new().parsing_mode(ParsingMode::Strict);
+	/// ```
+	pub fn parsing_mode(&mut self, parsing_mode: ParsingMode) -> Self {
+		self.parsing_mode = parsing_mode;
+		*self
+	}
+
+	// Applies `trim_whitespace` and `remove_empty_items` to a text item, `None` meaning it shouldn't be written
+	pub(crate) fn text_item(self, text: &str) -> Option<&str> {
+		let text = if self.trim_whitespace {
+			text.trim()
+		} else {
+			text
+		};
+
+		if text.is_empty() && self.remove_empty_items {
+			return None;
+		}
+
+		Some(text)
+	}
+
+	// Warns about a malformed ISRC, see `validate_isrc`
+	pub(crate) fn check_isrc(self, isrc: &str) {
+		if !self.validate_isrc {
+			return;
+		}
+
+		if let Err(err) = Isrc::parse(isrc, ParsingMode::BestAttempt) {
+			log::warn!("Writing a malformed ISRC {isrc:?}: {err}");
+		}
+	}
+}
+
+/// How to round a fractional BPM
+///
+/// See [`WriteOptions::bpm_rounding`]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum BpmRounding {
+	/// Round to the nearest integer, with halves rounded up (126.5 -> 127)
+	#[default]
+	Nearest,
+	/// Round down (126.5 -> 126)
+	Down,
+	/// Round up (126.2 -> 127)
+	Up,
+}
+
+impl BpmRounding {
+	/// Round a BPM, returning `None` if it doesn't fit in a `u16`
+	pub(crate) fn round(self, bpm: f64) -> Option<u16> {
+		let rounded = match self {
+			BpmRounding::Nearest => bpm.round(),
+			BpmRounding::Down => bpm.floor(),
+			BpmRounding::Up => bpm.ceil(),
+		};
+
+		if !(0.0..=f64::from(u16::MAX)).contains(&rounded) {
+			return None;
+		}
+
+		Some(rounded as u16)
+	}
+}
+
+/// Where to place a new RIFF INFO list
+///
+/// See [`WriteOptions::riff_info_placement`]
+#[derive(Copy, Clone, Debug, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum RiffInfoPlacement {
+	/// Directly after the `data` chunk, or at the end of the file if there isn't one
+	#[default]
+	AfterData,
+	/// After the last chunk in the file
+	EndOfFile,
+}
@@ -1,9 +1,11 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::aac::AacFile;
+use lofty::id3::v2::{Id3v2Tag, Id3v2TagFlags};
 use lofty::{
-	Accessor, FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType,
-	TaggedFileExt,
+	Accessor, AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem,
+	TagType, TaggedFileExt, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{Cursor, Seek, Write};
 
 #[test]
 fn read() {
@@ -94,3 +96,75 @@ fn remove_id3v2() {
 fn remove_id3v1() {
 	crate::remove_tag!("tests/files/assets/minimal/full_test.aac", TagType::Id3v1);
 }
+
+#[test]
+fn save_empty_id3v2() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.aac", TagType::Id3v2);
+}
+
+#[test]
+fn audio_offsets() {
+	// An ID3v2 tag, followed by 4 bytes of junk
+	let content = std::fs::read("tests/files/assets/junk_between_id3_and_adts.aac").unwrap();
+	let aac_file = AacFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let start = aac_file.audio_start_offset() as usize;
+	assert_eq!(start, 287);
+	assert_eq!(content[start], 0xFF);
+	assert_eq!(content[start + 1] & 0xF6, 0xF0);
+
+	let end = aac_file.audio_end_offset() as usize;
+	assert_eq!(end, content.len() - 128);
+	assert_eq!(&content[end..end + 3], b"TAG");
+
+	// An ID3v2 tag, followed by the audio and an ID3v1 tag
+	let content = std::fs::read("tests/files/assets/minimal/full_test.aac").unwrap();
+	let aac_file = AacFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let start = aac_file.audio_start_offset() as usize;
+	assert_eq!(start, 31);
+	assert_eq!(content[start], 0xFF);
+	assert_eq!(content[start + 1] & 0xF6, 0xF0);
+
+	let end = aac_file.audio_end_offset() as usize;
+	assert_eq!(&content[end..end + 3], b"TAG");
+}
+
+#[test]
+fn read_appended_id3v2() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.aac").unwrap();
+	let original_file =
+		AacFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_flags(Id3v2TagFlags {
+		footer: true,
+		..Id3v2TagFlags::default()
+	});
+	id3v2.set_artist(String::from("Qux artist"));
+
+	let mut appended = Vec::new();
+	id3v2
+		.dump_to(&mut appended, WriteOptions::default())
+		.unwrap();
+
+	// Move the ID3v2 tag from the start of the file to the end, before the ID3v1 tag
+	let audio_start = original_file.audio_start_offset() as usize;
+	let audio_end = original_file.audio_end_offset() as usize;
+	let audio = &original[audio_start..audio_end];
+	let content = [audio, &appended, &original[audio_end..]].concat();
+
+	let file = AacFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	assert_eq!(
+		file.id3v2().unwrap().artist().as_deref(),
+		Some("Qux artist")
+	);
+	assert_eq!(
+		file.id3v1().unwrap().artist().as_deref(),
+		Some("Bar artist")
+	);
+
+	// The tag isn't counted as audio
+	assert_eq!(file.audio_end_offset(), audio.len() as u64);
+	assert_eq!(file.properties(), original_file.properties());
+}
@@ -1,6 +1,9 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::iff::aiff::{AiffCompressionType, AiffFile};
+use lofty::iff::SampleFormat;
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType,
+	TaggedFileExt,
 };
 use std::io::{Seek, Write};
 
@@ -67,3 +70,47 @@ fn remove_text_chunks() {
 fn remove_id3v2() {
 	crate::remove_tag!("tests/files/assets/minimal/full_test.aiff", TagType::Id3v2);
 }
+
+#[test]
+fn save_empty_text_chunks() {
+	crate::save_empty_tag!(
+		"tests/files/assets/minimal/full_test.aiff",
+		TagType::AiffText
+	);
+}
+
+#[test]
+fn save_empty_id3v2() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.aiff", TagType::Id3v2);
+}
+
+#[test]
+fn float_aifc() {
+	for (path, compression_type, sample_size) in [
+		(
+			"tests/files/assets/ieee_float_32bit.aifc",
+			AiffCompressionType::fl32,
+			32,
+		),
+		(
+			"tests/files/assets/ieee_float_64bit.aifc",
+			AiffCompressionType::fl64,
+			64,
+		),
+	] {
+		let mut file = std::fs::File::open(path).unwrap();
+		let aiff_file = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+		let properties = aiff_file.properties();
+		assert_eq!(properties.compression_type(), Some(&compression_type));
+		assert_eq!(properties.sample_format(), SampleFormat::FloatPcm);
+		assert_eq!(properties.sample_size(), sample_size);
+		assert_eq!(properties.sample_rate(), 44100);
+		assert_eq!(properties.duration().as_millis(), 10);
+	}
+
+	// Plain AIFF is always integer PCM
+	let mut file = std::fs::File::open("tests/files/assets/minimal/full_test.aiff").unwrap();
+	let aiff_file = AiffFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(aiff_file.properties().sample_format(), SampleFormat::IntPcm);
+}
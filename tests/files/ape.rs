@@ -1,8 +1,10 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::ape::ApeFile;
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, Tag, TagExt, TagItem, TagType,
+	TaggedFileExt, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{Cursor, Seek, Write};
 
 #[test]
 fn read() {
@@ -73,3 +75,35 @@ fn remove_id3v1() {
 fn remove_id3v2() {
 	crate::remove_tag!("tests/files/assets/minimal/full_test.ape", TagType::Id3v2);
 }
+
+#[test]
+fn save_empty_ape() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.ape", TagType::Ape);
+}
+
+#[test]
+fn keep_empty_ape() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.ape");
+
+	Tag::new(TagType::Ape)
+		.save_to(&mut file, WriteOptions::new().remove_tags_if_empty(false))
+		.unwrap();
+
+	file.rewind().unwrap();
+	let tagged_file = lofty::read_from(&mut file).unwrap();
+	assert!(tagged_file.tag(TagType::Ape).unwrap().is_empty());
+}
+
+#[test]
+fn audio_offsets() {
+	// An ID3v2 tag, followed by the audio, an APE tag, and an ID3v1 tag
+	let content = std::fs::read("tests/files/assets/minimal/full_test.ape").unwrap();
+	let ape_file = ApeFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let start = ape_file.audio_start_offset() as usize;
+	assert_eq!(start, 31);
+	assert_eq!(&content[start..start + 4], b"MAC ");
+
+	let end = ape_file.audio_end_offset() as usize;
+	assert_eq!(&content[end..end + 8], b"APETAGEX");
+}
@@ -1,8 +1,10 @@
-use lofty::flac::FlacFile;
-use lofty::{Accessor, AudioFile, ParseOptions, ParsingMode};
+use crate::temp_file;
+use lofty::error::ErrorKind;
+use lofty::flac::{ApplicationBlock, FlacFile};
+use lofty::{Accessor, AudioFile, ParseOptions, ParsingMode, TagExt, WriteOptions};
 
 use std::fs::File;
-use std::io::Seek;
+use std::io::{Read, Seek, Write};
 
 #[test]
 fn multiple_vorbis_comments() {
@@ -29,3 +31,213 @@ fn multiple_vorbis_comments() {
 		Some("Artist 2")
 	);
 }
+
+#[test]
+fn keep_empty_vorbis_comments() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.flac");
+
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new().read_properties(false)).unwrap();
+	let vendor = f.vorbis_comments().unwrap().vendor().to_owned();
+
+	let tag = f.vorbis_comments_mut().unwrap();
+	tag.clear();
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::new().remove_tags_if_empty(false))
+		.unwrap();
+
+	// The `VORBIS_COMMENT` block should still exist, with only the vendor string
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new().read_properties(false)).unwrap();
+
+	let tag = f.vorbis_comments().unwrap();
+	assert!(tag.is_empty());
+	assert_eq!(tag.vendor(), vendor);
+}
+
+// The types of the metadata blocks in a file, and whether they're marked as the last block
+fn block_types(file: &mut File) -> Vec<(u8, bool)> {
+	let mut content = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut content).unwrap();
+	file.rewind().unwrap();
+
+	assert_eq!(&content[..4], b"fLaC");
+
+	let mut blocks = Vec::new();
+	let mut pos = 4;
+	loop {
+		let last = content[pos] & 0x80 != 0;
+		blocks.push((content[pos] & 0x7F, last));
+
+		if last {
+			break blocks;
+		}
+
+		let size = u32::from_be_bytes([0, content[pos + 1], content[pos + 2], content[pos + 3]]);
+		pos += 4 + size as usize;
+	}
+}
+
+#[test]
+fn application_blocks() {
+	const STREAMINFO: u8 = 0;
+	const PADDING: u8 = 1;
+	const APPLICATION: u8 = 2;
+	const VORBIS_COMMENT: u8 = 4;
+
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.flac");
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(f.application_blocks().is_empty());
+
+	let first = ApplicationBlock {
+		id: *b"MYAP",
+		data: vec![1, 2, 3],
+	};
+	let second = ApplicationBlock {
+		id: *b"ATCH",
+		data: Vec::new(),
+	};
+	assert_eq!(f.insert_application_block(first.clone()), None);
+	assert_eq!(f.insert_application_block(second.clone()), None);
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// The new blocks are placed before the padding
+	assert_eq!(
+		block_types(&mut file),
+		[
+			(STREAMINFO, false),
+			(VORBIS_COMMENT, false),
+			(APPLICATION, false),
+			(APPLICATION, false),
+			(PADDING, true)
+		]
+	);
+
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(f.application_blocks(), [first.clone(), second.clone()]);
+	assert_eq!(f.application_block(*b"ATCH"), Some(&second));
+
+	// Saving the tag alone leaves the blocks untouched
+	let tag = f.vorbis_comments_mut().unwrap();
+	tag.set_title(String::from("Foo title"));
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(f.application_blocks(), [first.clone(), second.clone()]);
+
+	// Replacing a block keeps its position
+	let updated = ApplicationBlock {
+		id: *b"MYAP",
+		data: vec![4, 5, 6, 7],
+	};
+	assert_eq!(f.insert_application_block(updated.clone()), Some(first));
+	assert_eq!(f.remove_application_block(*b"ATCH"), Some(second));
+	assert_eq!(f.remove_application_block(*b"ATCH"), None);
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(f.application_blocks(), [updated]);
+	assert_eq!(
+		f.vorbis_comments().unwrap().title().as_deref(),
+		Some("Foo title")
+	);
+	assert_eq!(f.properties().sample_rate(), 48000);
+
+	// Removing every block leaves the original layout
+	f.remove_application_block(*b"MYAP");
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(
+		block_types(&mut file),
+		[
+			(STREAMINFO, false),
+			(VORBIS_COMMENT, false),
+			(PADDING, true)
+		]
+	);
+}
+
+#[test]
+fn oversized_application_block() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.flac");
+	let mut f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// The ID is included in the block size, which is limited to 24 bits
+	f.insert_application_block(ApplicationBlock {
+		id: *b"MYAP",
+		data: vec![0; (1 << 24) - 4],
+	});
+
+	file.rewind().unwrap();
+	let err = f.save_to(&mut file, WriteOptions::default()).unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::TooMuchData));
+
+	// The largest possible block fits
+	f.insert_application_block(ApplicationBlock {
+		id: *b"MYAP",
+		data: vec![0; (1 << 24) - 5],
+	});
+
+	file.rewind().unwrap();
+	f.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let f = FlacFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(
+		f.application_block(*b"MYAP").unwrap().data.len(),
+		(1 << 24) - 5
+	);
+}
+
+#[test]
+fn application_blocks_metaflac() {
+	use std::process::Command;
+
+	// Verify we have metaflac available, otherwise skip
+	match Command::new("metaflac").arg("--version").output() {
+		Err(e) if matches!(e.kind(), std::io::ErrorKind::NotFound) => {
+			eprintln!("Skipping test, `metaflac` is not installed!");
+			return;
+		},
+		Err(e) => panic!("{}", e),
+		_ => {},
+	}
+
+	let mut file = tempfile::NamedTempFile::new().unwrap();
+	file.write_all(&std::fs::read("tests/files/assets/minimal/full_test.flac").unwrap())
+		.unwrap();
+	file.rewind().unwrap();
+
+	let mut f = FlacFile::read_from(file.as_file_mut(), ParseOptions::new()).unwrap();
+	f.insert_application_block(ApplicationBlock {
+		id: *b"MYAP",
+		data: b"analysis".to_vec(),
+	});
+
+	file.rewind().unwrap();
+	f.save_to(file.as_file_mut(), WriteOptions::default())
+		.unwrap();
+
+	let output = Command::new("metaflac")
+		.args(["--list", "--block-type=APPLICATION"])
+		.arg(file.path())
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	assert!(stdout.contains("type: 2 (APPLICATION)"));
+	assert!(stdout.contains("length: 12"));
+	// The ID as an integer, 0x4D594150
+	assert!(stdout.contains("application ID: 4d594150"));
+}
@@ -7,6 +7,7 @@ mod mpc;
 mod mpeg;
 mod ogg;
 pub(crate) mod util;
+mod verify;
 mod wav;
 mod wavpack;
 mod zero_sized;
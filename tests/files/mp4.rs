@@ -1,8 +1,14 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::error::ErrorKind;
+use lofty::id3::v2::Id3v2Tag;
+use lofty::mp4::{AtomData, AtomIdent, Ilst, Mp4Codec, Mp4File};
+use lofty::ogg::VorbisComments;
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	Accessor, AudioFile, FileType, ItemKey, ItemValue, MimeType, ParseOptions, ParsingMode,
+	Picture, PictureType, Probe, Tag, TagExt, TagItem, TagType, TaggedFileExt, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{Cursor, Read, Seek, Write};
+use std::time::Duration;
 
 #[test]
 fn read() {
@@ -55,3 +61,331 @@ fn remove() {
 		TagType::Mp4Ilst
 	);
 }
+
+#[test]
+fn save_empty_ilst() {
+	crate::save_empty_tag!(
+		"tests/files/assets/minimal/m4a_codec_aac.m4a",
+		TagType::Mp4Ilst
+	);
+}
+
+#[test]
+fn keep_empty_ilst() {
+	let mut file = temp_file!("tests/files/assets/minimal/m4a_codec_aac.m4a");
+
+	Tag::new(TagType::Mp4Ilst)
+		.save_to(&mut file, WriteOptions::new().remove_tags_if_empty(false))
+		.unwrap();
+
+	file.rewind().unwrap();
+	let tagged_file = lofty::read_from(&mut file).unwrap();
+	assert!(tagged_file.tag(TagType::Mp4Ilst).unwrap().is_empty());
+}
+
+#[test]
+fn read_fragmented() {
+	let file = Probe::open("tests/files/assets/fragmented.m4a")
+		.unwrap()
+		.read()
+		.unwrap();
+
+	assert_eq!(file.file_type(), FileType::Mp4);
+	assert_eq!(file.properties().duration(), Duration::from_millis(1449));
+
+	crate::verify_artist!(file, primary_tag, "Foo artist", 1);
+}
+
+#[test]
+fn write_fragmented() {
+	let mut file = temp_file!("tests/files/assets/fragmented.m4a");
+
+	let mut tagged_file = Probe::new(&mut file)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	// The new tag fits in the existing padding, so the fragments don't have to move
+	crate::set_artist!(tagged_file, tag_mut, TagType::Mp4Ilst, "Foo artist", 1 => file, "Bar artist");
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(mp4_file.is_fragmented());
+	assert_eq!(
+		mp4_file.properties().duration(),
+		Duration::from_millis(1449)
+	);
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Bar artist")
+	);
+}
+
+#[test]
+fn write_fragmented_too_large() {
+	let mut file = temp_file!("tests/files/assets/fragmented.m4a");
+
+	let mut original = Vec::new();
+	file.read_to_end(&mut original).unwrap();
+	file.rewind().unwrap();
+
+	// Far too large for the existing padding
+	let mut tag = Tag::new(TagType::Mp4Ilst);
+	tag.insert_text(ItemKey::Comment, "A".repeat(4096));
+
+	let err = tag.save_to(&mut file, WriteOptions::default()).unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::FragmentedFile));
+
+	// The file is left untouched
+	file.rewind().unwrap();
+	let mut current = Vec::new();
+	file.read_to_end(&mut current).unwrap();
+	assert_eq!(original, current);
+}
+
+#[test]
+fn read_fragmented_trailing_garbage() {
+	let mut content = std::fs::read("tests/files/assets/fragmented.m4a").unwrap();
+	content.extend_from_slice(&[0; 4]);
+
+	let Err(err) = Mp4File::read_from(
+		&mut Cursor::new(&content),
+		ParseOptions::new().parsing_mode(ParsingMode::Strict),
+	) else {
+		panic!("Expected trailing garbage to be an error in strict mode");
+	};
+	assert!(matches!(err.kind(), ErrorKind::SizeMismatch));
+
+	// Everything up to the garbage is still used
+	let mp4_file = Mp4File::read_from(
+		&mut Cursor::new(&content),
+		ParseOptions::new().parsing_mode(ParsingMode::BestAttempt),
+	)
+	.unwrap();
+	assert_eq!(
+		mp4_file.properties().duration(),
+		Duration::from_millis(1449)
+	);
+}
+
+#[test]
+fn read_fragmented_extended_trun() {
+	let content = std::fs::read("tests/files/assets/fragmented.m4a").unwrap();
+
+	// Rewrite every moof.traf.trun with a 64-bit size, growing its parents to match
+	let mut extended = Vec::with_capacity(content.len());
+	let mut parents = Vec::new();
+	let mut pos = 0;
+	while pos < content.len() {
+		let ident = &content[pos + 4..pos + 8];
+		let len = u32::from_be_bytes(content[pos..pos + 4].try_into().unwrap()) as usize;
+
+		match ident {
+			b"moof" | b"traf" => {
+				parents.push(extended.len());
+				extended.extend_from_slice(&content[pos..pos + 8]);
+				pos += 8;
+			},
+			b"trun" => {
+				extended.extend_from_slice(&1_u32.to_be_bytes());
+				extended.extend_from_slice(b"trun");
+				extended.extend_from_slice(&(len as u64 + 8).to_be_bytes());
+				extended.extend_from_slice(&content[pos + 8..pos + len]);
+				pos += len;
+
+				for parent in parents.drain(..) {
+					let parent_len =
+						u32::from_be_bytes(extended[parent..parent + 4].try_into().unwrap());
+					extended[parent..parent + 4].copy_from_slice(&(parent_len + 8).to_be_bytes());
+				}
+			},
+			_ => {
+				extended.extend_from_slice(&content[pos..pos + len]);
+				pos += len;
+			},
+		}
+	}
+	assert_eq!(extended.len(), content.len() + 16);
+
+	let mp4_file = Mp4File::read_from(
+		&mut Cursor::new(&extended),
+		ParseOptions::new().parsing_mode(ParsingMode::Strict),
+	)
+	.unwrap();
+	assert_eq!(
+		mp4_file.properties().duration(),
+		Duration::from_millis(1449)
+	);
+}
+
+#[test]
+fn read_truncated_tkhd() {
+	let mut content = std::fs::read("tests/files/assets/fragmented.m4a").unwrap();
+
+	// Shrink moov.trak.tkhd to just its header
+	let tkhd = content.windows(4).position(|w| w == b"tkhd").unwrap() - 4;
+	content[tkhd..tkhd + 4].copy_from_slice(&8_u32.to_be_bytes());
+
+	let Err(err) = Mp4File::read_from(&mut Cursor::new(&content), ParseOptions::new()) else {
+		panic!("Expected a truncated tkhd atom to be an error");
+	};
+	assert!(matches!(err.kind(), ErrorKind::FileDecoding(_)));
+}
+
+#[test]
+fn podcast_descriptions() {
+	let mut file = temp_file!("tests/files/assets/minimal/m4a_codec_aac.m4a");
+
+	let descriptions = [
+		(ItemKey::Comment, *b"\xa9cmt", "Episode comment"),
+		(ItemKey::Description, *b"desc", "Short episode description"),
+		(
+			ItemKey::PodcastDescription,
+			*b"ldes",
+			"Long episode description",
+		),
+		(
+			ItemKey::PodcastSeriesDescription,
+			*b"sdes",
+			"Series description",
+		),
+	];
+
+	let mut tag = Tag::new(TagType::Mp4Ilst);
+	for (key, _, value) in &descriptions {
+		tag.insert_text(key.clone(), String::from(*value));
+	}
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Each description is stored in its own atom
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let ilst = mp4_file.ilst().unwrap();
+	for (_, fourcc, value) in &descriptions {
+		let atom = ilst.get(&AtomIdent::Fourcc(*fourcc)).unwrap();
+		assert_eq!(
+			atom.data().next(),
+			Some(&AtomData::UTF8(String::from(*value)))
+		);
+	}
+
+	// And none of them are merged when converting between formats
+	let tag: Tag = ilst.clone().into();
+	for tag_type in [TagType::Id3v2, TagType::VorbisComments] {
+		let converted: Tag = match tag_type {
+			TagType::Id3v2 => Id3v2Tag::from(tag.clone()).into(),
+			_ => VorbisComments::from(tag.clone()).into(),
+		};
+
+		let round_tripped: Tag = Ilst::from(converted).into();
+		for (key, _, value) in &descriptions {
+			assert_eq!(round_tripped.get_string(key), Some(*value), "{tag_type:?}");
+		}
+	}
+}
+
+#[test]
+fn picture_types() {
+	let mut file = temp_file!("tests/files/assets/minimal/m4a_codec_aac.m4a");
+
+	let picture = |pic_type| {
+		Picture::new_unchecked(
+			pic_type,
+			Some(MimeType::Png),
+			None,
+			b"\x89PNG\r\n\x1a\nfoo".to_vec(),
+		)
+	};
+
+	let mut tag = Tag::new(TagType::Mp4Ilst);
+	tag.push_picture(picture(PictureType::CoverFront));
+	tag.push_picture(picture(PictureType::BandLogo));
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// The types are stored alongside `covr`
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+	assert!(contents
+		.windows(b"PICTURE_TYPES".len())
+		.any(|w| w == b"PICTURE_TYPES"));
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let ilst = mp4_file.ilst().unwrap();
+
+	// The atom holding the types isn't exposed
+	assert_eq!(ilst.len(), 1);
+
+	let pic_types = ilst
+		.pictures()
+		.unwrap()
+		.map(Picture::pic_type)
+		.collect::<Vec<_>>();
+	assert_eq!(pic_types, [PictureType::CoverFront, PictureType::BandLogo]);
+
+	let tag: Tag = ilst.clone().into();
+	let pic_types = tag
+		.pictures()
+		.iter()
+		.map(Picture::pic_type)
+		.collect::<Vec<_>>();
+	assert_eq!(pic_types, [PictureType::CoverFront, PictureType::BandLogo]);
+
+	// Nothing extra is written if every picture is `PictureType::Other`
+	let mut file = temp_file!("tests/files/assets/minimal/m4a_codec_aac.m4a");
+	let mut tag = Tag::new(TagType::Mp4Ilst);
+	tag.push_picture(picture(PictureType::Other));
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+	assert!(!contents
+		.windows(b"PICTURE_TYPES".len())
+		.any(|w| w == b"PICTURE_TYPES"));
+}
+
+#[test]
+fn drm_protected() {
+	// Old iTunes purchases replace the `mp4a` sample entry with `drms`, the rest of the
+	// file (including the `ilst`) is left unencrypted
+	let mut content = std::fs::read("tests/files/assets/minimal/m4a_codec_aac.m4a").unwrap();
+	let pos = content.windows(4).position(|w| w == b"mp4a").unwrap();
+	content[pos..pos + 4].copy_from_slice(b"drms");
+
+	let mp4_file = Mp4File::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let properties = mp4_file.properties();
+	assert!(properties.is_drm_protected());
+	assert_eq!(properties.codec(), &Mp4Codec::Unknown);
+	assert_eq!(properties.sample_rate(), 0);
+	assert_eq!(properties.channels(), 0);
+	assert_eq!(properties.audio_bitrate(), 0);
+	assert_eq!(properties.overall_bitrate(), 0);
+
+	assert_eq!(
+		mp4_file.ilst().unwrap().artist().as_deref(),
+		Some("Foo artist")
+	);
+
+	// Not an error when reading through a `TaggedFile` either
+	let tagged_file = Probe::new(Cursor::new(&content))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+	assert_eq!(tagged_file.properties().sample_rate(), None);
+	assert_eq!(tagged_file.properties().channels(), None);
+	crate::verify_artist!(tagged_file, primary_tag, "Foo artist", 1);
+
+	// An unprotected file
+	let mp4_file = Mp4File::read_from(
+		&mut std::fs::File::open("tests/files/assets/minimal/m4a_codec_aac.m4a").unwrap(),
+		ParseOptions::new(),
+	)
+	.unwrap();
+	assert!(!mp4_file.properties().is_drm_protected());
+	assert_eq!(mp4_file.properties().codec(), &Mp4Codec::AAC);
+}
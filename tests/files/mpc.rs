@@ -4,7 +4,7 @@ use lofty::{
 	AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType,
 	TaggedFile, TaggedFileExt,
 };
-use std::io::{Seek, Write};
+use std::io::{Cursor, Seek, Write};
 
 // Marker test so IntelliJ Rust recognizes this as a test module
 #[test]
@@ -106,3 +106,23 @@ fn read_sv5() {
 	// Finally, verify ID3v2 (read only)
 	crate::verify_artist!(file, tag, TagType::Id3v2, "Baz artist", 1);
 }
+
+#[test]
+fn audio_offsets() {
+	for (path, signature) in [
+		("tests/files/assets/minimal/mpc_sv8.mpc", &b"MPCK"[..]),
+		("tests/files/assets/minimal/mpc_sv7.mpc", &b"MP+"[..]),
+		("tests/files/assets/minimal/mpc_sv5.mpc", &[][..]),
+	] {
+		// An ID3v2 tag, followed by the audio, an APE tag, and an ID3v1 tag
+		let content = std::fs::read(path).unwrap();
+		let mpc_file = MpcFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+		let start = mpc_file.audio_start_offset() as usize;
+		assert_eq!(start, 31);
+		assert!(content[start..].starts_with(signature));
+
+		let end = mpc_file.audio_end_offset() as usize;
+		assert_eq!(&content[end..end + 8], b"APETAGEX");
+	}
+}
@@ -1,13 +1,19 @@
 use crate::{set_artist, temp_file, verify_artist};
 use std::borrow::Cow;
 
-use lofty::id3::v2::{Frame, FrameFlags, FrameId, FrameValue, Id3v2Tag, KeyValueFrame};
+use lofty::error::ErrorKind;
+use lofty::id3::v2::{
+	Frame, FrameFlags, FrameId, FrameValue, GeneralEncapsulatedObject, Id3v2Tag, Id3v2TagFlags,
+	KeyValueFrame, Popularimeter, PrivateFrame, SeekFrame,
+};
 use lofty::mpeg::MpegFile;
+use lofty::ogg::VorbisComments;
 use lofty::{
-	Accessor, AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, Tag, TagExt, TagItem,
-	TagType, TaggedFileExt,
+	Accessor, AudioFile, BoundTaggedFile, Conflict, FileType, ItemKey, ItemValue, MergeTag,
+	ParseOptions, Probe, ReconcileAction, ReconcilePolicy, SplitTag, Tag, TagExt, TagItem, TagType,
+	TaggedFileExt, TextEncoding, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{Cursor, Read, Seek, Write};
 
 #[test]
 fn read() {
@@ -56,6 +62,97 @@ fn read_with_junk_bytes_between_frames() {
 	assert_eq!(id3v1_tag.title().as_deref(), Some("title test"));
 }
 
+#[test]
+fn read_with_riff_prefix() {
+	// A file with 300 bytes of a RIFF header before the first frame, which contains
+	// something that looks like a frame sync
+	let mut file = std::fs::File::open("tests/files/assets/riff_prefix.mp3").unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	assert_eq!(mpeg_file.first_frame_offset(), Some(300));
+
+	let properties = mpeg_file.properties();
+	assert_eq!(properties.duration().as_millis(), 1464);
+	assert_eq!(properties.sample_rate(), 48000);
+	assert_eq!(properties.channels(), 2);
+
+	// The frames are too far into the file
+	file.rewind().unwrap();
+	let Err(err) = MpegFile::read_from(&mut file, ParseOptions::new().max_junk_bytes(100)) else {
+		panic!("Expected the frame search to fail");
+	};
+	assert!(
+		err.to_string().contains("ParseOptions::max_junk_bytes"),
+		"{err}"
+	);
+
+	// The frames are only needed for the properties
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(
+		&mut file,
+		ParseOptions::new()
+			.read_properties(false)
+			.max_junk_bytes(100),
+	)
+	.unwrap();
+	assert_eq!(mpeg_file.first_frame_offset(), None);
+}
+
+#[test]
+fn read_with_zero_padding_before_id3v2() {
+	// The same as `full_test.mp3`, with 16 zero bytes before the ID3v2 tag
+	let file = Probe::open("tests/files/assets/zero_padded_id3v2.mp3")
+		.unwrap()
+		.options(ParseOptions::new().read_properties(false))
+		.read()
+		.unwrap();
+
+	assert_eq!(file.file_type(), FileType::Mpeg);
+
+	crate::verify_artist!(file, primary_tag, "Foo artist", 1);
+	crate::verify_artist!(file, tag, TagType::Id3v1, "Bar artist", 1);
+	crate::verify_artist!(file, tag, TagType::Ape, "Baz artist", 1);
+}
+
+#[test]
+fn write_with_zero_padding_before_id3v2() {
+	let mut file = temp_file!("tests/files/assets/zero_padded_id3v2.mp3");
+
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Foo artist", 1 => file, "Bar artist");
+
+	// The existing tag should be replaced, and the padding removed
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+
+	assert!(contents.starts_with(b"ID3"));
+	assert_eq!(
+		contents
+			.windows(3)
+			.filter(|window| window == b"ID3")
+			.count(),
+		1
+	);
+
+	file.rewind().unwrap();
+	let tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	crate::verify_artist!(tagged_file, primary_tag, "Bar artist", 1);
+	crate::verify_artist!(tagged_file, tag, TagType::Id3v1, "Bar artist", 1);
+}
+
 #[test]
 fn issue_82_solidus_in_tag() {
 	let file = Probe::open("tests/files/assets/issue_82_solidus_in_tag.mp3")
@@ -149,7 +246,7 @@ fn save_to_id3v2() {
 	tag.set_title("title".to_string());
 
 	file.rewind().unwrap();
-	tag.save_to(&mut file).unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
 
 	// Now reread the file
 	file.rewind().unwrap();
@@ -190,7 +287,7 @@ fn save_number_of_track_and_disk_to_id3v2() {
 	tag.set_disk(disk);
 
 	file.rewind().unwrap();
-	tag.save_to(&mut file).unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
 
 	// Now reread the file
 	file.rewind().unwrap();
@@ -231,7 +328,7 @@ fn save_total_of_track_and_disk_to_id3v2() {
 	tag.set_disk_total(disk_total);
 
 	file.rewind().unwrap();
-	tag.save_to(&mut file).unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
 
 	// Now reread the file
 	file.rewind().unwrap();
@@ -277,7 +374,7 @@ fn save_number_pair_of_track_and_disk_to_id3v2() {
 	tag.set_disk_total(disk_total);
 
 	file.rewind().unwrap();
-	tag.save_to(&mut file).unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
 
 	// Now reread the file
 	file.rewind().unwrap();
@@ -311,6 +408,108 @@ fn remove_ape() {
 	crate::remove_tag!("tests/files/assets/minimal/full_test.mp3", TagType::Ape);
 }
 
+#[test]
+fn remove_ape_keeps_id3v1() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	TagType::Ape.remove_from(&mut file).unwrap();
+
+	let mut contents = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut contents).unwrap();
+
+	// The ID3v1 tag is moved up to where the APE tag was
+	let ape_start = original
+		.windows(8)
+		.position(|window| window == b"APETAGEX")
+		.unwrap();
+	assert_eq!(contents[..ape_start], original[..ape_start]);
+	assert_eq!(contents[ape_start..], original[original.len() - 128..]);
+
+	file.rewind().unwrap();
+	let tagged_file = lofty::read_from(&mut file).unwrap();
+	assert!(tagged_file.tag(TagType::Ape).is_none());
+	assert!(tagged_file.tag(TagType::Id3v1).is_some());
+}
+
+#[test]
+#[cfg(unix)]
+fn remove_trailing_tags_without_rewrite() {
+	use std::os::unix::fs::MetadataExt;
+
+	const AUDIO_SIZE: u64 = 100 * 1024 * 1024;
+
+	// A sparse file, any rewrite would have to allocate the entire 100MB hole
+	let mut file = tempfile::tempfile().unwrap();
+	let audio = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	file.write_all(&audio).unwrap();
+	file.set_len(AUDIO_SIZE).unwrap();
+
+	let mut tag = Tag::new(TagType::Ape);
+	tag.set_artist(String::from("Foo artist"));
+	file.seek(std::io::SeekFrom::End(0)).unwrap();
+	tag.dump_to(&mut file, WriteOptions::default()).unwrap();
+
+	let mut tag = Tag::new(TagType::Id3v1);
+	tag.set_artist(String::from("Bar artist"));
+	tag.dump_to(&mut file, WriteOptions::default()).unwrap();
+
+	let allocated = file.metadata().unwrap().blocks() * 512;
+
+	file.rewind().unwrap();
+	TagType::Ape.remove_from(&mut file).unwrap();
+	assert_eq!(file.metadata().unwrap().len(), AUDIO_SIZE + 128);
+
+	file.rewind().unwrap();
+	TagType::Id3v1.remove_from(&mut file).unwrap();
+	assert_eq!(file.metadata().unwrap().len(), AUDIO_SIZE);
+
+	// Only the last few KB should have been touched
+	assert!(file.metadata().unwrap().blocks() * 512 <= allocated);
+}
+
+#[test]
+fn save_empty_id3v2() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.mp3", TagType::Id3v2);
+}
+
+#[test]
+fn save_empty_id3v1() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.mp3", TagType::Id3v1);
+}
+
+#[test]
+fn save_empty_ape() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.mp3", TagType::Ape);
+}
+
+#[test]
+fn save_empty_id3v1_no_existing() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+	TagType::Id3v1.remove_from(&mut file).unwrap();
+
+	let len = file.metadata().unwrap().len();
+
+	// Saving an empty tag shouldn't create one
+	file.rewind().unwrap();
+	Tag::new(TagType::Id3v1)
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+	assert_eq!(file.metadata().unwrap().len(), len);
+
+	// Unless we ask for it
+	file.rewind().unwrap();
+	Tag::new(TagType::Id3v1)
+		.save_to(&mut file, WriteOptions::new().remove_tags_if_empty(false))
+		.unwrap();
+	assert_eq!(file.metadata().unwrap().len(), len + 128);
+
+	file.rewind().unwrap();
+	let tagged_file = lofty::read_from(&mut file).unwrap();
+	assert!(tagged_file.tag(TagType::Id3v1).unwrap().is_empty());
+}
+
 #[test]
 fn read_and_write_tpil_frame() {
 	let key_value_pairs = vec![
@@ -337,7 +536,7 @@ fn read_and_write_tpil_frame() {
 	);
 
 	file.rewind().unwrap();
-	tag.save_to(&mut file).unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
 
 	// Now reread the file
 	file.rewind().unwrap();
@@ -356,3 +555,1105 @@ fn read_and_write_tpil_frame() {
 
 	assert_eq!(key_value_pairs, content.key_value_pairs);
 }
+
+#[test]
+fn reconcile_conflicts() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let read = |file: &mut std::fs::File| {
+		file.rewind().unwrap();
+		Probe::new(file).guess_file_type().unwrap().read().unwrap()
+	};
+
+	// Each tag has a different artist
+	let mut tagged_file = read(&mut file);
+	let conflicts = tagged_file.conflicts();
+	assert_eq!(conflicts.len(), 1);
+	assert_eq!(conflicts[0].key(), &ItemKey::TrackArtist);
+	assert_eq!(
+		conflicts[0].values_of(TagType::Ape),
+		Some(&[ItemValue::Text(String::from("Baz artist"))][..])
+	);
+
+	// Keep the APE artist, and write it to every tag in a single save
+	let mut callback = |conflict: &Conflict| {
+		assert_eq!(conflict.key(), &ItemKey::TrackArtist);
+		Some(TagType::Ape)
+	};
+	tagged_file.reconcile(
+		ReconcilePolicy::Manual(&mut callback),
+		ReconcileAction::Overwrite,
+	);
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let mut tagged_file = read(&mut file);
+	assert!(tagged_file.conflicts().is_empty());
+	for tag in tagged_file.tags() {
+		assert_eq!(tag.artist().as_deref(), Some("Baz artist"));
+	}
+
+	// Strip the artist from everything but the primary tag
+	tagged_file
+		.tag_mut(TagType::Id3v1)
+		.unwrap()
+		.set_artist(String::from("Qux artist"));
+	tagged_file.reconcile(ReconcilePolicy::PreferPrimary, ReconcileAction::Remove);
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let tagged_file = read(&mut file);
+	assert_eq!(
+		tagged_file.tag(TagType::Id3v2).unwrap().artist().as_deref(),
+		Some("Baz artist")
+	);
+	// The artist was the only item in the ID3v1 tag, so it is no longer written
+	assert!(tagged_file.tag(TagType::Id3v1).is_none());
+	assert_eq!(
+		tagged_file.tag(TagType::Ape).unwrap().artist().as_deref(),
+		Some("Baz artist")
+	);
+}
+
+#[test]
+fn save_stale_file() {
+	let file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+	let mut other_handle = file.try_clone().unwrap();
+
+	let mut bound_file = BoundTaggedFile::read_from(file, ParseOptions::new()).unwrap();
+	bound_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_artist(String::from("Qux artist"));
+
+	// Another program rewrites the file in the meantime
+	let mut tagged_file = Probe::new(&mut other_handle)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+	tagged_file.remove(TagType::Ape);
+	other_handle.rewind().unwrap();
+	tagged_file
+		.save_to(&mut other_handle, WriteOptions::default())
+		.unwrap();
+
+	let err = bound_file.save(WriteOptions::default()).unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::StaleFile));
+
+	bound_file
+		.save(WriteOptions::default().ignore_stale_file(true))
+		.unwrap();
+
+	// The fingerprint is refreshed after saving
+	bound_file.save(WriteOptions::default()).unwrap();
+
+	other_handle.rewind().unwrap();
+	let tagged_file = Probe::new(&mut other_handle)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+	assert_eq!(
+		tagged_file.tag(TagType::Id3v2).unwrap().artist().as_deref(),
+		Some("Qux artist")
+	);
+}
+
+#[test]
+fn save_to_stale_file() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let mut mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let original_title = mpeg_file.id3v2().unwrap().title().map(Cow::into_owned);
+	mpeg_file
+		.id3v2_mut()
+		.unwrap()
+		.set_artist(String::from("Qux artist"));
+
+	file.rewind().unwrap();
+	let mut tagged_file = Probe::new(&mut file)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	// Someone else saves the file in the meantime, growing the ID3v2 tag
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title("A much longer title ".repeat(100));
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	// The fingerprint is refreshed after saving, so the same file can be saved again
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let err = mpeg_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::StaleFile));
+
+	file.rewind().unwrap();
+	mpeg_file
+		.save_to(&mut file, WriteOptions::default().ignore_stale_file(true))
+		.unwrap();
+
+	// Now the generic file is out of date
+	file.rewind().unwrap();
+	let err = tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::StaleFile));
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.artist().as_deref(), Some("Qux artist"));
+	assert_eq!(id3v2.title().map(Cow::into_owned), original_title);
+}
+
+#[test]
+fn save_lossy_id3v1_genre() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let read = |file: &mut std::fs::File| {
+		file.rewind().unwrap();
+		Probe::new(file).guess_file_type().unwrap().read().unwrap()
+	};
+
+	let mut tagged_file = read(&mut file);
+	let mut id3v1 = tagged_file.tag(TagType::Id3v1).unwrap().clone();
+	id3v1.set_genre(String::from("Liquid DnB"));
+
+	let len = file.metadata().unwrap().len();
+
+	// Writing only the ID3v1 tag can refuse to lose the genre...
+	file.rewind().unwrap();
+	let err = id3v1
+		.save_to(
+			&mut file,
+			WriteOptions::default().error_on_lossy_id3v1_genre(true),
+		)
+		.unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::LossyId3v1Genre(genre) if genre == "Liquid DnB"));
+	assert_eq!(file.metadata().unwrap().len(), len);
+	assert!(read(&mut file)
+		.tag(TagType::Id3v1)
+		.unwrap()
+		.genre()
+		.is_none());
+
+	// ...or write it as unknown
+	file.rewind().unwrap();
+	id3v1.save_to(&mut file, WriteOptions::default()).unwrap();
+	let id3v1_read = read(&mut file);
+	let id3v1_read = id3v1_read.tag(TagType::Id3v1).unwrap();
+	assert!(id3v1_read.genre().is_none());
+	assert_eq!(id3v1_read.title(), id3v1.title());
+
+	// With an ID3v2 tag in the file, the genre is kept there instead
+	assert!(tagged_file.tag(TagType::Id3v2).unwrap().genre().is_none());
+	tagged_file.insert_tag(id3v1);
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(
+			&mut file,
+			WriteOptions::default().error_on_lossy_id3v1_genre(true),
+		)
+		.unwrap();
+
+	let tagged_file = read(&mut file);
+	assert_eq!(
+		tagged_file.tag(TagType::Id3v2).unwrap().genre().as_deref(),
+		Some("Liquid DnB")
+	);
+	assert!(tagged_file.tag(TagType::Id3v1).unwrap().genre().is_none());
+
+	// Representable genres are written as usual
+	let mut id3v1 = tagged_file.tag(TagType::Id3v1).unwrap().clone();
+	id3v1.set_genre(String::from("Progressive Rock"));
+	file.rewind().unwrap();
+	id3v1
+		.save_to(
+			&mut file,
+			WriteOptions::default().error_on_lossy_id3v1_genre(true),
+		)
+		.unwrap();
+	assert_eq!(
+		read(&mut file)
+			.tag(TagType::Id3v1)
+			.unwrap()
+			.genre()
+			.as_deref(),
+		Some("Progressive Rock")
+	);
+}
+
+#[test]
+fn plan_save() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let read_all = |file: &mut std::fs::File| {
+		let mut content = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut content).unwrap();
+		file.rewind().unwrap();
+		content
+	};
+
+	let original = read_all(&mut file);
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+
+	let check_plan = |tagged_file: &lofty::TaggedFile, file: &mut std::fs::File| {
+		let original = read_all(file);
+
+		let plan = tagged_file
+			.plan_save(&*file, WriteOptions::default())
+			.unwrap();
+		assert_eq!(read_all(file), original);
+
+		file.rewind().unwrap();
+		tagged_file.save_to(file, WriteOptions::default()).unwrap();
+
+		// Applying the plan to the original file gives the same result as saving
+		let saved = read_all(file);
+		let mut applied = original;
+		applied.resize(plan.new_len() as usize, 0);
+		for region in plan.regions() {
+			let region = region.start as usize..region.end as usize;
+			applied[region.clone()].copy_from_slice(&saved[region]);
+		}
+		assert_eq!(applied, saved);
+
+		plan
+	};
+
+	// A new frame has to move the audio, and the rewritten tag gets the default padding
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title(String::from("Foo title"));
+	let plan = check_plan(&tagged_file, &mut file);
+	assert!(plan.requires_rewrite());
+	assert_eq!(plan.original_len(), original.len() as u64);
+	assert_eq!(plan.new_len(), original.len() as u64 + 20 + 1024);
+
+	// A title of the same length fits in place
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title(String::from("Bar title"));
+	let plan = check_plan(&tagged_file, &mut file);
+	assert!(!plan.requires_rewrite());
+	assert_eq!(plan.original_len(), plan.new_len());
+	assert_eq!(plan.bytes_written(), 3);
+
+	let tags = plan.tags().collect::<Vec<_>>();
+	assert_eq!(tags.len(), tagged_file.tags().len());
+	let mut id3v2_bytes = Vec::new();
+	tagged_file
+		.tag(TagType::Id3v2)
+		.unwrap()
+		.dump_to(&mut id3v2_bytes, WriteOptions::default())
+		.unwrap();
+	assert!(tags.contains(&(TagType::Id3v2, id3v2_bytes.as_slice())));
+
+	// As does a longer title, using the padding from the first write
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title(String::from("A much longer title"));
+	let plan = check_plan(&tagged_file, &mut file);
+	assert!(!plan.requires_rewrite());
+	assert_eq!(plan.new_len(), original.len() as u64 + 20 + 1024);
+
+	// Saving again changes nothing
+	let plan = check_plan(&tagged_file, &mut file);
+	assert!(plan.is_unchanged());
+
+	// A tag growing in front of the audio moves it, regardless of what happens at the end of the file
+	let id3v1_genre = tagged_file.tag(TagType::Id3v1).unwrap().genre();
+	assert_ne!(id3v1_genre.as_deref(), Some("Jazz"));
+
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title("A much longer title ".repeat(300));
+	tagged_file
+		.tag_mut(TagType::Id3v1)
+		.unwrap()
+		.set_genre(String::from("Jazz"));
+	let plan = check_plan(&tagged_file, &mut file);
+	assert!(plan.requires_rewrite());
+	assert!(plan.new_len() > plan.original_len() + 5000);
+	assert_eq!(plan.regions().last().unwrap().end, plan.new_len());
+}
+
+#[test]
+fn popm_survives_tag_conversion() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	// A 5 star rating, as written by MusicBee and Windows Media Player
+	let ratings = [
+		Popularimeter {
+			email: String::from("MusicBee"),
+			rating: 255,
+			counter: 0,
+		},
+		Popularimeter {
+			email: String::from("Windows Media Player 9 Series"),
+			rating: 255,
+			// Needs a 5 byte counter
+			counter: u64::from(u32::MAX) + 1,
+		},
+	];
+
+	let mut id3v2 = Id3v2Tag::default();
+	for rating in &ratings {
+		id3v2.insert(Frame::new("POPM", rating.clone(), FrameFlags::default()).unwrap());
+	}
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Change an unrelated item through `Tag`, and save again
+	file.rewind().unwrap();
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+	let tag = tagged_file.tag_mut(TagType::Id3v2).unwrap();
+	assert_eq!(tag.get_items(&ItemKey::Popularimeter).count(), 2);
+	tag.set_title(String::from("Foo title"));
+
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+
+	// `Popularimeter`s are only compared by email, so every field has to be checked
+	let popms = id3v2
+		.into_iter()
+		.filter_map(|frame| match frame.content() {
+			FrameValue::Popularimeter(popm) => Some(popm.clone()),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(popms.len(), ratings.len());
+	for (popm, expected) in popms.iter().zip(&ratings) {
+		assert_eq!(popm.email, expected.email);
+		assert_eq!(popm.rating, expected.rating);
+		assert_eq!(popm.counter, expected.counter);
+	}
+}
+
+#[test]
+fn audio_offsets() {
+	// 417 zero bytes before a single frame, and no tags. With nothing to compare the frame to, it
+	// can't be verified, but the audio still starts directly after the padding.
+	let content = std::fs::read("tests/files/assets/junk.mp3").unwrap();
+	let mpeg_file = MpegFile::read_from(
+		&mut Cursor::new(&content),
+		ParseOptions::new().read_properties(false),
+	)
+	.unwrap();
+
+	let start = mpeg_file.audio_start_offset() as usize;
+	assert_eq!(start, 417);
+	assert_eq!(content[start], 0xFF);
+	assert_eq!(content[start + 1] & 0xE0, 0xE0);
+	assert_eq!(mpeg_file.audio_end_offset() as usize, content.len());
+
+	// This is where the two differ, the frame isn't reported as the first one since it wasn't verified
+	assert_eq!(mpeg_file.first_frame_offset(), None);
+
+	// An ID3v2 tag, followed by 4 bytes of junk
+	let content = std::fs::read("tests/files/assets/junk_between_id3_and_mp3.mp3").unwrap();
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let start = mpeg_file.audio_start_offset() as usize;
+	assert_eq!(start, 287);
+	assert_eq!(mpeg_file.first_frame_offset(), Some(287));
+	assert_eq!(content[start], 0xFF);
+	assert_eq!(content[start + 1] & 0xE0, 0xE0);
+
+	let end = mpeg_file.audio_end_offset() as usize;
+	assert_eq!(end, content.len() - 128);
+	assert_eq!(&content[end..end + 3], b"TAG");
+
+	// An ID3v2 tag, followed by the audio, an APE tag, and an ID3v1 tag
+	let content = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+	let start = mpeg_file.audio_start_offset() as usize;
+	assert_eq!(Some(start as u64), mpeg_file.first_frame_offset());
+	assert_eq!(content[start], 0xFF);
+	assert_eq!(content[start + 1] & 0xE0, 0xE0);
+
+	let end = mpeg_file.audio_end_offset() as usize;
+	assert_eq!(&content[end..end + 8], b"APETAGEX");
+}
+
+#[test]
+fn unmapped_frames_survive_tag_conversion() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let geob = GeneralEncapsulatedObject {
+		encoding: TextEncoding::UTF8,
+		mime_type: Some(String::from("text/plain")),
+		file_name: Some(String::from("foo.txt")),
+		description: Some(String::from("Foo description")),
+		data: b"Foo data".to_vec(),
+	};
+	let private = PrivateFrame {
+		owner: String::from("foo@bar.com"),
+		private_data: vec![1, 2, 3, 4],
+	};
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_title(String::from("Bar title"));
+	id3v2.insert(Frame::new("GEOB", geob.clone(), FrameFlags::default()).unwrap());
+	id3v2.insert(Frame::new("PRIV", private.clone(), FrameFlags::default()).unwrap());
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Only change the title through `Tag`
+	file.rewind().unwrap();
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+	tagged_file
+		.tag_mut(TagType::Id3v2)
+		.unwrap()
+		.set_title(String::from("Foo title"));
+
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.title().as_deref(), Some("Foo title"));
+	assert_eq!(id3v2.len(), 3);
+
+	let geob_frame = id3v2.get(&FrameId::Valid(Cow::Borrowed("GEOB"))).unwrap();
+	match geob_frame.content() {
+		FrameValue::GeneralEncapsulatedObject(read_geob) => {
+			assert_eq!(read_geob.mime_type, geob.mime_type);
+			assert_eq!(read_geob.file_name, geob.file_name);
+			assert_eq!(read_geob.description, geob.description);
+			assert_eq!(read_geob.data, geob.data);
+		},
+		content => panic!("Expected a GEOB frame, found {content:?}"),
+	}
+
+	let priv_frame = id3v2.get(&FrameId::Valid(Cow::Borrowed("PRIV"))).unwrap();
+	assert_eq!(priv_frame.content(), &FrameValue::Private(private));
+
+	// Removing the tag through `TaggedFile` takes the frames with it
+	file.rewind().unwrap();
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+	tagged_file.remove(TagType::Id3v2);
+	tagged_file.insert_tag(Tag::new(TagType::Id3v2));
+
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(mpeg_file.id3v2().is_none());
+}
+
+#[test]
+fn itunnorm_survives_comment_edit() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2
+		.set_comment_with(Some(*b"eng"), String::new(), String::from("Foo comment"))
+		.unwrap();
+	id3v2
+		.set_comment_with(
+			Some(*b"eng"),
+			String::from("iTunNORM"),
+			String::from(" 00000A2B 00000A2B 00001D4C 00001D4C"),
+		)
+		.unwrap();
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Only the general comment is visible through `Tag`
+	file.rewind().unwrap();
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+	let tag = tagged_file.tag_mut(TagType::Id3v2).unwrap();
+	assert_eq!(tag.get_strings(&ItemKey::Comment).count(), 1);
+	tag.set_comment(String::from("Bar comment"));
+
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(id3v2.len(), 2);
+	assert_eq!(id3v2.comment_with(Some(*b"eng"), ""), Some("Bar comment"));
+	assert_eq!(
+		id3v2.comment_with(Some(*b"eng"), "iTunNORM"),
+		Some(" 00000A2B 00000A2B 00001D4C 00001D4C")
+	);
+}
+
+#[test]
+fn id3v2_padding() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	// The size of the ID3v2 tag at the start of the file, excluding the header and footer
+	let tag_size = |file: &mut std::fs::File| {
+		let mut header = [0; 10];
+		file.rewind().unwrap();
+		file.read_exact(&mut header).unwrap();
+		file.rewind().unwrap();
+
+		assert_eq!(&header[..3], b"ID3");
+		header[6..]
+			.iter()
+			.fold(0, |size, b| (size << 7) | usize::from(*b))
+	};
+
+	let dumped_size = |tag: &Id3v2Tag| {
+		let mut tag_bytes = Vec::new();
+		tag.dump_to(&mut tag_bytes, WriteOptions::default())
+			.unwrap();
+		tag_bytes.len() - 10
+	};
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_title(String::from("Foo title"));
+	id3v2.set_artist(String::from("Bar artist"));
+	let frames_size = dumped_size(&id3v2);
+
+	id3v2
+		.save_to(&mut file, WriteOptions::new().preferred_padding(2048))
+		.unwrap();
+	assert_eq!(tag_size(&mut file), frames_size + 2048);
+
+	let mut content = Vec::new();
+	file.read_to_end(&mut content).unwrap();
+	assert!(content[10 + frames_size..][..2048].iter().all(|b| *b == 0));
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let read_id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(read_id3v2.title().as_deref(), Some("Foo title"));
+	assert_eq!(read_id3v2.artist().as_deref(), Some("Bar artist"));
+
+	// A smaller tag fits in the space of the previous one, the rest of which becomes padding
+	id3v2.remove_artist();
+	let smaller_frames_size = dumped_size(&id3v2);
+	assert!(smaller_frames_size + 1024 <= tag_size(&mut file));
+
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(tag_size(&mut file), frames_size + 2048);
+
+	// Padding isn't allowed in a tag with a footer
+	id3v2.set_flags(Id3v2TagFlags {
+		footer: true,
+		..Id3v2TagFlags::default()
+	});
+
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+	assert_eq!(tag_size(&mut file), smaller_frames_size);
+
+	let mut content = Vec::new();
+	file.read_to_end(&mut content).unwrap();
+	assert_eq!(&content[10 + smaller_frames_size..][..3], b"3DI");
+}
+
+#[test]
+fn id3v2_in_place() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	// The tag grows, so the first write has to move the audio and leaves room for it to grow again
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_title(String::from("Foo title"));
+	id3v2.set_artist(String::from("Bar artist"));
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let audio_start = MpegFile::read_from(&mut file, ParseOptions::new())
+		.unwrap()
+		.audio_start_offset();
+
+	file.rewind().unwrap();
+	let mut original = Vec::new();
+	file.read_to_end(&mut original).unwrap();
+
+	// Fits within the padding left by the previous write
+	id3v2.set_title(String::from("A title longer than the previous one"));
+	id3v2.set_album(String::from("Baz album"));
+
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mut content = Vec::new();
+	file.read_to_end(&mut content).unwrap();
+
+	// The audio is untouched, at the same offset
+	assert_eq!(content.len(), original.len());
+	assert_eq!(
+		&content[audio_start as usize..],
+		&original[audio_start as usize..]
+	);
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(mpeg_file.audio_start_offset(), audio_start);
+
+	let read_id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(
+		read_id3v2.title().as_deref(),
+		Some("A title longer than the previous one")
+	);
+	assert_eq!(read_id3v2.artist().as_deref(), Some("Bar artist"));
+	assert_eq!(read_id3v2.album().as_deref(), Some("Baz album"));
+}
+
+fn appended_id3v2_tag() -> Vec<u8> {
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_flags(Id3v2TagFlags {
+		footer: true,
+		..Id3v2TagFlags::default()
+	});
+	id3v2.set_artist(String::from("Qux artist"));
+
+	let mut tag = Vec::new();
+	id3v2.dump_to(&mut tag, WriteOptions::default()).unwrap();
+	tag
+}
+
+#[test]
+fn read_appended_id3v2() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let original_file =
+		MpegFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+
+	let audio_start = original_file.audio_start_offset() as usize;
+	let audio_end = original_file.audio_end_offset() as usize;
+	let audio = &original[audio_start..audio_end];
+
+	// The file ends with an APE tag followed by an ID3v1 tag
+	let (ape, id3v1) = original[audio_end..].split_at(original.len() - audio_end - 128);
+	let id3v2 = appended_id3v2_tag();
+
+	// The appended ID3v2 tag can be on either side of the APE tag
+	for layout in [[audio, &id3v2, ape, id3v1], [audio, ape, &id3v2, id3v1]] {
+		let content = layout.concat();
+		let file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+
+		assert_eq!(
+			file.id3v2().unwrap().artist().as_deref(),
+			Some("Qux artist")
+		);
+		assert_eq!(file.ape().unwrap().artist().as_deref(), Some("Baz artist"));
+		assert_eq!(
+			file.id3v1().unwrap().artist().as_deref(),
+			Some("Bar artist")
+		);
+
+		// The tag isn't counted as audio
+		assert_eq!(file.audio_start_offset(), 0);
+		assert_eq!(file.audio_end_offset(), audio.len() as u64);
+		assert_eq!(file.properties(), original_file.properties());
+	}
+}
+
+#[test]
+fn read_appended_id3v2_with_prepended_tag() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let original_file =
+		MpegFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+
+	let audio_end = original_file.audio_end_offset() as usize;
+	let content = [
+		&original[..audio_end],
+		&appended_id3v2_tag(),
+		&original[audio_end..],
+	]
+	.concat();
+
+	// The tag at the start of the file takes precedence
+	let file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	assert_eq!(
+		file.id3v2().unwrap().artist().as_deref(),
+		Some("Foo artist")
+	);
+	assert_eq!(file.audio_end_offset(), audio_end as u64);
+	assert_eq!(file.properties(), original_file.properties());
+}
+
+fn read_file_content(file: &mut std::fs::File) -> Vec<u8> {
+	file.rewind().unwrap();
+
+	let mut content = Vec::new();
+	file.read_to_end(&mut content).unwrap();
+	content
+}
+
+#[test]
+fn write_appended_id3v2() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let original_file =
+		MpegFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+
+	// Only the audio, and the APE and ID3v1 tags
+	let audio_start = original_file.audio_start_offset() as usize;
+	let audio_end = original_file.audio_end_offset() as usize;
+	let untagged = &original[audio_start..];
+
+	let mut file = tempfile::tempfile().unwrap();
+	file.write_all(untagged).unwrap();
+	file.rewind().unwrap();
+
+	let write_options = WriteOptions::default().append_id3v2(true);
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_artist(String::from("Qux artist"));
+	id3v2.save_to(&mut file, write_options).unwrap();
+
+	// The tag is placed between the audio and the APE tag
+	let content = read_file_content(&mut file);
+	let audio_len = audio_end - audio_start;
+	assert_eq!(&content[..audio_len], &untagged[..audio_len]);
+	assert_eq!(&content[audio_len..audio_len + 3], b"ID3");
+	assert!(content.ends_with(&untagged[audio_len..]));
+
+	// Replaces the previously appended tag
+	id3v2.set_artist(String::from("A longer artist"));
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, write_options).unwrap();
+
+	let content = read_file_content(&mut file);
+	assert_eq!(content.windows(3).filter(|w| w == b"3DI").count(), 1);
+	assert!(content.ends_with(&untagged[audio_len..]));
+
+	let file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	assert_eq!(
+		file.id3v2().unwrap().artist().as_deref(),
+		Some("A longer artist")
+	);
+	assert_eq!(file.ape().unwrap().artist().as_deref(), Some("Baz artist"));
+	assert_eq!(
+		file.id3v1().unwrap().artist().as_deref(),
+		Some("Bar artist")
+	);
+	assert_eq!(file.audio_end_offset(), audio_len as u64);
+}
+
+#[test]
+fn write_appended_id3v2_with_seek() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+	let original = read_file_content(&mut file);
+
+	let write_options = WriteOptions::default().append_id3v2(true);
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_artist(String::from("Qux artist"));
+
+	// The tag at the start of the file has no padding, so it can't hold a SEEK frame
+	file.rewind().unwrap();
+	assert!(id3v2.save_to(&mut file, write_options).is_err());
+	assert_eq!(read_file_content(&mut file), original);
+
+	// Leave room in the leading tag
+	let mut leading = Id3v2Tag::default();
+	leading.set_artist(String::from("Foo artist"));
+	leading.set_title(String::from("Foo title"));
+	file.rewind().unwrap();
+	leading.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let before = read_file_content(&mut file);
+	let before_file = MpegFile::read_from(&mut Cursor::new(&before), ParseOptions::new()).unwrap();
+	let leading_len = before_file.audio_start_offset() as usize;
+	let audio = leading_len..before_file.audio_end_offset() as usize;
+
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, write_options).unwrap();
+
+	// The leading tag keeps its size, and the audio is untouched
+	let content = read_file_content(&mut file);
+	assert_eq!(&content[audio.clone()], &before[audio.clone()]);
+
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	assert_eq!(mpeg_file.audio_start_offset(), leading_len as u64);
+
+	// The appended frames take precedence
+	let read_id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(read_id3v2.artist().as_deref(), Some("Qux artist"));
+	assert_eq!(read_id3v2.title().as_deref(), Some("Foo title"));
+
+	let Some(FrameValue::Seek(SeekFrame { offset })) = read_id3v2
+		.get(&FrameId::Valid(Cow::Borrowed("SEEK")))
+		.map(Frame::content)
+	else {
+		panic!("Expected a SEEK frame");
+	};
+	let appended_start = leading_len + *offset as usize;
+	assert_eq!(&content[appended_start..appended_start + 3], b"ID3");
+	assert_eq!(appended_start, audio.end);
+
+	// Only the SEEK frame is added to the leading tag, the rest of it is untouched
+	let seek_start = content.windows(4).position(|w| w == b"SEEK").unwrap();
+	assert_eq!(&content[..seek_start], &before[..seek_start]);
+	assert!(content[seek_start + 14..leading_len]
+		.iter()
+		.all(|b| *b == 0));
+
+	// Saving normally replaces both tags with one at the start of the file
+	file.rewind().unwrap();
+	read_id3v2
+		.clone()
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let content = read_file_content(&mut file);
+	assert!(!content.windows(3).any(|w| w == b"3DI"));
+
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	let read_id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(read_id3v2.artist().as_deref(), Some("Qux artist"));
+	assert!(read_id3v2
+		.get(&FrameId::Valid(Cow::Borrowed("SEEK")))
+		.is_none());
+	assert_eq!(
+		mpeg_file.audio_end_offset() - mpeg_file.audio_start_offset(),
+		audio.len() as u64
+	);
+}
+
+#[test]
+fn write_appended_id3v2_keeps_leading_tag() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+
+	let write_options = WriteOptions::default().append_id3v2(true);
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_artist(String::from("Qux artist"));
+
+	// An ID3v2.3 tag with room to spare, which can't hold a SEEK frame
+	let mut leading = Id3v2Tag::default();
+	leading.set_artist(String::from("Foo artist"));
+	file.rewind().unwrap();
+	leading
+		.save_to(&mut file, WriteOptions::default().use_id3v23(true))
+		.unwrap();
+
+	let before = read_file_content(&mut file);
+	file.rewind().unwrap();
+	assert!(id3v2.save_to(&mut file, write_options).is_err());
+	assert_eq!(read_file_content(&mut file), before);
+
+	// An ID3v2.4 tag with a frame that would be trimmed if it were rewritten
+	let mut leading = Id3v2Tag::default();
+	leading.set_artist(String::from("Foo artist"));
+	leading.set_title(String::from("Foo title  "));
+	file.rewind().unwrap();
+	leading.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let before = read_file_content(&mut file);
+	let leading_len = MpegFile::read_from(&mut Cursor::new(&before), ParseOptions::new())
+		.unwrap()
+		.audio_start_offset() as usize;
+
+	file.rewind().unwrap();
+	id3v2
+		.save_to(
+			&mut file,
+			WriteOptions::default()
+				.append_id3v2(true)
+				.trim_whitespace(true),
+		)
+		.unwrap();
+
+	let after = read_file_content(&mut file);
+	let seek_start = after[..leading_len]
+		.windows(4)
+		.position(|w| w == b"SEEK")
+		.unwrap();
+	assert_eq!(&after[..seek_start], &before[..seek_start]);
+
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&after), ParseOptions::new()).unwrap();
+	let read_id3v2 = mpeg_file.id3v2().unwrap();
+	assert_eq!(read_id3v2.artist().as_deref(), Some("Qux artist"));
+	assert_eq!(read_id3v2.title().as_deref(), Some("Foo title  "));
+}
+
+#[test]
+fn ape_before_appended_id3v2() {
+	let original = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	let original_file =
+		MpegFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+
+	let audio_start = original_file.audio_start_offset() as usize;
+	let audio_end = original_file.audio_end_offset() as usize;
+	let audio = &original[audio_start..audio_end];
+	let (ape, id3v1) = original[audio_end..].split_at(original.len() - audio_end - 128);
+	let id3v2 = appended_id3v2_tag();
+
+	let mut file = tempfile::tempfile().unwrap();
+	file.write_all(&[audio, ape, &id3v2, id3v1].concat())
+		.unwrap();
+
+	// The existing APE tag is replaced, rather than a new one being written after the ID3v2 tag
+	let mut tag = Tag::new(TagType::Ape);
+	tag.set_artist(String::from("Quux artist"));
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let content = read_file_content(&mut file);
+	assert_eq!(content.windows(8).filter(|w| w == b"APETAGEX").count(), 2);
+	assert!(content.starts_with(audio));
+	assert!(content.ends_with(&[&id3v2, id3v1].concat()));
+
+	let mpeg_file = MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+	assert_eq!(
+		mpeg_file.ape().unwrap().artist().as_deref(),
+		Some("Quux artist")
+	);
+	assert_eq!(
+		mpeg_file.id3v2().unwrap().artist().as_deref(),
+		Some("Qux artist")
+	);
+
+	// Removing the trailing tags leaves the ID3v2 tag in place
+	file.rewind().unwrap();
+	TagType::Ape.remove_from(&mut file).unwrap();
+	assert_eq!(
+		read_file_content(&mut file),
+		[audio, &id3v2, id3v1].concat()
+	);
+
+	file.rewind().unwrap();
+	TagType::Id3v1.remove_from(&mut file).unwrap();
+	assert_eq!(read_file_content(&mut file), [audio, &id3v2].concat());
+}
+
+#[test]
+fn rewrite_id3v2_with_extended_header() {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+	let original = read_file_content(&mut file);
+	let original_file =
+		MpegFile::read_from(&mut Cursor::new(&original), ParseOptions::new()).unwrap();
+	let audio =
+		original_file.audio_start_offset() as usize..original_file.audio_end_offset() as usize;
+
+	// A CRC is stored in the extended header
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.set_flags(Id3v2TagFlags {
+		crc: true,
+		..Id3v2TagFlags::default()
+	});
+	id3v2.set_title(String::from("Foo title"));
+
+	for _ in 0..2 {
+		file.rewind().unwrap();
+		id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		let content = read_file_content(&mut file);
+		let mpeg_file =
+			MpegFile::read_from(&mut Cursor::new(&content), ParseOptions::new()).unwrap();
+		let start = mpeg_file.audio_start_offset() as usize;
+		assert_eq!(
+			&content[start..start + audio.len()],
+			&original[audio.clone()]
+		);
+		assert_eq!(
+			mpeg_file.id3v2().unwrap().title().as_deref(),
+			Some("Foo title")
+		);
+	}
+}
+
+#[test]
+fn musicbrainz_ufid_round_trip() {
+	const RECORDING_ID: &str = "189002e7-3285-4e2e-92a3-7f6c30d407a2";
+	const CDDB_OWNER: &str = "http://www.cddb.com/id3/taginfo1.html";
+
+	fn ufid_owners(id3v2: &Id3v2Tag) -> Vec<(&str, &[u8])> {
+		id3v2
+			.into_iter()
+			.filter_map(|frame| match frame.content() {
+				FrameValue::UniqueFileIdentifier(ufid) => {
+					Some((ufid.owner.as_str(), ufid.identifier.as_slice()))
+				},
+				_ => None,
+			})
+			.collect()
+	}
+
+	// Tagged the way MusicBrainz Picard does, with another UFID frame from a different owner
+	let mut file = temp_file!("tests/files/assets/picard_ufid.mp3");
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let id3v2 = mpeg_file.id3v2().unwrap().clone();
+	assert_eq!(
+		ufid_owners(&id3v2),
+		[
+			("http://musicbrainz.org", RECORDING_ID.as_bytes()),
+			(CDDB_OWNER, &b"3CD3N48Q24867\0"[..]),
+		]
+	);
+
+	let (remainder, mut tag) = id3v2.split_tag();
+	assert_eq!(
+		tag.get_string(&ItemKey::MusicBrainzRecordingId),
+		Some(RECORDING_ID)
+	);
+	assert_eq!(
+		ufid_owners(&remainder),
+		[(CDDB_OWNER, &b"3CD3N48Q24867\0"[..])]
+	);
+
+	// Both frames are written back
+	tag.set_title(String::from("New title"));
+	let id3v2 = remainder.merge_tag(tag.clone());
+	file.rewind().unwrap();
+	id3v2.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let written = mpeg_file.id3v2().unwrap();
+	assert_eq!(written.title().as_deref(), Some("New title"));
+	assert_eq!(ufid_owners(written).len(), 2);
+	assert!(ufid_owners(written).contains(&("http://musicbrainz.org", RECORDING_ID.as_bytes())));
+
+	// Writing the generic tag directly, and after a round trip through Vorbis comments
+	let mut from_vorbis_comments = Tag::from(VorbisComments::from(tag.clone()));
+	from_vorbis_comments.re_map(TagType::Id3v2);
+
+	for tag in [tag, from_vorbis_comments] {
+		let mut file = temp_file!("tests/files/assets/minimal/full_test.mp3");
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		file.rewind().unwrap();
+		let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+		assert_eq!(
+			ufid_owners(mpeg_file.id3v2().unwrap()),
+			[("http://musicbrainz.org", RECORDING_ID.as_bytes())]
+		);
+	}
+}
@@ -1,7 +1,10 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::ogg::{OpusFile, SpeexFile, VorbisFile};
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType,
+	TaggedFileExt, WriteOptions,
 };
+use ogg_pager::Page;
 use std::io::{Seek, Write};
 
 // The tests for OGG Opus/Vorbis are nearly identical
@@ -62,6 +65,81 @@ fn vorbis_remove() {
 	)
 }
 
+#[test]
+fn vorbis_skeleton_read() {
+	read("tests/files/assets/skeleton.ogg", FileType::Vorbis)
+}
+
+#[test]
+fn vorbis_skeleton_write() {
+	write("tests/files/assets/skeleton.ogg", FileType::Vorbis);
+	skeleton_preserved("tests/files/assets/skeleton.ogg");
+}
+
+#[test]
+fn opus_skeleton_read() {
+	read("tests/files/assets/skeleton.opus", FileType::Opus)
+}
+
+#[test]
+fn opus_skeleton_write() {
+	write("tests/files/assets/skeleton.opus", FileType::Opus);
+	skeleton_preserved("tests/files/assets/skeleton.opus");
+}
+
+#[test]
+fn skeleton_properties() {
+	for (skeleton, plain) in [
+		(
+			"tests/files/assets/skeleton.ogg",
+			"tests/files/assets/minimal/full_test.ogg",
+		),
+		(
+			"tests/files/assets/skeleton.opus",
+			"tests/files/assets/minimal/full_test.opus",
+		),
+	] {
+		let skeleton = lofty::read_from_path(skeleton).unwrap();
+		let plain = lofty::read_from_path(plain).unwrap();
+
+		assert_eq!(
+			skeleton.properties().duration(),
+			plain.properties().duration()
+		);
+		assert_eq!(
+			skeleton.properties().sample_rate(),
+			plain.properties().sample_rate()
+		);
+	}
+}
+
+#[test]
+fn vorbis_page_headers_preserved() {
+	page_headers_preserved("tests/files/assets/minimal/full_test.ogg", |file| {
+		VorbisFile::read_from(file, ParseOptions::new())
+			.unwrap()
+			.stream_serial()
+	});
+}
+
+#[test]
+fn opus_page_headers_preserved() {
+	page_headers_preserved("tests/files/assets/minimal/full_test.opus", |file| {
+		OpusFile::read_from(file, ParseOptions::new())
+			.unwrap()
+			.stream_serial()
+	});
+}
+
+#[test]
+fn speex_page_headers_preserved() {
+	page_headers_preserved("tests/files/assets/minimal/full_test.spx", |file| {
+		SpeexFile::read_from(file, ParseOptions::new())
+			.unwrap()
+			.stream_serial()
+	});
+}
+
 #[test]
 fn speex_read() {
 	read("tests/files/assets/minimal/full_test.spx", FileType::Speex)
@@ -118,6 +196,119 @@ fn write(path: &str, file_type: FileType) {
 	crate::set_artist!(tagged_file, primary_tag_mut, "Bar artist", 2 => file, "Foo artist");
 }
 
+fn pages(file: &mut std::fs::File) -> Vec<Page> {
+	let mut pages = Vec::new();
+
+	file.rewind().unwrap();
+	while let Ok(page) = Page::read(file) {
+		pages.push(page);
+	}
+
+	pages
+}
+
+// Change the comments of a file with an Ogg Skeleton stream, and verify that only the
+// audio stream's header pages were changed
+fn skeleton_preserved(path: &str) {
+	let mut file = temp_file!(path);
+	let original_pages = pages(&mut file);
+
+	let skeleton_serial = original_pages[0].header().stream_serial;
+	let audio_serial = original_pages[1].header().stream_serial;
+	assert_ne!(audio_serial, skeleton_serial);
+
+	file.rewind().unwrap();
+	let mut tagged_file = Probe::new(&mut file)
+		.options(ParseOptions::new().read_properties(false))
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+
+	crate::set_artist!(tagged_file, primary_tag_mut, "Foo artist", 2 => file, "Bar artist");
+
+	let new_pages = pages(&mut file);
+
+	// The skeleton pages should be untouched
+	let skeleton_pages = |pages: &[Page]| {
+		pages
+			.iter()
+			.filter(|page| page.header().stream_serial == skeleton_serial)
+			.map(Page::as_bytes)
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(skeleton_pages(&original_pages), skeleton_pages(&new_pages));
+
+	// The header pages may be paginated differently, but the streams should be interleaved the same way
+	let interleave = |pages: &[Page]| {
+		let mut serials = pages
+			.iter()
+			.map(|page| page.header().stream_serial)
+			.collect::<Vec<_>>();
+		serials.dedup();
+		serials
+	};
+	assert_eq!(interleave(&original_pages), interleave(&new_pages));
+
+	// The audio data itself shouldn't change
+	let audio_pages = |pages: &[Page]| {
+		pages
+			.iter()
+			.filter(|page| page.header().stream_serial == audio_serial && page.header().abgp != 0)
+			.map(|page| (page.header().abgp, page.content().to_vec()))
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(audio_pages(&original_pages), audio_pages(&new_pages));
+}
+
+// Change the size of the comment header, and verify that every page keeps its serial, sequence number,
+// flags, and granule position. Only the pages holding the comment header may have different content.
+fn page_headers_preserved(path: &str, stream_serial: fn(&mut std::fs::File) -> u32) {
+	// Everything preceding the CRC
+	const HEADER_FIELDS: std::ops::Range<usize> = 0..22;
+
+	let mut file = temp_file!(path);
+	let original_pages = pages(&mut file);
+
+	file.rewind().unwrap();
+	let serial = stream_serial(&mut file);
+	assert_eq!(serial, original_pages[0].header().stream_serial);
+
+	file.rewind().unwrap();
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+	let tag = tagged_file.primary_tag_mut().unwrap();
+	tag.insert_text(
+		ItemKey::TrackArtist,
+		String::from("An artist name longer than the original"),
+	);
+
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	let new_pages = pages(&mut file);
+	assert_eq!(original_pages.len(), new_pages.len());
+
+	file.rewind().unwrap();
+	assert_eq!(stream_serial(&mut file), serial);
+
+	// The header pages following the identification header, before any audio
+	let comment_pages = original_pages
+		.iter()
+		.skip(1)
+		.take_while(|page| matches!(page.header().abgp, 0 | u64::MAX))
+		.count();
+
+	for (idx, (original, new)) in original_pages.iter().zip(&new_pages).enumerate() {
+		let (original, new) = (original.as_bytes(), new.as_bytes());
+		if (1..=comment_pages).contains(&idx) {
+			assert_eq!(original[HEADER_FIELDS], new[HEADER_FIELDS], "page {idx}");
+			assert_ne!(original, new, "page {idx}");
+		} else {
+			assert_eq!(original, new, "page {idx}");
+		}
+	}
+}
+
 fn remove(path: &str, tag_type: TagType) {
 	let mut file = temp_file!(path);
 
@@ -170,15 +361,67 @@ fn flac_remove_id3v2() {
 	crate::remove_tag!("tests/files/assets/flac_with_id3v2.flac", TagType::Id3v2);
 }
 
+#[test]
+fn flac_save_empty_vorbis_comments() {
+	crate::save_empty_tag!(
+		"tests/files/assets/minimal/full_test.flac",
+		TagType::VorbisComments
+	);
+}
+
 #[test]
 fn flac_try_write_non_empty_id3v2() {
 	use lofty::id3::v2::Id3v2Tag;
-	use lofty::Accessor;
+	use lofty::{Accessor, WriteOptions};
 
 	let mut tag = Id3v2Tag::default();
 	tag.set_artist(String::from("Foo artist"));
 
 	assert!(tag
-		.save_to_path("tests/files/assets/flac_with_id3v2.flac")
+		.save_to_path(
+			"tests/files/assets/flac_with_id3v2.flac",
+			WriteOptions::default()
+		)
 		.is_err());
 }
+
+#[test]
+fn opus_oversized_picture() {
+	use lofty::error::ErrorKind;
+	use lofty::{MimeType, Picture, PictureType, WriteOptions};
+
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.opus");
+	let mut tagged_file = lofty::read_from(&mut file).unwrap();
+
+	let picture = Picture::new_unchecked(
+		PictureType::CoverFront,
+		Some(MimeType::Png),
+		None,
+		vec![0; 3000],
+	);
+	let encoded_size = picture.encoded_size_for(TagType::VorbisComments).unwrap();
+	assert!(encoded_size > 4000);
+
+	tagged_file.primary_tag_mut().unwrap().push_picture(picture);
+
+	// Only a warning by default
+	let mut write_options = WriteOptions::default().max_picture_size(Some(encoded_size - 1));
+	file.rewind().unwrap();
+	tagged_file.save_to(&mut file, write_options).unwrap();
+
+	let err = tagged_file
+		.save_to(&mut file, write_options.error_on_oversized_pictures(true))
+		.unwrap_err();
+	assert!(matches!(err.kind(), ErrorKind::PictureTooLarge(size) if *size == encoded_size));
+
+	// The limit is inclusive
+	file.rewind().unwrap();
+	tagged_file
+		.save_to(
+			&mut file,
+			WriteOptions::default()
+				.max_picture_size(Some(encoded_size))
+				.error_on_oversized_pictures(true),
+		)
+		.unwrap();
+}
@@ -69,7 +69,8 @@ macro_rules! set_artist {
 
 		$file_write.rewind().unwrap();
 
-		$tag.save_to(&mut $file_write).unwrap();
+		$tag.save_to(&mut $file_write, lofty::WriteOptions::default())
+			.unwrap();
 	};
 }
 
@@ -101,3 +102,25 @@ macro_rules! remove_tag {
 		assert!(tagged_file.tag($tag_type).is_none());
 	};
 }
+
+#[macro_export]
+macro_rules! save_empty_tag {
+	($path:tt, $tag_type:path) => {
+		let mut file = temp_file!($path);
+
+		let mut tagged_file = lofty::read_from(&mut file).unwrap();
+		let tag = tagged_file.tag_mut($tag_type).unwrap();
+		assert!(!tag.is_empty());
+
+		tag.clear();
+
+		file.rewind().unwrap();
+		tag.save_to(&mut file, lofty::WriteOptions::default())
+			.unwrap();
+
+		// The tag should be gone, without affecting the rest of the file
+		file.rewind().unwrap();
+		let tagged_file = lofty::read_from(&mut file).unwrap();
+		assert!(tagged_file.tag($tag_type).is_none());
+	};
+}
@@ -0,0 +1,184 @@
+use lofty::id3::v2::{Id3v2Tag, Id3v2TagFlags};
+use lofty::verify::{verify, verify_path, IssueKind};
+use lofty::{Accessor, FileType, TagExt, WriteOptions};
+
+use std::io::Cursor;
+
+fn verify_bytes(bytes: Vec<u8>, file_type: FileType) -> Vec<IssueKind> {
+	verify(&mut Cursor::new(bytes), file_type)
+		.unwrap()
+		.into_iter()
+		.map(|issue| issue.kind())
+		.collect()
+}
+
+fn find_last(haystack: &[u8], needle: &[u8]) -> usize {
+	haystack
+		.windows(needle.len())
+		.rposition(|window| window == needle)
+		.unwrap()
+}
+
+#[test]
+fn valid_files() {
+	for path in [
+		"tests/files/assets/minimal/full_test.aac",
+		"tests/files/assets/minimal/full_test.aiff",
+		"tests/files/assets/minimal/full_test.ape",
+		"tests/files/assets/minimal/full_test.flac",
+		"tests/files/assets/minimal/full_test.mp3",
+		"tests/files/assets/minimal/full_test.ogg",
+		"tests/files/assets/minimal/full_test.opus",
+		"tests/files/assets/minimal/full_test.spx",
+		"tests/files/assets/minimal/m4a_codec_aac.m4a",
+		"tests/files/assets/minimal/wav_format_pcm.wav",
+		"tests/files/assets/minimal/full_test.wv",
+		"tests/files/assets/skeleton.ogg",
+		"tests/files/assets/fragmented.m4a",
+	] {
+		assert!(verify_path(path).unwrap().is_empty(), "{path}");
+	}
+}
+
+#[test]
+fn ogg_page_crc() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/full_test.ogg").unwrap();
+
+	// Corrupt the last byte of the second page, and the last byte of the file (the last page)
+	let second_page = find_last(&bytes[..8192], b"OggS");
+	bytes[second_page - 1] ^= 0xFF;
+	*bytes.last_mut().unwrap() ^= 0xFF;
+
+	let issues = verify(&mut Cursor::new(bytes), FileType::Vorbis).unwrap();
+
+	// Every page is checked, not just the first bad one
+	assert_eq!(issues.len(), 2);
+	for issue in issues {
+		assert!(matches!(
+			issue.kind(),
+			IssueKind::ChecksumMismatch { stored, calculated } if stored != calculated
+		));
+	}
+}
+
+#[test]
+fn id3v2_extended_header_crc() {
+	let mut tag = Id3v2Tag::default();
+	tag.set_artist(String::from("Foo artist"));
+	tag.set_flags(Id3v2TagFlags {
+		crc: true,
+		..Id3v2TagFlags::default()
+	});
+
+	let mut bytes = Vec::new();
+	tag.dump_to(&mut bytes, WriteOptions::default()).unwrap();
+
+	assert!(verify_bytes(bytes.clone(), FileType::Mpeg).is_empty());
+
+	// Change the artist
+	*bytes.last_mut().unwrap() = b'X';
+
+	let issues = verify_bytes(bytes, FileType::Mpeg);
+	assert_eq!(issues.len(), 1);
+	assert!(matches!(issues[0], IssueKind::ChecksumMismatch { .. }));
+}
+
+#[test]
+fn id3v2_size() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/full_test.mp3").unwrap();
+	bytes.truncate(20);
+
+	let issues = verify_bytes(bytes, FileType::Mpeg);
+	assert_eq!(issues.len(), 1);
+	assert!(matches!(
+		issues[0],
+		IssueKind::SizeMismatch {
+			stored: 21,
+			actual: 10
+		}
+	));
+}
+
+#[test]
+fn flac_streaminfo() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/full_test.flac").unwrap();
+	let streaminfo = find_last(&bytes[..64], b"fLaC") + 8;
+
+	// Minimum block size of 0, and a sample rate of 0
+	bytes[streaminfo] = 0;
+	bytes[streaminfo + 1] = 0;
+	bytes[streaminfo + 10] = 0;
+	bytes[streaminfo + 11] = 0;
+	bytes[streaminfo + 12] &= 0x0F;
+
+	let issues = verify_bytes(bytes, FileType::Flac);
+	assert_eq!(
+		issues,
+		[IssueKind::InvalidStructure, IssueKind::InvalidStructure]
+	);
+}
+
+#[test]
+fn flac_block_size() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/full_test.flac").unwrap();
+	let streaminfo_header = find_last(&bytes[..64], b"fLaC") + 4;
+
+	// STREAMINFO is always 34 bytes
+	bytes[streaminfo_header + 3] = 35;
+
+	let issues = verify_bytes(bytes, FileType::Flac);
+	assert!(matches!(
+		issues[0],
+		IssueKind::SizeMismatch {
+			stored: 35,
+			actual: 34
+		}
+	));
+}
+
+#[test]
+fn mp4_atom_sizes() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/m4a_codec_aac.m4a").unwrap();
+	let len = bytes.len();
+	bytes.truncate(len - 10);
+
+	let issues = verify_bytes(bytes, FileType::Mp4);
+	assert_eq!(issues.len(), 1);
+	assert!(
+		matches!(issues[0], IssueKind::SizeMismatch { stored, actual } if stored == actual + 10)
+	);
+}
+
+#[test]
+fn riff_size() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/wav_format_pcm.wav").unwrap();
+	let len = bytes.len() as u32;
+	bytes[4..8].copy_from_slice(&len.to_le_bytes());
+
+	let issues = verify_bytes(bytes, FileType::Wav);
+	assert_eq!(
+		issues,
+		[IssueKind::SizeMismatch {
+			stored: u64::from(len),
+			actual: u64::from(len) - 8
+		}]
+	);
+}
+
+#[test]
+fn ape_item_count() {
+	let mut bytes = std::fs::read("tests/files/assets/minimal/full_test.ape").unwrap();
+	let footer = find_last(&bytes, b"APETAGEX");
+
+	let item_count = u32::from_le_bytes(bytes[footer + 16..footer + 20].try_into().unwrap());
+	bytes[footer + 16..footer + 20].copy_from_slice(&(item_count + 1).to_le_bytes());
+
+	let issues = verify_bytes(bytes, FileType::Ape);
+	assert_eq!(
+		issues,
+		[IssueKind::CountMismatch {
+			stored: item_count + 1,
+			actual: item_count
+		}]
+	);
+}
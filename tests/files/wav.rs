@@ -1,8 +1,11 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::iff::wav::{BextChunk, ListChunk, RIFFInfoList, WavFile, WavFormat};
+use lofty::iff::SampleFormat;
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	AudioFile, FileType, ItemKey, ItemValue, ParseOptions, Probe, RiffInfoPlacement, TagExt,
+	TagItem, TagType, TaggedFileExt, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{BufReader, Read, Seek, Write};
 
 #[test]
 fn read() {
@@ -71,6 +74,22 @@ fn remove_riff_info() {
 	);
 }
 
+#[test]
+fn save_empty_riff_info() {
+	crate::save_empty_tag!(
+		"tests/files/assets/minimal/wav_format_pcm.wav",
+		TagType::RiffInfo
+	);
+}
+
+#[test]
+fn save_empty_id3v2() {
+	crate::save_empty_tag!(
+		"tests/files/assets/minimal/wav_format_pcm.wav",
+		TagType::Id3v2
+	);
+}
+
 #[test]
 fn issue_174_divide_by_zero() {
 	let file = Probe::open(
@@ -82,3 +101,396 @@ fn issue_174_divide_by_zero() {
 
 	assert_eq!(file.file_type(), FileType::Wav);
 }
+
+#[test]
+fn float_64bit() {
+	let mut file = std::fs::File::open("tests/files/assets/ieee_float_64bit.wav").unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let properties = wav_file.properties();
+	assert_eq!(properties.format(), &WavFormat::IEEE_FLOAT);
+	assert_eq!(properties.sample_format(), SampleFormat::FloatPcm);
+	assert_eq!(properties.bit_depth(), 64);
+	assert_eq!(properties.sample_rate(), 44100);
+	assert_eq!(properties.duration().as_millis(), 10);
+}
+
+#[test]
+fn extensible_valid_bits() {
+	// 24-bit samples, stored in 32-bit containers
+	let mut file = std::fs::File::open(
+		"tests/files/assets/hound/waveformatextensible-24bit-4byte-48kHz-stereo.wav",
+	)
+	.unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let properties = wav_file.properties();
+	assert_eq!(properties.bit_depth(), 24);
+	assert_eq!(properties.container_bit_depth(), 32);
+	assert_eq!(properties.valid_bit_depth(), Some(24));
+
+	// Not extensible, so there is no valid bits field
+	let mut file = std::fs::File::open("tests/files/assets/ieee_float_64bit.wav").unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let properties = wav_file.properties();
+	assert_eq!(properties.bit_depth(), 64);
+	assert_eq!(properties.container_bit_depth(), 64);
+	assert_eq!(properties.valid_bit_depth(), None);
+}
+
+// (fourcc, list type, content) for each top level chunk
+fn chunk_tree(file: &mut std::fs::File) -> Vec<([u8; 4], Option<[u8; 4]>, Vec<u8>)> {
+	let mut bytes = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut bytes).unwrap();
+
+	let mut tree = Vec::new();
+
+	let mut pos = 12;
+	while pos + 8 <= bytes.len() {
+		let fourcc: [u8; 4] = bytes[pos..pos + 4].try_into().unwrap();
+		let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+		let content = bytes[pos + 8..pos + 8 + size].to_vec();
+
+		let list_type = (&fourcc == b"LIST").then(|| content[..4].try_into().unwrap());
+		tree.push((fourcc, list_type, content));
+
+		pos += 8 + size + (size % 2);
+	}
+
+	tree
+}
+
+#[test]
+fn read_non_info_lists() {
+	let mut file = std::fs::File::open("tests/files/assets/wavl_list.wav").unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let lists = wav_file.list_chunks();
+	assert_eq!(lists.len(), 2);
+
+	assert_eq!(lists[0].list_type(), *b"wavl");
+	assert_eq!(lists[0].size(), 16);
+	assert_eq!(lists[1].list_type(), *b"adtl");
+	assert_eq!(lists[1].size(), 20);
+
+	// The INFO list following them should still be found
+	let riff_info = wav_file.riff_info().unwrap();
+	assert_eq!(riff_info.get("IART"), Some("Bar artist"));
+}
+
+#[test]
+fn write_preserves_non_info_lists() {
+	let mut file = temp_file!("tests/files/assets/wavl_list.wav");
+	let original_tree = chunk_tree(&mut file);
+
+	file.rewind().unwrap();
+	let original_lists: Vec<ListChunk> = WavFile::read_from(&mut file, ParseOptions::new())
+		.unwrap()
+		.list_chunks()
+		.to_vec();
+
+	let mut riff_info = RIFFInfoList::default();
+	riff_info.insert(String::from("IART"), String::from("Baz artist"));
+	riff_info.insert(String::from("INAM"), String::from("Foo title"));
+
+	file.rewind().unwrap();
+	riff_info
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let new_tree = chunk_tree(&mut file);
+	assert_eq!(original_tree.len(), new_tree.len());
+
+	// Only the INFO list should have changed, every other chunk should be in the same place
+	for (original, new) in original_tree.iter().zip(&new_tree) {
+		assert_eq!(original.0, new.0);
+		assert_eq!(original.1, new.1);
+
+		if original.1 == Some(*b"INFO") {
+			assert_ne!(original.2, new.2);
+		} else {
+			assert_eq!(original.2, new.2);
+		}
+	}
+
+	file.rewind().unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(wav_file.list_chunks(), &*original_lists);
+
+	let riff_info = wav_file.riff_info().unwrap();
+	assert_eq!(riff_info.get("IART"), Some("Baz artist"));
+	assert_eq!(riff_info.get("INAM"), Some("Foo title"));
+}
+
+#[test]
+fn write_riff_info_with_trailing_chunk() {
+	for placement in [RiffInfoPlacement::AfterData, RiffInfoPlacement::EndOfFile] {
+		let mut bytes =
+			std::fs::read("tests/files/assets/hound/pcmwaveformat-16bit-44100Hz-mono.wav").unwrap();
+		let original_len = bytes.len();
+
+		// A proprietary chunk following the audio, with a RIFF size that doesn't account for it
+		bytes.extend(b"prop");
+		bytes.extend(5_u32.to_le_bytes());
+		bytes.extend(b"Foo\0\0\0");
+		bytes[4..8].copy_from_slice(&(original_len as u32 - 6).to_le_bytes());
+
+		let mut file = tempfile::tempfile().unwrap();
+		file.write_all(&bytes).unwrap();
+
+		let mut riff_info = RIFFInfoList::default();
+		riff_info.insert(String::from("IART"), String::from("Foo artist"));
+
+		file.rewind().unwrap();
+		riff_info
+			.save_to(
+				&mut file,
+				WriteOptions::new().riff_info_placement(placement),
+			)
+			.unwrap();
+
+		let fourccs = chunk_tree(&mut file)
+			.into_iter()
+			.map(|(fourcc, list_type, _)| list_type.unwrap_or(fourcc))
+			.collect::<Vec<_>>();
+		match placement {
+			RiffInfoPlacement::AfterData => {
+				assert_eq!(fourccs, [*b"fmt ", *b"data", *b"INFO", *b"prop"])
+			},
+			_ => assert_eq!(fourccs, [*b"fmt ", *b"data", *b"prop", *b"INFO"]),
+		}
+
+		// The RIFF size now covers every chunk
+		let mut file_bytes = Vec::new();
+		file.rewind().unwrap();
+		file.read_to_end(&mut file_bytes).unwrap();
+		assert_eq!(
+			u32::from_le_bytes(file_bytes[4..8].try_into().unwrap()) as usize,
+			file_bytes.len() - 8
+		);
+
+		file.rewind().unwrap();
+		let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+		assert_eq!(
+			wav_file.riff_info().unwrap().get("IART"),
+			Some("Foo artist")
+		);
+
+		// Other readers should still be able to read the audio
+		file.rewind().unwrap();
+		let mut reader = hound::WavReader::new(BufReader::new(&mut file)).unwrap();
+		assert_eq!(reader.duration(), 4);
+		assert!(reader.samples::<i16>().all(|sample| sample.is_ok()));
+	}
+}
+
+#[test]
+fn write_bext() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_format_pcm.wav");
+	let original_tree = chunk_tree(&mut file);
+
+	file.rewind().unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(wav_file.bext().is_none());
+
+	// A new chunk is inserted at the start
+	let mut bext = BextChunk {
+		description: String::from("Foo description"),
+		originator: String::from("Bar originator"),
+		coding_history: String::from("A=PCM,F=48000,W=16,M=stereo\r\n"),
+		..BextChunk::default()
+	};
+	bext.write_to(&mut file).unwrap();
+
+	let tree = chunk_tree(&mut file);
+	assert_eq!(&tree[0].0, b"bext");
+	assert_eq!(tree[1..], original_tree[..]);
+
+	file.rewind().unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(wav_file.bext(), Some(&bext));
+	assert_eq!(wav_file.properties().sample_rate(), 48000);
+
+	// Setting loudness values on a version 0 chunk bumps its version
+	bext.loudness_value = Some(-23.0);
+	bext.loudness_range = Some(7.5);
+	bext.write_to(&mut file).unwrap();
+
+	let tree = chunk_tree(&mut file);
+	assert_eq!(tree.len(), original_tree.len() + 1);
+	assert_eq!(tree[1..], original_tree[..]);
+
+	file.rewind().unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let new_bext = wav_file.bext().unwrap();
+	assert_eq!(new_bext.version, 2);
+	assert_eq!(new_bext.loudness_value, Some(-23.0));
+	assert_eq!(new_bext.loudness_range, Some(7.5));
+	assert_eq!(new_bext.max_true_peak_level, None);
+	assert_eq!(new_bext.description, "Foo description");
+}
+
+#[test]
+fn remove_empty_id3v2_chunk() {
+	let mut file = temp_file!("tests/files/assets/minimal/wav_format_pcm.wav");
+	file.rewind().unwrap();
+	TagType::Id3v2.remove_from(&mut file).unwrap();
+	let original_tree = chunk_tree(&mut file);
+
+	let mut tag = lofty::Tag::new(TagType::Id3v2);
+	tag.insert_text(ItemKey::TrackTitle, String::from("Foo title"));
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// A proprietary chunk following the tag
+	file.seek(std::io::SeekFrom::End(0)).unwrap();
+	file.write_all(b"prop\x05\0\0\0Foo\0\0\0").unwrap();
+	let len = file.stream_position().unwrap();
+	file.seek(std::io::SeekFrom::Start(4)).unwrap();
+	file.write_all(&(len as u32 - 8).to_le_bytes()).unwrap();
+
+	let tree = chunk_tree(&mut file);
+	let (_, _, id3v2_chunk) = tree.iter().find(|(fourcc, ..)| fourcc == b"ID3 ").unwrap();
+	let id3v2_chunk_len = 8 + id3v2_chunk.len() + id3v2_chunk.len() % 2;
+
+	tag.clear();
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// The chunk is gone, and the following chunk moved up in its place
+	let tree = chunk_tree(&mut file);
+	assert_eq!(tree[..original_tree.len()], original_tree[..]);
+	assert_eq!(
+		&tree[original_tree.len()..],
+		&[(*b"prop", None, b"Foo\0\0".to_vec())]
+	);
+
+	let mut file_bytes = Vec::new();
+	file.rewind().unwrap();
+	file.read_to_end(&mut file_bytes).unwrap();
+	assert_eq!(file_bytes.len(), len as usize - id3v2_chunk_len);
+	assert_eq!(
+		u32::from_le_bytes(file_bytes[4..8].try_into().unwrap()) as usize,
+		file_bytes.len() - 8
+	);
+
+	file.rewind().unwrap();
+	let tagged_file = lofty::read_from(&mut file).unwrap();
+	assert!(tagged_file.tag(TagType::Id3v2).is_none());
+}
+
+#[test]
+fn read_two_fmt_chunks() {
+	// The second "fmt " chunk claims 8-bit 22.05 kHz stereo, only the first should be used
+	let mut file = std::fs::File::open("tests/files/assets/two_fmt_chunks.wav").unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	let properties = wav_file.properties();
+	assert_eq!(properties.channels(), 1);
+	assert_eq!(properties.sample_rate(), 44100);
+	assert_eq!(properties.bit_depth(), 16);
+	assert_eq!(properties.duration().as_millis(), 200);
+}
+
+#[test]
+fn write_two_fmt_chunks() {
+	let mut file = temp_file!("tests/files/assets/two_fmt_chunks.wav");
+	let original_tree = chunk_tree(&mut file);
+
+	let mut riff_info = RIFFInfoList::default();
+	riff_info.insert(String::from("IART"), String::from("Foo artist"));
+
+	file.rewind().unwrap();
+	riff_info
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let mut tag = lofty::Tag::new(TagType::Id3v2);
+	tag.insert_text(ItemKey::TrackTitle, String::from("Foo title"));
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// Both "fmt " chunks are left as they were, and never duplicated
+	let tree = chunk_tree(&mut file);
+	let fmt_chunks = |tree: &[([u8; 4], Option<[u8; 4]>, Vec<u8>)]| {
+		tree.iter()
+			.filter(|(fourcc, ..)| fourcc == b"fmt ")
+			.map(|(.., content)| content.clone())
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(fmt_chunks(&tree), fmt_chunks(&original_tree));
+	assert_eq!(tree[..original_tree.len()], original_tree[..]);
+
+	file.rewind().unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert_eq!(wav_file.properties().sample_rate(), 44100);
+	assert_eq!(
+		wav_file.riff_info().unwrap().get("IART"),
+		Some("Foo artist")
+	);
+}
+
+#[test]
+fn read_flac_data() {
+	// A RIFF container holding FLAC frames, as written by some field recorders
+	let mut file = std::fs::File::open("tests/files/assets/flac_in_riff.wav").unwrap();
+	let wav_file = WavFile::read_from(&mut file, ParseOptions::new()).unwrap();
+
+	// The properties come from the STREAMINFO block, not the "fmt " chunk
+	let properties = wav_file.properties();
+	assert_eq!(properties.format(), &WavFormat::Flac);
+	assert_eq!(properties.sample_format(), SampleFormat::Compressed);
+	assert_eq!(properties.channels(), 2);
+	assert_eq!(properties.sample_rate(), 48000);
+	assert_eq!(properties.bit_depth(), 16);
+	assert_eq!(properties.duration().as_millis(), 1428);
+	assert!(properties.bitrate() > 0);
+}
+
+#[test]
+fn write_flac_data() {
+	let mut file = temp_file!("tests/files/assets/flac_in_riff.wav");
+	let original_tree = chunk_tree(&mut file);
+
+	let mut riff_info = RIFFInfoList::default();
+	riff_info.insert(String::from("IART"), String::from("Foo artist"));
+
+	file.rewind().unwrap();
+	riff_info
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	let mut tag = lofty::Tag::new(TagType::Id3v2);
+	tag.insert_text(ItemKey::TrackTitle, String::from("Foo title"));
+	file.rewind().unwrap();
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	// The FLAC data is left alone
+	let tree = chunk_tree(&mut file);
+	assert_eq!(tree[..original_tree.len()], original_tree[..]);
+
+	file.rewind().unwrap();
+	let tagged_file = Probe::new(&mut file)
+		.guess_file_type()
+		.unwrap()
+		.read()
+		.unwrap();
+	assert_eq!(tagged_file.file_type(), FileType::Wav);
+	assert_eq!(tagged_file.properties().duration().as_millis(), 1428);
+	assert_eq!(
+		tagged_file
+			.tag(TagType::Id3v2)
+			.unwrap()
+			.get_string(&ItemKey::TrackTitle),
+		Some("Foo title")
+	);
+	assert_eq!(
+		tagged_file
+			.tag(TagType::RiffInfo)
+			.unwrap()
+			.get_string(&ItemKey::TrackArtist),
+		Some("Foo artist")
+	);
+}
@@ -1,8 +1,12 @@
 use crate::{set_artist, temp_file, verify_artist};
+use lofty::ape::ApeTag;
+use lofty::id3::v1::Id3v1Tag;
+use lofty::wavpack::WavPackFile;
 use lofty::{
-	FileType, ItemKey, ItemValue, ParseOptions, Probe, TagExt, TagItem, TagType, TaggedFileExt,
+	Accessor, AudioFile, FileType, ItemKey, ItemValue, ParseOptions, ParsingMode, Probe, Tag,
+	TagExt, TagItem, TagType, TaggedFileExt, WriteOptions,
 };
-use std::io::{Seek, Write};
+use std::io::{Read, Seek, Write};
 
 #[test]
 fn read() {
@@ -64,3 +68,178 @@ fn remove_id3v1() {
 fn remove_ape() {
 	crate::remove_tag!("tests/files/assets/minimal/full_test.wv", TagType::Ape);
 }
+
+#[test]
+fn save_empty_ape() {
+	crate::save_empty_tag!("tests/files/assets/minimal/full_test.wv", TagType::Ape);
+}
+
+// Strips both tags from the test file
+fn untagged_file() -> std::fs::File {
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.wv");
+	TagType::Ape.remove_from(&mut file).unwrap();
+	file.rewind().unwrap();
+	TagType::Id3v1.remove_from(&mut file).unwrap();
+	file.rewind().unwrap();
+
+	file
+}
+
+#[test]
+fn write_both_tags_to_untagged() {
+	// The tags should end up in the correct order (APE, then ID3v1), regardless of the order they're written in
+	for tag_types in [
+		[TagType::Id3v1, TagType::Ape],
+		[TagType::Ape, TagType::Id3v1],
+	] {
+		let mut file = untagged_file();
+
+		for tag_type in tag_types {
+			let mut tag = Tag::new(tag_type);
+			tag.set_artist(String::from("Foo artist"));
+
+			file.rewind().unwrap();
+			tag.save_to(&mut file, WriteOptions::default()).unwrap();
+		}
+
+		file.rewind().unwrap();
+		let mut contents = Vec::new();
+		file.read_to_end(&mut contents).unwrap();
+
+		let (audio_and_ape, id3v1) = contents.split_at(contents.len() - 128);
+		assert!(id3v1.starts_with(b"TAG"));
+		assert!(audio_and_ape[audio_and_ape.len() - 32..].starts_with(b"APETAGEX"));
+
+		file.rewind().unwrap();
+		let tagged_file = Probe::new(&mut file)
+			.options(ParseOptions::new().parsing_mode(ParsingMode::Strict))
+			.guess_file_type()
+			.unwrap()
+			.read()
+			.unwrap();
+
+		crate::verify_artist!(tagged_file, tag, TagType::Ape, "Foo artist", 1);
+		crate::verify_artist!(tagged_file, tag, TagType::Id3v1, "Foo artist", 1);
+		assert_eq!(tagged_file.properties().duration().as_millis(), 1428);
+	}
+}
+
+#[test]
+fn wavpack_file_round_trip() {
+	let mut file = untagged_file();
+
+	let mut wavpack_file = WavPackFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	assert!(!wavpack_file.contains_tag());
+
+	let mut ape = ApeTag::default();
+	ape.set_artist(String::from("Foo artist"));
+	wavpack_file.set_ape(ape);
+
+	let mut id3v1 = Id3v1Tag::default();
+	id3v1.set_artist(String::from("Bar artist"));
+	wavpack_file.set_id3v1(id3v1);
+
+	file.rewind().unwrap();
+	wavpack_file
+		.save_to(&mut file, WriteOptions::default())
+		.unwrap();
+
+	file.rewind().unwrap();
+	let wavpack_file = WavPackFile::read_from(
+		&mut file,
+		ParseOptions::new().parsing_mode(ParsingMode::Strict),
+	)
+	.unwrap();
+
+	assert_eq!(
+		wavpack_file.ape().and_then(|ape| ape.artist()).as_deref(),
+		Some("Foo artist")
+	);
+	assert_eq!(
+		wavpack_file
+			.id3v1()
+			.and_then(|id3v1| id3v1.artist())
+			.as_deref(),
+		Some("Bar artist")
+	);
+	assert_eq!(wavpack_file.properties().duration().as_millis(), 1428);
+}
+
+#[test]
+fn read_without_ape_strict() {
+	// With no APE tag, the end of the final block sits right before the ID3v1 tag
+	let mut file = temp_file!("tests/files/assets/minimal/full_test.wv");
+	TagType::Ape.remove_from(&mut file).unwrap();
+	file.rewind().unwrap();
+
+	let wavpack_file = WavPackFile::read_from(
+		&mut file,
+		ParseOptions::new().parsing_mode(ParsingMode::Strict),
+	)
+	.unwrap();
+
+	assert!(wavpack_file.ape().is_none());
+	assert_eq!(
+		wavpack_file
+			.id3v1()
+			.and_then(|id3v1| id3v1.artist())
+			.as_deref(),
+		Some("Bar artist")
+	);
+	assert_eq!(wavpack_file.properties().duration().as_millis(), 1428);
+}
+
+#[test]
+fn ape_footer_wrong_about_header() {
+	// Both files have the tags of `full_test.wv`, but the APE tag footer of the first claims there's
+	// a header when there isn't, and the second claims there isn't one when there is
+	const AUDIO_LEN: usize = 106_598;
+
+	for path in [
+		"tests/files/assets/ape_footer_claims_header.wv",
+		"tests/files/assets/ape_footer_claims_no_header.wv",
+	] {
+		let original = std::fs::read(path).unwrap();
+		let mut file = temp_file!(path);
+
+		let wavpack_file = WavPackFile::read_from(
+			&mut file,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
+
+		let mut ape = wavpack_file.ape().unwrap().clone();
+		assert_eq!(ape.artist().as_deref(), Some("Foo artist"));
+		assert_eq!(
+			wavpack_file
+				.id3v1()
+				.and_then(|id3v1| id3v1.artist())
+				.as_deref(),
+			Some("Bar artist")
+		);
+		assert_eq!(wavpack_file.properties().duration().as_millis(), 1428);
+
+		// Only the tag itself is replaced
+		ape.set_artist(String::from("Baz artist"));
+		file.rewind().unwrap();
+		ape.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		file.rewind().unwrap();
+		let mut contents = Vec::new();
+		file.read_to_end(&mut contents).unwrap();
+
+		assert!(contents[..AUDIO_LEN] == original[..AUDIO_LEN]);
+		assert!(contents[AUDIO_LEN..].starts_with(b"APETAGEX"));
+
+		file.rewind().unwrap();
+		let wavpack_file = WavPackFile::read_from(
+			&mut file,
+			ParseOptions::new().parsing_mode(ParsingMode::Strict),
+		)
+		.unwrap();
+		assert_eq!(
+			wavpack_file.ape().and_then(|ape| ape.artist()).as_deref(),
+			Some("Baz artist")
+		);
+	}
+}
@@ -22,3 +22,8 @@ fn crash2() {
 fn oom1() {
 	oom_test::<MpegFile>("mpegfile_read_from/oom-f8730cbfa5682ab12343ccb70de9b71a061ef4d0");
 }
+
+#[test]
+fn many_tiny_id3v2_frames() {
+	oom_test::<MpegFile>("mpegfile_read_from/many-tiny-id3v2-frames");
+}
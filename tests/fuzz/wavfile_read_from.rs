@@ -1,7 +1,20 @@
-use crate::oom_test;
+use crate::{get_reader, oom_test};
 use lofty::iff::wav::WavFile;
+use lofty::{AudioFile, ParseOptions};
 
 #[test]
 fn oom1() {
 	oom_test::<WavFile>("wavfile_read_from/oom-007573d233b412ea1b8038137db28e70d5678291");
 }
+
+#[test]
+fn two_fmt_chunks() {
+	// An invalid "fmt " chunk, followed by two valid ones. The first valid chunk should be used.
+	let mut reader = get_reader("wavfile_read_from/two-fmt-chunks");
+	let wav_file = WavFile::read_from(&mut reader, ParseOptions::new()).unwrap();
+
+	let properties = wav_file.properties();
+	assert_eq!(properties.channels(), 2);
+	assert_eq!(properties.sample_rate(), 48000);
+	assert_eq!(properties.bit_depth(), 24);
+}
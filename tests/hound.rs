@@ -2,8 +2,9 @@ use std::fs;
 use std::fs::File;
 use std::path::Path;
 
-use hound::WavReader;
+use hound::{SampleFormat as HoundSampleFormat, WavReader};
 use lofty::iff::wav::WavFile;
+use lofty::iff::SampleFormat;
 use lofty::{AudioFile, ParseOptions, Result};
 
 fn get_properties(path: &Path) -> Result<<WavFile as AudioFile>::Properties> {
@@ -12,6 +13,14 @@ fn get_properties(path: &Path) -> Result<<WavFile as AudioFile>::Properties> {
 	Ok(*wav_file.properties())
 }
 
+fn sample_format_matches(lofty: SampleFormat, hound: HoundSampleFormat) -> bool {
+	matches!(
+		(lofty, hound),
+		(SampleFormat::IntPcm, HoundSampleFormat::Int)
+			| (SampleFormat::FloatPcm, HoundSampleFormat::Float)
+	)
+}
+
 #[test]
 fn hound() {
 	let paths = fs::read_dir("tests/files/assets/hound").unwrap();
@@ -24,7 +33,12 @@ fn hound() {
 			let lofty = get_properties(&path).unwrap();
 			assert_eq!(lofty.channels() as u16, wav_reader.spec().channels);
 			assert_eq!(lofty.sample_rate(), wav_reader.spec().sample_rate);
+			// Hound reports the valid bits per sample, not the container size
 			assert_eq!(lofty.bit_depth() as u16, wav_reader.spec().bits_per_sample);
+			assert!(sample_format_matches(
+				lofty.sample_format(),
+				wav_reader.spec().sample_format
+			));
 		}
 	}
 }
@@ -43,6 +57,10 @@ fn hound_fuzz() {
 				assert_eq!(lofty.channels() as u16, wav_reader.spec().channels);
 				assert_eq!(lofty.sample_rate(), wav_reader.spec().sample_rate);
 				assert_eq!(lofty.bit_depth() as u16, wav_reader.spec().bits_per_sample);
+				assert!(sample_format_matches(
+					lofty.sample_format(),
+					wav_reader.spec().sample_format
+				));
 			} else if get_properties(&path).is_ok() {
 				println!("We are even better for this file!");
 			}
@@ -1,5 +1,7 @@
 use lofty::id3::v2::{AttachedPictureFrame, Id3v2Version};
-use lofty::{ParsingMode, Picture, PictureInformation, PictureType, TextEncoding};
+use lofty::{
+	MimeType, ParsingMode, Picture, PictureInformation, PictureType, TagType, TextEncoding,
+};
 
 use std::fs::File;
 use std::io::Read;
@@ -16,7 +18,8 @@ fn get_buf(path: &str) -> Vec<u8> {
 }
 
 fn create_original_picture() -> Picture {
-	let mut original_pic = Picture::from_reader(&mut &ORIGINAL_IMAGE[..]).unwrap();
+	let mut original_pic =
+		Picture::from_reader(&mut &ORIGINAL_IMAGE[..], ParsingMode::Strict).unwrap();
 
 	original_pic.set_description(Some(String::from("png_640x628.png")));
 	original_pic.set_pic_type(PictureType::CoverFront);
@@ -76,7 +79,7 @@ fn as_apic_bytes_v2() {
 fn ape_binary_item() {
 	let buf = get_buf("tests/picture/assets/png_640x628.apev2");
 
-	let pic = Picture::from_ape_bytes("Cover Art (Front)", &buf).unwrap();
+	let pic = Picture::from_ape_bytes("Cover Art (Front)", &buf, ParsingMode::Strict).unwrap();
 
 	assert_eq!(create_original_picture(), pic);
 }
@@ -113,3 +116,158 @@ fn as_flac_bytes() {
 
 	assert_eq!(&*buf, original_as_flac);
 }
+
+#[test]
+fn encoded_size_for() {
+	let picture = create_original_picture();
+	let info = PictureInformation::from_picture(&picture).unwrap();
+
+	let apic = AttachedPictureFrame {
+		encoding: TextEncoding::UTF8,
+		picture: picture.clone(),
+	};
+	assert_eq!(
+		picture.encoded_size_for(TagType::Id3v2),
+		Some(apic.as_bytes(Id3v2Version::V4).unwrap().len() as u64 + 10)
+	);
+
+	let ape_key = PictureType::CoverFront.as_ape_key().unwrap();
+	assert_eq!(
+		picture.encoded_size_for(TagType::Ape),
+		Some((8 + ape_key.len() + 1 + picture.as_ape_bytes().len()) as u64)
+	);
+
+	assert_eq!(
+		picture.encoded_size_for(TagType::VorbisComments),
+		Some(
+			(4 + "METADATA_BLOCK_PICTURE=".len() + picture.as_flac_bytes(info, true).len()) as u64
+		)
+	);
+
+	assert_eq!(
+		picture.encoded_size_for(TagType::Mp4Ilst),
+		Some(ORIGINAL_IMAGE.len() as u64 + 16)
+	);
+
+	assert_eq!(picture.encoded_size_for(TagType::Id3v1), None);
+}
+
+const PARSING_MODES: [ParsingMode; 3] = [
+	ParsingMode::Strict,
+	ParsingMode::BestAttempt,
+	ParsingMode::Relaxed,
+];
+
+#[test]
+fn ape_parsing_modes() {
+	// Unrecognized image format
+	let unknown_format = b"Foo\0Bar";
+	assert!(
+		Picture::from_ape_bytes("Cover Art (Front)", unknown_format, ParsingMode::Strict).is_err()
+	);
+	for parse_mode in &PARSING_MODES[1..] {
+		let pic =
+			Picture::from_ape_bytes("Cover Art (Front)", unknown_format, *parse_mode).unwrap();
+		assert_eq!(pic.mime_type(), None);
+		assert_eq!(pic.description(), Some("Foo"));
+		assert_eq!(pic.data(), b"Bar");
+	}
+
+	// Invalid UTF-8 description
+	let mut invalid_description = b"Foo\xFF\0".to_vec();
+	invalid_description.extend(ORIGINAL_IMAGE);
+	assert!(Picture::from_ape_bytes(
+		"Cover Art (Front)",
+		&invalid_description,
+		ParsingMode::Strict
+	)
+	.is_err());
+	let pic = Picture::from_ape_bytes(
+		"Cover Art (Front)",
+		&invalid_description,
+		ParsingMode::BestAttempt,
+	)
+	.unwrap();
+	assert_eq!(pic.description(), None);
+	let pic = Picture::from_ape_bytes(
+		"Cover Art (Front)",
+		&invalid_description,
+		ParsingMode::Relaxed,
+	)
+	.unwrap();
+	assert_eq!(pic.description(), Some("Foo\u{FFFD}"));
+
+	// Unterminated description, and no picture data
+	for bytes in [&b"Foo"[..], &b"Foo\0"[..]] {
+		for parse_mode in &PARSING_MODES[..2] {
+			assert!(Picture::from_ape_bytes("Cover Art (Front)", bytes, *parse_mode).is_err());
+		}
+
+		let pic =
+			Picture::from_ape_bytes("Cover Art (Front)", bytes, ParsingMode::Relaxed).unwrap();
+		assert_eq!(pic.description(), Some("Foo"));
+		assert!(pic.data().is_empty());
+	}
+}
+
+#[test]
+fn flac_parsing_modes() {
+	let picture = create_original_picture();
+	let info = PictureInformation::from_picture(&picture).unwrap();
+	let block = picture.as_flac_bytes(info, false);
+
+	// The description length runs past the end of the block
+	let desc_len_pos = 8 + picture.mime_type().unwrap().as_str().len();
+	let mut overlong_description = block.clone();
+	overlong_description[desc_len_pos..desc_len_pos + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+	for parse_mode in &PARSING_MODES[..2] {
+		assert!(Picture::from_flac_bytes(&overlong_description, false, *parse_mode).is_err());
+	}
+
+	let (pic, pic_info) =
+		Picture::from_flac_bytes(&overlong_description, false, ParsingMode::Relaxed).unwrap();
+	assert_eq!(pic.mime_type(), picture.mime_type());
+	// The rest of the block is the description
+	assert_eq!(
+		pic.description(),
+		Some(&*String::from_utf8_lossy(&block[desc_len_pos + 4..]))
+	);
+	assert!(pic.data().is_empty());
+	assert_eq!(pic_info, PictureInformation::default());
+
+	// Invalid UTF-8 MIME type
+	let mut invalid_mime_type = block.clone();
+	invalid_mime_type[8] = 0xFF;
+	assert!(Picture::from_flac_bytes(&invalid_mime_type, false, ParsingMode::Strict).is_err());
+	for parse_mode in &PARSING_MODES[1..] {
+		let (pic, _) = Picture::from_flac_bytes(&invalid_mime_type, false, *parse_mode).unwrap();
+		assert!(matches!(pic.mime_type(), Some(MimeType::Unknown(_))));
+		assert_eq!(pic.data(), picture.data());
+	}
+
+	// Truncated picture data
+	let truncated = &block[..block.len() - 1];
+	for parse_mode in &PARSING_MODES[..2] {
+		assert!(Picture::from_flac_bytes(truncated, false, *parse_mode).is_err());
+	}
+
+	let (pic, _) = Picture::from_flac_bytes(truncated, false, ParsingMode::Relaxed).unwrap();
+	assert_eq!(pic.data(), &picture.data()[..picture.data().len() - 1]);
+}
+
+#[test]
+fn from_reader_parsing_modes() {
+	for parse_mode in PARSING_MODES {
+		let pic = Picture::from_reader(&mut &ORIGINAL_IMAGE[..], parse_mode).unwrap();
+		assert_eq!(pic.mime_type(), Some(&MimeType::Png));
+	}
+
+	// No picture data
+	for parse_mode in &PARSING_MODES[..2] {
+		assert!(Picture::from_reader(&mut &[][..], *parse_mode).is_err());
+	}
+
+	let pic = Picture::from_reader(&mut &[][..], ParsingMode::Relaxed).unwrap();
+	assert_eq!(pic.mime_type(), None);
+	assert!(pic.data().is_empty());
+}
@@ -1,4 +1,4 @@
-use lofty::{MimeType, Picture};
+use lofty::{MimeType, ParsingMode, Picture};
 
 use std::fs::File;
 use std::io::Read;
@@ -14,37 +14,55 @@ fn get_buf(path: &str) -> Vec<u8> {
 
 #[test]
 fn picture_from_reader_png() {
-	let pic = Picture::from_reader(&mut &*get_buf("tests/picture/assets/png_640x628.png")).unwrap();
+	let pic = Picture::from_reader(
+		&mut &*get_buf("tests/picture/assets/png_640x628.png"),
+		ParsingMode::Strict,
+	)
+	.unwrap();
 
 	assert_eq!(pic.mime_type(), Some(&MimeType::Png));
 }
 
 #[test]
 fn picture_from_reader_jpeg() {
-	let pic =
-		Picture::from_reader(&mut &*get_buf("tests/picture/assets/jpeg_640x628.jpg")).unwrap();
+	let pic = Picture::from_reader(
+		&mut &*get_buf("tests/picture/assets/jpeg_640x628.jpg"),
+		ParsingMode::Strict,
+	)
+	.unwrap();
 
 	assert_eq!(pic.mime_type(), Some(&MimeType::Jpeg));
 }
 
 #[test]
 fn picture_from_reader_bmp() {
-	let pic = Picture::from_reader(&mut &*get_buf("tests/picture/assets/bmp_640x628.bmp")).unwrap();
+	let pic = Picture::from_reader(
+		&mut &*get_buf("tests/picture/assets/bmp_640x628.bmp"),
+		ParsingMode::Strict,
+	)
+	.unwrap();
 
 	assert_eq!(pic.mime_type(), Some(&MimeType::Bmp));
 }
 
 #[test]
 fn picture_from_reader_gif() {
-	let pic = Picture::from_reader(&mut &*get_buf("tests/picture/assets/gif_640x628.gif")).unwrap();
+	let pic = Picture::from_reader(
+		&mut &*get_buf("tests/picture/assets/gif_640x628.gif"),
+		ParsingMode::Strict,
+	)
+	.unwrap();
 
 	assert_eq!(pic.mime_type(), Some(&MimeType::Gif));
 }
 
 #[test]
 fn picture_from_reader_tiff() {
-	let pic =
-		Picture::from_reader(&mut &*get_buf("tests/picture/assets/tiff_640x628.tiff")).unwrap();
+	let pic = Picture::from_reader(
+		&mut &*get_buf("tests/picture/assets/tiff_640x628.tiff"),
+		ParsingMode::Strict,
+	)
+	.unwrap();
 
 	assert_eq!(pic.mime_type(), Some(&MimeType::Tiff));
 }
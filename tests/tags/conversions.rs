@@ -1,8 +1,19 @@
 // Tests for special case conversions
 
-use lofty::id3::v2::{CommentFrame, Frame, FrameFlags, FrameId, Id3v2Tag, UnsynchronizedTextFrame};
-use lofty::{ItemKey, Tag, TagType, TextEncoding};
+use lofty::ape::{ApeItem, ApeTag};
+use lofty::id3::v2::{
+	CommentFrame, Frame, FrameFlags, FrameId, Id3v2Tag, TextInformationFrame,
+	UnsynchronizedTextFrame,
+};
+use lofty::mp4::{Atom, AtomData, AtomIdent, Ilst, Mp4File};
+use lofty::mpeg::MpegFile;
+use lofty::ogg::VorbisComments;
+use lofty::{
+	Accessor, AudioFile, Isrc, ItemKey, ItemValue, ParseOptions, Tag, TagExt, TagItem, TagType,
+	TaggedFileExt, TextEncoding, WriteOptions,
+};
 use std::borrow::Cow;
+use std::io::{Read, Seek, Write};
 
 #[test]
 fn tag_to_id3v2_lang_frame() {
@@ -18,7 +29,7 @@ fn tag_to_id3v2_lang_frame() {
 			"USLT",
 			UnsynchronizedTextFrame {
 				encoding: TextEncoding::UTF8,
-				language: *b"eng",
+				language: *b"und",
 				description: String::new(),
 				content: String::from("Test lyrics")
 			},
@@ -34,7 +45,7 @@ fn tag_to_id3v2_lang_frame() {
 			"COMM",
 			CommentFrame {
 				encoding: TextEncoding::UTF8,
-				language: *b"eng",
+				language: *b"XXX",
 				description: String::new(),
 				content: String::from("Test comment")
 			},
@@ -44,3 +55,436 @@ fn tag_to_id3v2_lang_frame() {
 		.as_ref()
 	);
 }
+
+#[test]
+fn fractional_bpm_round_trip() {
+	let mut tag = Tag::new(TagType::Ape);
+	tag.set_bpm(126.5);
+
+	let ape: ApeTag = tag.clone().into();
+	assert_eq!(
+		ape.get("BPM").and_then(|item| item.value().text()),
+		Some("126.5")
+	);
+	assert_eq!(Tag::from(ape).bpm(), Some(126.5));
+
+	let vorbis_comments: VorbisComments = tag.into();
+	assert_eq!(vorbis_comments.get("BPM"), Some("126.5"));
+	assert_eq!(Tag::from(vorbis_comments).bpm(), Some(126.5));
+}
+
+fn verify_album_artist(tag: Tag) {
+	assert_eq!(tag.get_string(&ItemKey::AlbumArtist), Some("Foo artist"));
+	assert_eq!(tag.get_strings(&ItemKey::AlbumArtist).count(), 1);
+
+	// Only the canonical keys are written
+	let id3v2: Id3v2Tag = tag.clone().into();
+	assert_eq!(
+		id3v2
+			.get_text(&FrameId::Valid(Cow::Borrowed("TPE2")))
+			.as_deref(),
+		Some("Foo artist")
+	);
+
+	let ilst: Ilst = tag.clone().into();
+	assert_eq!(
+		ilst.get(&AtomIdent::Fourcc(*b"aART"))
+			.unwrap()
+			.data()
+			.collect::<Vec<_>>(),
+		[&AtomData::UTF8(String::from("Foo artist"))]
+	);
+
+	let ape: ApeTag = tag.clone().into();
+	assert_eq!(
+		ape.get("Album Artist").and_then(|item| item.value().text()),
+		Some("Foo artist")
+	);
+	assert!(ape.get("ALBUMARTIST").is_none());
+
+	let vorbis_comments: VorbisComments = tag.into();
+	assert_eq!(vorbis_comments.get("ALBUMARTIST"), Some("Foo artist"));
+	assert!(vorbis_comments.get("ALBUM ARTIST").is_none());
+}
+
+#[test]
+fn legacy_album_artist() {
+	let mut vorbis_comments = VorbisComments::default();
+	vorbis_comments.push(String::from("ALBUM ARTIST"), String::from("Foo artist"));
+	verify_album_artist(vorbis_comments.into());
+
+	let mut ape = ApeTag::default();
+	ape.insert(
+		ApeItem::new(
+			String::from("ALBUMARTIST"),
+			ItemValue::Text(String::from("Foo artist")),
+		)
+		.unwrap(),
+	);
+	verify_album_artist(ape.into());
+
+	let mut id3v2 = Id3v2Tag::default();
+	id3v2.insert(
+		Frame::new(
+			"TPE2",
+			TextInformationFrame {
+				encoding: TextEncoding::UTF8,
+				value: String::from("Foo artist"),
+			},
+			FrameFlags::default(),
+		)
+		.unwrap(),
+	);
+	verify_album_artist(id3v2.into());
+
+	let mut ilst = Ilst::default();
+	ilst.insert(Atom::new(
+		AtomIdent::Fourcc(*b"aART"),
+		AtomData::UTF8(String::from("Foo artist")),
+	));
+	verify_album_artist(ilst.into());
+}
+
+#[test]
+fn canonical_album_artist_preferred() {
+	let mut vorbis_comments = VorbisComments::default();
+	vorbis_comments.push(String::from("ALBUM ARTIST"), String::from("Bar artist"));
+	vorbis_comments.push(String::from("ALBUMARTIST"), String::from("Foo artist"));
+	verify_album_artist(vorbis_comments.into());
+
+	let mut ape = ApeTag::default();
+	ape.insert(
+		ApeItem::new(
+			String::from("ALBUMARTIST"),
+			ItemValue::Text(String::from("Bar artist")),
+		)
+		.unwrap(),
+	);
+	ape.insert(
+		ApeItem::new(
+			String::from("Album Artist"),
+			ItemValue::Text(String::from("Foo artist")),
+		)
+		.unwrap(),
+	);
+	verify_album_artist(ape.into());
+}
+
+// The formats with separate comment and description fields
+const COMMENT_AND_DESCRIPTION_TAG_TYPES: [TagType; 3] =
+	[TagType::Id3v2, TagType::Mp4Ilst, TagType::VorbisComments];
+
+fn convert(tag: Tag, tag_type: TagType) -> Tag {
+	match tag_type {
+		TagType::Id3v2 => Id3v2Tag::from(tag).into(),
+		TagType::Mp4Ilst => Ilst::from(tag).into(),
+		TagType::VorbisComments => VorbisComments::from(tag).into(),
+		_ => unreachable!(),
+	}
+}
+
+fn verify_comment_and_description(tag: &Tag, context: &str) {
+	assert_eq!(
+		tag.get_strings(&ItemKey::Comment).collect::<Vec<_>>(),
+		["Foo comment"],
+		"{context}"
+	);
+	assert_eq!(
+		tag.get_strings(&ItemKey::Description).collect::<Vec<_>>(),
+		["Bar description"],
+		"{context}"
+	);
+}
+
+#[test]
+fn comment_and_description_kept_separate() {
+	for from in COMMENT_AND_DESCRIPTION_TAG_TYPES {
+		let mut tag = Tag::new(from);
+		tag.insert_text(ItemKey::Comment, String::from("Foo comment"));
+		tag.insert_text(ItemKey::Description, String::from("Bar description"));
+
+		for to in COMMENT_AND_DESCRIPTION_TAG_TYPES {
+			let converted = convert(tag.clone(), to);
+			verify_comment_and_description(&converted, &format!("{from:?} -> {to:?}"));
+
+			let round_trip = convert(converted, from);
+			verify_comment_and_description(&round_trip, &format!("{from:?} -> {to:?} -> {from:?}"));
+		}
+	}
+
+	// The description is a `TXXX` frame, the comment keeps the `COMM` frame to itself
+	let mut vorbis_comments = VorbisComments::default();
+	vorbis_comments.push(String::from("COMMENT"), String::from("Foo comment"));
+	vorbis_comments.push(String::from("DESCRIPTION"), String::from("Bar description"));
+
+	let id3v2: Id3v2Tag = Tag::from(vorbis_comments).into();
+	assert_eq!(id3v2.comment().as_deref(), Some("Foo comment"));
+	assert_eq!(id3v2.get_user_text("DESCRIPTION"), Some("Bar description"));
+	assert!(id3v2.get(&FrameId::Valid(Cow::Borrowed("TIT3"))).is_none());
+}
+
+#[test]
+fn comment_and_description_file_round_trip() {
+	for path in [
+		"tests/files/assets/minimal/full_test.opus",
+		"tests/files/assets/minimal/full_test.flac",
+	] {
+		let mut file = temp_file(path);
+		let mut tagged_file = lofty::read_from(&mut file).unwrap();
+
+		let vorbis_comments = tagged_file.tag_mut(TagType::VorbisComments).unwrap();
+		vorbis_comments.insert_text(ItemKey::Comment, String::from("Foo comment"));
+		vorbis_comments.insert_text(ItemKey::Description, String::from("Bar description"));
+		tagged_file
+			.save_to(&mut file, WriteOptions::default())
+			.unwrap();
+
+		// Vorbis comments -> MP3
+		file.rewind().unwrap();
+		let tagged_file = lofty::read_from(&mut file).unwrap();
+		let mut tag = tagged_file.tag(TagType::VorbisComments).unwrap().clone();
+		verify_comment_and_description(&tag, path);
+
+		tag.re_map(TagType::Id3v2);
+		let mut mp3 = temp_file("tests/files/assets/minimal/full_test.mp3");
+		tag.save_to(&mut mp3, WriteOptions::default()).unwrap();
+
+		// MP3 -> Vorbis comments
+		mp3.rewind().unwrap();
+		let tagged_file = lofty::read_from(&mut mp3).unwrap();
+		let mut tag = tagged_file.tag(TagType::Id3v2).unwrap().clone();
+		verify_comment_and_description(&tag, path);
+
+		tag.re_map(TagType::VorbisComments);
+		file.rewind().unwrap();
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+		file.rewind().unwrap();
+		let tagged_file = lofty::read_from(&mut file).unwrap();
+		let vorbis_comments = tagged_file.tag(TagType::VorbisComments).unwrap();
+		verify_comment_and_description(vorbis_comments, path);
+	}
+}
+
+#[test]
+fn empty_and_whitespace_items() {
+	for (path, tag_type) in [
+		("tests/files/assets/minimal/full_test.mp3", TagType::Id3v2),
+		("tests/files/assets/minimal/full_test.mp3", TagType::Id3v1),
+		("tests/files/assets/minimal/full_test.ape", TagType::Ape),
+		(
+			"tests/files/assets/minimal/full_test.flac",
+			TagType::VorbisComments,
+		),
+		(
+			"tests/files/assets/minimal/full_test.opus",
+			TagType::VorbisComments,
+		),
+		(
+			"tests/files/assets/minimal/m4a_codec_aac.m4a",
+			TagType::Mp4Ilst,
+		),
+		(
+			"tests/files/assets/minimal/wav_format_pcm.wav",
+			TagType::RiffInfo,
+		),
+		(
+			"tests/files/assets/minimal/full_test.aiff",
+			TagType::AiffText,
+		),
+	] {
+		let context = format!("{path} ({tag_type:?})");
+		let mut file = temp_file(path);
+
+		let mut tag = Tag::new(tag_type);
+		tag.set_title(String::new());
+		tag.set_artist(String::from("  Foo artist "));
+
+		let read_tag = |file: &mut std::fs::File| {
+			file.rewind().unwrap();
+			lofty::read_from(file)
+				.unwrap()
+				.tag(tag_type)
+				.cloned()
+				.unwrap_or_else(|| Tag::new(tag_type))
+		};
+
+		// Empty items are never written by default, and whitespace is kept
+		tag.save_to(&mut file, WriteOptions::default()).unwrap();
+		let written = read_tag(&mut file);
+		assert!(written.title().is_none(), "{context}");
+		assert_eq!(
+			written.artist().as_deref(),
+			Some("  Foo artist "),
+			"{context}"
+		);
+
+		// Whitespace only items are empty once trimmed
+		tag.set_title(String::from(" \t"));
+		file.rewind().unwrap();
+		tag.save_to(&mut file, WriteOptions::new().trim_whitespace(true))
+			.unwrap();
+		let written = read_tag(&mut file);
+		assert!(written.title().is_none(), "{context}");
+		assert_eq!(written.artist().as_deref(), Some("Foo artist"), "{context}");
+
+		// Unless we ask for them, though ID3v1 can't tell an empty field from a missing one
+		tag.set_title(String::new());
+		file.rewind().unwrap();
+		tag.save_to(&mut file, WriteOptions::new().remove_empty_items(false))
+			.unwrap();
+		let written = read_tag(&mut file);
+		if tag_type == TagType::Id3v1 {
+			assert!(written.title().is_none(), "{context}");
+		} else {
+			assert_eq!(written.title().as_deref(), Some(""), "{context}");
+		}
+	}
+}
+
+#[test]
+fn isrc_round_trip() {
+	// A medley, with an ISRC for each of its tracks
+	const ISRCS: [&str; 2] = ["USRC17607839", "GBAYE6800011"];
+
+	let mut tag = Tag::new(TagType::Id3v2);
+	for isrc in ISRCS {
+		tag.push(TagItem::new(
+			ItemKey::Isrc,
+			ItemValue::Text(isrc.to_owned()),
+		));
+	}
+
+	for (path, tag_type) in [
+		("tests/files/assets/minimal/full_test.mp3", TagType::Id3v2),
+		(
+			"tests/files/assets/minimal/m4a_codec_aac.m4a",
+			TagType::Mp4Ilst,
+		),
+		(
+			"tests/files/assets/minimal/full_test.flac",
+			TagType::VorbisComments,
+		),
+		("tests/files/assets/minimal/full_test.ape", TagType::Ape),
+		("tests/files/assets/minimal/full_test.mp3", TagType::Id3v2),
+	] {
+		let context = format!("{path} ({tag_type:?})");
+
+		tag.re_map(tag_type);
+		let mut file = temp_file(path);
+		tag.save_to(&mut file, WriteOptions::new().validate_isrc(true))
+			.unwrap();
+
+		file.rewind().unwrap();
+		tag = lofty::read_from(&mut file)
+			.unwrap()
+			.tag(tag_type)
+			.cloned()
+			.unwrap();
+		assert_eq!(
+			tag.get_strings(&ItemKey::Isrc).collect::<Vec<_>>(),
+			ISRCS,
+			"{context}"
+		);
+
+		for isrc in tag.get_strings(&ItemKey::Isrc) {
+			assert_eq!(isrc.parse::<Isrc>().unwrap().to_string(), isrc, "{context}");
+		}
+	}
+}
+
+#[test]
+fn itunes_items() {
+	const COMPILATION_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("TCMP"));
+	const MOVEMENT_NAME_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("MVNM"));
+	const MOVEMENT_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("MVIN"));
+	const GROUPING_ID: FrameId<'static> = FrameId::Valid(Cow::Borrowed("GRP1"));
+
+	let mut tag = Tag::new(TagType::Id3v2);
+	tag.insert_text(ItemKey::FlagCompilation, String::from("true"));
+	tag.insert_text(ItemKey::Work, String::from("Symphony No. 5"));
+	tag.insert_text(ItemKey::Movement, String::from("Allegro con brio"));
+	tag.insert_text(ItemKey::MovementNumber, String::from("1"));
+	tag.insert_text(ItemKey::MovementTotal, String::from("4"));
+	tag.insert_text(ItemKey::AppleId3v2ContentGroup, String::from("Beethoven"));
+
+	let mut file = temp_file("tests/files/assets/minimal/full_test.mp3");
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mpeg_file = MpegFile::read_from(&mut file, ParseOptions::new()).unwrap();
+	let written = mpeg_file.id3v2().unwrap();
+
+	// Writing the `Tag` directly and converting it first should have the same result
+	for id3v2 in [written, &Id3v2Tag::from(tag.clone())] {
+		assert_eq!(id3v2.get_text(&COMPILATION_ID).as_deref(), Some("1"));
+		assert_eq!(
+			id3v2.get_text(&MOVEMENT_NAME_ID).as_deref(),
+			Some("Allegro con brio")
+		);
+		assert_eq!(id3v2.get_text(&MOVEMENT_ID).as_deref(), Some("1/4"));
+		assert_eq!(id3v2.get_text(&GROUPING_ID).as_deref(), Some("Beethoven"));
+		assert_eq!(id3v2.get_user_text("WORK"), Some("Symphony No. 5"));
+	}
+
+	let converted: Tag = written.clone().into();
+	assert_eq!(converted.get_string(&ItemKey::FlagCompilation), Some("1"));
+	assert_eq!(converted.get_string(&ItemKey::Work), Some("Symphony No. 5"));
+	assert_eq!(converted.get_string(&ItemKey::MovementNumber), Some("1"));
+	assert_eq!(converted.get_string(&ItemKey::MovementTotal), Some("4"));
+
+	// Anything that isn't a flag is dropped
+	tag.insert_text(ItemKey::FlagCompilation, String::from("maybe"));
+	assert!(Id3v2Tag::from(tag.clone()).get(&COMPILATION_ID).is_none());
+	tag.insert_text(ItemKey::FlagCompilation, String::from("Yes"));
+
+	tag.re_map(TagType::Mp4Ilst);
+	let mut file = temp_file("tests/files/assets/minimal/m4a_codec_aac.m4a");
+	tag.save_to(&mut file, WriteOptions::default()).unwrap();
+
+	file.rewind().unwrap();
+	let mp4_file = Mp4File::read_from(&mut file, ParseOptions::new()).unwrap();
+	let ilst = mp4_file.ilst().unwrap();
+
+	let data = |ident: &[u8; 4]| {
+		ilst.get(&AtomIdent::Fourcc(*ident))
+			.and_then(|atom| atom.data().next())
+			.cloned()
+	};
+	assert_eq!(data(b"cpil"), Some(AtomData::Bool(true)));
+	assert_eq!(
+		data(b"\xa9wrk"),
+		Some(AtomData::UTF8(String::from("Symphony No. 5")))
+	);
+	assert_eq!(
+		data(b"\xa9mvn"),
+		Some(AtomData::UTF8(String::from("Allegro con brio")))
+	);
+	assert_eq!(data(b"\xa9mvi"), Some(AtomData::SignedInteger(1)));
+	assert_eq!(data(b"\xa9mvc"), Some(AtomData::SignedInteger(4)));
+
+	// iTunes expects the movement number and count to be 16-bit integers
+	file.rewind().unwrap();
+	let mut contents = Vec::new();
+	file.read_to_end(&mut contents).unwrap();
+	let pos = contents
+		.windows(4)
+		.position(|window| window == b"\xa9mvi")
+		.unwrap();
+	assert_eq!(
+		&contents[pos + 4..pos + 22],
+		&[0, 0, 0, 18, b'd', b'a', b't', b'a', 0, 0, 0, 21, 0, 0, 0, 0, 0, 1]
+	);
+
+	let converted: Tag = ilst.clone().into();
+	assert_eq!(converted.get_string(&ItemKey::FlagCompilation), Some("1"));
+	assert_eq!(converted.get_string(&ItemKey::MovementNumber), Some("1"));
+	assert_eq!(converted.get_string(&ItemKey::MovementTotal), Some("4"));
+}
+
+fn temp_file(path: &str) -> std::fs::File {
+	let mut file = tempfile::tempfile().unwrap();
+	file.write_all(&std::fs::read(path).unwrap()).unwrap();
+	file.rewind().unwrap();
+	file
+}
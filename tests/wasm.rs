@@ -0,0 +1,36 @@
+//! Builds lofty for `wasm32-unknown-unknown`, which has no filesystem, without the "fs" feature
+//!
+//! This requires the target to be installed (`rustup target add wasm32-unknown-unknown`), so it's
+//! ignored by default. Run it with `cargo test --test wasm -- --ignored`.
+
+use std::process::Command;
+
+#[test]
+#[ignore = "requires the wasm32-unknown-unknown target"]
+fn check_without_fs() {
+	let output = Command::new(env!("CARGO"))
+		.args([
+			"check",
+			"--lib",
+			"--target",
+			"wasm32-unknown-unknown",
+			"--no-default-features",
+			"--features",
+			"id3v2_compression_support",
+			"--manifest-path",
+			concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+		])
+		// The outer `cargo test` holds the lock on the usual target directory
+		.env(
+			"CARGO_TARGET_DIR",
+			concat!(env!("CARGO_TARGET_TMPDIR"), "/wasm"),
+		)
+		.output()
+		.unwrap();
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+}